@@ -20,7 +20,7 @@ async fn main() -> surrealdb::Result<()> {
     let response = db
 
         // Start transaction
-        .query(BeginStatement)
+        .query(BeginStatement::default())
 
         // Setup accounts
         .query("
@@ -1,7 +1,17 @@
 use crate::api;
 use crate::api::err::Error;
 use crate::api::method::query::Response;
+use crate::api::opt::auth::Jwt;
+use crate::api::opt::ClientSession;
+use crate::api::opt::ConnectionState;
 use crate::api::opt::Endpoint;
+use crate::api::opt::ExportConfig;
+use crate::api::opt::Handle;
+use crate::api::opt::Interceptors;
+use crate::api::opt::RefreshSource;
+use crate::api::opt::Reply;
+use crate::api::opt::Request;
+use crate::api::opt::RetryPolicy;
 use crate::api::ExtraFeatures;
 use crate::api::Result;
 use crate::api::Surreal;
@@ -20,6 +30,14 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time;
+use trice::Instant;
+use uuid::Uuid;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio as time;
 
 #[derive(Debug)]
 #[allow(dead_code)] // used by the embedded and remote connections
@@ -35,6 +53,13 @@ pub struct Router<C: api::Connection> {
 	pub(crate) sender: Sender<Option<Route>>,
 	pub(crate) last_id: AtomicI64,
 	pub(crate) features: HashSet<ExtraFeatures>,
+	pub(crate) interceptors: Interceptors,
+	pub(crate) retry: Option<RetryPolicy>,
+	pub(crate) state: ConnectionState,
+	pub(crate) session: ClientSession,
+	// The isolated handle which the router sends requests for, if any
+	pub(crate) handle: Option<Arc<Handle>>,
+	pub(crate) staleness: Option<Duration>,
 }
 
 impl<C> Router<C>
@@ -44,6 +69,149 @@ where
 	pub(crate) fn next_id(&self) -> i64 {
 		self.last_id.fetch_add(1, Ordering::SeqCst)
 	}
+
+	/// Sends a request to the engine, running the interceptors around it
+	pub(crate) async fn send(&self, mut route: Route) -> Result<()> {
+		if let Some(handle) = &self.handle {
+			route.request.2.handle = Some(handle.clone());
+		}
+		let method = route.request.1;
+		if let Err(error) = self.refresh_token(method).await {
+			let _ = route.response.into_send_async(Err(error)).await;
+			return Ok(());
+		}
+		let interceptors = self.interceptors.list();
+		let retry = self.retry.filter(|_| method.is_retryable());
+		if interceptors.is_empty() && retry.is_none() && !method.changes_session() {
+			self.sender.send_async(Some(route)).await?;
+			return Ok(());
+		}
+		let Route {
+			request: (id, method, param),
+			response,
+		} = route;
+		let mut request = Request {
+			method,
+			param,
+		};
+		for interceptor in &interceptors {
+			if let Err(error) = interceptor.before(&mut request).await {
+				let _ = response.into_send_async(Err(error)).await;
+				return Ok(());
+			}
+		}
+		// Keep the parameters which change the session, to record the change once it succeeds
+		let change = method.changes_session().then(|| request.param.other.clone());
+		let start = Instant::now();
+		let result = match retry {
+			Some(policy) => self.send_with_retries(id, method, request.param, policy).await?,
+			None => {
+				let (sender, receiver) = flume::bounded(1);
+				let route = Route {
+					request: (id, method, request.param),
+					response: sender,
+				};
+				self.sender.send_async(Some(route)).await?;
+				receiver.into_recv_async().await?
+			}
+		};
+		if let (Some(params), Ok(DbResponse::Other(value))) = (change, &result) {
+			self.session.record(method, &params, value);
+		}
+		let reply = Reply {
+			method,
+			elapsed: start.elapsed(),
+			error: result.as_ref().err(),
+		};
+		for interceptor in interceptors.iter().rev() {
+			interceptor.after(&reply).await;
+		}
+		let _ = response.into_send_async(result).await;
+		Ok(())
+	}
+
+	/// Gets a new token before sending a request, when the token of the session is about to expire
+	async fn refresh_token(&self, method: Method) -> Result<()> {
+		let Some(refresh) = &self.session.refresh else {
+			return Ok(());
+		};
+		// Requests which authenticate the session replace its token themselves
+		if matches!(
+			method,
+			Method::Signin | Method::Signup | Method::Authenticate | Method::Invalidate
+		) || !self.session.expires_within(refresh.margin)
+		{
+			return Ok(());
+		}
+		let _refreshing = self.session.refreshing.lock().await;
+		// Another request may have refreshed the token in the meantime
+		if !self.session.expires_within(refresh.margin) {
+			return Ok(());
+		}
+		trace!("Refreshing the token of the session");
+		let token = match &refresh.source {
+			RefreshSource::Signin(credentials) => {
+				let token = self.request(Method::Signin, vec![credentials.clone()]).await?;
+				from_value(token)?
+			}
+			RefreshSource::Callback(refresh) => {
+				let token: Jwt = refresh().await?;
+				self.request(Method::Authenticate, vec![token.0.clone().into()]).await?;
+				token
+			}
+		};
+		self.session.set_token(Some(token));
+		Ok(())
+	}
+
+	/// Sends a request directly to the engine, bypassing the interceptors
+	async fn request(&self, method: Method, params: Vec<Value>) -> Result<Value> {
+		let (sender, receiver) = flume::bounded(1);
+		let route = Route {
+			request: (self.next_id(), method, Param::new(params)),
+			response: sender,
+		};
+		self.sender.send_async(Some(route)).await?;
+		match receiver.into_recv_async().await?? {
+			DbResponse::Other(value) => Ok(value),
+			DbResponse::Query(..) => unreachable!(),
+		}
+	}
+
+	/// Sends a request, sending it again if the connection to the server is lost
+	async fn send_with_retries(
+		&self,
+		id: i64,
+		method: Method,
+		mut param: Param,
+		policy: RetryPolicy,
+	) -> Result<Result<DbResponse>> {
+		// Lets the server return the outcome of the first attempt, instead of writing twice
+		if method.is_write() {
+			param.idempotency_key = Some(Uuid::new_v4().to_string());
+		}
+		let mut retry = 0;
+		loop {
+			let (sender, receiver) = flume::bounded(1);
+			let route = Route {
+				request: (id, method, param.clone()),
+				response: sender,
+			};
+			self.sender.send_async(Some(route)).await?;
+			match receiver.into_recv_async().await {
+				// The request was dropped, or failed to send, as the connection was lost
+				Err(..) | Ok(Err(crate::Error::Api(Error::Ws(..) | Error::Grpc(..))))
+					if retry < policy.retries =>
+				{
+					retry += 1;
+					trace!("Retrying request {id}; attempt {retry}");
+					time::sleep(policy.delay(retry)).await;
+				}
+				Ok(result) => return Ok(result),
+				Err(error) => return Err(error.into()),
+			}
+		}
+	}
 }
 
 impl<C> Drop for Router<C>
@@ -51,7 +219,10 @@ where
 	C: api::Connection,
 {
 	fn drop(&mut self) {
-		let _res = self.sender.send(None);
+		// Isolated handles share the engine of the client they were opened from
+		if self.handle.is_none() {
+			let _res = self.sender.send(None);
+		}
 	}
 }
 
@@ -113,13 +284,18 @@ pub enum DbResponse {
 }
 
 /// Holds the parameters given to the caller
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[allow(dead_code)] // used by the embedded and remote connections
 pub struct Param {
 	pub(crate) query: Option<(Query, BTreeMap<String, Value>)>,
 	pub(crate) other: Vec<Value>,
 	pub(crate) file: Option<PathBuf>,
 	pub(crate) sender: Option<channel::Sender<Result<Vec<u8>>>>,
+	pub(crate) timeout: Option<Duration>,
+	pub(crate) headers: Vec<(String, String)>,
+	pub(crate) idempotency_key: Option<String>,
+	pub(crate) backup: ExportConfig,
+	pub(crate) handle: Option<Arc<Handle>>,
 }
 
 impl Param {
@@ -129,6 +305,12 @@ impl Param {
 			other,
 			file: None,
 			sender: None,
+			timeout: None,
+			headers: Vec::new(),
+			idempotency_key: None,
+			backup: ExportConfig::default(),
+			handle: None,
+			staleness: None,
 		}
 	}
 
@@ -138,6 +320,12 @@ impl Param {
 			other: Vec::new(),
 			file: None,
 			sender: None,
+			timeout: None,
+			headers: Vec::new(),
+			idempotency_key: None,
+			backup: ExportConfig::default(),
+			handle: None,
+			staleness: None,
 		}
 	}
 
@@ -147,6 +335,12 @@ impl Param {
 			other: Vec::new(),
 			file: Some(file),
 			sender: None,
+			timeout: None,
+			headers: Vec::new(),
+			idempotency_key: None,
+			backup: ExportConfig::default(),
+			handle: None,
+			staleness: None,
 		}
 	}
 
@@ -156,8 +350,31 @@ impl Param {
 			other: Vec::new(),
 			file: None,
 			sender: Some(send),
+			timeout: None,
+			headers: Vec::new(),
+			idempotency_key: None,
+			backup: ExportConfig::default(),
+			handle: None,
+			staleness: None,
 		}
 	}
+
+	pub(crate) fn timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Sets how stale the results of a read can be, when it is sent to a read replica
+	pub(crate) fn staleness(mut self, staleness: Option<Duration>) -> Self {
+		self.staleness = staleness;
+		self
+	}
+
+	/// Sets the contents and the format of an export, or the format of an import
+	pub(crate) fn backup(mut self, config: ExportConfig) -> Self {
+		self.backup = config;
+		self
+	}
 }
 
 /// Connection trait implemented by supported protocols
@@ -0,0 +1,613 @@
+use super::Any;
+use super::IntoEndpoint;
+use crate::api::conn::Connection;
+use crate::api::conn::DbResponse;
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Route;
+use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::opt::Config;
+use crate::api::opt::ConnectionState;
+use crate::api::opt::Endpoint;
+use crate::api::opt::Status;
+use crate::api::OnceLockExt;
+use crate::api::Result;
+use crate::api::Surreal;
+use crate::sql::Statement;
+use crate::sql::Strand;
+use crate::sql::Value;
+use flume::Receiver;
+use indexmap::IndexMap;
+use std::iter;
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::sync::PoisonError;
+use std::time::Duration;
+use trice::Instant;
+
+/// How long to wait before trying to connect to an endpoint again, after losing the connection to it
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the replicas are assumed to take to apply a write, unless configured otherwise
+const REPLICA_LAG: Duration = Duration::from_secs(1);
+
+/// An ordered list of endpoints to connect to
+///
+/// Requests are sent to the first endpoint in the list which is reachable. When the
+/// connection to it is lost, requests are sent to the next endpoint in the list, until
+/// the connection to an earlier endpoint is restored. Changes to the session, such as
+/// signing in or selecting the namespace and database, are applied to every endpoint.
+///
+/// A list or array of addresses can also be used to connect, when no other options are needed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use surrealdb::engine::any::connect;
+/// use surrealdb::engine::any::Failover;
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// // Fail over to the second server when the first one is down
+/// let db = connect(["ws://db1:8000", "ws://db2:8000"]).await?;
+///
+/// // Spread the reads over both servers
+/// let endpoints = Failover::new(["ws://db1:8000", "ws://db2:8000"]).round_robin_reads(true);
+/// let db = connect(endpoints).await?;
+///
+/// // Send the writes to the first server, and the read-only queries to the others
+/// let endpoints = Failover::new(["ws://primary:8000", "ws://replica1:8000", "ws://replica2:8000"])
+///     .read_replicas(true);
+/// let db = connect(endpoints).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Failover {
+	endpoints: Vec<String>,
+	round_robin: bool,
+	read_replicas: bool,
+	replica_lag: Option<Duration>,
+	config: Config,
+}
+
+impl Failover {
+	/// Creates a list of endpoints, in the order in which they are preferred
+	pub fn new<T>(endpoints: impl IntoIterator<Item = T>) -> Self
+	where
+		T: Into<String>,
+	{
+		Self {
+			endpoints: endpoints.into_iter().map(Into::into).collect(),
+			round_robin: false,
+			read_replicas: false,
+			replica_lag: None,
+			config: Config::default(),
+		}
+	}
+
+	/// Spreads the `select` requests over every reachable endpoint, instead of sending them to the first
+	///
+	/// The client connects to every endpoint when this is enabled.
+	pub fn round_robin_reads(mut self, enabled: bool) -> Self {
+		self.round_robin = enabled;
+		self
+	}
+
+	/// Sends the writes to the first reachable endpoint, the primary, and spreads the
+	/// `select` requests and the read-only queries over the other reachable endpoints
+	///
+	/// A query is read-only when none of its statements write. The client connects to every
+	/// endpoint when this is enabled, and reads from the primary when no replica is reachable.
+	pub fn read_replicas(mut self, enabled: bool) -> Self {
+		self.read_replicas = enabled;
+		self
+	}
+
+	/// Sets how long the replicas take, at most, to apply a write made on the primary
+	///
+	/// This is used to keep the reads of queries with a [staleness bound](crate::method::Query::max_staleness)
+	/// on the primary, for as long as a replica may be missing a write made through this client.
+	/// It defaults to one second.
+	pub fn replica_lag(mut self, lag: Duration) -> Self {
+		self.replica_lag = Some(lag);
+		self
+	}
+
+	/// Sets the configuration used to connect to each endpoint
+	pub fn config(mut self, config: Config) -> Self {
+		self.config = config;
+		self
+	}
+}
+
+impl IntoEndpoint for Failover {
+	fn into_endpoint(self) -> Result<Endpoint> {
+		let mut urls = Vec::with_capacity(self.endpoints.len());
+		for endpoint in self.endpoints {
+			urls.push(endpoint.into_endpoint()?.endpoint);
+		}
+		if urls.is_empty() {
+			return Err(Error::NoEndpoints.into());
+		}
+		let mut config = self.config;
+		config.failover = urls.split_off(1);
+		config.round_robin = self.round_robin;
+		config.read_replicas = self.read_replicas;
+		config.replica_lag = self.replica_lag;
+		Ok(Endpoint {
+			endpoint: urls.remove(0),
+			config,
+		})
+	}
+}
+
+impl<T> IntoEndpoint for Vec<T>
+where
+	T: Into<String>,
+{
+	fn into_endpoint(self) -> Result<Endpoint> {
+		Failover::new(self).into_endpoint()
+	}
+}
+
+impl<T, const N: usize> IntoEndpoint for [T; N]
+where
+	T: Into<String>,
+{
+	fn into_endpoint(self) -> Result<Endpoint> {
+		Failover::new(self).into_endpoint()
+	}
+}
+
+/// One of the endpoints in the list
+struct Member {
+	address: Endpoint,
+	client: Option<Surreal<Any>>,
+	// Cleared when a request fails because the connection was lost
+	healthy: Arc<AtomicBool>,
+	last_attempt: Option<Instant>,
+}
+
+impl Member {
+	fn router(&self) -> Option<&Router<Any>> {
+		self.client.as_ref().and_then(|client| client.router.extract().ok())
+	}
+
+	fn is_usable(&self) -> bool {
+		self.healthy.load(Ordering::Relaxed)
+			&& self.client.as_ref().is_some_and(|client| client.status() == Status::Connected)
+	}
+
+	fn is_due(&self) -> bool {
+		self.last_attempt.map_or(true, |time| time.elapsed() >= RETRY_INTERVAL)
+	}
+
+	async fn connect(&mut self, capacity: usize, session: &Session) -> Result<()> {
+		self.last_attempt = Some(Instant::now());
+		let client = Any::connect(self.address.clone(), capacity).await?;
+		session.replay(&client).await?;
+		self.client = Some(client);
+		self.healthy.store(true, Ordering::Relaxed);
+		Ok(())
+	}
+}
+
+/// The changes made to the session, which are applied to endpoints when connecting to them
+#[derive(Default)]
+struct Session {
+	replay: IndexMap<Method, Param>,
+	vars: IndexMap<String, Param>,
+}
+
+impl Session {
+	fn record(&mut self, method: Method, param: &Param) {
+		match (method, &param.other[..]) {
+			(Method::Set, [Value::Strand(Strand(key)), ..]) => {
+				self.vars.insert(key.clone(), param.clone());
+			}
+			(Method::Unset, [Value::Strand(Strand(key)), ..]) => {
+				self.vars.remove(key);
+			}
+			(Method::Set | Method::Unset, _) => {}
+			_ => {
+				// Keep the latest change last, so that it is applied last
+				self.replay.shift_remove(&method);
+				self.replay.insert(method, param.clone());
+			}
+		}
+	}
+
+	async fn replay(&self, client: &Surreal<Any>) -> Result<()> {
+		let router = client.router.extract()?;
+		let vars = self.vars.values().map(|param| (&Method::Set, param));
+		for (method, param) in self.replay.iter().chain(vars) {
+			let (sender, receiver) = flume::bounded(1);
+			let route = Route {
+				request: (router.next_id(), *method, param.clone()),
+				response: sender,
+			};
+			router.sender.send_async(Some(route)).await?;
+			receiver.into_recv_async().await??;
+		}
+		Ok(())
+	}
+}
+
+/// Connects to a list of endpoints, failing over from one to the next
+pub(super) async fn connect(address: Endpoint, capacity: usize) -> Result<Surreal<Any>> {
+	let round_robin = address.config.round_robin;
+	let replicas = match address.config.read_replicas {
+		true => Some(address.config.replica_lag.unwrap_or(REPLICA_LAG)),
+		false => None,
+	};
+	let Endpoint {
+		endpoint,
+		mut config,
+	} = address;
+	let urls = mem::take(&mut config.failover);
+	let mut members: Vec<Member> = iter::once(endpoint)
+		.chain(urls)
+		.map(|endpoint| Member {
+			address: Endpoint {
+				endpoint,
+				config: config.clone(),
+			},
+			client: None,
+			healthy: Arc::new(AtomicBool::new(true)),
+			last_attempt: None,
+		})
+		.collect();
+
+	// Connect to the first endpoint which is reachable, or to all of them when spreading reads
+	let all = round_robin || replicas.is_some();
+	let session = Session::default();
+	let mut first_error = None;
+	for member in &mut members {
+		match member.connect(capacity, &session).await {
+			Ok(()) if !all => break,
+			Ok(()) => {}
+			Err(error) => {
+				trace!("Failed to connect to {}; {error}", member.address.endpoint);
+				first_error.get_or_insert(error);
+			}
+		}
+	}
+	let Some(features) = members.iter().find_map(Member::router).map(|r| r.features.clone()) else {
+		return Err(first_error.unwrap_or_else(|| Error::EndpointsUnreachable.into()));
+	};
+
+	let (route_tx, route_rx) = match capacity {
+		0 => flume::unbounded(),
+		capacity => flume::bounded(capacity),
+	};
+	let state = ConnectionState::default();
+
+	spawn(router(members, capacity, round_robin, replicas, session, state.clone(), route_rx));
+
+	Ok(Surreal {
+		router: Arc::new(OnceLock::with_value(Router {
+			features,
+			conn: PhantomData,
+			sender: route_tx,
+			last_id: AtomicI64::new(0),
+			interceptors: Default::default(),
+			retry: None,
+			session: Default::default(),
+			handle: None,
+			state,
+		})),
+	})
+}
+
+async fn router(
+	mut members: Vec<Member>,
+	capacity: usize,
+	round_robin: bool,
+	replicas: Option<Duration>,
+	mut session: Session,
+	state: ConnectionState,
+	route_rx: Receiver<Option<Route>>,
+) {
+	let mut next_read = 0;
+	// Connect to every endpoint, when reads are spread over them
+	let all = round_robin || replicas.is_some();
+	// When the latest write sent to the primary was answered
+	let last_write = Arc::new(Mutex::new(None));
+
+	while let Ok(Some(route)) = route_rx.recv_async().await {
+		let method = route.request.1;
+
+		// Make sure there is at least one endpoint to send the request to
+		let primary = match primary(&mut members, capacity, &session, all).await {
+			Ok(index) => {
+				state.set(Status::Connected);
+				index
+			}
+			Err(error) => {
+				state.set(Status::Reconnecting);
+				let _ = route.response.into_send_async(Err(error)).await;
+				continue;
+			}
+		};
+
+		match method {
+			// Changes to the session are sent to every endpoint
+			Method::Authenticate
+			| Method::Invalidate
+			| Method::Signin
+			| Method::Use
+			| Method::Set
+			| Method::Unset => {
+				session.record(method, &route.request.2);
+				broadcast(&members, route).await;
+			}
+			// Signing up creates the user, so it is only sent to one endpoint
+			Method::Signup => signup(&members, primary, &mut session, route).await,
+			// Reads are sent to the replicas, unless they could be too stale
+			_ if replicas.is_some() && is_read(method, &route.request.2) => {
+				let lag = replicas.unwrap_or(REPLICA_LAG);
+				let fresh = match route.request.2.staleness {
+					Some(staleness) if staleness < lag => {
+						let last = *last_write.lock().unwrap_or_else(PoisonError::into_inner);
+						last.map_or(true, |time: Instant| time.elapsed() >= lag)
+					}
+					_ => true,
+				};
+				let usable: Vec<_> = members
+					.iter()
+					.enumerate()
+					.filter(|(index, member)| *index != primary && member.is_usable())
+					.map(|(_, member)| member)
+					.collect();
+				let member = match usable.is_empty() || !fresh {
+					true => &members[primary],
+					false => {
+						next_read = (next_read + 1) % usable.len();
+						usable[next_read]
+					}
+				};
+				forward(member, route).await;
+			}
+			// Writes are sent to the primary, noting when they were answered
+			_ if replicas.is_some() && method.is_write() => {
+				let (sender, receiver) = flume::bounded(1);
+				let Route {
+					request,
+					response,
+				} = route;
+				let last_write = last_write.clone();
+				spawn(async move {
+					if let Ok(result) = receiver.into_recv_async().await {
+						*last_write.lock().unwrap_or_else(PoisonError::into_inner) =
+							Some(Instant::now());
+						let _ = response.into_send_async(result).await;
+					}
+				});
+				let route = Route {
+					request,
+					response: sender,
+				};
+				forward(&members[primary], route).await;
+			}
+			Method::Select if round_robin => {
+				let usable: Vec<_> = members.iter().filter(|member| member.is_usable()).collect();
+				let member = match usable.is_empty() {
+					true => &members[primary],
+					false => {
+						next_read = (next_read + 1) % usable.len();
+						usable[next_read]
+					}
+				};
+				forward(member, route).await;
+			}
+			_ => forward(&members[primary], route).await,
+		}
+	}
+}
+
+/// Returns the first endpoint which is usable, connecting to the endpoints in order
+///
+/// When `all` is set, the client tries to connect to every endpoint, rather than stopping at the first.
+async fn primary(
+	members: &mut [Member],
+	capacity: usize,
+	session: &Session,
+	all: bool,
+) -> Result<usize> {
+	let mut primary = None;
+	let mut last_error = None;
+	for (index, member) in members.iter_mut().enumerate() {
+		if primary.is_some() && !all {
+			break;
+		}
+		if member.client.is_some() && !member.healthy.load(Ordering::Relaxed) {
+			// Drop the connection, so that it is established again later
+			trace!("Lost the connection to {}", member.address.endpoint);
+			member.client = None;
+			member.last_attempt = Some(Instant::now());
+		}
+		if member.client.is_none() && member.is_due() {
+			match member.connect(capacity, session).await {
+				Ok(()) => trace!("Connected to {}", member.address.endpoint),
+				Err(error) => {
+					trace!("Failed to connect to {}; {error}", member.address.endpoint);
+					last_error = Some(error);
+				}
+			}
+		}
+		if primary.is_none() && member.is_usable() {
+			primary = Some(index);
+		}
+	}
+	if let Some(index) = primary {
+		return Ok(index);
+	}
+	// Fall back to an endpoint which is reconnecting, as its requests are sent once it has reconnected
+	match members.iter().position(|member| member.client.is_some()) {
+		Some(index) => Ok(index),
+		None => Err(last_error.unwrap_or_else(|| Error::EndpointsUnreachable.into())),
+	}
+}
+
+/// Whether a request only reads, so that it can be sent to a replica
+fn is_read(method: Method, param: &Param) -> bool {
+	match method {
+		Method::Select => true,
+		Method::Query => param.query.as_ref().is_some_and(|(query, _)| {
+			query.iter().all(|statement| match statement {
+				// Selecting the namespace or database changes the session of the connection
+				Statement::Use(_) => false,
+				Statement::Begin(_)
+				| Statement::Cancel(_)
+				| Statement::Commit(_)
+				| Statement::Savepoint(_)
+				| Statement::Rollback(_)
+				| Statement::Release(_) => true,
+				statement => !statement.writeable(),
+			})
+		}),
+		_ => false,
+	}
+}
+
+/// Sends a request to an endpoint, marking it as unhealthy if the connection to it is lost
+async fn forward(member: &Member, route: Route) {
+	let Some(router) = member.router() else {
+		return;
+	};
+	let Route {
+		request,
+		response,
+	} = route;
+	let (sender, receiver) = flume::bounded(1);
+	let request = Route {
+		request,
+		response: sender,
+	};
+	if router.sender.send_async(Some(request)).await.is_err() {
+		member.healthy.store(false, Ordering::Relaxed);
+		return;
+	}
+	let healthy = member.healthy.clone();
+	spawn(async move {
+		match receiver.into_recv_async().await {
+			Ok(result) => {
+				if let Err(crate::Error::Api(Error::Http(..) | Error::Ws(..) | Error::Grpc(..))) =
+					&result
+				{
+					healthy.store(false, Ordering::Relaxed);
+				}
+				let _ = response.into_send_async(result).await;
+			}
+			// The request was dropped, as the connection was lost
+			Err(..) => healthy.store(false, Ordering::Relaxed),
+		}
+	});
+}
+
+/// Signs up on one endpoint, and authenticates with the returned token on the others
+async fn signup(members: &[Member], primary: usize, session: &mut Session, route: Route) {
+	let Some(router) = members[primary].router() else {
+		return;
+	};
+	let id = route.request.0;
+	let (sender, receiver) = flume::bounded(1);
+	let request = Route {
+		request: route.request,
+		response: sender,
+	};
+	if router.sender.send_async(Some(request)).await.is_err() {
+		return;
+	}
+	let Ok(result) = receiver.into_recv_async().await else {
+		return;
+	};
+	if let Ok(DbResponse::Other(token @ Value::Strand(..))) = &result {
+		let param = Param::new(vec![token.clone()]);
+		session.record(Method::Authenticate, &param);
+		for (index, member) in members.iter().enumerate() {
+			let Some(router) = member.router().filter(|_| index != primary) else {
+				continue;
+			};
+			// The outcome is not awaited, as the response has already been received
+			let (sender, _) = flume::bounded(1);
+			let request = Route {
+				request: (id, Method::Authenticate, param.clone()),
+				response: sender,
+			};
+			let _ = router.sender.send_async(Some(request)).await;
+		}
+	}
+	let _ = route.response.into_send_async(result).await;
+}
+
+/// Sends a change to the session to every connected endpoint
+async fn broadcast(members: &[Member], route: Route) {
+	let (id, method, param) = route.request;
+	let mut receivers = Vec::with_capacity(members.len());
+	for router in members.iter().filter_map(Member::router) {
+		let (sender, receiver) = flume::bounded(1);
+		let request = Route {
+			request: (id, method, param.clone()),
+			response: sender,
+		};
+		if router.sender.send_async(Some(request)).await.is_ok() {
+			receivers.push(receiver);
+		}
+	}
+	let response = route.response;
+	spawn(async move {
+		// Respond with the first response, or with any error
+		let mut result = None;
+		for receiver in receivers {
+			let res = receiver.into_recv_async().await.map_err(crate::Error::from);
+			result = match (result, res) {
+				(None, res) => Some(res.and_then(|res| res)),
+				(Some(Ok(_)), Err(error) | Ok(Err(error))) => Some(Err(error)),
+				(result, _) => result,
+			};
+		}
+		if let Some(result) = result {
+			let _ = response.into_send_async(result).await;
+		}
+	});
+}
+
+fn spawn<F>(future: F)
+where
+	F: std::future::Future<Output = ()> + Send + 'static,
+{
+	#[cfg(not(target_arch = "wasm32"))]
+	tokio::spawn(future);
+
+	#[cfg(target_arch = "wasm32")]
+	wasm_bindgen_futures::spawn_local(future);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	fn query(sql: &str) -> Param {
+		Param::query(crate::sql::parse(sql).unwrap(), BTreeMap::new())
+	}
+
+	#[test]
+	fn read_only_queries() {
+		assert!(is_read(Method::Select, &Param::new(vec![])));
+		assert!(is_read(Method::Query, &query("SELECT * FROM person; RETURN 1")));
+		assert!(is_read(Method::Query, &query("BEGIN; SELECT * FROM person; COMMIT")));
+		assert!(!is_read(Method::Query, &query("SELECT * FROM person; CREATE person")));
+		assert!(!is_read(Method::Query, &query("USE NS test; SELECT * FROM person")));
+		assert!(!is_read(Method::Create, &Param::new(vec![])));
+	}
+}
@@ -83,6 +83,7 @@
 //! }
 //! ```
 
+mod failover;
 #[cfg(not(target_arch = "wasm32"))]
 mod native;
 #[cfg(target_arch = "wasm32")]
@@ -100,6 +101,8 @@ use std::sync::Arc;
 use std::sync::OnceLock;
 use url::Url;
 
+pub use failover::Failover;
+
 /// A trait for converting inputs to a server address object
 pub trait IntoEndpoint {
 	/// Converts an input into a server address object
@@ -172,6 +175,7 @@ impl Surreal<Any> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			lazy: false,
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -199,6 +203,16 @@ impl Surreal<Any> {
 /// // Connect using HTTPS
 /// let db = connect("https://cloud.surrealdb.com").await?;
 ///
+/// // Connect using gRPC
+/// let db = connect("grpc://localhost:8000").await?;
+///
+/// // Connect using gRPC over TLS
+/// let db = connect("grpcs://cloud.surrealdb.com").await?;
+///
+/// // Connect over a Unix domain socket using WebSockets, or HTTP
+/// let db = connect("ws+unix:///var/run/surreal.sock").await?;
+/// let db = connect("http+unix:///var/run/surreal.sock").await?;
+///
 /// // Instantiate an in-memory instance
 /// let db = connect("mem://").await?;
 ///
@@ -221,6 +235,7 @@ pub fn connect(address: impl IntoEndpoint) -> Connect<Any, Surreal<Any>> {
 		router: Arc::new(OnceLock::new()),
 		address: address.into_endpoint(),
 		capacity: 0,
+		lazy: false,
 		client: PhantomData,
 		response_type: PhantomData,
 	}
@@ -301,4 +316,15 @@ mod tests {
 			"client should have access to DB"
 		);
 	}
+
+	#[tokio::test]
+	async fn failover_to_reachable_endpoint() {
+		// The first endpoint can not be connected to
+		let db = connect(["unknown://localhost", "memory"]).await.unwrap();
+		db.use_ns("N").use_db("D").await.unwrap();
+		assert!(db.query("INFO FOR DB").await.unwrap().check().is_ok());
+		// There must be at least one endpoint
+		let error = connect(Vec::<String>::new()).await.unwrap_err();
+		assert!(matches!(error, crate::Error::Api(Error::NoEndpoints)));
+	}
 }
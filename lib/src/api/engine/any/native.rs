@@ -5,10 +5,14 @@ use crate::api::conn::Route;
 use crate::api::conn::Router;
 #[allow(unused_imports)] // used by the DB engines
 use crate::api::engine;
+use crate::api::engine::any::failover;
 use crate::api::engine::any::Any;
 #[cfg(feature = "protocol-http")]
 use crate::api::engine::remote::http;
+#[cfg(feature = "protocol-ws")]
+use crate::api::engine::remote::ws::native::Security;
 use crate::api::err::Error;
+use crate::api::opt::ConnectionState;
 use crate::api::opt::Endpoint;
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 #[cfg(feature = "protocol-http")]
@@ -27,6 +31,8 @@ use reqwest::ClientBuilder;
 use std::collections::HashSet;
 use std::future::Future;
 use std::marker::PhantomData;
+#[cfg(all(feature = "protocol-http", unix))]
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
@@ -36,6 +42,8 @@ use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 #[cfg(feature = "protocol-ws")]
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 use tokio_tungstenite::Connector;
+#[cfg(all(feature = "protocol-http", unix))]
+use url::Url;
 
 impl crate::api::Connection for Any {}
 
@@ -53,6 +61,11 @@ impl Connection for Any {
 		capacity: usize,
 	) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
 		Box::pin(async move {
+			// Connect to the first of the endpoints which is reachable
+			if !address.config.failover.is_empty() {
+				return failover::connect(address, capacity).await;
+			}
+
 			let (route_tx, route_rx) = match capacity {
 				0 => flume::unbounded(),
 				capacity => flume::bounded(capacity),
@@ -60,6 +73,7 @@ impl Connection for Any {
 
 			let (conn_tx, conn_rx) = flume::bounded::<Result<()>>(1);
 			let mut features = HashSet::new();
+			let state = ConnectionState::default();
 
 			match address.endpoint.scheme() {
 				"fdb" => {
@@ -120,6 +134,36 @@ impl Connection for Any {
 					.into());
 				}
 
+				"redb" => {
+					#[cfg(feature = "kv-redb")]
+					{
+						features.insert(ExtraFeatures::Backup);
+						engine::local::native::router(address, conn_tx, route_rx);
+						conn_rx.into_recv_async().await??
+					}
+
+					#[cfg(not(feature = "kv-redb"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the `redb` storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
+				"s3" | "gcs" => {
+					#[cfg(feature = "kv-object")]
+					{
+						features.insert(ExtraFeatures::Backup);
+						engine::local::native::router(address, conn_tx, route_rx);
+						conn_rx.into_recv_async().await??
+					}
+
+					#[cfg(not(feature = "kv-object"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the object storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
 				"tikv" => {
 					#[cfg(feature = "kv-tikv")]
 					{
@@ -134,15 +178,22 @@ impl Connection for Any {
 					);
 				}
 
-				"http" | "https" => {
+				"http" | "https" | "http+unix" => {
 					#[cfg(feature = "protocol-http")]
 					{
 						features.insert(ExtraFeatures::Backup);
 						let headers = http::default_headers();
+						// Compressed responses are decompressed by the client
+						let compression = address.config.compression;
 						#[allow(unused_mut)]
-						let mut builder = ClientBuilder::new().default_headers(headers);
+						let mut builder = ClientBuilder::new()
+							.default_headers(headers)
+							.gzip(compression)
+							.zstd(compression);
+						#[cfg(any(feature = "native-tls", feature = "rustls"))]
+						let tls_options = address.config.tls_options;
 						#[cfg(any(feature = "native-tls", feature = "rustls"))]
-						if let Some(tls) = address.config.tls_config {
+						if let Some(tls) = tls_options.resolve(address.config.tls_config)? {
 							builder = match tls {
 								#[cfg(feature = "native-tls")]
 								Tls::Native(config) => builder.use_preconfigured_tls(config),
@@ -150,8 +201,27 @@ impl Connection for Any {
 								Tls::Rust(config) => builder.use_preconfigured_tls(config),
 							};
 						}
+						// Requests to `http+unix` endpoints are sent over the Unix domain socket
+						let base_url = match address.endpoint.scheme() {
+							#[cfg(unix)]
+							"http+unix" => {
+								builder =
+									builder.unix_socket(PathBuf::from(address.endpoint.path()));
+								Url::parse(engine::remote::http::UNIX_URL)?
+							}
+							_ => address.endpoint,
+						};
+						#[cfg(any(feature = "native-tls", feature = "rustls"))]
+						let base_url = match &tls_options.server_name {
+							Some(name) if base_url.scheme() == "https" => {
+								let (resolved, url) =
+									http::native::server_name(builder, base_url, name).await?;
+								builder = resolved;
+								url
+							}
+							_ => base_url,
+						};
 						let client = builder.build()?;
-						let base_url = address.endpoint;
 						engine::remote::http::health(
 							client.get(base_url.join(Method::Health.as_str())?),
 						)
@@ -166,37 +236,37 @@ impl Connection for Any {
 					.into());
 				}
 
-				"ws" | "wss" => {
+				"ws" | "wss" | "ws+unix" => {
 					#[cfg(feature = "protocol-ws")]
 					{
-						let url = address.endpoint.join(engine::remote::ws::PATH)?;
+						let url = address.endpoint;
 						#[cfg(any(feature = "native-tls", feature = "rustls"))]
-						let maybe_connector = address.config.tls_config.map(Connector::from);
+						let security = Security {
+							connector: address
+								.config
+								.tls_options
+								.resolve(address.config.tls_config)?
+								.map(Connector::from),
+							server_name: address.config.tls_options.server_name,
+						};
 						#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
-						let maybe_connector = None;
+						let security = Security::default();
 						let config = WebSocketConfig {
-							max_send_queue: match capacity {
-								0 => None,
-								capacity => Some(capacity),
-							},
 							max_message_size: Some(engine::remote::ws::native::MAX_MESSAGE_SIZE),
 							max_frame_size: Some(engine::remote::ws::native::MAX_FRAME_SIZE),
-							accept_unmasked_frames: false,
+							..Default::default()
 						};
-						let socket = engine::remote::ws::native::connect(
-							&url,
-							Some(config),
-							maybe_connector.clone(),
-						)
-						.await?;
-						engine::remote::ws::native::router(
+						engine::remote::ws::native::spawn(
 							url,
-							maybe_connector,
+							security,
 							capacity,
 							config,
-							socket,
+							address.config.pool_size,
+							address.config.metrics,
+							state.clone(),
 							route_rx,
-						);
+						)
+						.await?;
 					}
 
 					#[cfg(not(feature = "protocol-ws"))]
@@ -206,6 +276,18 @@ impl Connection for Any {
 					.into());
 				}
 
+				"grpc" | "grpcs" => {
+					#[cfg(feature = "protocol-grpc")]
+					engine::remote::grpc::native::spawn(address, capacity, state.clone(), route_rx)
+						.await?;
+
+					#[cfg(not(feature = "protocol-grpc"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the `gRPC` remote engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
 				scheme => {
 					return Err(Error::Scheme(scheme.to_owned()).into());
 				}
@@ -217,6 +299,11 @@ impl Connection for Any {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					interceptors: Default::default(),
+					retry: None,
+					session: Default::default(),
+					handle: None,
+					state,
 				})),
 			})
 		})
@@ -234,7 +321,7 @@ impl Connection for Any {
 				request: (self.id, self.method, param),
 				response: sender,
 			};
-			router.sender.send_async(Some(route)).await?;
+			router.send(route).await?;
 			Ok(receiver)
 		})
 	}
@@ -5,8 +5,10 @@ use crate::api::conn::Route;
 use crate::api::conn::Router;
 #[allow(unused_imports)] // used by the DB engines
 use crate::api::engine;
+use crate::api::engine::any::failover;
 use crate::api::engine::any::Any;
 use crate::api::err::Error;
+use crate::api::opt::ConnectionState;
 use crate::api::opt::Endpoint;
 use crate::api::DbResponse;
 use crate::api::OnceLockExt;
@@ -38,6 +40,11 @@ impl Connection for Any {
 		capacity: usize,
 	) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
 		Box::pin(async move {
+			// Connect to the first of the endpoints which is reachable
+			if !address.config.failover.is_empty() {
+				return failover::connect(address, capacity).await;
+			}
+
 			let (route_tx, route_rx) = match capacity {
 				0 => flume::unbounded(),
 				capacity => flume::bounded(capacity),
@@ -45,6 +52,7 @@ impl Connection for Any {
 
 			let (conn_tx, conn_rx) = flume::bounded::<Result<()>>(1);
 			let mut features = HashSet::new();
+			let state = ConnectionState::default();
 
 			match address.endpoint.scheme() {
 				"fdb" => {
@@ -114,6 +122,34 @@ impl Connection for Any {
 					.into());
 				}
 
+				"redb" => {
+					#[cfg(feature = "kv-redb")]
+					{
+						engine::local::wasm::router(address, conn_tx, route_rx);
+						conn_rx.into_recv_async().await??;
+					}
+
+					#[cfg(not(feature = "kv-redb"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the `redb` storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
+				"s3" | "gcs" => {
+					#[cfg(feature = "kv-object")]
+					{
+						engine::local::wasm::router(address, conn_tx, route_rx);
+						conn_rx.into_recv_async().await??;
+					}
+
+					#[cfg(not(feature = "kv-object"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the object storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
 				"tikv" => {
 					#[cfg(feature = "kv-tikv")]
 					{
@@ -145,7 +181,13 @@ impl Connection for Any {
 					{
 						let mut address = address;
 						address.endpoint = address.endpoint.join(engine::remote::ws::PATH)?;
-						engine::remote::ws::wasm::router(address, capacity, conn_tx, route_rx);
+						engine::remote::ws::wasm::router(
+							address,
+							capacity,
+							state.clone(),
+							conn_tx,
+							route_rx,
+						);
 						conn_rx.into_recv_async().await??;
 					}
 
@@ -156,6 +198,13 @@ impl Connection for Any {
 					.into());
 				}
 
+				"grpc" | "grpcs" => {
+					return Err(DbError::Ds(
+						"Cannot connect to the `gRPC` remote engine as it is not supported in WebAssembly".to_owned(),
+					)
+					.into());
+				}
+
 				scheme => {
 					return Err(Error::Scheme(scheme.to_owned()).into());
 				}
@@ -167,6 +216,11 @@ impl Connection for Any {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					interceptors: Default::default(),
+					retry: None,
+					session: Default::default(),
+					handle: None,
+					state,
 				})),
 			})
 		})
@@ -184,7 +238,7 @@ impl Connection for Any {
 				request: (self.id, self.method, param),
 				response: sender,
 			};
-			router.sender.send_async(Some(route)).await?;
+			router.send(route).await?;
 			Ok(receiver)
 		})
 	}
@@ -29,15 +29,18 @@ pub(crate) mod wasm;
 use crate::api::conn::DbResponse;
 use crate::api::conn::Method;
 use crate::api::conn::Param;
+use crate::api::conn::Router;
 use crate::api::engine::create_statement;
 use crate::api::engine::delete_statement;
 use crate::api::engine::merge_statement;
 use crate::api::engine::patch_statement;
 use crate::api::engine::select_statement;
 use crate::api::engine::update_statement;
-#[cfg(not(target_arch = "wasm32"))]
 use crate::api::err::Error;
+use crate::api::opt::Handle;
+use crate::api::opt::Isolation;
 use crate::api::Connect;
+use crate::api::OnceLockExt;
 use crate::api::Response as QueryResponse;
 use crate::api::Result;
 use crate::api::Surreal;
@@ -45,7 +48,13 @@ use crate::api::Surreal;
 use crate::channel;
 use crate::dbs::Response;
 use crate::dbs::Session;
+use crate::iam::Auth;
+use crate::iam::Role;
 use crate::kvs::Datastore;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::opt::ExportConfig;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::opt::ExportFormat;
 use crate::opt::IntoEndpoint;
 use crate::sql::Array;
 use crate::sql::Query;
@@ -55,11 +64,15 @@ use crate::sql::Strand;
 use crate::sql::Value;
 use indexmap::IndexMap;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::mem;
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
+use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
+use std::sync::OnceLock;
+use std::sync::Weak;
 use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::fs::OpenOptions;
@@ -193,6 +206,23 @@ pub struct File;
 /// # Ok(())
 /// # }
 /// ```
+///
+/// Instantiating a RocksDB-backed instance which is encrypted at rest
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Config;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::RocksDb;
+///
+/// # let key = [0; 32];
+/// # let old_key = [1; 32];
+/// let config = Config::default().encryption_key(key).previous_encryption_key(old_key);
+/// let db = Surreal::new::<RocksDb>(("temp.db", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
 #[cfg(feature = "kv-rocksdb")]
 #[cfg_attr(docsrs, doc(cfg(feature = "kv-rocksdb")))]
 #[derive(Debug)]
@@ -234,6 +264,122 @@ pub struct RocksDb;
 #[derive(Debug)]
 pub struct SpeeDb;
 
+/// ReDB database
+///
+/// # Examples
+///
+/// Instantiating a ReDB-backed instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::ReDb;
+///
+/// let db = Surreal::new::<ReDb>("temp.db").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Instantiating a ReDB-backed strict instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Config;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::ReDb;
+///
+/// let config = Config::default().strict();
+/// let db = Surreal::new::<ReDb>(("temp.db", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv-redb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kv-redb")))]
+#[derive(Debug)]
+pub struct ReDb;
+
+/// S3-compatible object storage database
+///
+/// The data is persisted under a path prefix within an S3 bucket, and
+/// cached locally in memory. The credentials and region are read from
+/// the standard `AWS_*` environment variables.
+///
+/// # Examples
+///
+/// Instantiating an S3-backed instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::S3;
+///
+/// let db = Surreal::new::<S3>("bucket/prefix").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Instantiating an S3-backed strict instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Config;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::S3;
+///
+/// let config = Config::default().strict();
+/// let db = Surreal::new::<S3>(("bucket/prefix", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv-object")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kv-object")))]
+#[derive(Debug)]
+pub struct S3;
+
+/// Google Cloud Storage object storage database
+///
+/// The data is persisted under a path prefix within a Google Cloud Storage
+/// bucket, and cached locally in memory. The credentials are read from the
+/// standard `GOOGLE_*` environment variables.
+///
+/// # Examples
+///
+/// Instantiating a GCS-backed instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::Gcs;
+///
+/// let db = Surreal::new::<Gcs>("bucket/prefix").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Instantiating a GCS-backed strict instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Config;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::Gcs;
+///
+/// let config = Config::default().strict();
+/// let db = Surreal::new::<Gcs>(("bucket/prefix", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv-object")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kv-object")))]
+#[derive(Debug)]
+pub struct Gcs;
+
 /// IndxDB database
 ///
 /// # Examples
@@ -265,6 +411,22 @@ pub struct SpeeDb;
 /// # Ok(())
 /// # }
 /// ```
+///
+/// Instantiating an IndxDB-backed instance in a named store, with encrypted data
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Config;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::IndxDb;
+///
+/// let key = [0; 32];
+/// let config = Config::default().store_name("MyStore").encryption_key(key);
+/// let db = Surreal::new::<IndxDb>(("MyDatabase", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
 #[cfg(feature = "kv-indxdb")]
 #[cfg_attr(docsrs, doc(cfg(feature = "kv-indxdb")))]
 #[derive(Debug)]
@@ -355,10 +517,97 @@ impl Surreal<Db> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			lazy: false,
 			client: PhantomData,
 			response_type: PhantomData,
 		}
 	}
+
+	/// Opens another handle to the same datastore, with a session of its own
+	///
+	/// The namespace, database, authentication and parameters of the session of the new handle
+	/// are independent of those of this client and of any other handle. Handles which are
+	/// restricted to a namespace can not switch to another one, which allows serving several
+	/// tenants from a single embedded datastore.
+	///
+	/// The datastore stays open for as long as any of its handles is in use.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::engine::local::Mem;
+	/// use surrealdb::opt::Isolation;
+	/// use surrealdb::Surreal;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let db = Surreal::new::<Mem>(()).await?;
+	///
+	/// let acme = db.isolated(Isolation::new().namespace("acme"))?;
+	/// acme.use_db("app").await?;
+	///
+	/// let globex = db.isolated(Isolation::new().namespace("globex"))?;
+	/// globex.use_db("app").await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn isolated(&self, isolation: Isolation) -> Result<Surreal<Db>> {
+		let router = self.router.extract()?;
+		let handle = Handle::new(isolation, self.router.clone());
+		let router = Router {
+			features: router.features.clone(),
+			conn: PhantomData,
+			sender: router.sender.clone(),
+			last_id: AtomicI64::new(0),
+			interceptors: Default::default(),
+			retry: None,
+			session: Default::default(),
+			handle: Some(Arc::new(handle)),
+			state: router.state.clone(),
+		};
+		Ok(Surreal {
+			router: Arc::new(OnceLock::with_value(router)),
+		})
+	}
+}
+
+/// The sessions of the handles opened with [`Surreal::isolated`], which share an engine
+#[derive(Default)]
+struct Sessions(HashMap<usize, (Weak<Handle>, Session, BTreeMap<String, Value>)>);
+
+impl Sessions {
+	fn get(
+		&mut self,
+		handle: &Arc<Handle>,
+		kvs: &Datastore,
+	) -> (&mut Session, &mut BTreeMap<String, Value>) {
+		// The weak reference keeps the allocation, so the address is not reused while it is kept
+		let key = Arc::as_ptr(handle) as usize;
+		if !self.0.contains_key(&key) {
+			// Forget the sessions of the handles which have been dropped
+			self.0.retain(|_, (handle, ..)| handle.strong_count() > 0);
+			let session = isolated_session(&handle.isolation, kvs);
+			self.0.insert(key, (Arc::downgrade(handle), session, BTreeMap::new()));
+		}
+		let (_, session, vars) = self.0.get_mut(&key).unwrap();
+		(session, vars)
+	}
+}
+
+/// Returns the session which an isolated handle starts with
+fn isolated_session(isolation: &Isolation, kvs: &Datastore) -> Session {
+	let mut session = Session::default();
+	if let Some(ns) = &isolation.ns {
+		session.ns = Some(ns.clone());
+		// Without authentication, guests can access every namespace, so the handle acts as
+		// the owner of its namespace instead
+		if !kvs.is_auth_enabled() {
+			session.au = Arc::new(Auth::for_ns(Role::Owner, ns));
+		}
+	}
+	session.capabilities = isolation.capabilities.clone().map(Arc::new);
+	session.strict = isolation.strict;
+	session
 }
 
 fn process(responses: Vec<Response>) -> Result<QueryResponse> {
@@ -408,9 +657,10 @@ async fn export(
 	sess: &Session,
 	ns: String,
 	db: String,
+	config: ExportConfig,
 	chn: channel::Sender<Vec<u8>>,
 ) -> Result<()> {
-	if let Err(error) = kvs.export(sess, ns, db, chn).await?.await {
+	if let Err(error) = kvs.export_with(sess, ns, db, config, chn).await?.await {
 		if let crate::error::Db::Channel(message) = error {
 			// This is not really an error. Just logging it for improved visibility.
 			trace!("{message}");
@@ -449,6 +699,12 @@ async fn router(
 
 	match method {
 		Method::Use => {
+			if let (Some(handle), [Value::Strand(Strand(ns)), _]) = (&param.handle, &params[..]) {
+				if let Some(allowed) = handle.isolation.ns.as_ref().filter(|allowed| *allowed != ns)
+				{
+					return Err(Error::NamespaceNotAllowed(allowed.clone()).into());
+				}
+			}
 			match &mut params[..] {
 				[Value::Strand(Strand(ns)), Value::Strand(Strand(db))] => {
 					session.ns = Some(mem::take(ns));
@@ -490,10 +746,14 @@ async fn router(
 		}
 		Method::Invalidate => {
 			crate::iam::clear::clear(session)?;
+			// Isolated handles go back to the access they were opened with
+			if let Some(handle) = &param.handle {
+				session.au = isolated_session(&handle.isolation, kvs).au;
+			}
 			Ok(DbResponse::Other(Value::None))
 		}
 		Method::Create => {
-			let statement = create_statement(&mut params);
+			let statement = create_statement(&mut params, param.timeout);
 			let query = Query(Statements(vec![Statement::Create(statement)]));
 			let response = kvs.process(query, &*session, Some(vars.clone())).await?;
 			let value = take(true, response).await?;
@@ -521,7 +781,7 @@ async fn router(
 			Ok(DbResponse::Other(value))
 		}
 		Method::Select => {
-			let (one, statement) = select_statement(&mut params);
+			let (one, statement) = select_statement(&mut params, param.timeout);
 			let query = Query(Statements(vec![Statement::Select(statement)]));
 			let response = kvs.process(query, &*session, Some(vars.clone())).await?;
 			let value = take(one, response).await?;
@@ -559,7 +819,7 @@ async fn router(
 					let (mut writer, mut reader) = io::duplex(10_240);
 
 					// Write to channel.
-					let export = export(kvs, session, ns, db, tx);
+					let export = export(kvs, session, ns, db, param.backup, tx);
 
 					// Read from channel and write to pipe.
 					let bridge = async move {
@@ -598,9 +858,10 @@ async fn router(
 				(None, Some(backup)) => {
 					let kvs = kvs.clone();
 					let session = session.clone();
+					let config = param.backup;
 					tokio::spawn(async move {
 						let export = async {
-							if let Err(error) = export(&kvs, &session, ns, db, tx).await {
+							if let Err(error) = export(&kvs, &session, ns, db, config, tx).await {
 								let _ = backup.send(Err(error)).await;
 							}
 						};
@@ -642,7 +903,15 @@ async fn router(
 				}
 				.into());
 			}
-			let responses = kvs.execute(&statements, &*session, Some(vars.clone())).await?;
+			let responses = match param.backup.format {
+				ExportFormat::Sql => {
+					kvs.execute(&statements, &*session, Some(vars.clone())).await?
+				}
+				ExportFormat::Json => {
+					let statements = crate::kvs::json_to_sql(&statements)?;
+					kvs.execute(&statements, &*session, Some(vars.clone())).await?
+				}
+			};
 			for response in responses {
 				response.result?;
 			}
@@ -5,6 +5,7 @@ use crate::api::conn::Param;
 use crate::api::conn::Route;
 use crate::api::conn::Router;
 use crate::api::engine::local::Db;
+use crate::api::engine::local::Sessions;
 use crate::api::engine::local::DEFAULT_TICK_INTERVAL;
 use crate::api::err::Error;
 use crate::api::opt::Endpoint;
@@ -25,6 +26,8 @@ use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::future::Future;
 use std::marker::PhantomData;
+#[cfg(feature = "kv-mem")]
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
@@ -67,6 +70,11 @@ impl Connection for Db {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					interceptors: Default::default(),
+					retry: None,
+					session: Default::default(),
+					handle: None,
+					state: Default::default(),
 				})),
 			})
 		})
@@ -83,7 +91,7 @@ impl Connection for Db {
 				request: (0, self.method, param),
 				response: sender,
 			};
-			router.sender.send_async(Some(route)).await?;
+			router.send(route).await?;
 			Ok(receiver)
 		})
 	}
@@ -104,10 +112,14 @@ pub(crate) fn router(
 			_ => None,
 		};
 
+		// Only the in-memory engine is persisted to a snapshot
+		#[cfg(feature = "kv-mem")]
+		let snapshot = address.config.snapshot.clone().filter(|_| url.scheme() == "mem");
+
 		let kvs = {
 			let path = match url.scheme() {
 				"mem" => "memory".to_owned(),
-				"fdb" | "rocksdb" | "speedb" | "file" => match url.to_file_path() {
+				"fdb" | "rocksdb" | "speedb" | "redb" | "file" => match url.to_file_path() {
 					Ok(path) => format!("{}://{}", url.scheme(), path.display()),
 					Err(_) => {
 						let error = Error::InvalidUrl(url.as_str().to_owned());
@@ -118,15 +130,63 @@ pub(crate) fn router(
 				_ => url.as_str().to_owned(),
 			};
 
-			match Datastore::new(&path).await {
+			let kvs = match url.scheme() {
+				#[cfg(feature = "kv-tikv")]
+				"tikv" => Datastore::new_tikv(&path, address.config.tikv.clone()).await,
+				#[cfg(feature = "kv-fdb")]
+				"fdb" => Datastore::new_fdb(&path, address.config.fdb.clone()).await,
+				_ => Datastore::new(&path).await,
+			};
+
+			match kvs {
 				Ok(kvs) => {
-					// If a root user is specified, setup the initial datastore credentials
-					if let Some(root) = configured_root {
-						if let Err(error) = kvs.setup_initial_creds(root).await {
+					// Reload the data which was saved by a previous run
+					#[cfg(feature = "kv-mem")]
+					if let Some(path) = snapshot.as_ref().filter(|path| path.exists()) {
+						if let Err(error) = kvs.load_snapshot(path).await {
 							let _ = conn_tx.into_send_async(Err(error.into())).await;
 							return;
 						}
 					}
+					#[cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+					let kvs = match &address.config.encryption_key {
+						Some(key) => kvs.with_encryption_key(key.0),
+						None => kvs,
+					};
+					#[cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+					let kvs = address
+						.config
+						.previous_encryption_keys
+						.iter()
+						.fold(kvs, |kvs, key| kvs.with_previous_encryption_key(key.0));
+					// Join the replication group, and wait for it to elect a leader
+					#[cfg(feature = "replication")]
+					let kvs = match address.config.replication.clone() {
+						Some(options) => match kvs.with_replication(options).await {
+							Ok(kvs) => {
+								kvs.wait_for_leader(Duration::from_secs(10)).await;
+								kvs
+							}
+							Err(error) => {
+								let _ = conn_tx.into_send_async(Err(error.into())).await;
+								return;
+							}
+						},
+						None => kvs,
+					};
+					// If a root user is specified, setup the initial datastore credentials
+					if let Some(root) = configured_root {
+						match kvs.setup_initial_creds(root).await {
+							Ok(()) => {}
+							// The credentials are set up on the leader, and replicated from there
+							#[cfg(feature = "replication")]
+							Err(crate::err::Error::ReplicaNotLeader) => {}
+							Err(error) => {
+								let _ = conn_tx.into_send_async(Err(error.into())).await;
+								return;
+							}
+						}
+					}
 					let _ = conn_tx.into_send_async(Ok(())).await;
 					kvs.with_auth_enabled(configured_root.is_some())
 				}
@@ -152,13 +212,29 @@ pub(crate) fn router(
 		let mut vars = BTreeMap::new();
 		let mut stream = route_rx.into_stream();
 		let mut session = Session::default();
+		let mut sessions = Sessions::default();
 
 		let (maintenance_tx, maintenance_rx) = flume::bounded::<()>(1);
 		let tick_interval = address.config.tick_interval.unwrap_or(DEFAULT_TICK_INTERVAL);
 		run_maintenance(kvs.clone(), tick_interval, maintenance_rx);
 
+		let (builds_tx, builds_rx) = flume::bounded::<()>(1);
+		run_index_builds(kvs.clone(), tick_interval, builds_rx);
+
+		#[cfg(feature = "kv-mem")]
+		let (snapshot_tx, snapshot_rx) = flume::bounded::<()>(1);
+		#[cfg(feature = "kv-mem")]
+		if let (Some(path), Some(interval)) = (&snapshot, address.config.snapshot_interval) {
+			run_snapshots(kvs.clone(), path.clone(), interval, snapshot_rx);
+		}
+
 		while let Some(Some(route)) = stream.next().await {
-			match super::router(route.request, &kvs, &mut session, &mut vars).await {
+			// Requests from isolated handles use the sessions of their handles
+			let (session, vars) = match &route.request.2.handle {
+				Some(handle) => sessions.get(handle, &kvs),
+				None => (&mut session, &mut vars),
+			};
+			match super::router(route.request, &kvs, session, vars).await {
 				Ok(value) => {
 					let _ = route.response.into_send_async(Ok(value)).await;
 				}
@@ -170,6 +246,44 @@ pub(crate) fn router(
 
 		// Stop maintenance tasks
 		let _ = maintenance_tx.into_send_async(()).await;
+		let _ = builds_tx.into_send_async(()).await;
+
+		// Save the data for the next run
+		#[cfg(feature = "kv-mem")]
+		if let Some(path) = snapshot {
+			let _ = snapshot_tx.into_send_async(()).await;
+			if let Err(error) = kvs.save_snapshot(&path).await {
+				error!("Error saving the snapshot of the datastore: {error}");
+			}
+		}
+	});
+}
+
+#[cfg(feature = "kv-mem")]
+fn run_snapshots(
+	kvs: Arc<Datastore>,
+	path: PathBuf,
+	interval: Duration,
+	stop_signal: Receiver<()>,
+) {
+	tokio::spawn(async move {
+		let mut interval = time::interval(interval);
+		interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+		// Delay saving the first snapshot
+		interval.tick().await;
+
+		let ticker = IntervalStream::new(interval);
+
+		let streams = (ticker.map(Some), stop_signal.into_stream().map(|_| None));
+
+		let mut stream = streams.merge();
+
+		while let Some(Some(_)) = stream.next().await {
+			match kvs.save_snapshot(&path).await {
+				Ok(()) => trace!("Saved a snapshot of the datastore"),
+				Err(error) => error!("Error saving the snapshot of the datastore: {error}"),
+			}
+		}
 	});
 }
 
@@ -195,3 +309,25 @@ fn run_maintenance(kvs: Arc<Datastore>, tick_interval: Duration, stop_signal: Re
 		}
 	});
 }
+
+fn run_index_builds(kvs: Arc<Datastore>, tick_interval: Duration, stop_signal: Receiver<()>) {
+	tokio::spawn(async move {
+		let mut interval = time::interval(tick_interval);
+		// Don't start another build while one is still running
+		interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+		// Delay the first build
+		interval.tick().await;
+
+		let ticker = IntervalStream::new(interval);
+
+		let streams = (ticker.map(Some), stop_signal.into_stream().map(|_| None));
+
+		let mut stream = streams.merge();
+
+		while let Some(Some(_)) = stream.next().await {
+			if let Err(error) = kvs.build_indexes_in_background().await {
+				error!("Error building indexes in the background: {error}");
+			}
+		}
+	});
+}
@@ -5,6 +5,7 @@ use crate::api::conn::Param;
 use crate::api::conn::Route;
 use crate::api::conn::Router;
 use crate::api::engine::local::Db;
+use crate::api::engine::local::Sessions;
 use crate::api::engine::local::DEFAULT_TICK_INTERVAL;
 use crate::api::opt::Endpoint;
 use crate::api::OnceLockExt;
@@ -63,6 +64,11 @@ impl Connection for Db {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					interceptors: Default::default(),
+					retry: None,
+					session: Default::default(),
+					handle: None,
+					state: Default::default(),
 				})),
 			})
 		})
@@ -79,7 +85,7 @@ impl Connection for Db {
 				request: (0, self.method, param),
 				response: sender,
 			};
-			router.sender.send_async(Some(route)).await?;
+			router.send(route).await?;
 			Ok(receiver)
 		})
 	}
@@ -107,6 +113,16 @@ pub(crate) fn router(
 
 		let kvs = match Datastore::new(path).await {
 			Ok(kvs) => {
+				#[cfg(feature = "kv-indxdb")]
+				let kvs = match &address.config.store_name {
+					Some(name) => kvs.with_store_name(name),
+					None => kvs,
+				};
+				#[cfg(feature = "kv-indxdb")]
+				let kvs = match &address.config.encryption_key {
+					Some(key) => kvs.with_encryption_key(key.0),
+					None => kvs,
+				};
 				// If a root user is specified, setup the initial datastore credentials
 				if let Some(root) = configured_root {
 					if let Err(error) = kvs.setup_initial_creds(root).await {
@@ -126,7 +142,8 @@ pub(crate) fn router(
 		let kvs = kvs
 			.with_strict_mode(address.config.strict)
 			.with_query_timeout(address.config.query_timeout)
-			.with_transaction_timeout(address.config.transaction_timeout);
+			.with_transaction_timeout(address.config.transaction_timeout)
+			.with_capabilities(address.config.capabilities);
 
 		let kvs = match address.config.notifications {
 			true => kvs.with_notifications(),
@@ -137,13 +154,22 @@ pub(crate) fn router(
 		let mut vars = BTreeMap::new();
 		let mut stream = route_rx.into_stream();
 		let mut session = Session::default();
+		let mut sessions = Sessions::default();
 
 		let (maintenance_tx, maintenance_rx) = flume::bounded::<()>(1);
 		let tick_interval = address.config.tick_interval.unwrap_or(DEFAULT_TICK_INTERVAL);
 		run_maintenance(kvs.clone(), tick_interval, maintenance_rx);
 
+		let (builds_tx, builds_rx) = flume::bounded::<()>(1);
+		run_index_builds(kvs.clone(), tick_interval, builds_rx);
+
 		while let Some(Some(route)) = stream.next().await {
-			match super::router(route.request, &kvs, &mut session, &mut vars).await {
+			// Requests from isolated handles use the sessions of their handles
+			let (session, vars) = match &route.request.2.handle {
+				Some(handle) => sessions.get(handle, &kvs),
+				None => (&mut session, &mut vars),
+			};
+			match super::router(route.request, &kvs, session, vars).await {
 				Ok(value) => {
 					let _ = route.response.into_send_async(Ok(value)).await;
 				}
@@ -155,6 +181,12 @@ pub(crate) fn router(
 
 		// Stop maintenance tasks
 		let _ = maintenance_tx.into_send_async(()).await;
+		let _ = builds_tx.into_send_async(()).await;
+
+		// Flush any buffered writes to durable storage
+		if let Err(error) = kvs.flush().await {
+			error!("Error flushing the datastore: {error}");
+		}
 	});
 }
 
@@ -180,3 +212,25 @@ fn run_maintenance(kvs: Arc<Datastore>, tick_interval: Duration, stop_signal: Re
 		}
 	});
 }
+
+fn run_index_builds(kvs: Arc<Datastore>, tick_interval: Duration, stop_signal: Receiver<()>) {
+	spawn_local(async move {
+		let mut interval = time::interval(tick_interval);
+		// Don't start another build while one is still running
+		interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+		// Delay the first build
+		interval.tick().await;
+
+		let ticker = IntervalStream::new(interval);
+
+		let streams = (ticker.map(Some), stop_signal.into_stream().map(|_| None));
+
+		let mut stream = streams.merge();
+
+		while let Some(Some(_)) = stream.next().await {
+			if let Err(error) = kvs.build_indexes_in_background().await {
+				error!("Error building indexes in the background: {error}");
+			}
+		}
+	});
+}
@@ -6,11 +6,13 @@ pub mod any;
 	feature = "kv-tikv",
 	feature = "kv-rocksdb",
 	feature = "kv-speedb",
+	feature = "kv-redb",
+	feature = "kv-object",
 	feature = "kv-fdb",
 	feature = "kv-indxdb",
 ))]
 pub mod local;
-#[cfg(any(feature = "protocol-http", feature = "protocol-ws"))]
+#[cfg(any(feature = "protocol-http", feature = "protocol-ws", feature = "protocol-grpc"))]
 pub mod remote;
 
 use crate::sql::statements::CreateStatement;
@@ -22,6 +24,7 @@ use crate::sql::Data;
 use crate::sql::Field;
 use crate::sql::Fields;
 use crate::sql::Output;
+use crate::sql::Timeout;
 use crate::sql::Value;
 use crate::sql::Values;
 use futures::Stream;
@@ -29,6 +32,7 @@ use std::mem;
 use std::pin::Pin;
 use std::task::Context;
 use std::task::Poll;
+use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::time::Instant;
 #[cfg(not(target_arch = "wasm32"))]
@@ -54,7 +58,7 @@ fn split_params(params: &mut [Value]) -> (bool, Values, Value) {
 }
 
 #[allow(dead_code)] // used by the the embedded database and `http`
-fn create_statement(params: &mut [Value]) -> CreateStatement {
+fn create_statement(params: &mut [Value], timeout: Option<Duration>) -> CreateStatement {
 	let (_, what, data) = split_params(params);
 	let data = match data {
 		Value::None | Value::Null => None,
@@ -64,6 +68,7 @@ fn create_statement(params: &mut [Value]) -> CreateStatement {
 		what,
 		data,
 		output: Some(Output::After),
+		timeout: timeout.map(|v| Timeout(v.into())),
 		..Default::default()
 	}
 }
@@ -123,13 +128,14 @@ fn merge_statement(params: &mut [Value]) -> (bool, UpdateStatement) {
 }
 
 #[allow(dead_code)] // used by the the embedded database and `http`
-fn select_statement(params: &mut [Value]) -> (bool, SelectStatement) {
+fn select_statement(params: &mut [Value], timeout: Option<Duration>) -> (bool, SelectStatement) {
 	let (one, what, _) = split_params(params);
 	(
 		one,
 		SelectStatement {
 			what,
 			expr: Fields(vec![Field::All], false),
+			timeout: timeout.map(|v| Timeout(v.into())),
 			..Default::default()
 		},
 	)
@@ -0,0 +1,153 @@
+//! gRPC engine
+//!
+//! Requests are sent over a single bidirectional gRPC stream, using HTTP/2. Each
+//! request and response is framed as a length-prefixed binary message, so that
+//! many requests can be in flight at once, with flow control applied by HTTP/2.
+
+pub(crate) mod native;
+
+use crate::api::conn::Method;
+use crate::api::err::Error;
+use crate::api::Connect;
+use crate::api::Result;
+use crate::api::Surreal;
+use crate::opt::IntoEndpoint;
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
+use bytes::BytesMut;
+use std::marker::PhantomData;
+use std::time::Duration;
+use url::Url;
+
+pub(crate) const PATH: &str = "surrealdb.rpc.Rpc/Stream";
+const CONTENT_TYPE: &str = "application/grpc+bincode";
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
+const PING_METHOD: &str = "ping";
+/// The length of the header which prefixes each message
+const HEADER_LEN: usize = 5;
+
+/// The gRPC scheme used to connect to `grpc://` endpoints, using HTTP/2 without TLS
+#[derive(Debug)]
+pub struct Grpc;
+
+/// The gRPCs scheme used to connect to `grpcs://` endpoints, using HTTP/2 over TLS
+#[derive(Debug)]
+pub struct Grpcs;
+
+/// A gRPC client for communicating with the server via HTTP/2
+#[derive(Debug, Clone)]
+pub struct Client {
+	pub(crate) id: i64,
+	method: Method,
+}
+
+impl Surreal<Client> {
+	/// Connects to a specific database endpoint, saving the connection on the static client
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use once_cell::sync::Lazy;
+	/// use surrealdb::Surreal;
+	/// use surrealdb::engine::remote::grpc::Client;
+	/// use surrealdb::engine::remote::grpc::Grpc;
+	///
+	/// static DB: Lazy<Surreal<Client>> = Lazy::new(Surreal::init);
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// DB.connect::<Grpc>("localhost:8000").await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn connect<P>(
+		&self,
+		address: impl IntoEndpoint<P, Client = Client>,
+	) -> Connect<Client, ()> {
+		Connect {
+			router: self.router.clone(),
+			address: address.into_endpoint(),
+			capacity: 0,
+			lazy: false,
+			client: PhantomData,
+			response_type: PhantomData,
+		}
+	}
+}
+
+/// Returns the URL of the RPC stream, as gRPC runs over `http` and `https`
+pub(crate) fn stream_url(endpoint: &Url) -> Result<Url> {
+	let scheme = match endpoint.scheme() {
+		"grpcs" => "https",
+		_ => "http",
+	};
+	let address = &endpoint[url::Position::BeforeHost..url::Position::AfterPort];
+	let url = format!("{scheme}://{address}/");
+	Ok(Url::parse(&url).map_err(|_| Error::InvalidUrl(url))?.join(PATH)?)
+}
+
+/// Prefixes a message with its header, which holds the
+/// compression flag and the length of the message
+fn encode(message: &[u8]) -> Bytes {
+	let mut frame = BytesMut::with_capacity(HEADER_LEN + message.len());
+	frame.put_u8(0);
+	frame.put_u32(message.len() as u32);
+	frame.put_slice(message);
+	frame.freeze()
+}
+
+/// Splits the data received from the server into messages
+#[derive(Debug, Default)]
+struct Decoder {
+	buffer: BytesMut,
+}
+
+impl Decoder {
+	/// Adds received data, returning any messages which are now complete
+	fn decode(&mut self, data: &[u8]) -> Result<Vec<Bytes>> {
+		self.buffer.extend_from_slice(data);
+		let mut messages = Vec::new();
+		while self.buffer.len() >= HEADER_LEN {
+			let len = u32::from_be_bytes([
+				self.buffer[1],
+				self.buffer[2],
+				self.buffer[3],
+				self.buffer[4],
+			]) as usize;
+			if self.buffer.len() < HEADER_LEN + len {
+				break;
+			}
+			if self.buffer[0] != 0 {
+				return Err(Error::Grpc("Compressed messages are not supported".to_owned()).into());
+			}
+			self.buffer.advance(HEADER_LEN);
+			messages.push(self.buffer.split_to(len).freeze());
+		}
+		Ok(messages)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn decode_split_messages() {
+		let mut data = encode(b"one").to_vec();
+		data.extend_from_slice(&encode(b"two"));
+		let mut decoder = Decoder::default();
+		// Messages are only returned once complete
+		assert!(decoder.decode(&data[..4]).unwrap().is_empty());
+		assert_eq!(decoder.decode(&data[4..10]).unwrap(), vec![Bytes::from_static(b"one")]);
+		assert_eq!(decoder.decode(&data[10..]).unwrap(), vec![Bytes::from_static(b"two")]);
+	}
+
+	#[test]
+	fn url_of_stream() {
+		let url = stream_url(&Url::parse("grpc://localhost:8000").unwrap()).unwrap();
+		assert_eq!(url.as_str(), "http://localhost:8000/surrealdb.rpc.Rpc/Stream");
+		let url = stream_url(&Url::parse("grpcs://cloud.surrealdb.com").unwrap()).unwrap();
+		assert_eq!(url.as_str(), "https://cloud.surrealdb.com/surrealdb.rpc.Rpc/Stream");
+	}
+}
@@ -0,0 +1,441 @@
+use super::Client;
+use super::Decoder;
+use super::CONTENT_TYPE;
+use super::KEEP_ALIVE_INTERVAL;
+use super::PING_METHOD;
+use crate::api::conn::Connection;
+use crate::api::conn::DbResponse;
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Route;
+use crate::api::conn::Router;
+use crate::api::engine::remote::rpc::Response;
+use crate::api::err::Error;
+use crate::api::opt::ConnectionState;
+use crate::api::opt::Endpoint;
+use crate::api::opt::MetricsHook;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use crate::api::opt::Tls;
+use crate::api::OnceLockExt;
+use crate::api::Result;
+use crate::api::Surreal;
+use crate::sql::serde::{deserialize, serialize};
+use crate::sql::Strand;
+use crate::sql::Value;
+use bytes::Bytes;
+use flume::Receiver;
+use flume::Sender;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use futures_concurrency::stream::Merge as _;
+use indexmap::IndexMap;
+use reqwest::header::CONTENT_TYPE as CONTENT_TYPE_HEADER;
+use reqwest::header::TE;
+use reqwest::Body;
+use reqwest::ClientBuilder;
+use serde::Deserialize;
+use std::collections::hash_map::Entry;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::future::Future;
+use std::io;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
+use std::sync::OnceLock;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use tokio::net::lookup_host;
+use tokio::time;
+use url::Url;
+
+/// The messages sent by the server over the stream
+type Incoming = BoxStream<'static, reqwest::Result<Bytes>>;
+
+enum Either {
+	Request(Option<Route>),
+	Response(reqwest::Result<Bytes>),
+}
+
+impl crate::api::Connection for Client {}
+
+impl Connection for Client {
+	fn new(method: Method) -> Self {
+		Self {
+			id: 0,
+			method,
+		}
+	}
+
+	fn connect(
+		address: Endpoint,
+		capacity: usize,
+	) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
+		Box::pin(async move {
+			let (route_tx, route_rx) = match capacity {
+				0 => flume::unbounded(),
+				capacity => flume::bounded(capacity),
+			};
+
+			let state = ConnectionState::default();
+
+			spawn(address, capacity, state.clone(), route_rx).await?;
+
+			Ok(Surreal {
+				router: Arc::new(OnceLock::with_value(Router {
+					features: HashSet::new(),
+					conn: PhantomData,
+					sender: route_tx,
+					last_id: AtomicI64::new(0),
+					interceptors: Default::default(),
+					retry: None,
+					session: Default::default(),
+					handle: None,
+					state,
+				})),
+			})
+		})
+	}
+
+	fn send<'r>(
+		&'r mut self,
+		router: &'r Router<Self>,
+		param: Param,
+	) -> Pin<Box<dyn Future<Output = Result<Receiver<Result<DbResponse>>>> + Send + Sync + 'r>> {
+		Box::pin(async move {
+			self.id = router.next_id();
+			let (sender, receiver) = flume::bounded(1);
+			let route = Route {
+				request: (self.id, self.method, param),
+				response: sender,
+			};
+			router.send(route).await?;
+			Ok(receiver)
+		})
+	}
+}
+
+/// Connects to the server, and spawns the router which sends the requests
+pub(crate) async fn spawn(
+	address: Endpoint,
+	capacity: usize,
+	state: ConnectionState,
+	route_rx: Receiver<Option<Route>>,
+) -> Result<()> {
+	// Requests are sent over HTTP/2, without first negotiating the protocol
+	#[allow(unused_mut)]
+	let mut builder = ClientBuilder::new()
+		.http2_prior_knowledge()
+		.http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+		.http2_keep_alive_while_idle(true);
+
+	#[cfg(any(feature = "native-tls", feature = "rustls"))]
+	if let Some(tls) = address.config.tls_options.resolve(address.config.tls_config)? {
+		builder = match tls {
+			#[cfg(feature = "native-tls")]
+			Tls::Native(config) => builder.use_preconfigured_tls(config),
+			#[cfg(feature = "rustls")]
+			Tls::Rust(config) => builder.use_preconfigured_tls(config),
+		};
+	}
+
+	let url = super::stream_url(&address.endpoint)?;
+
+	#[cfg(any(feature = "native-tls", feature = "rustls"))]
+	let url = match &address.config.tls_options.server_name {
+		Some(name) if url.scheme() == "https" => {
+			let (resolved, url) = server_name(builder, url, name).await?;
+			builder = resolved;
+			url
+		}
+		_ => url,
+	};
+
+	let client = builder.build().map_err(|error| Error::Grpc(error.to_string()))?;
+	let metrics = address.config.metrics.clone();
+
+	let (sender, incoming) = open(&client, &url).await?;
+
+	router(url, client, capacity, sender, incoming, metrics, state, route_rx);
+
+	Ok(())
+}
+
+/// Connects to the address of the endpoint, while using the server
+/// name to verify the server certificate, and as the authority
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+async fn server_name(
+	builder: ClientBuilder,
+	mut url: Url,
+	name: &str,
+) -> Result<(ClientBuilder, Url)> {
+	let host = url.host_str().unwrap_or_default().to_owned();
+	let port = url.port_or_known_default().unwrap_or_default();
+	let mut addresses =
+		lookup_host((host.as_str(), port)).await.map_err(|e| Error::Grpc(e.to_string()))?;
+	let address = match addresses.next() {
+		Some(address) => address,
+		None => return Err(Error::Grpc(format!("Unable to resolve `{host}`")).into()),
+	};
+	url.set_host(Some(name))?;
+	Ok((builder.resolve(name, address), url))
+}
+
+/// Opens the bidirectional stream to the server, returning the
+/// channel used to send messages, and the messages received
+async fn open(client: &reqwest::Client, url: &Url) -> Result<(Sender<Bytes>, Incoming)> {
+	let (sender, receiver) = flume::unbounded::<Bytes>();
+	let body = Body::wrap_stream(receiver.into_stream().map(Ok::<_, io::Error>));
+	let response = client
+		.post(url.clone())
+		.header(CONTENT_TYPE_HEADER, CONTENT_TYPE)
+		.header(TE, "trailers")
+		.body(body)
+		.send()
+		.await
+		.map_err(|error| Error::Grpc(error.to_string()))?;
+	let status = response.status();
+	if !status.is_success() {
+		return Err(Error::Grpc(format!("The server responded with {status}")).into());
+	}
+	// The server may reject the stream without sending any messages
+	if let Some(code) = response.headers().get("grpc-status") {
+		if code != "0" {
+			let message = response
+				.headers()
+				.get("grpc-message")
+				.and_then(|v| v.to_str().ok())
+				.unwrap_or_default()
+				.to_owned();
+			return Err(Error::Grpc(format!("The stream was rejected; {message}")).into());
+		}
+	}
+	Ok((sender, response.bytes_stream().boxed()))
+}
+
+/// Serialises a request, and frames it to be sent over the stream
+fn frame(id: Option<i64>, method: &str, params: Vec<Value>, key: Option<String>) -> Bytes {
+	let mut request = BTreeMap::new();
+	if let Some(id) = id {
+		request.insert("id".to_owned(), Value::from(id));
+	}
+	request.insert("method".to_owned(), method.into());
+	// Lets the server deduplicate requests which are retried
+	if let Some(key) = key {
+		request.insert("key".to_owned(), key.into());
+	}
+	if !params.is_empty() {
+		request.insert("params".to_owned(), params.into());
+	}
+	let payload = Value::from(request);
+	trace!("Request {payload}");
+	super::encode(&serialize(&payload).unwrap())
+}
+
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+fn router(
+	url: Url,
+	client: reqwest::Client,
+	capacity: usize,
+	mut sender: Sender<Bytes>,
+	mut incoming: Incoming,
+	metrics: MetricsHook,
+	state: ConnectionState,
+	route_rx: Receiver<Option<Route>>,
+) {
+	tokio::spawn(async move {
+		let mut vars = IndexMap::new();
+		let mut replay = IndexMap::new();
+
+		'router: loop {
+			let mut routes = match capacity {
+				0 => HashMap::new(),
+				capacity => HashMap::with_capacity(capacity),
+			};
+			let mut decoder = Decoder::default();
+
+			let streams = (incoming.map(Either::Response), route_rx.stream().map(Either::Request));
+
+			let mut merged = streams.merge();
+
+			while let Some(either) = merged.next().await {
+				match either {
+					Either::Request(Some(Route {
+						request,
+						response,
+					})) => {
+						let (id, method, param) = request;
+						let mut params = match param.query {
+							Some((query, bindings)) => {
+								vec![query.into(), bindings.into()]
+							}
+							None => param.other,
+						};
+						// Ask the server to stop processing the request once it times out
+						if let Some(timeout) = param.timeout {
+							if let Method::Create = method {
+								params.resize(2, Value::None);
+							}
+							params.push(Value::Duration(timeout.into()));
+						}
+						match method {
+							Method::Set => {
+								if let [Value::Strand(Strand(key)), value] = &params[..2] {
+									vars.insert(key.clone(), value.clone());
+								}
+							}
+							Method::Unset => {
+								if let [Value::Strand(Strand(key))] = &params[..1] {
+									vars.remove(key);
+								}
+							}
+							_ => {}
+						}
+						let method_str = match method {
+							Method::Health => PING_METHOD,
+							_ => method.as_str(),
+						};
+						let message = frame(Some(id), method_str, params, param.idempotency_key);
+						if let Method::Authenticate
+						| Method::Invalidate
+						| Method::Signin
+						| Method::Signup
+						| Method::Use = method
+						{
+							replay.insert(method, message.clone());
+						}
+						let bytes = message.len();
+						match sender.send_async(message).await {
+							Ok(..) => {
+								metrics.sent(method, bytes);
+								match routes.entry(id) {
+									Entry::Vacant(entry) => {
+										entry.insert((method, response));
+									}
+									Entry::Occupied(..) => {
+										let error = Error::DuplicateRequestId(id);
+										if response
+											.into_send_async(Err(error.into()))
+											.await
+											.is_err()
+										{
+											trace!("Receiver dropped");
+										}
+									}
+								}
+							}
+							Err(error) => {
+								let error = Error::Grpc(error.to_string());
+								if response.into_send_async(Err(error.into())).await.is_err() {
+									trace!("Receiver dropped");
+								}
+								break;
+							}
+						}
+					}
+					Either::Response(result) => {
+						let messages = match result {
+							Ok(data) => match decoder.decode(&data) {
+								Ok(messages) => messages,
+								Err(error) => {
+									trace!("{error}");
+									break;
+								}
+							},
+							Err(error) => {
+								trace!("{error}");
+								break;
+							}
+						};
+						for message in messages {
+							match deserialize::<Response>(&message) {
+								Ok(response) => {
+									trace!("{response:?}");
+									if let Some(Ok(id)) = response.id.map(Value::coerce_to_i64) {
+										if let Some((method, sender)) = routes.remove(&id) {
+											metrics.received(method, message.len());
+											let _res = sender
+												.into_send_async(DbResponse::from(response.result))
+												.await;
+										}
+									}
+								}
+								Err(error) => {
+									#[derive(Deserialize)]
+									struct Response {
+										id: Option<Value>,
+									}
+
+									let error = Error::ResponseFromBinary {
+										binary: message.to_vec(),
+										error,
+									};
+
+									// Let's try to find out the ID of the response that failed to deserialise
+									if let Ok(Response {
+										id,
+									}) = deserialize(&message)
+									{
+										// Return an error if an ID was returned
+										if let Some(Ok(id)) = id.map(Value::coerce_to_i64) {
+											if let Some((_method, sender)) = routes.remove(&id) {
+												let _res =
+													sender.into_send_async(Err(error.into())).await;
+											}
+										}
+									} else {
+										// Unfortunately, we don't know which response failed to deserialize
+										warn!("Failed to deserialise message; {error:?}");
+									}
+								}
+							}
+						}
+					}
+					Either::Request(None) => {
+						break 'router;
+					}
+				}
+			}
+
+			state.lost();
+
+			let mut attempt = 0;
+
+			'reconnect: loop {
+				attempt += 1;
+				metrics.retry(attempt);
+				trace!("Reconnecting...");
+				match open(&client, &url).await {
+					Ok((s, i)) => {
+						sender = s;
+						incoming = i;
+						for (_, message) in &replay {
+							if let Err(error) = sender.send_async(message.clone()).await {
+								trace!("{error}");
+								time::sleep(time::Duration::from_secs(1)).await;
+								continue 'reconnect;
+							}
+						}
+						for (key, value) in &vars {
+							let params = vec![key.as_str().into(), value.clone()];
+							let message = frame(None, Method::Set.as_str(), params, None);
+							if let Err(error) = sender.send_async(message).await {
+								trace!("{error}");
+								time::sleep(time::Duration::from_secs(1)).await;
+								continue 'reconnect;
+							}
+						}
+						trace!("Reconnected successfully");
+						state.restored();
+						break;
+					}
+					Err(error) => {
+						trace!("Failed to reconnect; {error}");
+						time::sleep(time::Duration::from_secs(1)).await;
+					}
+				}
+			}
+		}
+	});
+}
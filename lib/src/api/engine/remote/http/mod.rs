@@ -22,6 +22,10 @@ use crate::api::Response as QueryResponse;
 use crate::api::Result;
 use crate::api::Surreal;
 use crate::dbs::Status;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::opt::ExportConfig;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::opt::ExportFormat;
 use crate::opt::IntoEndpoint;
 use crate::sql::serde::deserialize;
 use crate::sql::Array;
@@ -31,6 +35,7 @@ use crate::sql::Value;
 use futures::TryStreamExt;
 use indexmap::IndexMap;
 use reqwest::header::HeaderMap;
+use reqwest::header::HeaderName;
 use reqwest::header::HeaderValue;
 use reqwest::header::ACCEPT;
 #[cfg(not(target_arch = "wasm32"))]
@@ -51,8 +56,10 @@ use tokio_util::compat::FuturesAsyncReadCompatExt;
 use url::Url;
 
 const SQL_PATH: &str = "sql";
+#[cfg(all(unix, not(target_arch = "wasm32")))]
+pub(crate) const UNIX_URL: &str = "http://localhost";
 
-/// The HTTP scheme used to connect to `http://` endpoints
+/// The HTTP scheme used to connect to `http://` endpoints, or to `unix://` Unix domain sockets
 #[derive(Debug)]
 pub struct Http;
 
@@ -93,6 +100,7 @@ impl Surreal<Client> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			lazy: false,
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -275,6 +283,22 @@ async fn export(
 	Ok(Value::None)
 }
 
+/// The query parameters which select the contents and the format of an export or import
+#[cfg(not(target_arch = "wasm32"))]
+fn backup_query(config: &ExportConfig) -> Vec<(&'static str, String)> {
+	let mut query = Vec::new();
+	if let Some(tables) = &config.tables {
+		query.push(("tables", tables.join(",")));
+	}
+	if config.format == ExportFormat::Json {
+		query.push(("format", "json".to_owned()));
+	}
+	if let Some(since) = &config.since {
+		query.push(("since", since.0.to_rfc3339()));
+	}
+	query
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 async fn import(request: RequestBuilder, path: PathBuf) -> Result<Value> {
 	let file = match OpenOptions::new().read(true).open(&path).await {
@@ -320,6 +344,18 @@ pub(crate) async fn health(request: RequestBuilder) -> Result<Value> {
 	Ok(Value::None)
 }
 
+/// Adds the headers set by interceptors to the headers sent with every request
+fn request_headers(headers: &HeaderMap, extra: &[(String, String)]) -> Result<HeaderMap> {
+	let mut headers = headers.clone();
+	for (name, value) in extra {
+		let invalid = || Error::Http(format!("Invalid header `{name}`"));
+		let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+		let value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+		headers.insert(name, value);
+	}
+	Ok(headers)
+}
+
 async fn router(
 	(_, method, param): (i64, Method, Param),
 	base_url: &Url,
@@ -329,11 +365,12 @@ async fn router(
 	auth: &mut Option<Auth>,
 ) -> Result<DbResponse> {
 	let mut params = param.other;
+	let extra_headers = param.headers;
 
 	match method {
 		Method::Use => {
 			let path = base_url.join(SQL_PATH)?;
-			let mut request = client.post(path).headers(headers.clone());
+			let mut request = client.post(path).headers(request_headers(headers, &extra_headers)?);
 			let (ns, db) = match &mut params[..] {
 				[Value::Strand(Strand(ns)), Value::Strand(Strand(db))] => {
 					(Some(mem::take(ns)), Some(mem::take(db)))
@@ -382,7 +419,11 @@ async fn router(
 				[credentials] => credentials.to_string(),
 				_ => unreachable!(),
 			};
-			let request = client.post(path).headers(headers.clone()).auth(auth).body(credentials);
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.auth(auth)
+				.body(credentials);
 			let value = submit_auth(request).await?;
 			if let [credentials] = &mut params[..] {
 				if let Ok(Root {
@@ -408,7 +449,11 @@ async fn router(
 				[credentials] => credentials.to_string(),
 				_ => unreachable!(),
 			};
-			let request = client.post(path).headers(headers.clone()).auth(auth).body(credentials);
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.auth(auth)
+				.body(credentials);
 			let value = submit_auth(request).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -418,8 +463,11 @@ async fn router(
 				[Value::Strand(Strand(token))] => mem::take(token),
 				_ => unreachable!(),
 			};
-			let request =
-				client.post(path).headers(headers.clone()).bearer_auth(&token).body("RETURN true");
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.bearer_auth(&token)
+				.body("RETURN true");
 			take(true, request).await?;
 			*auth = Some(Auth::Bearer {
 				token,
@@ -432,55 +480,77 @@ async fn router(
 		}
 		Method::Create => {
 			let path = base_url.join(SQL_PATH)?;
-			let statement = create_statement(&mut params);
-			let request =
-				client.post(path).headers(headers.clone()).auth(auth).body(statement.to_string());
+			let statement = create_statement(&mut params, param.timeout);
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.auth(auth)
+				.body(statement.to_string());
 			let value = take(true, request).await?;
 			Ok(DbResponse::Other(value))
 		}
 		Method::Update => {
 			let path = base_url.join(SQL_PATH)?;
 			let (one, statement) = update_statement(&mut params);
-			let request =
-				client.post(path).headers(headers.clone()).auth(auth).body(statement.to_string());
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.auth(auth)
+				.body(statement.to_string());
 			let value = take(one, request).await?;
 			Ok(DbResponse::Other(value))
 		}
 		Method::Patch => {
 			let path = base_url.join(SQL_PATH)?;
 			let (one, statement) = patch_statement(&mut params);
-			let request =
-				client.post(path).headers(headers.clone()).auth(auth).body(statement.to_string());
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.auth(auth)
+				.body(statement.to_string());
 			let value = take(one, request).await?;
 			Ok(DbResponse::Other(value))
 		}
 		Method::Merge => {
 			let path = base_url.join(SQL_PATH)?;
 			let (one, statement) = merge_statement(&mut params);
-			let request =
-				client.post(path).headers(headers.clone()).auth(auth).body(statement.to_string());
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.auth(auth)
+				.body(statement.to_string());
 			let value = take(one, request).await?;
 			Ok(DbResponse::Other(value))
 		}
 		Method::Select => {
 			let path = base_url.join(SQL_PATH)?;
-			let (one, statement) = select_statement(&mut params);
-			let request =
-				client.post(path).headers(headers.clone()).auth(auth).body(statement.to_string());
+			let (one, statement) = select_statement(&mut params, param.timeout);
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.auth(auth)
+				.body(statement.to_string());
 			let value = take(one, request).await?;
 			Ok(DbResponse::Other(value))
 		}
 		Method::Delete => {
 			let path = base_url.join(SQL_PATH)?;
 			let (one, statement) = delete_statement(&mut params);
-			let request =
-				client.post(path).headers(headers.clone()).auth(auth).body(statement.to_string());
+			let request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.auth(auth)
+				.body(statement.to_string());
 			let value = take(one, request).await?;
 			Ok(DbResponse::Other(value))
 		}
 		Method::Query => {
 			let path = base_url.join(SQL_PATH)?;
-			let mut request = client.post(path).headers(headers.clone()).query(&vars).auth(auth);
+			let mut request = client
+				.post(path)
+				.headers(request_headers(headers, &extra_headers)?)
+				.query(&vars)
+				.auth(auth);
 			match param.query {
 				Some((query, bindings)) => {
 					let bindings: Vec<_> =
@@ -499,8 +569,9 @@ async fn router(
 			let path = base_url.join(Method::Export.as_str())?;
 			let request = client
 				.get(path)
-				.headers(headers.clone())
+				.headers(request_headers(headers, &extra_headers)?)
 				.auth(auth)
+				.query(&backup_query(&param.backup))
 				.header(ACCEPT, "application/octet-stream");
 			let value = export(request, (param.file, param.sender)).await?;
 			Ok(DbResponse::Other(value))
@@ -511,8 +582,9 @@ async fn router(
 			let file = param.file.expect("file to import from");
 			let request = client
 				.post(path)
-				.headers(headers.clone())
+				.headers(request_headers(headers, &extra_headers)?)
 				.auth(auth)
+				.query(&backup_query(&param.backup))
 				.header(CONTENT_TYPE, "application/octet-stream");
 			let value = import(request, file).await?;
 			Ok(DbResponse::Other(value))
@@ -537,7 +609,7 @@ async fn router(
 			};
 			let request = client
 				.post(path)
-				.headers(headers.clone())
+				.headers(request_headers(headers, &extra_headers)?)
 				.auth(auth)
 				.query(&[(key.as_str(), value.as_str())])
 				.body(format!("RETURN ${key}"));
@@ -559,7 +631,7 @@ async fn router(
 			};
 			let request = client
 				.post(path)
-				.headers(headers.clone())
+				.headers(request_headers(headers, &extra_headers)?)
 				.auth(auth)
 				.query(&[("table", table)])
 				.body("LIVE SELECT * FROM type::table($table)");
@@ -574,7 +646,7 @@ async fn router(
 			};
 			let request = client
 				.post(path)
-				.headers(headers.clone())
+				.headers(request_headers(headers, &extra_headers)?)
 				.auth(auth)
 				.query(&[("id", id)])
 				.body("KILL type::string($id)");
@@ -5,6 +5,8 @@ use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Route;
 use crate::api::conn::Router;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use crate::api::err::Error;
 use crate::api::opt::Endpoint;
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 use crate::api::opt::Tls;
@@ -20,10 +22,14 @@ use reqwest::ClientBuilder;
 use std::collections::HashSet;
 use std::future::Future;
 use std::marker::PhantomData;
+#[cfg(unix)]
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
 use std::sync::OnceLock;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use tokio::net::lookup_host;
 use url::Url;
 
 impl crate::api::Connection for Client {}
@@ -42,11 +48,17 @@ impl Connection for Client {
 		Box::pin(async move {
 			let headers = super::default_headers();
 
+			// Compressed responses are decompressed by the client
+			let compression = address.config.compression;
 			#[allow(unused_mut)]
-			let mut builder = ClientBuilder::new().default_headers(headers);
+			let mut builder =
+				ClientBuilder::new().default_headers(headers).gzip(compression).zstd(compression);
+
+			#[cfg(any(feature = "native-tls", feature = "rustls"))]
+			let tls_options = address.config.tls_options;
 
 			#[cfg(any(feature = "native-tls", feature = "rustls"))]
-			if let Some(tls) = address.config.tls_config {
+			if let Some(tls) = tls_options.resolve(address.config.tls_config)? {
 				builder = match tls {
 					#[cfg(feature = "native-tls")]
 					Tls::Native(config) => builder.use_preconfigured_tls(config),
@@ -55,9 +67,27 @@ impl Connection for Client {
 				};
 			}
 
-			let client = builder.build()?;
+			// Requests to `http+unix` endpoints are sent over the Unix domain socket
+			let base_url = match address.endpoint.scheme() {
+				#[cfg(unix)]
+				"http+unix" => {
+					builder = builder.unix_socket(PathBuf::from(address.endpoint.path()));
+					Url::parse(super::UNIX_URL)?
+				}
+				_ => address.endpoint,
+			};
 
-			let base_url = address.endpoint;
+			#[cfg(any(feature = "native-tls", feature = "rustls"))]
+			let base_url = match &tls_options.server_name {
+				Some(name) if base_url.scheme() == "https" => {
+					let (resolved, url) = server_name(builder, base_url, name).await?;
+					builder = resolved;
+					url
+				}
+				_ => base_url,
+			};
+
+			let client = builder.build()?;
 
 			super::health(client.get(base_url.join(Method::Health.as_str())?)).await?;
 
@@ -77,6 +107,11 @@ impl Connection for Client {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					interceptors: Default::default(),
+					retry: None,
+					session: Default::default(),
+					handle: None,
+					state: Default::default(),
 				})),
 			})
 		})
@@ -93,12 +128,32 @@ impl Connection for Client {
 				request: (0, self.method, param),
 				response: sender,
 			};
-			router.sender.send_async(Some(route)).await?;
+			router.send(route).await?;
 			Ok(receiver)
 		})
 	}
 }
 
+/// Sends the requests to the address of the endpoint, while using the
+/// server name to verify the server certificate, and in the `Host` header
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+pub(crate) async fn server_name(
+	builder: ClientBuilder,
+	mut url: Url,
+	name: &str,
+) -> Result<(ClientBuilder, Url)> {
+	let host = url.host_str().unwrap_or_default().to_owned();
+	let port = url.port_or_known_default().unwrap_or_default();
+	let mut addresses =
+		lookup_host((host.as_str(), port)).await.map_err(|e| Error::Http(e.to_string()))?;
+	let address = match addresses.next() {
+		Some(address) => address,
+		None => return Err(Error::Http(format!("Unable to resolve `{host}`")).into()),
+	};
+	url.set_host(Some(name))?;
+	Ok((builder.resolve(name, address), url))
+}
+
 pub(crate) fn router(base_url: Url, client: reqwest::Client, route_rx: Receiver<Option<Route>>) {
 	tokio::spawn(async move {
 		let mut headers = HeaderMap::new();
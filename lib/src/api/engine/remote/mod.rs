@@ -1,9 +1,14 @@
 //! Protocols for communicating with the server
 
+#[cfg(all(feature = "protocol-grpc", not(target_arch = "wasm32")))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "protocol-grpc", not(target_arch = "wasm32")))))]
+pub mod grpc;
 #[cfg(feature = "protocol-http")]
 #[cfg_attr(docsrs, doc(cfg(feature = "protocol-http")))]
 pub mod http;
-
 #[cfg(feature = "protocol-ws")]
 #[cfg_attr(docsrs, doc(cfg(feature = "protocol-ws")))]
 pub mod ws;
+
+#[cfg(any(feature = "protocol-ws", all(feature = "protocol-grpc", not(target_arch = "wasm32"))))]
+mod rpc;
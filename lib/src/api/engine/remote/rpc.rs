@@ -0,0 +1,76 @@
+//! The responses sent by the RPC endpoint of the server
+
+use crate::api;
+use crate::api::conn::DbResponse;
+use crate::api::err::Error;
+use crate::api::Result;
+use crate::dbs::Status;
+use crate::sql::Array;
+use crate::sql::Strand;
+use crate::sql::Value;
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct Failure {
+	pub(crate) code: i64,
+	pub(crate) message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) enum Data {
+	Other(Value),
+	Query(Vec<QueryMethodResponse>),
+}
+
+type ServerResult = std::result::Result<Data, Failure>;
+
+impl From<Failure> for Error {
+	fn from(failure: Failure) -> Self {
+		match failure.code {
+			-32600 => Self::InvalidRequest(failure.message),
+			-32602 => Self::InvalidParams(failure.message),
+			-32603 => Self::InternalError(failure.message),
+			-32700 => Self::ParseError(failure.message),
+			_ => Self::Query(failure.message),
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct QueryMethodResponse {
+	#[allow(dead_code)]
+	time: String,
+	status: Status,
+	result: Value,
+}
+
+impl DbResponse {
+	pub(crate) fn from(result: ServerResult) -> Result<Self> {
+		match result.map_err(Error::from)? {
+			Data::Other(value) => Ok(DbResponse::Other(value)),
+			Data::Query(results) => Ok(DbResponse::Query(api::Response(
+				results
+					.into_iter()
+					.map(|response| match response.status {
+						Status::Ok => match response.result {
+							Value::Array(Array(values)) => Ok(values),
+							Value::None | Value::Null => Ok(vec![]),
+							value => Ok(vec![value]),
+						},
+						Status::Err => match response.result {
+							Value::Strand(Strand(message)) => Err(Error::Query(message).into()),
+							message => Err(Error::Query(message.to_string()).into()),
+						},
+					})
+					.enumerate()
+					.collect(),
+			))),
+		}
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Response {
+	pub(crate) id: Option<Value>,
+	pub(crate) result: ServerResult,
+}
@@ -5,27 +5,20 @@ pub(crate) mod native;
 #[cfg(target_arch = "wasm32")]
 pub(crate) mod wasm;
 
-use crate::api;
-use crate::api::conn::DbResponse;
 use crate::api::conn::Method;
-use crate::api::err::Error;
 use crate::api::Connect;
-use crate::api::Result;
 use crate::api::Surreal;
-use crate::dbs::Status;
 use crate::opt::IntoEndpoint;
-use crate::sql::Array;
-use crate::sql::Strand;
-use crate::sql::Value;
-use serde::Deserialize;
 use std::marker::PhantomData;
 use std::time::Duration;
 
 pub(crate) const PATH: &str = "rpc";
+#[cfg(all(unix, not(target_arch = "wasm32")))]
+const UNIX_URL: &str = "ws://localhost";
 const PING_INTERVAL: Duration = Duration::from_secs(5);
 const PING_METHOD: &str = "ping";
 
-/// The WS scheme used to connect to `ws://` endpoints
+/// The WS scheme used to connect to `ws://` endpoints, or to `unix://` Unix domain sockets
 #[derive(Debug)]
 pub struct Ws;
 
@@ -67,73 +60,9 @@ impl Surreal<Client> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			lazy: false,
 			client: PhantomData,
 			response_type: PhantomData,
 		}
 	}
 }
-
-#[derive(Clone, Debug, Deserialize)]
-pub(crate) struct Failure {
-	pub(crate) code: i64,
-	pub(crate) message: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub(crate) enum Data {
-	Other(Value),
-	Query(Vec<QueryMethodResponse>),
-}
-
-type ServerResult = std::result::Result<Data, Failure>;
-
-impl From<Failure> for Error {
-	fn from(failure: Failure) -> Self {
-		match failure.code {
-			-32600 => Self::InvalidRequest(failure.message),
-			-32602 => Self::InvalidParams(failure.message),
-			-32603 => Self::InternalError(failure.message),
-			-32700 => Self::ParseError(failure.message),
-			_ => Self::Query(failure.message),
-		}
-	}
-}
-
-#[derive(Debug, Deserialize)]
-pub(crate) struct QueryMethodResponse {
-	#[allow(dead_code)]
-	time: String,
-	status: Status,
-	result: Value,
-}
-
-impl DbResponse {
-	fn from(result: ServerResult) -> Result<Self> {
-		match result.map_err(Error::from)? {
-			Data::Other(value) => Ok(DbResponse::Other(value)),
-			Data::Query(results) => Ok(DbResponse::Query(api::Response(
-				results
-					.into_iter()
-					.map(|response| match response.status {
-						Status::Ok => match response.result {
-							Value::Array(Array(values)) => Ok(values),
-							Value::None | Value::Null => Ok(vec![]),
-							value => Ok(vec![value]),
-						},
-						Status::Err => match response.result {
-							Value::Strand(Strand(message)) => Err(Error::Query(message).into()),
-							message => Err(Error::Query(message.to_string()).into()),
-						},
-					})
-					.enumerate()
-					.collect(),
-			))),
-		}
-	}
-}
-
-#[derive(Debug, Deserialize)]
-pub(crate) struct Response {
-	id: Option<Value>,
-	pub(crate) result: ServerResult,
-}
@@ -5,12 +5,14 @@ use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Route;
 use crate::api::conn::Router;
+use crate::api::engine::remote::rpc::Response;
 use crate::api::engine::remote::ws::Client;
-use crate::api::engine::remote::ws::Response;
 use crate::api::engine::remote::ws::PING_INTERVAL;
 use crate::api::engine::remote::ws::PING_METHOD;
 use crate::api::err::Error;
+use crate::api::opt::ConnectionState;
 use crate::api::opt::Endpoint;
+use crate::api::opt::MetricsHook;
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 use crate::api::opt::Tls;
 use crate::api::OnceLockExt;
@@ -21,6 +23,7 @@ use crate::sql::serde::{deserialize, serialize};
 use crate::sql::Strand;
 use crate::sql::Value;
 use flume::Receiver;
+use flume::Sender;
 use futures::stream::SplitSink;
 use futures::SinkExt;
 use futures::StreamExt;
@@ -33,13 +36,24 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::future::Future;
+use std::io;
 use std::marker::PhantomData;
 use std::mem;
 use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::task::Context;
+use std::task::Poll;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
 use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
 use tokio::time;
 use tokio::time::MissedTickBehavior;
 use tokio_tungstenite::tungstenite::error::Error as WsError;
@@ -74,17 +88,105 @@ impl From<Tls> for Connector {
 	}
 }
 
+/// How the connections to the server are secured
+#[derive(Clone, Default)]
+pub(crate) struct Security {
+	/// The TLS connector, when the default is not used
+	#[cfg_attr(not(any(feature = "native-tls", feature = "rustls")), allow(dead_code))]
+	pub(crate) connector: Option<Connector>,
+	/// The name used to verify the server, instead of the host in the address
+	pub(crate) server_name: Option<String>,
+}
+
+/// The connection to the server, over TCP or a Unix domain socket
+pub(crate) enum Stream {
+	Tcp(TcpStream),
+	#[cfg(unix)]
+	Unix(UnixStream),
+}
+
+impl AsyncRead for Stream {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		match self.get_mut() {
+			Stream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+			#[cfg(unix)]
+			Stream::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+		}
+	}
+}
+
+impl AsyncWrite for Stream {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<io::Result<usize>> {
+		match self.get_mut() {
+			Stream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+			#[cfg(unix)]
+			Stream::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+		}
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		match self.get_mut() {
+			Stream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+			#[cfg(unix)]
+			Stream::Unix(stream) => Pin::new(stream).poll_flush(cx),
+		}
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		match self.get_mut() {
+			Stream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+			#[cfg(unix)]
+			Stream::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+		}
+	}
+}
+
+/// Connects to the RPC endpoint of the server, at `ws://` and `wss://`
+/// addresses, or over the Unix domain socket of a `ws+unix://` address
 pub(crate) async fn connect(
-	url: &Url,
+	endpoint: &Url,
 	config: Option<WebSocketConfig>,
-	#[allow(unused_variables)] maybe_connector: Option<Connector>,
-) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+	security: Security,
+) -> Result<WebSocketStream<MaybeTlsStream<Stream>>> {
+	let (mut url, stream) = match endpoint.scheme() {
+		#[cfg(unix)]
+		"ws+unix" => {
+			let stream = UnixStream::connect(endpoint.path()).await.map_err(WsError::Io)?;
+			(Url::parse(super::UNIX_URL)?.join(PATH)?, Stream::Unix(stream))
+		}
+		_ => {
+			let url = endpoint.join(PATH)?;
+			let host = url.host_str().unwrap_or_default();
+			let port = url.port_or_known_default().unwrap_or_default();
+			let stream = TcpStream::connect(format!("{host}:{port}")).await.map_err(WsError::Io)?;
+			(url, Stream::Tcp(stream))
+		}
+	};
+
+	// The TLS handshake verifies the server against the host in the URL
+	if let Some(name) = &security.server_name {
+		if url.scheme() == "wss" {
+			url.set_host(Some(name))?;
+		}
+	}
+
 	#[cfg(any(feature = "native-tls", feature = "rustls"))]
 	let (socket, _) =
-		tokio_tungstenite::connect_async_tls_with_config(url, config, maybe_connector).await?;
+		tokio_tungstenite::client_async_tls_with_config(url, stream, config, security.connector)
+			.await?;
 
 	#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
-	let (socket, _) = tokio_tungstenite::connect_async_with_config(url, config).await?;
+	let (socket, _) =
+		tokio_tungstenite::client_async_with_config(url, MaybeTlsStream::Plain(stream), config)
+			.await?;
 
 	Ok(socket)
 }
@@ -104,30 +206,35 @@ impl Connection for Client {
 		capacity: usize,
 	) -> Pin<Box<dyn Future<Output = Result<Surreal<Self>>> + Send + Sync + 'static>> {
 		Box::pin(async move {
-			let url = address.endpoint.join(PATH)?;
+			let url = address.endpoint;
 			#[cfg(any(feature = "native-tls", feature = "rustls"))]
-			let maybe_connector = address.config.tls_config.map(Connector::from);
+			let security = Security {
+				connector: address
+					.config
+					.tls_options
+					.resolve(address.config.tls_config)?
+					.map(Connector::from),
+				server_name: address.config.tls_options.server_name,
+			};
 			#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
-			let maybe_connector = None;
+			let security = Security::default();
 
 			let config = WebSocketConfig {
-				max_send_queue: match capacity {
-					0 => None,
-					capacity => Some(capacity),
-				},
 				max_message_size: Some(MAX_MESSAGE_SIZE),
 				max_frame_size: Some(MAX_FRAME_SIZE),
-				accept_unmasked_frames: false,
+				..Default::default()
 			};
 
-			let socket = connect(&url, Some(config), maybe_connector.clone()).await?;
-
 			let (route_tx, route_rx) = match capacity {
 				0 => flume::unbounded(),
 				capacity => flume::bounded(capacity),
 			};
 
-			router(url, maybe_connector, capacity, config, socket, route_rx);
+			let size = address.config.pool_size;
+			let metrics = address.config.metrics;
+			let state = ConnectionState::default();
+
+			spawn(url, security, capacity, config, size, metrics, state.clone(), route_rx).await?;
 
 			Ok(Surreal {
 				router: Arc::new(OnceLock::with_value(Router {
@@ -135,6 +242,11 @@ impl Connection for Client {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					interceptors: Default::default(),
+					retry: None,
+					session: Default::default(),
+					handle: None,
+					state,
 				})),
 			})
 		})
@@ -152,19 +264,225 @@ impl Connection for Client {
 				request: (self.id, self.method, param),
 				response: sender,
 			};
-			router.sender.send_async(Some(route)).await?;
+			router.send(route).await?;
 			Ok(receiver)
 		})
 	}
 }
 
-#[allow(clippy::too_many_lines)]
+/// A connection within a pool of connections to the server
+struct Member {
+	/// The channel used to send requests over this connection
+	sender: Sender<Option<Route>>,
+	/// The number of requests awaiting a response over this connection
+	pending: Arc<AtomicUsize>,
+	/// Whether this connection is currently connected to the server
+	healthy: Arc<AtomicBool>,
+}
+
+impl Member {
+	/// Pings the server over this connection, marking the connection as
+	/// unhealthy if the server does not respond before the next check
+	fn check(&self, id: i64) {
+		let (sender, receiver) = flume::bounded(1);
+		let request = Route {
+			request: (id, Method::Health, Param::new(Vec::new())),
+			response: sender,
+		};
+		// A connection which is busy with other requests is checked later
+		if self.sender.try_send(Some(request)).is_err() {
+			return;
+		}
+		let healthy = self.healthy.clone();
+		tokio::spawn(async move {
+			let result = time::timeout(PING_INTERVAL, receiver.into_recv_async()).await;
+			healthy.store(matches!(result, Ok(Ok(Ok(..)))), Ordering::Relaxed);
+		});
+	}
+}
+
+/// Connects to the server, and spawns the router which sends the requests
+///
+/// When the pool size is greater than one, several connections are opened, and
+/// each request is sent over the healthy connection with the fewest requests
+/// awaiting a response. Requests which change the session, such as signing in
+/// or selecting the namespace and database, are sent over every connection.
+/// Live queries are started and killed over the first connection, as the server
+/// only sends the notifications of a live query over the connection which
+/// started it. Each connection is pinged periodically, and is only used while
+/// the server responds to the pings.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn spawn(
+	url: Url,
+	security: Security,
+	capacity: usize,
+	config: WebSocketConfig,
+	size: usize,
+	metrics: MetricsHook,
+	state: ConnectionState,
+	route_rx: Receiver<Option<Route>>,
+) -> Result<()> {
+	if size <= 1 {
+		let socket = connect(&url, Some(config), security.clone()).await?;
+		let healthy = Arc::new(AtomicBool::new(true));
+		router(url, security, capacity, config, socket, healthy, metrics, state, route_rx);
+		return Ok(());
+	}
+	let mut members: Vec<Member> = Vec::with_capacity(size);
+	for _ in 0..size {
+		let socket = match connect(&url, Some(config), security.clone()).await {
+			Ok(socket) => socket,
+			Err(error) => {
+				// Close the connections which were already opened
+				for member in members {
+					let _ = member.sender.send(None);
+				}
+				return Err(error);
+			}
+		};
+		let (sender, receiver) = match capacity {
+			0 => flume::unbounded(),
+			capacity => flume::bounded(capacity),
+		};
+		let healthy = Arc::new(AtomicBool::new(true));
+		let security = security.clone();
+		let metrics = metrics.clone();
+		let state = state.clone();
+		router(
+			url.clone(),
+			security,
+			capacity,
+			config,
+			socket,
+			healthy.clone(),
+			metrics,
+			state,
+			receiver,
+		);
+		members.push(Member {
+			sender,
+			pending: Arc::new(AtomicUsize::new(0)),
+			healthy,
+		});
+	}
+	pool(members, route_rx);
+	Ok(())
+}
+
+/// Spreads the requests over the connections within the pool
+fn pool(members: Vec<Member>, route_rx: Receiver<Option<Route>>) {
+	tokio::spawn(async move {
+		let mut interval = time::interval(PING_INTERVAL);
+		// don't bombard the server with pings if we miss some ticks
+		interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+		// Delay the first health check
+		interval.tick().await;
+
+		let pinger = IntervalStream::new(interval);
+
+		let streams = (route_rx.into_stream().map(Either::Request), pinger.map(|_| Either::Ping));
+
+		let mut stream = streams.merge();
+		// The health checks use negative ids, which are never used by requests
+		let mut check_id = 0;
+
+		while let Some(either) = stream.next().await {
+			let route = match either {
+				Either::Request(Some(route)) => route,
+				Either::Ping => {
+					for member in members.iter() {
+						check_id -= 1;
+						member.check(check_id);
+					}
+					continue;
+				}
+				Either::Request(None) | Either::Response(..) => break,
+			};
+			let (id, method, param) = route.request;
+			match method {
+				// Changes to the session are sent over every connection
+				Method::Authenticate
+				| Method::Invalidate
+				| Method::Signin
+				| Method::Signup
+				| Method::Use
+				| Method::Set
+				| Method::Unset => {
+					let mut receivers = Vec::with_capacity(members.len());
+					for member in members.iter() {
+						let (sender, receiver) = flume::bounded(1);
+						let request = Route {
+							request: (id, method, Param::new(param.other.clone())),
+							response: sender,
+						};
+						if member.sender.send_async(Some(request)).await.is_ok() {
+							receivers.push(receiver);
+						}
+					}
+					let response = route.response;
+					tokio::spawn(async move {
+						// Respond with the first response, or with any error
+						let mut result = None;
+						for receiver in receivers {
+							let res = receiver.into_recv_async().await.map_err(crate::Error::from);
+							result = match (result, res) {
+								(None, res) => Some(res.and_then(|res| res)),
+								(Some(Ok(_)), Err(error) | Ok(Err(error))) => Some(Err(error)),
+								(result, _) => result,
+							};
+						}
+						if let Some(result) = result {
+							let _ = response.into_send_async(result).await;
+						}
+					});
+				}
+				// Other requests are sent over a single connection
+				_ => {
+					let member = match method {
+						// Live queries are bound to the connection which started them
+						Method::Live | Method::Kill => &members[0],
+						_ => members
+							.iter()
+							.filter(|member| member.healthy.load(Ordering::Relaxed))
+							.min_by_key(|member| member.pending.load(Ordering::Relaxed))
+							.unwrap_or(&members[0]),
+					};
+					let (sender, receiver) = flume::bounded(1);
+					let request = Route {
+						request: (id, method, param),
+						response: sender,
+					};
+					let pending = member.pending.clone();
+					pending.fetch_add(1, Ordering::Relaxed);
+					let _ = member.sender.send_async(Some(request)).await;
+					let response = route.response;
+					tokio::spawn(async move {
+						if let Ok(result) = receiver.into_recv_async().await {
+							let _ = response.into_send_async(result).await;
+						}
+						pending.fetch_sub(1, Ordering::Relaxed);
+					});
+				}
+			}
+		}
+
+		// Close each of the connections
+		for member in members {
+			let _ = member.sender.send(None);
+		}
+	});
+}
+
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 pub(crate) fn router(
 	url: Url,
-	maybe_connector: Option<Connector>,
+	security: Security,
 	capacity: usize,
 	config: WebSocketConfig,
-	mut socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+	mut socket: WebSocketStream<MaybeTlsStream<Stream>>,
+	healthy: Arc<AtomicBool>,
+	metrics: MetricsHook,
+	state: ConnectionState,
 	route_rx: Receiver<Option<Route>>,
 ) {
 	tokio::spawn(async move {
@@ -213,12 +531,19 @@ pub(crate) fn router(
 							response,
 						})) => {
 							let (id, method, param) = request;
-							let params = match param.query {
+							let mut params = match param.query {
 								Some((query, bindings)) => {
 									vec![query.into(), bindings.into()]
 								}
 								None => param.other,
 							};
+							// Ask the server to stop processing the request once it times out
+							if let Some(timeout) = param.timeout {
+								if let Method::Create = method {
+									params.resize(2, Value::None);
+								}
+								params.push(Value::Duration(timeout.into()));
+							}
 							match method {
 								Method::Set => {
 									if let [Value::Strand(Strand(key)), value] = &params[..2] {
@@ -240,6 +565,10 @@ pub(crate) fn router(
 								let mut request = BTreeMap::new();
 								request.insert("id".to_owned(), Value::from(id));
 								request.insert("method".to_owned(), method_str.into());
+								// Lets the server deduplicate requests which are retried
+								if let Some(key) = param.idempotency_key {
+									request.insert("key".to_owned(), key.into());
+								}
 								if !params.is_empty() {
 									request.insert("params".to_owned(), params.into());
 								}
@@ -256,9 +585,11 @@ pub(crate) fn router(
 							{
 								replay.insert(method, message.clone());
 							}
+							let bytes = message.len();
 							match socket_sink.send(message).await {
 								Ok(..) => {
 									last_activity = Instant::now();
+									metrics.sent(method, bytes);
 									match routes.entry(id) {
 										Entry::Vacant(entry) => {
 											entry.insert((method, response));
@@ -294,8 +625,8 @@ pub(crate) fn router(
 											if let Some(Ok(id)) =
 												response.id.map(Value::coerce_to_i64)
 											{
-												if let Some((_method, sender)) = routes.remove(&id)
-												{
+												if let Some((method, sender)) = routes.remove(&id) {
+													metrics.received(method, message.len());
 													let _res = sender
 														.into_send_async(DbResponse::from(
 															response.result,
@@ -364,9 +695,16 @@ pub(crate) fn router(
 				}
 			}
 
+			healthy.store(false, Ordering::Relaxed);
+			state.lost();
+
+			let mut attempt = 0;
+
 			'reconnect: loop {
+				attempt += 1;
+				metrics.retry(attempt);
 				trace!("Reconnecting...");
-				match connect(&url, Some(config), maybe_connector.clone()).await {
+				match connect(&url, Some(config), security.clone()).await {
 					Ok(s) => {
 						socket = s;
 						for (_, message) in &replay {
@@ -393,6 +731,8 @@ pub(crate) fn router(
 							}
 						}
 						trace!("Reconnected successfully");
+						healthy.store(true, Ordering::Relaxed);
+						state.restored();
 						break;
 					}
 					Err(error) => {
@@ -439,7 +779,7 @@ impl Response {
 	}
 }
 
-pub struct Socket(Option<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>);
+pub struct Socket(Option<SplitSink<WebSocketStream<MaybeTlsStream<Stream>>, Message>>);
 
 impl Drop for Socket {
 	fn drop(&mut self) {
@@ -455,3 +795,113 @@ impl Drop for Socket {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	// A connection within the pool which is not backed by a server
+	fn member() -> (Member, Receiver<Option<Route>>) {
+		let (sender, receiver) = flume::unbounded();
+		let member = Member {
+			sender,
+			pending: Arc::new(AtomicUsize::new(0)),
+			healthy: Arc::new(AtomicBool::new(true)),
+		};
+		(member, receiver)
+	}
+
+	async fn request(
+		router: &Sender<Option<Route>>,
+		id: i64,
+		method: Method,
+	) -> Receiver<Result<DbResponse>> {
+		let (sender, receiver) = flume::bounded(1);
+		let route = Route {
+			request: (id, method, Param::new(Vec::new())),
+			response: sender,
+		};
+		router.send_async(Some(route)).await.unwrap();
+		receiver
+	}
+
+	async fn received(connection: &Receiver<Option<Route>>) -> Route {
+		let route = time::timeout(Duration::from_secs(1), connection.recv_async()).await;
+		route.expect("no request was sent over the connection").unwrap().unwrap()
+	}
+
+	async fn settled(pending: &AtomicUsize) {
+		while pending.load(Ordering::Relaxed) > 0 {
+			time::sleep(Duration::from_millis(1)).await;
+		}
+	}
+
+	#[tokio::test]
+	async fn pool_size_limits_connections() {
+		let (members, connections): (Vec<_>, Vec<_>) = (0..3).map(|_| member()).unzip();
+		let (router, route_rx) = flume::unbounded();
+		pool(members, route_rx);
+		// Send more requests than there are connections
+		let mut responses = Vec::new();
+		for id in 1..=9 {
+			responses.push(request(&router, id, Method::Select).await);
+		}
+		// The requests are spread evenly over the connections
+		let mut routes = Vec::new();
+		for connection in connections.iter() {
+			for _ in 0..3 {
+				routes.push(received(connection).await);
+			}
+		}
+		time::sleep(Duration::from_millis(10)).await;
+		assert!(connections.iter().all(|connection| connection.is_empty()));
+	}
+
+	#[tokio::test]
+	async fn pool_reuses_idle_connections() {
+		let (members, connections): (Vec<_>, Vec<_>) = (0..2).map(|_| member()).unzip();
+		let pending = members[0].pending.clone();
+		let (router, route_rx) = flume::unbounded();
+		pool(members, route_rx);
+		for id in 1..=3 {
+			let response = request(&router, id, Method::Select).await;
+			// Each request is sent over the first idle connection
+			let route = received(&connections[0]).await;
+			assert_eq!(route.request.0, id);
+			route.response.send_async(Ok(DbResponse::Other(Value::None))).await.unwrap();
+			assert!(response.recv_async().await.unwrap().is_ok());
+			settled(&pending).await;
+		}
+		assert!(connections[1].is_empty());
+	}
+
+	#[tokio::test]
+	async fn pool_checkout_when_exhausted() {
+		let (members, connections): (Vec<_>, Vec<_>) = (0..2).map(|_| member()).unzip();
+		let pending = members[1].pending.clone();
+		let healthy = members.iter().map(|member| member.healthy.clone()).collect::<Vec<_>>();
+		let (router, route_rx) = flume::unbounded();
+		pool(members, route_rx);
+		// Make every connection busy
+		let _first = request(&router, 1, Method::Select).await;
+		let _busy = received(&connections[0]).await;
+		let second = request(&router, 2, Method::Select).await;
+		let route = received(&connections[1]).await;
+		// A request is queued on a busy connection, rather than failing
+		let _third = request(&router, 3, Method::Select).await;
+		assert_eq!(received(&connections[0]).await.request.0, 3);
+		// The connection which becomes idle first is checked out next
+		route.response.send_async(Ok(DbResponse::Other(Value::None))).await.unwrap();
+		assert!(second.recv_async().await.unwrap().is_ok());
+		settled(&pending).await;
+		let _fourth = request(&router, 4, Method::Select).await;
+		assert_eq!(received(&connections[1]).await.request.0, 4);
+		// Without any healthy connections, the first connection is used
+		for healthy in healthy.iter() {
+			healthy.store(false, Ordering::Relaxed);
+		}
+		let _fifth = request(&router, 5, Method::Select).await;
+		assert_eq!(received(&connections[0]).await.request.0, 5);
+	}
+}
@@ -5,11 +5,12 @@ use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Route;
 use crate::api::conn::Router;
+use crate::api::engine::remote::rpc::Response;
 use crate::api::engine::remote::ws::Client;
-use crate::api::engine::remote::ws::Response;
 use crate::api::engine::remote::ws::PING_INTERVAL;
 use crate::api::engine::remote::ws::PING_METHOD;
 use crate::api::err::Error;
+use crate::api::opt::ConnectionState;
 use crate::api::opt::Endpoint;
 use crate::api::OnceLockExt;
 use crate::api::Result;
@@ -77,8 +78,9 @@ impl Connection for Client {
 			};
 
 			let (conn_tx, conn_rx) = flume::bounded(1);
+			let state = ConnectionState::default();
 
-			router(address, capacity, conn_tx, route_rx);
+			router(address, capacity, state.clone(), conn_tx, route_rx);
 
 			conn_rx.into_recv_async().await??;
 
@@ -88,6 +90,11 @@ impl Connection for Client {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					interceptors: Default::default(),
+					retry: None,
+					session: Default::default(),
+					handle: None,
+					state,
 				})),
 			})
 		})
@@ -105,7 +112,7 @@ impl Connection for Client {
 				request: (self.id, self.method, param),
 				response: sender,
 			};
-			router.sender.send_async(Some(route)).await?;
+			router.send(route).await?;
 			Ok(receiver)
 		})
 	}
@@ -114,10 +121,12 @@ impl Connection for Client {
 pub(crate) fn router(
 	address: Endpoint,
 	capacity: usize,
+	state: ConnectionState,
 	conn_tx: Sender<Result<()>>,
 	route_rx: Receiver<Option<Route>>,
 ) {
 	spawn_local(async move {
+		let metrics = address.config.metrics.clone();
 		let (mut ws, mut socket) = match WsMeta::connect(&address.endpoint, None).await {
 			Ok(pair) => pair,
 			Err(error) => {
@@ -186,12 +195,19 @@ pub(crate) fn router(
 						response,
 					})) => {
 						let (id, method, param) = request;
-						let params = match param.query {
+						let mut params = match param.query {
 							Some((query, bindings)) => {
 								vec![query.into(), bindings.into()]
 							}
 							None => param.other,
 						};
+						// Ask the server to stop processing the request once it times out
+						if let Some(timeout) = param.timeout {
+							if let Method::Create = method {
+								params.resize(2, Value::None);
+							}
+							params.push(Value::Duration(timeout.into()));
+						}
 						match method {
 							Method::Set => {
 								if let [Value::Strand(Strand(key)), value] = &params[..2] {
@@ -213,6 +229,10 @@ pub(crate) fn router(
 							let mut request = BTreeMap::new();
 							request.insert("id".to_owned(), Value::from(id));
 							request.insert("method".to_owned(), method_str.into());
+							// Lets the server deduplicate requests which are retried
+							if let Some(key) = param.idempotency_key {
+								request.insert("key".to_owned(), key.into());
+							}
 							if !params.is_empty() {
 								request.insert("params".to_owned(), params.into());
 							}
@@ -229,9 +249,11 @@ pub(crate) fn router(
 						{
 							replay.insert(method, message.clone());
 						}
+						let bytes = len(&message);
 						match socket_sink.send(message).await {
 							Ok(..) => {
 								last_activity = Instant::now();
+								metrics.sent(method, bytes);
 								match routes.entry(id) {
 									Entry::Vacant(entry) => {
 										entry.insert((method, response));
@@ -264,7 +286,8 @@ pub(crate) fn router(
 								if let Some(response) = option {
 									trace!("{response:?}");
 									if let Some(Ok(id)) = response.id.map(Value::coerce_to_i64) {
-										if let Some((_method, sender)) = routes.remove(&id) {
+										if let Some((method, sender)) = routes.remove(&id) {
+											metrics.received(method, len(&message));
 											let _res = sender
 												.into_send_async(DbResponse::from(response.result))
 												.await;
@@ -328,7 +351,13 @@ pub(crate) fn router(
 				}
 			}
 
+			state.lost();
+
+			let mut attempt = 0;
+
 			'reconnect: loop {
+				attempt += 1;
+				metrics.retry(attempt);
 				trace!("Reconnecting...");
 				match WsMeta::connect(&address.endpoint, None).await {
 					Ok((mut meta, stream)) => {
@@ -370,6 +399,7 @@ pub(crate) fn router(
 							}
 						}
 						trace!("Reconnected successfully");
+						state.restored();
 						break;
 					}
 					Err(error) => {
@@ -382,6 +412,14 @@ pub(crate) fn router(
 	});
 }
 
+/// The size of the payload of a message
+fn len(message: &Message) -> usize {
+	match message {
+		Message::Text(text) => text.len(),
+		Message::Binary(binary) => binary.len(),
+	}
+}
+
 impl Response {
 	fn try_from(message: &Message) -> Result<Option<Self>> {
 		match message {
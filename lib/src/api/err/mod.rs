@@ -7,6 +7,7 @@ use crate::sql::Value;
 use serde::Serialize;
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 /// An error originating from a remote SurrealDB database
@@ -25,6 +26,14 @@ pub enum Error {
 	#[error("There was an error processing a remote WS request: {0}")]
 	Ws(String),
 
+	/// There was an error processing a remote gRPC request
+	#[error("There was an error processing a remote gRPC request: {0}")]
+	Grpc(String),
+
+	/// The certificates used to secure the connection are invalid
+	#[error("There was an error configuring TLS: {0}")]
+	Tls(String),
+
 	/// The specified scheme does not match any supported protocol or storage engine
 	#[error("Unsupported protocol or storage engine, `{0}`")]
 	Scheme(String),
@@ -142,6 +151,10 @@ pub enum Error {
 		error: io::Error,
 	},
 
+	/// Failed to write an export to a writer
+	#[error("Failed to write the export: {0}")]
+	ExportWrite(io::Error),
+
 	/// Tried to take only a single result when the query returned multiple records
 	#[error("Tried to take only a single result from a query that contains multiple")]
 	LossyTake(Response),
@@ -164,6 +177,26 @@ pub enum Error {
 		server_metadata: semver::BuildMetadata,
 		supported_metadata: semver::BuildMetadata,
 	},
+
+	/// The request did not complete within the specified timeout
+	#[error("The request did not complete within {0:?}")]
+	RequestTimedOut(Duration),
+
+	/// Tried to stream the results of a query which is not a single `SELECT` statement
+	#[error("Only a single SELECT statement can be streamed, but got `{0}`")]
+	InvalidStreamQuery(String),
+
+	/// Tried to connect to an empty list of endpoints
+	#[error("No endpoints were given to connect to")]
+	NoEndpoints,
+
+	/// None of the endpoints in the list could be connected to
+	#[error("Failed to connect to any of the endpoints")]
+	EndpointsUnreachable,
+
+	/// Tried to switch an isolated handle to a namespace other than its own
+	#[error("The handle is restricted to the `{0}` namespace")]
+	NamespaceNotAllowed(String),
 }
 
 #[cfg(feature = "protocol-http")]
@@ -0,0 +1,110 @@
+use crate::api::conn::Method;
+use crate::api::conn::Route;
+use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::opt::ClientSession;
+use crate::api::opt::ConnectionState;
+use crate::api::opt::Endpoint;
+use crate::api::opt::Status;
+use crate::api::Connection;
+use crate::api::ExtraFeatures;
+use crate::api::OnceLockExt;
+use crate::api::Result;
+use crate::api::Surreal;
+use flume::Receiver;
+use std::marker::PhantomData;
+use std::sync::atomic::AtomicI64;
+
+/// Creates a router which only connects to the database once it receives its first request
+///
+/// Requests are forwarded to the router of the connection once it has been established.
+/// When connecting fails, the request which triggered the connection fails with the same
+/// error, and the next request tries to connect again.
+pub(crate) fn router<C>(address: Endpoint, capacity: usize) -> Router<C>
+where
+	C: Connection,
+{
+	let (route_tx, route_rx) = match capacity {
+		0 => flume::unbounded(),
+		capacity => flume::bounded(capacity),
+	};
+	let state = ConnectionState::new(Status::Idle);
+	let router = Router {
+		// The features of the engine are not known until it is connected,
+		// so backups are checked once the requests are forwarded instead
+		features: [ExtraFeatures::Backup].into(),
+		conn: PhantomData,
+		sender: route_tx,
+		last_id: AtomicI64::new(0),
+		interceptors: Default::default(),
+		retry: address.config.retry,
+		session: ClientSession::new(address.config.token_refresh.clone()),
+		handle: None,
+		state: state.clone(),
+	};
+	address.config.metrics.register(&router.interceptors);
+
+	#[cfg(not(target_arch = "wasm32"))]
+	tokio::spawn(forward::<C>(address, capacity, state, route_rx));
+
+	#[cfg(target_arch = "wasm32")]
+	wasm_bindgen_futures::spawn_local(forward::<C>(address, capacity, state, route_rx));
+
+	router
+}
+
+async fn forward<C>(
+	address: Endpoint,
+	capacity: usize,
+	state: ConnectionState,
+	route_rx: Receiver<Option<Route>>,
+) where
+	C: Connection,
+{
+	let mut client = None;
+
+	while let Ok(Some(route)) = route_rx.recv_async().await {
+		if client.is_none() {
+			state.set(Status::Connecting);
+			match connect::<C>(address.clone(), capacity).await {
+				Ok(connected) => {
+					if let Ok(router) = connected.router.extract() {
+						state.establish(router.state.clone());
+					}
+					client = Some(connected);
+				}
+				Err(error) => {
+					trace!("Failed to connect lazily; {error}");
+					state.set(Status::Idle);
+					let _ = route.response.into_send_async(Err(error)).await;
+					continue;
+				}
+			}
+		}
+
+		let Some(Ok(router)) = client.as_ref().map(|client| client.router.extract()) else {
+			continue;
+		};
+
+		if matches!(route.request.1, Method::Export | Method::Import)
+			&& !router.features.contains(&ExtraFeatures::Backup)
+		{
+			let _ = route.response.into_send_async(Err(Error::BackupsNotSupported.into())).await;
+			continue;
+		}
+
+		if router.sender.send_async(Some(route)).await.is_err() {
+			// The engine has stopped, so the connection is closed
+			break;
+		}
+	}
+}
+
+async fn connect<C>(address: Endpoint, capacity: usize) -> Result<Surreal<C>>
+where
+	C: Connection,
+{
+	let client = C::connect(address, capacity).await?;
+	client.check_server_version().await?;
+	Ok(client)
+}
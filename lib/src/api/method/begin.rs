@@ -1,12 +1,17 @@
+use crate::api::method::query::add_bindings;
 use crate::api::method::Cancel;
 use crate::api::method::Commit;
+use crate::api::opt;
 use crate::api::Connection;
+use crate::api::OnceLockExt;
 use crate::api::Result;
 use crate::api::Surreal;
-use crate::sql::statements::BeginStatement;
+use crate::sql::Statement;
+use crate::sql::Value;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::future::IntoFuture;
-use std::ops::Deref;
 use std::pin::Pin;
 
 /// A beginning of a transaction
@@ -25,47 +30,65 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
-			self.client.query(BeginStatement).await?;
+			// Fail early if the client is not connected yet
+			self.client.router.extract()?;
 			Ok(Transaction {
 				client: self.client,
+				query: Vec::new(),
+				bindings: Ok(Default::default()),
 			})
 		})
 	}
 }
 
 /// An ongoing transaction
+///
+/// The statements added to the transaction are sent to the database together when it
+/// is committed, and run in a single transaction on the server, so that their changes
+/// are either all committed, or are all cancelled if any of them fail.
 #[derive(Debug)]
 #[must_use = "transactions must be committed or cancelled to complete them"]
 pub struct Transaction<C: Connection> {
 	client: Surreal<C>,
+	query: Vec<Result<Vec<Statement>>>,
+	bindings: Result<BTreeMap<String, Value>>,
 }
 
 impl<C> Transaction<C>
 where
 	C: Connection,
 {
+	/// Adds a set of SurrealQL statements to the transaction
+	///
+	/// The statements must not contain their own `BEGIN`, `COMMIT` or `CANCEL` statements.
+	pub fn query(mut self, query: impl opt::IntoQuery) -> Self {
+		self.query.push(query.into_query());
+		self
+	}
+
+	/// Binds a parameter or parameters to the statements in the transaction
+	pub fn bind(mut self, bindings: impl Serialize) -> Self {
+		add_bindings(&mut self.bindings, bindings);
+		self
+	}
+
 	/// Creates a commit future
+	///
+	/// The response holds a result for each statement in the transaction.
 	pub fn commit(self) -> Commit<C> {
 		Commit {
 			client: self.client,
+			query: self.query,
+			bindings: self.bindings,
 		}
 	}
 
 	/// Creates a cancel future
+	///
+	/// None of the statements in the transaction are run.
 	pub fn cancel(self) -> Cancel<C> {
 		Cancel {
 			client: self.client,
 		}
 	}
 }
-
-impl<C> Deref for Transaction<C>
-where
-	C: Connection,
-{
-	type Target = Surreal<C>;
-
-	fn deref(&self) -> &Self::Target {
-		&self.client
-	}
-}
@@ -1,7 +1,6 @@
 use crate::api::Connection;
 use crate::api::Result;
 use crate::api::Surreal;
-use crate::sql::statements::CancelStatement;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
@@ -21,9 +20,7 @@ where
 	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'static>>;
 
 	fn into_future(self) -> Self::IntoFuture {
-		Box::pin(async move {
-			self.client.query(CancelStatement).await?;
-			Ok(self.client)
-		})
+		// The statements have not been sent yet, so there is nothing to cancel on the server
+		Box::pin(async move { Ok(self.client) })
 	}
 }
@@ -1,7 +1,12 @@
+use crate::api::method::Query;
 use crate::api::Connection;
+use crate::api::OnceLockExt;
+use crate::api::Response;
 use crate::api::Result;
 use crate::api::Surreal;
-use crate::sql::statements::CommitStatement;
+use crate::sql::Statement;
+use crate::sql::Value;
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
@@ -11,19 +16,30 @@ use std::pin::Pin;
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct Commit<C: Connection> {
 	pub(crate) client: Surreal<C>,
+	pub(crate) query: Vec<Result<Vec<Statement>>>,
+	pub(crate) bindings: Result<BTreeMap<String, Value>>,
 }
 
 impl<C> IntoFuture for Commit<C>
 where
 	C: Connection,
 {
-	type Output = Result<Surreal<C>>;
+	type Output = Result<Response>;
 	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'static>>;
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
-			self.client.query(CommitStatement).await?;
-			Ok(self.client)
+			// The statements are wrapped in `BEGIN` and `COMMIT`
+			// so that they run in a single transaction
+			Query {
+				router: self.client.router.extract(),
+				query: self.query,
+				bindings: self.bindings,
+				atomic: true,
+				timeout: None,
+				staleness: None,
+			}
+			.await
 		})
 	}
 }
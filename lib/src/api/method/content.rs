@@ -1,6 +1,7 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::method::with_timeout;
 use crate::api::opt::Range;
 use crate::api::opt::Resource;
 use crate::api::Connection;
@@ -14,6 +15,7 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// A content future
 ///
@@ -26,6 +28,7 @@ pub struct Content<'r, C: Connection, D, R> {
 	pub(super) resource: Result<Resource>,
 	pub(super) range: Option<Range<Id>>,
 	pub(super) content: D,
+	pub(super) timeout: Option<Duration>,
 	pub(super) response_type: PhantomData<R>,
 }
 
@@ -38,6 +41,7 @@ macro_rules! into_future {
 				resource,
 				range,
 				content,
+				timeout,
 				..
 			} = self;
 			let content = to_value(content);
@@ -47,7 +51,8 @@ macro_rules! into_future {
 					None => resource?.into(),
 				};
 				let mut conn = Client::new(method);
-				conn.$method(router?, Param::new(vec![param, content?])).await
+				let param = Param::new(vec![param, content?]).timeout(timeout);
+				with_timeout(timeout, conn.$method(router?, param)).await
 			})
 		}
 	};
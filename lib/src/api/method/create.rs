@@ -1,6 +1,7 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::method::with_timeout;
 use crate::api::method::Content;
 use crate::api::opt::Resource;
 use crate::api::Connection;
@@ -12,6 +13,7 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// A record create future
 #[derive(Debug)]
@@ -19,6 +21,7 @@ use std::pin::Pin;
 pub struct Create<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) resource: Result<Resource>,
+	pub(super) timeout: Option<Duration>,
 	pub(super) response_type: PhantomData<R>,
 }
 
@@ -28,11 +31,13 @@ macro_rules! into_future {
 			let Create {
 				router,
 				resource,
+				timeout,
 				..
 			} = self;
-			Box::pin(async {
+			Box::pin(async move {
 				let mut conn = Client::new(Method::Create);
-				conn.$method(router?, Param::new(vec![resource?.into()])).await
+				let param = Param::new(vec![resource?.into()]).timeout(timeout);
+				with_timeout(timeout, conn.$method(router?, param)).await
 			})
 		}
 	};
@@ -85,7 +90,44 @@ where
 			resource: self.resource,
 			range: None,
 			content: data,
+			timeout: self.timeout,
 			response_type: PhantomData,
 		}
 	}
+
+	/// Limits how long the record can take to be created
+	///
+	/// The database stops processing the request once the timeout has
+	/// elapsed, and the client stops waiting for the response, returning
+	/// an error instead.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use serde::Serialize;
+	/// use std::time::Duration;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// #[derive(Serialize)]
+	/// struct User<'a> {
+	///     name: &'a str,
+	/// }
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let person: Option<Person> = db.create(("person", "tobie"))
+	///     .timeout(Duration::from_secs(5))
+	///     .content(User {
+	///         name: "Tobie",
+	///     })
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
 }
@@ -5,6 +5,7 @@ use crate::api::Connection;
 use crate::api::Error;
 use crate::api::ExtraFeatures;
 use crate::api::Result;
+use crate::opt::ExportConfig;
 use crate::opt::ExportDestination;
 use channel::Receiver;
 use futures::Stream;
@@ -16,6 +17,10 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::task::Context;
 use std::task::Poll;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncWrite;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncWriteExt;
 
 /// A database export future
 #[derive(Debug)]
@@ -23,6 +28,7 @@ use std::task::Poll;
 pub struct Export<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) target: ExportDestination,
+	pub(super) config: ExportConfig,
 	pub(super) response: PhantomData<R>,
 }
 
@@ -41,7 +47,9 @@ where
 			}
 			let mut conn = Client::new(Method::Export);
 			match self.target {
-				ExportDestination::File(path) => conn.execute_unit(router, Param::file(path)).await,
+				ExportDestination::File(path) => {
+					conn.execute_unit(router, Param::file(path).backup(self.config)).await
+				}
 				ExportDestination::Memory => unreachable!(),
 			}
 		})
@@ -66,7 +74,7 @@ where
 			let ExportDestination::Memory = self.target else {
 				unreachable!();
 			};
-			conn.execute_unit(router, Param::sender(tx)).await?;
+			conn.execute_unit(router, Param::sender(tx).backup(self.config)).await?;
 			Ok(Backup {
 				rx,
 			})
@@ -81,6 +89,33 @@ pub struct Backup {
 	rx: Receiver<Result<Vec<u8>>>,
 }
 
+impl Backup {
+	/// Writes the exported data to a writer, as it is received
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let mut stdout = tokio::io::stdout();
+	/// db.export(()).await?.write_to(&mut stdout).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(not(target_arch = "wasm32"))]
+	pub async fn write_to<W>(mut self, writer: &mut W) -> Result<()>
+	where
+		W: AsyncWrite + Unpin + ?Sized,
+	{
+		while let Some(bytes) = self.next().await {
+			writer.write_all(&bytes?).await.map_err(Error::ExportWrite)?;
+		}
+		writer.flush().await.map_err(Error::ExportWrite)?;
+		Ok(())
+	}
+}
+
 impl Stream for Backup {
 	type Item = Result<Vec<u8>>;
 
@@ -5,6 +5,8 @@ use crate::api::Connection;
 use crate::api::Error;
 use crate::api::ExtraFeatures;
 use crate::api::Result;
+use crate::opt::ExportConfig;
+use crate::opt::ExportFormat;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::path::PathBuf;
@@ -16,6 +18,7 @@ use std::pin::Pin;
 pub struct Import<'r, C: Connection> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) file: PathBuf,
+	pub(super) format: ExportFormat,
 }
 
 impl<'r, Client> IntoFuture for Import<'r, Client>
@@ -32,7 +35,11 @@ where
 				return Err(Error::BackupsNotSupported.into());
 			}
 			let mut conn = Client::new(Method::Import);
-			conn.execute_unit(router, Param::file(self.file)).await
+			let config = ExportConfig {
+				format: self.format,
+				..Default::default()
+			};
+			conn.execute_unit(router, Param::file(self.file).backup(config)).await
 		})
 	}
 }
@@ -0,0 +1,177 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::opt::Resource;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql::statements::InsertStatement;
+use crate::sql::to_value;
+use crate::sql::Data;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+/// The number of records sent to the database in each request, by default
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// An insert builder
+#[derive(Debug)]
+#[must_use = "insert does nothing until content is added"]
+pub struct Insert<'r, C: Connection, R> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) resource: Result<Resource>,
+	pub(super) response_type: PhantomData<R>,
+}
+
+impl<'r, C, R> Insert<'r, C, R>
+where
+	C: Connection,
+{
+	/// Inserts many records into the table
+	///
+	/// The records are sent to the database in batches, with each record inserted by its
+	/// own statement, so that a failure to insert one record does not affect the others.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use serde::Deserialize;
+	/// use serde::Serialize;
+	///
+	/// #[derive(Serialize, Deserialize)]
+	/// struct Person {
+	///     name: String,
+	/// }
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// let people = (0..10_000).map(|i| Person {
+	///     name: format!("Person {i}"),
+	/// });
+	///
+	/// let outcomes: Vec<surrealdb::Result<Person>> =
+	///     db.insert("person").content_many(people).batch_size(500).await?;
+	///
+	/// for outcome in outcomes {
+	///     if let Err(error) = outcome {
+	///         eprintln!("Failed to insert a person; {error}");
+	///     }
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn content_many<D>(self, data: impl IntoIterator<Item = D>) -> InsertMany<'r, C, D, R>
+	where
+		D: Serialize,
+	{
+		InsertMany {
+			router: self.router,
+			resource: self.resource,
+			content: data.into_iter().collect(),
+			batch_size: DEFAULT_BATCH_SIZE,
+			response_type: PhantomData,
+		}
+	}
+}
+
+/// A bulk insert future
+///
+/// Resolves to the outcome of inserting each record, in the order in which they were given.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct InsertMany<'r, C: Connection, D, R> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) resource: Result<Resource>,
+	pub(super) content: Vec<D>,
+	pub(super) batch_size: usize,
+	pub(super) response_type: PhantomData<R>,
+}
+
+impl<C, D, R> InsertMany<'_, C, D, R>
+where
+	C: Connection,
+{
+	/// Sets the number of records sent to the database in each request
+	///
+	/// Defaults to 1000 records. A batch size of zero is treated as one.
+	pub fn batch_size(mut self, size: usize) -> Self {
+		self.batch_size = size.max(1);
+		self
+	}
+}
+
+impl<'r, Client, D, R> IntoFuture for InsertMany<'r, Client, D, R>
+where
+	Client: Connection,
+	D: Serialize,
+	R: DeserializeOwned + Send + Sync + 'r,
+{
+	type Output = Result<Vec<Result<R>>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		let InsertMany {
+			router,
+			resource,
+			content,
+			batch_size,
+			..
+		} = self;
+		let content: Result<Vec<Value>> =
+			content.into_iter().map(|v| to_value(v).map_err(Into::into)).collect();
+		Box::pin(async move {
+			let router = router?;
+			let into: Value = resource?.into();
+			let content = content?;
+			let mut outcomes = Vec::with_capacity(content.len());
+			for batch in content.chunks(batch_size) {
+				// Each record is inserted by its own statement, so that it has its own outcome
+				let statements = batch
+					.iter()
+					.map(|value| {
+						Statement::Insert(InsertStatement {
+							into: into.clone(),
+							data: Data::SingleExpression(value.clone()),
+							..Default::default()
+						})
+					})
+					.collect();
+				let query = Query(Statements(statements));
+				let mut conn = Client::new(Method::Query);
+				let param = Param::query(query, BTreeMap::new());
+				let mut response = conn.execute_query(router, param).await?;
+				let mut errors = response.take_errors();
+				for index in 0..batch.len() {
+					let outcome = match errors.remove(&index) {
+						Some(error) => Err(error),
+						None => response.take::<Option<R>>(index).and_then(|record| {
+							record.ok_or_else(|| {
+								Error::InternalError(
+									"The database did not return the inserted record".to_owned(),
+								)
+								.into()
+							})
+						}),
+					};
+					outcomes.push(outcome);
+				}
+			}
+			Ok(outcomes)
+		})
+	}
+}
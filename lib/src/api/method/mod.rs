@@ -12,15 +12,18 @@ mod delete;
 mod export;
 mod health;
 mod import;
+mod insert;
 mod invalidate;
 mod kill;
 mod live;
 mod merge;
 mod patch;
+mod restore_session;
 mod select;
 mod set;
 mod signin;
 mod signup;
+mod stream;
 mod unset;
 mod update;
 mod use_db;
@@ -31,13 +34,9 @@ mod version;
 mod tests;
 
 pub use authenticate::Authenticate;
-#[doc(hidden)] // Not supported yet
 pub use begin::Begin;
-#[doc(hidden)] // Not supported yet
 pub use begin::Transaction;
-#[doc(hidden)] // Not supported yet
 pub use cancel::Cancel;
-#[doc(hidden)] // Not supported yet
 pub use commit::Commit;
 pub use content::Content;
 pub use create::Create;
@@ -46,6 +45,8 @@ pub use export::Backup;
 pub use export::Export;
 pub use health::Health;
 pub use import::Import;
+pub use insert::Insert;
+pub use insert::InsertMany;
 pub use invalidate::Invalidate;
 #[doc(hidden)] // Not supported yet
 pub use kill::Kill;
@@ -54,10 +55,12 @@ pub use live::Live;
 pub use merge::Merge;
 pub use patch::Patch;
 pub use query::Query;
+pub use restore_session::RestoreSession;
 pub use select::Select;
 pub use set::Set;
 pub use signin::Signin;
 pub use signup::Signup;
+pub use stream::QueryStream;
 pub use unset::Unset;
 pub use update::Update;
 pub use use_db::UseDb;
@@ -65,24 +68,40 @@ pub use use_ns::UseNs;
 pub use version::Version;
 
 use crate::api::conn::Method;
+use crate::api::err::Error;
 use crate::api::opt;
 use crate::api::opt::auth;
 use crate::api::opt::auth::Credentials;
 use crate::api::opt::auth::Jwt;
 use crate::api::opt::IntoEndpoint;
+use crate::api::opt::SessionState;
+use crate::api::opt::Status;
 use crate::api::Connect;
 use crate::api::Connection;
 use crate::api::OnceLockExt;
+use crate::api::Result;
 use crate::api::Surreal;
+use crate::opt::ExportConfig;
+use crate::opt::ExportFormat;
 use crate::opt::IntoExportDestination;
+use crate::sql::statements::SelectStatement;
 use crate::sql::to_value;
+use crate::sql::Field;
+use crate::sql::Fields;
 use crate::sql::Uuid;
 use crate::sql::Value;
+use crate::sql::Values;
 use serde::Serialize;
+use std::future::Future;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio as time;
 
 impl Method {
 	#[allow(dead_code)] // used by `ws` and `http`
@@ -110,6 +129,52 @@ impl Method {
 			Method::Version => "version",
 		}
 	}
+
+	/// Whether the request can be sent again, when the connection is lost
+	pub(crate) fn is_retryable(&self) -> bool {
+		!matches!(self, Method::Export | Method::Import)
+	}
+
+	/// Whether the request may write to the database, so is sent with an idempotency key
+	pub(crate) fn is_write(&self) -> bool {
+		matches!(
+			self,
+			Method::Create
+				| Method::Delete
+				| Method::Merge
+				| Method::Patch
+				| Method::Query
+				| Method::Update
+		)
+	}
+
+	/// Whether the request changes the session, which is tracked by the client
+	pub(crate) fn changes_session(&self) -> bool {
+		matches!(
+			self,
+			Method::Use
+				| Method::Signin
+				| Method::Signup
+				| Method::Authenticate
+				| Method::Invalidate
+				| Method::Set
+				| Method::Unset
+		)
+	}
+}
+
+/// Waits for a request to complete, failing if it takes longer than the timeout
+async fn with_timeout<T>(
+	timeout: Option<Duration>,
+	future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+	match timeout {
+		Some(duration) => match time::timeout(duration, future).await {
+			Ok(result) => result,
+			Err(_) => Err(Error::RequestTimedOut(duration).into()),
+		},
+		None => future.await,
+	}
 }
 
 impl<C> Surreal<C>
@@ -239,18 +304,86 @@ where
 			router: Arc::new(OnceLock::new()),
 			address: address.into_endpoint(),
 			capacity: 0,
+			lazy: false,
 			client: PhantomData,
 			response_type: PhantomData,
 		}
 	}
 
-	#[doc(hidden)] // Not supported yet
-	pub fn transaction(self) -> Begin<C> {
+	/// Begins a transaction
+	///
+	/// Statements added to the transaction are sent to the database together once it is
+	/// committed, and run in a single transaction, rather than having to concatenate
+	/// `BEGIN` and `COMMIT` statements into a single query.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// let transaction = db.begin().await?;
+	///
+	/// let mut response = transaction
+	///     .query("UPDATE account:one SET balance += $amount")
+	///     .query("UPDATE account:two SET balance -= $amount")
+	///     .bind(("amount", 300))
+	///     .commit()
+	///     .await?;
+	///
+	/// // Check that both statements succeeded
+	/// let errors = response.take_errors();
+	/// assert!(errors.is_empty());
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn begin(&self) -> Begin<C> {
 		Begin {
-			client: self,
+			client: self.clone(),
 		}
 	}
 
+	/// Registers an interceptor, which runs around every request sent by this client
+	///
+	/// The interceptor is shared by all clones of this client.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::opt::Interceptor;
+	/// use surrealdb::opt::Request;
+	/// use std::future::Future;
+	/// use std::pin::Pin;
+	///
+	/// struct Tenant;
+	///
+	/// impl Interceptor for Tenant {
+	///     fn before<'a>(
+	///         &'a self,
+	///         request: &'a mut Request,
+	///     ) -> Pin<Box<dyn Future<Output = surrealdb::Result<()>> + Send + Sync + 'a>> {
+	///         request.insert_header("X-Tenant", "acme");
+	///         Box::pin(async { Ok(()) })
+	///     }
+	/// }
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("http://localhost:8000").await?;
+	/// db.intercept(Tenant)?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn intercept(&self, interceptor: impl opt::Interceptor) -> Result<()> {
+		self.router.extract()?.interceptors.push(interceptor);
+		Ok(())
+	}
+
 	/// Switch to a specific namespace
 	///
 	/// # Examples
@@ -586,6 +719,55 @@ where
 		}
 	}
 
+	/// Returns the state of the session, so that it can be restored later
+	///
+	/// The state is made up of the namespace and database in use, the token which the session
+	/// is authenticated with, and the parameters set on the connection. It only reflects the
+	/// requests sent through this client, so changes made by queries, such as `USE` statements,
+	/// are not included. Signing in as a root user does not return a token, so that session
+	/// cannot be restored.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let state = db.session_state()?;
+	/// let saved = serde_json::to_string(&state).unwrap();
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn session_state(&self) -> Result<SessionState> {
+		Ok(self.router.extract()?.session.state())
+	}
+
+	/// Restores a session, which was saved with [`Surreal::session_state`]
+	///
+	/// Selects the namespace and database, authenticates with the token, and sets the
+	/// parameters of the session, so that a client can resume a session after restarting.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::opt::SessionState;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// # let saved = String::new();
+	/// let state: SessionState = serde_json::from_str(&saved).unwrap();
+	/// db.restore_session(state).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn restore_session(&self, state: SessionState) -> RestoreSession<C> {
+		RestoreSession {
+			router: self.router.extract(),
+			state,
+		}
+	}
+
 	/// Runs a set of SurrealQL statements against the database
 	///
 	/// # Examples
@@ -623,9 +805,42 @@ where
 			router: self.router.extract(),
 			query: vec![query.into_query()],
 			bindings: Ok(Default::default()),
+			atomic: false,
+			timeout: None,
+			staleness: None,
 		}
 	}
 
+	/// Parses a set of SurrealQL statements once, so that they can be run repeatedly
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Parse the query only once
+	/// let query = db.prepare("SELECT * FROM person WHERE age > $age")?;
+	///
+	/// // Run the query with different parameters
+	/// for age in [18, 30, 65] {
+	///     let mut result = db.query(&query).bind(("age", age)).await?;
+	///     let people: Vec<Person> = result.take(0)?;
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn prepare(&self, query: impl opt::IntoQuery) -> Result<opt::Prepared> {
+		opt::Prepared::new(query)
+	}
+
 	/// Selects all records in a table, or a specific record
 	///
 	/// # Examples
@@ -655,6 +870,101 @@ where
 			router: self.router.extract(),
 			resource: resource.into_resource(),
 			range: None,
+			timeout: None,
+			filter: None,
+			order: Vec::new(),
+			limit: None,
+			start: None,
+			response_type: PhantomData,
+		}
+	}
+
+	/// Streams the records returned by a `SELECT` statement
+	///
+	/// Unlike [`Surreal::query`], the records are not all buffered in memory at once.
+	/// They are fetched from the database in batches as the stream is consumed, which
+	/// makes this suitable for exporting very large tables. The query must be a single
+	/// `SELECT` statement, and any `START` or `LIMIT` clauses must be numbers.
+	///
+	/// Records are paged through using `START` and `LIMIT`, so the query should be
+	/// ordered if records may be added or removed while the stream is being consumed.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::TryStreamExt;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// let mut people = db
+	///     .query_stream::<Person>("SELECT * FROM person WHERE age > $age ORDER BY id")
+	///     .bind(("age", 18))
+	///     .batch_size(500);
+	///
+	/// while let Some(person) = people.try_next().await? {
+	///     // Process each person
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn query_stream<R>(&self, query: impl opt::IntoQuery) -> QueryStream<C, R> {
+		QueryStream {
+			router: self.router.extract(),
+			statement: stream::select_statement(query.into_query()),
+			bindings: Ok(Default::default()),
+			batch_size: stream::DEFAULT_BATCH_SIZE,
+			records: None,
+			response_type: PhantomData,
+		}
+	}
+
+	/// Streams all records in a table
+	///
+	/// The records are fetched from the database in batches as the stream is consumed,
+	/// rather than all being buffered in memory at once. See [`Surreal::query_stream`].
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::TryStreamExt;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// let mut people = db.select_stream::<Person>("person");
+	///
+	/// while let Some(person) = people.try_next().await? {
+	///     // Process each person
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn select_stream<R>(&self, resource: impl opt::IntoResource<Vec<R>>) -> QueryStream<C, R> {
+		QueryStream {
+			router: self.router.extract(),
+			statement: resource.into_resource().map(|resource| SelectStatement {
+				expr: Fields(vec![Field::All], false),
+				what: Values(vec![resource.into()]),
+				..Default::default()
+			}),
+			bindings: Ok(Default::default()),
+			batch_size: stream::DEFAULT_BATCH_SIZE,
+			records: None,
 			response_type: PhantomData,
 		}
 	}
@@ -707,6 +1017,38 @@ where
 	/// ```
 	pub fn create<R>(&self, resource: impl opt::IntoResource<R>) -> Create<C, R> {
 		Create {
+			router: self.router.extract(),
+			resource: resource.into_resource(),
+			timeout: None,
+			response_type: PhantomData,
+		}
+	}
+
+	/// Inserts records into a table
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[derive(serde::Serialize, serde::Deserialize)]
+	/// # struct Person;
+	/// #
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// # let people: Vec<Person> = Vec::new();
+	/// // Insert many records, in batches of 1000
+	/// let outcomes: Vec<surrealdb::Result<Person>> =
+	///     db.insert("person").content_many(people).await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn insert<R>(&self, resource: impl opt::IntoResource<Vec<R>>) -> Insert<C, R> {
+		Insert {
 			router: self.router.extract(),
 			resource: resource.into_resource(),
 			response_type: PhantomData,
@@ -925,6 +1267,9 @@ where
 
 	/// Checks whether the server is healthy or not
 	///
+	/// When the connection is [lazy](Connect::lazy), this connects to the
+	/// database if it is not connected yet.
+	///
 	/// # Examples
 	///
 	/// ```no_run
@@ -941,6 +1286,35 @@ where
 		}
 	}
 
+	/// Returns the state of the connection to the database
+	///
+	/// Unlike [`health`](Self::health), this does not send a request to the
+	/// server, so it can be called as often as needed, such as by readiness probes.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::opt::Status;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("ws://localhost:8000").await?;
+	/// match db.status() {
+	///     Status::Connected => println!("Connected"),
+	///     Status::Reconnecting => println!("Lost the connection, reconnecting"),
+	///     status => println!("Not connected; {status:?}"),
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn status(&self) -> Status {
+		match self.router.get() {
+			None => Status::Uninitialised,
+			Some(router) if router.sender.is_disconnected() => Status::Disconnected,
+			Some(router) => router.state.get(),
+		}
+	}
+
 	#[doc(hidden)] // Not supported yet
 	pub fn kill(&self, query_id: Uuid) -> Kill<C> {
 		Kill {
@@ -992,9 +1366,54 @@ where
 	/// # }
 	/// ```
 	pub fn export<R>(&self, target: impl IntoExportDestination<R>) -> Export<C, R> {
+		self.export_to(target, ExportConfig::default())
+	}
+
+	/// Exports the contents of the database, in a format and for the tables of the config
+	///
+	/// The tables can be limited to a subset of the database, and exported as SurrealQL or
+	/// as JSON lines. When `since` is set, only the changes made since that time are exported,
+	/// which requires the tables to be defined with a `CHANGEFEED`.
+	///
+	/// # Support
+	///
+	/// Currently only supported by HTTP and the local engines. *Not* supported on WebAssembly.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::opt::ExportConfig;
+	/// use surrealdb::opt::ExportFormat;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Export the records of some tables as JSON lines
+	/// let config = ExportConfig {
+	///     tables: Some(vec!["person".to_owned(), "account".to_owned()]),
+	///     format: ExportFormat::Json,
+	///     ..Default::default()
+	/// };
+	/// db.export_to("people.jsonl", config.clone()).await?;
+	///
+	/// // Stream the export to a writer
+	/// let mut stdout = tokio::io::stdout();
+	/// db.export_to((), config).await?.write_to(&mut stdout).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn export_to<R>(
+		&self,
+		target: impl IntoExportDestination<R>,
+		config: ExportConfig,
+	) -> Export<C, R> {
 		Export {
 			router: self.router.extract(),
 			target: target.into_export_destination(),
+			config,
 			response: PhantomData,
 		}
 	}
@@ -1019,12 +1438,41 @@ where
 	/// # }
 	/// ```
 	pub fn import<P>(&self, file: P) -> Import<C>
+	where
+		P: AsRef<Path>,
+	{
+		self.import_from(file, ExportFormat::Sql)
+	}
+
+	/// Restores the database from a file, which was exported in the specified format
+	///
+	/// # Support
+	///
+	/// Currently only supported by HTTP and the local engines. *Not* supported on WebAssembly.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::opt::ExportFormat;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// db.import_from("people.jsonl", ExportFormat::Json).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn import_from<P>(&self, file: P, format: ExportFormat) -> Import<C>
 	where
 		P: AsRef<Path>,
 	{
 		Import {
 			router: self.router.extract(),
 			file: file.as_ref().to_owned(),
+			format,
 		}
 	}
 }
@@ -2,6 +2,7 @@ use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
 use crate::api::err::Error;
+use crate::api::method::with_timeout;
 use crate::api::opt;
 use crate::api::Connection;
 use crate::api::Result;
@@ -12,6 +13,7 @@ use crate::sql::Object;
 use crate::sql::Statement;
 use crate::sql::Statements;
 use crate::sql::Strand;
+use crate::sql::Timeout;
 use crate::sql::Value;
 use indexmap::IndexMap;
 use serde::de::DeserializeOwned;
@@ -22,6 +24,7 @@ use std::future::Future;
 use std::future::IntoFuture;
 use std::mem;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// A query future
 #[derive(Debug)]
@@ -30,6 +33,9 @@ pub struct Query<'r, C: Connection> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) query: Vec<Result<Vec<Statement>>>,
 	pub(super) bindings: Result<BTreeMap<String, Value>>,
+	pub(super) atomic: bool,
+	pub(super) timeout: Option<Duration>,
+	pub(super) staleness: Option<Duration>,
 }
 
 impl<'r, Client> IntoFuture for Query<'r, Client>
@@ -45,14 +51,36 @@ where
 			for query in self.query {
 				statements.extend(query?);
 			}
-			let query = sql::Query(Statements(statements));
-			let param = Param::query(query, self.bindings?);
+			if let Some(timeout) = self.timeout {
+				for statement in statements.iter_mut() {
+					set_timeout(statement, timeout);
+				}
+			}
+			let mut query = sql::Query(Statements(statements));
+			if self.atomic {
+				query = query.atomic()?;
+			}
+			let param = Param::query(query, self.bindings?).staleness(self.staleness);
 			let mut conn = Client::new(Method::Query);
-			conn.execute_query(self.router?, param).await
+			with_timeout(self.timeout, conn.execute_query(self.router?, param)).await
 		})
 	}
 }
 
+/// Sets the timeout of a statement, unless it already specifies one
+fn set_timeout(statement: &mut Statement, timeout: Duration) {
+	let current = match statement {
+		Statement::Create(v) => &mut v.timeout,
+		Statement::Delete(v) => &mut v.timeout,
+		Statement::Insert(v) => &mut v.timeout,
+		Statement::Relate(v) => &mut v.timeout,
+		Statement::Select(v) => &mut v.timeout,
+		Statement::Update(v) => &mut v.timeout,
+		_ => return,
+	};
+	current.get_or_insert(Timeout(timeout.into()));
+}
+
 impl<'r, C> Query<'r, C>
 where
 	C: Connection,
@@ -63,6 +91,86 @@ where
 		self
 	}
 
+	/// Runs the statements in a single transaction, so that their changes
+	/// are either all committed, or are all cancelled if any of them fail
+	///
+	/// The statements must not contain their own `BEGIN`, `COMMIT` or
+	/// `CANCEL` statements. A result is still returned for each statement.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let response = db
+	///     .query("UPDATE account:one SET balance += 300.00")
+	///     .query("UPDATE account:two SET balance -= 300.00")
+	///     .atomic()
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn atomic(mut self) -> Self {
+		self.atomic = true;
+		self
+	}
+
+	/// Limits how long the statements can take to run
+	///
+	/// Each statement which does not specify its own `TIMEOUT` clause is
+	/// stopped by the database once the timeout has elapsed, and the client
+	/// stops waiting for the response, returning an error instead.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use std::time::Duration;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let response = db
+	///     .query("SELECT * FROM person WHERE age > 18")
+	///     .timeout(Duration::from_secs(5))
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Limits how stale the results of a read-only query can be, when it is sent to a read replica
+	///
+	/// When the client reads from replicas, a query whose statements do not write is sent to a
+	/// replica, unless the replica may not yet have applied a write made through this client,
+	/// and the results could then be older than the bound. The query is then sent to the primary.
+	/// Without a bound, read-only queries are always sent to a replica.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use std::time::Duration;
+	/// use surrealdb::engine::any::{connect, Failover};
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let endpoints = Failover::new(["ws://primary:8000", "ws://replica:8000"]).read_replicas(true);
+	/// let db = connect(endpoints).await?;
+	/// let response = db
+	///     .query("SELECT * FROM person WHERE age > 18")
+	///     .max_staleness(Duration::ZERO)
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn max_staleness(mut self, staleness: Duration) -> Self {
+		self.staleness = Some(staleness);
+		self
+	}
+
 	/// Binds a parameter or parameters to a query
 	///
 	/// # Examples
@@ -105,29 +213,37 @@ where
 	/// # }
 	/// ```
 	pub fn bind(mut self, bindings: impl Serialize) -> Self {
-		if let Ok(current) = &mut self.bindings {
-			match to_value(bindings) {
-				Ok(mut bindings) => {
-					if let Value::Array(Array(array)) = &mut bindings {
-						if let [Value::Strand(Strand(key)), value] = &mut array[..] {
-							let mut map = BTreeMap::new();
-							map.insert(mem::take(key), mem::take(value));
-							bindings = map.into();
-						}
-					}
-					match &mut bindings {
-						Value::Object(Object(map)) => current.append(map),
-						_ => {
-							self.bindings = Err(Error::InvalidBindings(bindings).into());
-						}
+		add_bindings(&mut self.bindings, bindings);
+		self
+	}
+}
+
+/// Adds a key/value tuple, or the fields of an object, to the current bindings
+pub(super) fn add_bindings(
+	current: &mut Result<BTreeMap<String, Value>>,
+	bindings: impl Serialize,
+) {
+	if let Ok(map) = current {
+		match to_value(bindings) {
+			Ok(mut bindings) => {
+				if let Value::Array(Array(array)) = &mut bindings {
+					if let [Value::Strand(Strand(key)), value] = &mut array[..] {
+						let mut map = BTreeMap::new();
+						map.insert(mem::take(key), mem::take(value));
+						bindings = map.into();
 					}
 				}
-				Err(error) => {
-					self.bindings = Err(error.into());
+				match &mut bindings {
+					Value::Object(Object(other)) => map.append(other),
+					_ => {
+						*current = Err(Error::InvalidBindings(bindings).into());
+					}
 				}
 			}
+			Err(error) => {
+				*current = Err(error.into());
+			}
 		}
-		self
 	}
 }
 
@@ -0,0 +1,53 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::opt::SessionState;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql::Value;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+
+/// A session restore future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RestoreSession<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) state: SessionState,
+}
+
+impl<'r, Client> IntoFuture for RestoreSession<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let SessionState {
+				ns,
+				db,
+				token,
+				vars,
+			} = self.state;
+			if ns.is_some() || db.is_some() {
+				let ns = ns.map_or(Value::None, Value::from);
+				let db = db.map_or(Value::None, Value::from);
+				let mut conn = Client::new(Method::Use);
+				conn.execute_unit(router, Param::new(vec![ns, db])).await?;
+			}
+			if let Some(token) = token {
+				let mut conn = Client::new(Method::Authenticate);
+				conn.execute_unit(router, Param::new(vec![token.0.into()])).await?;
+			}
+			for (key, value) in vars {
+				let mut conn = Client::new(Method::Set);
+				conn.execute_unit(router, Param::new(vec![key.into(), value])).await?;
+			}
+			Ok(())
+		})
+	}
+}
@@ -1,17 +1,37 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::method::with_timeout;
+use crate::api::opt;
+use crate::api::opt::Filter;
 use crate::api::opt::Range;
 use crate::api::opt::Resource;
 use crate::api::Connection;
+use crate::api::Response;
 use crate::api::Result;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Array;
+use crate::sql::Cond;
+use crate::sql::Field;
+use crate::sql::Fields;
 use crate::sql::Id;
+use crate::sql::Limit;
+use crate::sql::Order;
+use crate::sql::Orders;
+use crate::sql::Query;
+use crate::sql::Start;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Timeout;
 use crate::sql::Value;
+use crate::sql::Values;
 use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::time::Duration;
 
 /// A select future
 #[derive(Debug)]
@@ -20,16 +40,26 @@ pub struct Select<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) resource: Result<Resource>,
 	pub(super) range: Option<Range<Id>>,
+	pub(super) timeout: Option<Duration>,
+	pub(super) filter: Option<Filter>,
+	pub(super) order: Vec<Order>,
+	pub(super) limit: Option<usize>,
+	pub(super) start: Option<usize>,
 	pub(super) response_type: PhantomData<R>,
 }
 
 macro_rules! into_future {
-	($method:ident) => {
+	($method:ident, $take:expr) => {
 		fn into_future(self) -> Self::IntoFuture {
 			let Select {
 				router,
 				resource,
 				range,
+				timeout,
+				filter,
+				order,
+				limit,
+				start,
 				..
 			} = self;
 			Box::pin(async move {
@@ -37,13 +67,52 @@ macro_rules! into_future {
 					Some(range) => resource?.with_range(range)?,
 					None => resource?.into(),
 				};
+				// Filtered or sorted selects are sent as a query built from the AST
+				if filter.is_some() || !order.is_empty() || limit.is_some() || start.is_some() {
+					let one = param.is_thing();
+					let statement = SelectStatement {
+						expr: Fields(vec![Field::All], false),
+						what: Values(vec![param]),
+						cond: filter.map(|v| Cond(v.0)),
+						order: (!order.is_empty()).then(|| Orders(order)),
+						limit: limit.map(|v| Limit(v.into())),
+						start: start.map(|v| Start(v.into())),
+						timeout: timeout.map(|v| Timeout(v.into())),
+						..Default::default()
+					};
+					let query = Query(Statements(vec![Statement::Select(statement)]));
+					let mut conn = Client::new(Method::Query);
+					let param = Param::query(query, BTreeMap::new());
+					let response =
+						with_timeout(timeout, conn.execute_query(router?, param)).await?;
+					return $take(response, one);
+				}
 				let mut conn = Client::new(Method::Select);
-				conn.$method(router?, Param::new(vec![param])).await
+				let param = Param::new(vec![param]).timeout(timeout);
+				with_timeout(timeout, conn.$method(router?, param)).await
 			})
 		}
 	};
 }
 
+/// Takes the records selected from the response to the query
+fn take_value(mut response: Response, one: bool) -> Result<Value> {
+	let value: Value = response.take(0)?;
+	match value {
+		// A single record is returned when selecting a record by its ID
+		Value::Array(Array(mut vec)) if one => Ok(vec.pop().unwrap_or_default()),
+		value => Ok(value),
+	}
+}
+
+fn take<R>(mut response: Response, _one: bool) -> Result<R>
+where
+	R: DeserializeOwned,
+	usize: crate::api::opt::QueryResult<R>,
+{
+	response.take(0)
+}
+
 impl<'r, Client> IntoFuture for Select<'r, Client, Value>
 where
 	Client: Connection,
@@ -51,7 +120,7 @@ where
 	type Output = Result<Value>;
 	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
 
-	into_future! {execute_value}
+	into_future! {execute_value, take_value}
 }
 
 impl<'r, Client, R> IntoFuture for Select<'r, Client, Option<R>>
@@ -62,7 +131,7 @@ where
 	type Output = Result<Option<R>>;
 	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
 
-	into_future! {execute_opt}
+	into_future! {execute_opt, take}
 }
 
 impl<'r, Client, R> IntoFuture for Select<'r, Client, Vec<R>>
@@ -73,7 +142,7 @@ where
 	type Output = Result<Vec<R>>;
 	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
 
-	into_future! {execute_vec}
+	into_future! {execute_vec, take}
 }
 
 impl<C> Select<'_, C, Value>
@@ -97,3 +166,94 @@ where
 		self
 	}
 }
+
+impl<C, R> Select<'_, C, R>
+where
+	C: Connection,
+{
+	/// Limits how long the records can take to be selected
+	///
+	/// The database stops processing the request once the timeout has
+	/// elapsed, and the client stops waiting for the response, returning
+	/// an error instead.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use std::time::Duration;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let people: Vec<Person> = db.select("person").timeout(Duration::from_secs(5)).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Restricts the records selected to those matching the filter
+	///
+	/// Calling this more than once requires the records to match every filter.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::opt::field;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let adults: Vec<Person> = db.select("person").filter(field("age").gt(18)).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn filter(mut self, filter: Filter) -> Self {
+		self.filter = Some(match self.filter.take() {
+			Some(existing) => existing.and(filter),
+			None => filter,
+		});
+		self
+	}
+
+	/// Sorts the records selected by a field, in ascending order
+	///
+	/// Calling this more than once sorts by each field in turn.
+	pub fn order_by(self, field: &str) -> Self {
+		self.order(field, true)
+	}
+
+	/// Sorts the records selected by a field, in descending order
+	pub fn order_by_desc(self, field: &str) -> Self {
+		self.order(field, false)
+	}
+
+	/// Limits the number of records selected
+	pub fn limit(mut self, limit: usize) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	/// Skips the specified number of records before selecting any
+	pub fn start(mut self, start: usize) -> Self {
+		self.start = Some(start);
+		self
+	}
+
+	fn order(mut self, field: &str, ascending: bool) -> Self {
+		self.order.push(Order {
+			order: opt::field(field).0,
+			random: false,
+			collate: false,
+			numeric: false,
+			direction: ascending,
+		});
+		self
+	}
+}
@@ -0,0 +1,196 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::method::query::add_bindings;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Limit;
+use crate::sql::Query;
+use crate::sql::Start;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Value;
+use futures::stream;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+/// The number of records fetched from the database at a time, by default
+pub(crate) const DEFAULT_BATCH_SIZE: usize = 1000;
+
+type Records<'r, R> = Pin<Box<dyn Stream<Item = Result<R>> + Send + 'r>>;
+
+/// A stream of the records returned by a `SELECT` statement
+///
+/// The records are fetched from the database in batches, using `START` and `LIMIT`,
+/// so that only a single batch is held in memory at a time. The next batch is only
+/// requested once every record in the current batch has been consumed.
+#[must_use = "streams do nothing unless polled"]
+pub struct QueryStream<'r, C: Connection, R> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) statement: Result<SelectStatement>,
+	pub(super) bindings: Result<BTreeMap<String, Value>>,
+	pub(super) batch_size: usize,
+	pub(super) records: Option<Records<'r, R>>,
+	pub(super) response_type: PhantomData<R>,
+}
+
+impl<C, R> QueryStream<'_, C, R>
+where
+	C: Connection,
+{
+	/// Sets the number of records fetched from the database at a time
+	///
+	/// Defaults to 1000 records. A batch size of zero is treated as one.
+	pub fn batch_size(mut self, size: usize) -> Self {
+		self.batch_size = size.max(1);
+		self
+	}
+
+	/// Binds a parameter or parameters to the query
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::TryStreamExt;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let mut people = db
+	///     .query_stream::<Person>("SELECT * FROM person WHERE age > $age")
+	///     .bind(("age", 18));
+	///
+	/// while let Some(person) = people.try_next().await? {
+	///     // Process each person
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn bind(mut self, bindings: impl Serialize) -> Self {
+		add_bindings(&mut self.bindings, bindings);
+		self
+	}
+}
+
+/// Checks that a query is a single `SELECT` statement, which can be streamed
+pub(super) fn select_statement(statements: Result<Vec<Statement>>) -> Result<SelectStatement> {
+	let mut statements = statements?;
+	match statements.pop() {
+		Some(Statement::Select(statement)) if statements.is_empty() => Ok(statement),
+		Some(statement) => {
+			statements.push(statement);
+			let query = Query(Statements(statements));
+			Err(Error::InvalidStreamQuery(query.to_string()).into())
+		}
+		None => Err(Error::InvalidStreamQuery(String::new()).into()),
+	}
+}
+
+/// Converts the `START` or `LIMIT` clause of a statement into a number
+fn clause(value: Option<&Value>) -> Result<Option<usize>> {
+	match value {
+		None => Ok(None),
+		Some(Value::Number(number)) => Ok(Some(number.as_usize())),
+		Some(value) => Err(Error::InvalidStreamQuery(format!(
+			"START and LIMIT must be numbers when streaming, but got `{value}`"
+		))
+		.into()),
+	}
+}
+
+/// Fetches the records in batches, ending once a batch is not full
+fn records<'r, C, R>(
+	router: Result<&'r Router<C>>,
+	statement: Result<SelectStatement>,
+	bindings: Result<BTreeMap<String, Value>>,
+	batch_size: usize,
+) -> Records<'r, R>
+where
+	C: Connection,
+	R: DeserializeOwned + Send + 'r,
+{
+	let batches = stream::once(async move {
+		let router = router?;
+		let statement = statement?;
+		let bindings = bindings?;
+		let offset = clause(statement.start.as_ref().map(|v| &v.0))?.unwrap_or_default();
+		let limit = clause(statement.limit.as_ref().map(|v| &v.0))?;
+		let state = (router, statement, bindings, offset, limit);
+		Ok::<_, crate::Error>(stream::try_unfold(Some(state), move |state| async move {
+			let Some((router, mut statement, bindings, offset, limit)) = state else {
+				return Ok(None);
+			};
+			let size = limit.map_or(batch_size, |limit| limit.min(batch_size));
+			if size == 0 {
+				return Ok(None);
+			}
+			statement.start = Some(Start(offset.into()));
+			statement.limit = Some(Limit(size.into()));
+			let query = Query(Statements(vec![Statement::Select(statement.clone())]));
+			let mut conn = C::new(Method::Query);
+			let param = Param::query(query, bindings.clone());
+			let batch: Vec<R> = conn.execute_query(router, param).await?.take(0)?;
+			// A batch which is not full is the last one
+			let next = (batch.len() == size).then(|| {
+				let limit = limit.map(|limit| limit - size);
+				(router, statement, bindings, offset + size, limit)
+			});
+			Ok(Some((batch, next)))
+		}))
+	});
+	batches
+		.try_flatten()
+		.map_ok(|batch| stream::iter(batch.into_iter().map(Ok)))
+		.try_flatten()
+		.boxed()
+}
+
+impl<'r, C, R> Stream for QueryStream<'r, C, R>
+where
+	C: Connection,
+	R: DeserializeOwned + Send + 'r,
+{
+	type Item = Result<R>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = &mut *self;
+		let records = this.records.get_or_insert_with(|| {
+			let router = mem::replace(&mut this.router, Err(Error::ConnectionUninitialised.into()));
+			let statement = mem::replace(&mut this.statement, Ok(SelectStatement::default()));
+			let bindings = mem::replace(&mut this.bindings, Ok(BTreeMap::new()));
+			records(router, statement, bindings, this.batch_size)
+		});
+		records.poll_next_unpin(cx)
+	}
+}
+
+// The stream is not structurally pinned, as the records are boxed
+impl<C, R> Unpin for QueryStream<'_, C, R> where C: Connection {}
+
+impl<C, R> fmt::Debug for QueryStream<'_, C, R>
+where
+	C: Connection,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("QueryStream")
+			.field("router", &self.router)
+			.field("statement", &self.statement)
+			.field("bindings", &self.bindings)
+			.field("batch_size", &self.batch_size)
+			.finish_non_exhaustive()
+	}
+}
@@ -11,16 +11,33 @@ use crate::api::opt::auth::Jwt;
 use crate::api::opt::auth::Namespace;
 use crate::api::opt::auth::Root;
 use crate::api::opt::auth::Scope;
+use crate::api::opt::field;
+use crate::api::opt::Config;
+use crate::api::opt::Interceptor;
+use crate::api::opt::Metrics;
 use crate::api::opt::PatchOp;
+use crate::api::opt::Reply;
+use crate::api::opt::Request;
+use crate::api::opt::Status;
+use crate::api::opt::TokenRefresh;
 use crate::api::Response as QueryResponse;
 use crate::api::Surreal;
 use crate::sql::statements::BeginStatement;
 use crate::sql::statements::CommitStatement;
+use base64_lib::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64_lib::Engine;
+use futures::TryStreamExt;
 use once_cell::sync::Lazy;
 use protocol::Client;
 use protocol::Test;
 use semver::Version;
+use std::future::Future;
 use std::ops::Bound;
+use std::pin::Pin;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 use types::User;
 use types::USER;
 
@@ -102,7 +119,7 @@ async fn api() {
 		.await
 		.unwrap();
 	let _: QueryResponse = DB
-		.query(BeginStatement)
+		.query(BeginStatement::default())
 		.query("CREATE account:one SET balance = 135605.16")
 		.query("CREATE account:two SET balance = 91031.31")
 		.query("UPDATE account:one SET balance += 300.00")
@@ -110,12 +127,21 @@ async fn api() {
 		.query(CommitStatement)
 		.await
 		.unwrap();
+	let _: QueryResponse =
+		DB.query("SELECT * FROM user").timeout(Duration::from_secs(5)).await.unwrap();
 
 	// create
 	let _: Vec<User> = DB.create(USER).await.unwrap();
 	let _: Option<User> = DB.create((USER, "john")).await.unwrap();
 	let _: Vec<User> = DB.create(USER).content(User::default()).await.unwrap();
 	let _: Option<User> = DB.create((USER, "john")).content(User::default()).await.unwrap();
+	let _: Vec<User> = DB.create(USER).timeout(Duration::from_secs(5)).await.unwrap();
+	let _: Option<User> = DB
+		.create((USER, "john"))
+		.timeout(Duration::from_secs(5))
+		.content(User::default())
+		.await
+		.unwrap();
 
 	// select
 	let _: Vec<User> = DB.select(USER).await.unwrap();
@@ -129,6 +155,15 @@ async fn api() {
 	let _: Vec<User> = DB.select(USER).range("jane"..="john").await.unwrap();
 	let _: Vec<User> =
 		DB.select(USER).range((Bound::Excluded("jane"), Bound::Included("john"))).await.unwrap();
+	let _: Vec<User> = DB.select(USER).timeout(Duration::from_secs(5)).await.unwrap();
+	let _: Option<User> = DB.select((USER, "john")).timeout(Duration::from_secs(5)).await.unwrap();
+	let _: Vec<User> = DB
+		.select(USER)
+		.filter(field("age").gt(18).and(field("name").ne("john")))
+		.order_by("name")
+		.limit(10)
+		.await
+		.unwrap();
 
 	// update
 	let _: Vec<User> = DB.update(USER).await.unwrap();
@@ -164,6 +199,190 @@ async fn api() {
 	let _: Version = DB.version().await.unwrap();
 }
 
+#[derive(Default)]
+struct Counter {
+	before: Arc<AtomicUsize>,
+	after: Arc<AtomicUsize>,
+}
+
+impl Interceptor for Counter {
+	fn before<'a>(
+		&'a self,
+		request: &'a mut Request,
+	) -> Pin<Box<dyn Future<Output = crate::api::Result<()>> + Send + Sync + 'a>> {
+		Box::pin(async move {
+			self.before.fetch_add(1, Ordering::SeqCst);
+			match request.method() {
+				"health" => {
+					Err(crate::api::err::Error::InternalError("rejected".to_owned()).into())
+				}
+				_ => Ok(()),
+			}
+		})
+	}
+
+	fn after<'a>(
+		&'a self,
+		reply: &'a Reply<'a>,
+	) -> Pin<Box<dyn Future<Output = ()> + Send + Sync + 'a>> {
+		Box::pin(async move {
+			assert!(reply.error().is_none());
+			self.after.fetch_add(1, Ordering::SeqCst);
+		})
+	}
+}
+
+#[tokio::test]
+async fn interceptors() {
+	let db = Surreal::new::<Test>(()).await.unwrap();
+	let counter = Counter::default();
+	let (before, after) = (counter.before.clone(), counter.after.clone());
+	db.intercept(counter).unwrap();
+	// Requests are sent through the interceptor
+	let _: Version = db.version().await.unwrap();
+	let _: Vec<User> = db.select(USER).await.unwrap();
+	assert_eq!(before.load(Ordering::SeqCst), 2);
+	assert_eq!(after.load(Ordering::SeqCst), 2);
+	// Rejected requests are not sent
+	db.health().await.unwrap_err();
+	assert_eq!(before.load(Ordering::SeqCst), 3);
+	assert_eq!(after.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn transactions() {
+	let db = Surreal::new::<Test>(()).await.unwrap();
+	let _: QueryResponse = db
+		.begin()
+		.await
+		.unwrap()
+		.query("CREATE user SET name = $name")
+		.query("UPDATE user SET active = true")
+		.bind(("name", "John Doe"))
+		.commit()
+		.await
+		.unwrap();
+	let _: Surreal<Client> = db.begin().await.unwrap().query("CREATE user").cancel().await.unwrap();
+	// Transactions cannot be nested
+	db.begin().await.unwrap().query("BEGIN; CREATE user; COMMIT").commit().await.unwrap_err();
+}
+
+#[tokio::test]
+async fn insert_many() {
+	let db = Surreal::new::<Test>(()).await.unwrap();
+	let users = vec![User::default(), User::default(), User::default()];
+	// Every record has its own outcome, even when split across batches
+	let outcomes: Vec<crate::api::Result<User>> =
+		db.insert(USER).content_many(users).batch_size(2).await.unwrap();
+	assert_eq!(outcomes.len(), 3);
+}
+
+#[tokio::test]
+async fn streams() {
+	let db = Surreal::new::<Test>(()).await.unwrap();
+	// An empty batch ends the stream
+	let users: Vec<User> = db.select_stream(USER).batch_size(10).try_collect().await.unwrap();
+	assert!(users.is_empty());
+	let users: Vec<User> = db
+		.query_stream("SELECT * FROM user WHERE age > $age")
+		.bind(("age", 18))
+		.try_collect()
+		.await
+		.unwrap();
+	assert!(users.is_empty());
+	// Only a single select statement can be streamed
+	let mut users = db.query_stream::<User>("SELECT * FROM user; SELECT * FROM post");
+	users.try_next().await.unwrap_err();
+	let mut users = db.query_stream::<User>("SELECT * FROM user LIMIT $limit");
+	users.try_next().await.unwrap_err();
+}
+
+#[derive(Default)]
+struct Requests(Arc<AtomicUsize>);
+
+impl Metrics for Requests {
+	fn request(&self, reply: &Reply<'_>) {
+		assert!(reply.error().is_none());
+		self.0.fetch_add(1, Ordering::SeqCst);
+	}
+}
+
+#[tokio::test]
+async fn metrics() {
+	let requests = Requests::default();
+	let count = requests.0.clone();
+	let db = Surreal::new::<Test>(Config::default().metrics(requests)).await.unwrap();
+	// The version is checked when connecting
+	assert_eq!(count.load(Ordering::SeqCst), 1);
+	let _: Vec<User> = db.select(USER).await.unwrap();
+	assert_eq!(count.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn lazy_connection() {
+	let db = Surreal::<Client>::init();
+	assert_eq!(db.status(), Status::Uninitialised);
+	let db = Surreal::new::<Test>(()).lazy().await.unwrap();
+	assert_eq!(db.status(), Status::Idle);
+	db.health().await.unwrap();
+	assert_eq!(db.status(), Status::Connected);
+	let _: Vec<User> = db.select(USER).await.unwrap();
+}
+
+#[tokio::test]
+async fn session_state() {
+	let db = Surreal::new::<Test>(()).await.unwrap();
+	db.use_ns("test-ns").use_db("test-db").await.unwrap();
+	db.set("name", "Tobie").await.unwrap();
+	let _: Jwt = db
+		.signin(Database {
+			namespace: "test-ns",
+			database: "test-db",
+			username: "user",
+			password: "pass",
+		})
+		.await
+		.unwrap();
+	let state = db.session_state().unwrap();
+	assert_eq!(state.ns.as_deref(), Some("test-ns"));
+	assert_eq!(state.db.as_deref(), Some("test-db"));
+	assert_eq!(state.token.as_ref().map(Jwt::as_insecure_token), Some("jwt"));
+	assert!(state.vars.contains_key("name"));
+	// Restore the session on another client
+	let other = Surreal::new::<Test>(()).await.unwrap();
+	other.restore_session(state).await.unwrap();
+	let state = other.session_state().unwrap();
+	assert_eq!(state.ns.as_deref(), Some("test-ns"));
+	assert_eq!(state.token.map(Jwt::into_insecure_token).as_deref(), Some("jwt"));
+	db.invalidate().await.unwrap();
+	assert!(db.session_state().unwrap().token.is_none());
+}
+
+#[tokio::test]
+async fn token_refresh() {
+	fn token(exp: i64) -> String {
+		let payload = URL_SAFE_NO_PAD.encode(format!(r#"{{"exp":{exp}}}"#));
+		format!("header.{payload}.signature")
+	}
+	let refreshes = Arc::new(AtomicUsize::new(0));
+	let refresh = TokenRefresh::with({
+		let refreshes = refreshes.clone();
+		move || {
+			refreshes.fetch_add(1, Ordering::SeqCst);
+			async { Ok(Jwt::from(token(chrono::Utc::now().timestamp() + 3600))) }
+		}
+	});
+	let db = Surreal::new::<Test>(Config::default().token_refresh(refresh)).await.unwrap();
+	// The token expires within the default margin, so it is refreshed before the next request
+	db.authenticate(token(chrono::Utc::now().timestamp() + 10)).await.unwrap();
+	assert_eq!(refreshes.load(Ordering::SeqCst), 0);
+	let _: Vec<User> = db.select(USER).await.unwrap();
+	assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+	// The new token is still valid
+	let _: Vec<User> = db.select(USER).await.unwrap();
+	assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+}
+
 fn send_and_sync(_: impl Send + Sync) {}
 
 #[test]
@@ -5,6 +5,7 @@ use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Route;
 use crate::api::conn::Router;
+use crate::api::opt::Config;
 use crate::api::opt::Endpoint;
 use crate::api::opt::IntoEndpoint;
 use crate::api::Connect;
@@ -36,6 +37,17 @@ impl IntoEndpoint<Test> for () {
 	}
 }
 
+impl IntoEndpoint<Test> for Config {
+	type Client = Client;
+
+	fn into_endpoint(self) -> Result<Endpoint> {
+		Ok(Endpoint {
+			endpoint: Url::parse("test://")?,
+			config: self,
+		})
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct Client {
 	method: Method,
@@ -50,6 +62,7 @@ impl Surreal<Client> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			lazy: false,
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -78,6 +91,11 @@ impl Connection for Client {
 				conn: PhantomData,
 				sender: route_tx,
 				last_id: AtomicI64::new(0),
+				interceptors: Default::default(),
+				retry: None,
+				session: Default::default(),
+				handle: None,
+				state: Default::default(),
 			};
 			server::mock(route_rx);
 			Ok(Surreal {
@@ -97,13 +115,7 @@ impl Connection for Client {
 				request: (0, self.method, param),
 				response: sender,
 			};
-			router
-				.sender
-				.send_async(Some(route))
-				.await
-				.as_ref()
-				.map_err(ToString::to_string)
-				.unwrap();
+			router.send(route).await.unwrap();
 			Ok(receiver)
 		})
 	}
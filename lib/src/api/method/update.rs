@@ -119,6 +119,7 @@ where
 			resource: self.resource,
 			range: self.range,
 			content: data,
+			timeout: None,
 			response_type: PhantomData,
 		}
 	}
@@ -6,6 +6,7 @@ pub mod method;
 pub mod opt;
 
 mod conn;
+mod lazy;
 
 pub use method::query::Response;
 
@@ -38,6 +39,7 @@ pub struct Connect<C: Connection, Response> {
 	router: Arc<OnceLock<Router<C>>>,
 	address: Result<Endpoint>,
 	capacity: usize,
+	lazy: bool,
 	client: PhantomData<C>,
 	response_type: PhantomData<Response>,
 }
@@ -76,6 +78,70 @@ where
 		self.capacity = capacity;
 		self
 	}
+
+	/// Sets the number of connections to open to the server
+	///
+	/// Queries are spread over the connections in the pool, so that
+	/// a single connection does not limit the throughput of highly
+	/// concurrent applications. Each query is sent over the healthy
+	/// connection with the fewest queries awaiting a response, and
+	/// changes to the session, such as signing in or switching the
+	/// namespace and database, are applied to every connection.
+	///
+	/// This is only supported by the WebSocket client. Setting the
+	/// pool size to `0` or `1` (the default) opens one connection.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// use surrealdb::engine::remote::ws::Ws;
+	/// use surrealdb::Surreal;
+	///
+	/// let db = Surreal::new::<Ws>("localhost:8000")
+	///     .with_pool(8)
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_pool(mut self, size: usize) -> Self {
+		if let Ok(address) = &mut self.address {
+			address.config.pool_size = size;
+		}
+		self
+	}
+
+	/// Defers connecting to the database until the first request is sent
+	///
+	/// This allows services to start before the database is reachable.
+	/// Awaiting the connection returns straight away, and the connection
+	/// is established by the first request. When connecting fails, that
+	/// request fails with the error, and the next request tries again.
+	/// Use [`Surreal::status`] to check whether the client is connected.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// use surrealdb::engine::remote::ws::Ws;
+	/// use surrealdb::opt::Status;
+	/// use surrealdb::Surreal;
+	///
+	/// let db = Surreal::new::<Ws>("localhost:8000").lazy().await?;
+	/// assert_eq!(db.status(), Status::Idle);
+	///
+	/// // Connects to the database
+	/// db.health().await?;
+	/// assert_eq!(db.status(), Status::Connected);
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub const fn lazy(mut self) -> Self {
+		self.lazy = true;
+		self
+	}
 }
 
 impl<Client> IntoFuture for Connect<Client, Surreal<Client>>
@@ -87,7 +153,23 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
-			let client = Client::connect(self.address?, self.capacity).await?;
+			let address = self.address?;
+			if self.lazy {
+				let router = lazy::router(address, self.capacity);
+				return Ok(Surreal {
+					router: Arc::new(OnceLock::with_value(router)),
+				});
+			}
+			let metrics = address.config.metrics.clone();
+			let retry = address.config.retry;
+			let refresh = address.config.token_refresh.clone();
+			let mut client = Client::connect(address, self.capacity).await?;
+			// The new connection has no other references yet
+			if let Some(router) = Arc::get_mut(&mut client.router).and_then(OnceLock::get_mut) {
+				router.retry = retry;
+				router.session.refresh = refresh;
+			}
+			metrics.register(&client.router.extract()?.interceptors);
 			client.check_server_version().await?;
 			Ok(client)
 		})
@@ -107,9 +189,21 @@ where
 			if self.router.get().is_some() {
 				return Err(Error::AlreadyConnected.into());
 			}
-			let arc = Client::connect(self.address?, self.capacity).await?.router;
+			let address = self.address?;
+			if self.lazy {
+				let router = lazy::router(address, self.capacity);
+				self.router.set(router).map_err(|_| Error::AlreadyConnected)?;
+				return Ok(());
+			}
+			let metrics = address.config.metrics.clone();
+			let retry = address.config.retry;
+			let refresh = address.config.token_refresh.clone();
+			let arc = Client::connect(address, self.capacity).await?.router;
 			let cell = Arc::into_inner(arc).expect("new connection to have no references");
-			let router = cell.into_inner().expect("router to be set");
+			let mut router = cell.into_inner().expect("router to be set");
+			router.retry = retry;
+			router.session.refresh = refresh;
+			metrics.register(&router.interceptors);
 			self.router.set(router).map_err(|_| Error::AlreadyConnected)?;
 			let client = Surreal {
 				router: self.router,
@@ -1,8 +1,14 @@
+use super::{Metrics, MetricsHook, RetryPolicy, TokenRefresh};
 use crate::{dbs::Capabilities, iam::Level};
+#[cfg(any(feature = "kv-indxdb", feature = "kv-rocksdb", feature = "kv-speedb"))]
+use std::fmt;
+#[cfg(all(feature = "kv-mem", not(target_arch = "wasm32")))]
+use std::path::PathBuf;
 use std::time::Duration;
+use url::Url;
 
 /// Configuration for server connection, including: strictness, notifications, query_timeout, transaction_timeout
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Config {
 	pub(crate) strict: bool,
 	pub(crate) notifications: bool,
@@ -10,6 +16,8 @@ pub struct Config {
 	pub(crate) transaction_timeout: Option<Duration>,
 	#[cfg(any(feature = "native-tls", feature = "rustls"))]
 	pub(crate) tls_config: Option<super::Tls>,
+	#[cfg(any(feature = "native-tls", feature = "rustls"))]
+	pub(crate) tls_options: super::TlsOptions,
 	// Only used by the local engines
 	// `Level::No` in this context means no authentication information was configured
 	pub(crate) auth: Level,
@@ -17,6 +25,52 @@ pub struct Config {
 	pub(crate) password: String,
 	pub(crate) tick_interval: Option<Duration>,
 	pub(crate) capabilities: Capabilities,
+	pub(crate) metrics: MetricsHook,
+	pub(crate) retry: Option<RetryPolicy>,
+	pub(crate) token_refresh: Option<TokenRefresh>,
+	// Only used by the WebSocket engine
+	pub(crate) pool_size: usize,
+	// Only used by the dynamic engine, when connecting to a list of endpoints
+	pub(crate) failover: Vec<Url>,
+	pub(crate) round_robin: bool,
+	pub(crate) read_replicas: bool,
+	pub(crate) replica_lag: Option<Duration>,
+	// Only used by the HTTP engine
+	pub(crate) compression: bool,
+	// Only used by the in-memory engine
+	#[cfg(all(feature = "kv-mem", not(target_arch = "wasm32")))]
+	pub(crate) snapshot: Option<PathBuf>,
+	#[cfg(all(feature = "kv-mem", not(target_arch = "wasm32")))]
+	pub(crate) snapshot_interval: Option<Duration>,
+	// Only used by the IndexedDB engine
+	#[cfg(feature = "kv-indxdb")]
+	pub(crate) store_name: Option<String>,
+	// Only used by the IndexedDB, RocksDB, and SpeeDB engines
+	#[cfg(any(feature = "kv-indxdb", feature = "kv-rocksdb", feature = "kv-speedb"))]
+	pub(crate) encryption_key: Option<EncryptionKey>,
+	#[cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+	pub(crate) previous_encryption_keys: Vec<EncryptionKey>,
+	// Only used by the TiKV engine
+	#[cfg(feature = "kv-tikv")]
+	pub(crate) tikv: crate::kvs::TikvOptions,
+	// Only used by the FoundationDB engine
+	#[cfg(feature = "kv-fdb")]
+	pub(crate) fdb: crate::kvs::FdbOptions,
+	// Only used by the RocksDB engine
+	#[cfg(feature = "replication")]
+	pub(crate) replication: Option<crate::kvs::ReplicationOptions>,
+}
+
+/// A key used to encrypt persisted data, which is never printed
+#[cfg(any(feature = "kv-indxdb", feature = "kv-rocksdb", feature = "kv-speedb"))]
+#[derive(Clone)]
+pub(crate) struct EncryptionKey(pub(crate) [u8; 32]);
+
+#[cfg(any(feature = "kv-indxdb", feature = "kv-rocksdb", feature = "kv-speedb"))]
+impl fmt::Debug for EncryptionKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("EncryptionKey(..)")
+	}
 }
 
 impl Config {
@@ -32,6 +86,11 @@ impl Config {
 	}
 
 	/// Enables `strict` server mode
+	///
+	/// This is used by the local engines, where namespaces, databases and tables then have
+	/// to be defined before they are used. Handles opened with
+	/// [`Surreal::isolated`](crate::Surreal::isolated) can override the mode of the
+	/// datastore with [`Isolation::strict`](super::Isolation::strict).
 	pub fn strict(mut self) -> Self {
 		self.strict = true;
 		self
@@ -85,6 +144,77 @@ impl Config {
 		self
 	}
 
+	/// Trust a PEM encoded root certificate, in addition to the system trust roots
+	///
+	/// This can be called more than once to trust several certificates. It is
+	/// ignored when a TLS configuration is supplied with [`Config::rustls`] or
+	/// [`Config::native_tls`].
+	#[cfg(any(feature = "native-tls", feature = "rustls"))]
+	#[cfg_attr(docsrs, doc(cfg(any(feature = "native-tls", feature = "rustls"))))]
+	pub fn root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+		self.tls_options.root_certificates.push(pem.into());
+		self
+	}
+
+	/// Present a PEM encoded client certificate, and its PKCS #8 private key, to the server
+	///
+	/// This is used to authenticate with servers which require mutual TLS. It is
+	/// ignored when a TLS configuration is supplied with [`Config::rustls`] or
+	/// [`Config::native_tls`].
+	#[cfg(any(feature = "native-tls", feature = "rustls"))]
+	#[cfg_attr(docsrs, doc(cfg(any(feature = "native-tls", feature = "rustls"))))]
+	pub fn client_certificate(
+		mut self,
+		certificate: impl Into<Vec<u8>>,
+		private_key: impl Into<Vec<u8>>,
+	) -> Self {
+		self.tls_options.client_certificate = Some((certificate.into(), private_key.into()));
+		self
+	}
+
+	/// Verify the server certificate against this name, instead of the host in the address
+	///
+	/// The name is also sent to the server using SNI. This allows connecting to a
+	/// server by its IP address, or through a tunnel, while still verifying it.
+	#[cfg(any(feature = "native-tls", feature = "rustls"))]
+	#[cfg_attr(docsrs, doc(cfg(any(feature = "native-tls", feature = "rustls"))))]
+	pub fn server_name(mut self, name: impl Into<String>) -> Self {
+		self.tls_options.server_name = Some(name.into());
+		self
+	}
+
+	/// Set whether the server may compress its responses to the HTTP engine
+	pub fn set_compression(mut self, compression: bool) -> Self {
+		self.compression = compression;
+		self
+	}
+
+	/// Ask the server to compress its responses, using gzip or zstd
+	///
+	/// This is used by the HTTP engine, to reduce the size of large query results.
+	pub fn compression(mut self) -> Self {
+		self.compression = true;
+		self
+	}
+
+	/// Report metrics about each request, such as its latency, payload size, and retries
+	pub fn metrics(mut self, metrics: impl Metrics) -> Self {
+		self.metrics = MetricsHook::new(metrics);
+		self
+	}
+
+	/// Retry requests which fail because the connection to the server was lost
+	pub fn retry(mut self, policy: RetryPolicy) -> Self {
+		self.retry = Some(policy);
+		self
+	}
+
+	/// Refresh the token of the session before it expires
+	pub fn token_refresh(mut self, refresh: TokenRefresh) -> Self {
+		self.token_refresh = Some(refresh);
+		self
+	}
+
 	/// Set the interval at which the database should run node maintenance tasks
 	pub fn tick_interval(mut self, interval: impl Into<Option<Duration>>) -> Self {
 		self.tick_interval = interval.into().filter(|x| !x.is_zero());
@@ -92,8 +222,225 @@ impl Config {
 	}
 
 	/// Set the capabilities for the database
+	///
+	/// This is used by the local engines, where the capabilities apply to every session of
+	/// the connection, such as which functions can be called, which network targets can be
+	/// reached, and whether scripting is allowed. Remote servers use their own capabilities.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::dbs::capabilities::Capabilities;
+	/// use surrealdb::dbs::capabilities::Targets;
+	/// use surrealdb::engine::local::Mem;
+	/// use surrealdb::opt::Config;
+	/// use surrealdb::Surreal;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let capabilities = Capabilities::default()
+	///     .with_scripting(true)
+	///     .with_network_targets(Targets::Some(["example.com".parse().unwrap()].into()))
+	///     .without_functions(Targets::Some(["http".parse().unwrap()].into()));
+	/// let db = Surreal::new::<Mem>(Config::new().capabilities(capabilities)).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
 	pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
 		self.capabilities = capabilities;
 		self
 	}
+
+	/// Persist the in-memory engine to a snapshot file
+	///
+	/// The snapshot is loaded when the datastore starts, if it exists, and saved again
+	/// when the datastore shuts down, as well as periodically when an interval is set
+	/// with [`Config::snapshot_interval`]. This keeps the speed of the in-memory engine,
+	/// while allowing it to start again with its data after a restart.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use std::time::Duration;
+	/// use surrealdb::engine::local::Mem;
+	/// use surrealdb::opt::Config;
+	/// use surrealdb::Surreal;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let config = Config::new()
+	///     .snapshot("data.snapshot")
+	///     .snapshot_interval(Duration::from_secs(60));
+	/// let db = Surreal::new::<Mem>(config).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(all(feature = "kv-mem", not(target_arch = "wasm32")))]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-mem")))]
+	pub fn snapshot(mut self, path: impl Into<PathBuf>) -> Self {
+		self.snapshot = Some(path.into());
+		self
+	}
+
+	/// Set the interval at which the in-memory engine saves its snapshot
+	///
+	/// Without an interval, the snapshot is only saved when the datastore shuts down.
+	#[cfg(all(feature = "kv-mem", not(target_arch = "wasm32")))]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-mem")))]
+	pub fn snapshot_interval(mut self, interval: impl Into<Option<Duration>>) -> Self {
+		self.snapshot_interval = interval.into().filter(|x| !x.is_zero());
+		self
+	}
+
+	/// Set the store, within the IndexedDB database, in which the data is persisted
+	#[cfg(feature = "kv-indxdb")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-indxdb")))]
+	pub fn store_name(mut self, name: impl Into<String>) -> Self {
+		self.store_name = Some(name.into());
+		self
+	}
+
+	/// Encrypt the data persisted by the IndexedDB, RocksDB, or SpeeDB engines with a 256-bit key
+	///
+	/// Values are encrypted using ChaCha20-Poly1305 in the browser, and AES-256-GCM on disk.
+	#[cfg(any(feature = "kv-indxdb", feature = "kv-rocksdb", feature = "kv-speedb"))]
+	#[cfg_attr(
+		docsrs,
+		doc(cfg(any(feature = "kv-indxdb", feature = "kv-rocksdb", feature = "kv-speedb")))
+	)]
+	pub fn encryption_key(mut self, key: [u8; 32]) -> Self {
+		self.encryption_key = Some(EncryptionKey(key));
+		self
+	}
+
+	/// Allow data encrypted with a previous key to be read by the RocksDB or SpeeDB engines
+	///
+	/// When rotating the encryption key, set the new key with [`Config::encryption_key`], and
+	/// the old key with this method. Values are encrypted with the new key when next written.
+	#[cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+	#[cfg_attr(docsrs, doc(cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))))]
+	pub fn previous_encryption_key(mut self, key: [u8; 32]) -> Self {
+		self.previous_encryption_keys.push(EncryptionKey(key));
+		self
+	}
+
+	/// Connect to a TiKV cluster using mutual TLS
+	///
+	/// The paths point to the PEM encoded CA certificate which signed the certificates
+	/// of the cluster, and to the PEM encoded certificate and private key of the client.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::engine::local::TiKv;
+	/// use surrealdb::opt::Config;
+	/// use surrealdb::Surreal;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let config = Config::new().tikv_tls("ca.pem", "client.pem", "client-key.pem");
+	/// let db = Surreal::new::<TiKv>(("localhost:2379", config)).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "kv-tikv")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-tikv")))]
+	pub fn tikv_tls(
+		mut self,
+		ca_path: impl Into<std::path::PathBuf>,
+		cert_path: impl Into<std::path::PathBuf>,
+		key_path: impl Into<std::path::PathBuf>,
+	) -> Self {
+		self.tikv = self.tikv.with_tls(ca_path, cert_path, key_path);
+		self
+	}
+
+	/// Set how long requests to the TiKV placement driver and nodes may take
+	#[cfg(feature = "kv-tikv")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-tikv")))]
+	pub fn tikv_timeout(mut self, timeout: Duration) -> Self {
+		self.tikv = self.tikv.with_timeout(timeout);
+		self
+	}
+
+	/// Set how TiKV requests are retried when the cached location of a region is out of date
+	///
+	/// After a region is split, merged, or moved, its location is fetched again from the
+	/// placement driver, and the request is retried up to `attempts` times, waiting from
+	/// `base` up to `max` between attempts.
+	#[cfg(feature = "kv-tikv")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-tikv")))]
+	pub fn tikv_region_backoff(mut self, base: Duration, max: Duration, attempts: u32) -> Self {
+		self.tikv = self.tikv.with_region_backoff(base, max, attempts);
+		self
+	}
+
+	/// Set how long a FoundationDB transaction may run before it is cancelled
+	#[cfg(feature = "kv-fdb")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-fdb")))]
+	pub fn fdb_timeout(mut self, timeout: Duration) -> Self {
+		self.fdb = self.fdb.with_timeout(timeout);
+		self
+	}
+
+	/// Set how many times a statement is retried, when its FoundationDB transaction conflicts
+	#[cfg(feature = "kv-fdb")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-fdb")))]
+	pub fn fdb_retry_limit(mut self, limit: u32) -> Self {
+		self.fdb = self.fdb.with_retry_limit(limit);
+		self
+	}
+
+	/// Set the datacenter in which this node runs, so that FoundationDB prefers local storage servers
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use std::time::Duration;
+	/// use surrealdb::engine::local::FDb;
+	/// use surrealdb::opt::Config;
+	/// use surrealdb::Surreal;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let config = Config::new().fdb_datacenter_id("dc1").fdb_timeout(Duration::from_secs(5));
+	/// let db = Surreal::new::<FDb>(("fdb.cluster", config)).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "kv-fdb")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "kv-fdb")))]
+	pub fn fdb_datacenter_id(mut self, id: impl Into<String>) -> Self {
+		self.fdb = self.fdb.with_datacenter_id(id);
+		self
+	}
+
+	/// Replicate the writes made to a RocksDB datastore to the other nodes of a replication group
+	///
+	/// Only the leader of the group accepts writes, while every node can serve reads.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::engine::local::RocksDb;
+	/// use surrealdb::kvs::ReplicationOptions;
+	/// use surrealdb::opt::Config;
+	/// use surrealdb::Surreal;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let options = ReplicationOptions::new(1, "10.0.0.1:7000".parse().unwrap(), "/tmp/raft")
+	///     .with_peer(2, "10.0.0.2:7000".parse().unwrap())
+	///     .with_peer(3, "10.0.0.3:7000".parse().unwrap());
+	/// let config = Config::new().replication(options);
+	/// let db = Surreal::new::<RocksDb>(("/tmp/data", config)).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "replication")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "replication")))]
+	pub fn replication(mut self, options: crate::kvs::ReplicationOptions) -> Self {
+		self.replication = Some(options);
+		self
+	}
 }
@@ -0,0 +1,62 @@
+use crate::api::engine::remote::grpc::Client;
+use crate::api::engine::remote::grpc::Grpc;
+use crate::api::engine::remote::grpc::Grpcs;
+use crate::api::err::Error;
+use crate::api::opt::IntoEndpoint;
+use crate::api::Endpoint;
+use crate::api::Result;
+use crate::opt::Config;
+use std::net::SocketAddr;
+use url::Url;
+
+macro_rules! endpoints {
+	($($name:ty),*) => {
+		$(
+			impl IntoEndpoint<Grpc> for $name {
+				type Client = Client;
+
+				fn into_endpoint(self) -> Result<Endpoint> {
+					let url = format!("grpc://{self}");
+					Ok(Endpoint {
+						endpoint: Url::parse(&url).map_err(|_| Error::InvalidUrl(url))?,
+						config: Default::default(),
+					})
+				}
+			}
+
+			impl IntoEndpoint<Grpc> for ($name, Config) {
+				type Client = Client;
+
+				fn into_endpoint(self) -> Result<Endpoint> {
+					let mut endpoint = IntoEndpoint::<Grpc>::into_endpoint(self.0)?;
+					endpoint.config = self.1;
+					Ok(endpoint)
+				}
+			}
+
+			impl IntoEndpoint<Grpcs> for $name {
+				type Client = Client;
+
+				fn into_endpoint(self) -> Result<Endpoint> {
+					let url = format!("grpcs://{self}");
+					Ok(Endpoint {
+						endpoint: Url::parse(&url).map_err(|_| Error::InvalidUrl(url))?,
+						config: Default::default(),
+					})
+				}
+			}
+
+			impl IntoEndpoint<Grpcs> for ($name, Config) {
+				type Client = Client;
+
+				fn into_endpoint(self) -> Result<Endpoint> {
+					let mut endpoint = IntoEndpoint::<Grpcs>::into_endpoint(self.0)?;
+					endpoint.config = self.1;
+					Ok(endpoint)
+				}
+			}
+		)*
+	}
+}
+
+endpoints!(&str, &String, String, SocketAddr);
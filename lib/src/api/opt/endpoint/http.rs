@@ -16,7 +16,7 @@ macro_rules! endpoints {
 				type Client = Client;
 
 				fn into_endpoint(self) -> Result<Endpoint> {
-					let url = format!("http://{self}");
+					let url = super::remote_url("http", self);
 					Ok(Endpoint {
 						endpoint: Url::parse(&url).map_err(|_| Error::InvalidUrl(url))?,
 						config: Default::default(),
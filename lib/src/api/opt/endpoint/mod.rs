@@ -1,3 +1,5 @@
+#[cfg(all(feature = "protocol-grpc", not(target_arch = "wasm32")))]
+mod grpc;
 #[cfg(feature = "protocol-http")]
 mod http;
 #[cfg(feature = "protocol-ws")]
@@ -9,6 +11,10 @@ mod fdb;
 mod indxdb;
 #[cfg(feature = "kv-mem")]
 mod mem;
+#[cfg(feature = "kv-object")]
+mod object;
+#[cfg(feature = "kv-redb")]
+mod redb;
 #[cfg(feature = "kv-rocksdb")]
 mod rocksdb;
 #[cfg(feature = "kv-speedb")]
@@ -23,7 +29,7 @@ use url::Url;
 use super::Config;
 
 /// A server address used to connect to the server
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[allow(dead_code)] // used by the embedded and remote connections
 pub struct Endpoint {
 	pub(crate) endpoint: Url,
@@ -38,7 +44,19 @@ pub trait IntoEndpoint<Scheme> {
 	fn into_endpoint(self) -> Result<Endpoint>;
 }
 
-#[cfg(any(feature = "kv-fdb", feature = "kv-rocksdb", feature = "kv-speedb"))]
+#[cfg(any(feature = "kv-fdb", feature = "kv-rocksdb", feature = "kv-speedb", feature = "kv-redb"))]
 fn make_url(scheme: &str, path: impl AsRef<std::path::Path>) -> String {
 	format!("{scheme}://{}", path.as_ref().display())
 }
+
+/// Prefixes a server address with the scheme. The address of a Unix domain socket,
+/// such as `unix:///var/run/surreal.sock`, becomes `ws+unix:///var/run/surreal.sock`
+/// for the `ws` scheme, so that the connection is made over the socket instead.
+#[cfg(any(feature = "protocol-http", feature = "protocol-ws"))]
+fn remote_url(scheme: &str, address: impl std::fmt::Display) -> String {
+	let address = address.to_string();
+	match address.strip_prefix("unix:") {
+		Some(path) => format!("{scheme}+unix:{path}"),
+		None => format!("{scheme}://{address}"),
+	}
+}
@@ -0,0 +1,40 @@
+use crate::api::engine::local::Db;
+use crate::api::engine::local::Gcs;
+use crate::api::engine::local::S3;
+use crate::api::err::Error;
+use crate::api::opt::Config;
+use crate::api::opt::Endpoint;
+use crate::api::opt::IntoEndpoint;
+use crate::api::Result;
+use url::Url;
+
+macro_rules! endpoints {
+	($scheme:ty, $prefix:literal, $($name:ty),*) => {
+		$(
+			impl IntoEndpoint<$scheme> for $name {
+				type Client = Db;
+
+				fn into_endpoint(self) -> Result<Endpoint> {
+					let url = format!(concat!($prefix, "://{}"), self);
+					Ok(Endpoint {
+						endpoint: Url::parse(&url).map_err(|_| Error::InvalidUrl(url))?,
+						config: Default::default(),
+					})
+				}
+			}
+
+			impl IntoEndpoint<$scheme> for ($name, Config) {
+				type Client = Db;
+
+				fn into_endpoint(self) -> Result<Endpoint> {
+					let mut endpoint = IntoEndpoint::<$scheme>::into_endpoint(self.0)?;
+					endpoint.config = self.1;
+					Ok(endpoint)
+				}
+			}
+		)*
+	}
+}
+
+endpoints!(S3, "s3", &str, &String, String);
+endpoints!(Gcs, "gcs", &str, &String, String);
@@ -16,7 +16,7 @@ macro_rules! endpoints {
 				type Client = Client;
 
 				fn into_endpoint(self) -> Result<Endpoint> {
-					let url = format!("ws://{self}");
+					let url = super::remote_url("ws", self);
 					Ok(Endpoint {
 						endpoint: Url::parse(&url).map_err(|_| Error::InvalidUrl(url))?,
 						config: Default::default(),
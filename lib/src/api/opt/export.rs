@@ -1,3 +1,5 @@
+pub use crate::kvs::ExportConfig;
+pub use crate::kvs::ExportFormat;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -0,0 +1,124 @@
+use crate::sql::Expression;
+use crate::sql::Idiom;
+use crate::sql::Operator;
+use crate::sql::Part;
+use crate::sql::Subquery;
+use crate::sql::Value;
+
+/// A field of a record, used to filter the records selected
+///
+/// Nested fields are separated by dots, such as `address.city`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use surrealdb::opt::field;
+///
+/// # #[derive(serde::Deserialize)]
+/// # struct Person;
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// # let db = surrealdb::engine::any::connect("mem://").await?;
+/// let adults: Vec<Person> = db
+///     .select("person")
+///     .filter(field("age").gte(18).and(field("address.country").eq("GB")))
+///     .order_by("name")
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn field(name: &str) -> Field {
+	Field(Idiom(name.split('.').map(|v| Part::Field(v.to_owned().into())).collect()))
+}
+
+/// A field of a record, created with [`field`]
+#[derive(Debug, Clone)]
+pub struct Field(pub(crate) Idiom);
+
+macro_rules! comparisons {
+	($($(#[$doc:meta])* $name:ident => $operator:ident,)*) => {
+		impl Field {
+			$(
+				$(#[$doc])*
+				pub fn $name(self, value: impl Into<Value>) -> Filter {
+					self.compare(Operator::$operator, value.into())
+				}
+			)*
+		}
+	};
+}
+
+comparisons! {
+	/// Matches records where the field is equal to the value
+	eq => Equal,
+	/// Matches records where the field is not equal to the value
+	ne => NotEqual,
+	/// Matches records where the field is greater than the value
+	gt => MoreThan,
+	/// Matches records where the field is greater than or equal to the value
+	gte => MoreThanOrEqual,
+	/// Matches records where the field is less than the value
+	lt => LessThan,
+	/// Matches records where the field is less than or equal to the value
+	lte => LessThanOrEqual,
+	/// Matches records where the field, such as an array, contains the value
+	contains => Contain,
+	/// Matches records where the field is one of the values in an array
+	inside => Inside,
+}
+
+impl Field {
+	fn compare(self, operator: Operator, value: Value) -> Filter {
+		Filter(
+			Expression::Binary {
+				l: Value::Idiom(self.0),
+				o: operator,
+				r: value,
+			}
+			.into(),
+		)
+	}
+}
+
+/// A condition which the records selected must match
+#[derive(Debug, Clone)]
+pub struct Filter(pub(crate) Value);
+
+impl Filter {
+	/// Matches records which match both this condition and the other
+	pub fn and(self, other: Filter) -> Filter {
+		self.join(Operator::And, other)
+	}
+
+	/// Matches records which match either this condition or the other
+	pub fn or(self, other: Filter) -> Filter {
+		self.join(Operator::Or, other)
+	}
+
+	fn join(self, operator: Operator, other: Filter) -> Filter {
+		// Nested conditions are wrapped in parentheses when formatted
+		Filter(
+			Expression::Binary {
+				l: Value::Subquery(Box::new(Subquery::Value(self.0))),
+				o: operator,
+				r: Value::Subquery(Box::new(Subquery::Value(other.0))),
+			}
+			.into(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn format_filter() {
+		let filter =
+			field("age").gte(18).and(field("address.city").eq("London").or(field("vip").eq(true)));
+		assert_eq!(
+			filter.0.to_string(),
+			"(age >= 18) AND ((address.city = 'London') OR (vip = true))"
+		);
+	}
+}
@@ -0,0 +1,143 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::Result;
+use crate::sql::Query;
+use crate::sql::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Hooks which run around every request sent by the client
+///
+/// Interceptors are registered with [`Surreal::intercept`](crate::Surreal::intercept), and
+/// can be used to add headers, record metrics, rewrite queries, or refresh authentication
+/// tokens. The `before` hooks run in the order in which the interceptors were registered,
+/// and the `after` hooks run in the reverse order.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::future::Future;
+/// use std::pin::Pin;
+/// use surrealdb::opt::Interceptor;
+/// use surrealdb::opt::Reply;
+///
+/// struct Timing;
+///
+/// impl Interceptor for Timing {
+///     fn after<'a>(
+///         &'a self,
+///         reply: &'a Reply<'a>,
+///     ) -> Pin<Box<dyn Future<Output = ()> + Send + Sync + 'a>> {
+///         Box::pin(async move {
+///             println!("{} took {:?}", reply.method(), reply.elapsed());
+///         })
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// # let db = surrealdb::Surreal::new::<surrealdb::engine::remote::ws::Ws>("localhost:8000").await?;
+/// db.intercept(Timing)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait Interceptor: Send + Sync + 'static {
+	/// Runs before a request is sent, and can modify the request
+	///
+	/// Returning an error stops the request from being sent, and returns the error to the caller.
+	fn before<'a>(
+		&'a self,
+		request: &'a mut Request,
+	) -> Pin<Box<dyn Future<Output = Result<()>> + Send + Sync + 'a>> {
+		let _ = request;
+		Box::pin(async { Ok(()) })
+	}
+
+	/// Runs once the response to a request has been received
+	fn after<'a>(
+		&'a self,
+		reply: &'a Reply<'a>,
+	) -> Pin<Box<dyn Future<Output = ()> + Send + Sync + 'a>> {
+		let _ = reply;
+		Box::pin(async {})
+	}
+}
+
+/// A request which is about to be sent by the client
+#[derive(Debug)]
+pub struct Request {
+	pub(crate) method: Method,
+	pub(crate) param: Param,
+}
+
+impl Request {
+	/// The name of the method being called, such as `query` or `select`
+	pub fn method(&self) -> &str {
+		self.method.as_str()
+	}
+
+	/// The query, and its bindings, sent by the `query` method
+	pub fn query_mut(&mut self) -> Option<(&mut Query, &mut BTreeMap<String, Value>)> {
+		self.param.query.as_mut().map(|(query, bindings)| (query, bindings))
+	}
+
+	/// The parameters sent by the other methods
+	pub fn params_mut(&mut self) -> &mut Vec<Value> {
+		&mut self.param.other
+	}
+
+	/// Adds a header to the request, which is only sent by the HTTP engine
+	pub fn insert_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+		self.param.headers.push((name.into(), value.into()));
+	}
+}
+
+/// The outcome of a request sent by the client
+#[derive(Debug)]
+pub struct Reply<'a> {
+	pub(crate) method: Method,
+	pub(crate) elapsed: Duration,
+	pub(crate) error: Option<&'a crate::Error>,
+}
+
+impl Reply<'_> {
+	/// The name of the method which was called, such as `query` or `select`
+	pub fn method(&self) -> &str {
+		self.method.as_str()
+	}
+
+	/// How long the request took to complete
+	pub fn elapsed(&self) -> Duration {
+		self.elapsed
+	}
+
+	/// The error returned by the request, if it failed
+	pub fn error(&self) -> Option<&crate::Error> {
+		self.error
+	}
+}
+
+/// The interceptors registered on a client
+#[derive(Default)]
+pub(crate) struct Interceptors(RwLock<Vec<Arc<dyn Interceptor>>>);
+
+impl Interceptors {
+	pub(crate) fn push(&self, interceptor: impl Interceptor) {
+		self.0.write().unwrap_or_else(|e| e.into_inner()).push(Arc::new(interceptor));
+	}
+
+	pub(crate) fn list(&self) -> Vec<Arc<dyn Interceptor>> {
+		self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+	}
+}
+
+impl fmt::Debug for Interceptors {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Interceptors({})", self.list().len())
+	}
+}
@@ -0,0 +1,89 @@
+use crate::dbs::Capabilities;
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+/// The options of a handle which is isolated from the other handles to the same datastore
+///
+/// Passed to [`Surreal::isolated`](crate::Surreal::isolated), so that several tenants can share
+/// an embedded datastore without sharing the namespace, database, authentication and parameters
+/// of their sessions.
+///
+/// # Examples
+///
+/// ```no_run
+/// use surrealdb::engine::local::Mem;
+/// use surrealdb::opt::capabilities::Capabilities;
+/// use surrealdb::opt::Isolation;
+/// use surrealdb::Surreal;
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// let db = Surreal::new::<Mem>(()).await?;
+///
+/// let isolation = Isolation::new()
+///     .namespace("tenant")
+///     .capabilities(Capabilities::default().with_scripting(false));
+/// let tenant = db.isolated(isolation)?;
+/// tenant.use_db("app").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Isolation {
+	pub(crate) ns: Option<String>,
+	pub(crate) capabilities: Option<Capabilities>,
+	pub(crate) strict: Option<bool>,
+}
+
+impl Isolation {
+	/// Creates options for a handle which is not restricted to a namespace
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Restricts the handle to a namespace
+	///
+	/// The session of the handle starts in this namespace, and switching to another
+	/// namespace with `USE` fails.
+	pub fn namespace(mut self, ns: impl Into<String>) -> Self {
+		self.ns = Some(ns.into());
+		self
+	}
+
+	/// Sets the capabilities of the handle, instead of those of the datastore
+	pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+		self.capabilities = Some(capabilities);
+		self
+	}
+
+	/// Sets whether the handle runs in strict mode, instead of using the mode of the datastore
+	///
+	/// In strict mode, namespaces, databases and tables have to be defined before they are used.
+	pub fn strict(mut self, strict: bool) -> Self {
+		self.strict = Some(strict);
+		self
+	}
+}
+
+/// An isolated handle, which the engine keeps a separate session for
+pub(crate) struct Handle {
+	pub(crate) isolation: Isolation,
+	// Keeps the client which the handle was opened from, and so the datastore, open
+	_parent: Arc<dyn Any + Send + Sync>,
+}
+
+impl Handle {
+	pub(crate) fn new(isolation: Isolation, parent: Arc<dyn Any + Send + Sync>) -> Self {
+		Self {
+			isolation,
+			_parent: parent,
+		}
+	}
+}
+
+impl fmt::Debug for Handle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Handle").field("isolation", &self.isolation).finish_non_exhaustive()
+	}
+}
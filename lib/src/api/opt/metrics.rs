@@ -0,0 +1,134 @@
+use crate::api::conn::Method;
+use crate::api::opt::Interceptor;
+use crate::api::opt::Interceptors;
+use crate::api::opt::Reply;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Callbacks which receive metrics about the requests sent by the client
+///
+/// Metrics are registered with [`Config::metrics`](crate::opt::Config::metrics), and can
+/// be used to feed Prometheus, OpenTelemetry, or other monitoring systems. Every callback
+/// has a default implementation which does nothing, so only the metrics of interest need
+/// to be implemented. The callbacks are called from the engine, so they should be quick
+/// and must not block.
+///
+/// The size of each payload is reported by the WebSocket and gRPC engines.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::atomic::AtomicU64;
+/// use std::sync::atomic::Ordering;
+/// use surrealdb::engine::remote::ws::Ws;
+/// use surrealdb::opt::Config;
+/// use surrealdb::opt::Metrics;
+/// use surrealdb::opt::Reply;
+/// use surrealdb::Surreal;
+///
+/// #[derive(Default)]
+/// struct Stats {
+///     requests: AtomicU64,
+///     bytes_sent: AtomicU64,
+/// }
+///
+/// impl Metrics for Stats {
+///     fn request(&self, reply: &Reply<'_>) {
+///         self.requests.fetch_add(1, Ordering::Relaxed);
+///         println!("{} took {:?}", reply.method(), reply.elapsed());
+///     }
+///
+///     fn sent(&self, _method: &str, bytes: usize) {
+///         self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// let config = Config::default().metrics(Stats::default());
+/// let db = Surreal::new::<Ws>(("localhost:8000", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait Metrics: Send + Sync + 'static {
+	/// Called once a request has completed, with how long it took and whether it failed
+	fn request(&self, reply: &Reply<'_>) {
+		let _ = reply;
+	}
+
+	/// Called once a request has been sent to the server, with the size of its payload
+	fn sent(&self, method: &str, bytes: usize) {
+		let _ = (method, bytes);
+	}
+
+	/// Called once a response has been received from the server, with the size of its payload
+	fn received(&self, method: &str, bytes: usize) {
+		let _ = (method, bytes);
+	}
+
+	/// Called each time the client retries connecting to the server, after losing its connection
+	///
+	/// The attempts are counted from one, and are reset once the client has reconnected.
+	fn retry(&self, attempt: u32) {
+		let _ = attempt;
+	}
+}
+
+/// The metrics registered on a connection, if any
+#[derive(Clone, Default)]
+pub(crate) struct MetricsHook(Option<Arc<dyn Metrics>>);
+
+impl MetricsHook {
+	pub(crate) fn new(metrics: impl Metrics) -> Self {
+		Self(Some(Arc::new(metrics)))
+	}
+
+	/// Reports the latency of each request, using an interceptor
+	pub(crate) fn register(&self, interceptors: &Interceptors) {
+		if let Some(metrics) = &self.0 {
+			interceptors.push(Recorder(metrics.clone()));
+		}
+	}
+
+	pub(crate) fn sent(&self, method: Method, bytes: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.sent(method.as_str(), bytes);
+		}
+	}
+
+	pub(crate) fn received(&self, method: Method, bytes: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.received(method.as_str(), bytes);
+		}
+	}
+
+	pub(crate) fn retry(&self, attempt: u32) {
+		if let Some(metrics) = &self.0 {
+			metrics.retry(attempt);
+		}
+	}
+}
+
+impl fmt::Debug for MetricsHook {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.0 {
+			Some(_) => f.write_str("MetricsHook(Some(..))"),
+			None => f.write_str("MetricsHook(None)"),
+		}
+	}
+}
+
+/// Reports the outcome of each request to the metrics
+struct Recorder(Arc<dyn Metrics>);
+
+impl Interceptor for Recorder {
+	fn after<'a>(
+		&'a self,
+		reply: &'a Reply<'a>,
+	) -> Pin<Box<dyn Future<Output = ()> + Send + Sync + 'a>> {
+		self.0.request(reply);
+		Box::pin(async {})
+	}
+}
@@ -6,8 +6,15 @@ pub mod capabilities;
 mod config;
 mod endpoint;
 mod export;
+mod filter;
+mod interceptor;
+mod isolation;
+mod metrics;
 mod query;
 mod resource;
+mod retry;
+mod session;
+mod status;
 mod tls;
 
 use crate::api::err::Error;
@@ -26,8 +33,21 @@ use serde_json::Value as JsonValue;
 pub use config::*;
 pub use endpoint::*;
 pub use export::*;
+pub use filter::*;
+pub use interceptor::*;
+pub(crate) use isolation::Handle;
+pub use isolation::Isolation;
+pub use metrics::Metrics;
+pub(crate) use metrics::MetricsHook;
 pub use query::*;
 pub use resource::*;
+pub use retry::*;
+pub(crate) use session::ClientSession;
+pub use session::SessionState;
+pub(crate) use session::Source as RefreshSource;
+pub use session::TokenRefresh;
+pub(crate) use status::ConnectionState;
+pub use status::Status;
 pub use tls::*;
 
 /// Record ID
@@ -17,6 +17,32 @@ pub trait IntoQuery {
 	fn into_query(self) -> Result<Vec<Statement>>;
 }
 
+/// A query which has been parsed once, and which can be run repeatedly
+///
+/// Values for the parameters in the query are specified each time it is run,
+/// using [`Query::bind`](crate::api::method::Query::bind).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prepared(Vec<Statement>);
+
+impl Prepared {
+	/// Parses a query, so that it can be run repeatedly
+	pub fn new(query: impl IntoQuery) -> Result<Self> {
+		Ok(Self(query.into_query()?))
+	}
+}
+
+impl IntoQuery for Prepared {
+	fn into_query(self) -> Result<Vec<Statement>> {
+		Ok(self.0)
+	}
+}
+
+impl IntoQuery for &Prepared {
+	fn into_query(self) -> Result<Vec<Statement>> {
+		Ok(self.0.clone())
+	}
+}
+
 impl IntoQuery for sql::Query {
 	fn into_query(self) -> Result<Vec<Statement>> {
 		let sql::Query(Statements(statements)) = self;
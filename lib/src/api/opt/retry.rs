@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+/// How requests are retried when the connection to the server is lost
+///
+/// Requests are retried when they fail because the connection was lost, such as when
+/// the server restarts, and the WebSocket or gRPC engine reconnects. Requests which
+/// write to the database are sent with an idempotency key, so that the server returns
+/// the outcome of the first attempt, rather than running a request such as `CREATE`
+/// twice when the response to the first attempt was lost. Exports and imports are
+/// never retried.
+///
+/// The delay before each retry doubles, starting from the backoff, up to the maximum.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use surrealdb::engine::remote::ws::Ws;
+/// use surrealdb::opt::Config;
+/// use surrealdb::opt::RetryPolicy;
+/// use surrealdb::Surreal;
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// let retry = RetryPolicy::new(5).backoff(Duration::from_millis(200));
+/// let config = Config::default().retry(retry);
+/// let db = Surreal::new::<Ws>(("localhost:8000", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+	pub(crate) retries: u32,
+	pub(crate) backoff: Duration,
+	pub(crate) max_backoff: Duration,
+}
+
+impl RetryPolicy {
+	/// Retries each request up to the specified number of times
+	pub fn new(retries: u32) -> Self {
+		Self {
+			retries,
+			backoff: Duration::from_millis(100),
+			max_backoff: Duration::from_secs(5),
+		}
+	}
+
+	/// Sets the delay before the first retry, which defaults to 100 milliseconds
+	pub fn backoff(mut self, backoff: Duration) -> Self {
+		self.backoff = backoff;
+		self
+	}
+
+	/// Sets the maximum delay between retries, which defaults to 5 seconds
+	pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+		self.max_backoff = max_backoff;
+		self
+	}
+
+	/// The delay before the specified retry, counting from one
+	pub(crate) fn delay(&self, retry: u32) -> Duration {
+		let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+		self.backoff.saturating_mul(factor).min(self.max_backoff)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn delay_doubles_up_to_max() {
+		let policy = RetryPolicy::new(10)
+			.backoff(Duration::from_millis(100))
+			.max_backoff(Duration::from_millis(500));
+		assert_eq!(policy.delay(1), Duration::from_millis(100));
+		assert_eq!(policy.delay(2), Duration::from_millis(200));
+		assert_eq!(policy.delay(3), Duration::from_millis(400));
+		assert_eq!(policy.delay(4), Duration::from_millis(500));
+		assert_eq!(policy.delay(40), Duration::from_millis(500));
+	}
+}
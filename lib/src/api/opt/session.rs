@@ -0,0 +1,244 @@
+use crate::api::conn::Method;
+use crate::api::opt::auth::Credentials;
+use crate::api::opt::auth::Jwt;
+use crate::api::opt::auth::Signin;
+use crate::api::Result;
+use crate::sql::to_value;
+use crate::sql::Value;
+use base64_lib::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64_lib::Engine;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The state of a client session, which can be saved and restored later
+///
+/// Returned by [`Surreal::session_state`](crate::Surreal::session_state), and restored with
+/// [`Surreal::restore_session`](crate::Surreal::restore_session), so that a long-lived client
+/// can resume its session after restarting, without signing in again.
+///
+/// ⚠️: The state contains the token of the session, so it should be stored securely.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionState {
+	/// The namespace in use
+	pub ns: Option<String>,
+	/// The database in use
+	pub db: Option<String>,
+	/// The token which the session is authenticated with
+	pub token: Option<Jwt>,
+	/// The parameters set on the session
+	pub vars: BTreeMap<String, Value>,
+}
+
+type RefreshFuture = Pin<Box<dyn Future<Output = Result<Jwt>> + Send + Sync>>;
+
+type Refresher = Arc<dyn Fn() -> RefreshFuture + Send + Sync>;
+
+#[derive(Clone)]
+pub(crate) enum Source {
+	/// Signs in again with the same credentials
+	Signin(Value),
+	/// Calls a closure which returns a new token
+	Callback(Refresher),
+}
+
+/// How the client refreshes the token of its session before it expires
+///
+/// Before sending a request, the client checks the expiry of the token of the session. When it
+/// expires within the margin, which defaults to one minute, the client gets a new token first,
+/// so that long-lived clients do not start failing with expired tokens.
+///
+/// # Examples
+///
+/// ```no_run
+/// use surrealdb::engine::remote::ws::Ws;
+/// use surrealdb::opt::auth::Scope;
+/// use surrealdb::opt::Config;
+/// use surrealdb::opt::TokenRefresh;
+/// use surrealdb::Surreal;
+///
+/// # #[derive(serde::Serialize)]
+/// # struct Credentials { email: String, pass: String }
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// let params = Credentials {
+///     email: "info@surrealdb.com".to_owned(),
+///     pass: "123456".to_owned(),
+/// };
+/// let credentials = || Scope {
+///     namespace: "namespace",
+///     database: "database",
+///     scope: "user_scope",
+///     params: &params,
+/// };
+/// let config = Config::default().token_refresh(TokenRefresh::signin(credentials())?);
+/// let db = Surreal::new::<Ws>(("localhost:8000", config)).await?;
+/// db.signin(credentials()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TokenRefresh {
+	pub(crate) source: Source,
+	pub(crate) margin: Duration,
+}
+
+impl TokenRefresh {
+	/// Refreshes the token by signing in again with the specified credentials
+	pub fn signin(credentials: impl Credentials<Signin, Jwt>) -> Result<Self> {
+		Ok(Self {
+			source: Source::Signin(to_value(credentials)?),
+			margin: Duration::from_secs(60),
+		})
+	}
+
+	/// Refreshes the token by calling a closure, which returns a new token
+	///
+	/// The session is then authenticated with the new token, such as one obtained from a
+	/// refresh token by an authentication service.
+	pub fn with<F, Fut>(refresh: F) -> Self
+	where
+		F: Fn() -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<Jwt>> + Send + Sync + 'static,
+	{
+		Self {
+			source: Source::Callback(Arc::new(move || -> RefreshFuture { Box::pin(refresh()) })),
+			margin: Duration::from_secs(60),
+		}
+	}
+
+	/// Sets how long before the token expires it is refreshed
+	pub fn margin(mut self, margin: Duration) -> Self {
+		self.margin = margin;
+		self
+	}
+}
+
+impl fmt::Debug for TokenRefresh {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TokenRefresh").field("margin", &self.margin).finish_non_exhaustive()
+	}
+}
+
+/// The session of a client, as seen by the requests which it sent
+#[derive(Debug, Default)]
+pub(crate) struct ClientSession {
+	state: Mutex<SessionState>,
+	pub(crate) refresh: Option<TokenRefresh>,
+	// Makes concurrent requests wait for the same refresh, rather than refreshing several times
+	pub(crate) refreshing: futures::lock::Mutex<()>,
+}
+
+impl ClientSession {
+	pub(crate) fn new(refresh: Option<TokenRefresh>) -> Self {
+		Self {
+			refresh,
+			..Default::default()
+		}
+	}
+
+	pub(crate) fn state(&self) -> SessionState {
+		self.state.lock().unwrap_or_else(|e| e.into_inner()).clone()
+	}
+
+	pub(crate) fn set_token(&self, token: Option<Jwt>) {
+		self.state.lock().unwrap_or_else(|e| e.into_inner()).token = token;
+	}
+
+	/// Records a change to the session, once the request which made it has succeeded
+	pub(crate) fn record(&self, method: Method, params: &[Value], response: &Value) {
+		let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+		match (method, params) {
+			(Method::Use, [ns, db]) => {
+				if let Value::Strand(ns) = ns {
+					state.ns = Some(ns.0.clone());
+				}
+				if let Value::Strand(db) = db {
+					state.db = Some(db.0.clone());
+				}
+			}
+			// Signing in as a root user does not return a token
+			(Method::Signin | Method::Signup, _) => {
+				state.token = match response {
+					Value::Strand(token) => Some(Jwt(token.0.clone())),
+					_ => None,
+				};
+			}
+			(Method::Authenticate, [Value::Strand(token)]) => {
+				state.token = Some(Jwt(token.0.clone()));
+			}
+			(Method::Invalidate, _) => {
+				state.token = None;
+			}
+			(Method::Set, [Value::Strand(key), value]) => {
+				state.vars.insert(key.0.clone(), value.clone());
+			}
+			(Method::Unset, [Value::Strand(key)]) => {
+				state.vars.remove(&key.0);
+			}
+			_ => {}
+		}
+	}
+
+	/// Checks whether the token of the session expires within the margin
+	pub(crate) fn expires_within(&self, margin: Duration) -> bool {
+		let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+		match state.token.as_ref().and_then(expiry) {
+			Some(exp) => exp - chrono::Utc::now().timestamp() <= margin.as_secs() as i64,
+			None => false,
+		}
+	}
+}
+
+/// Reads the expiry of a token, without verifying it
+fn expiry(token: &Jwt) -> Option<i64> {
+	let payload = token.0.split('.').nth(1)?;
+	let bytes = URL_SAFE_NO_PAD.decode(payload).ok()?;
+	let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+	claims.get("exp")?.as_i64()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn token(exp: i64) -> Jwt {
+		let payload = URL_SAFE_NO_PAD.encode(format!(r#"{{"exp":{exp}}}"#));
+		Jwt(format!("header.{payload}.signature"))
+	}
+
+	#[test]
+	fn token_expiry() {
+		let session = ClientSession::default();
+		assert!(!session.expires_within(Duration::from_secs(60)));
+		let now = chrono::Utc::now().timestamp();
+		session.set_token(Some(token(now + 30)));
+		assert!(session.expires_within(Duration::from_secs(60)));
+		session.set_token(Some(token(now + 3600)));
+		assert!(!session.expires_within(Duration::from_secs(60)));
+	}
+
+	#[test]
+	fn records_session_changes() {
+		let session = ClientSession::default();
+		session.record(Method::Use, &["ns".into(), "db".into()], &Value::None);
+		session.record(Method::Set, &["name".into(), "Tobie".into()], &Value::None);
+		session.record(Method::Signin, &[Value::None], &"token".into());
+		let state = session.state();
+		assert_eq!(state.ns.as_deref(), Some("ns"));
+		assert_eq!(state.db.as_deref(), Some("db"));
+		assert_eq!(state.vars.get("name"), Some(&Value::from("Tobie")));
+		assert_eq!(state.token.map(Jwt::into_insecure_token).as_deref(), Some("token"));
+		session.record(Method::Invalidate, &[], &Value::None);
+		session.record(Method::Unset, &["name".into()], &Value::None);
+		let state = session.state();
+		assert!(state.token.is_none());
+		assert!(state.vars.is_empty());
+	}
+}
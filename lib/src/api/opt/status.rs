@@ -0,0 +1,106 @@
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+/// The state of the connection to the database
+///
+/// Returned by [`Surreal::status`](crate::Surreal::status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Status {
+	/// The client has not been connected to a database yet
+	Uninitialised,
+	/// The connection is lazy, and will be established by the first request
+	Idle,
+	/// The client is connecting to the database
+	Connecting,
+	/// The client is connected to the database
+	Connected,
+	/// The connection to the database was lost, and the client is reconnecting
+	Reconnecting,
+	/// The connection to the database has been closed
+	Disconnected,
+}
+
+impl Status {
+	const fn to_u8(self) -> u8 {
+		match self {
+			Status::Uninitialised => 0,
+			Status::Idle => 1,
+			Status::Connecting => 2,
+			Status::Connected => 3,
+			Status::Reconnecting => 4,
+			Status::Disconnected => 5,
+		}
+	}
+
+	const fn from_u8(status: u8) -> Self {
+		match status {
+			0 => Status::Uninitialised,
+			1 => Status::Idle,
+			2 => Status::Connecting,
+			3 => Status::Connected,
+			4 => Status::Reconnecting,
+			_ => Status::Disconnected,
+		}
+	}
+}
+
+/// The state of a connection, shared between the client and its engine
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionState(Arc<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+	status: AtomicU8,
+	// The number of connections which are reconnecting, as a pool has several
+	lost: AtomicUsize,
+	// The state of the connection which was established by a lazy connection
+	established: OnceLock<ConnectionState>,
+}
+
+impl ConnectionState {
+	pub(crate) fn new(status: Status) -> Self {
+		Self(Arc::new(Inner {
+			status: AtomicU8::new(status.to_u8()),
+			lost: AtomicUsize::new(0),
+			established: OnceLock::new(),
+		}))
+	}
+
+	pub(crate) fn get(&self) -> Status {
+		if let Some(state) = self.0.established.get() {
+			return state.get();
+		}
+		if self.0.lost.load(Ordering::Relaxed) > 0 {
+			return Status::Reconnecting;
+		}
+		Status::from_u8(self.0.status.load(Ordering::Relaxed))
+	}
+
+	pub(crate) fn set(&self, status: Status) {
+		self.0.status.store(status.to_u8(), Ordering::Relaxed);
+	}
+
+	/// Marks one of the connections as lost, until it is restored
+	pub(crate) fn lost(&self) {
+		self.0.lost.fetch_add(1, Ordering::Relaxed);
+	}
+
+	pub(crate) fn restored(&self) {
+		self.0.lost.fetch_sub(1, Ordering::Relaxed);
+	}
+
+	/// Follows the state of the connection established by a lazy connection
+	pub(crate) fn establish(&self, state: ConnectionState) {
+		let _ = self.0.established.set(state);
+	}
+}
+
+impl Default for ConnectionState {
+	fn default() -> Self {
+		Self::new(Status::Connected)
+	}
+}
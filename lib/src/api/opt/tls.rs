@@ -1,7 +1,14 @@
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use crate::api::err::Error;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use crate::api::Result;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use std::fmt;
+
 /// TLS Configuration
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "native-tls", feature = "rustls"))))]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Tls {
 	/// Native TLS configuration
 	#[cfg(feature = "native-tls")]
@@ -12,3 +19,123 @@ pub enum Tls {
 	#[cfg_attr(docsrs, doc(cfg(feature = "rustls")))]
 	Rust(rustls::ClientConfig),
 }
+
+/// The certificates, and server name, used to secure connections to the server
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+#[derive(Default, Clone)]
+pub(crate) struct TlsOptions {
+	/// PEM encoded root certificates, trusted in addition to the system roots
+	pub(crate) root_certificates: Vec<Vec<u8>>,
+	/// The PEM encoded certificate chain and private key presented to the server
+	pub(crate) client_certificate: Option<(Vec<u8>, Vec<u8>)>,
+	/// The name used to verify the server, instead of the host in the address
+	pub(crate) server_name: Option<String>,
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+impl fmt::Debug for TlsOptions {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TlsOptions")
+			.field("root_certificates", &self.root_certificates.len())
+			.field("client_certificate", &self.client_certificate.is_some())
+			.field("server_name", &self.server_name)
+			.finish()
+	}
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+impl TlsOptions {
+	/// Returns the TLS configuration supplied by the user, or one built from
+	/// the certificates if any were specified. Native TLS is used when both
+	/// TLS implementations are enabled.
+	pub(crate) fn resolve(&self, tls: Option<Tls>) -> Result<Option<Tls>> {
+		if tls.is_some() {
+			return Ok(tls);
+		}
+		if self.root_certificates.is_empty() && self.client_certificate.is_none() {
+			return Ok(None);
+		}
+		#[cfg(feature = "native-tls")]
+		return self.native().map(Some);
+		#[cfg(not(feature = "native-tls"))]
+		return self.rustls().map(Some);
+	}
+
+	/// Builds a native TLS connector from the certificates
+	#[cfg(feature = "native-tls")]
+	fn native(&self) -> Result<Tls> {
+		let mut builder = native_tls::TlsConnector::builder();
+		for pem in &self.root_certificates {
+			builder.add_root_certificate(native_tls::Certificate::from_pem(pem).map_err(invalid)?);
+		}
+		if let Some((cert, key)) = &self.client_certificate {
+			builder.identity(native_tls::Identity::from_pkcs8(cert, key).map_err(invalid)?);
+		}
+		Ok(Tls::Native(builder.build().map_err(invalid)?))
+	}
+
+	/// Builds a Rustls configuration from the certificates
+	#[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+	fn rustls(&self) -> Result<Tls> {
+		use std::sync::Arc;
+		// Trust the bundled web roots, and the custom root certificates
+		let mut roots = rustls::RootCertStore::empty();
+		roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+		for pem in &self.root_certificates {
+			let certs = rustls_pemfile::certs(&mut pem.as_slice())
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(invalid)?;
+			if certs.is_empty() {
+				return Err(Error::Tls("No root certificate found".to_owned()).into());
+			}
+			for cert in certs {
+				roots.add(cert).map_err(invalid)?;
+			}
+		}
+		// Use the same cryptography provider as the HTTP and WebSocket clients
+		let provider = Arc::new(rustls::crypto::ring::default_provider());
+		let builder = rustls::ClientConfig::builder_with_provider(provider)
+			.with_safe_default_protocol_versions()
+			.map_err(invalid)?
+			.with_root_certificates(roots);
+		// Present the client certificate, if specified
+		let config = match &self.client_certificate {
+			Some((cert, key)) => {
+				let certs = rustls_pemfile::certs(&mut cert.as_slice())
+					.collect::<Result<Vec<_>, _>>()
+					.map_err(invalid)?;
+				let key = rustls_pemfile::private_key(&mut key.as_slice())
+					.map_err(invalid)?
+					.ok_or_else(|| Error::Tls("No private key found".to_owned()))?;
+				builder.with_client_auth_cert(certs, key).map_err(invalid)?
+			}
+			None => builder.with_no_client_auth(),
+		};
+		Ok(Tls::Rust(config))
+	}
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+fn invalid(error: impl fmt::Display) -> crate::Error {
+	Error::Tls(error.to_string()).into()
+}
+
+#[cfg(all(test, any(feature = "native-tls", feature = "rustls")))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn no_certificates() {
+		let options = TlsOptions::default();
+		assert!(options.resolve(None).unwrap().is_none());
+	}
+
+	#[test]
+	fn invalid_root_certificate() {
+		let options = TlsOptions {
+			root_certificates: vec![b"invalid".to_vec()],
+			..Default::default()
+		};
+		assert!(options.resolve(None).is_err());
+	}
+}
@@ -0,0 +1,89 @@
+use crate::cf::TableMutations;
+use crate::err::Error;
+use crate::key::change;
+use crate::key::thing;
+use crate::kvs::{Key, Scanner, Transaction};
+use crate::sql::statements::DefineTableStatement;
+use crate::sql::{Datetime, Value};
+use crate::vs;
+use chrono::Utc;
+use std::collections::BTreeMap;
+
+// Reads the values which the records of a table had at the specified time,
+// for every record which has been changed since then, from the change feed.
+//
+// The values are keyed by the storage keys of the records, and the records
+// which did not exist at that time have a value of NONE. Any record which
+// is not returned has not changed since then, so its current value applies.
+//
+// The history is accurate to the interval at which timestamps are mapped
+// to versionstamps, so changes made just before the specified time may be
+// treated as if they were made after it.
+pub async fn originals(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &DefineTableStatement,
+	at: &Datetime,
+	batch: u32,
+) -> Result<BTreeMap<Key, Value>, Error> {
+	// The change feed needs to include the original values
+	let cf = match &tb.changefeed {
+		Some(cf) if cf.store_original => cf,
+		_ => {
+			return Err(Error::TbHistoryNotEnabled {
+				value: tb.name.to_raw(),
+			})
+		}
+	};
+	let expired = || Error::VersionExpired {
+		tb: tb.name.to_raw(),
+		version: at.to_string(),
+	};
+	// Nothing has changed since a time in the future
+	let now = Utc::now();
+	let age = match now.signed_duration_since(at.0).to_std() {
+		Ok(age) => age,
+		Err(_) => return Ok(BTreeMap::new()),
+	};
+	// Older changes may have been removed from the change feed
+	if age > cf.expiry {
+		return Err(expired());
+	}
+	// Find the versionstamp which was current at the time
+	let ts = at.0.timestamp() as u64;
+	let vs = tx.get_versionstamp_from_timestamp(ts, ns, db, false).await?;
+	let beg = change::prefix_ts(ns, db, vs.unwrap_or_else(|| vs::u64_to_versionstamp(0)));
+	let end = change::suffix(ns, db);
+	// Scan the changes made since then, one batch at a time
+	let mut res = BTreeMap::new();
+	let mut scanner = Scanner::new(beg..end, batch);
+	loop {
+		let batch = scanner.next(tx).await?;
+		if batch.is_empty() {
+			break;
+		}
+		for (k, v) in batch {
+			// Skip the changes to other tables
+			let dec = change::Cf::decode(&k)?;
+			if dec.tb != tb.name.as_str() {
+				continue;
+			}
+			let TableMutations(_, mutations) = v.into();
+			for m in mutations {
+				let id = m.id();
+				let key: Key = thing::new(ns, db, &id.tb, &id.id).into();
+				// Only the first change has the value from before the time
+				if res.contains_key(&key) {
+					continue;
+				}
+				match m.original() {
+					Some(v) => res.insert(key, v.clone()),
+					// The change was made before the original values were included
+					None => return Err(expired()),
+				};
+			}
+		}
+	}
+	Ok(res)
+}
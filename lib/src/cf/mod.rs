@@ -1,9 +1,11 @@
 pub(crate) mod gc;
+pub(crate) mod history;
 pub(crate) mod mutations;
 pub(crate) mod reader;
 pub(crate) mod writer;
 
 pub use self::gc::*;
+pub use self::history::originals;
 pub use self::mutations::*;
 pub use self::reader::read;
 pub use self::writer::Writer;
@@ -17,6 +17,10 @@ pub enum TableMutation {
 	// we do include it in the first field for convenience.
 	Set(Thing, Value),
 	Del(Thing),
+	// The same mutations, along with the value which the record had before it
+	// was changed, for tables which include the original values in the changefeed.
+	SetWithOriginal(Thing, Value, Value),
+	DelWithOriginal(Thing, Value),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
@@ -50,22 +54,55 @@ impl Default for DatabaseMutation {
 pub struct ChangeSet(pub [u8; 10], pub DatabaseMutation);
 
 impl TableMutation {
+	/// Returns the record which was changed
+	pub fn id(&self) -> &Thing {
+		match self {
+			TableMutation::Set(t, _) | TableMutation::SetWithOriginal(t, _, _) => t,
+			TableMutation::Del(t) | TableMutation::DelWithOriginal(t, _) => t,
+		}
+	}
+
+	/// Returns the value which the record had before it was changed, if it was recorded
+	pub fn original(&self) -> Option<&Value> {
+		match self {
+			TableMutation::SetWithOriginal(_, _, o) | TableMutation::DelWithOriginal(_, o) => {
+				Some(o)
+			}
+			_ => None,
+		}
+	}
+
+	/// Converts the mutation into an object with the kind of the change as
+	/// its key, along with the original value of the record if it was recorded
 	pub fn into_value(self) -> Value {
-		let (k, v) = match self {
-			TableMutation::Set(_t, v) => ("update".to_string(), v),
-			TableMutation::Del(t) => {
-				let mut h = BTreeMap::<String, Value>::new();
-				h.insert("id".to_string(), Value::Thing(t));
-				let o = Object::from(h);
-				("delete".to_string(), Value::Object(o))
+		let (k, v, original) = match self {
+			TableMutation::Set(_t, v) => ("update".to_string(), v, None),
+			// The record did not exist before the change
+			TableMutation::SetWithOriginal(_t, v, o) if o.is_none() => {
+				("create".to_string(), v, None)
+			}
+			TableMutation::SetWithOriginal(_t, v, o) => ("update".to_string(), v, Some(o)),
+			TableMutation::Del(t) => ("delete".to_string(), Self::deleted(t), None),
+			TableMutation::DelWithOriginal(t, o) => {
+				("delete".to_string(), Self::deleted(t), Some(o))
 			}
 		};
 
 		let mut h = BTreeMap::<String, Value>::new();
 		h.insert(k, v);
+		if let Some(o) = original {
+			h.insert("original".to_string(), o);
+		}
 		let o = crate::sql::object::Object::from(h);
 		Value::Object(o)
 	}
+
+	fn deleted(t: Thing) -> Value {
+		let mut h = BTreeMap::<String, Value>::new();
+		h.insert("id".to_string(), Value::Thing(t));
+		let o = Object::from(h);
+		Value::Object(o)
+	}
 }
 
 impl DatabaseMutation {
@@ -94,8 +131,12 @@ impl ChangeSet {
 impl Display for TableMutation {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		match self {
-			TableMutation::Set(id, v) => write!(f, "SET {} {}", id, v),
-			TableMutation::Del(id) => write!(f, "DEL {}", id),
+			TableMutation::Set(id, v) | TableMutation::SetWithOriginal(id, v, _) => {
+				write!(f, "SET {} {}", id, v)
+			}
+			TableMutation::Del(id) | TableMutation::DelWithOriginal(id, _) => {
+				write!(f, "DEL {}", id)
+			}
 		}
 	}
 }
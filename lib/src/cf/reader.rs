@@ -1,7 +1,7 @@
 use crate::cf::{ChangeSet, DatabaseMutation, TableMutations};
 use crate::err::Error;
 use crate::key::change;
-use crate::kvs::Transaction;
+use crate::kvs::{Key, Transaction};
 use crate::sql::statements::show::ShowSince;
 use crate::vs;
 
@@ -13,6 +13,10 @@ use crate::vs;
 //
 // You can use this to read the change feed in chunks.
 // The second call would start from the last versionstamp + 1 of the first call.
+//
+// When reading as a named consumer, the read starts after the last change
+// which the consumer has read, and the offset of the consumer is advanced
+// past the returned changes, once the transaction is committed.
 pub async fn read(
 	tx: &mut Transaction,
 	ns: &str,
@@ -21,8 +25,8 @@ pub async fn read(
 	start: ShowSince,
 	limit: Option<u32>,
 ) -> Result<Vec<ChangeSet>, Error> {
-	let beg = match start {
-		ShowSince::Versionstamp(x) => change::prefix_ts(ns, db, vs::u64_to_versionstamp(x)),
+	let beg = match &start {
+		ShowSince::Versionstamp(x) => change::prefix_ts(ns, db, vs::u64_to_versionstamp(*x)),
 		ShowSince::Timestamp(x) => {
 			let ts = x.0.timestamp() as u64;
 			let vs = tx.get_versionstamp_from_timestamp(ts, ns, db, true).await?;
@@ -35,6 +39,15 @@ pub async fn read(
 				}
 			}
 		}
+		ShowSince::Consumer(co) => match tx.get(offset_key(ns, db, tb, co)).await? {
+			// Start after all the changes at the last versionstamp
+			Some(vs) => {
+				let mut k = change::prefix_ts(ns, db, decode_versionstamp(&vs)?);
+				k.push(0xff);
+				k
+			}
+			None => change::prefix_ts(ns, db, vs::u64_to_versionstamp(0)),
+		},
 	};
 	let end = change::suffix(ns, db);
 
@@ -43,6 +56,7 @@ pub async fn read(
 	let _x = tx.scan(beg..end, limit).await?;
 
 	let mut vs: Option<[u8; 10]> = None;
+	let mut last: Option<[u8; 10]> = None;
 	let mut buf: Vec<TableMutations> = Vec::new();
 
 	let mut r = Vec::<ChangeSet>::new();
@@ -52,6 +66,9 @@ pub async fn read(
 
 		let dec = crate::key::change::Cf::decode(&k).unwrap();
 
+		// Consumers also skip past the changes to other tables
+		last = Some(dec.vs);
+
 		if let Some(tb) = tb {
 			if dec.tb != tb {
 				continue;
@@ -85,5 +102,22 @@ pub async fn read(
 		r.push(ChangeSet(vs.unwrap(), db_mut));
 	}
 
+	// Advance the offset of the consumer past the read changes
+	if let (ShowSince::Consumer(co), Some(vs)) = (&start, last) {
+		tx.set(offset_key(ns, db, tb, co), vs.to_vec()).await?;
+	}
+
 	Ok(r)
 }
+
+// Returns the key which stores the offset of a change feed consumer
+fn offset_key(ns: &str, db: &str, tb: Option<&str>, co: &str) -> Key {
+	match tb {
+		Some(tb) => crate::key::table::co::new(ns, db, tb, co).into(),
+		None => crate::key::database::co::new(ns, db, co).into(),
+	}
+}
+
+fn decode_versionstamp(v: &[u8]) -> Result<vs::Versionstamp, Error> {
+	v.try_into().map_err(|_| Error::InvalidVersionstamp)
+}
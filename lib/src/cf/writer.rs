@@ -14,15 +14,20 @@ use std::collections::HashMap;
 // value = serialized table mutations
 type PreparedWrite = (Vec<u8>, Vec<u8>, Vec<u8>, crate::kvs::Val);
 
+#[derive(Clone)]
 pub struct Writer {
 	buf: Buffer,
 }
 
+#[derive(Clone)]
 pub struct Buffer {
 	pub b: HashMap<ChangeKey, TableMutations>,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+// Mark is the number of mutations buffered for each table at a point in a transaction.
+pub(crate) struct Mark(HashMap<ChangeKey, usize>);
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
 pub struct ChangeKey {
 	pub ns: String,
 	pub db: String,
@@ -58,17 +63,38 @@ impl Writer {
 		}
 	}
 
-	pub(crate) fn update(&mut self, ns: &str, db: &str, tb: &str, id: Thing, v: Cow<'_, Value>) {
-		if v.is_some() {
-			self.buf.push(
-				ns.to_string(),
-				db.to_string(),
-				tb.to_string(),
-				TableMutation::Set(id, v.into_owned()),
-			);
-		} else {
-			self.buf.push(ns.to_string(), db.to_string(), tb.to_string(), TableMutation::Del(id));
-		}
+	pub(crate) fn update(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: Thing,
+		v: Cow<'_, Value>,
+		original: Option<Cow<'_, Value>>,
+	) {
+		let m = match (v.is_some(), original) {
+			(true, None) => TableMutation::Set(id, v.into_owned()),
+			(false, None) => TableMutation::Del(id),
+			(true, Some(o)) => TableMutation::SetWithOriginal(id, v.into_owned(), o.into_owned()),
+			(false, Some(o)) => TableMutation::DelWithOriginal(id, o.into_owned()),
+		};
+		self.buf.push(ns.to_string(), db.to_string(), tb.to_string(), m);
+	}
+
+	// mark returns the number of mutations currently buffered for each table.
+	pub(crate) fn mark(&self) -> Mark {
+		Mark(self.buf.b.iter().map(|(k, v)| (k.clone(), v.1.len())).collect())
+	}
+
+	// truncate discards all the mutations buffered since the specified mark was taken.
+	pub(crate) fn truncate(&mut self, mark: &Mark) {
+		self.buf.b.retain(|k, v| match mark.0.get(k) {
+			Some(len) => {
+				v.1.truncate(*len);
+				true
+			}
+			None => false,
+		});
 	}
 
 	// get returns all the mutations buffered for this transaction,
@@ -124,11 +150,13 @@ mod tests {
 		ddb.name = crate::sql::Ident(db.to_string());
 		ddb.changefeed = Some(ChangeFeed {
 			expiry: Duration::from_secs(10),
+			store_original: false,
 		});
 		let mut dtb = DefineTableStatement::default();
 		dtb.name = tb.into();
 		dtb.changefeed = Some(ChangeFeed {
 			expiry: Duration::from_secs(10),
+			store_original: false,
 		});
 
 		let ds = Datastore::new("memory").await.unwrap();
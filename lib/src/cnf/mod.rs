@@ -21,9 +21,110 @@ pub static MAX_COMPUTATION_DEPTH: Lazy<u8> = Lazy::new(|| {
 	option_env!("SURREAL_MAX_COMPUTATION_DEPTH").and_then(|s| s.parse::<u8>().ok()).unwrap_or(120)
 });
 
+/// Specifies how many records an ORDER BY clause sorts in memory before the sorted
+/// records are spilled to a temporary file on disk, and later merged with the rest of
+/// the result set. This limits the memory used when sorting very large result sets.
+pub static EXTERNAL_SORTING_BUFFER_LIMIT: Lazy<usize> = Lazy::new(|| {
+	option_env!("SURREAL_EXTERNAL_SORTING_BUFFER_LIMIT")
+		.and_then(|s| s.parse::<usize>().ok())
+		.unwrap_or(50_000)
+});
+
+/// Specifies how many segments the object storage engine persists before they are
+/// compacted, together with the previous snapshot, into a single new snapshot.
+pub static OBJECT_STORE_COMPACTION_THRESHOLD: Lazy<usize> = Lazy::new(|| {
+	option_env!("SURREAL_OBJECT_STORE_COMPACTION_THRESHOLD")
+		.and_then(|s| s.parse::<usize>().ok())
+		.unwrap_or(64)
+});
+
+/// Specifies how many key-value pairs are fetched from the storage engine in each
+/// batch, when iterating over the records of a table, a record range, or an index.
+pub static SCAN_BATCH_SIZE: Lazy<u32> = Lazy::new(|| {
+	option_env!("SURREAL_SCAN_BATCH_SIZE").and_then(|s| s.parse::<u32>().ok()).unwrap_or(1_000)
+});
+
+/// Specifies how many records are read in each batch of an export. Every batch
+/// is read in a short transaction of its own, which is closed before it is written.
+pub static EXPORT_BATCH_SIZE: Lazy<u32> = Lazy::new(|| {
+	option_env!("SURREAL_EXPORT_BATCH_SIZE").and_then(|s| s.parse::<u32>().ok()).unwrap_or(1_000)
+});
+
+/// Specifies how often, in seconds, the storage used by each table is collected
+/// in the background, for reporting by `INFO FOR TABLE ... STATS`.
+pub static STORAGE_USAGE_INTERVAL: Lazy<u64> = Lazy::new(|| {
+	option_env!("SURREAL_STORAGE_USAGE_INTERVAL").and_then(|s| s.parse::<u64>().ok()).unwrap_or(600)
+});
+
+/// Specifies how long, in seconds, a session can be idle before it is removed from
+/// the session registry, unless it has been terminated or has started live queries.
+pub static SESSION_IDLE_TIMEOUT: Lazy<u64> = Lazy::new(|| {
+	option_env!("SURREAL_SESSION_IDLE_TIMEOUT").and_then(|s| s.parse::<u64>().ok()).unwrap_or(3_600)
+});
+
+/// Specifies how long, in seconds, a node holds the lease to build the indexes in the
+/// background, unless it renews it. The lease is renewed with each batch of records
+/// indexed, and ensures that only one node of a cluster builds the indexes at a time.
+pub static INDEX_BUILD_LEASE: Lazy<u64> = Lazy::new(|| {
+	option_env!("SURREAL_INDEX_BUILD_LEASE").and_then(|s| s.parse::<u64>().ok()).unwrap_or(60)
+});
+
+/// Specifies how many values are scanned in each batch, when values encrypted
+/// with a previous encryption key are re-encrypted with the current key.
+pub static REENCRYPTION_BATCH_SIZE: Lazy<usize> = Lazy::new(|| {
+	option_env!("SURREAL_REENCRYPTION_BATCH_SIZE")
+		.and_then(|s| s.parse::<usize>().ok())
+		.unwrap_or(1_000)
+});
+
+/// Specifies how many incremental backups of a database are archived after
+/// each full backup, before the next full backup is archived.
+pub static ARCHIVE_INCREMENTAL_BACKUPS: Lazy<u32> = Lazy::new(|| {
+	option_env!("SURREAL_ARCHIVE_INCREMENTAL_BACKUPS")
+		.and_then(|s| s.parse::<u32>().ok())
+		.unwrap_or(24)
+});
+
+/// Specifies the size in bytes above which the value of a record is split into
+/// chunks, which are each stored under a separate key, so that large values do
+/// not exceed the value size limits of the storage engine.
+pub static VALUE_CHUNK_SIZE: Lazy<usize> = Lazy::new(|| {
+	option_env!("SURREAL_VALUE_CHUNK_SIZE")
+		.and_then(|s| s.parse::<usize>().ok())
+		.unwrap_or(64 * 1024)
+});
+
+/// Specifies the size in bytes above which an import into FoundationDB is committed,
+/// and continued in a new transaction, so that it stays within the 10MB limit on
+/// the size of FoundationDB transactions.
+pub static FDB_IMPORT_SPLIT_SIZE: Lazy<u64> = Lazy::new(|| {
+	option_env!("SURREAL_FDB_IMPORT_SPLIT_SIZE")
+		.and_then(|s| s.parse::<u64>().ok())
+		.unwrap_or(8 * 1024 * 1024)
+});
+
+/// Specifies how many times a statement is retried, when its transaction
+/// conflicts with another transaction, before the conflict is returned.
+pub static TRANSACTION_RETRY_ATTEMPTS: Lazy<u32> = Lazy::new(|| {
+	option_env!("SURREAL_TRANSACTION_RETRY_ATTEMPTS")
+		.and_then(|s| s.parse::<u32>().ok())
+		.unwrap_or(3)
+});
+
+/// Specifies how many milliseconds to wait before the first retry of a conflicting
+/// statement. The wait doubles with each further retry, with some random jitter.
+pub static TRANSACTION_RETRY_BACKOFF: Lazy<u64> = Lazy::new(|| {
+	option_env!("SURREAL_TRANSACTION_RETRY_BACKOFF")
+		.and_then(|s| s.parse::<u64>().ok())
+		.unwrap_or(10)
+});
+
 /// Specifies the names of parameters which can not be specified in a query.
 pub const PROTECTED_PARAM_NAMES: &[&str] = &["auth", "scope", "token", "session"];
 
+/// Specifies how many records an INSERT statement passes through the iterator at once.
+pub const INSERT_BATCH_SIZE: usize = 1000;
+
 /// The characters which are supported in server record IDs.
 pub const ID_CHARS: [char; 36] = [
 	'0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i',
@@ -3,9 +3,10 @@ use crate::ctx::reason::Reason;
 use crate::dbs::capabilities::FuncTarget;
 #[cfg(feature = "http")]
 use crate::dbs::capabilities::NetTarget;
-use crate::dbs::{Capabilities, Notification};
+use crate::dbs::{Capabilities, Compactions, Notification, Rebuilds, Sessions, Stats, Usage};
 use crate::err::Error;
 use crate::idx::planner::QueryPlanner;
+use crate::kvs::Datastore;
 use crate::sql::value::Value;
 use channel::Sender;
 use std::borrow::Cow;
@@ -45,6 +46,18 @@ pub struct Context<'a> {
 	query_planner: Option<&'a QueryPlanner<'a>>,
 	// Capabilities
 	capabilities: Arc<Capabilities>,
+	// Stores the session registry if available
+	sessions: Option<Sessions>,
+	// Stores the access statistics registry if available
+	stats: Option<Stats>,
+	// Stores the index rebuild registry if available
+	rebuilds: Option<Rebuilds>,
+	// Stores the storage compaction status if available
+	compactions: Option<Compactions>,
+	// Stores the storage usage registry if available
+	usage: Option<Usage>,
+	// Stores the datastore if available
+	datastore: Option<&'a Datastore>,
 }
 
 impl<'a> Default for Context<'a> {
@@ -75,6 +88,12 @@ impl<'a> Context<'a> {
 			notifications: None,
 			query_planner: None,
 			capabilities: Arc::new(Capabilities::default()),
+			sessions: None,
+			stats: None,
+			rebuilds: None,
+			compactions: None,
+			usage: None,
+			datastore: None,
 		}
 	}
 
@@ -88,6 +107,12 @@ impl<'a> Context<'a> {
 			notifications: parent.notifications.clone(),
 			query_planner: parent.query_planner,
 			capabilities: parent.capabilities.clone(),
+			sessions: parent.sessions.clone(),
+			stats: parent.stats.clone(),
+			rebuilds: parent.rebuilds.clone(),
+			compactions: parent.compactions.clone(),
+			usage: parent.usage.clone(),
+			datastore: parent.datastore,
 		}
 	}
 
@@ -129,6 +154,42 @@ impl<'a> Context<'a> {
 		self.notifications = chn.cloned()
 	}
 
+	/// Add the session registry to the context, so that we
+	/// can inspect and terminate the connected sessions.
+	pub fn add_sessions(&mut self, sessions: Option<&Sessions>) {
+		self.sessions = sessions.cloned()
+	}
+
+	/// Add the access statistics registry to the context, so
+	/// that we can record the records accessed on each table.
+	pub fn add_stats(&mut self, stats: Option<&Stats>) {
+		self.stats = stats.cloned()
+	}
+
+	/// Add the index rebuild registry to the context, so
+	/// that we can report the progress of index rebuilds.
+	pub fn add_rebuilds(&mut self, rebuilds: Option<&Rebuilds>) {
+		self.rebuilds = rebuilds.cloned()
+	}
+
+	/// Add the storage compaction status to the context,
+	/// so that we can report the compactions which have run.
+	pub fn add_compactions(&mut self, compactions: Option<&Compactions>) {
+		self.compactions = compactions.cloned()
+	}
+
+	/// Add the storage usage registry to the context, so
+	/// that we can report the storage used by each table.
+	pub fn add_usage(&mut self, usage: Option<&Usage>) {
+		self.usage = usage.cloned()
+	}
+
+	/// Add the datastore to the context, so that we can run
+	/// short transactions separately from the current one.
+	pub(crate) fn add_datastore(&mut self, ds: &'a Datastore) {
+		self.datastore = Some(ds)
+	}
+
 	/// Set the query planner
 	pub(crate) fn set_query_planner(&mut self, qp: &'a QueryPlanner) {
 		self.query_planner = Some(qp);
@@ -144,6 +205,30 @@ impl<'a> Context<'a> {
 		self.notifications.clone()
 	}
 
+	pub(crate) fn get_sessions(&self) -> Option<&Sessions> {
+		self.sessions.as_ref()
+	}
+
+	pub(crate) fn get_stats(&self) -> Option<&Stats> {
+		self.stats.as_ref()
+	}
+
+	pub(crate) fn get_rebuilds(&self) -> Option<&Rebuilds> {
+		self.rebuilds.as_ref()
+	}
+
+	pub(crate) fn get_compactions(&self) -> Option<&Compactions> {
+		self.compactions.as_ref()
+	}
+
+	pub(crate) fn get_usage(&self) -> Option<&Usage> {
+		self.usage.as_ref()
+	}
+
+	pub(crate) fn get_datastore(&self) -> Option<&'a Datastore> {
+		self.datastore
+	}
+
 	pub(crate) fn get_query_planner(&self) -> Option<&QueryPlanner> {
 		self.query_planner
 	}
@@ -176,6 +261,15 @@ impl<'a> Context<'a> {
 		matches!(self.done(), Some(Reason::Timedout))
 	}
 
+	/// Check if the context is ok to continue, returning an error
+	/// if the operation timed out or was cancelled.
+	pub fn check(&self) -> Result<(), Error> {
+		match self.done() {
+			Some(reason) => Err(reason.into()),
+			None => Ok(()),
+		}
+	}
+
 	/// Get a value from the context. If no value is stored under the
 	/// provided key, then this will return None.
 	pub fn value(&self, key: &str) -> Option<&Value> {
@@ -0,0 +1,96 @@
+use crate::sql::{Datetime, Value};
+use std::sync::{Arc, Mutex};
+
+/// The status of the compactions of the storage engine of a datastore
+#[derive(Clone, Debug, Default)]
+pub struct Compactions(Arc<Mutex<State>>);
+
+#[derive(Debug, Default)]
+struct State {
+	// Whether a compaction is running
+	running: bool,
+	// The number of compactions which have completed
+	completed: u64,
+	// When the latest compaction was started
+	started: Option<Datetime>,
+	// When the latest compaction finished
+	finished: Option<Datetime>,
+	// Why the latest compaction failed, if it did
+	error: Option<String>,
+	// The timestamp at which the latest scheduled compaction was due
+	scheduled: Option<u64>,
+}
+
+/// Tracks a running compaction. If the compaction is
+/// dropped before it has finished, it is recorded as
+/// having failed.
+pub(crate) struct Compaction(Arc<Mutex<State>>);
+
+impl Compaction {
+	/// Record the outcome of the compaction
+	pub(crate) fn finish(self, error: Option<String>) {
+		let mut state = self.0.lock().unwrap();
+		state.running = false;
+		state.finished = Some(Datetime::default());
+		if error.is_none() {
+			state.completed += 1;
+		}
+		state.error = error;
+	}
+}
+
+impl Drop for Compaction {
+	fn drop(&mut self) {
+		let mut state = self.0.lock().unwrap();
+		if state.running {
+			state.running = false;
+			state.error = Some("The compaction was interrupted".to_owned());
+		}
+	}
+}
+
+impl Compactions {
+	/// Register the start of a compaction, unless one is already running
+	pub(crate) fn start(&self) -> Option<Compaction> {
+		let mut state = self.0.lock().unwrap();
+		if state.running {
+			return None;
+		}
+		state.running = true;
+		state.started = Some(Datetime::default());
+		Some(Compaction(self.0.clone()))
+	}
+
+	/// Check whether a scheduled compaction is due, marking it as
+	/// started at the timestamp if it is
+	pub(crate) fn due(&self, ts: u64, every: u64) -> bool {
+		let mut state = self.0.lock().unwrap();
+		match state.scheduled {
+			Some(v) if ts < v.saturating_add(every) => false,
+			Some(_) => {
+				state.scheduled = Some(ts);
+				true
+			}
+			// The first compaction is due one interval after the datastore started
+			None => {
+				state.scheduled = Some(ts);
+				false
+			}
+		}
+	}
+
+	/// Output the status of the compactions
+	pub(crate) fn info(&self) -> Value {
+		let state = self.0.lock().unwrap();
+		let mut res = map! {
+			"running".to_string() => state.running.into(),
+			"completed".to_string() => state.completed.into(),
+			"started".to_string() => state.started.clone().map_or(Value::None, Value::from),
+			"finished".to_string() => state.finished.clone().map_or(Value::None, Value::from),
+		};
+		if let Some(error) = &state.error {
+			res.insert("error".to_string(), error.clone().into());
+		}
+		Value::from(res)
+	}
+}
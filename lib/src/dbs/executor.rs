@@ -1,9 +1,11 @@
+use crate::cnf;
 use crate::ctx::Context;
 use crate::dbs::response::Response;
 use crate::dbs::Notification;
 use crate::dbs::Options;
 use crate::dbs::QueryType;
 use crate::dbs::Transaction;
+use crate::doc::AfterEvent;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
@@ -14,9 +16,12 @@ use crate::sql::query::Query;
 use crate::sql::statement::Statement;
 use crate::sql::value::Value;
 use crate::sql::Base;
+use crate::sql::Isolation;
 use channel::Receiver;
 use futures::lock::Mutex;
+use rand::Rng;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::instrument;
 use trice::Instant;
 
@@ -24,6 +29,11 @@ pub(crate) struct Executor<'a> {
 	err: bool,
 	kvs: &'a Datastore,
 	txn: Option<Transaction>,
+	/// The savepoints in the current transaction, with the number of
+	/// buffered responses and notifications when each was created
+	savepoints: Vec<(String, usize, usize)>,
+	/// The AFTER events deferred by committed transactions
+	events: Vec<AfterEvent>,
 }
 
 impl<'a> Executor<'a> {
@@ -32,6 +42,8 @@ impl<'a> Executor<'a> {
 			kvs,
 			txn: None,
 			err: false,
+			savepoints: vec![],
+			events: vec![],
 		}
 	}
 
@@ -44,10 +56,10 @@ impl<'a> Executor<'a> {
 	/// - false if
 	///   - couldn't create transaction (sets err flag)
 	///   - a transaction has already begun
-	async fn begin(&mut self, write: bool) -> bool {
+	async fn begin(&mut self, write: bool, isolation: Option<Isolation>) -> bool {
 		match self.txn.as_ref() {
 			Some(_) => false,
-			None => match self.kvs.transaction(write, false).await {
+			None => match self.kvs.start_transaction(write, false, isolation).await {
 				Ok(v) => {
 					self.txn = Some(Arc::new(Mutex::new(v)));
 					true
@@ -76,6 +88,7 @@ impl<'a> Executor<'a> {
 					// already set
 					let _ = txn.cancel().await;
 				} else {
+					let events = txn.take_events();
 					let r = match txn.complete_changes(false).await {
 						Ok(_) => txn.commit().await,
 						r => r,
@@ -89,6 +102,8 @@ impl<'a> Executor<'a> {
 						self.err = true;
 						return Err(e);
 					}
+					// Keep the AFTER events to run once committed
+					self.events.extend(events);
 				}
 			}
 		}
@@ -115,6 +130,17 @@ impl<'a> Executor<'a> {
 		}
 	}
 
+	fn buf_rollback(&self, v: Response) -> Response {
+		Response {
+			time: v.time,
+			result: match v.result {
+				Ok(_) => Err(Error::QueryCancelled),
+				Err(e) => Err(e),
+			},
+			query_type: QueryType::Other,
+		}
+	}
+
 	fn buf_commit(&self, v: Response, commit_error: &Option<Error>) -> Response {
 		match &self.err {
 			true => Response {
@@ -122,9 +148,7 @@ impl<'a> Executor<'a> {
 				result: match v.result {
 					Ok(_) => Err(commit_error
 						.as_ref()
-						.map(|e| Error::QueryNotExecutedDetail {
-							message: e.to_string(),
-						})
+						.map(Self::not_committed)
 						.unwrap_or(Error::QueryNotExecuted)),
 					Err(e) => Err(e),
 				},
@@ -134,6 +158,34 @@ impl<'a> Executor<'a> {
 		}
 	}
 
+	/// The error for a statement whose transaction failed to commit,
+	/// which stays retryable when the transaction conflicted
+	fn not_committed(e: &Error) -> Error {
+		match e {
+			Error::TxRetryable => Error::TxRetryable,
+			e => Error::QueryNotExecutedDetail {
+				message: e.to_string(),
+			},
+		}
+	}
+
+	/// Check whether a statement which ran in its own transaction should
+	/// be run again, after the transaction conflicted with another one
+	fn retry(ctx: &Context<'_>, opt: &Options, stm: &Statement, e: &Error, attempt: u32) -> bool {
+		e.is_retryable() && attempt < opt.retries && stm.retryable() && ctx.done().is_none()
+	}
+
+	/// Wait before a statement is retried, for a randomised
+	/// duration which grows exponentially with each attempt
+	async fn backoff(attempt: u32) {
+		let max = cnf::TRANSACTION_RETRY_BACKOFF.saturating_mul(1 << (attempt - 1).min(10));
+		let dur = Duration::from_millis(rand::thread_rng().gen_range(max / 2..=max));
+		#[cfg(target_arch = "wasm32")]
+		wasmtimer::tokio::sleep(dur).await;
+		#[cfg(not(target_arch = "wasm32"))]
+		tokio::time::sleep(dur).await;
+	}
+
 	/// Consume the live query notifications
 	async fn clear(&self, _: &Context<'_>, rcv: Receiver<Notification>) {
 		while rcv.try_recv().is_ok() {
@@ -155,6 +207,56 @@ impl<'a> Executor<'a> {
 		}
 	}
 
+	/// Run the AFTER events which were deferred by committed transactions.
+	/// Each event runs in its own transaction, and any failure is logged
+	/// without affecting the result of the statement which triggered it.
+	async fn trigger(&mut self, ctx: &Context<'_>, opt: &Options, rcv: Receiver<Notification>) {
+		let mut opt = opt.new_with_perms(false);
+		let mut events = std::mem::take(&mut self.events);
+		while !events.is_empty() {
+			// Prevent events from endlessly triggering each other
+			opt = match opt.dive(1) {
+				Ok(v) => v,
+				Err(e) => {
+					warn!("Unable to run AFTER events: {}", e);
+					break;
+				}
+			};
+			// Collect the events triggered by these events
+			let mut next = vec![];
+			for ev in events {
+				let txn = match self.kvs.transaction(true, false).await {
+					Ok(v) => Arc::new(Mutex::new(v)),
+					Err(e) => {
+						warn!("Unable to run AFTER event: {}", e);
+						continue;
+					}
+				};
+				let res = ev.run(ctx, &opt, &txn).await;
+				let mut txn = txn.lock().await;
+				let res = match res {
+					Ok(_) => match txn.complete_changes(false).await {
+						Ok(_) => {
+							let mut evs = txn.take_events();
+							txn.commit().await.map(|_| next.append(&mut evs))
+						}
+						Err(e) => Err(e),
+					},
+					Err(e) => Err(e),
+				};
+				match res {
+					Ok(_) => self.flush(ctx, rcv.clone()).await,
+					Err(e) => {
+						let _ = txn.cancel().await;
+						self.clear(ctx, rcv.clone()).await;
+						warn!("An AFTER event failed: {}", e);
+					}
+				}
+			}
+			events = next;
+		}
+	}
+
 	async fn set_ns(&self, ctx: &mut Context<'_>, opt: &mut Options, ns: &str) {
 		let mut session = ctx.value("session").unwrap_or(&Value::None).clone();
 		session.put(NS.as_ref(), ns.to_owned().into());
@@ -179,13 +281,20 @@ impl<'a> Executor<'a> {
 		// Create a notification channel
 		let (send, recv) = channel::unbounded();
 		// Set the notification channel
-		let mut opt = opt.new_with_sender(send);
+		let mut opt = opt.new_with_sender(send.clone());
 		// Initialise buffer of responses
 		let mut buf: Vec<Response> = vec![];
 		// Initialise array of responses
 		let mut out: Vec<Response> = vec![];
+		// Whether the statements are being imported
+		let mut import = false;
+		// Whether the statements run as a single atomic batch
+		let mut atomic = false;
+		// The isolation level of the running transaction
+		let mut isolation = opt.isolation;
 		// Process all statements in query
-		for stm in qry.into_iter() {
+		let mut stms = qry.into_iter();
+		while let Some(stm) = stms.next() {
 			// Log the statement
 			debug!("Executing: {}", stm);
 			// Reset errors
@@ -202,27 +311,82 @@ impl<'a> Executor<'a> {
 			let is_stm_output = matches!(stm, Statement::Output(_));
 			// Process a single statement
 			let res = match stm {
+				// An atomic batch is committed or cancelled as a whole
+				Statement::Begin(_) | Statement::Commit(_) | Statement::Cancel(_) if atomic => {
+					Err(Error::TxAtomic)
+				}
 				// Specify runtime options
 				Statement::Option(mut stm) => {
-					// Allowed to run?
-					opt.is_allowed(Action::Edit, ResourceKind::Option, &Base::Db)?;
 					// Convert to uppercase
 					stm.name.0.make_ascii_uppercase();
+					// Run the rest of the statements in a single transaction
+					if stm.name.0 == "ATOMIC" {
+						if stm.what && !atomic {
+							atomic = true;
+							if self.txn.is_none() {
+								match self.kvs.start_transaction(true, false, isolation).await {
+									Ok(v) => self.txn = Some(Arc::new(Mutex::new(v))),
+									Err(e) => {
+										// Fail every statement of the batch which couldn't begin
+										let message = e.to_string();
+										for _ in stms.by_ref() {
+											out.push(Response {
+												time: Duration::ZERO,
+												result: Err(Error::QueryNotExecutedDetail {
+													message: message.clone(),
+												}),
+												query_type: QueryType::Other,
+											});
+										}
+									}
+								}
+							}
+						}
+						continue;
+					}
+					// Allowed to run?
+					opt.is_allowed(Action::Edit, ResourceKind::Option, &Base::Db)?;
 					// Process the option
 					opt = match stm.name.0.as_str() {
 						"FIELDS" => opt.with_fields(stm.what),
 						"EVENTS" => opt.with_events(stm.what),
 						"TABLES" => opt.with_tables(stm.what),
-						"IMPORT" => opt.with_import(stm.what),
+						"IMPORT" => {
+							import = stm.what;
+							opt.with_import(stm.what)
+						}
 						"FORCE" => opt.with_force(stm.what),
+						"STABLE" => opt.with_stable(stm.what),
 						_ => break,
 					};
 					// Continue
 					continue;
 				}
 				// Begin a new transaction
-				Statement::Begin(_) => {
-					self.begin(true).await;
+				Statement::Begin(stm) => {
+					if self.txn.is_none() {
+						isolation = stm.isolation.or(opt.isolation);
+						match self.kvs.start_transaction(true, false, isolation).await {
+							Ok(v) => self.txn = Some(Arc::new(Mutex::new(v))),
+							Err(e) => {
+								// Fail the statements of the transaction which couldn't begin
+								let message = e.to_string();
+								for stm in stms.by_ref() {
+									if matches!(stm, Statement::Commit(_) | Statement::Cancel(_)) {
+										break;
+									}
+									out.push(Response {
+										time: Duration::ZERO,
+										result: Err(Error::QueryNotExecutedDetail {
+											message: message.clone(),
+										}),
+										query_type: QueryType::Other,
+									});
+								}
+							}
+						}
+					}
+					self.savepoints.clear();
 					continue;
 				}
 				// Cancel a running transaction
@@ -233,6 +397,7 @@ impl<'a> Executor<'a> {
 					out.append(&mut buf);
 					debug_assert!(self.txn.is_none(), "cancel(true) should have unset txn");
 					self.txn = None;
+					self.savepoints.clear();
 					continue;
 				}
 				// Commit a running transaction
@@ -240,11 +405,69 @@ impl<'a> Executor<'a> {
 					let commit_error = self.commit(true).await.err();
 					buf = buf.into_iter().map(|v| self.buf_commit(v, &commit_error)).collect();
 					self.flush(&ctx, recv.clone()).await;
+					self.trigger(&ctx, &opt, recv.clone()).await;
 					out.append(&mut buf);
 					debug_assert!(self.txn.is_none(), "commit(true) should have unset txn");
 					self.txn = None;
+					self.savepoints.clear();
 					continue;
 				}
+				// Create a savepoint in a running transaction
+				Statement::Savepoint(stm) => match (&self.txn, self.err) {
+					(None, _) => Err(Error::TxSavepoint),
+					(Some(_), true) => Err(Error::QueryNotExecuted),
+					(Some(txn), false) => {
+						txn.lock().await.savepoint(&stm.name);
+						self.savepoints.push((stm.name.0, buf.len(), recv.len()));
+						Ok(Value::None)
+					}
+				},
+				// Undo the changes made since a savepoint
+				Statement::Rollback(stm) => match &self.txn {
+					None => Err(Error::TxSavepoint),
+					Some(txn) => {
+						let res = txn.lock().await.rollback_to_savepoint(&stm.name).await;
+						if res.is_ok() {
+							if let Some(pos) =
+								self.savepoints.iter().rposition(|v| v.0 == stm.name.0)
+							{
+								self.savepoints.truncate(pos + 1);
+								let (_, responses, notifications) = self.savepoints[pos];
+								// Mark the rolled back statements as cancelled
+								let rolled = buf.split_off(responses.min(buf.len()));
+								buf.extend(rolled.into_iter().map(|v| self.buf_rollback(v)));
+								// Discard the rolled back live query notifications
+								let mut pending = vec![];
+								while let Ok(v) = recv.try_recv() {
+									pending.push(v);
+								}
+								pending.truncate(notifications);
+								for v in pending {
+									let _ = send.try_send(v);
+								}
+							}
+							// The transaction can continue
+							self.err = false;
+						}
+						res.map(|_| Value::None)
+					}
+				},
+				// Remove a savepoint, keeping the changes made since
+				Statement::Release(stm) => match (&self.txn, self.err) {
+					(None, _) => Err(Error::TxSavepoint),
+					(Some(_), true) => Err(Error::QueryNotExecuted),
+					(Some(txn), false) => {
+						let res = txn.lock().await.release_savepoint(&stm.name);
+						if res.is_ok() {
+							if let Some(pos) =
+								self.savepoints.iter().rposition(|v| v.0 == stm.name.0)
+							{
+								self.savepoints.truncate(pos);
+							}
+						}
+						res.map(|_| Value::None)
+					}
+				},
 				// Switch to a different NS or DB
 				Statement::Use(stm) => {
 					if let Some(ref ns) = stm.ns {
@@ -258,7 +481,7 @@ impl<'a> Executor<'a> {
 				// Process param definition statements
 				Statement::Set(stm) => {
 					// Create a transaction
-					let loc = self.begin(stm.writeable()).await;
+					let loc = self.begin(stm.writeable(), opt.isolation).await;
 					// Check the transaction
 					match self.err {
 						// We failed to create a transaction
@@ -278,9 +501,7 @@ impl<'a> Executor<'a> {
 											Err(e) => {
 												// Clear live query notifications
 												self.clear(&ctx, recv.clone()).await;
-												Err(Error::QueryNotExecutedDetail {
-													message: e.to_string(),
-												})
+												Err(Self::not_committed(&e))
 											}
 											Ok(_) => {
 												// Flush live query notifications
@@ -310,59 +531,70 @@ impl<'a> Executor<'a> {
 					true => Err(Error::QueryNotExecuted),
 					// Compute the statement normally
 					false => {
-						// Create a transaction
-						let loc = self.begin(stm.writeable()).await;
-						// Check the transaction
-						match self.err {
-							// We failed to create a transaction
-							true => Err(Error::TxFailure),
-							// The transaction began successfully
-							false => {
-								let mut ctx = Context::new(&ctx);
-								// Process the statement
-								let res = match stm.timeout() {
-									// There is a timeout clause
-									Some(timeout) => {
-										// Set statement timeout
-										ctx.add_timeout(timeout);
-										// Process the statement
-										let res = stm.compute(&ctx, &opt, &self.txn(), None).await;
-										// Catch statement timeout
-										match ctx.is_timedout() {
-											true => Err(Error::QueryTimedout),
-											false => res,
+						let mut attempt = 0;
+						loop {
+							// Create a transaction
+							let loc = self.begin(stm.writeable(), opt.isolation).await;
+							// Check the transaction
+							let res = match self.err {
+								// We failed to create a transaction
+								true => Err(Error::TxFailure),
+								// The transaction began successfully
+								false => {
+									let mut ctx = Context::new(&ctx);
+									// Process the statement
+									let res = match stm.timeout() {
+										// There is a timeout clause
+										Some(timeout) => {
+											// Set statement timeout
+											ctx.add_timeout(timeout);
+											// Process the statement
+											let res =
+												stm.compute(&ctx, &opt, &self.txn(), None).await;
+											// Catch statement timeout
+											match ctx.is_timedout() {
+												true => Err(Error::QueryTimedout),
+												false => res,
+											}
 										}
-									}
-									// There is no timeout clause
-									None => stm.compute(&ctx, &opt, &self.txn(), None).await,
-								};
-								// Catch global timeout
-								let res = match ctx.is_timedout() {
-									true => Err(Error::QueryTimedout),
-									false => res,
-								};
-								// Finalise transaction and return the result.
-								if res.is_ok() && stm.writeable() {
-									if let Err(e) = self.commit(loc).await {
+										// There is no timeout clause
+										None => stm.compute(&ctx, &opt, &self.txn(), None).await,
+									};
+									// Catch global timeout or cancellation
+									let res = match ctx.done() {
+										Some(reason) => Err(reason.into()),
+										None => res,
+									};
+									// Finalise transaction and return the result.
+									if res.is_ok() && stm.writeable() {
+										if let Err(e) = self.commit(loc).await {
+											// Clear live query notification details
+											self.clear(&ctx, recv.clone()).await;
+											// The commit failed
+											Err(Self::not_committed(&e))
+										} else {
+											// Flush the live query change notifications
+											self.flush(&ctx, recv.clone()).await;
+											// Successful, committed result
+											res
+										}
+									} else {
+										self.cancel(loc).await;
 										// Clear live query notification details
 										self.clear(&ctx, recv.clone()).await;
-										// The commit failed
-										Err(Error::QueryNotExecutedDetail {
-											message: e.to_string(),
-										})
-									} else {
-										// Flush the live query change notifications
-										self.flush(&ctx, recv.clone()).await;
-										// Successful, committed result
+										// Return an error
 										res
 									}
-								} else {
-									self.cancel(loc).await;
-									// Clear live query notification details
-									self.clear(&ctx, recv.clone()).await;
-									// Return an error
-									res
 								}
+							};
+							// Retry the statement if it conflicted with another transaction
+							match res {
+								Err(e) if loc && Self::retry(&ctx, &opt, &stm, &e, attempt) => {
+									self.err = false;
+									attempt += 1;
+									Self::backoff(attempt).await;
+								}
+								res => break res,
 							}
 						}
 					}
@@ -386,13 +618,63 @@ impl<'a> Executor<'a> {
 			};
 			// Output the response
 			if self.txn.is_some() {
-				if is_stm_output {
+				// The statements of an atomic batch each keep their response
+				if is_stm_output && !atomic {
 					buf.clear();
 				}
 				buf.push(res);
 			} else {
 				out.push(res)
 			}
+			// Run any AFTER events once committed
+			if self.txn.is_none() {
+				self.trigger(&ctx, &opt, recv.clone()).await;
+			}
+			// Split an import which has grown too large into several transactions
+			if let (true, false, false, Some(limit), Some(txn)) =
+				(import, atomic, self.err, opt.split_size, &self.txn)
+			{
+				if txn.lock().await.size() >= limit {
+					// Commit the statements imported so far
+					let commit_error = self.commit(true).await.err();
+					buf = buf.into_iter().map(|v| self.buf_commit(v, &commit_error)).collect();
+					self.flush(&ctx, recv.clone()).await;
+					self.trigger(&ctx, &opt, recv.clone()).await;
+					out.append(&mut buf);
+					self.savepoints.clear();
+					// Continue the import in a new transaction
+					match self.kvs.start_transaction(true, false, isolation).await {
+						Ok(v) => self.txn = Some(Arc::new(Mutex::new(v))),
+						Err(e) => {
+							// Fail the remaining statements of the transaction
+							let message = e.to_string();
+							for stm in stms.by_ref() {
+								if matches!(stm, Statement::Commit(_) | Statement::Cancel(_)) {
+									break;
+								}
+								out.push(Response {
+									time: Duration::ZERO,
+									result: Err(Error::QueryNotExecutedDetail {
+										message: message.clone(),
+									}),
+									query_type: QueryType::Other,
+								});
+							}
+						}
+					}
+					// The rest of the import fails if the commit failed
+					self.err = commit_error.is_some();
+				}
+			}
+		}
+		// Commit an atomic batch, or cancel it if any statement failed
+		if atomic && self.txn.is_some() {
+			let commit_error = self.commit(true).await.err();
+			buf = buf.into_iter().map(|v| self.buf_commit(v, &commit_error)).collect();
+			self.flush(&ctx, recv.clone()).await;
+			self.trigger(&ctx, &opt, recv.clone()).await;
+			out.append(&mut buf);
+			self.savepoints.clear();
 		}
 		// Return responses
 		Ok(out)
@@ -1,39 +1,198 @@
-use crate::dbs::Iterable;
-use crate::sql::{Explain, Object, Value};
+use crate::ctx::Context;
+use crate::dbs::{Iterable, Options, Statement, Transaction};
+use crate::err::Error;
+use crate::key::thing;
+use crate::sql::with::With;
+use crate::sql::{Array, Explain, Object, Value};
 use std::collections::HashMap;
 
+/// The maximum number of records counted when estimating the size of a table
+const ESTIMATE_LIMIT: usize = 10_000;
+
+#[derive(Default)]
+pub(super) struct Explanation {
+	items: Vec<ExplainItem>,
+	plan: Option<Plan>,
+}
+
 #[derive(Default)]
-pub(super) struct Explanation(Vec<ExplainItem>);
+struct Plan {
+	// The predicates which are pushed down into an index
+	pushdown: Array,
+	// The processing stages which follow the iteration
+	stages: Array,
+	// The number of records which were output
+	rows: Option<usize>,
+}
 
 impl Explanation {
-	pub(super) fn new(e: Option<&Explain>, iterables: &Vec<Iterable>) -> (bool, Option<Self>) {
-		match e {
-			None => (true, None),
-			Some(e) => {
+	pub(super) async fn new(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		iterables: &Vec<Iterable>,
+	) -> Result<(bool, Option<Self>), Error> {
+		match stm.explain() {
+			None => Ok((true, None)),
+			Some(Explain {
+				full,
+				plan: false,
+			}) => {
 				let mut exp = Self::default();
 				for i in iterables {
-					exp.add_iter(i);
+					exp.items.push(ExplainItem::new_iter(i));
 				}
-				(e.0, Some(exp))
+				Ok((*full, Some(exp)))
+			}
+			Some(Explain {
+				full,
+				plan: true,
+			}) => {
+				let mut exp = Self::default();
+				let mut plan = Plan::default();
+				for i in iterables {
+					let mut item = ExplainItem::new_iter(i);
+					match i {
+						Iterable::Table(t) => {
+							item.details.push(("reason", reason(stm)));
+							item.details.push(("estimate", estimate(ctx, opt, txn, &t.0).await?));
+						}
+						Iterable::Index(_, _, io) => {
+							item.details.push(("estimate", io.estimate()));
+							plan.pushdown.push(io.explain());
+						}
+						Iterable::Value(_)
+						| Iterable::Thing(_)
+						| Iterable::Mergeable(_, _)
+						| Iterable::Relatable(_, _, _) => {
+							item.details.push(("estimate", Value::from(1)));
+						}
+						Iterable::Range(_) | Iterable::Edges(_) => {
+							item.details.push(("estimate", Value::None));
+						}
+					}
+					exp.items.push(item);
+				}
+				plan.stages = stages(stm);
+				exp.plan = Some(plan);
+				Ok((*full, Some(exp)))
 			}
 		}
 	}
 
-	fn add_iter(&mut self, iter: &Iterable) {
-		self.0.push(ExplainItem::new_iter(iter));
-	}
-
 	pub(super) fn add_fetch(&mut self, count: usize) {
-		self.0.push(ExplainItem::new_fetch(count));
+		match &mut self.plan {
+			Some(plan) => plan.rows = Some(count),
+			None => self.items.push(ExplainItem::new_fetch(count)),
+		}
 	}
 
 	pub(super) fn output(self, results: &mut Vec<Value>) {
-		for e in self.0 {
-			results.push(e.into());
+		match self.plan {
+			None => {
+				for e in self.items {
+					results.push(e.into());
+				}
+			}
+			Some(plan) => {
+				let mut res = Object::default();
+				res.insert(
+					"access".to_owned(),
+					self.items.into_iter().map(Value::from).collect::<Vec<_>>().into(),
+				);
+				res.insert("pushdown".to_owned(), plan.pushdown.into());
+				res.insert("stages".to_owned(), plan.stages.into());
+				if let Some(rows) = plan.rows {
+					res.insert("rows".to_owned(), rows.into());
+				}
+				results.push(res.into());
+			}
 		}
 	}
 }
 
+// Describe why a table is scanned instead of using an index
+fn reason(stm: &Statement<'_>) -> Value {
+	match (stm.with(), stm.conds()) {
+		(Some(With::NoIndex), _) => "WITH NOINDEX".into(),
+		(_, None) => "no condition".into(),
+		(Some(With::Index(i)), Some(_)) => {
+			format!("WITH INDEX {} does not match the condition", i.join(",")).into()
+		}
+		(_, Some(_)) => "no index matches the condition".into(),
+	}
+}
+
+// Count the records in a table, up to a maximum number of records
+async fn estimate(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	tb: &str,
+) -> Result<Value, Error> {
+	let mut beg = thing::prefix(opt.ns(), opt.db(), tb);
+	let end = thing::suffix(opt.ns(), opt.db(), tb);
+	let mut count = 0;
+	while count < ESTIMATE_LIMIT {
+		// Check if the context is finished
+		if ctx.is_done() {
+			break;
+		}
+		// Get the next 1000 key-value entries
+		let res = txn.lock().await.scan(beg.clone()..end.clone(), 1000).await?;
+		count += res.len();
+		// Ready the next batch
+		match res.last() {
+			Some((k, _)) if res.len() == 1000 => {
+				beg = k.clone();
+				beg.push(0x00);
+			}
+			_ => break,
+		}
+	}
+	Ok(count.min(ESTIMATE_LIMIT).into())
+}
+
+// Describe the processing stages which follow the iteration
+fn stages(stm: &Statement<'_>) -> Array {
+	let mut res = Array::new();
+	if let Some(v) = stm.conds() {
+		res.push(ExplainItem::new_stage("Filter", vec![("cond", v.0.to_owned())]).into());
+	}
+	if let Some(v) = stm.split() {
+		let fields = v.iter().map(|v| Value::from(v.to_string())).collect::<Vec<_>>();
+		res.push(ExplainItem::new_stage("Split", vec![("fields", fields.into())]).into());
+	}
+	if let Some(v) = stm.group() {
+		let fields = v.iter().map(|v| Value::from(v.to_string())).collect::<Vec<_>>();
+		let mut details = vec![("fields", fields.into())];
+		let grouping = stm.grouping().to_string();
+		if !grouping.is_empty() {
+			details.push(("grouping", grouping.into()));
+		}
+		if let Some(v) = stm.having() {
+			details.push(("having", v.0.to_owned()));
+		}
+		res.push(ExplainItem::new_stage("Group", details).into());
+	}
+	if let Some(v) = stm.order() {
+		let fields = v.iter().map(|v| Value::from(v.to_string())).collect::<Vec<_>>();
+		res.push(ExplainItem::new_stage("Order", vec![("fields", fields.into())]).into());
+	}
+	if let Some(v) = stm.start() {
+		res.push(ExplainItem::new_stage("Start", vec![("value", v.0.to_owned())]).into());
+	}
+	if let Some(v) = stm.limit() {
+		res.push(ExplainItem::new_stage("Limit", vec![("value", v.0.to_owned())]).into());
+	}
+	if let Some(v) = stm.fetch() {
+		let fields = v.iter().map(|v| Value::from(v.to_string())).collect::<Vec<_>>();
+		res.push(ExplainItem::new_stage("Fetch", vec![("fields", fields.into())]).into());
+	}
+	res
+}
+
 struct ExplainItem {
 	name: Value,
 	details: Vec<(&'static str, Value)>,
@@ -47,6 +206,13 @@ impl ExplainItem {
 		}
 	}
 
+	fn new_stage(name: &str, details: Vec<(&'static str, Value)>) -> Self {
+		Self {
+			name: name.into(),
+			details,
+		}
+	}
+
 	fn new_iter(iter: &Iterable) -> Self {
 		match iter {
 			Iterable::Value(v) => Self {
@@ -4,6 +4,8 @@ use crate::ctx::Context;
 use crate::dbs::distinct::AsyncDistinct;
 use crate::dbs::distinct::SyncDistinct;
 use crate::dbs::explanation::Explanation;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::dbs::sorter::{self, Spill};
 use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
@@ -13,7 +15,11 @@ use crate::idx::planner::executor::IteratorRef;
 use crate::idx::planner::plan::IndexOption;
 use crate::sql::array::Array;
 use crate::sql::edges::Edges;
+use crate::sql::expression::Expression;
 use crate::sql::field::Field;
+use crate::sql::idiom::Idiom;
+use crate::sql::operator::Operator;
+use crate::sql::order::Orders;
 use crate::sql::range::Range;
 use crate::sql::table::Table;
 use crate::sql::thing::Thing;
@@ -65,8 +71,10 @@ pub(crate) struct Iterator {
 	// Iterator runtime error
 	error: Option<Error>,
 	// Iterator output results
-	// TODO: Should be stored on disk / (mmap?)
 	results: Vec<Value>,
+	// Iterator sorted results spilled to disk
+	#[cfg(not(target_arch = "wasm32"))]
+	spills: Vec<Spill>,
 	// Iterator input values
 	entries: Vec<Iterable>,
 }
@@ -278,7 +286,8 @@ impl Iterator {
 		// Process the query START clause
 		self.setup_start(&cancel_ctx, opt, txn, stm).await?;
 		// Extract the expected behaviour depending on the presence of EXPLAIN with or without FULL
-		let (do_iterate, mut explanation) = Explanation::new(stm.explain(), &self.entries);
+		let (do_iterate, mut explanation) =
+			Explanation::new(ctx, opt, txn, stm, &self.entries).await?;
 
 		if do_iterate {
 			// Process prepared values
@@ -287,6 +296,8 @@ impl Iterator {
 			if let Some(e) = self.error.take() {
 				return Err(e);
 			}
+			// Stop if the query timed out or was cancelled
+			ctx.check()?;
 			// Process any SPLIT clause
 			self.output_split(ctx, opt, txn, stm).await?;
 			// Process any GROUP clause
@@ -353,12 +364,24 @@ impl Iterator {
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
 		if let Some(splits) = stm.split() {
+			// Check if the results hold their record ids
+			let keyed = keyed(opt, stm);
 			// Loop over each split clause
 			for split in splits.iter() {
 				// Get the query result
 				let res = mem::take(&mut self.results);
 				// Loop over each value
 				for obj in &res {
+					// Check if the context is finished
+					ctx.check()?;
+					// Separate any record id kept with the value
+					let (obj, rid) = match keyed {
+						true => {
+							let (obj, rid) = split_key(obj);
+							(obj, Some(rid))
+						}
+						false => (obj, None),
+					};
 					// Get the value at the path
 					let val = obj.pick(split);
 					// Set the value at the path
@@ -370,7 +393,7 @@ impl Iterator {
 								// Set the value at the path
 								obj.set(ctx, opt, txn, split, val).await?;
 								// Add the object to the results
-								self.results.push(obj);
+								self.results.push(with_key(obj, rid));
 							}
 						}
 						_ => {
@@ -379,7 +402,7 @@ impl Iterator {
 							// Set the value at the path
 							obj.set(ctx, opt, txn, split, val).await?;
 							// Add the object to the results
-							self.results.push(obj);
+							self.results.push(with_key(obj, rid));
 						}
 					}
 				}
@@ -398,111 +421,189 @@ impl Iterator {
 	) -> Result<(), Error> {
 		if let Some(fields) = stm.expr() {
 			if let Some(groups) = stm.group() {
-				// Create the new grouped collection
-				let mut grp: BTreeMap<Array, Array> = BTreeMap::new();
+				// Get the grouping sets to compute
+				let sets = stm.grouping().sets(groups.len());
+				// Split any HAVING clause into its predicates
+				let mut having = Vec::new();
+				if let Some(v) = stm.having() {
+					predicates(&v.0, &mut having);
+				}
 				// Get the query result
-				let res = mem::take(&mut self.results);
-				// Loop over each value
-				for obj in res {
-					// Create a new column set
-					let mut arr = Array::with_capacity(groups.len());
-					// Loop over each group clause
-					for group in groups.iter() {
-						// Get the value at the path
-						let val = obj.pick(group);
-						// Set the value at the path
-						arr.push(val);
-					}
-					// Add to grouped collection
-					match grp.get_mut(&arr) {
-						Some(v) => v.push(obj),
-						None => {
-							grp.insert(arr, Array::from(obj));
+				let mut res = mem::take(&mut self.results);
+				// Loop over each grouping set
+				for (i, set) in sets.iter().enumerate() {
+					// Get the group fields in this grouping set
+					let keys: Vec<&Idiom> =
+						groups.iter().zip(set).filter(|(_, v)| **v).map(|(g, _)| &g.0).collect();
+					// Get the group fields which are rolled up in this grouping set
+					let rolled: Vec<&Idiom> =
+						groups.iter().zip(set).filter(|(_, v)| !**v).map(|(g, _)| &g.0).collect();
+					// Predicates on the grouped fields can be checked before aggregating
+					let (pre, post): (Vec<&Value>, Vec<&Value>) =
+						having.iter().copied().partition(|v| grouped(v, &keys));
+					// Create the new grouped collection
+					let mut grp: BTreeMap<Array, Array> = BTreeMap::new();
+					// Only clone the query result if it is needed again
+					let res = match i == sets.len() - 1 {
+						true => mem::take(&mut res),
+						false => res.clone(),
+					};
+					// Loop over each value
+					for obj in res {
+						// Check if the context is finished
+						ctx.check()?;
+						// Create a new column set
+						let mut arr = Array::with_capacity(groups.len());
+						// Loop over each group clause in this grouping set
+						for (group, _) in groups.iter().zip(set).filter(|(_, v)| **v) {
+							// Get the value at the path
+							let val = obj.pick(group);
+							// Set the value at the path
+							arr.push(val);
+						}
+						// Add to grouped collection
+						match grp.get_mut(&arr) {
+							Some(v) => v.push(obj),
+							None => {
+								grp.insert(arr, Array::from(obj));
+							}
 						}
 					}
-				}
-				// Loop over each grouped collection
-				for (_, vals) in grp {
-					// Create a new value
-					let mut obj = Value::base();
-					// Save the collected values
-					let vals = Value::from(vals);
-					// Loop over each group clause
-					for field in fields.other() {
-						// Process the field
-						if let Field::Single {
-							expr,
-							alias,
-						} = field
-						{
-							let idiom = alias
-								.as_ref()
-								.map(Cow::Borrowed)
-								.unwrap_or_else(|| Cow::Owned(expr.to_idiom()));
-							match expr {
-								Value::Function(f) if f.is_aggregate() => {
-									let x =
-										vals.all().get(ctx, opt, txn, None, idiom.as_ref()).await?;
-									let x = f.aggregate(x).compute(ctx, opt, txn, None).await?;
-									obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
-								}
-								_ => {
-									let x = vals.first();
-									let x = if let Some(alias) = alias {
-										let cur = (&x).into();
-										alias.compute(ctx, opt, txn, Some(&cur)).await?
-									} else {
-										let cur = (&x).into();
-										expr.compute(ctx, opt, txn, Some(&cur)).await?
-									};
-									obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
+					// Loop over each grouped collection
+					for (_, vals) in grp {
+						// Check if the context is finished
+						ctx.check()?;
+						// Create a new value
+						let mut obj = Value::base();
+						// Save the collected values
+						let vals = Value::from(vals);
+						// Skip this group if the grouped fields do not match
+						if !check(ctx, opt, txn, &vals.first(), &pre).await? {
+							continue;
+						}
+						// Loop over each group clause
+						for field in fields.other() {
+							// Process the field
+							if let Field::Single {
+								expr,
+								alias,
+							} = field
+							{
+								let idiom = alias
+									.as_ref()
+									.map(Cow::Borrowed)
+									.unwrap_or_else(|| Cow::Owned(expr.to_idiom()));
+								match expr {
+									Value::Function(f) if f.is_aggregate() => {
+										let x = vals
+											.all()
+											.get(ctx, opt, txn, None, idiom.as_ref())
+											.await?;
+										let x = f.aggregate(x).compute(ctx, opt, txn, None).await?;
+										obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
+									}
+									Value::Function(f) if f.is_grouping() => {
+										// Indicate whether the group field is rolled up in this grouping set
+										let x = match f.args() {
+											[Value::Idiom(i)] => rolled.contains(&i),
+											_ => false,
+										};
+										obj.set(
+											ctx,
+											opt,
+											txn,
+											idiom.as_ref(),
+											Value::from(x as i64),
+										)
+										.await?;
+									}
+									// A rolled up group field has no single value within the group
+									_ if rolled.iter().any(|r| idiom.starts_with(r)) => {
+										obj.set(ctx, opt, txn, idiom.as_ref(), Value::Null).await?;
+									}
+									_ => {
+										let x = vals.first();
+										let x = if let Some(alias) = alias {
+											let cur = (&x).into();
+											alias.compute(ctx, opt, txn, Some(&cur)).await?
+										} else {
+											let cur = (&x).into();
+											expr.compute(ctx, opt, txn, Some(&cur)).await?
+										};
+										obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
+									}
 								}
 							}
 						}
+						// Skip this group if the aggregated values do not match
+						if !check(ctx, opt, txn, &obj, &post).await? {
+							continue;
+						}
+						// Add the object to the results
+						self.results.push(obj);
 					}
-					// Add the object to the results
-					self.results.push(obj);
 				}
 			}
 		}
 		Ok(())
 	}
 
+	/// Sorts the result set according to any ORDER clause.
+	///
+	/// The sort is stable, so records which compare equal on every
+	/// ORDER clause keep the order in which they were iterated. When
+	/// the `STABLE` option is enabled, any remaining ties are broken
+	/// on the record id, so that the output order does not depend on
+	/// the iteration order (for instance with PARALLEL or index scans),
+	/// and paginated queries using START and LIMIT do not skip or
+	/// repeat records across pages. The record id is kept with each
+	/// result until it is sorted, so it does not need to be projected.
+	/// Ordering by RAND() and grouped results are never stable.
+	///
+	/// If the result set grew too large to be sorted in memory, then
+	/// the sorted runs which were spilled to disk during iteration are
+	/// merged with the remaining records, stopping once enough records
+	/// have been output to satisfy any START and LIMIT clauses.
 	#[inline]
 	async fn output_order(
 		&mut self,
-		_ctx: &Context<'_>,
-		_opt: &Options,
+		ctx: &Context<'_>,
+		opt: &Options,
 		_txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
 		if let Some(orders) = stm.order() {
-			// Sort the full result set
-			self.results.sort_by(|a, b| {
-				// Loop over each order clause
-				for order in orders.iter() {
-					// Reverse the ordering if DESC
-					let o = match order.random {
-						true => {
-							let a = rand::random::<f64>();
-							let b = rand::random::<f64>();
-							a.partial_cmp(&b)
-						}
-						false => match order.direction {
-							true => a.compare(b, order, order.collate, order.numeric),
-							false => b.compare(a, order, order.collate, order.numeric),
-						},
-					};
-					//
-					match o {
-						Some(Ordering::Greater) => return Ordering::Greater,
-						Some(Ordering::Equal) => continue,
-						Some(Ordering::Less) => return Ordering::Less,
-						None => continue,
-					}
-				}
-				Ordering::Equal
-			})
+			// Check if the context is finished
+			ctx.check()?;
+			// Check if the results hold their record ids
+			let keyed = keyed(opt, stm);
+			#[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+			let mut sorted = false;
+			// Merge any sorted runs which were spilled to disk
+			#[cfg(not(target_arch = "wasm32"))]
+			if !self.spills.is_empty() {
+				let spills = mem::take(&mut self.spills);
+				let vals = mem::take(&mut self.results);
+				let max = self.limit.map(|l| l + self.start.unwrap_or(0));
+				self.results =
+					sorter::merge(ctx, spills, vals, max, |a, b| compare(orders, keyed, a, b))?;
+				sorted = true;
+			}
+			// Records read in the order of an index may not need a sort
+			if !sorted
+				&& !(ctx.get_query_planner().map_or(false, |p| p.is_ordered())
+					&& self
+						.results
+						.windows(2)
+						.all(|w| compare(orders, keyed, &w[0], &w[1]) != Ordering::Greater))
+			{
+				// Sort the full result set
+				self.results.sort_by(|a, b| compare(orders, keyed, a, b))
+			}
+			// Remove the record ids which were kept to break any ties
+			if keyed {
+				self.results = mem::take(&mut self.results).into_iter().map(unkey).collect();
+			}
 		}
 		Ok(())
 	}
@@ -547,6 +648,8 @@ impl Iterator {
 			for fetch in fetchs.iter() {
 				// Loop over each result value
 				for obj in &mut self.results {
+					// Check if the context is finished
+					ctx.check()?;
 					// Fetch the value at the path
 					obj.fetch(ctx, opt, txn, fetch).await?;
 				}
@@ -566,16 +669,8 @@ impl Iterator {
 	) -> Result<(), Error> {
 		// Prevent deep recursion
 		let opt = &opt.dive(4)?;
-		// If any iterator requires distinct, we new to create a global distinct instance
-		let mut distinct = SyncDistinct::new(ctx);
 		// Process all prepared values
-		for v in mem::take(&mut self.entries) {
-			// Distinct is passed only for iterators that really requires it
-			let dis = SyncDistinct::requires_distinct(ctx, distinct.as_mut(), &v);
-			v.iterate(ctx, opt, txn, stm, self, dis).await?;
-		}
-		// Everything processed ok
-		Ok(())
+		self.iterate_sequential(ctx, opt, txn, stm).await
 	}
 
 	#[cfg(not(target_arch = "wasm32"))]
@@ -592,18 +687,7 @@ impl Iterator {
 		// Check if iterating in parallel
 		match stm.parallel() {
 			// Run statements sequentially
-			false => {
-				// If any iterator requires distinct, we new to create a global distinct instance
-				let mut distinct = SyncDistinct::new(ctx);
-				// Process all prepared values
-				for v in mem::take(&mut self.entries) {
-					// Distinct is passed only for iterators that really requires it
-					let dis = SyncDistinct::requires_distinct(ctx, distinct.as_mut(), &v);
-					v.iterate(ctx, opt, txn, stm, self, dis).await?;
-				}
-				// Everything processed ok
-				Ok(())
-			}
+			false => self.iterate_sequential(ctx, opt, txn, stm).await,
 			// Run statements in parallel
 			true => {
 				// If any iterator requires distinct, we new to create a global distinct instance
@@ -645,8 +729,8 @@ impl Iterator {
 				// Create an async closure to process results
 				let aproc = async {
 					// Process all processed values
-					while let Ok(r) = vals.recv().await {
-						self.result(r, stm);
+					while let Ok((rid, r)) = vals.recv().await {
+						self.result(opt, stm, rid, r);
 					}
 					// Shutdown the executor
 					let _ = end.send(()).await;
@@ -663,6 +747,41 @@ impl Iterator {
 		}
 	}
 
+	/// Process all prepared values one after another
+	async fn iterate_sequential(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// If any iterator requires distinct, we new to create a global distinct instance
+		let mut distinct = SyncDistinct::new(ctx);
+		// Process all prepared values
+		let mut entries = mem::take(&mut self.entries).into_iter().peekable();
+		while let Some(v) = entries.next() {
+			match v {
+				// Consecutive records of a SELECT statement are fetched together
+				Iterable::Thing(v) if stm.is_select() && stm.version().is_none() => {
+					let mut things = vec![v];
+					while let Some(Iterable::Thing(v)) =
+						entries.next_if(|v| matches!(v, Iterable::Thing(_)))
+					{
+						things.push(v);
+					}
+					Iterable::iterate_things(things, ctx, opt, txn, stm, self).await?;
+				}
+				v => {
+					// Distinct is passed only for iterators that really requires it
+					let dis = SyncDistinct::requires_distinct(ctx, distinct.as_mut(), &v);
+					v.iterate(ctx, opt, txn, stm, self, dis).await?;
+				}
+			}
+		}
+		// Everything processed ok
+		Ok(())
+	}
+
 	/// Process a new record Thing and Value
 	pub async fn process(
 		&mut self,
@@ -672,25 +791,54 @@ impl Iterator {
 		stm: &Statement<'_>,
 		pro: Processed,
 	) {
+		// Keep the record id if the results are sorted with it
+		let rid = match keyed(opt, stm) {
+			true => pro.rid.clone(),
+			false => None,
+		};
 		// Process the document
 		let res = Document::process(ctx, opt, txn, stm, pro).await;
 		// Process the result
-		self.result(res, stm);
+		self.result(opt, stm, rid, res);
 	}
 
 	/// Accept a processed record result
-	fn result(&mut self, res: Result<Value, Error>, stm: &Statement<'_>) {
+	#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+	fn result(
+		&mut self,
+		opt: &Options,
+		stm: &Statement<'_>,
+		rid: Option<Thing>,
+		res: Result<Value, Error>,
+	) {
 		// Process the result
 		match res {
 			Err(Error::Ignore) => {
 				return;
 			}
+			// The context was cancelled, either because the
+			// LIMIT was reached, or because the query timed
+			// out, which is checked once iteration finishes
+			Err(Error::QueryTimedout | Error::QueryCancelled) => {
+				return;
+			}
 			Err(e) => {
 				self.error = Some(e);
 				self.run.cancel();
 				return;
 			}
-			Ok(v) => self.results.push(v),
+			// Keep the record id to break any ties when sorting
+			Ok(v) => match keyed(opt, stm) {
+				true => self.results.push(with_key(v, Some(&rid.map_or(Value::None, Value::from)))),
+				false => self.results.push(v),
+			},
+		}
+		// Check if we should spill to disk
+		#[cfg(not(target_arch = "wasm32"))]
+		if let Err(e) = self.spill(opt, stm) {
+			self.error = Some(e);
+			self.run.cancel();
+			return;
 		}
 		// Check if we can exit
 		if stm.group().is_none() && stm.order().is_none() {
@@ -705,4 +853,149 @@ impl Iterator {
 			}
 		}
 	}
+
+	/// Spills the sorted results to disk once the buffer is full
+	#[cfg(not(target_arch = "wasm32"))]
+	fn spill(&mut self, opt: &Options, stm: &Statement<'_>) -> Result<(), Error> {
+		// Records can only be sorted before they are split or grouped
+		if stm.split().is_some() || stm.group().is_some() {
+			return Ok(());
+		}
+		if let Some(orders) = stm.order() {
+			if self.results.len() >= *crate::cnf::EXTERNAL_SORTING_BUFFER_LIMIT {
+				let vals = mem::take(&mut self.results);
+				let keyed = keyed(opt, stm);
+				let spill = Spill::write(vals, |a, b| compare(orders, keyed, a, b))?;
+				self.spills.push(spill);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Whether each result is kept with its record id until the results are sorted,
+/// so that ties are broken on the record id with the `STABLE` option
+fn keyed(opt: &Options, stm: &Statement<'_>) -> bool {
+	opt.stable
+		&& stm.group().is_none()
+		&& stm.order().map_or(false, |o| !o.iter().any(|o| o.random))
+}
+
+/// Splits a result from the record id it is kept with
+fn split_key(v: &Value) -> (&Value, &Value) {
+	match v {
+		Value::Array(a) if a.len() == 2 => (&a[0], &a[1]),
+		v => (v, &Value::None),
+	}
+}
+
+/// Keeps a result with a record id, if the results are keyed
+fn with_key(v: Value, rid: Option<&Value>) -> Value {
+	match rid {
+		Some(rid) => Value::from(vec![v, rid.to_owned()]),
+		None => v,
+	}
+}
+
+/// Removes the record id which a result is kept with
+fn unkey(v: Value) -> Value {
+	match v {
+		Value::Array(mut a) if a.len() == 2 => a.0.swap_remove(0),
+		v => v,
+	}
+}
+
+/// Compares two records according to the ORDER clauses,
+/// breaking any ties on the record ids of keyed results
+fn compare(orders: &Orders, keyed: bool, a: &Value, b: &Value) -> Ordering {
+	let ((a, a_id), (b, b_id)) = match keyed {
+		true => (split_key(a), split_key(b)),
+		false => ((a, &Value::None), (b, &Value::None)),
+	};
+	// Loop over each order clause
+	for order in orders.iter() {
+		// Reverse the ordering if DESC
+		let o = match order.random {
+			true => {
+				let a = rand::random::<f64>();
+				let b = rand::random::<f64>();
+				a.partial_cmp(&b)
+			}
+			false => match order.direction {
+				true => a.compare(b, order, order.collate, order.numeric),
+				false => b.compare(a, order, order.collate, order.numeric),
+			},
+		};
+		//
+		match o {
+			Some(Ordering::Greater) => return Ordering::Greater,
+			Some(Ordering::Equal) => continue,
+			Some(Ordering::Less) => return Ordering::Less,
+			None => continue,
+		}
+	}
+	// Break any ties on the record id
+	if keyed {
+		if let Some(o) = a_id.partial_cmp(b_id) {
+			return o;
+		}
+	}
+	Ordering::Equal
+}
+
+// Split a HAVING clause into the predicates which must all match
+fn predicates<'a>(v: &'a Value, out: &mut Vec<&'a Value>) {
+	match v {
+		Value::Expression(e) => match e.as_ref() {
+			Expression::Binary {
+				l,
+				o: Operator::And,
+				r,
+			} => {
+				predicates(l, out);
+				predicates(r, out);
+			}
+			_ => out.push(v),
+		},
+		v => out.push(v),
+	}
+}
+
+// Check if a predicate only compares grouped fields with static values
+fn grouped(v: &Value, keys: &[&Idiom]) -> bool {
+	let operand = |v: &Value| match v {
+		Value::Idiom(i) => keys.contains(&i),
+		v => v.is_static(),
+	};
+	match v {
+		Value::Expression(e) => match e.as_ref() {
+			Expression::Binary {
+				l,
+				r,
+				..
+			} => operand(l) && operand(r),
+			Expression::Unary {
+				v,
+				..
+			} => operand(v),
+		},
+		_ => false,
+	}
+}
+
+// Check if all of the predicates match a value
+async fn check(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	doc: &Value,
+	predicates: &[&Value],
+) -> Result<bool, Error> {
+	let cur = doc.into();
+	for v in predicates {
+		if !v.compute(ctx, opt, txn, Some(&cur)).await?.is_truthy() {
+			return Ok(false);
+		}
+	}
+	Ok(true)
 }
@@ -2,22 +2,36 @@
 //! In this module we essentially manage the entire lifecycle of a database request acting as the
 //! glue between the API and the response. In this module we use channels as a transport layer
 //! and executors to process the operations. This module also gives a `context` to the transaction.
+mod compactions;
 mod distinct;
 mod executor;
 mod explanation;
 mod iterator;
 mod notification;
 mod options;
+mod quotas;
+mod rebuilds;
 mod response;
 mod session;
+mod sessions;
+#[cfg(not(target_arch = "wasm32"))]
+mod sorter;
 mod statement;
+mod stats;
 mod transaction;
+mod usage;
 mod variables;
 
+pub use self::compactions::*;
 pub use self::notification::*;
 pub use self::options::*;
+pub use self::quotas::*;
+pub use self::rebuilds::*;
 pub use self::response::*;
 pub use self::session::*;
+pub use self::sessions::*;
+pub use self::stats::*;
+pub use self::usage::*;
 
 pub(crate) use self::executor::*;
 pub(crate) use self::iterator::*;
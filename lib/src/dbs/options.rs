@@ -4,6 +4,7 @@ use crate::dbs::Notification;
 use crate::err::Error;
 use crate::iam::{Action, Auth, ResourceKind, Role};
 use crate::sql::Base;
+use crate::sql::Isolation;
 use channel::Sender;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -48,6 +49,16 @@ pub struct Options {
 	pub futures: bool,
 	/// Should we process variable field projections?
 	pub projections: bool,
+	/// Should ORDER BY break ties on the record id?
+	pub stable: bool,
+	/// The isolation level of new transactions, if not the storage engine default
+	pub isolation: Option<Isolation>,
+	/// How many key-value pairs are fetched in each batch when scanning
+	pub batch_size: u32,
+	/// How many times conflicting statements are retried
+	pub retries: u32,
+	/// The size in bytes above which import transactions are split
+	pub split_size: Option<u64>,
 	/// The channel over which we send notifications
 	pub sender: Option<Sender<Notification>>,
 	/// Datastore capabilities
@@ -78,6 +89,11 @@ impl Options {
 			indexes: true,
 			futures: false,
 			projections: false,
+			stable: false,
+			isolation: None,
+			batch_size: *cnf::SCAN_BATCH_SIZE,
+			retries: *cnf::TRANSACTION_RETRY_ATTEMPTS,
+			split_size: None,
 			auth_enabled: true,
 			sender: None,
 			auth: Arc::new(Auth::default()),
@@ -208,6 +224,41 @@ impl Options {
 		self
 	}
 
+	/// Specify whether ORDER BY clauses should be sorted
+	/// deterministically, breaking ties on the record id.
+	pub fn with_stable(mut self, stable: bool) -> Self {
+		self.stable = stable;
+		self
+	}
+
+	/// Specify the isolation level of the transactions
+	/// which are started with this `Options` object.
+	pub fn with_isolation(mut self, isolation: Option<Isolation>) -> Self {
+		self.isolation = isolation;
+		self
+	}
+
+	/// Specify how many key-value pairs are fetched
+	/// in each batch when scanning tables and indexes.
+	pub fn with_batch_size(mut self, batch_size: u32) -> Self {
+		self.batch_size = batch_size.max(1);
+		self
+	}
+
+	/// Specify how many times a statement is retried when
+	/// its transaction conflicts with another transaction.
+	pub fn with_retries(mut self, retries: u32) -> Self {
+		self.retries = retries;
+		self
+	}
+
+	/// Specify the size in bytes above which the transaction of an import
+	/// is committed, and the import continued in a new transaction.
+	pub fn with_split_size(mut self, split_size: Option<u64>) -> Self {
+		self.split_size = split_size;
+		self
+	}
+
 	/// Create a new Options object for a subquery
 	pub fn with_import(mut self, import: bool) -> Self {
 		self.fields = !import;
@@ -1,16 +1,20 @@
+use crate::cf;
 use crate::ctx::Context;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::dbs::distinct::AsyncDistinct;
 use crate::dbs::distinct::SyncDistinct;
 use crate::dbs::{Iterable, Iterator, Operable, Options, Processed, Statement, Transaction};
 use crate::err::Error;
+use crate::idx::ft::docids::DocId;
 use crate::idx::planner::executor::IteratorRef;
 use crate::idx::planner::plan::IndexOption;
 use crate::key::{graph, thing};
+use crate::kvs::{Key, Scanner};
 use crate::sql::dir::Dir;
 use crate::sql::{Edges, Range, Table, Thing, Value};
 #[cfg(not(target_arch = "wasm32"))]
 use channel::Sender;
+use std::collections::BTreeMap;
 use std::ops::Bound;
 
 impl Iterable {
@@ -26,6 +30,18 @@ impl Iterable {
 		Processor::Iterator(dis, ite).process_iterable(ctx, opt, txn, stm, self).await
 	}
 
+	/// Processes several records, which are fetched from the store together
+	pub(crate) async fn iterate_things(
+		things: Vec<Thing>,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		ite: &mut Iterator,
+	) -> Result<(), Error> {
+		Processor::Iterator(None, ite).process_things(ctx, opt, txn, stm, things).await
+	}
+
 	#[cfg(not(target_arch = "wasm32"))]
 	pub(crate) async fn channel(
 		self,
@@ -140,13 +156,17 @@ impl<'a> Processor<'a> {
 		// Check that the table exists
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 		// Fetch the data from the store
-		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-		let val = txn.clone().lock().await.get(key).await?;
+		let key: Key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id).into();
+		let val = match Self::originals(opt, txn, stm, &v.tb).await?.remove(&key) {
+			// The record has changed since the specified version
+			Some(v) => v,
+			None => match txn.clone().lock().await.get(key).await? {
+				Some(v) => Value::from(v),
+				None => Value::None,
+			},
+		};
 		// Parse the data from the store
-		let val = Operable::Value(match val {
-			Some(v) => Value::from(v),
-			None => Value::None,
-		});
+		let val = Operable::Value(val);
 		// Process the document record
 		let pro = Processed {
 			ir: None,
@@ -159,6 +179,86 @@ impl<'a> Processor<'a> {
 		Ok(())
 	}
 
+	async fn process_things(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		v: Vec<Thing>,
+	) -> Result<(), Error> {
+		if ctx.is_ok() {
+			// Check that the tables exist
+			for v in v.iter() {
+				txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
+			}
+			// Process the records in batches
+			let mut v = v.into_iter().map(|v| (v, None)).peekable();
+			while v.peek().is_some() {
+				let records = v.by_ref().take(opt.batch_size as usize).collect();
+				self.process_records(ctx, opt, txn, stm, None, records).await?;
+			}
+		}
+		// Everything ok
+		Ok(())
+	}
+
+	/// Fetches and processes the records in order
+	///
+	/// The records of a SELECT statement are fetched with one batched read. Any
+	/// other statement can modify the records which follow the current record,
+	/// so these are fetched one by one, just before they are processed.
+	async fn process_records(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		ir: Option<IteratorRef>,
+		records: Vec<(Thing, Option<DocId>)>,
+	) -> Result<(), Error> {
+		// Fetch the data of all records from the store
+		let mut vals = match stm.is_select() {
+			true => {
+				let keys = records
+					.iter()
+					.map(|(v, _)| thing::new(opt.ns(), opt.db(), &v.tb, &v.id))
+					.collect();
+				Some(txn.lock().await.multi_get(keys).await?.into_iter())
+			}
+			false => None,
+		};
+		for (rid, doc_id) in records {
+			// Check the context
+			if ctx.is_done() {
+				break;
+			}
+			// Fetch the data from the store
+			let val = match vals.as_mut() {
+				Some(vals) => vals.next().flatten(),
+				None => {
+					let key = thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+					txn.lock().await.get(key).await?
+				}
+			};
+			// Parse the data from the store
+			let val = Operable::Value(match val {
+				Some(v) => Value::from(v),
+				None => Value::None,
+			});
+			// Process the document record
+			let pro = Processed {
+				ir,
+				rid: Some(rid),
+				doc_id,
+				val,
+			};
+			self.process(ctx, opt, txn, stm, pro).await?;
+		}
+		// Everything ok
+		Ok(())
+	}
+
 	async fn process_mergeable(
 		&mut self,
 		ctx: &Context<'_>,
@@ -240,63 +340,10 @@ impl<'a> Processor<'a> {
 		// Prepare the start and end keys
 		let beg = thing::prefix(opt.ns(), opt.db(), &v);
 		let end = thing::suffix(opt.ns(), opt.db(), &v);
-		// Prepare the next holder key
-		let mut nxt: Option<Vec<u8>> = None;
-		// Loop until no more keys
-		loop {
-			// Check if the context is finished
-			if ctx.is_done() {
-				break;
-			}
-			// Get the next 1000 key-value entries
-			let res = match nxt {
-				None => {
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, 1000).await?
-				}
-				Some(ref mut beg) => {
-					beg.push(0x00);
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, 1000).await?
-				}
-			};
-			// If there are key-value entries then fetch them
-			if !res.is_empty() {
-				// Get total results
-				let n = res.len();
-				// Loop over results
-				for (i, (k, v)) in res.into_iter().enumerate() {
-					// Check the context
-					if ctx.is_done() {
-						break;
-					}
-					// Ready the next
-					if n == i + 1 {
-						nxt = Some(k.clone());
-					}
-					// Parse the data from the store
-					let key: thing::Thing = (&k).into();
-					let val: Value = (&v).into();
-					let rid = Thing::from((key.tb, key.id));
-					// Create a new operable value
-					let val = Operable::Value(val);
-					// Process the record
-					let pro = Processed {
-						ir: None,
-						rid: Some(rid),
-						doc_id: None,
-						val,
-					};
-					self.process(ctx, opt, txn, stm, pro).await?;
-				}
-				continue;
-			}
-			break;
-		}
-		// Everything ok
-		Ok(())
+		// Read the history of the table if needed
+		let originals = Self::originals(opt, txn, stm, &v).await?;
+		// Process the records within the range
+		self.process_scan(ctx, opt, txn, stm, beg, end, originals).await
 	}
 
 	async fn process_range(
@@ -329,65 +376,125 @@ impl<'a> Processor<'a> {
 				key
 			}
 		};
-		// Prepare the next holder key
-		let mut nxt: Option<Vec<u8>> = None;
+		// Read the history of the table if needed
+		let originals = Self::originals(opt, txn, stm, &v.tb).await?;
+		// Process the records within the range
+		self.process_scan(ctx, opt, txn, stm, beg, end, originals).await
+	}
+
+	/// Reads the values which the records of a table had at the VERSION
+	/// of the statement, for all of the records which changed since then
+	async fn originals(
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		tb: &str,
+	) -> Result<BTreeMap<Key, Value>, Error> {
+		match stm.version() {
+			Some(v) => {
+				let mut run = txn.lock().await;
+				let tb = run.get_and_cache_tb(opt.ns(), opt.db(), tb).await?;
+				cf::originals(&mut run, opt.ns(), opt.db(), &tb, &v.0, opt.batch_size).await
+			}
+			None => Ok(BTreeMap::new()),
+		}
+	}
+
+	/// Processes the records within a range of keys, one batch at a time
+	///
+	/// The records which have changed since the VERSION of the statement are
+	/// processed with their original values, in the order of their keys.
+	#[allow(clippy::too_many_arguments)]
+	async fn process_scan(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		beg: Vec<u8>,
+		end: Vec<u8>,
+		mut originals: BTreeMap<Key, Value>,
+	) -> Result<(), Error> {
+		// Only the changed records within the range are needed
+		originals.retain(|k, _| *k >= beg && *k < end);
+		// Scan the record keys in batches
+		let mut scanner = Scanner::new(beg..end, opt.batch_size);
 		// Loop until no more keys
 		loop {
 			// Check if the context is finished
 			if ctx.is_done() {
 				break;
 			}
-			// Get the next 1000 key-value entries
-			let res = match nxt {
-				None => {
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, 1000).await?
-				}
-				Some(ref mut beg) => {
-					beg.push(0x00);
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, 1000).await?
+			// Get the next batch of key-value entries
+			let res = scanner.next(&mut *txn.lock().await).await?;
+			// Exit when settled
+			if res.is_empty() {
+				break;
+			}
+			// Loop over results
+			for (k, v) in res.into_iter() {
+				// Check the context
+				if ctx.is_done() {
+					break;
 				}
-			};
-			// If there are key-value entries then fetch them
-			if !res.is_empty() {
-				// Get total results
-				let n = res.len();
-				// Loop over results
-				for (i, (k, v)) in res.into_iter().enumerate() {
-					// Check the context
-					if ctx.is_done() {
+				// Process the deleted records which come before this record
+				while let Some(e) = originals.first_entry() {
+					if e.key() >= &k {
 						break;
 					}
-					// Ready the next
-					if n == i + 1 {
-						nxt = Some(k.clone());
-					}
-					// Parse the data from the store
-					let key: thing::Thing = (&k).into();
-					let val: Value = (&v).into();
-					let rid = Thing::from((key.tb, key.id));
-					// Create a new operable value
-					let val = Operable::Value(val);
-					// Process the record
-					let pro = Processed {
-						ir: None,
-						rid: Some(rid),
-						doc_id: None,
-						val,
-					};
-					self.process(ctx, opt, txn, stm, pro).await?;
+					let (k, v) = e.remove_entry();
+					self.process_key(ctx, opt, txn, stm, &k, v).await?;
 				}
-				continue;
+				// Parse the data from the store
+				let val = match originals.remove(&k) {
+					Some(v) => v,
+					None => (&v).into(),
+				};
+				// Process the record
+				self.process_key(ctx, opt, txn, stm, &k, val).await?;
 			}
-			break;
+		}
+		// Process the deleted records which come after all others
+		for (k, v) in originals {
+			// Check the context
+			if ctx.is_done() {
+				break;
+			}
+			self.process_key(ctx, opt, txn, stm, &k, v).await?;
 		}
 		// Everything ok
 		Ok(())
 	}
 
+	/// Processes the value of a record which is stored at the given key
+	async fn process_key(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		k: &Key,
+		val: Value,
+	) -> Result<(), Error> {
+		// Skip records which did not exist at the version
+		if val.is_none() {
+			return Ok(());
+		}
+		// Parse the record id from the key
+		let key: thing::Thing = k.into();
+		let rid = Thing::from((key.tb, key.id));
+		// Create a new operable value
+		let val = Operable::Value(val);
+		// Process the record
+		let pro = Processed {
+			ir: None,
+			rid: Some(rid),
+			doc_id: None,
+			val,
+		};
+		self.process(ctx, opt, txn, stm, pro).await
+	}
+
 	async fn process_edge(
 		&mut self,
 		ctx: &Context<'_>,
@@ -465,70 +572,31 @@ impl<'a> Processor<'a> {
 			},
 		};
 		//
-		for (beg, end) in keys.iter() {
-			// Prepare the next holder key
-			let mut nxt: Option<Vec<u8>> = None;
+		for (beg, end) in keys.into_iter() {
+			// Scan the graph keys in batches
+			let mut scanner = Scanner::new(beg..end, opt.batch_size);
 			// Loop until no more keys
 			loop {
 				// Check if the context is finished
 				if ctx.is_done() {
 					break;
 				}
-				// Get the next 1000 key-value entries
-				let res = match nxt {
-					None => {
-						let min = beg.clone();
-						let max = end.clone();
-						txn.lock().await.scan(min..max, 1000).await?
-					}
-					Some(ref mut beg) => {
-						beg.push(0x00);
-						let min = beg.clone();
-						let max = end.clone();
-						txn.lock().await.scan(min..max, 1000).await?
-					}
-				};
-				// If there are key-value entries then fetch them
-				if !res.is_empty() {
-					// Get total results
-					let n = res.len();
-					// Exit when settled
-					if n == 0 {
-						break;
-					}
-					// Loop over results
-					for (i, (k, _)) in res.into_iter().enumerate() {
-						// Check the context
-						if ctx.is_done() {
-							break;
-						}
-						// Ready the next
-						if n == i + 1 {
-							nxt = Some(k.clone());
-						}
-						// Parse the data from the store
-						let gra: graph::Graph = (&k).into();
-						// Fetch the data from the store
-						let key = thing::new(opt.ns(), opt.db(), gra.ft, &gra.fk);
-						let val = txn.lock().await.get(key).await?;
-						let rid = Thing::from((gra.ft, gra.fk));
-						// Parse the data from the store
-						let val = Operable::Value(match val {
-							Some(v) => Value::from(v),
-							None => Value::None,
-						});
-						// Process the record
-						let pro = Processed {
-							ir: None,
-							rid: Some(rid),
-							doc_id: None,
-							val,
-						};
-						self.process(ctx, opt, txn, stm, pro).await?;
-					}
-					continue;
+				// Get the next batch of key-value entries
+				let res = scanner.next(&mut *txn.lock().await).await?;
+				// Exit when settled
+				if res.is_empty() {
+					break;
 				}
-				break;
+				// Parse the records from the graph keys
+				let records = res
+					.iter()
+					.map(|(k, _)| {
+						let gra: graph::Graph = k.into();
+						(Thing::from((gra.ft, gra.fk)), None)
+					})
+					.collect();
+				// Process the records
+				self.process_records(ctx, opt, txn, stm, None, records).await?;
 			}
 		}
 		// Everything ok
@@ -550,46 +618,57 @@ impl<'a> Processor<'a> {
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &table.0, opt.strict).await?;
 		if let Some(pla) = ctx.get_query_planner() {
 			if let Some(exe) = pla.get_query_executor(&table.0) {
+				let covering = io.covering().cloned();
 				if let Some(mut iterator) = exe.new_iterator(opt, ir, io).await? {
-					let mut things = iterator.next_batch(txn, 1000).await?;
+					// The records are built from the values of the index
+					if let Some(cols) = covering {
+						let mut things = iterator.next_covered_batch(txn, opt.batch_size).await?;
+						while !things.is_empty() {
+							// Check if the context is finished
+							if ctx.is_done() {
+								break;
+							}
+							for (rid, fd) in things {
+								// If a record is from another table we can skip it
+								if !rid.tb.eq(table.as_str()) {
+									continue;
+								}
+								let mut val = Value::base();
+								val.def(&rid);
+								for (col, v) in cols.iter().zip(fd) {
+									val.put(col, v);
+								}
+								let pro = Processed {
+									ir: Some(ir),
+									rid: Some(rid),
+									doc_id: None,
+									val: Operable::Value(val),
+								};
+								self.process(ctx, opt, txn, stm, pro).await?;
+							}
+							// Collect the next batch of records
+							things = iterator.next_covered_batch(txn, opt.batch_size).await?;
+						}
+						return Ok(());
+					}
+					let mut things = iterator.next_batch(txn, opt.batch_size).await?;
 					while !things.is_empty() {
 						// Check if the context is finished
 						if ctx.is_done() {
 							break;
 						}
 
-						for (thing, doc_id) in things {
-							// Check the context
-							if ctx.is_done() {
-								break;
-							}
-
-							// If the record is from another table we can skip
-							if !thing.tb.eq(table.as_str()) {
-								continue;
-							}
-
-							// Fetch the data from the store
-							let key = thing::new(opt.ns(), opt.db(), &table.0, &thing.id);
-							let val = txn.lock().await.get(key.clone()).await?;
-							let rid = Thing::from((key.tb, key.id));
-							// Parse the data from the store
-							let val = Operable::Value(match val {
-								Some(v) => Value::from(v),
-								None => Value::None,
-							});
-							// Process the document record
-							let pro = Processed {
-								ir: Some(ir),
-								rid: Some(rid),
-								doc_id: Some(doc_id),
-								val,
-							};
-							self.process(ctx, opt, txn, stm, pro).await?;
-						}
+						// If a record is from another table we can skip it
+						let records = things
+							.into_iter()
+							.filter(|(thing, _)| thing.tb.eq(table.as_str()))
+							.map(|(thing, doc_id)| (thing, Some(doc_id)))
+							.collect();
+						// Process the records
+						self.process_records(ctx, opt, txn, stm, Some(ir), records).await?;
 
 						// Collect the next batch of ids
-						things = iterator.next_batch(txn, 1000).await?;
+						things = iterator.next_batch(txn, opt.batch_size).await?;
 					}
 					// Everything ok
 					return Ok(());
@@ -0,0 +1,82 @@
+use crate::dbs::Usage;
+use crate::err::Error;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+/// A registry of the storage quotas of the namespaces and databases of a
+/// datastore, which are enforced whenever a record, index entry, or graph
+/// edge is written
+#[derive(Clone, Debug, Default)]
+pub struct Quotas {
+	// The maximum storage in bytes used by each namespace
+	namespaces: Arc<RwLock<BTreeMap<String, u64>>>,
+	// The maximum storage in bytes used by each database
+	databases: Arc<RwLock<BTreeMap<(String, String), u64>>>,
+}
+
+impl Quotas {
+	/// Set the maximum storage in bytes used by a namespace, or remove its quota
+	pub fn set_namespace(&self, ns: &str, bytes: Option<u64>) {
+		let mut namespaces = self.namespaces.write().unwrap();
+		match bytes {
+			Some(v) => namespaces.insert(ns.to_owned(), v),
+			None => namespaces.remove(ns),
+		};
+	}
+
+	/// Set the maximum storage in bytes used by a database, or remove its quota
+	pub fn set_database(&self, ns: &str, db: &str, bytes: Option<u64>) {
+		let mut databases = self.databases.write().unwrap();
+		match bytes {
+			Some(v) => databases.insert((ns.to_owned(), db.to_owned()), v),
+			None => databases.remove(&(ns.to_owned(), db.to_owned())),
+		};
+	}
+
+	/// Output the maximum storage in bytes used by a namespace, if it has a quota
+	pub fn namespace(&self, ns: &str) -> Option<u64> {
+		self.namespaces.read().unwrap().get(ns).copied()
+	}
+
+	/// Output the maximum storage in bytes used by a database, if it has a quota
+	pub fn database(&self, ns: &str, db: &str) -> Option<u64> {
+		let key = (ns.to_owned(), db.to_owned());
+		self.databases.read().unwrap().get(&key).copied()
+	}
+
+	/// Check whether any quotas have been set
+	pub(crate) fn is_empty(&self) -> bool {
+		self.namespaces.read().unwrap().is_empty() && self.databases.read().unwrap().is_empty()
+	}
+
+	/// Check that writing the bytes to a database stays within the quotas of the database
+	/// and its namespace, given the bytes which have already been written in the transaction
+	pub(crate) fn check(
+		&self,
+		usage: &Usage,
+		written: &HashMap<(String, String), u64>,
+		ns: &str,
+		db: &str,
+		bytes: u64,
+	) -> Result<(), Error> {
+		if let Some(quota) = self.namespace(ns) {
+			let pending: u64 = written.iter().filter(|((n, _), _)| n == ns).map(|(_, v)| v).sum();
+			if usage.namespace(ns) + pending + bytes > quota {
+				return Err(Error::NsQuotaExceeded {
+					value: ns.to_owned(),
+					quota,
+				});
+			}
+		}
+		if let Some(quota) = self.database(ns, db) {
+			let pending = written.get(&(ns.to_owned(), db.to_owned())).copied().unwrap_or_default();
+			if usage.database(ns, db) + pending + bytes > quota {
+				return Err(Error::DbQuotaExceeded {
+					value: db.to_owned(),
+					quota,
+				});
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,99 @@
+use crate::sql::{Datetime, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A registry of the index rebuilds which have run on a datastore
+#[derive(Clone, Debug, Default)]
+pub struct Rebuilds(Arc<RwLock<BTreeMap<(String, String, String, String), Arc<Entry>>>>);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Status {
+	Building,
+	Ready,
+	Failed,
+}
+
+impl fmt::Display for Status {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Building => f.write_str("building"),
+			Self::Ready => f.write_str("ready"),
+			Self::Failed => f.write_str("failed"),
+		}
+	}
+}
+
+#[derive(Debug)]
+struct Entry {
+	// When the rebuild was started
+	started: Datetime,
+	// The number of records in the table
+	total: u64,
+	// The number of records which have been processed
+	processed: AtomicU64,
+	// The current state of the rebuild
+	status: Mutex<Status>,
+}
+
+/// Tracks the progress of a running index rebuild. If the
+/// rebuild is dropped before it has finished, it is marked
+/// as having failed.
+pub(crate) struct Rebuild(Arc<Entry>);
+
+impl Rebuild {
+	/// Record that a number of records have been processed
+	pub(crate) fn progress(&self, count: u64) {
+		self.0.processed.fetch_add(count, Ordering::Relaxed);
+	}
+	/// Mark the rebuild as having completed successfully
+	pub(crate) fn finish(self) {
+		*self.0.status.lock().unwrap() = Status::Ready;
+	}
+}
+
+impl Drop for Rebuild {
+	fn drop(&mut self) {
+		let mut status = self.0.status.lock().unwrap();
+		if *status == Status::Building {
+			*status = Status::Failed;
+		}
+	}
+}
+
+impl Rebuilds {
+	/// Register the start of an index rebuild, replacing
+	/// the progress of any previous rebuild of the index
+	pub(crate) fn start(&self, ns: &str, db: &str, tb: &str, ix: &str, total: u64) -> Rebuild {
+		let key = (ns.to_owned(), db.to_owned(), tb.to_owned(), ix.to_owned());
+		let entry = Arc::new(Entry {
+			started: Datetime::default(),
+			total,
+			processed: AtomicU64::new(0),
+			status: Mutex::new(Status::Building),
+		});
+		self.0.write().unwrap().insert(key, entry.clone());
+		Rebuild(entry)
+	}
+
+	/// Forget the progress of any rebuilds of an index
+	pub(crate) fn remove(&self, ns: &str, db: &str, tb: &str, ix: &str) {
+		let key = (ns.to_owned(), db.to_owned(), tb.to_owned(), ix.to_owned());
+		self.0.write().unwrap().remove(&key);
+	}
+
+	/// Output the progress of the latest rebuild of an index
+	pub(crate) fn info(&self, ns: &str, db: &str, tb: &str, ix: &str) -> Value {
+		let key = (ns.to_owned(), db.to_owned(), tb.to_owned(), ix.to_owned());
+		match self.0.read().unwrap().get(&key) {
+			Some(v) => Value::from(map! {
+				"status".to_string() => v.status.lock().unwrap().to_string().into(),
+				"started".to_string() => v.started.clone().into(),
+				"processed".to_string() => v.processed.load(Ordering::Relaxed).into(),
+				"total".to_string() => v.total.into(),
+			}),
+			None => Value::None,
+		}
+	}
+}
@@ -1,4 +1,5 @@
 use crate::ctx::Context;
+use crate::dbs::Capabilities;
 use crate::iam::Auth;
 use crate::iam::{Level, Role};
 use crate::sql::value::Value;
@@ -27,6 +28,10 @@ pub struct Session {
 	pub tk: Option<Value>,
 	/// The current scope authentication data
 	pub sd: Option<Value>,
+	/// The capabilities of the session, which override those of the datastore
+	pub capabilities: Option<Arc<Capabilities>>,
+	/// Whether the session runs in strict mode, which overrides the mode of the datastore
+	pub strict: Option<bool>,
 }
 
 impl Session {
@@ -48,6 +53,18 @@ impl Session {
 		self
 	}
 
+	/// Set the capabilities of the session, instead of using those of the datastore
+	pub fn with_capabilities(mut self, capabilities: Capabilities) -> Session {
+		self.capabilities = Some(Arc::new(capabilities));
+		self
+	}
+
+	/// Set whether the session runs in strict mode, instead of using the mode of the datastore
+	pub fn with_strict(mut self, strict: bool) -> Session {
+		self.strict = Some(strict);
+		self
+	}
+
 	// Set the realtime functionality of the session
 	pub fn with_rt(mut self, rt: bool) -> Session {
 		self.rt = rt;
@@ -132,6 +149,8 @@ impl Session {
 			sc: Some(sc.to_owned()),
 			tk: None,
 			sd: Some(rid),
+			capabilities: None,
+			strict: None,
 		}
 	}
 
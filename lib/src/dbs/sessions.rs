@@ -0,0 +1,153 @@
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::iam::Auth;
+use crate::sql::{Array, Datetime, Duration, Uuid, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
+
+/// A registry of the connected sessions on a datastore
+#[derive(Clone, Debug, Default)]
+pub struct Sessions(Arc<RwLock<BTreeMap<String, Entry>>>);
+
+#[derive(Debug)]
+struct Entry {
+	// The session authentication information
+	au: Arc<Auth>,
+	// The connection IP address
+	ip: Option<String>,
+	// The connection origin
+	or: Option<String>,
+	// The currently selected namespace
+	ns: Option<String>,
+	// The currently selected database
+	db: Option<String>,
+	// When the session was first seen
+	connected: Datetime,
+	// When the session last ran a query
+	active: Datetime,
+	// The live queries started by the session
+	lq: BTreeSet<Uuid>,
+	// Whether the session has been terminated
+	terminated: bool,
+}
+
+impl Sessions {
+	/// Register activity for a realtime session, which is identified by the
+	/// connection id assigned by the server. Other sessions are not registered,
+	/// as their ids are supplied by the client, and they have no connection.
+	pub(crate) fn touch(&self, sess: &Session) -> Result<(), Error> {
+		if let (true, Some(id)) = (sess.rt, &sess.id) {
+			let mut sessions = self.0.write().unwrap();
+			match sessions.get_mut(id) {
+				// The session has been terminated
+				Some(v) if v.terminated => {
+					return Err(Error::SessionTerminated {
+						value: id.to_owned(),
+					})
+				}
+				// The session is already registered
+				Some(v) => {
+					v.au = sess.au.clone();
+					v.ns = sess.ns.clone();
+					v.db = sess.db.clone();
+					v.active = Datetime::default();
+				}
+				// This is a new session
+				None => {
+					sessions.insert(
+						id.to_owned(),
+						Entry {
+							au: sess.au.clone(),
+							ip: sess.ip.clone(),
+							or: sess.or.clone(),
+							ns: sess.ns.clone(),
+							db: sess.db.clone(),
+							connected: Datetime::default(),
+							active: Datetime::default(),
+							lq: BTreeSet::new(),
+							terminated: false,
+						},
+					);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Record a live query started by a session
+	pub(crate) fn add_live(&self, id: &str, lq: Uuid) {
+		if let Some(v) = self.0.write().unwrap().get_mut(id) {
+			v.lq.insert(lq);
+		}
+	}
+
+	/// Forget a live query which has been killed
+	pub(crate) fn del_live(&self, lq: &Uuid) {
+		for v in self.0.write().unwrap().values_mut() {
+			v.lq.remove(lq);
+		}
+	}
+
+	/// Terminate a session, returning the live queries it had started
+	pub(crate) fn terminate(&self, id: &str) -> Result<Vec<Uuid>, Error> {
+		match self.0.write().unwrap().get_mut(id) {
+			Some(v) if !v.terminated => {
+				v.terminated = true;
+				Ok(std::mem::take(&mut v.lq).into_iter().collect())
+			}
+			_ => Err(Error::SessionNotFound {
+				value: id.to_owned(),
+			}),
+		}
+	}
+
+	/// Check if a session has been terminated
+	pub fn is_terminated(&self, id: &str) -> bool {
+		matches!(self.0.read().unwrap().get(id), Some(v) if v.terminated)
+	}
+
+	/// Remove a session from the registry, once its connection has closed
+	pub fn remove(&self, id: &str) {
+		self.0.write().unwrap().remove(id);
+	}
+
+	/// Remove the sessions which have been idle for longer than the timeout, in
+	/// case their connections closed without removing them. Sessions which have
+	/// been terminated, or which have started live queries, are kept until then.
+	pub(crate) fn expire(&self, timeout: std::time::Duration) {
+		let now = Datetime::default();
+		self.0.write().unwrap().retain(|_, v| {
+			let idle = (now.0 - v.active.0).to_std().unwrap_or_default();
+			v.terminated || !v.lq.is_empty() || idle < timeout
+		});
+	}
+
+	/// Output the active sessions
+	pub(crate) fn info(&self) -> Value {
+		let now = Datetime::default();
+		self.0
+			.read()
+			.unwrap()
+			.iter()
+			.filter(|(_, v)| !v.terminated)
+			.map(|(id, v)| {
+				let idle = (now.0 - v.active.0).to_std().unwrap_or_default();
+				Value::from(map! {
+					"id".to_string() => id.to_owned().into(),
+					"auth".to_string() => Value::from(map! {
+						"id".to_string() => v.au.id().to_owned().into(),
+						"level".to_string() => v.au.level().to_string().into(),
+					}),
+					"ip".to_string() => v.ip.to_owned().into(),
+					"or".to_string() => v.or.to_owned().into(),
+					"ns".to_string() => v.ns.to_owned().into(),
+					"db".to_string() => v.db.to_owned().into(),
+					"connected".to_string() => v.connected.clone().into(),
+					"idle".to_string() => Duration::from(idle).into(),
+					"live".to_string() => v.lq.iter().cloned().map(Value::from).collect::<Array>().into(),
+				})
+			})
+			.collect::<Array>()
+			.into()
+	}
+}
@@ -0,0 +1,169 @@
+use crate::ctx::Context;
+use crate::err::Error;
+use crate::sql::value::Value;
+use revision::Revisioned;
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::mem;
+use std::path::PathBuf;
+use ulid::Ulid;
+
+/// A sorted run of records which has been spilled to disk, so
+/// that large ORDER BY result sets do not need to be held in
+/// memory whilst they are being sorted. The file is removed
+/// when the run is dropped.
+pub(super) struct Spill {
+	path: PathBuf,
+	len: usize,
+}
+
+impl Drop for Spill {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.path);
+	}
+}
+
+impl Spill {
+	/// Sorts the records, and writes them to a temporary file
+	pub(super) fn write<F>(mut vals: Vec<Value>, cmp: F) -> Result<Self, Error>
+	where
+		F: Fn(&Value, &Value) -> Ordering,
+	{
+		// Sort the records in this run
+		vals.sort_by(cmp);
+		// Create the temporary file
+		let path = std::env::temp_dir().join(format!("surrealdb-sort-{}", Ulid::new()));
+		let spill = Self {
+			path,
+			len: vals.len(),
+		};
+		// Write the records to the file
+		let mut file = BufWriter::new(File::create(&spill.path)?);
+		for v in vals.iter() {
+			v.serialize_revisioned(&mut file)?;
+		}
+		file.flush()?;
+		// The run was spilled successfully
+		Ok(spill)
+	}
+
+	/// Opens the run, for reading the records in order
+	fn read(&self) -> Result<Reader, Error> {
+		Ok(Reader {
+			file: BufReader::new(File::open(&self.path)?),
+			len: self.len,
+		})
+	}
+}
+
+/// Reads the records back from a spilled run
+struct Reader {
+	file: BufReader<File>,
+	len: usize,
+}
+
+impl Reader {
+	fn next(&mut self) -> Result<Option<Value>, Error> {
+		match self.len {
+			0 => Ok(None),
+			_ => {
+				self.len -= 1;
+				Ok(Some(Value::deserialize_revisioned(&mut self.file)?))
+			}
+		}
+	}
+}
+
+/// Merges the spilled runs with the remaining in-memory records,
+/// outputting at most `max` records in sorted order.
+pub(super) fn merge<F>(
+	ctx: &Context<'_>,
+	spills: Vec<Spill>,
+	mut vals: Vec<Value>,
+	max: Option<usize>,
+	cmp: F,
+) -> Result<Vec<Value>, Error>
+where
+	F: Fn(&Value, &Value) -> Ordering,
+{
+	// Sort the records which are still in memory
+	vals.sort_by(&cmp);
+	let mut vals = vals.into_iter();
+	// Open each of the spilled runs
+	let mut runs = spills.iter().map(Spill::read).collect::<Result<Vec<_>, _>>()?;
+	// Fetch the first record from each source
+	let mut heads = Vec::with_capacity(runs.len() + 1);
+	for run in runs.iter_mut() {
+		heads.push(run.next()?);
+	}
+	heads.push(vals.next());
+	// Output the smallest record each time
+	let max = max.unwrap_or(usize::MAX);
+	let mut out = Vec::new();
+	while out.len() < max {
+		// Check if the context is finished
+		ctx.check()?;
+		// Find the source with the smallest record
+		let mut min: Option<(usize, &Value)> = None;
+		for (i, v) in heads.iter().enumerate() {
+			if let Some(v) = v {
+				min = match min {
+					Some((m, w)) if cmp(v, w) != Ordering::Less => Some((m, w)),
+					_ => Some((i, v)),
+				};
+			}
+		}
+		// All of the sources have been exhausted
+		let Some((i, _)) = min else {
+			break;
+		};
+		// Replace the record with the next one from the source
+		let next = match runs.get_mut(i) {
+			Some(run) => run.next()?,
+			None => vals.next(),
+		};
+		if let Some(v) = mem::replace(&mut heads[i], next) {
+			out.push(v);
+		}
+	}
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn values(v: &[i64]) -> Vec<Value> {
+		v.iter().map(|v| Value::from(*v)).collect()
+	}
+
+	#[test]
+	fn merge_spilled_runs() {
+		let ctx = Context::default();
+		let cmp = |a: &Value, b: &Value| a.cmp(b);
+		let a = Spill::write(values(&[9, 1, 5]), cmp).unwrap();
+		let b = Spill::write(values(&[4, 8, 2]), cmp).unwrap();
+		let out = merge(&ctx, vec![a, b], values(&[7, 3, 6]), None, cmp).unwrap();
+		assert_eq!(out, values(&[1, 2, 3, 4, 5, 6, 7, 8, 9]));
+	}
+
+	#[test]
+	fn merge_spilled_runs_with_max() {
+		let ctx = Context::default();
+		let cmp = |a: &Value, b: &Value| b.cmp(a);
+		let a = Spill::write(values(&[9, 1, 5]), cmp).unwrap();
+		let b = Spill::write(values(&[4, 8, 2]), cmp).unwrap();
+		let out = merge(&ctx, vec![a, b], vec![], Some(4), cmp).unwrap();
+		assert_eq!(out, values(&[9, 8, 5, 4]));
+	}
+
+	#[test]
+	fn spilled_runs_are_removed() {
+		let spill = Spill::write(values(&[1, 2, 3]), |a, b| a.cmp(b)).unwrap();
+		let path = spill.path.clone();
+		assert!(path.exists());
+		drop(spill);
+		assert!(!path.exists());
+	}
+}
@@ -2,7 +2,7 @@ use crate::sql::cond::Cond;
 use crate::sql::data::Data;
 use crate::sql::fetch::Fetchs;
 use crate::sql::field::Fields;
-use crate::sql::group::Groups;
+use crate::sql::group::{Grouping, Groups};
 use crate::sql::idiom::Idioms;
 use crate::sql::limit::Limit;
 use crate::sql::order::Orders;
@@ -17,6 +17,8 @@ use crate::sql::statements::relate::RelateStatement;
 use crate::sql::statements::select::SelectStatement;
 use crate::sql::statements::show::ShowStatement;
 use crate::sql::statements::update::UpdateStatement;
+use crate::sql::version::Version;
+use crate::sql::with::With;
 use crate::sql::Explain;
 use std::fmt;
 
@@ -161,6 +163,22 @@ impl<'a> Statement<'a> {
 			_ => None,
 		}
 	}
+	/// Returns the grouping sets of any GROUP clause
+	#[inline]
+	pub fn grouping(&self) -> Grouping {
+		match self {
+			Statement::Select(v) => v.grouping,
+			_ => Grouping::Simple,
+		}
+	}
+	/// Returns any HAVING clause if specified
+	#[inline]
+	pub fn having(&self) -> Option<&Cond> {
+		match self {
+			Statement::Select(v) => v.having.as_ref(),
+			_ => None,
+		}
+	}
 	/// Returns any ORDER clause if specified
 	#[inline]
 	pub fn order(&self) -> Option<&Orders> {
@@ -215,7 +233,32 @@ impl<'a> Statement<'a> {
 			Statement::Update(v) => v.parallel,
 			Statement::Relate(v) => v.parallel,
 			Statement::Delete(v) => v.parallel,
-			Statement::Insert(v) => v.parallel,
+			// The records are processed in turn so that the changes of a failing record can be undone
+			Statement::Insert(v) => v.parallel && !v.partial,
+			_ => false,
+		}
+	}
+	/// Returns whether per-record errors should be reported
+	#[inline]
+	pub fn partial(&self) -> bool {
+		match self {
+			Statement::Insert(v) => v.partial,
+			_ => false,
+		}
+	}
+	/// Returns whether an existing record should be left unchanged
+	#[inline]
+	pub fn if_not_exists(&self) -> bool {
+		match self {
+			Statement::Create(v) => v.if_not_exists,
+			_ => false,
+		}
+	}
+	/// Returns whether the returned records should be locked
+	#[inline]
+	pub fn lock(&self) -> bool {
+		match self {
+			Statement::Select(v) => v.lock,
 			_ => false,
 		}
 	}
@@ -227,4 +270,20 @@ impl<'a> Statement<'a> {
 			_ => None,
 		}
 	}
+	/// Returns any WITH clause if specified
+	#[inline]
+	pub fn with(&self) -> Option<&With> {
+		match self {
+			Statement::Select(v) => v.with.as_ref(),
+			_ => None,
+		}
+	}
+	/// Returns any VERSION clause if specified
+	#[inline]
+	pub fn version(&self) -> Option<&Version> {
+		match self {
+			Statement::Select(v) => v.version.as_ref(),
+			_ => None,
+		}
+	}
 }
@@ -0,0 +1,157 @@
+use crate::sql::{Array, Id, Object, Thing, Value};
+use arc_swap::ArcSwapOption;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// The number of hot record ids tracked for each table
+const HOT_KEYS: usize = 16;
+
+/// A registry of approximate record access statistics for a datastore
+#[derive(Clone, Debug, Default)]
+pub struct Stats(Arc<RwLock<BTreeMap<(String, String, String), Arc<Entry>>>>);
+
+/// The access counters for a single table
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableStats {
+	/// The namespace of the table
+	pub ns: String,
+	/// The database of the table
+	pub db: String,
+	/// The name of the table
+	pub tb: String,
+	/// The number of record reads on the table
+	pub reads: u64,
+	/// The number of record writes on the table
+	pub writes: u64,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+	// The number of record reads
+	reads: AtomicU64,
+	// The number of record writes
+	writes: AtomicU64,
+	// The most frequently accessed record ids
+	hot: [Slot; HOT_KEYS],
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+	// The hash of the tracked record id
+	hash: AtomicU64,
+	// The number of accesses, or zero if the slot is empty
+	count: AtomicU64,
+	// The tracked record id
+	id: ArcSwapOption<Id>,
+}
+
+impl Entry {
+	// Count an access to a record id, using the space-saving
+	// algorithm, so that only a bounded number of record ids
+	// are tracked, while the most accessed ids are retained.
+	// The slots are updated without locking, so concurrent
+	// accesses may be miscounted, within the approximation.
+	fn touch(&self, id: &Id) {
+		let mut hasher = DefaultHasher::new();
+		id.hash(&mut hasher);
+		let hash = hasher.finish();
+		// Count the access if the record id is tracked
+		let tracked = self.hot.iter().find(|s| {
+			s.count.load(Ordering::Relaxed) > 0 && s.hash.load(Ordering::Relaxed) == hash
+		});
+		if let Some(slot) = tracked {
+			slot.count.fetch_add(1, Ordering::Relaxed);
+			return;
+		}
+		// Otherwise replace the least accessed record id
+		if let Some(slot) = self.hot.iter().min_by_key(|s| s.count.load(Ordering::Relaxed)) {
+			let min = slot.count.load(Ordering::Relaxed);
+			// Another access may have claimed the slot at the same time
+			if slot
+				.count
+				.compare_exchange(min, min + 1, Ordering::AcqRel, Ordering::Relaxed)
+				.is_ok()
+			{
+				slot.hash.store(hash, Ordering::Relaxed);
+				slot.id.store(Some(Arc::new(id.to_owned())));
+			}
+		}
+	}
+}
+
+impl Stats {
+	/// Record an access to a record on a table
+	pub(crate) fn record(&self, ns: &str, db: &str, rid: &Thing, write: bool) {
+		let key = (ns.to_owned(), db.to_owned(), rid.tb.to_owned());
+		// Fetch the table entry, or create it if needed
+		let entry = self.0.read().unwrap().get(&key).cloned();
+		let entry = match entry {
+			Some(v) => v,
+			None => self.0.write().unwrap().entry(key).or_default().clone(),
+		};
+		// Increment the access counters
+		match write {
+			true => entry.writes.fetch_add(1, Ordering::Relaxed),
+			false => entry.reads.fetch_add(1, Ordering::Relaxed),
+		};
+		// Track the accessed record id
+		entry.touch(&rid.id);
+	}
+
+	/// Output the access counters for all tables
+	pub fn tables(&self) -> Vec<TableStats> {
+		self.0
+			.read()
+			.unwrap()
+			.iter()
+			.map(|((ns, db, tb), v)| TableStats {
+				ns: ns.to_owned(),
+				db: db.to_owned(),
+				tb: tb.to_owned(),
+				reads: v.reads.load(Ordering::Relaxed),
+				writes: v.writes.load(Ordering::Relaxed),
+			})
+			.collect()
+	}
+
+	/// Output the access statistics for the tables in a database
+	pub(crate) fn info(&self, ns: &str, db: &str) -> Value {
+		let mut res = Object::default();
+		for ((_, _, tb), v) in
+			self.0.read().unwrap().iter().filter(|((n, d, _), _)| n == ns && d == db)
+		{
+			// Sort the hot record ids by access count
+			let mut hot: Vec<_> = v
+				.hot
+				.iter()
+				.filter_map(|s| match s.count.load(Ordering::Relaxed) {
+					0 => None,
+					c => s.id.load_full().map(|id| (id.as_ref().clone(), c)),
+				})
+				.collect();
+			hot.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+			// Output the table statistics
+			res.insert(
+				tb.to_owned(),
+				Value::from(map! {
+					"reads".to_string() => v.reads.load(Ordering::Relaxed).into(),
+					"writes".to_string() => v.writes.load(Ordering::Relaxed).into(),
+					"hot".to_string() => hot
+						.into_iter()
+						.map(|(id, count)| {
+							Value::from(map! {
+								"id".to_string() => Value::from(Thing::from((tb.to_owned(), id))),
+								"count".to_string() => count.into(),
+							})
+						})
+						.collect::<Array>()
+						.into(),
+				}),
+			);
+		}
+		res.into()
+	}
+}
@@ -0,0 +1,118 @@
+use crate::sql::{Datetime, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A registry of the approximate storage used by each table of a datastore,
+/// which is collected periodically in the background
+#[derive(Clone, Debug, Default)]
+pub struct Usage {
+	// The storage used by each table, when it was last collected
+	tables: Arc<RwLock<BTreeMap<(String, String, String), TableUsage>>>,
+	// The timestamp at which the latest scheduled collection was started
+	scheduled: Arc<Mutex<Option<u64>>>,
+	// The bytes written to each database since the last collection
+	written: Arc<Mutex<BTreeMap<(String, String), u64>>>,
+}
+
+/// The storage used by a single table
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableUsage {
+	/// The number of records in the table
+	pub records: u64,
+	/// The size in bytes of the records and graph edges of the table
+	pub size: u64,
+	/// The size in bytes of each index on the table
+	pub indexes: BTreeMap<String, u64>,
+	/// When the storage used by the table was collected
+	pub collected: Datetime,
+}
+
+impl TableUsage {
+	/// The size in bytes of the records, graph edges, and indexes of the table
+	pub fn total(&self) -> u64 {
+		self.size + self.indexes.values().sum::<u64>()
+	}
+}
+
+impl Usage {
+	/// Check whether a scheduled collection is due, marking it as
+	/// started at the timestamp if it is
+	pub(crate) fn due(&self, ts: u64, every: u64) -> bool {
+		let mut scheduled = self.scheduled.lock().unwrap();
+		match *scheduled {
+			Some(v) if ts < v.saturating_add(every) => false,
+			// The first collection is due as soon as the datastore starts
+			_ => {
+				*scheduled = Some(ts);
+				true
+			}
+		}
+	}
+
+	/// Replace the storage used by every table, so that
+	/// tables which have since been removed are forgotten
+	pub(crate) fn replace(&self, tables: BTreeMap<(String, String, String), TableUsage>) {
+		*self.tables.write().unwrap() = tables;
+		self.written.lock().unwrap().clear();
+	}
+
+	/// Count the bytes written to each database by a committed transaction
+	pub(crate) fn written(&self, written: HashMap<(String, String), u64>) {
+		let mut counters = self.written.lock().unwrap();
+		for (key, bytes) in written {
+			*counters.entry(key).or_default() += bytes;
+		}
+	}
+
+	/// Output the storage used by a namespace, in bytes
+	///
+	/// This is the storage used by its tables when they were last collected,
+	/// along with the bytes written to its databases since then. Deleted
+	/// records are only accounted for once the storage is next collected.
+	pub fn namespace(&self, ns: &str) -> u64 {
+		let tables = self.tables.read().unwrap();
+		let collected: u64 =
+			tables.iter().filter(|((n, _, _), _)| n == ns).map(|(_, v)| v.total()).sum();
+		let written = self.written.lock().unwrap();
+		let written: u64 = written.iter().filter(|((n, _), _)| n == ns).map(|(_, v)| v).sum();
+		collected + written
+	}
+
+	/// Output the storage used by a database, in bytes
+	///
+	/// This is the storage used by its tables when they were last collected,
+	/// along with the bytes written to the database since then. Deleted
+	/// records are only accounted for once the storage is next collected.
+	pub fn database(&self, ns: &str, db: &str) -> u64 {
+		let tables = self.tables.read().unwrap();
+		let collected: u64 =
+			tables.iter().filter(|((n, d, _), _)| n == ns && d == db).map(|(_, v)| v.total()).sum();
+		let key = (ns.to_owned(), db.to_owned());
+		let written = self.written.lock().unwrap().get(&key).copied().unwrap_or_default();
+		collected + written
+	}
+
+	/// Output the storage used by a table, if it has been collected
+	pub fn table(&self, ns: &str, db: &str, tb: &str) -> Option<TableUsage> {
+		let key = (ns.to_owned(), db.to_owned(), tb.to_owned());
+		self.tables.read().unwrap().get(&key).cloned()
+	}
+
+	/// Output the storage used by a table
+	pub(crate) fn info(&self, ns: &str, db: &str, tb: &str) -> Value {
+		match self.table(ns, db, tb) {
+			Some(v) => Value::from(map! {
+				"records".to_string() => v.records.into(),
+				"size".to_string() => v.size.into(),
+				"indexes".to_string() => v
+					.indexes
+					.into_iter()
+					.map(|(k, v)| (k, Value::from(v)))
+					.collect::<BTreeMap<_, _>>()
+					.into(),
+				"collected".to_string() => v.collected.into(),
+			}),
+			None => Value::None,
+		}
+	}
+}
@@ -26,6 +26,9 @@ impl<'a> Document<'a> {
 					&tb.permissions.select
 				} else if self.is_new() {
 					&tb.permissions.create
+				} else if stm.if_not_exists() {
+					// An existing record is only returned, and not modified
+					&tb.permissions.select
 				} else {
 					&tb.permissions.update
 				};
@@ -20,7 +20,7 @@ impl<'a> Document<'a> {
 		// Get the table for the record
 		let tb = self.tb(opt, txn).await?;
 		// Check if changefeeds are enabled
-		if tb.changefeed.is_some() {
+		if let Some(cf) = &tb.changefeed {
 			// Clone transaction
 			let run = txn.clone();
 			// Claim transaction
@@ -31,7 +31,12 @@ impl<'a> Document<'a> {
 			let tb = tb.name.as_str();
 			let id = self.id.as_ref().unwrap();
 			// Create the changefeed entry
-			run.record_change(ns, db, tb, id, self.current.doc.clone());
+			if cf.store_original {
+				let original = self.initial.doc.clone();
+				run.record_change_with_original(ns, db, tb, id, self.current.doc.clone(), original);
+			} else {
+				run.record_change(ns, db, tb, id, self.current.doc.clone());
+			}
 		}
 		// Carry on
 		Ok(())
@@ -5,6 +5,7 @@ use crate::dbs::{Operable, Transaction};
 use crate::dbs::{Options, Processed};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 use channel::Sender;
 
@@ -15,7 +16,7 @@ impl<'a> Document<'a> {
 		opt: &Options,
 		txn: &Transaction,
 		stm: &Statement<'_>,
-		chn: Sender<Result<Value, Error>>,
+		chn: Sender<(Option<Thing>, Result<Value, Error>)>,
 		mut pro: Processed,
 	) -> Result<(), Error> {
 		// Loop over maximum two times
@@ -39,6 +40,9 @@ impl<'a> Document<'a> {
 				Statement::Update(_) => doc.update(ctx, opt, txn, stm).await,
 				Statement::Relate(_) => doc.relate(ctx, opt, txn, stm).await,
 				Statement::Delete(_) => doc.delete(ctx, opt, txn, stm).await,
+				Statement::Insert(_) if stm.partial() => {
+					doc.insert_partial(ctx, opt, txn, stm).await
+				}
 				Statement::Insert(_) => doc.insert(ctx, opt, txn, stm).await,
 				_ => unreachable!(),
 			};
@@ -75,8 +79,21 @@ impl<'a> Document<'a> {
 				// Otherwise the record creation succeeded
 				Ok(v) => Ok(v),
 			};
-			// Send back the result
-			let _ = chn.send(res).await;
+			// Record the access statistics for this record,
+			// only counting the records which were written
+			if let (Some(stats), Some(rid)) = (ctx.get_stats(), pro.rid.as_ref()) {
+				let write = !matches!(stm, Statement::Select(_));
+				if !write || res.is_ok() {
+					stats.record(opt.ns(), opt.db(), rid, write);
+				}
+			}
+			// Report the outcome for this record if requested
+			let res = match stm.partial() {
+				true => Document::outcome(pro.rid.as_ref(), res),
+				false => res,
+			};
+			// Send back the result, with the record id
+			let _ = chn.send((pro.rid.take(), res)).await;
 			// Break the loop
 			break;
 		}
@@ -13,10 +13,25 @@ impl<'a> Document<'a> {
 		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<Value, Error> {
+		// Check if record has expired
+		self.expired(ctx, opt, txn, stm).await?;
 		// Check if exists
-		self.exist(ctx, opt, txn, stm).await?;
+		match self.exist(ctx, opt, txn, stm).await {
+			// Leave an existing record unchanged
+			Err(Error::RecordExists {
+				..
+			}) if stm.if_not_exists() => {
+				// Check if allowed
+				self.allow(ctx, opt, txn, stm).await?;
+				// Yield document
+				return self.pluck(ctx, opt, txn, stm).await;
+			}
+			res => res?,
+		}
 		// Alter record data
 		self.alter(ctx, opt, txn, stm).await?;
+		// Run BEFORE event queries
+		self.trigger(ctx, opt, txn, stm).await?;
 		// Merge fields data
 		self.field(ctx, opt, txn, stm).await?;
 		// Reset fields data
@@ -13,10 +13,14 @@ impl<'a> Document<'a> {
 		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<Value, Error> {
+		// Check if record has expired
+		self.expired(ctx, opt, txn, stm).await?;
 		// Check where clause
 		self.check(ctx, opt, txn, stm).await?;
 		// Check if allowed
 		self.allow(ctx, opt, txn, stm).await?;
+		// Run BEFORE event queries
+		self.trigger(ctx, opt, txn, stm).await?;
 		// Erase document
 		self.erase(ctx, opt, stm).await?;
 		// Purge index data
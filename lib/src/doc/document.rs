@@ -6,6 +6,8 @@ use crate::iam::Action;
 use crate::iam::ResourceKind;
 use crate::idx::ft::docids::DocId;
 use crate::idx::planner::executor::IteratorRef;
+use crate::sql::idiom::Idiom;
+use crate::sql::operation::Operation;
 use crate::sql::statements::define::DefineEventStatement;
 use crate::sql::statements::define::DefineFieldStatement;
 use crate::sql::statements::define::DefineIndexStatement;
@@ -108,6 +110,44 @@ impl<'a> Document<'a> {
 	pub fn is_new(&self) -> bool {
 		self.initial.doc.is_none()
 	}
+	/// Get the changes made to this document as JSON Patch operations,
+	/// with a test operation before any modified or removed field, which
+	/// contains the value of that field before the change was made
+	pub fn diff(&self) -> Vec<Operation> {
+		// A created or deleted document is diffed against an empty object
+		let empty = Value::base();
+		let initial = match self.initial.doc.is_some() {
+			true => self.initial.doc.as_ref(),
+			false => &empty,
+		};
+		let current = match self.current.doc.is_some() {
+			true => self.current.doc.as_ref(),
+			false => &empty,
+		};
+		// Include the previous value of any changed fields
+		let mut ops = vec![];
+		for op in initial.diff(current, Idiom::default()) {
+			match &op {
+				Operation::Remove {
+					path,
+				}
+				| Operation::Replace {
+					path,
+					..
+				}
+				| Operation::Change {
+					path,
+					..
+				} => ops.push(Operation::Test {
+					path: path.clone(),
+					value: initial.pick(path),
+				}),
+				_ => (),
+			}
+			ops.push(op);
+		}
+		ops
+	}
 	/// Get the table for this document
 	pub async fn tb(
 		&self,
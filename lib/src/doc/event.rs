@@ -1,17 +1,63 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
-use crate::doc::Document;
+use crate::doc::{CursorDoc, Document};
 use crate::err::Error;
+use crate::sql::statements::define::{DefineEventStatement, Trigger};
+use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 use std::ops::Deref;
 
-impl<'a> Document<'a> {
-	pub async fn event(
+/// An AFTER event which runs once the transaction which
+/// triggered it has been successfully committed
+pub(crate) struct AfterEvent {
+	ns: String,
+	db: String,
+	ev: DefineEventStatement,
+	rid: Thing,
+	met: Value,
+	before: Value,
+	after: Value,
+}
+
+impl AfterEvent {
+	/// Process this event within its own transaction
+	pub(crate) async fn run(
 		&self,
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
+	) -> Result<(), Error> {
+		// Run in the namespace and database of the change
+		let mut opt = opt.new_with_perms(false);
+		opt.set_ns(Some(self.ns.as_str().into()));
+		opt.set_db(Some(self.db.as_str().into()));
+		// Configure the context
+		let mut ctx = Context::new(ctx);
+		ctx.add_value("event", &self.met);
+		ctx.add_value("value", &self.after);
+		ctx.add_value("after", &self.after);
+		ctx.add_value("before", &self.before);
+		// Process the event
+		let doc = CursorDoc::new(None, Some(&self.rid), None, &self.after);
+		let val = self.ev.when.compute(&ctx, &opt, txn, Some(&doc)).await?;
+		if val.is_truthy() {
+			for v in self.ev.then.iter() {
+				v.compute(&ctx, &opt, txn, Some(&doc)).await?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<'a> Document<'a> {
+	/// Run any BEFORE events, which can modify the document
+	/// before it is stored, or return an error to abort the change
+	pub async fn trigger(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
 		// Check events
@@ -19,24 +65,22 @@ impl<'a> Document<'a> {
 			return Ok(());
 		}
 		// Check if forced
-		if !opt.force && !self.changed() {
+		if !opt.force && !stm.is_delete() && !self.changed() {
 			return Ok(());
 		}
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
 		// Don't run permissions
 		let opt = &opt.new_with_perms(false);
-		// Loop through all event statements
+		// Loop through all BEFORE event statements
 		for ev in self.ev(opt, txn).await?.iter() {
-			// Get the event action
-			let met = if stm.is_delete() {
-				Value::from("DELETE")
-			} else if self.is_new() {
-				Value::from("CREATE")
-			} else {
-				Value::from("UPDATE")
-			};
+			// Skip any other event statements
+			if ev.trigger != Some(Trigger::Before) {
+				continue;
+			}
 			// Configure the context
 			let mut ctx = Context::new(ctx);
-			ctx.add_value("event", met);
+			ctx.add_value("event", self.action(stm));
 			ctx.add_value("value", self.current.doc.deref());
 			ctx.add_value("after", self.current.doc.deref());
 			ctx.add_value("before", self.initial.doc.deref());
@@ -44,12 +88,88 @@ impl<'a> Document<'a> {
 			let val = ev.when.compute(&ctx, opt, txn, Some(&self.current)).await?;
 			// Execute event if value is truthy
 			if val.is_truthy() {
+				let mut res = Value::None;
 				for v in ev.then.iter() {
-					v.compute(&ctx, opt, txn, Some(&self.current)).await?;
+					res = v.compute(&ctx, opt, txn, Some(&self.current)).await?;
+				}
+				// Merge any returned object into the document
+				if res.is_object() && !stm.is_delete() {
+					self.current.doc.to_mut().merge(res)?;
+					self.current.doc.to_mut().def(rid);
 				}
 			}
 		}
 		// Carry on
 		Ok(())
 	}
+
+	pub async fn event(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Check events
+		if !opt.events {
+			return Ok(());
+		}
+		// Check if forced
+		if !opt.force && !self.changed() {
+			return Ok(());
+		}
+		// Don't run permissions
+		let opt = &opt.new_with_perms(false);
+		// Loop through all event statements
+		for ev in self.ev(opt, txn).await?.iter() {
+			// Get the event action
+			let met = self.action(stm);
+			match ev.trigger {
+				// BEFORE events have already been processed
+				Some(Trigger::Before) => continue,
+				// AFTER events are deferred until the transaction is committed
+				Some(Trigger::After) => {
+					txn.lock().await.defer_event(AfterEvent {
+						ns: opt.ns().to_owned(),
+						db: opt.db().to_owned(),
+						ev: ev.clone(),
+						rid: self.id.cloned().unwrap(),
+						met,
+						before: self.initial.doc.deref().clone(),
+						after: self.current.doc.deref().clone(),
+					});
+				}
+				// Other events are processed within the transaction
+				None => {
+					// Configure the context
+					let mut ctx = Context::new(ctx);
+					ctx.add_value("event", met);
+					ctx.add_value("value", self.current.doc.deref());
+					ctx.add_value("after", self.current.doc.deref());
+					ctx.add_value("before", self.initial.doc.deref());
+					// Process conditional clause
+					let val = ev.when.compute(&ctx, opt, txn, Some(&self.current)).await?;
+					// Execute event if value is truthy
+					if val.is_truthy() {
+						for v in ev.then.iter() {
+							v.compute(&ctx, opt, txn, Some(&self.current)).await?;
+						}
+					}
+				}
+			}
+		}
+		// Carry on
+		Ok(())
+	}
+
+	/// Get the event action for this document
+	fn action(&self, stm: &Statement<'_>) -> Value {
+		if stm.is_delete() {
+			Value::from("DELETE")
+		} else if self.is_new() {
+			Value::from("CREATE")
+		} else {
+			Value::from("UPDATE")
+		}
+	}
 }
@@ -0,0 +1,190 @@
+use crate::ctx::Context;
+use crate::dbs::Statement;
+use crate::dbs::Workable;
+use crate::dbs::{Options, Transaction};
+use crate::doc::Document;
+use crate::err::Error;
+use crate::kvs;
+use crate::sql::duration::Duration;
+use crate::sql::statements::DeleteStatement;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use chrono::Utc;
+use std::borrow::Cow;
+
+impl<'a> Document<'a> {
+	pub async fn expired(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Check if the record has expired
+		if !self.is_expired(opt, txn).await? {
+			return Ok(());
+		}
+		match stm {
+			// Expired records are hidden from reads
+			Statement::Select(_) => Err(Error::Ignore),
+			// Expired records no longer exist, so they are not updated or deleted
+			Statement::Update(_) | Statement::Delete(_) => {
+				self.remove_expired(ctx, opt, txn, stm).await?;
+				Err(Error::Ignore)
+			}
+			// Expired records are replaced as if they did not exist
+			_ => self.remove_expired(ctx, opt, txn, stm).await,
+		}
+	}
+	/// Remove an expired record, along with its index entries, edges, and expiry,
+	/// without running the events, views, and live queries of the table. The
+	/// document is then processed as a record which does not exist.
+	async fn remove_expired(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Erase document
+		self.erase(ctx, opt, stm).await?;
+		// Purge index data
+		self.index(ctx, opt, txn, stm).await?;
+		// Purge record data
+		self.purge(ctx, opt, txn, stm).await?;
+		// Forget the expired record
+		self.initial.doc = Cow::Owned(Value::None);
+		// Carry on
+		Ok(())
+	}
+	/// Check whether the TTL of the table has elapsed for this record,
+	/// before the record has been purged by the datastore
+	pub(super) async fn is_expired(&self, opt: &Options, txn: &Transaction) -> Result<bool, Error> {
+		// Check if this record exists
+		let rid = match self.id {
+			Some(rid) if self.current.doc.is_some() => rid,
+			_ => return Ok(false),
+		};
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Check if the table has a TTL
+		match run.get_and_cache_tb(opt.ns(), opt.db(), &rid.tb).await {
+			Ok(tb) if tb.ttl.is_some() => (),
+			Ok(_)
+			| Err(Error::TbNotFound {
+				..
+			}) => return Ok(false),
+			Err(e) => return Err(e),
+		}
+		// Get the expiry of the record
+		let key = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		Ok(match run.get(key).await? {
+			Some(v) => decode(&v) <= now(),
+			None => false,
+		})
+	}
+	/// Set the expiry of this record, when the table has a TTL
+	pub(super) async fn set_expiry(
+		&self,
+		opt: &Options,
+		run: &mut kvs::Transaction,
+	) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Check if the table has a TTL
+		let ttl = match run.get_and_cache_tb(opt.ns(), opt.db(), &rid.tb).await?.ttl {
+			Some(ref ttl) => ttl.as_millis() as u64,
+			None => return Ok(()),
+		};
+		// Remove the previous expiry
+		self.clear_expiry(opt, run).await?;
+		// Queue the record for purging
+		let ts = now().saturating_add(ttl);
+		let key = crate::key::table::eq::new(opt.ns(), opt.db(), &rid.tb, ts, &rid.id);
+		run.set(key, vec![]).await?;
+		let key = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		run.set(key, ts.to_be_bytes().to_vec()).await?;
+		// Carry on
+		Ok(())
+	}
+	/// Remove the expiry of this record, if it has one
+	pub(super) async fn clear_expiry(
+		&self,
+		opt: &Options,
+		run: &mut kvs::Transaction,
+	) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Remove the record from the queue
+		let key = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		if let Some(v) = run.get(key.clone()).await? {
+			let ts = decode(&v);
+			run.del(crate::key::table::eq::new(opt.ns(), opt.db(), &rid.tb, ts, &rid.id)).await?;
+			run.del(key).await?;
+		}
+		// Carry on
+		Ok(())
+	}
+}
+
+/// Remove a record which has expired, unless its expiry has changed since it was queued
+pub(crate) async fn purge_expired(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	rid: &Thing,
+	ts: u64,
+) -> Result<(), Error> {
+	// Claim transaction
+	let mut run = txn.lock().await;
+	// Remove the queue entry
+	run.del(crate::key::table::eq::new(opt.ns(), opt.db(), &rid.tb, ts, &rid.id)).await?;
+	// Check the record still expires at this time
+	let key = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+	match run.get(key).await? {
+		Some(v) if decode(&v) == ts => (),
+		_ => return Ok(()),
+	}
+	// Fetch the record
+	let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+	let val = match run.get(key).await? {
+		Some(v) => Value::from(v),
+		None => Value::None,
+	};
+	// Release the transaction
+	drop(run);
+	// Remove the record
+	let stm = DeleteStatement::default();
+	let stm = Statement::from(&stm);
+	let mut doc = Document::new(None, Some(rid), None, &val, Workable::Normal);
+	doc.remove_expired(ctx, opt, txn, &stm).await
+}
+
+/// Queue an existing record for purging, when a TTL is defined on its table,
+/// unless the record already has an expiry
+pub(crate) async fn backfill_expiry(
+	opt: &Options,
+	run: &mut kvs::Transaction,
+	rid: &Thing,
+	ttl: &Duration,
+) -> Result<(), Error> {
+	let key = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+	if run.exi(key.clone()).await? {
+		return Ok(());
+	}
+	let ts = now().saturating_add(ttl.as_millis() as u64);
+	let eq = crate::key::table::eq::new(opt.ns(), opt.db(), &rid.tb, ts, &rid.id);
+	run.set(eq, vec![]).await?;
+	run.set(key, ts.to_be_bytes().to_vec()).await?;
+	Ok(())
+}
+
+/// The current time, in milliseconds since the unix epoch
+fn now() -> u64 {
+	Utc::now().timestamp_millis() as u64
+}
+
+/// Decode an expiry which was stored as big-endian bytes
+fn decode(v: &[u8]) -> u64 {
+	v.try_into().map(u64::from_be_bytes).unwrap_or_default()
+}
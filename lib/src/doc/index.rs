@@ -4,13 +4,16 @@ use crate::dbs::{Options, Transaction};
 use crate::doc::{CursorDoc, Document};
 use crate::err::Error;
 use crate::idx::ft::FtIndex;
+use crate::idx::trees::hnsw::HnswIndex;
 use crate::idx::trees::store::TreeStoreType;
+use crate::idx::trigram;
 use crate::idx::IndexKeyBase;
 use crate::sql::array::Array;
-use crate::sql::index::{Index, SearchParams};
+use crate::sql::index::{HnswParams, Index, SearchParams};
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Part, Thing, Value};
 use crate::{key, kvs};
+use std::collections::BTreeSet;
 
 impl<'a> Document<'a> {
 	pub async fn index(
@@ -51,16 +54,7 @@ impl<'a> Document<'a> {
 				let mut ic = IndexOperation::new(opt, ix, o, n, rid);
 
 				// Index operation dispatching
-				match &ix.index {
-					Index::Uniq => ic.index_unique(&mut run).await?,
-					Index::Idx => ic.index_non_unique(&mut run).await?,
-					Index::Search(p) => ic.index_full_text(&mut run, p).await?,
-					Index::MTree(_) => {
-						return Err(Error::FeatureNotYetImplemented {
-							feature: "MTree indexing",
-						})
-					}
-				};
+				ic.compute(&mut run).await?;
 			}
 		}
 		// Carry on
@@ -68,6 +62,26 @@ impl<'a> Document<'a> {
 	}
 }
 
+/// Add the index entries for a single record to an index. This is
+/// used when rebuilding an index, once its existing entries have
+/// been removed, so there are no old index entries to remove.
+pub(crate) async fn reindex(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	ix: &DefineIndexStatement,
+	rid: &Thing,
+	val: &Value,
+) -> Result<(), Error> {
+	// Calculate the new values
+	let doc = CursorDoc::new(None, Some(rid), None, val);
+	let n = build_opt_values(ctx, opt, txn, ix, &doc).await?;
+	// Claim transaction
+	let mut run = txn.lock().await;
+	// Create the new index entries
+	IndexOperation::new(opt, ix, None, n, rid).compute(&mut run).await
+}
+
 /// Extract from the given document, the values required by the index and put then in an array.
 /// Eg. IF the index is composed of the columns `name` and `instrument`
 /// Given this doc: { "id": 1, "instrument":"piano", "name":"Tobie" }
@@ -85,6 +99,11 @@ async fn build_opt_values(
 	let mut o = Vec::with_capacity(ix.cols.len());
 	for i in ix.cols.iter() {
 		let v = i.compute(ctx, opt, txn, Some(doc)).await?;
+		// A collated index holds the folded strings
+		let v = match &ix.collate {
+			Some(c) => c.fold(v),
+			None => v,
+		};
 		o.push(v);
 	}
 	Ok(Some(o))
@@ -238,6 +257,20 @@ impl<'a> IndexOperation<'a> {
 		}
 	}
 
+	async fn compute(&mut self, run: &mut kvs::Transaction) -> Result<(), Error> {
+		let ix = self.ix;
+		match &ix.index {
+			Index::Uniq => self.index_unique(run).await,
+			Index::Idx => self.index_non_unique(run).await,
+			Index::Search(p) => self.index_full_text(run, p).await,
+			Index::MTree(_) => Err(Error::FeatureNotYetImplemented {
+				feature: "MTree indexing",
+			}),
+			Index::Hnsw(p) => self.index_hnsw(run, p).await,
+			Index::Trigram => self.index_trigram(run).await,
+		}
+	}
+
 	fn get_unique_index_key(&self, v: &'a Array) -> key::index::Index {
 		crate::key::index::Index::new(
 			self.opt.ns(),
@@ -346,4 +379,48 @@ impl<'a> IndexOperation<'a> {
 		}
 		ft.finish(run).await
 	}
+
+	async fn index_hnsw(&self, run: &mut kvs::Transaction, p: &HnswParams) -> Result<(), Error> {
+		let ikb = IndexKeyBase::new(self.opt, self.ix);
+		let mut hnsw = HnswIndex::new(run, ikb, p, TreeStoreType::Write).await?;
+		if let Some(n) = &self.n {
+			hnsw.index_document(run, self.rid, n).await?;
+		} else {
+			hnsw.remove_document(run, self.rid).await?;
+		}
+		hnsw.finish(run).await
+	}
+	/// Each trigram of the strings of a record is a non unique entry of the index
+	async fn index_trigram(&mut self, run: &mut kvs::Transaction) -> Result<(), Error> {
+		let mut o = BTreeSet::new();
+		if let Some(v) = self.o.take() {
+			v.iter().for_each(|v| trigram::trigrams(v, &mut o));
+		}
+		let mut n = BTreeSet::new();
+		if let Some(v) = self.n.take() {
+			v.iter().for_each(|v| trigram::trigrams(v, &mut n));
+		}
+		// Delete the trigrams the record no longer holds
+		for t in o.difference(&n) {
+			let a = Array::from(vec![t.as_str()]);
+			run.del(self.get_trigram_key(&a)).await?;
+		}
+		// Create the new trigrams
+		for t in n.difference(&o) {
+			let a = Array::from(vec![t.as_str()]);
+			run.set(self.get_trigram_key(&a), self.rid).await?;
+		}
+		Ok(())
+	}
+
+	fn get_trigram_key<'b>(&'b self, a: &'b Array) -> key::index::Index<'b> {
+		crate::key::index::Index::new(
+			self.opt.ns(),
+			self.opt.db(),
+			&self.ix.what,
+			&self.ix.name,
+			a,
+			Some(&self.rid.id),
+		)
+	}
 }
@@ -3,8 +3,12 @@ use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 
+// The savepoint which the changes of a failing record are undone to
+const PARTIAL_SAVEPOINT: &str = "__insert_partial__";
+
 impl<'a> Document<'a> {
 	pub async fn insert(
 		&mut self,
@@ -13,6 +17,8 @@ impl<'a> Document<'a> {
 		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<Value, Error> {
+		// Check if record has expired
+		self.expired(ctx, opt, txn, stm).await?;
 		// Check whether current record exists
 		match self.current.doc.is_some() {
 			// We attempted to INSERT a document with an ID,
@@ -52,6 +58,8 @@ impl<'a> Document<'a> {
 	) -> Result<Value, Error> {
 		// Merge record data
 		self.merge(ctx, opt, txn, stm).await?;
+		// Run BEFORE event queries
+		self.trigger(ctx, opt, txn, stm).await?;
 		// Merge fields data
 		self.field(ctx, opt, txn, stm).await?;
 		// Reset fields data
@@ -87,6 +95,8 @@ impl<'a> Document<'a> {
 		self.allow(ctx, opt, txn, stm).await?;
 		// Alter record data
 		self.alter(ctx, opt, txn, stm).await?;
+		// Run BEFORE event queries
+		self.trigger(ctx, opt, txn, stm).await?;
 		// Merge fields data
 		self.field(ctx, opt, txn, stm).await?;
 		// Reset fields data
@@ -110,4 +120,64 @@ impl<'a> Document<'a> {
 		// Yield document
 		self.pluck(ctx, opt, txn, stm).await
 	}
+	// Process a record of an INSERT PARTIAL statement
+	// within a lightweight savepoint, so that any changes
+	// made by the record before an error was raised are
+	// undone when the error is reported for the record.
+	pub(crate) async fn insert_partial(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<Value, Error> {
+		txn.lock().await.lightweight_savepoint(PARTIAL_SAVEPOINT);
+		let res = self.insert(ctx, opt, txn, stm).await;
+		let mut run = txn.lock().await;
+		if let Err(e) = &res {
+			if !Document::aborts(e) {
+				run.rollback_to_savepoint(PARTIAL_SAVEPOINT).await?;
+			}
+		}
+		run.release_savepoint(PARTIAL_SAVEPOINT)?;
+		res
+	}
+	// Errors which affect the whole statement or the
+	// transaction, rather than a single record
+	fn aborts(e: &Error) -> bool {
+		matches!(
+			e,
+			Error::Ignore
+				| Error::QueryCancelled
+				| Error::QueryTimedout
+				| Error::Tx(_)
+				| Error::TxFailure
+				| Error::TxFinished
+				| Error::TxReadonly
+				| Error::TxTooLarge
+		)
+	}
+	// Convert the result of a record into an outcome
+	// object, so that a failing record does not cause
+	// the whole statement to fail. The changes made by
+	// the failing record have already been undone.
+	pub(crate) fn outcome(rid: Option<&Thing>, res: Result<Value, Error>) -> Result<Value, Error> {
+		match res {
+			// Errors which affect the whole statement or the
+			// transaction are passed through as normal
+			Err(e) if Document::aborts(&e) => Err(e),
+			// Otherwise report the error for this record
+			Err(e) => Ok(Value::from(map! {
+				"id".to_string() => rid.cloned().map(Value::from).unwrap_or_default(),
+				"status".to_string() => Value::from("ERR"),
+				"result".to_string() => Value::from(e.to_string()),
+			})),
+			// Or report the result for this record
+			Ok(v) => Ok(Value::from(map! {
+				"id".to_string() => rid.cloned().map(Value::from).unwrap_or_default(),
+				"status".to_string() => Value::from("OK"),
+				"result".to_string() => v,
+			})),
+		}
+	}
 }
@@ -0,0 +1,34 @@
+use crate::ctx::Context;
+use crate::dbs::Statement;
+use crate::dbs::{Options, Transaction};
+use crate::doc::Document;
+use crate::err::Error;
+
+impl<'a> Document<'a> {
+	pub async fn lock(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Check if the document should be locked
+		if !stm.lock() {
+			return Ok(());
+		}
+		// Check if this is a stored record
+		let rid = match self.id {
+			Some(rid) if !self.current.doc.is_none() => rid,
+			_ => return Ok(()),
+		};
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Rewrite the unchanged record data, so that any
+		// concurrent write to this record will conflict
+		// with this transaction when it is committed
+		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		run.set(key, self).await?;
+		// Carry on
+		Ok(())
+	}
+}
@@ -5,6 +5,9 @@
 //! - `initial`: value before the transaction
 //! - `id`: traditionally an integer but can be an object or collection such as an array
 pub(crate) use self::document::*;
+pub(crate) use self::event::AfterEvent;
+pub(crate) use self::expire::{backfill_expiry, purge_expired};
+pub(crate) use self::index::reindex;
 
 mod document; // The entry point for a document to be processed
 
@@ -29,12 +32,15 @@ mod empty; // Checks whether the specified document actually exists
 mod erase; // Removes all content and field data for this document
 mod event; // Processes any table events relevant for this document
 mod exist; // Checks whether the specified document actually exists
+mod expire; // Checks and tracks when this document expires
 mod field; // Processes any schema-defined fields for this document
 mod index; // Attempts to store the index data for this document
 mod lives; // Processes any live queries relevant for this document
+mod lock; // Takes a write intent on this document for a SELECT FOR UPDATE
 mod merge; // Merges any field changes for an INSERT statement
 mod pluck; // Pulls the projected expressions from the document
 mod purge; // Deletes this document, and any edges or indexes
 mod reset; // Resets internal fields which were set for this document
 mod store; // Writes the document content to the storage engine
 mod table; // Processes any foreign tables relevant for this document
+mod truncate; // Cleans up after this document for a TRUNCATE statement
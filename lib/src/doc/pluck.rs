@@ -25,14 +25,24 @@ impl<'a> Document<'a> {
 			Some(v) => match v {
 				Output::None => Err(Error::Ignore),
 				Output::Null => Ok(Value::Null),
-				Output::Diff => {
-					Ok(self.initial.doc.diff(self.current.doc.as_ref(), Idiom::default()).into())
-				}
+				Output::Diff => Ok(self.diff().into()),
 				Output::After => self.current.doc.compute(ctx, opt, txn, Some(&self.current)).await,
 				Output::Before => {
 					self.initial.doc.compute(ctx, opt, txn, Some(&self.initial)).await
 				}
 				Output::Fields(v) => v.compute(ctx, opt, txn, Some(&self.current), false).await,
+				Output::BeforeAfter => {
+					let mut before =
+						self.initial.doc.compute(ctx, opt, txn, Some(&self.initial)).await?;
+					self.redact(ctx, opt, txn, stm, &mut before).await?;
+					let mut after =
+						self.current.doc.compute(ctx, opt, txn, Some(&self.current)).await?;
+					self.redact(ctx, opt, txn, stm, &mut after).await?;
+					return Ok(Value::from(map! {
+						"before".to_string() => before,
+						"after".to_string() => after,
+					}));
+				}
 			},
 			None => match stm {
 				Statement::Live(s) => match s.expr.len() {
@@ -57,6 +67,20 @@ impl<'a> Document<'a> {
 				_ => Err(Error::Ignore),
 			},
 		}?;
+		// Remove any fields which should not be output
+		self.redact(ctx, opt, txn, stm, &mut out).await?;
+		// Output result
+		Ok(out)
+	}
+	/// Remove any fields which can not be viewed, or which were omitted
+	async fn redact(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		out: &mut Value,
+	) -> Result<(), Error> {
 		// Check if this record exists
 		if self.id.is_some() {
 			// Should we run permissions checks?
@@ -99,7 +123,7 @@ impl<'a> Document<'a> {
 		}
 		// Remove metadata fields on output
 		out.del(ctx, opt, txn, &*META).await?;
-		// Output result
-		Ok(out)
+		// Carry on
+		Ok(())
 	}
 }
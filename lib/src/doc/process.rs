@@ -18,6 +18,10 @@ impl<'a> Document<'a> {
 	) -> Result<Value, Error> {
 		// Loop over maximum two times
 		for _ in 0..2 {
+			// Check current context
+			if ctx.is_done() {
+				return Err(Error::Ignore);
+			}
 			// Setup a new workable
 			let ins = match pro.val {
 				Operable::Value(v) => (v, Workable::Normal),
@@ -33,6 +37,9 @@ impl<'a> Document<'a> {
 				Statement::Update(_) => doc.update(ctx, opt, txn, stm).await,
 				Statement::Relate(_) => doc.relate(ctx, opt, txn, stm).await,
 				Statement::Delete(_) => doc.delete(ctx, opt, txn, stm).await,
+				Statement::Insert(_) if stm.partial() => {
+					doc.insert_partial(ctx, opt, txn, stm).await
+				}
 				Statement::Insert(_) => doc.insert(ctx, opt, txn, stm).await,
 				_ => unreachable!(),
 			};
@@ -69,6 +76,19 @@ impl<'a> Document<'a> {
 				// Otherwise the record creation succeeded
 				Ok(v) => Ok(v),
 			};
+			// Record the access statistics for this record,
+			// only counting the records which were written
+			if let (Some(stats), Some(rid)) = (ctx.get_stats(), pro.rid.as_ref()) {
+				let write = !matches!(stm, Statement::Select(_));
+				if !write || res.is_ok() {
+					stats.record(opt.ns(), opt.db(), rid, write);
+				}
+			}
+			// Report the outcome for this record if requested
+			let res = match stm.partial() {
+				true => Document::outcome(pro.rid.as_ref(), res),
+				false => res,
+			};
 			// Send back the result
 			return res;
 		}
@@ -33,6 +33,8 @@ impl<'a> Document<'a> {
 			// Purge the record data
 			let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
 			run.del(key).await?;
+			// Purge the record expiry
+			self.clear_expiry(opt, &mut run).await?;
 			// Purge the record edges
 			match (
 				self.initial.doc.pick(&*EDGE),
@@ -13,6 +13,8 @@ impl<'a> Document<'a> {
 		txn: &Transaction,
 		stm: &Statement<'_>,
 	) -> Result<Value, Error> {
+		// Check if record has expired
+		self.expired(ctx, opt, txn, stm).await?;
 		// Check current record
 		match self.current.doc.is_some() {
 			// Create new edge
@@ -21,6 +23,8 @@ impl<'a> Document<'a> {
 				self.edges(ctx, opt, txn, stm).await?;
 				// Alter record data
 				self.alter(ctx, opt, txn, stm).await?;
+				// Run BEFORE event queries
+				self.trigger(ctx, opt, txn, stm).await?;
 				// Merge fields data
 				self.field(ctx, opt, txn, stm).await?;
 				// Reset fields data
@@ -52,6 +56,8 @@ impl<'a> Document<'a> {
 				self.edges(ctx, opt, txn, stm).await?;
 				// Alter record data
 				self.alter(ctx, opt, txn, stm).await?;
+				// Run BEFORE event queries
+				self.trigger(ctx, opt, txn, stm).await?;
 				// Merge fields data
 				self.field(ctx, opt, txn, stm).await?;
 				// Reset fields data
@@ -7,7 +7,7 @@ use crate::sql::value::Value;
 
 impl<'a> Document<'a> {
 	pub async fn select(
-		&self,
+		&mut self,
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
@@ -15,10 +15,14 @@ impl<'a> Document<'a> {
 	) -> Result<Value, Error> {
 		// Check if record exists
 		self.empty(ctx, opt, txn, stm).await?;
+		// Check if record has expired
+		self.expired(ctx, opt, txn, stm).await?;
 		// Check where clause
 		self.check(ctx, opt, txn, stm).await?;
 		// Check if allowed
 		self.allow(ctx, opt, txn, stm).await?;
+		// Lock the document if needed
+		self.lock(ctx, opt, txn, stm).await?;
 		// Yield document
 		self.pluck(ctx, opt, txn, stm).await
 	}
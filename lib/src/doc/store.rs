@@ -27,6 +27,8 @@ impl<'a> Document<'a> {
 		// Store the record data
 		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
 		run.set(key, self).await?;
+		// Store the record expiry
+		self.set_expiry(opt, &mut run).await?;
 		// Carry on
 		Ok(())
 	}
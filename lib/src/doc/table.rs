@@ -1,23 +1,31 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
-use crate::doc::Document;
+use crate::doc::{CursorDoc, Document};
 use crate::err::Error;
+use crate::fnc::util::hll::Sketch;
+use crate::sql::bytes::Bytes;
+use crate::sql::cond::Cond;
 use crate::sql::data::Data;
 use crate::sql::expression::Expression;
 use crate::sql::field::{Field, Fields};
+use crate::sql::function::Function;
+use crate::sql::group::Groups;
 use crate::sql::idiom::Idiom;
 use crate::sql::number::Number;
 use crate::sql::operator::Operator;
 use crate::sql::part::Part;
 use crate::sql::paths::ID;
 use crate::sql::statement::Statement as Query;
+use crate::sql::statements::define::DefineTableStatement;
 use crate::sql::statements::delete::DeleteStatement;
 use crate::sql::statements::ifelse::IfelseStatement;
+use crate::sql::statements::select::SelectStatement;
 use crate::sql::statements::update::UpdateStatement;
 use crate::sql::subquery::Subquery;
 use crate::sql::thing::Thing;
 use crate::sql::value::{Value, Values};
+use crate::sql::view::View;
 use futures::future::try_join_all;
 
 type Ops = Vec<(Idiom, Operator, Value)>;
@@ -47,8 +55,6 @@ impl<'a> Document<'a> {
 		}
 		// Don't run permissions
 		let opt = &opt.new_with_perms(false);
-		// Get the record id
-		let rid = self.id.as_ref().unwrap();
 		// Get the query action
 		let act = if stm.is_delete() {
 			Action::Delete
@@ -59,183 +65,360 @@ impl<'a> Document<'a> {
 		};
 		// Loop through all foreign table statements
 		for ft in self.ft(opt, txn).await?.iter() {
-			// Get the table definition
-			let tb = ft.view.as_ref().unwrap();
-			// Check if there is a GROUP BY clause
-			match &tb.group {
-				// There is a GROUP BY clause specified
-				Some(group) => {
-					// Set the previous record id
-					let old = Thing {
-						tb: ft.name.to_raw(),
-						id: try_join_all(
-							group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.initial))),
-						)
-						.await?
-						.into_iter()
-						.collect::<Vec<_>>()
-						.into(),
-					};
-					// Set the current record id
-					let rid = Thing {
-						tb: ft.name.to_raw(),
-						id: try_join_all(
-							group.iter().map(|v| v.compute(ctx, opt, txn, Some(&self.current))),
-						)
-						.await?
-						.into_iter()
-						.collect::<Vec<_>>()
-						.into(),
-					};
-					// Check if a WHERE clause is specified
-					match &tb.cond {
-						// There is a WHERE clause specified
-						Some(cond) => {
-							match cond.compute(ctx, opt, txn, Some(&self.current)).await? {
-								v if v.is_truthy() => {
-									if !opt.force && act != Action::Create {
-										// Delete the old value
-										let act = Action::Delete;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(old)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-									if act != Action::Delete {
-										// Update the new value
-										let act = Action::Update;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(rid)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-								}
-								_ => {
-									if !opt.force && act != Action::Create {
-										// Update the new value
-										let act = Action::Update;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(old)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
+			// Skip any views which are not maintained on write
+			if !ft.view.as_ref().unwrap().is_incremental() {
+				continue;
+			}
+			// Update the view with this change
+			self.view(ctx, opt, txn, &act, ft).await?;
+		}
+		// Carry on
+		Ok(())
+	}
+	/// Add this document to a view which is being populated
+	pub(crate) async fn populate(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		ft: &DefineTableStatement,
+	) -> Result<(), Error> {
+		// Don't run permissions
+		let opt = &opt.new_with_perms(false);
+		// Process the document as a new record
+		self.view(ctx, opt, txn, &Action::Create, ft).await
+	}
+	/// Apply the changes to this document to a single view
+	async fn view(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		ft: &DefineTableStatement,
+	) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Get the table definition
+		let tb = ft.view.as_ref().unwrap();
+		// Check if there is a GROUP BY clause
+		match &tb.group {
+			// There is a GROUP BY clause specified
+			Some(group) => {
+				// Get the group which the initial record belonged to
+				let old = match act {
+					Action::Create => None,
+					_ => match self.matches(ctx, opt, txn, tb, &self.initial).await? {
+						true => Some(self.grouping(ctx, opt, txn, group, &self.initial).await?),
+						false => None,
+					},
+				};
+				// Get the group which the current record belongs to
+				let new = match act {
+					Action::Delete => None,
+					_ => match self.matches(ctx, opt, txn, tb, &self.current).await? {
+						true => Some(self.grouping(ctx, opt, txn, group, &self.current).await?),
+						false => None,
+					},
+				};
+				// Check if the aggregates can be updated with the change alone
+				let reversible = Self::reversible(tb);
+				match (old, new) {
+					// The record has changed within the same group
+					(Some(old), Some(new)) if old == new => {
+						// Set the current record id
+						let rid = Thing {
+							tb: ft.name.to_raw(),
+							id: new.into(),
+						};
+						match reversible && !self.uncounted(opt, txn, &rid).await? {
+							// Remove the old value and add the new value
+							true => {
+								for act in [Action::Delete, Action::Update] {
+									self.delta(ctx, opt, txn, act, &rid, tb).await?;
 								}
 							}
-						}
-						// No WHERE clause is specified
-						None => {
-							if !opt.force && act != Action::Create {
-								// Delete the old value
-								let act = Action::Delete;
-								// Modify the value in the table
-								let stm = UpdateStatement {
-									what: Values(vec![Value::from(old)]),
-									data: Some(self.data(ctx, opt, txn, act, &tb.expr).await?),
-									..UpdateStatement::default()
-								};
-								// Execute the statement
-								stm.compute(ctx, opt, txn, None).await?;
-							}
-							if act != Action::Delete {
-								// Update the new value
-								let act = Action::Update;
-								// Modify the value in the table
-								let stm = UpdateStatement {
-									what: Values(vec![Value::from(rid)]),
-									data: Some(self.data(ctx, opt, txn, act, &tb.expr).await?),
-									..UpdateStatement::default()
-								};
-								// Execute the statement
-								stm.compute(ctx, opt, txn, None).await?;
-							}
+							// Recalculate the group
+							false => self.regroup(ctx, opt, txn, ft, &old).await?,
 						}
 					}
-				}
-				// No GROUP BY clause is specified
-				None => {
-					// Set the current record id
-					let rid = Thing {
-						tb: ft.name.to_raw(),
-						id: rid.id.clone(),
-					};
-					// Check if a WHERE clause is specified
-					match &tb.cond {
-						// There is a WHERE clause specified
-						Some(cond) => {
-							match cond.compute(ctx, opt, txn, Some(&self.current)).await? {
-								v if v.is_truthy() => {
-									// Define the statement
-									let stm = match act {
-										// Delete the value in the table
-										Action::Delete => Query::Delete(DeleteStatement {
-											what: Values(vec![Value::from(rid)]),
-											..DeleteStatement::default()
-										}),
-										// Update the value in the table
-										_ => Query::Update(UpdateStatement {
-											what: Values(vec![Value::from(rid)]),
-											data: Some(self.full(ctx, opt, txn, &tb.expr).await?),
-											..UpdateStatement::default()
-										}),
-									};
-									// Execute the statement
-									stm.compute(ctx, opt, txn, None).await?;
-								}
-								_ => {
-									// Delete the value in the table
+					(old, new) => {
+						// Remove the record from the group which it was in
+						if let Some(old) = old {
+							// Set the previous record id
+							let rid = Thing {
+								tb: ft.name.to_raw(),
+								id: old.clone().into(),
+							};
+							match reversible && !self.uncounted(opt, txn, &rid).await? {
+								// Remove the old value, and the group once it is empty
+								true => {
+									self.delta(ctx, opt, txn, Action::Delete, &rid, tb).await?;
 									let stm = DeleteStatement {
 										what: Values(vec![Value::from(rid)]),
+										cond: Some(Cond(Value::Expression(Box::new(
+											Expression::Binary {
+												l: Value::Idiom(Self::members()),
+												o: Operator::LessThanOrEqual,
+												r: Value::from(0),
+											},
+										)))),
 										..DeleteStatement::default()
 									};
-									// Execute the statement
 									stm.compute(ctx, opt, txn, None).await?;
 								}
+								// Recalculate the group, as any minimum or maximum
+								// values can not be known without looking at it
+								false => self.regroup(ctx, opt, txn, ft, &old).await?,
 							}
 						}
-						// No WHERE clause is specified
-						None => {
-							// Define the statement
-							let stm = match act {
-								// Delete the value in the table
-								Action::Delete => Query::Delete(DeleteStatement {
-									what: Values(vec![Value::from(rid)]),
-									..DeleteStatement::default()
-								}),
-								// Update the value in the table
-								_ => Query::Update(UpdateStatement {
-									what: Values(vec![Value::from(rid)]),
-									data: Some(self.full(ctx, opt, txn, &tb.expr).await?),
-									..UpdateStatement::default()
-								}),
+						// Add the record to the group which it is now in
+						if let Some(new) = new {
+							// Set the current record id
+							let rid = Thing {
+								tb: ft.name.to_raw(),
+								id: new.clone().into(),
 							};
-							// Execute the statement
-							stm.compute(ctx, opt, txn, None).await?;
+							match self.uncounted(opt, txn, &rid).await? {
+								// Add the new value
+								false => {
+									self.delta(ctx, opt, txn, Action::Update, &rid, tb).await?
+								}
+								// Recalculate a group stored before its records were counted
+								true => self.regroup(ctx, opt, txn, ft, &new).await?,
+							}
 						}
 					}
 				}
 			}
+			// No GROUP BY clause is specified
+			None => {
+				// Set the current record id
+				let rid = Thing {
+					tb: ft.name.to_raw(),
+					id: rid.id.clone(),
+				};
+				// Check if the current record is in the view
+				let stm = match act != &Action::Delete
+					&& self.matches(ctx, opt, txn, tb, &self.current).await?
+				{
+					// Update the value in the table
+					true => Query::Update(UpdateStatement {
+						what: Values(vec![Value::from(rid)]),
+						data: Some(self.full(ctx, opt, txn, &tb.expr).await?),
+						..UpdateStatement::default()
+					}),
+					// Delete the value in the table
+					false => Query::Delete(DeleteStatement {
+						what: Values(vec![Value::from(rid)]),
+						..DeleteStatement::default()
+					}),
+				};
+				// Execute the statement
+				stm.compute(ctx, opt, txn, None).await?;
+			}
 		}
 		// Carry on
 		Ok(())
 	}
+	/// Check if a version of this document matches the view WHERE clause
+	async fn matches(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		tb: &View,
+		doc: &CursorDoc<'_>,
+	) -> Result<bool, Error> {
+		match &tb.cond {
+			Some(cond) => Ok(cond.compute(ctx, opt, txn, Some(doc)).await?.is_truthy()),
+			None => Ok(true),
+		}
+	}
+	/// Compute the GROUP BY values for a version of this document
+	async fn grouping(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		group: &Groups,
+		doc: &CursorDoc<'_>,
+	) -> Result<Vec<Value>, Error> {
+		try_join_all(group.iter().map(|v| v.compute(ctx, opt, txn, Some(doc)))).await
+	}
+	/// Apply the change to this document to the aggregates of a view group
+	async fn delta(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		act: Action,
+		rid: &Thing,
+		tb: &View,
+	) -> Result<(), Error> {
+		// Modify the value in the table
+		let stm = UpdateStatement {
+			what: Values(vec![Value::from(rid.clone())]),
+			data: Some(self.data(ctx, opt, txn, act, rid, &tb.expr).await?),
+			..UpdateStatement::default()
+		};
+		// Execute the statement
+		stm.compute(ctx, opt, txn, None).await?;
+		// Carry on
+		Ok(())
+	}
+	/// Check if a view group is stored without the number of records in
+	/// it, as groups were stored before the records were counted. These
+	/// groups are recalculated, rather than changed, when they are next
+	/// changed, so that they are removed once they no longer hold records.
+	async fn uncounted(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		rid: &Thing,
+	) -> Result<bool, Error> {
+		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		match txn.lock().await.get(key).await? {
+			Some(v) => Ok(!Value::from(&v).pick(&Self::members()).is_number()),
+			None => Ok(false),
+		}
+	}
+	/// Check if every aggregate in the view can be reversed when a
+	/// record is removed from a group. The previous minimum or maximum
+	/// value of a group can not be known without looking at the group,
+	/// and a value can not be removed from a distinct count sketch.
+	fn reversible(tb: &View) -> bool {
+		!tb.expr.other().any(|v| match v {
+			Field::Single {
+				expr: Value::Function(f),
+				..
+			} => {
+				f.is_rolling()
+					&& [
+						"math::min",
+						"math::max",
+						"time::min",
+						"time::max",
+						"count::distinct_approx",
+					]
+					.contains(&f.name())
+			}
+			_ => false,
+		})
+	}
+	/// Recalculate a view group from the records in the source tables.
+	/// This is used when a record leaves a group whose aggregates can
+	/// not be reversed, so that any minimum or maximum values are correct,
+	/// and so that the group is removed once it no longer holds records.
+	async fn regroup(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		ft: &DefineTableStatement,
+		key: &[Value],
+	) -> Result<(), Error> {
+		// Get the table definition
+		let tb = ft.view.as_ref().unwrap();
+		let group = tb.group.as_ref().unwrap();
+		// Select only the records in this group
+		let mut cond = tb.cond.as_ref().map(|v| v.0.clone());
+		for (g, v) in group.iter().zip(key.iter()) {
+			let exp = Value::Expression(Box::new(Expression::Binary {
+				l: Value::Idiom(g.0.clone()),
+				o: Operator::Equal,
+				r: v.clone(),
+			}));
+			cond = Some(match cond {
+				Some(c) => Value::Expression(Box::new(Expression::Binary {
+					l: c,
+					o: Operator::And,
+					r: exp,
+				})),
+				None => exp,
+			});
+		}
+		// Also calculate the number of records in the group, the counters used
+		// for any mean values, and the distinct values of any distinct count sketches
+		let mut fields = tb.expr.clone();
+		fields.0.push(Field::Single {
+			expr: Value::Function(Box::new(Function::Normal("count".to_owned(), vec![]))),
+			alias: Some(Self::members()),
+		});
+		let mut sketches = vec![];
+		for field in tb.expr.other() {
+			if let Field::Single {
+				expr: Value::Function(f),
+				alias,
+			} = field
+			{
+				let idiom = || alias.clone().unwrap_or_else(|| f.to_idiom());
+				match f.name() {
+					"math::mean" if f.is_rolling() => {
+						let count = Function::Normal("count".to_owned(), vec![]);
+						fields.0.push(Field::Single {
+							expr: Value::Function(Box::new(count)),
+							alias: Some(Self::counter(&idiom())),
+						});
+					}
+					"count::distinct_approx" => {
+						let distinct =
+							Function::Normal("array::distinct".to_owned(), f.args().to_vec());
+						let key = Self::sketch(&idiom());
+						fields.0.push(Field::Single {
+							expr: Value::Function(Box::new(distinct)),
+							alias: Some(key.clone()),
+						});
+						sketches.push(key);
+					}
+					_ => {}
+				}
+			}
+		}
+		// Select the group from the source tables
+		let stm = SelectStatement {
+			expr: fields,
+			what: Values(tb.what.0.iter().map(|v| Value::Table(v.clone())).collect()),
+			cond: cond.map(Cond),
+			group: Some(group.clone()),
+			..SelectStatement::default()
+		};
+		let val = stm.compute(ctx, opt, txn, None).await?.first();
+		// Set the group record id
+		let rid = Thing {
+			tb: ft.name.to_raw(),
+			id: key.to_vec().into(),
+		};
+		// Define the statement
+		let stm = match val {
+			// The group no longer has any records
+			Value::None => Query::Delete(DeleteStatement {
+				what: Values(vec![Value::from(rid)]),
+				..DeleteStatement::default()
+			}),
+			// Replace the value in the table
+			mut val => {
+				val.cut(ID.as_ref());
+				// Store the sketches of the distinct values, rather than the values
+				for key in sketches {
+					let mut sketch = Sketch::default();
+					if let Value::Array(v) = val.pick(&key) {
+						v.iter().for_each(|v| sketch.add(v));
+					}
+					val.put(&key, Value::from(Bytes::from(sketch.into_bytes())));
+				}
+				Query::Update(UpdateStatement {
+					what: Values(vec![Value::from(rid)]),
+					data: Some(Data::ReplaceExpression(val)),
+					..UpdateStatement::default()
+				})
+			}
+		};
+		// Execute the statement
+		stm.compute(ctx, opt, txn, None).await?;
+		// Carry on
+		Ok(())
+	}
 	//
 	async fn full(
 		&self,
@@ -255,6 +438,7 @@ impl<'a> Document<'a> {
 		opt: &Options,
 		txn: &Transaction,
 		act: Action,
+		rid: &Thing,
 		exp: &Fields,
 	) -> Result<Data, Error> {
 		//
@@ -302,6 +486,10 @@ impl<'a> Document<'a> {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
 							self.mean(&mut ops, &act, idiom, val);
 						}
+						"count::distinct_approx" => {
+							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
+							self.distinct(opt, txn, &mut ops, &act, rid, idiom, val).await?;
+						}
 						_ => unreachable!(),
 					},
 					_ => {
@@ -311,6 +499,8 @@ impl<'a> Document<'a> {
 				}
 			}
 		}
+		// Count the records in the group
+		self.chg(&mut ops, &act, Self::members(), Value::from(1));
 		//
 		Ok(Data::SetExpression(ops))
 	}
@@ -370,12 +560,39 @@ impl<'a> Document<'a> {
 			));
 		}
 	}
+	/// Add the value to the distinct count sketch of the field in the foreign table
+	#[allow(clippy::too_many_arguments)]
+	async fn distinct(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		ops: &mut Ops,
+		act: &Action,
+		rid: &Thing,
+		key: Idiom,
+		val: Value,
+	) -> Result<(), Error> {
+		if act == &Action::Update {
+			let key_s = Self::sketch(&key);
+			// Get the sketch of the group so far
+			let thg = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+			let mut sketch = match txn.lock().await.get(thg).await? {
+				Some(v) => match Value::from(&v).pick(&key_s) {
+					Value::Bytes(b) => Sketch::from_bytes(b.into_inner()),
+					_ => Sketch::default(),
+				},
+				None => Sketch::default(),
+			};
+			sketch.add(&val);
+			ops.push((key, Operator::Equal, Value::from(sketch.estimate())));
+			ops.push((key_s, Operator::Equal, Value::from(Bytes::from(sketch.into_bytes()))));
+		}
+		Ok(())
+	}
 	/// Set the new average value for the field in the foreign table
 	fn mean(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
 		//
-		let mut key_c = Idiom::from(vec![Part::from("__")]);
-		key_c.0.push(Part::from(key.to_hash()));
-		key_c.0.push(Part::from("c"));
+		let key_c = Self::counter(&key);
 		//
 		ops.push((
 			key.clone(),
@@ -441,4 +658,22 @@ impl<'a> Document<'a> {
 			Value::from(1),
 		));
 	}
+	/// The hidden field which counts the records in a group
+	fn members() -> Idiom {
+		Idiom::from(vec![Part::from("__"), Part::from("n")])
+	}
+	/// The hidden field which counts the values in a mean
+	fn counter(key: &Idiom) -> Idiom {
+		let mut key_c = Idiom::from(vec![Part::from("__")]);
+		key_c.0.push(Part::from(key.to_hash()));
+		key_c.0.push(Part::from("c"));
+		key_c
+	}
+	/// The hidden field which holds the sketch of a distinct count
+	fn sketch(key: &Idiom) -> Idiom {
+		let mut key_s = Idiom::from(vec![Part::from("__")]);
+		key_s.0.push(Part::from(key.to_hash()));
+		key_s.0.push(Part::from("s"));
+		key_s
+	}
 }
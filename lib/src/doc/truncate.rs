@@ -0,0 +1,28 @@
+use crate::ctx::Context;
+use crate::dbs::Statement;
+use crate::dbs::{Options, Transaction};
+use crate::doc::Document;
+use crate::err::Error;
+
+impl<'a> Document<'a> {
+	/// Removes a record for a TRUNCATE statement, which removes the record data and
+	/// index data itself with range deletes, so only cleans up what depends on it.
+	pub async fn truncate(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Erase document
+		self.erase(ctx, opt, stm).await?;
+		// Purge record data
+		self.purge(ctx, opt, txn, stm).await?;
+		// Run table queries
+		self.table(ctx, opt, txn, stm).await?;
+		// Run lives queries
+		self.lives(ctx, opt, txn, stm).await?;
+		// Run change feeds queries
+		self.changefeeds(ctx, opt, txn, stm).await
+	}
+}
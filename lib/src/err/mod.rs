@@ -48,6 +48,11 @@ pub enum Error {
 	#[error("{0}")]
 	Deprecated(String),
 
+	/// An error which occurred when a request was first processed, returned
+	/// again when the request is retried with the same idempotency key
+	#[error("{0}")]
+	Replayed(String),
+
 	/// A custom error has been thrown
 	#[error("An error occurred: {0}")]
 	Thrown(String),
@@ -56,6 +61,18 @@ pub enum Error {
 	#[error("There was a problem with the underlying datastore: {0}")]
 	Ds(String),
 
+	/// A compaction of the storage engine is already running
+	#[error("A compaction of the storage engine is already running")]
+	DsCompactionRunning,
+
+	/// The lease to build indexes was taken by another node
+	#[error("The lease to build indexes was taken by another node")]
+	DsBuildLeaseLost,
+
+	/// The datastore is shutting down, and is not accepting new queries
+	#[error("The datastore is shutting down")]
+	DsShutdown,
+
 	/// There was a problem with a datastore transaction
 	#[error("There was a problem with a datastore transaction: {0}")]
 	Tx(String),
@@ -68,6 +85,14 @@ pub enum Error {
 	#[error("Couldn't update a finished transaction")]
 	TxFinished,
 
+	/// A savepoint was used outside of a transaction
+	#[error("Savepoints can only be used within a transaction")]
+	TxSavepoint,
+
+	/// A transaction statement was used within an atomic batch
+	#[error("Transaction statements can not be used within an atomic batch")]
+	TxAtomic,
+
 	/// The current transaction was created as read-only
 	#[error("Couldn't write to a read only transaction")]
 	TxReadonly,
@@ -92,6 +117,44 @@ pub enum Error {
 	#[error("Transaction is too large")]
 	TxTooLarge,
 
+	/// The transaction conflicted with a concurrent transaction, and can be retried
+	#[error("Failed to commit transaction due to a read or write conflict. This transaction can be retried")]
+	TxRetryable,
+
+	/// The isolation level is not supported by the KV store
+	#[error("The storage engine does not support the {value} isolation level")]
+	TxIsolationNotSupported {
+		value: String,
+	},
+
+	/// The KV store has run out of the storage quota available to it
+	#[error("The storage quota for the datastore has been exceeded")]
+	StorageQuotaExceeded,
+
+	/// The write would exceed the storage quota of the namespace
+	#[error("The storage quota of {quota} bytes for the namespace '{value}' has been exceeded")]
+	NsQuotaExceeded {
+		value: String,
+		quota: u64,
+	},
+
+	/// The write would exceed the storage quota of the database
+	#[error("The storage quota of {quota} bytes for the database '{value}' has been exceeded")]
+	DbQuotaExceeded {
+		value: String,
+		quota: u64,
+	},
+
+	/// A write was made on a node which is not the leader of its replication group
+	#[error("Writes can only be made on the leader of the replication group")]
+	ReplicaNotLeader,
+
+	/// The changes of a write could not be replicated to a majority of the replication group
+	#[error("The write could not be replicated: {message}")]
+	ReplicationFailed {
+		message: String,
+	},
+
 	/// No namespace has been selected
 	#[error("Specify a namespace to use")]
 	NsEmpty,
@@ -124,6 +187,13 @@ pub enum Error {
 		sql: String,
 	},
 
+	/// There was an error with a line of a JSON import
+	#[error("Unable to import line {line} of the JSON: {message}")]
+	InvalidImport {
+		line: usize,
+		message: String,
+	},
+
 	/// There was an error with the provided JSON Patch
 	#[error("The JSON Patch contains invalid operations. {message}")]
 	InvalidPatch {
@@ -165,6 +235,12 @@ pub enum Error {
 		field: String,
 	},
 
+	/// A parameterized ORDER BY field is checked once it has been computed
+	#[error("Found '{field}' in ORDER BY clause, but field is not present in SELECT expression")]
+	InvalidOrderField {
+		field: String,
+	},
+
 	#[error("Found '{field}' in GROUP BY clause on line {line}, but field is not present in SELECT expression")]
 	InvalidGroup {
 		line: usize,
@@ -183,6 +259,49 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The AFTER clause could not be used to paginate the query
+	#[error("Unable to paginate with the AFTER clause: {message}")]
+	InvalidCursor {
+		message: String,
+	},
+
+	/// The table does not keep the history needed to read it at an earlier version
+	#[error("The table '{value}' can not be read at an earlier version, as its changefeed does not INCLUDE ORIGINAL values")]
+	TbHistoryNotEnabled {
+		value: String,
+	},
+
+	/// The table does not retain its history as far back as the specified version
+	#[error("The table '{tb}' does not retain its history as far back as {version}")]
+	VersionExpired {
+		tb: String,
+		version: String,
+	},
+
+	/// The table can not be included in an incremental backup
+	#[error("The table '{value}' can not be included in an incremental backup, as it is not defined with a CHANGEFEED")]
+	BackupChangefeedRequired {
+		value: String,
+	},
+
+	/// The changes since the previous backup are no longer kept in the change feeds
+	#[error("The changes since the backup at versionstamp {since} are no longer kept in the change feeds")]
+	BackupExpired {
+		since: u64,
+	},
+
+	/// The backups can not be restored one after another
+	#[error("The backups can not be restored: {message}")]
+	InvalidBackupChain {
+		message: String,
+	},
+
+	/// The shortest path target must evaluate to a record id
+	#[error("Found {value} but the shortest path target must evaluate to a record id")]
+	InvalidShortest {
+		value: String,
+	},
+
 	/// There was an error with the provided JavaScript code
 	#[error("Problem with embedded script function. {message}")]
 	InvalidScript {
@@ -285,6 +404,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested sequence does not exist
+	#[error("The sequence '{value}' does not exist")]
+	SqNotFound {
+		value: String,
+	},
+
 	// The cluster node already exists
 	#[error("The node '{value}' already exists")]
 	ClAlreadyExists {
@@ -315,6 +440,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested savepoint does not exist
+	#[error("The savepoint '{value}' does not exist")]
+	SpNotFound {
+		value: String,
+	},
+
 	/// The requested live query does not exist
 	#[error("The live query '{value}' does not exist")]
 	LvNotFound {
@@ -339,6 +470,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested field already exists
+	#[error("The field '{value}' already exists")]
+	FdAlreadyExists {
+		value: String,
+	},
+
 	/// The requested root user does not exist
 	#[error("The root user '{value}' does not exist")]
 	UserRootNotFound {
@@ -416,6 +553,18 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The session has been terminated
+	#[error("The session '{value}' has been terminated")]
+	SessionTerminated {
+		value: String,
+	},
+
+	/// The requested session does not exist
+	#[error("The session '{value}' does not exist")]
+	SessionNotFound {
+		value: String,
+	},
+
 	/// The permissions do not allow this query to be run on this table
 	#[error("You don't have permission to run this query on the `{table}` table")]
 	TablePermissions {
@@ -440,6 +589,12 @@ pub enum Error {
 		table: String,
 	},
 
+	/// The specified table can not be refreshed as it is not setup as a foreign table view
+	#[error("Unable to refresh the `{table}` table as it is not setup as a view")]
+	TableIsNotView {
+		table: String,
+	},
+
 	/// A database entry for the specified record already exists
 	#[error("Database record `{thing}` already exists")]
 	RecordExists {
@@ -561,12 +716,45 @@ pub enum Error {
 	#[error("Index is corrupted")]
 	CorruptedIndex,
 
+	/// The chunks of a large record value are missing or inconsistent
+	#[error("The chunks of a large value are missing or corrupted")]
+	CorruptedChunks,
+
 	/// The query planner did not find an index able to support the match @@ operator on a given expression
 	#[error("There was no suitable full-text index supporting the expression '{value}'")]
 	NoIndexFoundForMatch {
 		value: String,
 	},
 
+	/// The query planner did not find an index able to support the knn <|k|> operator on a given expression
+	#[error("There was no suitable vector index supporting the expression '{value}'")]
+	NoIndexFoundForKnn {
+		value: String,
+	},
+
+	/// There is no index on the field whose facets are requested
+	#[error("There was no suitable index on the field '{value}' to compute its facets")]
+	NoIndexFoundForFacets {
+		value: String,
+	},
+
+	/// A vector does not have the dimension of the index
+	#[error("Incorrect vector dimension ({current}). Expected a vector of {expected} dimension.")]
+	InvalidVectorDimension {
+		current: usize,
+		expected: usize,
+	},
+
+	/// A vector contains a value which is not a number
+	#[error("Incorrect vector value: {value}")]
+	InvalidVectorValue {
+		value: String,
+	},
+
+	/// A phrase or a proximity operator is used on a full-text index not storing the positions of the terms
+	#[error("Phrase and proximity queries require a full-text index defined with HIGHLIGHTS")]
+	FtPositionsNotIndexed,
+
 	/// Represents an error when analyzing a value
 	#[error("A value can't be analyzed: {0}")]
 	AnalyzerError(String),
@@ -640,6 +828,13 @@ pub enum Error {
 	NetTargetNotAllowed(String),
 }
 
+impl Error {
+	/// Check whether the statement which failed with this error can be retried in a new transaction
+	pub fn is_retryable(&self) -> bool {
+		matches!(self, Error::TxRetryable)
+	}
+}
+
 impl From<Error> for String {
 	fn from(e: Error) -> String {
 		e.to_string()
@@ -658,7 +853,7 @@ impl From<JWTError> for Error {
 	}
 }
 
-#[cfg(feature = "kv-mem")]
+#[cfg(any(feature = "kv-mem", feature = "kv-object"))]
 impl From<echodb::err::Error> for Error {
 	fn from(e: echodb::err::Error) -> Error {
 		match e {
@@ -675,6 +870,7 @@ impl From<indxdb::err::Error> for Error {
 		match e {
 			indxdb::err::Error::KeyAlreadyExists => Error::TxKeyAlreadyExists,
 			indxdb::err::Error::ValNotExpectedValue => Error::TxConditionNotMet,
+			_ if e.to_string().contains("QuotaExceededError") => Error::StorageQuotaExceeded,
 			_ => Error::Tx(e.to_string()),
 		}
 	}
@@ -685,6 +881,7 @@ impl From<tikv::Error> for Error {
 	fn from(e: tikv::Error) -> Error {
 		match e {
 			tikv::Error::DuplicateKeyInsertion => Error::TxKeyAlreadyExists,
+			tikv::Error::KeyError(ke) if ke.conflict.is_some() => Error::TxRetryable,
 			tikv::Error::KeyError(ke) if ke.abort.contains("KeyTooLarge") => Error::TxKeyTooLarge,
 			tikv::Error::RegionError(re) if re.raft_entry_too_large.is_some() => Error::TxTooLarge,
 			_ => Error::Tx(e.to_string()),
@@ -695,6 +892,58 @@ impl From<tikv::Error> for Error {
 #[cfg(feature = "kv-speedb")]
 impl From<speedb::Error> for Error {
 	fn from(e: speedb::Error) -> Error {
+		match e.kind() {
+			speedb::ErrorKind::Busy | speedb::ErrorKind::TryAgain => Error::TxRetryable,
+			_ => Error::Tx(e.to_string()),
+		}
+	}
+}
+
+#[cfg(any(feature = "kv-object", feature = "archive"))]
+impl From<object_store::Error> for Error {
+	fn from(e: object_store::Error) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::Error> for Error {
+	fn from(e: redb::Error) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::DatabaseError> for Error {
+	fn from(e: redb::DatabaseError) -> Error {
+		Error::Ds(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::TransactionError> for Error {
+	fn from(e: redb::TransactionError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::TableError> for Error {
+	fn from(e: redb::TableError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::StorageError> for Error {
+	fn from(e: redb::StorageError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::CommitError> for Error {
+	fn from(e: redb::CommitError) -> Error {
 		Error::Tx(e.to_string())
 	}
 }
@@ -702,7 +951,10 @@ impl From<speedb::Error> for Error {
 #[cfg(feature = "kv-rocksdb")]
 impl From<rocksdb::Error> for Error {
 	fn from(e: rocksdb::Error) -> Error {
-		Error::Tx(e.to_string())
+		match e.kind() {
+			rocksdb::ErrorKind::Busy | rocksdb::ErrorKind::TryAgain => Error::TxRetryable,
+			_ => Error::Tx(e.to_string()),
+		}
 	}
 }
 
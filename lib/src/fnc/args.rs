@@ -264,6 +264,47 @@ impl<A: FromArg, B: FromArg, C: FromArg> FromArgs for (A, B, Option<C>) {
 	}
 }
 
+// Some functions take 3 or 4 arguments, so the fourth argument is optional.
+impl<A: FromArg, B: FromArg, C: FromArg, D: FromArg> FromArgs for (A, B, C, Option<D>) {
+	fn from_args(name: &str, args: Vec<Value>) -> Result<Self, Error> {
+		let err = || Error::InvalidArguments {
+			name: name.to_owned(),
+			message: String::from("Expected 3 or 4 arguments."),
+		};
+		// Process the function arguments
+		let mut args = args.into_iter();
+		// Process the first function argument
+		let a = A::from_arg(args.next().ok_or_else(err)?).map_err(|e| Error::InvalidArguments {
+			name: name.to_owned(),
+			message: format!("Argument 1 was the wrong type. {e}"),
+		})?;
+		// Process the second function argument
+		let b = B::from_arg(args.next().ok_or_else(err)?).map_err(|e| Error::InvalidArguments {
+			name: name.to_owned(),
+			message: format!("Argument 2 was the wrong type. {e}"),
+		})?;
+		// Process the third function argument
+		let c = C::from_arg(args.next().ok_or_else(err)?).map_err(|e| Error::InvalidArguments {
+			name: name.to_owned(),
+			message: format!("Argument 3 was the wrong type. {e}"),
+		})?;
+		// Process the fourth function argument
+		let d = match args.next() {
+			Some(d) => Some(D::from_arg(d).map_err(|e| Error::InvalidArguments {
+				name: name.to_owned(),
+				message: format!("Argument 4 was the wrong type. {e}"),
+			})?),
+			None => None,
+		};
+		// Process additional function arguments
+		if args.next().is_some() {
+			// Too many arguments
+			return Err(err());
+		}
+		Ok((a, b, c, d))
+	}
+}
+
 // Some functions take 0, 1, or 2 arguments, so both arguments are optional.
 // It is safe to assume that, if the first argument is None, the second argument will also be None.
 impl<A: FromArg, B: FromArg> FromArgs for (Option<A>, Option<B>) {
@@ -372,3 +413,55 @@ impl<A: FromArg, B: FromArg, C: FromArg> FromArgs for (A, Option<B>, Option<C>)
 		Ok((a, b, c))
 	}
 }
+
+// Some functions take 3, 4, or 5 arguments. It is safe to assume that, if the fourth argument is
+// None, the fifth argument will also be None.
+impl<A: FromArg, B: FromArg, C: FromArg, D: FromArg, E: FromArg> FromArgs
+	for (A, B, C, Option<D>, Option<E>)
+{
+	fn from_args(name: &str, args: Vec<Value>) -> Result<Self, Error> {
+		let err = || Error::InvalidArguments {
+			name: name.to_owned(),
+			message: String::from("Expected 3, 4, or 5 arguments."),
+		};
+		// Process the function arguments
+		let mut args = args.into_iter();
+		// Process the first function argument
+		let a = A::from_arg(args.next().ok_or_else(err)?).map_err(|e| Error::InvalidArguments {
+			name: name.to_owned(),
+			message: format!("Argument 1 was the wrong type. {e}"),
+		})?;
+		// Process the second function argument
+		let b = B::from_arg(args.next().ok_or_else(err)?).map_err(|e| Error::InvalidArguments {
+			name: name.to_owned(),
+			message: format!("Argument 2 was the wrong type. {e}"),
+		})?;
+		// Process the third function argument
+		let c = C::from_arg(args.next().ok_or_else(err)?).map_err(|e| Error::InvalidArguments {
+			name: name.to_owned(),
+			message: format!("Argument 3 was the wrong type. {e}"),
+		})?;
+		// Process the fourth function argument
+		let d = match args.next() {
+			Some(d) => Some(D::from_arg(d).map_err(|e| Error::InvalidArguments {
+				name: name.to_owned(),
+				message: format!("Argument 4 was the wrong type. {e}"),
+			})?),
+			None => None,
+		};
+		// Process the fifth function argument
+		let e = match args.next() {
+			Some(e) => Some(E::from_arg(e).map_err(|e| Error::InvalidArguments {
+				name: name.to_owned(),
+				message: format!("Argument 5 was the wrong type. {e}"),
+			})?),
+			None => None,
+		};
+		// Process additional function arguments
+		if args.next().is_some() {
+			// Too many arguments
+			return Err(err());
+		}
+		Ok((a, b, c, d, e))
+	}
+}
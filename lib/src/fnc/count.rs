@@ -1,4 +1,5 @@
 use crate::err::Error;
+use crate::fnc::util::hll::Sketch;
 use crate::sql::value::Value;
 
 pub fn count((arg,): (Option<Value>,)) -> Result<Value, Error> {
@@ -9,3 +10,13 @@ pub fn count((arg,): (Option<Value>,)) -> Result<Value, Error> {
 		})
 		.unwrap_or_else(|| 1.into()))
 }
+
+/// Estimate the number of distinct values, such as the values of a field within a group
+pub fn distinct_approx((arg,): (Value,)) -> Result<Value, Error> {
+	let mut sketch = Sketch::default();
+	match arg {
+		Value::Array(v) => v.iter().for_each(|v| sketch.add(v)),
+		v => sketch.add(&v),
+	}
+	Ok(sketch.estimate().into())
+}
@@ -0,0 +1,9 @@
+use crate::err::Error;
+use crate::sql::Value;
+
+/// Returns 1 if a group field is rolled up into the results of a ROLLUP or CUBE
+/// grouping set, and 0 otherwise. The field is only rolled up when computing the
+/// fields of a grouped SELECT statement, so this is 0 anywhere else.
+pub fn grouping((_,): (Value,)) -> Result<Value, Error> {
+	Ok(0.into())
+}
@@ -14,15 +14,18 @@ pub mod crypto;
 pub mod duration;
 pub mod encoding;
 pub mod geo;
+pub mod grouping;
 pub mod http;
 pub mod math;
 pub mod meta;
 pub mod not;
 pub mod operate;
 pub mod parse;
+pub mod path;
 pub mod rand;
 pub mod script;
 pub mod search;
+pub mod sequence;
 pub mod session;
 pub mod sleep;
 pub mod string;
@@ -41,7 +44,9 @@ pub async fn run(
 	args: Vec<Value>,
 ) -> Result<Value, Error> {
 	if name.eq("sleep")
+		|| name.starts_with("path")
 		|| name.starts_with("search")
+		|| name.starts_with("sequence")
 		|| name.starts_with("http")
 		|| name.starts_with("type::field")
 		|| name.starts_with("type::fields")
@@ -127,6 +132,7 @@ pub fn synchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Va
 		"bytes::len" => bytes::len,
 		//
 		"count" => count::count,
+		"count::distinct_approx" => count::distinct_approx,
 		//
 		"crypto::md5" => crypto::md5,
 		"crypto::sha1" => crypto::sha1,
@@ -161,6 +167,8 @@ pub fn synchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Va
 		"geo::hash::decode" => geo::hash::decode,
 		"geo::hash::encode" => geo::hash::encode,
 		//
+		"grouping" => grouping::grouping,
+		//
 		"math::abs" => math::abs,
 		"math::bottom" => math::bottom,
 		"math::ceil" => math::ceil,
@@ -295,6 +303,7 @@ pub fn synchronous(ctx: &Context<'_>, name: &str, args: Vec<Value>) -> Result<Va
 		"type::string" => r#type::string,
 		"type::table" => r#type::table,
 		"type::thing" => r#type::thing,
+		"type::validate" => r#type::validate,
 		"type::is::array" => r#type::is::array,
 		"type::is::bool" => r#type::is::bool,
 		"type::is::bytes" => r#type::is::bytes,
@@ -385,10 +394,15 @@ pub async fn asynchronous(
 		"http::patch" => http::patch(ctx).await,
 		"http::delete" => http::delete(ctx).await,
 		//
+		"path::shortest" => path::shortest((ctx, opt, txn)).await,
+		//
+		"search::facets" => search::facets((ctx, opt, txn, doc)).await,
 		"search::score" => search::score((ctx, txn, doc)).await,
 		"search::highlight" => search::highlight((ctx,txn, doc)).await,
 		"search::offsets" => search::offsets((ctx, txn, doc)).await,
 		//
+		"sequence::next" => sequence::next((ctx, opt, txn)).await,
+		//
 		"sleep" => sleep::sleep(ctx).await,
 		//
 		"type::field" => r#type::field((ctx, opt, txn, doc)).await,
@@ -0,0 +1,201 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::key::graph;
+use crate::key::thing;
+use crate::sql::dir::Dir;
+use crate::sql::permission::Permission;
+use crate::sql::{Thing, Value};
+use std::collections::{HashMap, VecDeque};
+
+/// The traversal options which can be passed to a path function
+struct Traversal {
+	/// The edge directions which can be followed
+	dirs: Vec<Dir>,
+	/// The maximum number of edges in a path
+	depth: Option<usize>,
+}
+
+impl Traversal {
+	fn parse(name: &str, opts: Option<Value>) -> Result<Self, Error> {
+		let err = |message: &str| Error::InvalidArguments {
+			name: name.to_owned(),
+			message: message.to_owned(),
+		};
+		let mut res = Traversal {
+			dirs: vec![Dir::Out],
+			depth: None,
+		};
+		match opts {
+			None | Some(Value::None) | Some(Value::Null) => (),
+			Some(Value::Object(o)) => {
+				match o.get("dir") {
+					None | Some(Value::None) => (),
+					Some(Value::Strand(v)) => {
+						res.dirs = match v.to_lowercase().as_str() {
+							"out" => vec![Dir::Out],
+							"in" => vec![Dir::In],
+							"both" => vec![Dir::Out, Dir::In],
+							_ => {
+								return Err(err("The 'dir' option must be 'out', 'in', or 'both'."))
+							}
+						}
+					}
+					Some(_) => return Err(err("The 'dir' option must be a string.")),
+				}
+				match o.get("depth") {
+					None | Some(Value::None) => (),
+					Some(v) => match v.to_owned().coerce_to_u64() {
+						Ok(v) => res.depth = Some(v as usize),
+						Err(_) => {
+							return Err(err("The 'depth' option must be a positive integer."))
+						}
+					},
+				}
+			}
+			Some(_) => return Err(err("Argument 4 was the wrong type. Expected an object.")),
+		}
+		Ok(res)
+	}
+}
+
+/// Find the shortest path between two records, following the edges in a
+/// single edge table. The path is returned as an array of alternating
+/// records and edges, starting with the first record and ending with the
+/// second record, or NONE if the records are not connected.
+pub async fn shortest(
+	(ctx, opt, txn): (&Context<'_>, Option<&Options>, Option<&Transaction>),
+	(from, to, tb, opts): (Thing, Thing, String, Option<Value>),
+) -> Result<Value, Error> {
+	match (opt, txn) {
+		(Some(opt), Some(txn)) => {
+			// Check that a database is selected
+			opt.valid_for_db()?;
+			// Process the traversal options
+			let trv = Traversal::parse("path::shortest", opts)?;
+			// A record is always connected to itself
+			if from == to {
+				return Ok(vec![Value::from(from)].into());
+			}
+			// Get the edge table permissions
+			let perms = match opt.check_perms(Action::View) {
+				true => match txn.lock().await.get_and_cache_tb(opt.ns(), opt.db(), &tb).await {
+					Ok(v) => Some(v.permissions.select.to_owned()),
+					Err(Error::TbNotFound {
+						..
+					}) => return Ok(Value::None),
+					Err(e) => return Err(e),
+				},
+				false => None,
+			};
+			// The record and edge through which each record was reached
+			let mut prev: HashMap<Thing, (Thing, Thing)> = HashMap::new();
+			// The records which are yet to be visited, and their distance
+			let mut next = VecDeque::from([(from.clone(), 0)]);
+			// Perform a breadth-first search
+			while let Some((rid, depth)) = next.pop_front() {
+				// Check if the context is finished
+				ctx.check()?;
+				// Don't traverse any further than the maximum depth
+				if trv.depth.is_some_and(|max| depth >= max) {
+					continue;
+				}
+				for dir in trv.dirs.iter() {
+					for (eg, nxt) in neighbours(ctx, opt, txn, &rid, dir, &tb, &perms).await? {
+						// Skip any records which have already been reached
+						if nxt == from || prev.contains_key(&nxt) {
+							continue;
+						}
+						prev.insert(nxt.clone(), (rid.clone(), eg));
+						// Check if we have reached the destination
+						if nxt == to {
+							return Ok(path(&from, &to, &prev).into());
+						}
+						next.push_back((nxt, depth + 1));
+					}
+				}
+			}
+			Ok(Value::None)
+		}
+		_ => Ok(Value::None),
+	}
+}
+
+/// Fetch the edges from a record in a specific direction, along
+/// with the record which is found at the other end of each edge
+async fn neighbours(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	rid: &Thing,
+	dir: &Dir,
+	tb: &str,
+	perms: &Option<Permission>,
+) -> Result<Vec<(Thing, Thing)>, Error> {
+	let mut out = Vec::new();
+	// Fetch the edges from this record
+	let beg = graph::ftprefix(opt.ns(), opt.db(), &rid.tb, &rid.id, dir, tb);
+	let end = graph::ftsuffix(opt.ns(), opt.db(), &rid.tb, &rid.id, dir, tb);
+	let edges = txn.lock().await.getr(beg..end, u32::MAX).await?;
+	for (k, _) in edges {
+		let gra: graph::Graph = (&k).into();
+		let eg = Thing::from((gra.ft, gra.fk));
+		// Check that this edge can be viewed
+		if !viewable(ctx, opt, txn, &eg, perms).await? {
+			continue;
+		}
+		// Fetch the record at the other end of this edge
+		let beg = graph::egprefix(opt.ns(), opt.db(), &eg.tb, &eg.id, dir);
+		let end = graph::egsuffix(opt.ns(), opt.db(), &eg.tb, &eg.id, dir);
+		let keys = txn.lock().await.getr(beg..end, u32::MAX).await?;
+		for (k, _) in keys {
+			let gra: graph::Graph = (&k).into();
+			out.push((eg.clone(), Thing::from((gra.ft, gra.fk))));
+		}
+	}
+	Ok(out)
+}
+
+/// Check whether the edge table permissions allow an edge to be viewed
+async fn viewable(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	eg: &Thing,
+	perms: &Option<Permission>,
+) -> Result<bool, Error> {
+	match perms {
+		None | Some(Permission::Full) => Ok(true),
+		Some(Permission::None) => Ok(false),
+		Some(Permission::Specific(e)) => {
+			// Fetch the edge record
+			let key = thing::new(opt.ns(), opt.db(), &eg.tb, &eg.id);
+			let val = match txn.lock().await.get(key).await? {
+				Some(v) => Value::from(v),
+				None => return Ok(false),
+			};
+			// Disable permissions
+			let opt = &opt.new_with_perms(false);
+			// Process the PERMISSION clause
+			let doc = CursorDoc::new(None, Some(eg), None, &val);
+			Ok(e.compute(ctx, opt, txn, Some(&doc)).await?.is_truthy())
+		}
+	}
+}
+
+/// Walk back from the destination to build the path
+fn path(from: &Thing, to: &Thing, prev: &HashMap<Thing, (Thing, Thing)>) -> Vec<Value> {
+	let mut out = vec![Value::from(to.clone())];
+	let mut rid = to;
+	while rid != from {
+		let (p, eg) = &prev[rid];
+		out.push(Value::from(eg.clone()));
+		out.push(Value::from(p.clone()));
+		rid = p;
+	}
+	out.reverse();
+	out
+}
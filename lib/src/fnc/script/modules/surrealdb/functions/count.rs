@@ -0,0 +1,26 @@
+use js::{prelude::Rest, Ctx};
+
+use super::run;
+use crate::sql::value::Value;
+
+pub struct Package;
+
+impl js::module::ModuleDef for Package {
+	fn declare(decls: &mut js::module::Declarations) -> js::Result<()> {
+		decls.declare("default")?;
+		decls.declare("distinct_approx")?;
+		Ok(())
+	}
+	fn evaluate<'js>(ctx: &js::Ctx<'js>, exports: &mut js::module::Exports<'js>) -> js::Result<()> {
+		let default = js::Function::new(ctx.clone(), |ctx: Ctx<'js>, args: Rest<Value>| {
+			run(ctx, "count", args.0)
+		})?
+		.with_name("count")?;
+		let value =
+			crate::fnc::script::modules::impl_module_def!(ctx, "count", "distinct_approx", run,);
+		exports.export("distinct_approx", value.clone())?;
+		default.set("distinct_approx", value)?;
+		exports.export("default", default)?;
+		Ok(())
+	}
+}
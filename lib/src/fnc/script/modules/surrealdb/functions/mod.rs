@@ -9,6 +9,7 @@ use super::query::{QueryContext, QUERY_DATA_PROP_NAME};
 
 mod array;
 mod bytes;
+mod count;
 mod crypto;
 mod duration;
 mod encoding;
@@ -17,8 +18,10 @@ mod http;
 mod math;
 mod meta;
 mod parse;
+mod path;
 mod rand;
 mod search;
+mod sequence;
 mod session;
 mod string;
 mod time;
@@ -32,19 +35,22 @@ impl_module_def!(
 	"", // root path
 	"array" => (array::Package),
 	"bytes" => (bytes::Package),
-	"count" => run,
+	"count" => (count::Package),
 	"crypto" => (crypto::Package),
 	"duration" => (duration::Package),
 	"encoding" => (encoding::Package),
 	"geo" => (geo::Package),
+	"grouping" => run,
 	"http" => (http::Package),
 	"math" => (math::Package),
 	"meta" => (meta::Package),
 	"not" => run,
 	"parse" => (parse::Package),
+	"path" => (path::Package),
 	"rand" => (rand::Package),
 	"array" => (array::Package),
 	"search" => (search::Package),
+	"sequence" => (sequence::Package),
 	"session" => (session::Package),
 	"sleep" => fut Async,
 	"string" => (string::Package),
@@ -1,9 +1,10 @@
 use crate::ctx::Context;
-use crate::dbs::Transaction;
+use crate::dbs::{Options, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::idx::ft::highlighter::HighlightParams;
 use crate::idx::planner::executor::QueryExecutor;
-use crate::sql::{Thing, Value};
+use crate::sql::{idiom, Thing, Value};
 
 fn get_execution_context<'a>(
 	ctx: &'a Context<'_>,
@@ -37,10 +38,22 @@ pub async fn score(
 
 pub async fn highlight(
 	(ctx, txn, doc): (&Context<'_>, Option<&Transaction>, Option<&CursorDoc<'_>>),
-	(prefix, suffix, match_ref): (Value, Value, Value),
+	(prefix, suffix, match_ref, fragment_length, fragments): (
+		Value,
+		Value,
+		Value,
+		Option<usize>,
+		Option<usize>,
+	),
 ) -> Result<Value, Error> {
 	if let Some((txn, exe, doc, thg)) = get_execution_context(ctx, txn, doc) {
-		exe.highlight(txn, thg, prefix, suffix, &match_ref, doc.doc.as_ref()).await
+		let hp = HighlightParams {
+			prefix,
+			suffix,
+			fragment_length,
+			fragments,
+		};
+		exe.highlight(txn, thg, hp, &match_ref, doc.doc.as_ref()).await
 	} else {
 		Ok(Value::None)
 	}
@@ -56,3 +69,20 @@ pub async fn offsets(
 		Ok(Value::None)
 	}
 }
+
+pub async fn facets(
+	(ctx, opt, txn, doc): (
+		&Context<'_>,
+		Option<&Options>,
+		Option<&Transaction>,
+		Option<&CursorDoc<'_>>,
+	),
+	(match_ref, field, size): (Value, String, Option<usize>),
+) -> Result<Value, Error> {
+	if let (Some(opt), Some((txn, exe, _, _))) = (opt, get_execution_context(ctx, txn, doc)) {
+		let field = idiom(&field)?;
+		exe.facets(opt, txn, &match_ref, &field, size).await
+	} else {
+		Ok(Value::None)
+	}
+}
@@ -0,0 +1,32 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::Value;
+
+pub async fn next(
+	(ctx, opt, txn): (&Context<'_>, Option<&Options>, Option<&Transaction>),
+	(name,): (String,),
+) -> Result<Value, Error> {
+	match (opt, txn) {
+		(Some(opt), Some(txn)) => {
+			// Check that a database is selected
+			opt.valid_for_db()?;
+			// Check that the sequence is defined
+			let def = txn.lock().await.get_db_sequence(opt.ns(), opt.db(), &name).await?;
+			// Increment the sequence outside of this transaction
+			if let Some(ds) = ctx.get_datastore() {
+				if let Some(val) = ds.next_sequence(opt.ns(), opt.db(), &name, def.first()).await? {
+					return Ok(val.into());
+				}
+			}
+			// Claim transaction
+			let mut run = txn.lock().await;
+			// Increment the sequence
+			let val = run.next_db_sequence(opt.ns(), opt.db(), &name).await?;
+			// Return the new value
+			Ok(val.into())
+		}
+		_ => Ok(Value::None),
+	}
+}
@@ -2,7 +2,11 @@ use crate::ctx::Context;
 use crate::dbs::{Options, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
-use crate::sql::parser::idiom;
+use crate::sql::idiom::Idiom;
+use crate::sql::kind::Kind;
+use crate::sql::object::Object;
+use crate::sql::parser::{idiom, kind};
+use crate::sql::part::Part;
 use crate::sql::table::Table;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
@@ -120,6 +124,67 @@ pub fn thing((arg1, arg2): (Value, Option<Value>)) -> Result<Value, Error> {
 	})
 }
 
+pub fn validate((val, knd): (Value, String)) -> Result<Value, Error> {
+	// Parse the string as a Kind
+	let knd = kind(&knd)?;
+	// Check the value against the Kind
+	let mut errors = Vec::new();
+	validate_kind(&val, &knd, &mut Idiom::default(), &mut errors)?;
+	// Return any validation errors
+	Ok(errors.into())
+}
+
+/// Checks a value against a `Kind`, recording an error for each nested
+/// value which can not be coerced, instead of stopping at the first one
+fn validate_kind(
+	val: &Value,
+	knd: &Kind,
+	path: &mut Idiom,
+	errors: &mut Vec<Value>,
+) -> Result<(), Error> {
+	match (knd, val) {
+		// An optional value can be empty
+		(Kind::Option(_), Value::None) => Ok(()),
+		// Otherwise check the inner kind
+		(Kind::Option(k), v) => validate_kind(v, k, path, errors),
+		// Check each of the array items
+		(Kind::Array(k, len) | Kind::Set(k, len), Value::Array(v)) => {
+			if let Some(len) = len {
+				if v.len() > *len as usize {
+					errors.push(validate_error(val, knd, path));
+				}
+			}
+			for (i, v) in v.iter().enumerate() {
+				path.0.push(Part::from(i));
+				validate_kind(v, k, path, errors)?;
+				path.0.pop();
+			}
+			Ok(())
+		}
+		// Check any other kind by coercing the value
+		_ => match val.clone().coerce_to(knd) {
+			Err(Error::CoerceTo {
+				..
+			}) => {
+				errors.push(validate_error(val, knd, path));
+				Ok(())
+			}
+			Err(e) => Err(e),
+			Ok(_) => Ok(()),
+		},
+	}
+}
+
+/// Describes where a value did not match the expected `Kind`
+fn validate_error(val: &Value, knd: &Kind, path: &Idiom) -> Value {
+	Object::from(map! {
+		String::from("path") => Value::from(path.to_path()),
+		String::from("expected") => Value::from(knd.to_string()),
+		String::from("found") => val.clone(),
+	})
+	.into()
+}
+
 pub mod is {
 	use crate::err::Error;
 	use crate::sql::value::Value;
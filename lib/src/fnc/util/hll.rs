@@ -0,0 +1,90 @@
+//! HyperLogLog sketches, which estimate the number of distinct values of a set from a
+//! fixed amount of memory. The first bits of the hash of a value select a register, which
+//! keeps the largest number of leading zeros seen in the remaining bits. A sketch does not
+//! depend on the order in which the values were added, so it can be maintained incrementally.
+use crate::sql::value::Value;
+use md5::Digest;
+use md5::Md5;
+
+/// The number of bits of the hash which select a register
+const PRECISION: u32 = 12;
+
+/// The number of registers, for a standard error of about 1.6%
+const REGISTERS: usize = 1 << PRECISION;
+
+pub struct Sketch(Vec<u8>);
+
+impl Default for Sketch {
+	fn default() -> Self {
+		Self(vec![0; REGISTERS])
+	}
+}
+
+impl Sketch {
+	/// Restore a stored sketch, which is empty if the bytes are not a sketch
+	pub fn from_bytes(bytes: Vec<u8>) -> Self {
+		match bytes.len() {
+			REGISTERS => Self(bytes),
+			_ => Self::default(),
+		}
+	}
+
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.0
+	}
+
+	/// Add a value to the set, ignoring empty values
+	pub fn add(&mut self, v: &Value) {
+		if v.is_none_or_null() {
+			return;
+		}
+		let digest = Md5::digest(v.to_string().as_bytes());
+		let mut bytes = [0; 8];
+		bytes.copy_from_slice(&digest[..8]);
+		let hash = u64::from_be_bytes(bytes);
+		let register = (hash >> (64 - PRECISION)) as usize;
+		// The last bit bounds the rank when the remaining bits are all zeros
+		let rest = (hash << PRECISION) | (1 << (PRECISION - 1));
+		let rank = rest.leading_zeros() as u8 + 1;
+		if rank > self.0[register] {
+			self.0[register] = rank;
+		}
+	}
+
+	/// Estimate the number of distinct values added to the sketch
+	pub fn estimate(&self) -> u64 {
+		let m = REGISTERS as f64;
+		let alpha = 0.7213 / (1.0 + 1.079 / m);
+		let sum: f64 = self.0.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+		let raw = alpha * m * m / sum;
+		// Small sets are estimated more precisely from the number of empty registers
+		let empty = self.0.iter().filter(|r| **r == 0).count();
+		let estimate = match raw <= 2.5 * m && empty > 0 {
+			true => m * (m / empty as f64).ln(),
+			false => raw,
+		};
+		estimate.round() as u64
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::fnc::util::hll::Sketch;
+	use crate::sql::value::Value;
+
+	#[test]
+	fn estimate() {
+		let mut sketch = Sketch::default();
+		assert_eq!(sketch.estimate(), 0);
+		for i in 0..10_000 {
+			// Each value is added twice
+			sketch.add(&Value::from(i % 5_000));
+		}
+		sketch.add(&Value::None);
+		let estimate = sketch.estimate() as f64;
+		assert!((estimate - 5_000.0).abs() < 250.0, "{estimate}");
+		// The stored sketch holds the same values
+		let sketch = Sketch::from_bytes(sketch.into_bytes());
+		assert_eq!(sketch.estimate() as f64, estimate);
+	}
+}
@@ -26,6 +26,7 @@ pub enum ResourceKind {
 	Event,
 	Field,
 	Index,
+	Sequence,
 
 	// IAM
 	Actor,
@@ -47,6 +48,7 @@ impl std::fmt::Display for ResourceKind {
 			ResourceKind::Event => write!(f, "Event"),
 			ResourceKind::Field => write!(f, "Field"),
 			ResourceKind::Index => write!(f, "Index"),
+			ResourceKind::Sequence => write!(f, "Sequence"),
 			ResourceKind::Actor => write!(f, "Actor"),
 		}
 	}
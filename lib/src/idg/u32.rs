@@ -53,6 +53,17 @@ impl U32 {
 		doc_id
 	}
 
+	// get_next_fresh_id returns an id which has never been returned before. Any freed ids
+	// are discarded, as the ids of namespaces, databases and tables are used in the keys
+	// of their data, and must not be used by another namespace, database or table.
+	pub(crate) fn get_next_fresh_id(&mut self) -> Id {
+		self.updated = true;
+		self.available_ids = None;
+		let id = self.next_id;
+		self.next_id += 1;
+		id
+	}
+
 	pub(crate) fn remove_id(&mut self, id: Id) {
 		if let Some(available_ids) = &mut self.available_ids {
 			available_ids.insert(id);
@@ -178,4 +189,23 @@ mod tests {
 			assert_eq!(id2, 4);
 		}
 	}
+
+	#[tokio::test]
+	async fn test_get_fresh_ids() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let (tx, mut d) = get_ids(&ds).await;
+		assert_eq!(d.get_next_fresh_id(), 0);
+		assert_eq!(d.get_next_fresh_id(), 1);
+		finish(tx, d).await.unwrap();
+		// It never reuses a removed id
+		let (tx, mut d) = get_ids(&ds).await;
+		d.remove_id(0);
+		finish(tx, d).await.unwrap();
+		let (tx, mut d) = get_ids(&ds).await;
+		assert_eq!(d.get_next_fresh_id(), 2);
+		finish(tx, d).await.unwrap();
+		let (tx, mut d) = get_ids(&ds).await;
+		assert_eq!(d.get_next_id(), 3);
+		finish(tx, d).await.unwrap();
+	}
 }
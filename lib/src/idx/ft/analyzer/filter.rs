@@ -4,9 +4,12 @@ use crate::sql::filter::Filter as SqlFilter;
 use crate::sql::language::Language;
 use deunicode::deunicode;
 use rust_stemmers::{Algorithm, Stemmer};
+use std::collections::HashSet;
 
 pub(super) enum Filter {
 	Stemmer(Stemmer),
+	Stemmers(Vec<Stemmer>),
+	Stopwords(HashSet<String>),
 	Ascii,
 	Ngram(u16, u16),
 	EdgeNgram(u16, u16),
@@ -14,37 +17,44 @@ pub(super) enum Filter {
 	Uppercase,
 }
 
-impl From<SqlFilter> for Filter {
-	fn from(f: SqlFilter) -> Self {
-		match f {
+impl Filter {
+	fn new(f: SqlFilter) -> Option<Self> {
+		Some(match f {
 			SqlFilter::Ascii => Filter::Ascii,
 			SqlFilter::EdgeNgram(min, max) => Filter::EdgeNgram(min, max),
 			SqlFilter::Lowercase => Filter::Lowercase,
 			SqlFilter::Ngram(min, max) => Filter::Ngram(min, max),
-			SqlFilter::Snowball(l) => {
-				let a = match l {
-					Language::Arabic => Stemmer::create(Algorithm::Arabic),
-					Language::Danish => Stemmer::create(Algorithm::Danish),
-					Language::Dutch => Stemmer::create(Algorithm::Dutch),
-					Language::English => Stemmer::create(Algorithm::English),
-					Language::French => Stemmer::create(Algorithm::French),
-					Language::German => Stemmer::create(Algorithm::German),
-					Language::Greek => Stemmer::create(Algorithm::Greek),
-					Language::Hungarian => Stemmer::create(Algorithm::Hungarian),
-					Language::Italian => Stemmer::create(Algorithm::Italian),
-					Language::Norwegian => Stemmer::create(Algorithm::Norwegian),
-					Language::Portuguese => Stemmer::create(Algorithm::Portuguese),
-					Language::Romanian => Stemmer::create(Algorithm::Romanian),
-					Language::Russian => Stemmer::create(Algorithm::Russian),
-					Language::Spanish => Stemmer::create(Algorithm::Spanish),
-					Language::Swedish => Stemmer::create(Algorithm::Swedish),
-					Language::Tamil => Stemmer::create(Algorithm::Tamil),
-					Language::Turkish => Stemmer::create(Algorithm::Turkish),
-				};
-				Filter::Stemmer(a)
+			SqlFilter::Snowball(l) => Filter::Stemmer(stemmer(l)),
+			SqlFilter::Stemmers(l) => Filter::Stemmers(l.into_iter().map(stemmer).collect()),
+			SqlFilter::Stopwords(w) => {
+				Filter::Stopwords(w.into_iter().map(|w| w.to_lowercase()).collect())
 			}
+			// Synonyms are expanded by the analyzer, at query time only
+			SqlFilter::Synonyms(_) => return None,
 			SqlFilter::Uppercase => Filter::Uppercase,
-		}
+		})
+	}
+}
+
+fn stemmer(l: Language) -> Stemmer {
+	match l {
+		Language::Arabic => Stemmer::create(Algorithm::Arabic),
+		Language::Danish => Stemmer::create(Algorithm::Danish),
+		Language::Dutch => Stemmer::create(Algorithm::Dutch),
+		Language::English => Stemmer::create(Algorithm::English),
+		Language::French => Stemmer::create(Algorithm::French),
+		Language::German => Stemmer::create(Algorithm::German),
+		Language::Greek => Stemmer::create(Algorithm::Greek),
+		Language::Hungarian => Stemmer::create(Algorithm::Hungarian),
+		Language::Italian => Stemmer::create(Algorithm::Italian),
+		Language::Norwegian => Stemmer::create(Algorithm::Norwegian),
+		Language::Portuguese => Stemmer::create(Algorithm::Portuguese),
+		Language::Romanian => Stemmer::create(Algorithm::Romanian),
+		Language::Russian => Stemmer::create(Algorithm::Russian),
+		Language::Spanish => Stemmer::create(Algorithm::Spanish),
+		Language::Swedish => Stemmer::create(Algorithm::Swedish),
+		Language::Tamil => Stemmer::create(Algorithm::Tamil),
+		Language::Turkish => Stemmer::create(Algorithm::Turkish),
 	}
 }
 
@@ -53,7 +63,7 @@ impl Filter {
 		if let Some(f) = f {
 			let mut r = Vec::with_capacity(f.len());
 			for f in f {
-				r.push(f.into());
+				r.extend(Self::new(f));
 			}
 			Some(r)
 		} else {
@@ -77,6 +87,8 @@ impl Filter {
 			Filter::Lowercase => Self::lowercase(c),
 			Filter::Ngram(min, max) => Self::ngram(c, *min, *max),
 			Filter::Stemmer(s) => Self::stem(s, c),
+			Filter::Stemmers(s) => Self::stems(s, c),
+			Filter::Stopwords(w) => Self::stopword(w, c),
 			Filter::Uppercase => Self::uppercase(c),
 		}
 	}
@@ -112,6 +124,38 @@ impl Filter {
 		Self::check_term(c, s.stem(&c.to_lowercase()).into())
 	}
 
+	#[inline]
+	fn stems(s: &[Stemmer], c: &str) -> FilterResult {
+		let c_lc = c.to_lowercase();
+		let mut stems: Vec<String> = vec![];
+		for s in s {
+			let stem: String = s.stem(&c_lc).into();
+			if !stem.is_empty() && !stems.contains(&stem) {
+				stems.push(stem);
+			}
+		}
+		if stems.len() == 1 {
+			return Self::check_term(c, stems.remove(0));
+		}
+		let terms = stems
+			.into_iter()
+			.map(|s| match s.eq(c) {
+				true => Term::Unchanged,
+				false => Term::NewTerm(s),
+			})
+			.collect();
+		FilterResult::Terms(terms)
+	}
+
+	#[inline]
+	fn stopword(w: &HashSet<String>, c: &str) -> FilterResult {
+		if w.contains(&c.to_lowercase()) {
+			FilterResult::Ignore
+		} else {
+			FilterResult::Term(Term::Unchanged)
+		}
+	}
+
 	#[inline]
 	fn ngram(c: &str, min: u16, max: u16) -> FilterResult {
 		let min = min as usize;
@@ -184,8 +228,7 @@ mod tests {
 
 	#[test]
 	fn test_arabic_stemmer() {
-		let input =
-			"الكلاب تحب الجري في الحديقة، لكن كلبي الصغير يفضل النوم في سريره بدلاً من الجري";
+		let input = "الكلاب تحب الجري في الحديقة، لكن كلبي الصغير يفضل النوم في سريره بدلاً من الجري";
 		let output = vec![
 			"كلاب", "تحب", "الجر", "في", "حديق", "لكن", "كلب", "صغير", "يفضل", "نوم", "في", "سرير",
 			"بدل", "من", "الجر",
@@ -731,4 +774,22 @@ mod tests {
 			&["āl", "āle", "ia", "iac", "es", "est"],
 		);
 	}
+
+	#[test]
+	fn test_mixed_stemmers() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,class FILTERS lowercase,snowball(english,german);",
+			"running Häuser",
+			&["run", "running", "häuser", "haus"],
+		);
+	}
+
+	#[test]
+	fn test_stopwords() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,class FILTERS stopwords('the','FOX','und');",
+			"The quick fox und der Hund",
+			&["quick", "der", "Hund"],
+		);
+	}
 }
@@ -1,16 +1,19 @@
 use crate::err::Error;
 use crate::idx::ft::analyzer::tokenizer::{Tokenizer, Tokens};
 use crate::idx::ft::doclength::DocLength;
+use crate::idx::ft::fields::FieldFrequencies;
 use crate::idx::ft::offsets::{Offset, OffsetRecords};
-use crate::idx::ft::postings::TermFrequency;
+use crate::idx::ft::query;
+use crate::idx::ft::query::Proximity;
 use crate::idx::ft::terms::{TermId, Terms};
 use crate::kvs::Transaction;
+use crate::sql::filter::{synonym_rules, Filter as SqlFilter};
 use crate::sql::statements::DefineAnalyzerStatement;
 use crate::sql::tokenizer::Tokenizer as SqlTokenizer;
 use crate::sql::Value;
 use filter::Filter;
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 mod filter;
 mod tokenizer;
@@ -24,38 +27,144 @@ impl Analyzers {
 pub(super) struct Analyzer {
 	t: Option<Vec<SqlTokenizer>>,
 	f: Option<Vec<Filter>>,
+	/// The synonyms of each term, expanded at query time
+	s: HashMap<String, Vec<String>>,
 }
 
 impl From<DefineAnalyzerStatement> for Analyzer {
 	fn from(az: DefineAnalyzerStatement) -> Self {
-		Self {
+		let rules: Vec<String> = az
+			.filters
+			.iter()
+			.flatten()
+			.filter_map(|f| match f {
+				SqlFilter::Synonyms(r) => Some(r.clone()),
+				_ => None,
+			})
+			.collect();
+		let mut a = Self {
 			t: az.tokenizers,
 			f: Filter::from(az.filters),
+			s: HashMap::new(),
+		};
+		for r in rules {
+			a.add_synonyms(&r);
 		}
+		a
 	}
 }
 
 impl Analyzer {
+	/// Extract the terms of a query, with the phrases and the proximity operators joining them.
+	/// Each term resolves to the ids of the indexed terms matching it.
 	pub(super) async fn extract_terms(
 		&self,
 		t: &Terms,
 		tx: &mut Transaction,
 		query_string: String,
-	) -> Result<Vec<Option<TermId>>, Error> {
-		let tokens = self.analyze(query_string)?;
+		distance: u8,
+	) -> Result<(Vec<Vec<TermId>>, Vec<Proximity>), Error> {
+		let parts = query::parse(&query_string);
 		// We first collect every unique terms
-		// as it can contains duplicates
-		let mut terms = HashSet::new();
-		for token in tokens.list() {
-			terms.insert(token);
+		// as it can contains duplicates,
+		// along with the sequence of terms of each part.
+		let mut terms: Vec<String> = vec![];
+		let mut indexes: HashMap<String, usize> = HashMap::new();
+		let mut sequences = Vec::with_capacity(parts.len());
+		for part in &parts {
+			let tokens = self.analyze(part.text.to_string())?;
+			let mut seq: Vec<usize> = vec![];
+			let mut last_pos = None;
+			for (tk, pos) in tokens.list().iter().zip(tokens.positions()) {
+				let term = tokens.get_token_string(tk)?;
+				let idx = match indexes.entry(term.to_string()) {
+					Entry::Occupied(e) => *e.get(),
+					Entry::Vacant(e) => {
+						terms.push(term.to_string());
+						*e.insert(terms.len() - 1)
+					}
+				};
+				// A word is represented in the sequence by its last term (the longest n-gram...)
+				if last_pos == Some(pos) {
+					seq.pop();
+				}
+				seq.push(idx);
+				last_pos = Some(pos);
+			}
+			sequences.push(seq);
+		}
+		let mut proximities = vec![];
+		for (i, part) in parts.iter().enumerate() {
+			if part.phrase && sequences[i].len() > 1 {
+				proximities.push(Proximity::phrase(sequences[i].clone()));
+			}
+			if let Some(d) = part.near {
+				let left = sequences[i].last();
+				let right = sequences.get(i + 1).and_then(|s| s.first());
+				if let (Some(l), Some(r)) = (left, right) {
+					proximities.push(Proximity::near(*l, *r, d));
+				}
+			}
 		}
-		// Now we can extract the term ids
+		// Now we can extract the term ids.
+		// A term also matches the documents containing any of its synonyms.
 		let mut res = Vec::with_capacity(terms.len());
-		for term in terms {
-			let opt_term_id = t.get_term_id(tx, tokens.get_token_string(term)?).await?;
-			res.push(opt_term_id);
+		for term in &terms {
+			let mut term_ids = Self::get_term_ids(t, tx, term, distance).await?;
+			if let Some(synonyms) = self.s.get(term) {
+				for synonym in synonyms {
+					for id in Self::get_term_ids(t, tx, synonym, distance).await? {
+						if !term_ids.contains(&id) {
+							term_ids.push(id);
+						}
+					}
+				}
+			}
+			res.push(term_ids);
+		}
+		Ok((res, proximities))
+	}
+
+	/// With an edit distance, a term resolves to every term close enough to it
+	async fn get_term_ids(
+		t: &Terms,
+		tx: &mut Transaction,
+		term: &str,
+		distance: u8,
+	) -> Result<Vec<TermId>, Error> {
+		if distance > 0 {
+			t.get_fuzzy_term_ids(tx, term, distance).await
+		} else {
+			Ok(t.get_term_id(tx, term).await?.into_iter().collect())
+		}
+	}
+
+	/// The rules are analyzed like the documents, so that they match the indexed terms
+	fn add_synonyms(&mut self, rules: &str) {
+		let Some(rules) = synonym_rules(rules) else {
+			return;
+		};
+		for (term, synonym) in rules {
+			let (Ok(term), Ok(synonym)) =
+				(self.analyze(term.to_string()), self.analyze(synonym.to_string()))
+			else {
+				continue;
+			};
+			for tk in term.list() {
+				let Ok(tk) = term.get_token_string(tk) else {
+					continue;
+				};
+				for sy in synonym.list() {
+					let Ok(sy) = synonym.get_token_string(sy) else {
+						continue;
+					};
+					let synonyms = self.s.entry(tk.to_string()).or_default();
+					if sy != tk && !synonyms.iter().any(|s| s == sy) {
+						synonyms.push(sy.to_string());
+					}
+				}
+			}
 		}
-		Ok(res)
 	}
 
 	/// This method is used for indexing.
@@ -65,26 +174,19 @@ impl Analyzer {
 		terms: &mut Terms,
 		tx: &mut Transaction,
 		field_content: &[Value],
-	) -> Result<(DocLength, Vec<(TermId, TermFrequency)>), Error> {
+	) -> Result<(DocLength, Vec<(TermId, FieldFrequencies)>), Error> {
 		let mut dl = 0;
 		// Let's first collect all the inputs, and collect the tokens.
 		// We need to store them because everything after is zero-copy
 		let mut inputs = vec![];
-		self.analyze_content(field_content, &mut inputs)?;
-		// We then collect every unique terms and count the frequency
-		let mut tf: HashMap<&str, TermFrequency> = HashMap::new();
-		for tks in &inputs {
+		let fields = self.analyze_content(field_content, &mut inputs)?;
+		// We then collect every unique terms and count the frequency in each field
+		let mut tf: HashMap<&str, FieldFrequencies> = HashMap::new();
+		for (tks, fd) in inputs.iter().zip(fields) {
 			for tk in tks.list() {
 				dl += 1;
 				let s = tks.get_token_string(tk)?;
-				match tf.entry(s) {
-					Entry::Vacant(e) => {
-						e.insert(1);
-					}
-					Entry::Occupied(mut e) => {
-						e.insert(*e.get() + 1);
-					}
-				}
+				tf.entry(s).or_insert_with(|| vec![0; field_content.len()])[fd] += 1;
 			}
 		}
 		// Now we can resolve the term ids
@@ -97,49 +199,55 @@ impl Analyzer {
 
 	/// This method is used for indexing.
 	/// It will create new term ids for non already existing terms.
+	#[allow(clippy::type_complexity)]
 	pub(super) async fn extract_terms_with_frequencies_with_offsets(
 		&self,
 		terms: &mut Terms,
 		tx: &mut Transaction,
 		content: &[Value],
-	) -> Result<(DocLength, Vec<(TermId, TermFrequency)>, Vec<(TermId, OffsetRecords)>), Error> {
+	) -> Result<(DocLength, Vec<(TermId, FieldFrequencies)>, Vec<(TermId, OffsetRecords)>), Error>
+	{
 		let mut dl = 0;
 		// Let's first collect all the inputs, and collect the tokens.
 		// We need to store them because everything after is zero-copy
 		let mut inputs = Vec::with_capacity(content.len());
-		self.analyze_content(content, &mut inputs)?;
-		// We then collect every unique terms and count the frequency and extract the offsets
-		let mut tfos: HashMap<&str, Vec<Offset>> = HashMap::new();
-		for (i, tks) in inputs.iter().enumerate() {
-			for tk in tks.list() {
+		let fields = self.analyze_content(content, &mut inputs)?;
+		// We then collect every unique terms and count the frequency in each field and extract the offsets
+		let mut tfos: HashMap<&str, (FieldFrequencies, Vec<Offset>)> = HashMap::new();
+		for (i, (tks, fd)) in inputs.iter().zip(fields).enumerate() {
+			for (tk, pos) in tks.list().iter().zip(tks.positions()) {
 				dl += 1;
 				let s = tks.get_token_string(tk)?;
-				let o = tk.new_offset(i as u32);
-				match tfos.entry(s) {
-					Entry::Vacant(e) => {
-						e.insert(vec![o]);
-					}
-					Entry::Occupied(mut e) => e.get_mut().push(o),
-				}
+				let o = tk.new_offset(i as u32, pos);
+				let (f, os) = tfos.entry(s).or_insert_with(|| (vec![0; content.len()], vec![]));
+				f[fd] += 1;
+				os.push(o);
 			}
 		}
 
 		// Now we can resolve the term ids
 		let mut tfid = Vec::with_capacity(tfos.len());
 		let mut osid = Vec::with_capacity(tfos.len());
-		for (t, o) in tfos {
+		for (t, (f, o)) in tfos {
 			let id = terms.resolve_term_id(tx, t).await?;
-			tfid.push((id, o.len() as TermFrequency));
+			tfid.push((id, f));
 			osid.push((id, OffsetRecords(o)));
 		}
 		Ok((dl, tfid, osid))
 	}
 
-	fn analyze_content(&self, content: &[Value], tks: &mut Vec<Tokens>) -> Result<(), Error> {
-		for v in content {
+	/// Analyze the value of each field, returning the field of each of the resulting inputs
+	fn analyze_content(
+		&self,
+		content: &[Value],
+		tks: &mut Vec<Tokens>,
+	) -> Result<Vec<usize>, Error> {
+		let mut fields = Vec::with_capacity(content.len());
+		for (i, v) in content.iter().enumerate() {
 			self.analyze_value(v, tks)?;
+			fields.resize(tks.len(), i);
 		}
-		Ok(())
+		Ok(fields)
 	}
 
 	fn analyze_value(&self, val: &Value, tks: &mut Vec<Tokens>) -> Result<(), Error> {
@@ -65,6 +65,27 @@ impl Tokens {
 	pub(super) fn list(&self) -> &Vec<Token> {
 		&self.t
 	}
+
+	/// The position of each token within the sequence of words of the input.
+	/// Overlapping tokens, such as the n-grams or the stems of a same word, share their position.
+	pub(super) fn positions(&self) -> Vec<Position> {
+		let mut res = Vec::with_capacity(self.t.len());
+		let mut pos = 0;
+		let mut end = None;
+		for t in &self.t {
+			let (s, e) = t.chars();
+			match end {
+				Some(x) if s >= x => {
+					pos += 1;
+					end = Some(e);
+				}
+				Some(x) => end = Some(e.max(x)),
+				None => end = Some(e),
+			}
+			res.push(pos);
+		}
+		res
+	}
 }
 
 #[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Hash)]
@@ -103,16 +124,21 @@ impl Token {
 		}
 	}
 
-	pub(super) fn new_offset(&self, i: u32) -> Offset {
+	pub(super) fn new_offset(&self, i: u32, pos: Position) -> Offset {
+		let (start, end) = self.chars();
+		Offset::new(i, start, end, pos)
+	}
+
+	fn chars(&self) -> (Position, Position) {
 		match self {
 			Token::Ref {
 				chars,
 				..
-			} => Offset::new(i, chars.0, chars.1),
+			} => *chars,
 			Token::String {
 				chars,
 				..
-			} => Offset::new(i, chars.0, chars.1),
+			} => *chars,
 		}
 	}
 
@@ -155,12 +181,23 @@ impl Token {
 
 pub(super) struct Tokenizer {
 	splitters: Vec<Splitter>,
+	grams: Vec<Grams>,
 }
 
 impl Tokenizer {
 	pub(in crate::idx::ft) fn new(t: &[SqlTokenizer]) -> Self {
+		let mut splitters = Vec::new();
+		let mut grams = Vec::new();
+		for t in t {
+			match t {
+				SqlTokenizer::Ngram(min, max) => grams.push(Grams::new(false, *min, *max)),
+				SqlTokenizer::EdgeNgram(min, max) => grams.push(Grams::new(true, *min, *max)),
+				t => splitters.push(t.into()),
+			}
+		}
 		Self {
-			splitters: t.iter().map(|t| t.into()).collect(),
+			splitters,
+			grams,
 		}
 	}
 
@@ -215,11 +252,82 @@ impl Tokenizer {
 				bytes: (last_byte_pos, current_byte_pos),
 			});
 		}
+		if !w.grams.is_empty() {
+			t = w.split_grams(&i, t);
+		}
 		Tokens {
 			i,
 			t,
 		}
 	}
+
+	/// Replace each word by its n-grams. Every n-gram refers to its own
+	/// position within the input, so that it can be highlighted on its own.
+	fn split_grams(&self, i: &str, words: Vec<Token>) -> Vec<Token> {
+		let mut t = Vec::new();
+		for w in words {
+			let Token::Ref {
+				chars,
+				bytes,
+			} = w
+			else {
+				continue;
+			};
+			// The byte offset of each character of the word, plus the end of the word
+			let offsets: Vec<Position> = i[(bytes.0 as usize)..(bytes.1 as usize)]
+				.char_indices()
+				.map(|(b, _)| bytes.0 + b as Position)
+				.chain(std::iter::once(bytes.1))
+				.collect();
+			let len = offsets.len() - 1;
+			for g in &self.grams {
+				for (s, e) in g.ranges(len) {
+					t.push(Token::Ref {
+						chars: (chars.0 + s as Position, chars.0 + e as Position),
+						bytes: (offsets[s], offsets[e]),
+					});
+				}
+			}
+		}
+		t
+	}
+}
+
+struct Grams {
+	/// Only the n-grams starting at the beginning of the word
+	edge: bool,
+	min: usize,
+	max: usize,
+}
+
+impl Grams {
+	fn new(edge: bool, min: u16, max: u16) -> Self {
+		Self {
+			edge,
+			min: (min as usize).max(1),
+			max: max as usize,
+		}
+	}
+
+	/// The character ranges of the n-grams of a word of `len` characters.
+	/// A word shorter than `min` doesn't have any n-gram.
+	fn ranges(&self, len: usize) -> Vec<(usize, usize)> {
+		let mut r = Vec::new();
+		let starts = if self.edge {
+			len.min(1)
+		} else {
+			len
+		};
+		for s in 0..starts {
+			for l in self.min..=self.max {
+				if s + l > len {
+					break;
+				}
+				r.push((s, s + l));
+			}
+		}
+		r
+	}
 }
 
 struct Splitter {
@@ -243,6 +351,8 @@ impl Splitter {
 			SqlTokenizer::Camel => self.camel_state(c),
 			SqlTokenizer::Class => self.class_state(c),
 			SqlTokenizer::Punct => self.punct_state(c),
+			// N-grams are extracted from the words once they are split
+			SqlTokenizer::Ngram(..) | SqlTokenizer::EdgeNgram(..) => false,
 		}
 	}
 
@@ -368,4 +478,22 @@ static LANGUAGE: &str = "Rust";"#,
 			],
 		);
 	}
+
+	#[test]
+	fn test_tokenize_edgengram() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,edgengram(2,4) FILTERS lowercase",
+			"SurrealDB is a database",
+			&["su", "sur", "surr", "is", "da", "dat", "data"],
+		);
+	}
+
+	#[test]
+	fn test_tokenize_ngram() {
+		test_analyzer(
+			"DEFINE ANALYZER test TOKENIZERS blank,ngram(2,3) FILTERS lowercase",
+			"Abcd Éte a",
+			&["ab", "abc", "bc", "bcd", "cd", "ét", "éte", "te"],
+		);
+	}
 }
@@ -25,7 +25,7 @@ pub(crate) struct DocIds {
 }
 
 impl DocIds {
-	pub(super) async fn new(
+	pub(in crate::idx) async fn new(
 		tx: &mut Transaction,
 		index_key_base: IndexKeyBase,
 		default_btree_order: u32,
@@ -78,7 +78,7 @@ impl DocIds {
 
 	/// Returns the doc_id for the given doc_key.
 	/// If the doc_id does not exists, a new one is created, and associated to the given key.
-	pub(super) async fn resolve_doc_id(
+	pub(in crate::idx) async fn resolve_doc_id(
 		&mut self,
 		tx: &mut Transaction,
 		doc_key: Key,
@@ -97,7 +97,7 @@ impl DocIds {
 		Ok(Resolved::New(doc_id))
 	}
 
-	pub(super) async fn remove_doc(
+	pub(in crate::idx) async fn remove_doc(
 		&mut self,
 		tx: &mut Transaction,
 		doc_key: Key,
@@ -119,7 +119,7 @@ impl DocIds {
 		}
 	}
 
-	pub(super) async fn get_doc_key(
+	pub(in crate::idx) async fn get_doc_key(
 		&self,
 		tx: &mut Transaction,
 		doc_id: DocId,
@@ -137,7 +137,7 @@ impl DocIds {
 		self.btree.statistics(tx, &mut store).await
 	}
 
-	pub(super) async fn finish(&mut self, tx: &mut Transaction) -> Result<(), Error> {
+	pub(in crate::idx) async fn finish(&mut self, tx: &mut Transaction) -> Result<(), Error> {
 		let updated = self.store.lock().await.finish(tx).await?;
 		if self.updated || updated {
 			let state = State {
@@ -172,20 +172,20 @@ impl State {
 }
 
 #[derive(Debug, PartialEq)]
-pub(super) enum Resolved {
+pub(in crate::idx) enum Resolved {
 	New(DocId),
 	Existing(DocId),
 }
 
 impl Resolved {
-	pub(super) fn doc_id(&self) -> &DocId {
+	pub(in crate::idx) fn doc_id(&self) -> &DocId {
 		match self {
 			Resolved::New(doc_id) => doc_id,
 			Resolved::Existing(doc_id) => doc_id,
 		}
 	}
 
-	pub(super) fn was_existing(&self) -> bool {
+	pub(in crate::idx) fn was_existing(&self) -> bool {
 		match self {
 			Resolved::New(_) => false,
 			Resolved::Existing(_) => true,
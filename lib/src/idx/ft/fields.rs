@@ -0,0 +1,57 @@
+use crate::err::Error;
+use crate::idx::ft::docids::DocId;
+use crate::idx::ft::postings::TermFrequency;
+use crate::idx::ft::terms::TermId;
+use crate::idx::IndexKeyBase;
+use crate::kvs::Transaction;
+
+/// The frequency of a term in each of the fields of a document
+pub(super) type FieldFrequencies = Vec<TermFrequency>;
+
+/// Stores the frequencies of the terms in each field, so that the
+/// indexes with boosted fields can weigh the terms when scoring.
+pub(super) struct Fields {
+	index_key_base: IndexKeyBase,
+}
+
+impl Fields {
+	pub(super) fn new(index_key_base: IndexKeyBase) -> Self {
+		Self {
+			index_key_base,
+		}
+	}
+
+	pub(super) async fn set_frequencies(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+		term_id: TermId,
+		frequencies: &FieldFrequencies,
+	) -> Result<(), Error> {
+		let key = self.index_key_base.new_bw_key(doc_id, term_id);
+		tx.set(key, bincode::serialize(frequencies)?).await
+	}
+
+	pub(super) async fn get_frequencies(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+		term_id: TermId,
+	) -> Result<Option<FieldFrequencies>, Error> {
+		let key = self.index_key_base.new_bw_key(doc_id, term_id);
+		match tx.get(key).await? {
+			Some(val) => Ok(Some(bincode::deserialize(&val)?)),
+			None => Ok(None),
+		}
+	}
+
+	pub(super) async fn remove_frequencies(
+		&self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+		term_id: TermId,
+	) -> Result<(), Error> {
+		let key = self.index_key_base.new_bw_key(doc_id, term_id);
+		tx.del(key).await
+	}
+}
@@ -6,24 +6,44 @@ use std::collections::hash_map::Entry as HEntry;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::default::Default;
+use std::ops::Range;
+
+/// How the terms matching a search are highlighted
+pub(crate) struct HighlightParams {
+	/// The text inserted before each matching term
+	pub(crate) prefix: Value,
+	/// The text inserted after each matching term
+	pub(crate) suffix: Value,
+	/// The number of characters of each fragment. The whole text is returned when it is not set.
+	pub(crate) fragment_length: Option<usize>,
+	/// The maximum number of fragments returned for each field
+	pub(crate) fragments: Option<usize>,
+}
 
 pub(super) struct Highlighter {
 	prefix: Vec<char>,
 	suffix: Vec<char>,
-	fields: Vec<(Idiom, Value)>,
+	fragment_length: Option<usize>,
+	fragments: Option<usize>,
+	columns: Vec<(Idiom, Vec<Value>)>,
 	offseter: Offseter,
 }
 
 impl Highlighter {
-	pub(super) fn new(prefix: Value, suffix: Value, idiom: &Idiom, doc: &Value) -> Self {
-		let prefix = prefix.to_raw_string().chars().collect();
-		let suffix = suffix.to_raw_string().chars().collect();
-		// Extract the fields we want to highlight
-		let fields = doc.walk(idiom);
+	pub(super) fn new(hp: HighlightParams, cols: &[Idiom], doc: &Value) -> Self {
+		let prefix = hp.prefix.to_raw_string().chars().collect();
+		let suffix = hp.suffix.to_raw_string().chars().collect();
+		// Extract the fields we want to highlight, for every column of the index
+		let columns = cols
+			.iter()
+			.map(|c| (c.clone(), doc.walk(c).into_iter().map(|(_, v)| v).collect()))
+			.collect();
 		Self {
-			fields,
+			columns,
 			prefix,
 			suffix,
+			fragment_length: hp.fragment_length,
+			fragments: hp.fragments,
 			offseter: Offseter::default(),
 		}
 	}
@@ -50,57 +70,138 @@ impl Highlighter {
 			_ => {}
 		}
 	}
+
+	/// Insert the prefix and the suffix around the matching terms within a range of the text
+	fn decorate(
+		&self,
+		v: &[char],
+		m: &BTreeMap<Position, Position>,
+		r: Range<usize>,
+	) -> Result<String, Error> {
+		let len = v.len();
+		let mut v = v[r.clone()].to_vec();
+		let mut l = v.len();
+		let mut d = 0;
+
+		// We use a closure to append the prefix and the suffix
+		let mut append = |s: usize, ix: &Vec<char>| -> Result<(), Error> {
+			let p = s - r.start + d;
+			if p > l {
+				return Err(Error::HighlightError(format!("position overflow: {s} - len: {l}")));
+			}
+			v.splice(p..p, ix.clone());
+			let xl = ix.len();
+			d += xl;
+			l += xl;
+			Ok(())
+		};
+
+		let terms = m
+			.range(r.start as Position..)
+			.take_while(|(s, _)| r.end == len || (**s as usize) < r.end);
+		for (s, e) in terms {
+			append(*s as usize, &self.prefix)?;
+			// A term overlapping the end of a fragment is closed at the end of the fragment
+			let e = match r.end < len {
+				true => (*e as usize).min(r.end),
+				false => *e as usize,
+			};
+			append(e, &self.suffix)?;
+		}
+
+		Ok(v.iter().collect())
+	}
+
+	/// Split the text into fragments surrounding the matching terms
+	fn fragment(
+		&self,
+		v: &[char],
+		m: &BTreeMap<Position, Position>,
+		length: usize,
+		res: &mut Vec<Value>,
+	) -> Result<(), Error> {
+		// Group the terms which fit within the same fragment
+		let mut ranges: Vec<Range<usize>> = vec![];
+		for (s, e) in m {
+			let (s, e) = (*s as usize, *e as usize);
+			if s > v.len() {
+				return Err(Error::HighlightError(format!(
+					"position overflow: {s} - len: {}",
+					v.len()
+				)));
+			}
+			let e = e.min(v.len());
+			match ranges.last_mut() {
+				Some(r) if e <= r.start + length => r.end = r.end.max(e),
+				_ => ranges.push(s..e),
+			}
+		}
+		for r in ranges {
+			// Share the remaining characters of the fragment around the terms
+			let length = length.max(r.len());
+			let start = r.start.saturating_sub((length - r.len()) / 2);
+			let end = (start + length).min(v.len());
+			let start = start.min(end.saturating_sub(length));
+			res.push(Value::from(self.decorate(v, m, start..end)?));
+		}
+		Ok(())
+	}
 }
 
 impl TryFrom<Highlighter> for Value {
 	type Error = Error;
 
-	fn try_from(hl: Highlighter) -> Result<Self, Error> {
-		if hl.fields.is_empty() {
+	fn try_from(mut hl: Highlighter) -> Result<Self, Error> {
+		if hl.columns.iter().all(|(_, f)| f.is_empty()) {
 			return Ok(Self::None);
 		}
-		let mut vals = vec![];
-		for (_, f) in hl.fields {
-			Highlighter::extract(f, &mut vals);
-		}
-		let mut res = Vec::with_capacity(vals.len());
-		for (idx, val) in vals.into_iter().enumerate() {
-			if let Some(m) = hl.offseter.offsets.get(&(idx as u32)) {
-				let mut v: Vec<char> = val.chars().collect();
-				let mut l = v.len();
-				let mut d = 0;
-
-				// We use a closure to append the prefix and the suffix
-				let mut append = |s: u32, ix: &Vec<char>| -> Result<(), Error> {
-					let p = (s as usize) + d;
-					if p > l {
-						return Err(Error::HighlightError(format!(
-							"position overflow: {s} - len: {l}"
-						)));
+		let columns = std::mem::take(&mut hl.columns);
+		let single = columns.len() == 1;
+		let mut out = BTreeMap::new();
+		// The offsets index the values of every column in turn
+		let mut idx = 0;
+		for (col, fields) in columns {
+			let mut vals = vec![];
+			for f in fields {
+				Highlighter::extract(f, &mut vals);
+			}
+			let mut res = Vec::with_capacity(vals.len());
+			for val in vals {
+				let m = hl.offseter.offsets.get(&idx);
+				idx += 1;
+				match (hl.fragment_length, m) {
+					(None, Some(m)) => {
+						let v: Vec<char> = val.chars().collect();
+						res.push(Value::from(hl.decorate(&v, m, 0..v.len())?));
 					}
-					v.splice(p..p, ix.clone());
-					let xl = ix.len();
-					d += xl;
-					l += xl;
-					Ok(())
-				};
-
-				for (s, e) in m {
-					append(*s, &hl.prefix)?;
-					append(*e, &hl.suffix)?;
+					(None, None) => res.push(Value::from(val)),
+					(Some(l), Some(m)) => {
+						let v: Vec<char> = val.chars().collect();
+						hl.fragment(&v, m, l, &mut res)?;
+					}
+					// Only the fragments containing matching terms are returned
+					(Some(_), None) => {}
 				}
-
-				let s: String = v.iter().collect();
-				res.push(Value::from(s));
-			} else {
-				res.push(Value::from(val));
 			}
+			let res = match hl.fragment_length {
+				Some(_) => {
+					if let Some(n) = hl.fragments {
+						res.truncate(n);
+					}
+					Value::from(res)
+				}
+				None => match res.len() {
+					0 => Value::None,
+					1 => res.remove(0),
+					_ => Value::from(res),
+				},
+			};
+			if single {
+				return Ok(res);
+			}
+			out.insert(col.to_string(), res);
 		}
-		Ok(match res.len() {
-			0 => Value::None,
-			1 => res.remove(0),
-			_ => Value::from(res),
-		})
+		Ok(Value::from(Object::from(out)))
 	}
 }
 
@@ -0,0 +1,77 @@
+//! A Levenshtein automaton, which recognises the terms within an edit distance of a query term.
+//! A state holds, for each prefix of the query term, the edit distance to the characters read so
+//! far. The automaton stops reading a term as soon as no state can lead to a match anymore.
+
+pub(super) struct Levenshtein {
+	term: Vec<char>,
+	distance: usize,
+}
+
+impl Levenshtein {
+	pub(super) fn new(term: &str, distance: u8) -> Self {
+		Self {
+			term: term.chars().collect(),
+			distance: distance as usize,
+		}
+	}
+
+	fn start(&self) -> Vec<usize> {
+		(0..=self.term.len()).collect()
+	}
+
+	fn step(&self, state: &[usize], c: char) -> Vec<usize> {
+		let mut next = Vec::with_capacity(state.len());
+		next.push(state[0] + 1);
+		for (i, t) in self.term.iter().enumerate() {
+			let cost = usize::from(*t != c);
+			let d = (state[i] + cost).min(state[i + 1] + 1).min(next[i] + 1);
+			next.push(d);
+		}
+		next
+	}
+
+	fn is_match(&self, state: &[usize]) -> bool {
+		state[self.term.len()] <= self.distance
+	}
+
+	fn can_match(&self, state: &[usize]) -> bool {
+		state.iter().any(|d| *d <= self.distance)
+	}
+
+	/// Whether a term is within the edit distance of the query term
+	pub(super) fn matches(&self, term: &str) -> bool {
+		// Terms whose length differs too much can't match
+		let len = term.chars().count();
+		if len.abs_diff(self.term.len()) > self.distance {
+			return false;
+		}
+		let mut state = self.start();
+		for c in term.chars() {
+			state = self.step(&state, c);
+			if !self.can_match(&state) {
+				return false;
+			}
+		}
+		self.is_match(&state)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::ft::levenshtein::Levenshtein;
+
+	#[test]
+	fn test_levenshtein() {
+		let l = Levenshtein::new("surrealdb", 2);
+		assert!(l.matches("surrealdb"));
+		assert!(l.matches("surealdb"));
+		assert!(l.matches("surrealbd"));
+		assert!(l.matches("surrealdb12"));
+		assert!(!l.matches("surre"));
+		assert!(!l.matches("postgres"));
+		let l = Levenshtein::new("ãb", 1);
+		assert!(l.matches("ab"));
+		assert!(l.matches("b"));
+		assert!(!l.matches("cd"));
+	}
+}
@@ -1,9 +1,12 @@
 pub(crate) mod analyzer;
 pub(crate) mod docids;
 mod doclength;
-mod highlighter;
+mod fields;
+pub(crate) mod highlighter;
+mod levenshtein;
 mod offsets;
 mod postings;
+pub(super) mod query;
 pub(super) mod scorer;
 pub(super) mod termdocs;
 pub(crate) mod terms;
@@ -12,9 +15,11 @@ use crate::err::Error;
 use crate::idx::ft::analyzer::Analyzer;
 use crate::idx::ft::docids::{DocId, DocIds};
 use crate::idx::ft::doclength::DocLengths;
-use crate::idx::ft::highlighter::{Highlighter, Offseter};
+use crate::idx::ft::fields::Fields;
+use crate::idx::ft::highlighter::{HighlightParams, Highlighter, Offseter};
 use crate::idx::ft::offsets::Offsets;
 use crate::idx::ft::postings::Postings;
+use crate::idx::ft::query::{Proximities, Proximity};
 use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::{TermDocs, TermsDocs};
 use crate::idx::ft::terms::{TermId, Terms};
@@ -42,12 +47,15 @@ pub(crate) struct FtIndex {
 	index_key_base: IndexKeyBase,
 	state: State,
 	bm25: Option<Bm25Params>,
+	/// The weight of the terms of each field
+	boosts: Vec<f64>,
 	highlighting: bool,
 	doc_ids: Arc<RwLock<DocIds>>,
 	doc_lengths: Arc<RwLock<DocLengths>>,
 	postings: Arc<RwLock<Postings>>,
 	terms: Arc<RwLock<Terms>>,
 	offsets: Offsets,
+	fields: Fields,
 	term_docs: TermDocs,
 }
 
@@ -121,6 +129,7 @@ impl FtIndex {
 		));
 		let termdocs = TermDocs::new(index_key_base.clone());
 		let offsets = Offsets::new(index_key_base.clone());
+		let fields = Fields::new(index_key_base.clone());
 		let mut bm25 = None;
 		if let Scoring::Bm {
 			k1,
@@ -137,6 +146,7 @@ impl FtIndex {
 			state_key,
 			index_key_base,
 			bm25,
+			boosts: p.boosts.iter().map(|b| b.to_float()).collect(),
 			highlighting: p.hl,
 			analyzer: az.into(),
 			doc_ids,
@@ -145,6 +155,7 @@ impl FtIndex {
 			terms,
 			term_docs: termdocs,
 			offsets,
+			fields,
 		})
 	}
 
@@ -184,11 +195,17 @@ impl FtIndex {
 				}
 				// Remove the offsets if any
 				if self.highlighting {
-					for term_id in term_list {
+					for term_id in &term_list {
 						// TODO?: Removal can be done with a prefix on doc_id
 						self.offsets.remove_offsets(tx, doc_id, term_id).await?;
 					}
 				}
+				// Remove the frequencies of each field if any
+				if !self.boosts.is_empty() {
+					for term_id in &term_list {
+						self.fields.remove_frequencies(tx, doc_id, term_id).await?;
+					}
+				}
 			}
 		}
 		Ok(())
@@ -238,8 +255,13 @@ impl FtIndex {
 		// Set the terms postings and term docs
 		let mut terms_ids = RoaringTreemap::default();
 		let mut p = self.postings.write().await;
-		for (term_id, term_freq) in terms_and_frequencies {
+		for (term_id, field_freqs) in terms_and_frequencies {
+			let term_freq = field_freqs.iter().sum();
 			p.update_posting(tx, term_id, doc_id, term_freq).await?;
+			// The frequency in each field is kept to apply the boosts when scoring
+			if !self.boosts.is_empty() {
+				self.fields.set_frequencies(tx, doc_id, term_id, &field_freqs).await?;
+			}
 			if let Some(old_term_ids) = &mut old_term_ids {
 				old_term_ids.remove(term_id);
 			}
@@ -259,6 +281,15 @@ impl FtIndex {
 			}
 		}
 
+		// In case of an update, we remove the frequencies of the terms that does not exist anymore
+		if !self.boosts.is_empty() {
+			if let Some(old_term_ids) = &old_term_ids {
+				for old_term_id in old_term_ids {
+					self.fields.remove_frequencies(tx, doc_id, old_term_id).await?;
+				}
+			}
+		}
+
 		if self.highlighting {
 			// Set the offset if any
 			if let Some(ofs) = offsets {
@@ -296,29 +327,44 @@ impl FtIndex {
 		&self,
 		tx: &mut Transaction,
 		query_string: String,
-	) -> Result<Vec<Option<TermId>>, Error> {
+		distance: u8,
+	) -> Result<(Vec<Vec<TermId>>, Vec<Proximity>), Error> {
 		let t = self.terms.read().await;
-		let terms = self.analyzer.extract_terms(&t, tx, query_string).await?;
-		Ok(terms)
+		self.analyzer.extract_terms(&t, tx, query_string, distance).await
+	}
+
+	/// The positions of the terms are stored with their offsets, which requires highlighting
+	pub(super) fn new_proximities(
+		&self,
+		terms: &[Vec<TermId>],
+		proximities: Vec<Proximity>,
+	) -> Result<Option<Proximities>, Error> {
+		if proximities.is_empty() {
+			return Ok(None);
+		}
+		if !self.highlighting {
+			return Err(Error::FtPositionsNotIndexed);
+		}
+		Ok(Some(Proximities::new(
+			Offsets::new(self.index_key_base.clone()),
+			terms.to_vec(),
+			proximities,
+		)))
 	}
 
 	pub(super) async fn get_terms_docs(
 		&self,
 		tx: &mut Transaction,
-		terms: &Vec<Option<TermId>>,
-	) -> Result<Vec<Option<(TermId, RoaringTreemap)>>, Error> {
+		terms: &Vec<Vec<TermId>>,
+	) -> Result<Vec<Vec<(TermId, RoaringTreemap)>>, Error> {
 		let mut terms_docs = Vec::with_capacity(terms.len());
-		for opt_term_id in terms {
-			if let Some(term_id) = opt_term_id {
+		for term_ids in terms {
+			let mut tds = Vec::with_capacity(term_ids.len());
+			for term_id in term_ids {
 				let docs = self.term_docs.get_docs(tx, *term_id).await?;
-				if let Some(docs) = docs {
-					terms_docs.push(Some((*term_id, docs)));
-				} else {
-					terms_docs.push(Some((*term_id, RoaringTreemap::new())));
-				}
-			} else {
-				terms_docs.push(None);
+				tds.push((*term_id, docs.unwrap_or_else(RoaringTreemap::new)));
 			}
+			terms_docs.push(tds);
 		}
 		Ok(terms_docs)
 	}
@@ -328,16 +374,21 @@ impl FtIndex {
 		terms_docs: TermsDocs,
 	) -> Result<Option<HitsIterator>, Error> {
 		let mut hits: Option<RoaringTreemap> = None;
-		for opt_term_docs in terms_docs.iter() {
-			if let Some((_, term_docs)) = opt_term_docs {
-				if let Some(h) = hits {
-					hits = Some(h.bitand(term_docs));
-				} else {
-					hits = Some(term_docs.clone());
-				}
-			} else {
+		for tds in terms_docs.iter() {
+			// If one of the term is missing, there is no hit
+			if tds.is_empty() {
 				return Ok(None);
 			}
+			// A document matches a fuzzy term if it contains any of the terms close enough to it
+			let mut term_docs = RoaringTreemap::new();
+			for (_, docs) in tds {
+				term_docs |= docs;
+			}
+			if let Some(h) = hits {
+				hits = Some(h.bitand(term_docs));
+			} else {
+				hits = Some(term_docs);
+			}
 		}
 		if let Some(hits) = hits {
 			if !hits.is_empty() {
@@ -356,6 +407,8 @@ impl FtIndex {
 				self.state.total_docs_lengths,
 				self.state.doc_count,
 				bm25.clone(),
+				Fields::new(self.index_key_base.clone()),
+				self.boosts.clone(),
 			)));
 		}
 		Ok(None)
@@ -366,15 +419,14 @@ impl FtIndex {
 		&self,
 		tx: &mut Transaction,
 		thg: &Thing,
-		terms: &[Option<TermId>],
-		prefix: Value,
-		suffix: Value,
-		idiom: &Idiom,
+		terms: &[Vec<TermId>],
+		hp: HighlightParams,
+		cols: &[Idiom],
 		doc: &Value,
 	) -> Result<Value, Error> {
 		let doc_key: Key = thg.into();
 		if let Some(doc_id) = self.doc_ids.read().await.get_doc_id(tx, doc_key).await? {
-			let mut hl = Highlighter::new(prefix, suffix, idiom, doc);
+			let mut hl = Highlighter::new(hp, cols, doc);
 			for term_id in terms.iter().flatten() {
 				let o = self.offsets.get_offsets(tx, doc_id, *term_id).await?;
 				if let Some(o) = o {
@@ -390,7 +442,7 @@ impl FtIndex {
 		&self,
 		tx: &mut Transaction,
 		thg: &Thing,
-		terms: &[Option<TermId>],
+		terms: &[Vec<TermId>],
 	) -> Result<Value, Error> {
 		let doc_key: Key = thg.into();
 		if let Some(doc_id) = self.doc_ids.read().await.get_doc_id(tx, doc_key).await? {
@@ -493,7 +545,7 @@ mod tests {
 		fti: &FtIndex,
 		qs: &str,
 	) -> (Option<HitsIterator>, BM25Scorer) {
-		let t = fti.extract_terms(tx, qs.to_string()).await.unwrap();
+		let (t, _) = fti.extract_terms(tx, qs.to_string(), 0).await.unwrap();
 		let td = Arc::new(fti.get_terms_docs(tx, &t).await.unwrap());
 		let scr = fti.new_scorer(td.clone()).unwrap().unwrap();
 		let hits = fti.new_hits_iterator(td).unwrap();
@@ -520,6 +572,7 @@ mod tests {
 				postings_order: order,
 				terms_order: order,
 				sc: Scoring::bm25(),
+				boosts: vec![],
 				hl,
 			},
 			TreeStoreType::Write,
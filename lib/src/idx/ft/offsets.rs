@@ -61,14 +61,17 @@ pub(super) struct Offset {
 	pub(super) index: u32,
 	pub(super) start: Position,
 	pub(super) end: Position,
+	/// The position of the term within the sequence of words of the value
+	pub(super) pos: Position,
 }
 
 impl Offset {
-	pub(super) fn new(index: u32, start: Position, end: Position) -> Self {
+	pub(super) fn new(index: u32, start: Position, end: Position, pos: Position) -> Self {
 		Self {
 			index,
 			start,
 			end,
+			pos,
 		}
 	}
 }
@@ -96,6 +99,10 @@ impl TryFrom<OffsetRecords> for Val {
 			decompressed.push(o.start);
 			decompressed.push(o.end);
 		}
+		// The positions come last, so that the offsets stored without them can still be read
+		for o in &offsets.0 {
+			decompressed.push(o.pos);
+		}
 		Ok(bincode::serialize(&decompressed)?)
 	}
 }
@@ -119,7 +126,13 @@ impl TryFrom<Val> for OffsetRecords {
 		for index in indexes {
 			let start = *iter.next().ok_or(Error::CorruptedIndex)?;
 			let end = *iter.next().ok_or(Error::CorruptedIndex)?;
-			res.push(Offset::new(index, start, end));
+			res.push(Offset::new(index, start, end, 0));
+		}
+		for o in &mut res {
+			match iter.next() {
+				Some(pos) => o.pos = *pos,
+				None => break,
+			}
 		}
 		Ok(OffsetRecords(res))
 	}
@@ -132,8 +145,11 @@ mod tests {
 
 	#[test]
 	fn test_offset_records() {
-		let o =
-			OffsetRecords(vec![Offset::new(0, 1, 2), Offset::new(0, 11, 22), Offset::new(1, 3, 4)]);
+		let o = OffsetRecords(vec![
+			Offset::new(0, 1, 2, 0),
+			Offset::new(0, 11, 22, 3),
+			Offset::new(1, 3, 4, 0),
+		]);
 		let v: Val = o.clone().try_into().unwrap();
 		let o2 = v.try_into().unwrap();
 		assert_eq!(o, o2)
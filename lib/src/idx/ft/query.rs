@@ -0,0 +1,231 @@
+//! The phrases and the proximity operators of a full-text query.
+//! A quoted phrase such as `"quick brown fox"` matches the documents where its terms follow each other.
+//! `quick NEAR/3 fox` matches the documents where both terms are at most 3 words apart, in any order.
+use crate::err::Error;
+use crate::idx::ft::docids::DocId;
+use crate::idx::ft::offsets::{Offsets, Position};
+use crate::idx::ft::terms::TermId;
+use crate::kvs::Transaction;
+use std::collections::{HashMap, HashSet};
+
+/// A word, or a quoted phrase, of a query
+#[derive(Debug, PartialEq)]
+pub(super) struct Part<'a> {
+	pub(super) text: &'a str,
+	/// Whether the terms of the part have to follow each other
+	pub(super) phrase: bool,
+	/// The distance to the next part, when both are the operands of a `NEAR/n` operator
+	pub(super) near: Option<u32>,
+}
+
+/// Split a query into its words and its quoted phrases
+pub(super) fn parse(query: &str) -> Vec<Part<'_>> {
+	let mut parts: Vec<Part> = vec![];
+	let mut operand = false;
+	let mut rest = query;
+	loop {
+		rest = rest.trim_start();
+		if rest.is_empty() {
+			break;
+		}
+		let (text, phrase) = if let Some(r) = rest.strip_prefix('"') {
+			// An unterminated phrase runs until the end of the query
+			let end = r.find('"').unwrap_or(r.len());
+			rest = r.get(end + 1..).unwrap_or("");
+			(&r[..end], true)
+		} else {
+			let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+			let text = &rest[..end];
+			rest = &rest[end..];
+			if let (Some(d), Some(p)) = (near_distance(text), parts.last_mut()) {
+				p.near = Some(d);
+				p.phrase = true;
+				operand = true;
+				continue;
+			}
+			(text, false)
+		};
+		parts.push(Part {
+			text,
+			// The operands of a proximity operator are matched as phrases
+			phrase: phrase || operand,
+			near: None,
+		});
+		operand = false;
+	}
+	parts
+}
+
+/// The distance of a `NEAR/n` operator
+fn near_distance(word: &str) -> Option<u32> {
+	let op = word.get(..5)?;
+	if op.eq_ignore_ascii_case("NEAR/") {
+		word[5..].parse().ok()
+	} else {
+		None
+	}
+}
+
+/// Terms which have to be close to each other within a value
+#[derive(Debug, PartialEq)]
+pub(crate) struct Proximity {
+	/// The index of each term within the terms of the query
+	pub(super) terms: Vec<usize>,
+	/// The largest number of positions between two consecutive terms
+	pub(super) distance: u32,
+	/// Whether the terms have to appear in this order
+	pub(super) ordered: bool,
+}
+
+impl Proximity {
+	pub(super) fn phrase(terms: Vec<usize>) -> Self {
+		Self {
+			terms,
+			distance: 1,
+			ordered: true,
+		}
+	}
+
+	pub(super) fn near(left: usize, right: usize, distance: u32) -> Self {
+		Self {
+			terms: vec![left, right],
+			distance,
+			ordered: false,
+		}
+	}
+
+	fn is_close(&self, p: Position, q: Position) -> bool {
+		let d = if self.ordered {
+			q.saturating_sub(p)
+		} else {
+			p.abs_diff(q)
+		};
+		d >= 1 && d <= self.distance
+	}
+
+	/// Whether the terms are close enough, given the value index and the position of each of their occurrences
+	fn matches(&self, positions: &HashMap<usize, HashSet<(u32, Position)>>) -> bool {
+		let mut terms = self.terms.iter();
+		let Some(first) = terms.next() else {
+			return true;
+		};
+		let mut current: Vec<(u32, Position)> =
+			positions.get(first).map(|p| p.iter().copied().collect()).unwrap_or_default();
+		for t in terms {
+			let Some(next) = positions.get(t) else {
+				return false;
+			};
+			current = next
+				.iter()
+				.filter(|(i, q)| current.iter().any(|(j, p)| i == j && self.is_close(*p, *q)))
+				.copied()
+				.collect();
+			if current.is_empty() {
+				return false;
+			}
+		}
+		!current.is_empty()
+	}
+}
+
+/// Checks the positions of the terms of a document against the phrases and proximity operators of a query
+pub(crate) struct Proximities {
+	offsets: Offsets,
+	terms: Vec<Vec<TermId>>,
+	proximities: Vec<Proximity>,
+}
+
+impl Proximities {
+	pub(super) fn new(
+		offsets: Offsets,
+		terms: Vec<Vec<TermId>>,
+		proximities: Vec<Proximity>,
+	) -> Self {
+		Self {
+			offsets,
+			terms,
+			proximities,
+		}
+	}
+
+	pub(crate) async fn matches(&self, tx: &mut Transaction, doc_id: DocId) -> Result<bool, Error> {
+		let mut positions: HashMap<usize, HashSet<(u32, Position)>> = HashMap::new();
+		for p in &self.proximities {
+			for t in &p.terms {
+				if positions.contains_key(t) {
+					continue;
+				}
+				// A term matches any of its alternatives (fuzzy matches, synonyms...)
+				let mut pos = HashSet::new();
+				for term_id in self.terms.get(*t).into_iter().flatten() {
+					if let Some(o) = self.offsets.get_offsets(tx, doc_id, *term_id).await? {
+						pos.extend(o.0.into_iter().map(|o| (o.index, o.pos)));
+					}
+				}
+				positions.insert(*t, pos);
+			}
+			if !p.matches(&positions) {
+				return Ok(false);
+			}
+		}
+		Ok(true)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::ft::query::{parse, Part, Proximity};
+	use std::collections::{HashMap, HashSet};
+
+	fn part(text: &str, phrase: bool, near: Option<u32>) -> Part<'_> {
+		Part {
+			text,
+			phrase,
+			near,
+		}
+	}
+
+	#[test]
+	fn test_parse() {
+		assert_eq!(
+			parse("hello world"),
+			vec![part("hello", false, None), part("world", false, None)]
+		);
+		assert_eq!(
+			parse(r#"the "quick  brown" fox"#),
+			vec![
+				part("the", false, None),
+				part("quick  brown", true, None),
+				part("fox", false, None)
+			]
+		);
+		assert_eq!(
+			parse(r#""quick brown" near/3 fox jumps"#),
+			vec![
+				part("quick brown", true, Some(3)),
+				part("fox", true, None),
+				part("jumps", false, None)
+			]
+		);
+		// An operator without a left operand is a word, an unterminated phrase runs until the end
+		assert_eq!(
+			parse(r#"NEAR/2 "lazy dog"#),
+			vec![part("NEAR/2", false, None), part("lazy dog", true, None)]
+		);
+	}
+
+	#[test]
+	fn test_proximity() {
+		let positions = HashMap::from([
+			(0, HashSet::from([(0, 1), (1, 5)])),
+			(1, HashSet::from([(0, 2)])),
+			(2, HashSet::from([(1, 2)])),
+		]);
+		assert!(Proximity::phrase(vec![0, 1]).matches(&positions));
+		assert!(!Proximity::phrase(vec![1, 0]).matches(&positions));
+		// The terms have to be within the same value
+		assert!(!Proximity::near(0, 2, 2).matches(&positions));
+		assert!(Proximity::near(2, 0, 3).matches(&positions));
+		assert!(Proximity::near(1, 0, 1).matches(&positions));
+	}
+}
@@ -1,8 +1,10 @@
 use crate::err::Error;
 use crate::idx::ft::docids::DocId;
 use crate::idx::ft::doclength::{DocLength, DocLengths};
+use crate::idx::ft::fields::Fields;
 use crate::idx::ft::postings::{Postings, TermFrequency};
 use crate::idx::ft::termdocs::TermsDocs;
+use crate::idx::ft::terms::TermId;
 use crate::idx::ft::Bm25Params;
 use crate::kvs::Transaction;
 use std::sync::Arc;
@@ -17,9 +19,13 @@ pub(crate) struct BM25Scorer {
 	average_doc_length: f32,
 	doc_count: f32,
 	bm25: Bm25Params,
+	fields: Fields,
+	/// The weight of the terms of each field
+	boosts: Vec<f64>,
 }
 
 impl BM25Scorer {
+	#[allow(clippy::too_many_arguments)]
 	pub(super) fn new(
 		postings: Arc<RwLock<Postings>>,
 		terms_docs: TermsDocs,
@@ -27,6 +33,8 @@ impl BM25Scorer {
 		total_docs_length: u128,
 		doc_count: u64,
 		bm25: Bm25Params,
+		fields: Fields,
+		boosts: Vec<f64>,
 	) -> Self {
 		Self {
 			postings,
@@ -35,6 +43,8 @@ impl BM25Scorer {
 			average_doc_length: (total_docs_length as f32) / (doc_count as f32),
 			doc_count: doc_count as f32,
 			bm25,
+			fields,
+			boosts,
 		}
 	}
 
@@ -42,12 +52,26 @@ impl BM25Scorer {
 		&self,
 		tx: &mut Transaction,
 		doc_id: DocId,
+		term_id: TermId,
 		term_doc_count: DocLength,
 		term_frequency: TermFrequency,
 	) -> Result<Score, Error> {
 		let doc_length =
 			self.doc_lengths.read().await.get_doc_length(tx, doc_id).await?.unwrap_or(0);
-		Ok(self.compute_bm25_score(term_frequency as f32, term_doc_count as f32, doc_length as f32))
+		let score = |tf: TermFrequency| {
+			self.compute_bm25_score(tf as f32, term_doc_count as f32, doc_length as f32)
+		};
+		// With boosted fields, the score of the term in each field is weighed by its boost
+		if !self.boosts.is_empty() {
+			if let Some(freqs) = self.fields.get_frequencies(tx, doc_id, term_id).await? {
+				let mut sc = 0.0;
+				for (i, tf) in freqs.into_iter().enumerate().filter(|(_, tf)| *tf > 0) {
+					sc += self.boosts.get(i).copied().unwrap_or(1.0) as f32 * score(tf);
+				}
+				return Ok(sc);
+			}
+		}
+		Ok(score(term_frequency))
 	}
 
 	pub(crate) async fn score(
@@ -61,7 +85,7 @@ impl BM25Scorer {
 				if let Some(term_freq) =
 					self.postings.read().await.get_term_frequency(tx, *term_id, doc_id).await?
 				{
-					sc += self.term_score(tx, doc_id, docs.len(), term_freq).await?;
+					sc += self.term_score(tx, doc_id, *term_id, docs.len(), term_freq).await?;
 				}
 			}
 		}
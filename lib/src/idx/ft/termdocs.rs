@@ -7,7 +7,8 @@ use crate::kvs::Transaction;
 use roaring::RoaringTreemap;
 use std::sync::Arc;
 
-pub(in crate::idx) type TermsDocs = Arc<Vec<Option<(TermId, RoaringTreemap)>>>;
+/// The documents of each term of a query, or of every term close enough to it for a fuzzy query
+pub(in crate::idx) type TermsDocs = Arc<Vec<Vec<(TermId, RoaringTreemap)>>>;
 
 pub(super) struct TermDocs {
 	index_key_base: IndexKeyBase,
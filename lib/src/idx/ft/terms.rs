@@ -1,8 +1,10 @@
 use crate::err::Error;
+use crate::idx::ft::levenshtein::Levenshtein;
 use crate::idx::trees::bkeys::FstKeys;
 use crate::idx::trees::btree::{BState, BStatistics, BTree, BTreeNodeStore};
 use crate::idx::trees::store::{TreeNodeProvider, TreeNodeStore, TreeStoreType};
 use crate::idx::{IndexKeyBase, VersionedSerdeState};
+use crate::key::index::bu::Bu;
 use crate::kvs::{Key, Transaction};
 use revision::revisioned;
 use roaring::RoaringTreemap;
@@ -93,6 +95,33 @@ impl Terms {
 		self.btree.search(tx, &mut store, &term.into()).await
 	}
 
+	/// Collect the terms of the dictionary which are within an edit distance of a term
+	pub(super) async fn get_fuzzy_term_ids(
+		&self,
+		tx: &mut Transaction,
+		term: &str,
+		distance: u8,
+	) -> Result<Vec<TermId>, Error> {
+		let lev = Levenshtein::new(term, distance);
+		let mut res = vec![];
+		let mut beg = self.index_key_base.new_bu_key(0);
+		let end = self.index_key_base.new_bu_key(self.next_term_id);
+		loop {
+			let batch = tx.scan(beg..end.clone(), 1000).await?;
+			let Some((last, _)) = batch.last() else {
+				break;
+			};
+			beg = last.clone();
+			beg.push(0x00);
+			for (key, val) in batch {
+				if lev.matches(&String::from_utf8_lossy(&val)) {
+					res.push(Bu::decode(&key)?.term_id);
+				}
+			}
+		}
+		Ok(res)
+	}
+
 	pub(super) async fn remove_term_id(
 		&mut self,
 		tx: &mut Transaction,
@@ -285,6 +314,34 @@ mod tests {
 		tx.commit().await.unwrap();
 	}
 
+	#[tokio::test]
+	async fn test_fuzzy_terms() {
+		const BTREE_ORDER: u32 = 7;
+
+		let idx = IndexKeyBase::default();
+
+		let ds = Datastore::new("memory").await.unwrap();
+
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut t =
+			Terms::new(&mut tx, idx.clone(), BTREE_ORDER, TreeStoreType::Write).await.unwrap();
+
+		for term in ["hello", "hallo", "help", "world"] {
+			t.resolve_term_id(&mut tx, term).await.unwrap();
+		}
+
+		let mut res = t.get_fuzzy_term_ids(&mut tx, "hello", 1).await.unwrap();
+		res.sort();
+		assert_eq!(res, vec![0, 1]);
+		let mut res = t.get_fuzzy_term_ids(&mut tx, "hello", 2).await.unwrap();
+		res.sort();
+		assert_eq!(res, vec![0, 1, 2]);
+		assert!(t.get_fuzzy_term_ids(&mut tx, "word", 0).await.unwrap().is_empty());
+
+		t.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+	}
+
 	fn random_term_freq_vec(term_count: usize) -> Vec<(String, TermFrequency)> {
 		let mut i = 1;
 		let mut vec = Vec::with_capacity(term_count);
@@ -1,6 +1,7 @@
 pub(crate) mod ft;
 pub(crate) mod planner;
 pub mod trees;
+pub(crate) mod trigram;
 
 use crate::dbs::Options;
 use crate::err::Error;
@@ -18,6 +19,11 @@ use crate::key::index::bp::Bp;
 use crate::key::index::bs::Bs;
 use crate::key::index::bt::Bt;
 use crate::key::index::bu::Bu;
+use crate::key::index::bw::Bw;
+use crate::key::index::he::He;
+use crate::key::index::hq::Hq;
+use crate::key::index::hs::Hs;
+use crate::key::index::hv::Hv;
 use crate::kvs::{Key, Val};
 use crate::sql::statements::DefineIndexStatement;
 use revision::Revisioned;
@@ -117,6 +123,18 @@ impl IndexKeyBase {
 		.into()
 	}
 
+	fn new_bw_key(&self, doc_id: DocId, term_id: TermId) -> Key {
+		Bw::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			doc_id,
+			term_id,
+		)
+		.into()
+	}
+
 	fn new_bp_key(&self, node_id: Option<NodeId>) -> Key {
 		Bp::new(
 			self.inner.ns.as_str(),
@@ -171,6 +189,48 @@ impl IndexKeyBase {
 		)
 		.into()
 	}
+
+	fn new_he_key(&self, doc_id: DocId) -> Key {
+		He::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			doc_id,
+		)
+		.into()
+	}
+
+	fn new_hs_key(&self) -> Key {
+		Hs::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+		)
+		.into()
+	}
+
+	fn new_hv_key(&self, doc_id: DocId) -> Key {
+		Hv::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			doc_id,
+		)
+		.into()
+	}
+
+	fn new_hq_key(&self) -> Key {
+		Hq::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+		)
+		.into()
+	}
 }
 
 /// This trait provides `Revision` based default implementations for serialization/deserialization
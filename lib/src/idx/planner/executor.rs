@@ -1,22 +1,30 @@
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
 use crate::idx::ft::docids::{DocId, DocIds};
+use crate::idx::ft::highlighter::HighlightParams;
+use crate::idx::ft::query::Proximities;
 use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::terms::TermId;
 use crate::idx::ft::{FtIndex, MatchRef};
 use crate::idx::planner::iterators::{
-	MatchesThingIterator, NonUniqueEqualThingIterator, ThingIterator, UniqueEqualThingIterator,
+	IndexRangeThingIterator, KnnThingIterator, MatchesThingIterator, NonUniqueEqualThingIterator,
+	ThingIterator, TrigramThingIterator, UniqueEqualThingIterator,
 };
 use crate::idx::planner::plan::IndexOption;
 use crate::idx::planner::tree::IndexMap;
+use crate::idx::trees::hnsw::HnswIndex;
 use crate::idx::trees::store::TreeStoreType;
+use crate::idx::trigram;
 use crate::idx::IndexKeyBase;
+use crate::key;
 use crate::kvs;
 use crate::kvs::Key;
 use crate::sql::index::Index;
-use crate::sql::{Expression, Operator, Table, Thing, Value};
-use std::collections::HashMap;
+use crate::sql::{Expression, Idiom, Operator, Strand, Table, Thing, Value};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -27,6 +35,7 @@ pub(crate) struct QueryExecutor {
 	ft_map: HashMap<String, FtIndex>,
 	mr_entries: HashMap<MatchRef, FtEntry>,
 	exp_entries: HashMap<Expression, FtEntry>,
+	knn_entries: HashMap<Expression, KnnEntry>,
 	iterators: Vec<Expression>,
 }
 
@@ -42,10 +51,21 @@ impl QueryExecutor {
 		let mut mr_entries = HashMap::default();
 		let mut exp_entries = HashMap::default();
 		let mut ft_map = HashMap::default();
+		let mut knn_entries = HashMap::default();
 
 		// Create all the instances of FtIndex
 		// Build the FtEntries and map them to Expressions and MatchRef
 		for (exp, io) in index_map.consume() {
+			// Find the nearest neighbours of the vectors compared by KNN operators
+			if let (Index::Hnsw(p), Operator::Knn(k)) = (&io.ix().index, io.op()) {
+				if let Some(v) = io.array().first() {
+					let ikb = IndexKeyBase::new(opt, io.ix());
+					let mut hnsw = HnswIndex::new(&mut run, ikb, p, TreeStoreType::Read).await?;
+					let res = hnsw.knn(&mut run, v, *k as usize).await?;
+					knn_entries.insert(exp, KnnEntry::new(res));
+				}
+				continue;
+			}
 			let mut entry = None;
 			if let Index::Search(p) = &io.ix().index {
 				let ixn = &io.ix().name.0;
@@ -82,6 +102,7 @@ impl QueryExecutor {
 			ft_map,
 			mr_entries,
 			exp_entries,
+			knn_entries,
 			iterators: Vec::new(),
 		})
 	}
@@ -121,6 +142,8 @@ impl QueryExecutor {
 			Index::Search {
 				..
 			} => self.new_search_index_iterator(ir, io).await,
+			Index::Hnsw(_) => Ok(self.new_knn_iterator(ir)),
+			Index::Trigram => Ok(Self::new_trigram_iterator(opt, &io)),
 			_ => Err(Error::FeatureNotYetImplemented {
 				feature: "VectorSearch iterator",
 			}),
@@ -128,6 +151,9 @@ impl QueryExecutor {
 	}
 
 	fn new_index_iterator(opt: &Options, io: IndexOption) -> Result<Option<ThingIterator>, Error> {
+		if !io.is_exact() {
+			return Ok(Some(Self::new_range_iterator(opt, &io)));
+		}
 		if io.op() == &Operator::Equal {
 			return Ok(Some(ThingIterator::NonUniqueEqual(NonUniqueEqualThingIterator::new(
 				opt,
@@ -142,6 +168,9 @@ impl QueryExecutor {
 		opt: &Options,
 		io: IndexOption,
 	) -> Result<Option<ThingIterator>, Error> {
+		if !io.is_exact() {
+			return Ok(Some(Self::new_range_iterator(opt, &io)));
+		}
 		if io.op() == &Operator::Equal {
 			return Ok(Some(ThingIterator::UniqueEqual(UniqueEqualThingIterator::new(
 				opt,
@@ -152,17 +181,67 @@ impl QueryExecutor {
 		Ok(None)
 	}
 
+	/// Scan the leading columns of a composite index, and the range of the next column
+	fn new_range_iterator(opt: &Options, io: &IndexOption) -> ThingIterator {
+		let (from, to) = match io.range() {
+			Some((from, to)) => (Self::widen_from(from), Self::widen_to(to)),
+			None => (Bound::Unbounded, Bound::Unbounded),
+		};
+		ThingIterator::IndexRange(IndexRangeThingIterator::new(
+			opt,
+			io.ix(),
+			io.array(),
+			from.as_ref(),
+			to.as_ref(),
+		))
+	}
+
+	/// The keys of an index sort the numbers by kind before their value, so a range
+	/// starting with a float or a decimal has to start with the smallest integer.
+	/// The records of a wider range are filtered by the condition afterwards.
+	fn widen_from(from: &Bound<Value>) -> Bound<Value> {
+		match from {
+			Bound::Included(Value::Number(n)) | Bound::Excluded(Value::Number(n))
+				if !n.is_int() =>
+			{
+				Bound::Included(Value::from(i64::MIN))
+			}
+			b => b.clone(),
+		}
+	}
+
+	/// A range ending with a number has to include the numbers of any kind
+	fn widen_to(to: &Bound<Value>) -> Bound<Value> {
+		match to {
+			Bound::Included(Value::Number(_)) | Bound::Excluded(Value::Number(_)) => {
+				Bound::Excluded(Value::Strand(Strand::default()))
+			}
+			b => b.clone(),
+		}
+	}
+
+	/// Find the records holding every trigram of the searched string
+	fn new_trigram_iterator(opt: &Options, io: &IndexOption) -> Option<ThingIterator> {
+		let trigrams = trigram::query_trigrams(io.qs()?);
+		Some(ThingIterator::Trigram(TrigramThingIterator::new(opt, io.ix(), trigrams)))
+	}
+
 	async fn new_search_index_iterator(
 		&self,
 		ir: IteratorRef,
 		io: IndexOption,
 	) -> Result<Option<ThingIterator>, Error> {
 		if let Some(exp) = self.iterators.get(ir as usize) {
-			if let Operator::Matches(_) = io.op() {
+			if let Operator::Matches(..) | Operator::Fuzzy(..) = io.op() {
 				let ixn = &io.ix().name.0;
 				if let Some(fti) = self.ft_map.get(ixn) {
 					if let Some(fte) = self.exp_entries.get(exp) {
-						let it = MatchesThingIterator::new(fti, fte.0.terms_docs.clone()).await?;
+						let it = MatchesThingIterator::new(
+							fti,
+							fte.0.terms_docs.clone(),
+							fte.0.proximities.clone(),
+						)
+						.await?;
 						return Ok(Some(ThingIterator::Matches(it)));
 					}
 				}
@@ -171,6 +250,12 @@ impl QueryExecutor {
 		Ok(None)
 	}
 
+	fn new_knn_iterator(&self, ir: IteratorRef) -> Option<ThingIterator> {
+		let exp = self.iterators.get(ir as usize)?;
+		let entry = self.knn_entries.get(exp)?;
+		Some(ThingIterator::Knn(KnnThingIterator::new(entry.things.clone())))
+	}
+
 	pub(crate) async fn matches(
 		&self,
 		txn: &Transaction,
@@ -191,16 +276,16 @@ impl QueryExecutor {
 					if term_goals == 0 {
 						return Ok(Value::Bool(false));
 					}
-					for opt_td in ft.0.terms_docs.iter() {
-						if let Some((_, docs)) = opt_td {
-							if !docs.contains(doc_id) {
-								return Ok(Value::Bool(false));
-							}
-						} else {
-							// If one of the term is missing, it can't be a match
+					for tds in ft.0.terms_docs.iter() {
+						// If one of the term is missing, it can't be a match
+						if !tds.iter().any(|(_, docs)| docs.contains(doc_id)) {
 							return Ok(Value::Bool(false));
 						}
 					}
+					// The terms of the phrases have to be close to each other
+					if let Some(p) = &ft.0.proximities {
+						return Ok(Value::Bool(p.matches(&mut run, doc_id).await?));
+					}
 					return Ok(Value::Bool(true));
 				}
 				return Ok(Value::Bool(false));
@@ -213,6 +298,18 @@ impl QueryExecutor {
 		})
 	}
 
+	pub(crate) fn knn(&self, thg: &Thing, exp: &Expression) -> Result<Value, Error> {
+		// Does the record id match this executor's table?
+		if thg.tb.eq(&self.table) {
+			if let Some(entry) = self.knn_entries.get(exp) {
+				return Ok(Value::Bool(entry.set.contains(thg)));
+			}
+		}
+		Err(Error::NoIndexFoundForKnn {
+			value: exp.to_string(),
+		})
+	}
+
 	fn get_ft_entry(&self, match_ref: &Value) -> Option<&FtEntry> {
 		if let Some(mr) = Self::get_match_ref(match_ref) {
 			self.mr_entries.get(&mr)
@@ -234,15 +331,14 @@ impl QueryExecutor {
 		&self,
 		txn: &Transaction,
 		thg: &Thing,
-		prefix: Value,
-		suffix: Value,
+		hp: HighlightParams,
 		match_ref: &Value,
 		doc: &Value,
 	) -> Result<Value, Error> {
 		if let Some((e, ft)) = self.get_ft_entry_and_index(match_ref) {
 			let mut run = txn.lock().await;
 			return ft
-				.highlight(&mut run, thg, &e.0.terms, prefix, suffix, e.0.index_option.id(), doc)
+				.highlight(&mut run, thg, &e.0.terms, hp, &e.0.index_option.ix().cols, doc)
 				.await;
 		}
 		Ok(Value::None)
@@ -261,6 +357,103 @@ impl QueryExecutor {
 		Ok(Value::None)
 	}
 
+	/// Count the documents matched by a full-text query for each value of a field.
+	/// The values are read from an index on the field, rather than from the documents.
+	pub(crate) async fn facets(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		match_ref: &Value,
+		field: &Idiom,
+		size: Option<usize>,
+	) -> Result<Value, Error> {
+		let Some((e, ft)) = self.get_ft_entry_and_index(match_ref) else {
+			return Ok(Value::None);
+		};
+		// The facets are the same for every matched document, so they are only computed once
+		let mut facets = e.0.facets.write().await;
+		let buckets = match facets.entry(field.clone()) {
+			Entry::Occupied(e) => e.into_mut(),
+			Entry::Vacant(v) => {
+				let mut run = txn.lock().await;
+				let buckets = self.compute_facets(opt, &mut run, e, ft, field).await?;
+				v.insert(buckets)
+			}
+		};
+		let buckets = match size {
+			Some(size) => buckets.iter().take(size).cloned().collect(),
+			None => buckets.clone(),
+		};
+		Ok(Value::from(buckets))
+	}
+
+	async fn compute_facets(
+		&self,
+		opt: &Options,
+		run: &mut kvs::Transaction,
+		e: &FtEntry,
+		ft: &FtIndex,
+		field: &Idiom,
+	) -> Result<Vec<Value>, Error> {
+		let indexes = run.all_tb_indexes(opt.ns(), opt.db(), &self.table).await?;
+		let Some(ix) = indexes.iter().find(|ix| {
+			!ix.building
+				&& matches!(ix.index, Index::Idx | Index::Uniq)
+				&& ix.cols.len() == 1
+				&& ix.cols[0].eq(field)
+		}) else {
+			return Err(Error::NoIndexFoundForFacets {
+				value: field.to_string(),
+			});
+		};
+		// Collect the matched documents
+		let mut things = HashSet::new();
+		if let Some(mut hits) = ft.new_hits_iterator(e.0.terms_docs.clone())? {
+			while let Some((thg, doc_id)) = hits.next(run).await? {
+				if let Some(p) = &e.0.proximities {
+					if !p.matches(run, doc_id).await? {
+						continue;
+					}
+				}
+				things.insert(thg);
+			}
+		}
+		// Count the matched documents of each value of the index
+		let mut counts: BTreeMap<Value, u64> = BTreeMap::new();
+		if !things.is_empty() {
+			let rng = key::index::Index::range(opt.ns(), opt.db(), &ix.what, &ix.name);
+			let mut beg = rng.start;
+			loop {
+				let res = run.scan(beg.clone()..rng.end.clone(), 1000).await?;
+				let Some((last, _)) = res.last() else {
+					break;
+				};
+				beg = last.clone();
+				beg.push(0x00);
+				for (k, v) in &res {
+					let thg: Thing = v.into();
+					if things.contains(&thg) {
+						let k = key::index::Index::decode(k)?;
+						let fd = k.fd.into_owned().0.into_iter().next().unwrap_or_default();
+						*counts.entry(fd).or_default() += 1;
+					}
+				}
+			}
+		}
+		// The most frequent values come first
+		let mut counts: Vec<(Value, u64)> = counts.into_iter().collect();
+		counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+		Ok(counts
+			.into_iter()
+			.map(|(value, count)| {
+				Value::from(map! {
+					"count".to_string() => Value::from(count),
+					"value".to_string() => value,
+				})
+			})
+			.collect())
+	}
+
 	pub(crate) async fn score(
 		&self,
 		txn: &Transaction,
@@ -293,9 +486,12 @@ struct FtEntry(Arc<Inner>);
 struct Inner {
 	index_option: IndexOption,
 	doc_ids: Arc<RwLock<DocIds>>,
-	terms: Vec<Option<TermId>>,
+	terms: Vec<Vec<TermId>>,
 	terms_docs: TermsDocs,
+	proximities: Option<Arc<Proximities>>,
 	scorer: Option<BM25Scorer>,
+	/// The facets of each field, once computed
+	facets: RwLock<HashMap<Idiom, Vec<Value>>>,
 }
 
 impl FtEntry {
@@ -305,7 +501,12 @@ impl FtEntry {
 		io: IndexOption,
 	) -> Result<Option<Self>, Error> {
 		if let Some(qs) = io.qs() {
-			let terms = ft.extract_terms(tx, qs.to_owned()).await?;
+			let distance = match io.op() {
+				Operator::Fuzzy(_, d) => *d,
+				_ => 0,
+			};
+			let (terms, proximities) = ft.extract_terms(tx, qs.to_owned(), distance).await?;
+			let proximities = ft.new_proximities(&terms, proximities)?.map(Arc::new);
 			let terms_docs = Arc::new(ft.get_terms_docs(tx, &terms).await?);
 			Ok(Some(Self(Arc::new(Inner {
 				index_option: io,
@@ -313,9 +514,27 @@ impl FtEntry {
 				scorer: ft.new_scorer(terms_docs.clone())?,
 				terms,
 				terms_docs,
+				proximities,
+				facets: RwLock::new(HashMap::new()),
 			}))))
 		} else {
 			Ok(None)
 		}
 	}
 }
+
+/// The nearest neighbours found for a KNN expression, nearest first
+struct KnnEntry {
+	things: Vec<Thing>,
+	set: HashSet<Thing>,
+}
+
+impl KnnEntry {
+	fn new(res: Vec<(Thing, f64)>) -> Self {
+		let things: Vec<Thing> = res.into_iter().map(|(thg, _)| thg).collect();
+		Self {
+			set: things.iter().cloned().collect(),
+			things,
+		}
+	}
+}
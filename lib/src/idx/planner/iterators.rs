@@ -1,17 +1,24 @@
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
 use crate::idx::ft::docids::{DocId, NO_DOC_ID};
+use crate::idx::ft::query::Proximities;
 use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::{FtIndex, HitsIterator};
 use crate::key;
-use crate::kvs::Key;
+use crate::kvs::{Key, Val};
 use crate::sql::statements::DefineIndexStatement;
-use crate::sql::{Array, Thing};
+use crate::sql::{Array, Thing, Value};
+use std::collections::VecDeque;
+use std::ops::Bound;
+use std::sync::Arc;
 
 pub(crate) enum ThingIterator {
 	NonUniqueEqual(NonUniqueEqualThingIterator),
+	IndexRange(IndexRangeThingIterator),
 	UniqueEqual(UniqueEqualThingIterator),
 	Matches(MatchesThingIterator),
+	Knn(KnnThingIterator),
+	Trigram(TrigramThingIterator),
 }
 
 impl ThingIterator {
@@ -22,12 +29,53 @@ impl ThingIterator {
 	) -> Result<Vec<(Thing, DocId)>, Error> {
 		match self {
 			ThingIterator::NonUniqueEqual(i) => i.next_batch(tx, size).await,
+			ThingIterator::IndexRange(i) => i.next_batch(tx, size).await,
 			ThingIterator::UniqueEqual(i) => i.next_batch(tx, size).await,
 			ThingIterator::Matches(i) => i.next_batch(tx, size).await,
+			ThingIterator::Knn(i) => Ok(i.next_batch(size)),
+			ThingIterator::Trigram(i) => i.next_batch(tx, size).await,
+		}
+	}
+
+	/// The next records, along with the values of the columns of the index they were found with
+	pub(crate) async fn next_covered_batch(
+		&mut self,
+		tx: &Transaction,
+		size: u32,
+	) -> Result<Vec<(Thing, Array)>, Error> {
+		match self {
+			ThingIterator::NonUniqueEqual(i) => covered(scan(tx, &mut i.beg, &i.end, size).await?),
+			ThingIterator::IndexRange(i) => covered(scan(tx, &mut i.beg, &i.end, size).await?),
+			ThingIterator::UniqueEqual(i) => i.next_covered_batch(tx).await,
+			ThingIterator::Matches(_) | ThingIterator::Knn(_) | ThingIterator::Trigram(_) => {
+				Err(Error::Unreachable)
+			}
 		}
 	}
 }
 
+/// Scan the next entries of an index, up to the end key
+async fn scan(
+	txn: &Transaction,
+	beg: &mut Vec<u8>,
+	end: &[u8],
+	limit: u32,
+) -> Result<Vec<(Key, Val)>, Error> {
+	let res = txn.lock().await.scan(beg.clone()..end.to_vec(), limit).await?;
+	if let Some((key, _)) = res.last() {
+		*beg = key.clone();
+		beg.push(0x00);
+	}
+	Ok(res)
+}
+
+/// Decode the values of the columns of the index from the keys of its entries
+fn covered(res: Vec<(Key, Val)>) -> Result<Vec<(Thing, Array)>, Error> {
+	res.iter()
+		.map(|(key, val)| Ok((val.into(), key::index::Index::decode(key)?.fd.into_owned())))
+		.collect()
+}
+
 pub(crate) struct NonUniqueEqualThingIterator {
 	beg: Vec<u8>,
 	end: Vec<u8>,
@@ -52,13 +100,47 @@ impl NonUniqueEqualThingIterator {
 		txn: &Transaction,
 		limit: u32,
 	) -> Result<Vec<(Thing, DocId)>, Error> {
-		let min = self.beg.clone();
-		let max = self.end.clone();
-		let res = txn.lock().await.scan(min..max, limit).await?;
-		if let Some((key, _)) = res.last() {
-			self.beg = key.clone();
-			self.beg.push(0x00);
+		let res = scan(txn, &mut self.beg, &self.end, limit).await?;
+		let res = res.iter().map(|(_, val)| (val.into(), NO_DOC_ID)).collect();
+		Ok(res)
+	}
+}
+
+/// Iterates the records of a composite index, in the order of its columns
+pub(crate) struct IndexRangeThingIterator {
+	beg: Vec<u8>,
+	end: Vec<u8>,
+}
+
+impl IndexRangeThingIterator {
+	pub(super) fn new(
+		opt: &Options,
+		ix: &DefineIndexStatement,
+		prefix: &Array,
+		from: Bound<&Value>,
+		to: Bound<&Value>,
+	) -> Self {
+		let (beg, end) = key::index::Index::range_composite(
+			opt.ns(),
+			opt.db(),
+			&ix.what,
+			&ix.name,
+			prefix,
+			from,
+			to,
+		);
+		Self {
+			beg,
+			end,
 		}
+	}
+
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let res = scan(txn, &mut self.beg, &self.end, limit).await?;
 		let res = res.iter().map(|(_, val)| (val.into(), NO_DOC_ID)).collect();
 		Ok(res)
 	}
@@ -88,17 +170,35 @@ impl UniqueEqualThingIterator {
 		}
 		Ok(vec![])
 	}
+
+	async fn next_covered_batch(
+		&mut self,
+		txn: &Transaction,
+	) -> Result<Vec<(Thing, Array)>, Error> {
+		if let Some(key) = self.key.take() {
+			if let Some(val) = txn.lock().await.get(key.clone()).await? {
+				return covered(vec![(key, val)]);
+			}
+		}
+		Ok(vec![])
+	}
 }
 
 pub(crate) struct MatchesThingIterator {
 	hits: Option<HitsIterator>,
+	proximities: Option<Arc<Proximities>>,
 }
 
 impl MatchesThingIterator {
-	pub(super) async fn new(fti: &FtIndex, terms_docs: TermsDocs) -> Result<Self, Error> {
+	pub(super) async fn new(
+		fti: &FtIndex,
+		terms_docs: TermsDocs,
+		proximities: Option<Arc<Proximities>>,
+	) -> Result<Self, Error> {
 		let hits = fti.new_hits_iterator(terms_docs)?;
 		Ok(Self {
 			hits,
+			proximities,
 		})
 	}
 
@@ -112,6 +212,12 @@ impl MatchesThingIterator {
 			let mut run = txn.lock().await;
 			while limit > 0 {
 				if let Some(hit) = hits.next(&mut run).await? {
+					// The documents containing every term may not contain the phrases
+					if let Some(p) = &self.proximities {
+						if !p.matches(&mut run, hit.1).await? {
+							continue;
+						}
+					}
 					res.push(hit);
 				} else {
 					break;
@@ -122,3 +228,88 @@ impl MatchesThingIterator {
 		Ok(res)
 	}
 }
+
+pub(crate) struct KnnThingIterator {
+	things: VecDeque<Thing>,
+}
+
+impl KnnThingIterator {
+	pub(super) fn new(things: Vec<Thing>) -> Self {
+		Self {
+			things: things.into(),
+		}
+	}
+
+	fn next_batch(&mut self, limit: u32) -> Vec<(Thing, DocId)> {
+		let n = (limit as usize).min(self.things.len());
+		self.things.drain(..n).map(|thg| (thg, NO_DOC_ID)).collect()
+	}
+}
+
+/// Iterates the records holding every trigram of a searched string.
+/// The entries of the first trigram are scanned, then each record is checked for the others.
+pub(crate) struct TrigramThingIterator {
+	ns: String,
+	db: String,
+	tb: String,
+	ix: String,
+	beg: Vec<u8>,
+	end: Vec<u8>,
+	others: Vec<Array>,
+}
+
+impl TrigramThingIterator {
+	pub(super) fn new(opt: &Options, ix: &DefineIndexStatement, trigrams: Vec<String>) -> Self {
+		let mut trigrams = trigrams.into_iter().map(|t| Array::from(vec![t]));
+		let first = trigrams.next().unwrap_or_default();
+		let (beg, end) =
+			key::index::Index::range_all_ids(opt.ns(), opt.db(), &ix.what, &ix.name, &first);
+		Self {
+			ns: opt.ns().to_owned(),
+			db: opt.db().to_owned(),
+			tb: ix.what.0.to_owned(),
+			ix: ix.name.0.to_owned(),
+			beg,
+			end,
+			others: trigrams.collect(),
+		}
+	}
+
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let mut res = vec![];
+		// An empty batch ends the iteration, so batches are scanned until a record matches
+		while res.is_empty() {
+			let entries = scan(txn, &mut self.beg, &self.end, limit).await?;
+			if entries.is_empty() {
+				break;
+			}
+			let mut run = txn.lock().await;
+			for (_, val) in &entries {
+				let thg: Thing = val.into();
+				let mut found = true;
+				for t in &self.others {
+					let key = key::index::Index::new(
+						&self.ns,
+						&self.db,
+						&self.tb,
+						&self.ix,
+						t,
+						Some(&thg.id),
+					);
+					if !run.exi(key).await? {
+						found = false;
+						break;
+					}
+				}
+				if found {
+					res.push((thg, NO_DOC_ID));
+				}
+			}
+		}
+		Ok(res)
+	}
+}
@@ -7,29 +7,48 @@ use crate::ctx::Context;
 use crate::dbs::{Iterable, Iterator, Options, Transaction};
 use crate::err::Error;
 use crate::idx::planner::executor::QueryExecutor;
-use crate::idx::planner::plan::{Plan, PlanBuilder};
+use crate::idx::planner::plan::{IndexOption, Plan, PlanBuilder};
 use crate::idx::planner::tree::Tree;
+use crate::sql::kind::Kind;
+use crate::sql::order::Orders;
 use crate::sql::with::With;
-use crate::sql::{Cond, Table};
+use crate::sql::{Cond, Idiom, Table};
 use std::collections::HashMap;
 
 pub(crate) struct QueryPlanner<'a> {
 	opt: &'a Options,
 	with: &'a Option<With>,
 	cond: &'a Option<Cond>,
+	order: &'a Option<Orders>,
+	/// The fields read by the statement, when they are known in advance
+	idioms: Option<Vec<Idiom>>,
 	/// There is one executor per table
 	executors: HashMap<String, QueryExecutor>,
 	requires_distinct: bool,
+	/// The number of tables whose records are iterated
+	tables: usize,
+	/// Whether an index iterates the records in the order of the ORDER clause
+	ordered: bool,
 }
 
 impl<'a> QueryPlanner<'a> {
-	pub(crate) fn new(opt: &'a Options, with: &'a Option<With>, cond: &'a Option<Cond>) -> Self {
+	pub(crate) fn new(
+		opt: &'a Options,
+		with: &'a Option<With>,
+		cond: &'a Option<Cond>,
+		order: &'a Option<Orders>,
+		idioms: Option<Vec<Idiom>>,
+	) -> Self {
 		Self {
 			opt,
 			with,
 			cond,
+			order,
+			idioms,
 			executors: HashMap::default(),
 			requires_distinct: false,
+			tables: 0,
+			ordered: false,
 		}
 	}
 
@@ -40,11 +59,20 @@ impl<'a> QueryPlanner<'a> {
 		t: Table,
 		it: &mut Iterator,
 	) -> Result<(), Error> {
+		self.tables += 1;
 		let res = Tree::build(ctx, self.opt, txn, &t, self.cond).await?;
-		if let Some((node, im)) = res {
+		if let Some((node, im, composites)) = res {
 			let mut exe = QueryExecutor::new(self.opt, txn, &t, im).await?;
-			let ok = match PlanBuilder::build(node, self.with)? {
+			let ok = match PlanBuilder::build(node, self.with, composites)? {
 				Plan::SingleIndex(exp, io) => {
+					if let Some(order) = self.order {
+						self.ordered = io.is_ordered_by(order);
+					}
+					let io = if self.is_covering(txn, &t, &io).await? {
+						io.with_covering()
+					} else {
+						io
+					};
 					let ir = exe.add_iterator(exp);
 					it.ingest(Iterable::Index(t.clone(), ir, io));
 					true
@@ -68,6 +96,27 @@ impl<'a> QueryPlanner<'a> {
 		Ok(())
 	}
 
+	/// Whether the index holds every field read by the statement. As the elements
+	/// of an array are indexed separately, the fields have to be defined with a type
+	/// which is not an array.
+	async fn is_covering(
+		&self,
+		txn: &Transaction,
+		t: &Table,
+		io: &IndexOption,
+	) -> Result<bool, Error> {
+		let Some(idioms) = &self.idioms else {
+			return Ok(false);
+		};
+		if !io.covers(idioms) {
+			return Ok(false);
+		}
+		let fields = txn.lock().await.all_tb_fields(self.opt.ns(), self.opt.db(), &t.0).await?;
+		Ok(io.ix().cols.iter().all(|c| {
+			fields.iter().any(|f| f.name.eq(c) && f.kind.as_ref().map_or(false, Kind::is_scalar))
+		}))
+	}
+
 	pub(crate) fn has_executors(&self) -> bool {
 		!self.executors.is_empty()
 	}
@@ -79,4 +128,9 @@ impl<'a> QueryPlanner<'a> {
 	pub(crate) fn requires_distinct(&self) -> bool {
 		self.requires_distinct
 	}
+
+	/// Whether the records are iterated from a single index, in the order of the ORDER clause
+	pub(crate) fn is_ordered(&self) -> bool {
+		self.ordered && self.tables == 1
+	}
 }
@@ -1,12 +1,15 @@
 use crate::err::Error;
 use crate::idx::ft::MatchRef;
 use crate::idx::planner::tree::Node;
+use crate::sql::index::Index;
+use crate::sql::order::Orders;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::with::With;
-use crate::sql::{Array, Object};
-use crate::sql::{Expression, Idiom, Operator, Value};
+use crate::sql::{Array, Idioms, Object};
+use crate::sql::{Expression, Idiom, Operator, Part, Value};
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::ops::Bound;
 use std::sync::Arc;
 
 pub(super) struct PlanBuilder<'a> {
@@ -17,7 +20,11 @@ pub(super) struct PlanBuilder<'a> {
 }
 
 impl<'a> PlanBuilder<'a> {
-	pub(super) fn build(root: Node, with: &'a Option<With>) -> Result<Plan, Error> {
+	pub(super) fn build(
+		root: Node,
+		with: &'a Option<With>,
+		composites: Vec<(Expression, IndexOption)>,
+	) -> Result<Plan, Error> {
 		if let Some(with) = with {
 			if matches!(with, With::NoIndex) {
 				return Ok(Plan::TableIterator);
@@ -33,6 +40,18 @@ impl<'a> PlanBuilder<'a> {
 		if !b.eval_node(root)? {
 			return Ok(Plan::TableIterator);
 		}
+		// A composite index is used when it covers several conditions, or when it is the only option
+		if b.all_and {
+			let best = composites
+				.into_iter()
+				.filter_map(|(e, io)| b.filter_index_option(Some(io)).map(|io| (e, io)))
+				.max_by_key(|(_, io)| io.columns());
+			if let Some((e, io)) = best {
+				if io.columns() > 1 || b.indexes.is_empty() {
+					return Ok(Plan::SingleIndex(e, io));
+				}
+			}
+		}
 		// If we didn't found any index, we're done with no index plan
 		if b.indexes.is_empty() {
 			return Ok(Plan::TableIterator);
@@ -123,7 +142,7 @@ pub(super) enum Plan {
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(crate) struct IndexOption(Arc<Inner>);
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(super) struct Inner {
 	ix: DefineIndexStatement,
 	id: Idiom,
@@ -131,6 +150,10 @@ pub(super) struct Inner {
 	qs: Option<String>,
 	op: Operator,
 	mr: Option<MatchRef>,
+	/// The bounds of the column following the values of a composite index
+	range: Option<(Bound<Value>, Bound<Value>)>,
+	/// Whether the query is answered from the values of the index, without fetching the records
+	covering: bool,
 }
 
 impl IndexOption {
@@ -149,6 +172,28 @@ impl IndexOption {
 			a,
 			qs,
 			mr,
+			range: None,
+			covering: false,
+		}))
+	}
+
+	/// Scan a composite index, whose leading columns are equal to the values,
+	/// and whose next column is within the range (if any)
+	pub(super) fn new_composite(
+		ix: DefineIndexStatement,
+		id: Idiom,
+		a: Array,
+		range: Option<(Bound<Value>, Bound<Value>)>,
+	) -> Self {
+		Self(Arc::new(Inner {
+			ix,
+			id,
+			op: Operator::Equal,
+			a,
+			qs: None,
+			mr: None,
+			range,
+			covering: false,
 		}))
 	}
 
@@ -168,26 +213,105 @@ impl IndexOption {
 		self.0.qs.as_ref()
 	}
 
-	pub(super) fn id(&self) -> &Idiom {
-		&self.0.id
-	}
-
 	pub(super) fn match_ref(&self) -> Option<&MatchRef> {
 		self.0.mr.as_ref()
 	}
 
+	pub(super) fn range(&self) -> Option<&(Bound<Value>, Bound<Value>)> {
+		self.0.range.as_ref()
+	}
+
+	/// Whether every column of the index is compared for equality
+	pub(super) fn is_exact(&self) -> bool {
+		self.0.range.is_none() && self.0.a.len() >= self.0.ix.cols.len()
+	}
+
+	/// The number of columns of the index which are constrained
+	fn columns(&self) -> usize {
+		self.0.a.len() + usize::from(self.0.range.is_some())
+	}
+
+	/// Whether the records are iterated in the order of the ORDER clause.
+	/// The columns which are compared for equality can be skipped, as they
+	/// are the same for every record.
+	pub(super) fn is_ordered_by(&self, orders: &Orders) -> bool {
+		// A collated index is ordered by its folded strings
+		if !matches!(self.ix().index, Index::Idx | Index::Uniq)
+			|| self.ix().collate.is_some()
+			|| orders.is_empty()
+		{
+			return false;
+		}
+		if orders.iter().any(|o| o.random || o.collate || o.numeric || !o.direction) {
+			return false;
+		}
+		let cols = &self.ix().cols.0;
+		(0..=self.0.a.len().min(cols.len())).any(|s| {
+			cols.len() >= s + orders.len()
+				&& orders.iter().zip(&cols[s..]).all(|(o, c)| o.order.eq(c))
+		})
+	}
+
+	/// Whether the values of the index hold every field read by a statement
+	pub(super) fn covers(&self, idioms: &[Idiom]) -> bool {
+		let cols = &self.ix().cols;
+		matches!(self.ix().index, Index::Idx | Index::Uniq)
+			&& self.ix().collate.is_none()
+			&& cols.iter().all(|c| c.iter().all(|p| matches!(p, Part::Field(_))))
+			&& idioms.iter().all(|i| i.is_id() || cols.contains(i))
+	}
+
+	pub(super) fn with_covering(self) -> Self {
+		let mut inner = Inner::clone(&self.0);
+		inner.covering = true;
+		Self(Arc::new(inner))
+	}
+
+	/// The columns of the index, when the records are built from its values
+	pub(crate) fn covering(&self) -> Option<&Idioms> {
+		self.0.covering.then_some(&self.0.ix.cols)
+	}
+
 	pub(crate) fn explain(&self) -> Value {
 		let v = if self.0.a.len() == 1 {
 			self.0.a[0].clone()
 		} else {
 			Value::Array(self.0.a.clone())
 		};
-		Value::Object(Object::from(HashMap::from([
+		let mut e = HashMap::from([
 			("index", Value::from(self.ix().name.0.to_owned())),
 			("operator", Value::from(self.op().to_string())),
 			("value", v),
-		])))
+		]);
+		if let Some((from, to)) = &self.0.range {
+			e.insert("from", explain_bound(from));
+			e.insert("to", explain_bound(to));
+		}
+		if self.0.covering {
+			e.insert("covering", Value::from(true));
+		}
+		Value::Object(Object::from(e))
 	}
+
+	/// Estimate the number of records matched by this index
+	pub(crate) fn estimate(&self) -> Value {
+		match (&self.ix().index, self.op()) {
+			(Index::Uniq, Operator::Equal | Operator::Exact) if self.is_exact() => Value::from(1),
+			_ => Value::None,
+		}
+	}
+}
+
+fn explain_bound(b: &Bound<Value>) -> Value {
+	let (value, inclusive) = match b {
+		Bound::Included(v) => (v.clone(), true),
+		Bound::Excluded(v) => (v.clone(), false),
+		Bound::Unbounded => (Value::None, false),
+	};
+	Value::Object(Object::from(HashMap::from([
+		("value", value),
+		("inclusive", Value::from(inclusive)),
+	])))
 }
 
 #[cfg(test)]
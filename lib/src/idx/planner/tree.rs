@@ -2,11 +2,15 @@ use crate::ctx::Context;
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
 use crate::idx::planner::plan::IndexOption;
+use crate::idx::trigram;
 use crate::sql::index::Index;
 use crate::sql::statements::DefineIndexStatement;
-use crate::sql::{Array, Cond, Expression, Idiom, Operator, Subquery, Table, Value};
+use crate::sql::{
+	Array, Cond, Expression, Function, Idiom, Operator, Part, Subquery, Table, Value,
+};
 use async_recursion::async_recursion;
 use std::collections::HashMap;
+use std::ops::Bound;
 use std::sync::Arc;
 
 pub(super) struct Tree {}
@@ -20,7 +24,7 @@ impl Tree {
 		txn: &'a Transaction,
 		table: &'a Table,
 		cond: &'a Option<Cond>,
-	) -> Result<Option<(Node, IndexMap)>, Error> {
+	) -> Result<Option<(Node, IndexMap, Vec<(Expression, IndexOption)>)>, Error> {
 		let mut b = TreeBuilder {
 			ctx,
 			opt,
@@ -28,15 +32,26 @@ impl Tree {
 			table,
 			indexes: None,
 			index_map: IndexMap::default(),
+			comparisons: Vec::new(),
 		};
 		let mut res = None;
 		if let Some(cond) = cond {
-			res = Some((b.eval_value(&cond.0).await?, b.index_map));
+			let node = b.eval_value(&cond.0).await?;
+			let composites = b.composite_index_options();
+			res = Some((node, b.index_map, composites));
 		}
 		Ok(res)
 	}
 }
 
+/// A field compared with a value, which may be resolved by a composite index
+struct Comparison {
+	exp: Expression,
+	id: Idiom,
+	op: Operator,
+	value: Value,
+}
+
 struct TreeBuilder<'a> {
 	ctx: &'a Context<'a>,
 	opt: &'a Options,
@@ -44,6 +59,7 @@ struct TreeBuilder<'a> {
 	table: &'a Table,
 	indexes: Option<Arc<[DefineIndexStatement]>>,
 	index_map: IndexMap,
+	comparisons: Vec<Comparison>,
 }
 
 impl<'a> TreeBuilder<'a> {
@@ -58,14 +74,26 @@ impl<'a> TreeBuilder<'a> {
 				.await?;
 			self.indexes = Some(indexes);
 		}
+		let mut search = None;
 		if let Some(indexes) = &self.indexes {
 			for ix in indexes.as_ref() {
+				// Indexes which are still being built are incomplete
+				if ix.building {
+					continue;
+				}
 				if ix.cols.len() == 1 && ix.cols[0].eq(i) {
 					return Ok(Some(ix.clone()));
 				}
+				// A full-text index over several fields matches the terms of any of them
+				if search.is_none()
+					&& matches!(ix.index, Index::Search(_))
+					&& ix.cols.iter().any(|c| c.eq(i))
+				{
+					search = Some(ix.clone());
+				}
 			}
 		}
-		Ok(None)
+		Ok(search)
 	}
 
 	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
@@ -78,7 +106,9 @@ impl<'a> TreeBuilder<'a> {
 			Value::Number(_) => Node::Scalar(v.to_owned()),
 			Value::Bool(_) => Node::Scalar(v.to_owned()),
 			Value::Thing(_) => Node::Scalar(v.to_owned()),
+			Value::Regex(_) => Node::Scalar(v.to_owned()),
 			Value::Subquery(s) => self.eval_subquery(s).await?,
+			Value::Function(f) => self.eval_function(v, f).await?,
 			Value::Param(p) => {
 				let v = p.compute(self.ctx, self.opt, self.txn, None).await?;
 				self.eval_value(&v).await?
@@ -87,6 +117,18 @@ impl<'a> TreeBuilder<'a> {
 		})
 	}
 
+	/// The vector compared by a KNN operator is an array
+	async fn eval_vector(&mut self, v: &Value) -> Result<Node, Error> {
+		let v = match v {
+			Value::Param(p) => p.compute(self.ctx, self.opt, self.txn, None).await?,
+			v => v.to_owned(),
+		};
+		Ok(match v {
+			Value::Array(_) => Node::Scalar(v),
+			_ => Node::Unsupported,
+		})
+	}
+
 	async fn eval_idiom(&mut self, i: &Idiom) -> Result<Node, Error> {
 		Ok(if let Some(ix) = self.find_index(i).await? {
 			Node::IndexedField(i.to_owned(), ix)
@@ -95,6 +137,42 @@ impl<'a> TreeBuilder<'a> {
 		})
 	}
 
+	/// A function of the fields of a record is resolved by an index computing the same function
+	async fn eval_computed(&mut self, v: &Value) -> Result<Node, Error> {
+		let i = computed(v);
+		Ok(if let Some(ix) = self.find_index(&i).await? {
+			Node::IndexedField(i, ix)
+		} else if self.is_column(&i) {
+			// The column of a composite index
+			Node::NonIndexedField
+		} else {
+			Node::Unsupported
+		})
+	}
+
+	/// `string::contains(field, value)` can be resolved like `field CONTAINS value`
+	async fn eval_function(&mut self, v: &Value, f: &Function) -> Result<Node, Error> {
+		let node = self.eval_computed(v).await?;
+		if let (Node::Unsupported, Function::Normal(name, args)) = (&node, f) {
+			if let ("string::contains", [l @ Value::Idiom(_), r]) = (name.as_str(), args.as_slice())
+			{
+				let e = Expression::Binary {
+					l: l.clone(),
+					o: Operator::Contain,
+					r: r.clone(),
+				};
+				return self.eval_expression(&e).await;
+			}
+		}
+		Ok(node)
+	}
+
+	fn is_column(&self, i: &Idiom) -> bool {
+		self.indexes.as_ref().map_or(false, |indexes| {
+			indexes.iter().any(|ix| !ix.building && ix.cols.iter().any(|c| c.eq(i)))
+		})
+	}
+
 	async fn eval_expression(&mut self, e: &Expression) -> Result<Node, Error> {
 		match e {
 			Expression::Unary {
@@ -108,7 +186,12 @@ impl<'a> TreeBuilder<'a> {
 				r,
 			} => {
 				let left = self.eval_value(l).await?;
-				let right = self.eval_value(r).await?;
+				let right = match o {
+					Operator::Knn(_) => self.eval_vector(r).await?,
+					_ => self.eval_value(r).await?,
+				};
+				self.add_comparison(e, l, o, &right, true);
+				self.add_comparison(e, r, o, &left, false);
 				if let Some(io) = self.index_map.0.get(e) {
 					return Ok(Node::Expression {
 						io: Some(io.clone()),
@@ -142,26 +225,41 @@ impl<'a> TreeBuilder<'a> {
 		e: &Expression,
 	) -> Option<IndexOption> {
 		if let Some(v) = v.is_scalar() {
+			// A regex is only matched by the trigrams of an index
+			let regex = matches!(v, Value::Regex(_));
 			let (found, mr, qs) = match &ix.index {
-				Index::Idx => (Operator::Equal.eq(op), None, None),
-				Index::Uniq => (Operator::Equal.eq(op), None, None),
+				Index::Idx => (Operator::Equal.eq(op) && !regex, None, None),
+				Index::Uniq => (Operator::Equal.eq(op) && !regex, None, None),
 				Index::Search {
 					..
 				} => {
-					if let Operator::Matches(mr) = op {
+					if let (Operator::Matches(mr) | Operator::Fuzzy(mr, _), false) = (op, regex) {
 						(true, *mr, Some(v.clone().to_raw_string()))
 					} else {
 						(false, None, None)
 					}
 				}
 				Index::MTree(_) => (false, None, None),
+				Index::Hnsw(_) => (matches!(op, Operator::Knn(_)), None, None),
+				Index::Trigram => {
+					let qs = match (op, v) {
+						(Operator::Contain, Value::Strand(s)) => Some(s.as_str().to_owned()),
+						(Operator::Equal, Value::Regex(r)) => {
+							trigram::literal_prefix(r.regex().as_str())
+						}
+						_ => None,
+					};
+					// A string shorter than a trigram can't be searched with the index
+					let qs = qs.filter(|s| !trigram::query_trigrams(s).is_empty());
+					(qs.is_some(), None, qs)
+				}
 			};
 			if found {
 				let io = IndexOption::new(
 					ix.clone(),
 					id.clone(),
 					op.to_owned(),
-					Array::from(v.clone()),
+					Array::from(fold(ix, v.clone())),
 					qs,
 					mr,
 				);
@@ -172,6 +270,102 @@ impl<'a> TreeBuilder<'a> {
 		None
 	}
 
+	/// Collect the comparisons of a field with a value, the field being on either side
+	fn add_comparison(
+		&mut self,
+		e: &Expression,
+		field: &Value,
+		op: &Operator,
+		value: &Node,
+		field_on_left: bool,
+	) {
+		let Some(value) = value.is_scalar().filter(|v| !matches!(v, Value::Regex(_))) else {
+			return;
+		};
+		let id = match field {
+			Value::Idiom(id) => id.clone(),
+			Value::Function(_) => computed(field),
+			_ => return,
+		};
+		let op = match (op, field_on_left) {
+			(Operator::Equal, _) => Operator::Equal,
+			(Operator::LessThan, true) | (Operator::MoreThan, false) => Operator::LessThan,
+			(Operator::LessThanOrEqual, true) | (Operator::MoreThanOrEqual, false) => {
+				Operator::LessThanOrEqual
+			}
+			(Operator::MoreThan, true) | (Operator::LessThan, false) => Operator::MoreThan,
+			(Operator::MoreThanOrEqual, true) | (Operator::LessThanOrEqual, false) => {
+				Operator::MoreThanOrEqual
+			}
+			_ => return,
+		};
+		self.comparisons.push(Comparison {
+			exp: e.clone(),
+			id,
+			op,
+			value: value.clone(),
+		});
+	}
+
+	/// Build an option for each index whose leading columns are compared for equality,
+	/// and whose next column may be compared with a range.
+	/// The single column equalities are already resolved as regular index options.
+	fn composite_index_options(&self) -> Vec<(Expression, IndexOption)> {
+		let mut res = Vec::new();
+		let Some(indexes) = &self.indexes else {
+			return res;
+		};
+		for ix in indexes.iter() {
+			if ix.building || !matches!(ix.index, Index::Idx | Index::Uniq) {
+				continue;
+			}
+			let mut exp = None;
+			let mut prefix = Vec::new();
+			for col in ix.cols.iter() {
+				let Some(c) =
+					self.comparisons.iter().find(|c| c.op == Operator::Equal && c.id.eq(col))
+				else {
+					break;
+				};
+				exp.get_or_insert_with(|| c.exp.clone());
+				prefix.push(fold(ix, c.value.clone()));
+			}
+			let mut range = None;
+			// The folded strings of a collated index are not ordered like the compared values
+			if let (Some(col), None) = (ix.cols.get(prefix.len()), &ix.collate) {
+				let mut from = Bound::Unbounded;
+				let mut to = Bound::Unbounded;
+				for c in self.comparisons.iter().filter(|c| c.id.eq(col)) {
+					match c.op {
+						Operator::MoreThan => from = Bound::Excluded(c.value.clone()),
+						Operator::MoreThanOrEqual => from = Bound::Included(c.value.clone()),
+						Operator::LessThan => to = Bound::Excluded(c.value.clone()),
+						Operator::LessThanOrEqual => to = Bound::Included(c.value.clone()),
+						_ => continue,
+					}
+					exp.get_or_insert_with(|| c.exp.clone());
+				}
+				if from != Bound::Unbounded || to != Bound::Unbounded {
+					range = Some((from, to));
+				}
+			}
+			// A single column compared for equality is already a regular index option
+			if ix.cols.len() == 1 && range.is_none() {
+				continue;
+			}
+			if let Some(exp) = exp {
+				let io = IndexOption::new_composite(
+					ix.clone(),
+					ix.cols[0].clone(),
+					Array::from(prefix),
+					range,
+				);
+				res.push((exp, io));
+			}
+		}
+		res
+	}
+
 	async fn eval_subquery(&mut self, s: &Subquery) -> Result<Node, Error> {
 		Ok(match s {
 			Subquery::Value(v) => self.eval_value(v).await?,
@@ -180,6 +374,19 @@ impl<'a> TreeBuilder<'a> {
 	}
 }
 
+/// The column of an index computing a value, such as `string::lowercase(email)`
+fn computed(v: &Value) -> Idiom {
+	Idiom::from(vec![Part::Start(v.clone())])
+}
+
+/// The value looked up in an index, whose strings may be folded by its collation
+fn fold(ix: &DefineIndexStatement, v: Value) -> Value {
+	match &ix.collate {
+		Some(c) => c.fold(v),
+		None => v,
+	}
+}
+
 /// For each expression the a possible index option
 #[derive(Default)]
 pub(super) struct IndexMap(HashMap<Expression, IndexOption>);
@@ -0,0 +1,702 @@
+//! Hierarchical Navigable Small World graphs, used to find the approximate nearest neighbours of a vector.
+//! Each indexed vector is a node of the graph, which is linked to its nearest neighbours on each of the
+//! layers it belongs to. The upper layers contain fewer nodes, so that a search can quickly move towards
+//! the neighbourhood of the query vector, before exploring the densely linked bottom layer.
+//! When the index is quantized, the graph is built and searched with compressed vectors, while
+//! the full precision vectors are stored apart, to re-rank the nearest candidates of a search.
+use crate::err::Error;
+use crate::idx::ft::docids::{DocId, DocIds, Resolved};
+use crate::idx::trees::quantizer::{Codebooks, Quantizer, Vector, TRAINING_VECTORS};
+use crate::idx::trees::store::TreeStoreType;
+use crate::idx::{IndexKeyBase, VersionedSerdeState};
+use crate::key::index::he::He;
+use crate::kvs::{Key, Transaction};
+use crate::sql::index::{Distance, HnswParams};
+use crate::sql::{Thing, Value};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// The highest layer a node can be inserted in
+const MAX_LEVEL: usize = 16;
+
+pub(crate) struct HnswIndex {
+	ikb: IndexKeyBase,
+	state_key: Key,
+	state: State,
+	dimension: usize,
+	distance: Distance,
+	m: usize,
+	m0: usize,
+	ef_construction: usize,
+	ml: f64,
+	quantizer: Quantizer,
+	doc_ids: DocIds,
+	// The nodes which were read or changed by this transaction
+	nodes: HashMap<DocId, Node>,
+	// The nodes which were changed by this transaction, and have to be written
+	changed: HashSet<DocId>,
+	updated: bool,
+}
+
+impl HnswIndex {
+	pub(crate) async fn new(
+		tx: &mut Transaction,
+		ikb: IndexKeyBase,
+		p: &HnswParams,
+		store_type: TreeStoreType,
+	) -> Result<Self, Error> {
+		if matches!(p.distance, Distance::Mahalanobis) {
+			return Err(Error::FeatureNotYetImplemented {
+				feature: "Mahalanobis distance in HNSW indexes",
+			});
+		}
+		let state_key = ikb.new_hs_key();
+		let state = match tx.get(state_key.clone()).await? {
+			Some(val) => State::try_from_val(val)?,
+			None => State::default(),
+		};
+		let mut quantizer = Quantizer::new(p.quantization.as_ref(), p.dimension as usize);
+		if let Some(val) = tx.get(ikb.new_hq_key()).await? {
+			quantizer.set_codebooks(Codebooks::try_from_val(val)?);
+		}
+		let doc_ids = DocIds::new(tx, ikb.clone(), p.doc_ids_order, store_type).await?;
+		let m = (p.m as usize).max(2);
+		Ok(Self {
+			ikb,
+			state_key,
+			state,
+			dimension: p.dimension as usize,
+			distance: p.distance.clone(),
+			m,
+			m0: m * 2,
+			ef_construction: (p.ef_construction as usize).max(1),
+			ml: 1.0 / (m as f64).ln(),
+			quantizer,
+			doc_ids,
+			nodes: HashMap::new(),
+			changed: HashSet::new(),
+			updated: false,
+		})
+	}
+
+	/// Add the vector of a record to the graph, replacing its previous vector
+	pub(crate) async fn index_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+		content: &[Value],
+	) -> Result<(), Error> {
+		let Some(vector) = content.first().map(|v| self.vector(v)).transpose()?.flatten() else {
+			return self.remove_document(tx, rid).await;
+		};
+		let id = match self.doc_ids.resolve_doc_id(tx, rid.into()).await? {
+			Resolved::New(id) => id,
+			Resolved::Existing(id) => {
+				self.delete(tx, id).await?;
+				id
+			}
+		};
+		self.insert(tx, id, vector).await
+	}
+
+	/// Remove the vector of a record from the graph
+	pub(crate) async fn remove_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+	) -> Result<(), Error> {
+		if let Some(id) = self.doc_ids.remove_doc(tx, rid.into()).await? {
+			self.delete(tx, id).await?;
+		}
+		Ok(())
+	}
+
+	/// Find the `k` records whose vectors are the nearest to a vector, nearest first
+	pub(crate) async fn knn(
+		&mut self,
+		tx: &mut Transaction,
+		vector: &Value,
+		k: usize,
+	) -> Result<Vec<(Thing, f64)>, Error> {
+		let Some(q) = self.vector(vector)? else {
+			return Ok(vec![]);
+		};
+		let Some(entry) = self.state.entry else {
+			return Ok(vec![]);
+		};
+		let Some(d) = self.distance_to(tx, &q, entry).await? else {
+			return Err(Error::CorruptedIndex);
+		};
+		let mut eps = vec![Candidate(d, entry)];
+		for layer in (1..=self.state.level as usize).rev() {
+			eps = self.search_layer(tx, &q, &eps, 1, layer).await?;
+		}
+		let mut found = self.search_layer(tx, &q, &eps, self.ef_construction.max(k), 0).await?;
+		// Re-rank the candidates with their full precision vectors
+		if self.quantizer.is_enabled() {
+			for c in found.iter_mut() {
+				if let Some(val) = tx.get(self.ikb.new_hv_key(c.1)).await? {
+					if let Vector::Full(v) = Vector::try_from_val(val)? {
+						c.0 = self.distance(&q, &v);
+					}
+				}
+			}
+			found.sort();
+		}
+		let mut res = Vec::with_capacity(k);
+		for Candidate(d, id) in found.into_iter().take(k) {
+			if let Some(key) = self.doc_ids.get_doc_key(tx, id).await? {
+				res.push((key.into(), d));
+			}
+		}
+		Ok(res)
+	}
+
+	pub(crate) async fn finish(&mut self, tx: &mut Transaction) -> Result<(), Error> {
+		if self.quantizer.needs_training() && self.state.count as usize >= TRAINING_VECTORS {
+			self.train(tx).await?;
+		}
+		for id in self.changed.drain() {
+			if let Some(node) = self.nodes.get(&id) {
+				tx.set(self.ikb.new_he_key(id), node.try_to_val()?).await?;
+			}
+		}
+		if self.updated {
+			tx.set(self.state_key.clone(), self.state.try_to_val()?).await?;
+		}
+		self.doc_ids.finish(tx).await
+	}
+
+	/// Extract a vector from a value, checking its dimension
+	fn vector(&self, v: &Value) -> Result<Option<Vec<f64>>, Error> {
+		let a = match v {
+			Value::None | Value::Null => return Ok(None),
+			Value::Array(a) => a,
+			v => {
+				return Err(Error::InvalidVectorValue {
+					value: v.to_string(),
+				})
+			}
+		};
+		let mut vector = Vec::with_capacity(a.len());
+		for v in a.iter() {
+			match v {
+				Value::Number(n) => vector.push(n.to_float()),
+				v => {
+					return Err(Error::InvalidVectorValue {
+						value: v.to_string(),
+					})
+				}
+			}
+		}
+		if vector.len() != self.dimension {
+			return Err(Error::InvalidVectorDimension {
+				current: vector.len(),
+				expected: self.dimension,
+			});
+		}
+		Ok(Some(vector))
+	}
+
+	async fn insert(
+		&mut self,
+		tx: &mut Transaction,
+		id: DocId,
+		vector: Vec<f64>,
+	) -> Result<(), Error> {
+		if self.quantizer.is_enabled() {
+			tx.set(self.ikb.new_hv_key(id), Vector::Full(vector.clone()).try_to_val()?).await?;
+		}
+		self.state.count += 1;
+		self.updated = true;
+		let level = self.random_level();
+		// The first node is the entry point of the graph
+		let Some(entry) = self.state.entry else {
+			self.put(id, vector, level);
+			self.state.entry = Some(id);
+			self.state.level = level as u16;
+			return Ok(());
+		};
+		let top = self.state.level as usize;
+		let Some(d) = self.distance_to(tx, &vector, entry).await? else {
+			return Err(Error::CorruptedIndex);
+		};
+		// Move towards the vector on the layers above the layer of the node
+		let mut eps = vec![Candidate(d, entry)];
+		for layer in (level + 1..=top).rev() {
+			eps = self.search_layer(tx, &vector, &eps, 1, layer).await?;
+		}
+		// Link the node to its nearest neighbours on each of its layers
+		let mut layers = vec![Vec::new(); level + 1];
+		for layer in (0..=level.min(top)).rev() {
+			let found = self.search_layer(tx, &vector, &eps, self.ef_construction, layer).await?;
+			let neighbours: Vec<DocId> = found.iter().take(self.m).map(|c| c.1).collect();
+			for n in &neighbours {
+				self.connect(tx, *n, id, &vector, layer).await?;
+			}
+			layers[layer] = neighbours;
+			eps = found;
+		}
+		self.nodes.insert(
+			id,
+			Node {
+				vector: self.quantizer.encode(vector),
+				layers,
+			},
+		);
+		self.changed.insert(id);
+		// The node becomes the entry point when it reaches a higher layer
+		if level > top {
+			self.state.entry = Some(id);
+			self.state.level = level as u16;
+		}
+		Ok(())
+	}
+
+	async fn delete(&mut self, tx: &mut Transaction, id: DocId) -> Result<(), Error> {
+		let Some(node) = self.node(tx, id).await?.cloned() else {
+			return Ok(());
+		};
+		self.nodes.remove(&id);
+		self.changed.remove(&id);
+		tx.del(self.ikb.new_he_key(id)).await?;
+		if self.quantizer.is_enabled() {
+			tx.del(self.ikb.new_hv_key(id)).await?;
+		}
+		self.state.count = self.state.count.saturating_sub(1);
+		self.updated = true;
+		// Link the neighbours of the node to each other, in place of the node
+		for (layer, links) in node.layers.iter().enumerate() {
+			for n in links {
+				let Some(origin) = self.vector_of(tx, *n).await? else {
+					continue;
+				};
+				let mut candidates = self.links(*n, layer);
+				candidates.retain(|c| *c != id);
+				for c in links {
+					if c != n && !candidates.contains(c) {
+						candidates.push(*c);
+					}
+				}
+				let links = self.nearest(tx, &origin, candidates, None, layer).await?;
+				self.set_links(*n, layer, links);
+			}
+		}
+		// Choose another entry point, from the highest layer which still has a node
+		if self.state.entry == Some(id) {
+			let mut next = None;
+			for n in node.layers.iter().rev().flatten() {
+				if let Some(neighbour) = self.node(tx, *n).await? {
+					next = Some((*n, neighbour.layers.len() - 1));
+					break;
+				}
+			}
+			if next.is_none() {
+				next = self.any_node(tx).await?;
+			}
+			self.state = match next {
+				Some((entry, level)) => State {
+					entry: Some(entry),
+					level: level as u16,
+					count: self.state.count,
+				},
+				None => State::default(),
+			};
+		}
+		Ok(())
+	}
+
+	/// Add a link from a node to another node, keeping only the nearest links when there are too many
+	async fn connect(
+		&mut self,
+		tx: &mut Transaction,
+		from: DocId,
+		to: DocId,
+		vector: &[f64],
+		layer: usize,
+	) -> Result<(), Error> {
+		let Some(origin) = self.vector_of(tx, from).await? else {
+			return Ok(());
+		};
+		let mut links = self.links(from, layer);
+		if links.contains(&to) {
+			return Ok(());
+		}
+		links.push(to);
+		if links.len() > self.max_links(layer) {
+			links = self.nearest(tx, &origin, links, Some((to, vector)), layer).await?;
+		}
+		self.set_links(from, layer, links);
+		Ok(())
+	}
+
+	/// Keep the nodes which are the nearest to a vector, up to the number of links allowed on a layer
+	async fn nearest(
+		&mut self,
+		tx: &mut Transaction,
+		origin: &[f64],
+		candidates: Vec<DocId>,
+		pending: Option<(DocId, &[f64])>,
+		layer: usize,
+	) -> Result<Vec<DocId>, Error> {
+		let mut scored = Vec::with_capacity(candidates.len());
+		for c in candidates {
+			let d = match pending {
+				// The node being inserted is not stored yet
+				Some((id, vector)) if id == c => Some(self.distance(origin, vector)),
+				_ => self.distance_to(tx, origin, c).await?,
+			};
+			if let Some(d) = d {
+				scored.push(Candidate(d, c));
+			}
+		}
+		scored.sort();
+		Ok(scored.into_iter().take(self.max_links(layer)).map(|c| c.1).collect())
+	}
+
+	/// Search a layer for the nodes nearest to a vector, starting from the entry points, nearest first
+	async fn search_layer(
+		&mut self,
+		tx: &mut Transaction,
+		q: &[f64],
+		eps: &[Candidate],
+		ef: usize,
+		layer: usize,
+	) -> Result<Vec<Candidate>, Error> {
+		let mut visited: HashSet<DocId> = eps.iter().map(|c| c.1).collect();
+		let mut candidates: BinaryHeap<Reverse<Candidate>> =
+			eps.iter().copied().map(Reverse).collect();
+		let mut found: BinaryHeap<Candidate> = eps.iter().copied().collect();
+		while let Some(Reverse(c)) = candidates.pop() {
+			// Stop once the nearest candidate is further than every node found
+			if found.len() >= ef && found.peek().is_some_and(|f| c.0 > f.0) {
+				break;
+			}
+			if self.node(tx, c.1).await?.is_none() {
+				continue;
+			}
+			let links = self.links(c.1, layer);
+			for n in links {
+				if !visited.insert(n) {
+					continue;
+				}
+				let Some(d) = self.distance_to(tx, q, n).await? else {
+					continue;
+				};
+				if found.len() < ef || found.peek().is_some_and(|f| d < f.0) {
+					candidates.push(Reverse(Candidate(d, n)));
+					found.push(Candidate(d, n));
+					if found.len() > ef {
+						found.pop();
+					}
+				}
+			}
+		}
+		Ok(found.into_sorted_vec())
+	}
+
+	async fn node(&mut self, tx: &mut Transaction, id: DocId) -> Result<Option<&Node>, Error> {
+		if !self.nodes.contains_key(&id) {
+			match tx.get(self.ikb.new_he_key(id)).await? {
+				Some(val) => {
+					self.nodes.insert(id, Node::try_from_val(val)?);
+				}
+				None => return Ok(None),
+			}
+		}
+		Ok(self.nodes.get(&id))
+	}
+
+	/// Find any node of the graph, with its highest layer
+	async fn any_node(&mut self, tx: &mut Transaction) -> Result<Option<(DocId, usize)>, Error> {
+		let beg = self.ikb.new_he_key(0);
+		let end = self.ikb.new_he_key(DocId::MAX);
+		if let Some((key, val)) = tx.scan(beg..end, 1).await?.pop() {
+			let id = He::decode(&key)?.doc_id;
+			let node = Node::try_from_val(val)?;
+			let level = node.layers.len() - 1;
+			self.nodes.insert(id, node);
+			return Ok(Some((id, level)));
+		}
+		Ok(None)
+	}
+
+	/// The links of a cached node on a layer
+	fn links(&self, id: DocId, layer: usize) -> Vec<DocId> {
+		self.nodes.get(&id).and_then(|n| n.layers.get(layer).cloned()).unwrap_or_default()
+	}
+
+	/// The vector of a node, as held by the graph
+	async fn vector_of(
+		&mut self,
+		tx: &mut Transaction,
+		id: DocId,
+	) -> Result<Option<Vec<f64>>, Error> {
+		if self.node(tx, id).await?.is_none() {
+			return Ok(None);
+		}
+		match self.nodes.get(&id) {
+			Some(n) => Ok(Some(self.quantizer.decode(&n.vector)?.into_owned())),
+			None => Ok(None),
+		}
+	}
+
+	async fn distance_to(
+		&mut self,
+		tx: &mut Transaction,
+		q: &[f64],
+		id: DocId,
+	) -> Result<Option<f64>, Error> {
+		if self.node(tx, id).await?.is_none() {
+			return Ok(None);
+		}
+		match self.nodes.get(&id) {
+			Some(n) => Ok(Some(self.distance(q, &self.quantizer.decode(&n.vector)?))),
+			None => Ok(None),
+		}
+	}
+
+	/// Learn the centroids of product quantization, and compress the vectors already in the graph
+	async fn train(&mut self, tx: &mut Transaction) -> Result<(), Error> {
+		let beg = self.ikb.new_hv_key(0);
+		let end = self.ikb.new_hv_key(DocId::MAX);
+		let mut vectors = Vec::with_capacity(TRAINING_VECTORS);
+		for (_, val) in tx.scan(beg..end, TRAINING_VECTORS as u32).await? {
+			if let Vector::Full(v) = Vector::try_from_val(val)? {
+				vectors.push(v);
+			}
+		}
+		let Some(codebooks) = self.quantizer.train(&vectors) else {
+			return Ok(());
+		};
+		tx.set(self.ikb.new_hq_key(), codebooks.try_to_val()?).await?;
+		for (id, node) in self.nodes.iter_mut() {
+			if let Vector::Full(v) = &node.vector {
+				node.vector = self.quantizer.encode(v.clone());
+				self.changed.insert(*id);
+			}
+		}
+		// The nodes which are not cached are compressed in batches
+		let mut beg = self.ikb.new_he_key(0);
+		let end = self.ikb.new_he_key(DocId::MAX);
+		loop {
+			let res = tx.scan(beg.clone()..end.clone(), 1000).await?;
+			let Some((key, _)) = res.last() else {
+				break;
+			};
+			beg = key.clone();
+			beg.push(0x00);
+			for (key, val) in res {
+				let id = He::decode(&key)?.doc_id;
+				if self.nodes.contains_key(&id) {
+					continue;
+				}
+				let mut node = Node::try_from_val(val)?;
+				if let Vector::Full(v) = node.vector {
+					node.vector = self.quantizer.encode(v);
+					tx.set(key, node.try_to_val()?).await?;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn distance(&self, a: &[f64], b: &[f64]) -> f64 {
+		compute_distance(&self.distance, a, b)
+	}
+
+	fn put(&mut self, id: DocId, vector: Vec<f64>, level: usize) {
+		self.nodes.insert(
+			id,
+			Node {
+				vector: self.quantizer.encode(vector),
+				layers: vec![Vec::new(); level + 1],
+			},
+		);
+		self.changed.insert(id);
+	}
+
+	fn set_links(&mut self, id: DocId, layer: usize, links: Vec<DocId>) {
+		if let Some(node) = self.nodes.get_mut(&id) {
+			if let Some(current) = node.layers.get_mut(layer) {
+				*current = links;
+				self.changed.insert(id);
+			}
+		}
+	}
+
+	fn max_links(&self, layer: usize) -> usize {
+		match layer {
+			0 => self.m0,
+			_ => self.m,
+		}
+	}
+
+	fn random_level(&self) -> usize {
+		let r = 1.0 - rand::random::<f64>();
+		((-r.ln() * self.ml).floor() as usize).min(MAX_LEVEL)
+	}
+}
+
+fn compute_distance(distance: &Distance, a: &[f64], b: &[f64]) -> f64 {
+	let pairs = a.iter().zip(b.iter());
+	match distance {
+		Distance::Euclidean => pairs.map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt(),
+		Distance::Manhattan => pairs.map(|(a, b)| (a - b).abs()).sum(),
+		Distance::Cosine => {
+			let (mut dot, mut na, mut nb) = (0.0, 0.0, 0.0);
+			for (a, b) in pairs {
+				dot += a * b;
+				na += a * a;
+				nb += b * b;
+			}
+			match na * nb {
+				n if n > 0.0 => 1.0 - dot / n.sqrt(),
+				_ => 1.0,
+			}
+		}
+		Distance::Hamming => pairs.filter(|(a, b)| a != b).count() as f64,
+		Distance::Minkowski(order) => {
+			let p = order.to_float();
+			pairs.map(|(a, b)| (a - b).abs().powf(p)).sum::<f64>().powf(1.0 / p)
+		}
+		// Rejected when the index is opened
+		Distance::Mahalanobis => f64::NAN,
+	}
+}
+
+/// A node, and its distance to the vector being searched for
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Candidate(f64, DocId);
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Candidate {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+	}
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+struct State {
+	/// The node from which searches start, on the highest layer
+	entry: Option<DocId>,
+	/// The highest layer of the graph
+	level: u16,
+	/// The number of nodes in the graph
+	count: u64,
+}
+
+impl VersionedSerdeState for State {}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+struct Node {
+	vector: Vector,
+	/// The neighbours of the node, on each layer from the bottom layer up to the highest layer of the node
+	layers: Vec<Vec<DocId>>,
+}
+
+impl VersionedSerdeState for Node {}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::trees::hnsw::HnswIndex;
+	use crate::idx::trees::store::TreeStoreType;
+	use crate::idx::IndexKeyBase;
+	use crate::kvs::Datastore;
+	use crate::sql::index::{Distance, HnswParams, Quantization};
+	use crate::sql::{Array, Thing, Value};
+	use test_log::test;
+
+	fn vector(v: &[f64]) -> Value {
+		Value::Array(Array::from(v.iter().map(|v| Value::from(*v)).collect::<Vec<_>>()))
+	}
+
+	/// Index the points of a grid, and find the nearest neighbours of one of them, which is removed
+	async fn check_knn(params: HnswParams, width: usize, height: usize) -> HnswIndex {
+		let ds = Datastore::new("memory").await.unwrap();
+		{
+			let mut tx = ds.transaction(true, false).await.unwrap();
+			let mut hnsw =
+				HnswIndex::new(&mut tx, IndexKeyBase::default(), &params, TreeStoreType::Write)
+					.await
+					.unwrap();
+			for i in 0..width * height {
+				let rid: Thing = ("t", i.to_string().as_str()).into();
+				let v = vector(&[(i % width) as f64, (i / width) as f64]);
+				hnsw.index_document(&mut tx, &rid, &[v]).await.unwrap();
+			}
+			// Remove the exact match of the query
+			let rid: Thing = ("t", (5 * width + 5).to_string().as_str()).into();
+			hnsw.remove_document(&mut tx, &rid).await.unwrap();
+			hnsw.finish(&mut tx).await.unwrap();
+			tx.commit().await.unwrap();
+		}
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let mut hnsw =
+			HnswIndex::new(&mut tx, IndexKeyBase::default(), &params, TreeStoreType::Read)
+				.await
+				.unwrap();
+		let res = hnsw.knn(&mut tx, &vector(&[5.0, 5.0]), 4).await.unwrap();
+		let mut ids: Vec<usize> =
+			res.iter().map(|(rid, _)| rid.id.to_raw().parse().unwrap()).collect();
+		ids.sort();
+		assert_eq!(ids, vec![4 * width + 5, 5 * width + 4, 5 * width + 6, 6 * width + 5]);
+		assert!(res.iter().all(|(_, d)| *d == 1.0));
+		// A vector with another dimension is rejected
+		hnsw.knn(&mut tx, &vector(&[5.0]), 4).await.unwrap_err();
+		tx.cancel().await.unwrap();
+		hnsw
+	}
+
+	#[test(tokio::test)]
+	async fn knn_finds_the_nearest_vectors() {
+		let params = HnswParams {
+			dimension: 2,
+			distance: Distance::Euclidean,
+			m: 4,
+			ef_construction: 16,
+			doc_ids_order: 100,
+			quantization: None,
+		};
+		check_knn(params, 10, 10).await;
+	}
+
+	#[test(tokio::test)]
+	async fn knn_with_scalar_quantization() {
+		let params = HnswParams {
+			dimension: 2,
+			distance: Distance::Euclidean,
+			m: 4,
+			ef_construction: 16,
+			doc_ids_order: 100,
+			quantization: Some(Quantization::Scalar),
+		};
+		check_knn(params, 10, 10).await;
+	}
+
+	#[test(tokio::test)]
+	async fn knn_with_product_quantization() {
+		let params = HnswParams {
+			dimension: 2,
+			distance: Distance::Euclidean,
+			m: 8,
+			ef_construction: 64,
+			doc_ids_order: 100,
+			quantization: Some(Quantization::Product(2)),
+		};
+		// The centroids are learnt once the index holds enough vectors
+		let hnsw = check_knn(params, 40, 30).await;
+		assert!(!hnsw.quantizer.needs_training());
+	}
+}
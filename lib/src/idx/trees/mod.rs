@@ -1,3 +1,5 @@
 pub mod bkeys;
 pub mod btree;
+pub mod hnsw;
+mod quantizer;
 pub mod store;
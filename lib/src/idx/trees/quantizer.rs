@@ -0,0 +1,288 @@
+//! Compresses the vectors held by a vector index, to reduce its memory footprint.
+//! Scalar quantization stores each component of a vector as a byte, between the smallest
+//! and the largest components of the vector. Product quantization splits each vector into
+//! subvectors, and stores the index of the nearest of up to 256 centroids for each of them.
+//! The centroids are learnt with k-means, once the index holds enough vectors.
+use crate::err::Error;
+use crate::idx::VersionedSerdeState;
+use crate::sql::index::Quantization;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// The number of vectors an index holds when the centroids of product quantization are learnt
+pub(super) const TRAINING_VECTORS: usize = 1024;
+
+/// The largest number of centroids of each subvector, so that a centroid is identified by a byte
+const CENTROIDS: usize = 256;
+
+/// The number of iterations of k-means
+const ITERATIONS: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(super) enum Vector {
+	/// A vector in full precision
+	Full(Vec<f64>),
+	/// Each component is `min + code * scale`
+	Scalar {
+		min: f64,
+		scale: f64,
+		codes: Vec<u8>,
+	},
+	/// The centroid of each subvector
+	Product(Vec<u8>),
+}
+
+impl VersionedSerdeState for Vector {}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(super) struct Codebooks {
+	/// The centroids of each subvector
+	centroids: Vec<Vec<Vec<f64>>>,
+}
+
+impl VersionedSerdeState for Codebooks {}
+
+pub(super) enum Quantizer {
+	None,
+	Scalar,
+	Product {
+		dimension: usize,
+		subvectors: usize,
+		codebooks: Option<Codebooks>,
+	},
+}
+
+impl Quantizer {
+	pub(super) fn new(quantization: Option<&Quantization>, dimension: usize) -> Self {
+		match quantization {
+			None => Self::None,
+			Some(Quantization::Scalar) => Self::Scalar,
+			Some(Quantization::Product(subvectors)) => Self::Product {
+				dimension,
+				subvectors: (*subvectors as usize).clamp(1, dimension.max(1)),
+				codebooks: None,
+			},
+		}
+	}
+
+	/// Whether the vectors are compressed, in which case their full precision is stored apart
+	pub(super) fn is_enabled(&self) -> bool {
+		!matches!(self, Self::None)
+	}
+
+	/// Whether the centroids have to be learnt before the vectors can be compressed
+	pub(super) fn needs_training(&self) -> bool {
+		matches!(
+			self,
+			Self::Product {
+				codebooks: None,
+				..
+			}
+		)
+	}
+
+	pub(super) fn set_codebooks(&mut self, c: Codebooks) {
+		if let Self::Product {
+			codebooks,
+			..
+		} = self
+		{
+			*codebooks = Some(c);
+		}
+	}
+
+	/// Learn the centroids of each subvector, returning them so that they can be stored
+	pub(super) fn train(&mut self, vectors: &[Vec<f64>]) -> Option<Codebooks> {
+		let Self::Product {
+			dimension,
+			subvectors,
+			codebooks,
+		} = self
+		else {
+			return None;
+		};
+		if vectors.is_empty() {
+			return None;
+		}
+		let centroids = ranges(*dimension, *subvectors)
+			.map(|r| {
+				let points: Vec<&[f64]> = vectors.iter().map(|v| &v[r.clone()]).collect();
+				kmeans(&points, CENTROIDS.min(points.len()))
+			})
+			.collect();
+		let c = Codebooks {
+			centroids,
+		};
+		*codebooks = Some(c.clone());
+		Some(c)
+	}
+
+	/// Compress a vector
+	pub(super) fn encode(&self, vector: Vec<f64>) -> Vector {
+		match self {
+			Self::None => Vector::Full(vector),
+			Self::Scalar => {
+				let min = vector.iter().copied().fold(f64::INFINITY, f64::min);
+				let max = vector.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+				let scale = match max > min {
+					true => (max - min) / u8::MAX as f64,
+					false => 0.0,
+				};
+				let codes = vector
+					.iter()
+					.map(|x| match scale > 0.0 {
+						true => ((x - min) / scale).round() as u8,
+						false => 0,
+					})
+					.collect();
+				Vector::Scalar {
+					min,
+					scale,
+					codes,
+				}
+			}
+			Self::Product {
+				dimension,
+				subvectors,
+				codebooks: Some(c),
+			} => Vector::Product(
+				ranges(*dimension, *subvectors)
+					.zip(c.centroids.iter())
+					.map(|(r, centroids)| nearest(&vector[r], centroids) as u8)
+					.collect(),
+			),
+			// The vectors are kept in full precision until the centroids are learnt
+			Self::Product {
+				codebooks: None,
+				..
+			} => Vector::Full(vector),
+		}
+	}
+
+	/// Approximate the full precision of a compressed vector
+	pub(super) fn decode<'a>(&self, vector: &'a Vector) -> Result<Cow<'a, [f64]>, Error> {
+		Ok(match vector {
+			Vector::Full(v) => Cow::Borrowed(v),
+			Vector::Scalar {
+				min,
+				scale,
+				codes,
+			} => Cow::Owned(codes.iter().map(|c| min + *c as f64 * scale).collect()),
+			Vector::Product(codes) => {
+				let Self::Product {
+					codebooks: Some(c),
+					..
+				} = self
+				else {
+					return Err(Error::CorruptedIndex);
+				};
+				let mut v = Vec::new();
+				for (code, centroids) in codes.iter().zip(c.centroids.iter()) {
+					let Some(centroid) = centroids.get(*code as usize) else {
+						return Err(Error::CorruptedIndex);
+					};
+					v.extend_from_slice(centroid);
+				}
+				Cow::Owned(v)
+			}
+		})
+	}
+}
+
+/// The components covered by each subvector
+fn ranges(dimension: usize, subvectors: usize) -> impl Iterator<Item = Range<usize>> {
+	(0..subvectors).map(move |i| i * dimension / subvectors..(i + 1) * dimension / subvectors)
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+	a.iter().zip(b.iter()).map(|(a, b)| (a - b).powi(2)).sum()
+}
+
+fn nearest(point: &[f64], centroids: &[Vec<f64>]) -> usize {
+	let mut best = (0, f64::INFINITY);
+	for (i, c) in centroids.iter().enumerate() {
+		let d = squared_distance(point, c);
+		if d < best.1 {
+			best = (i, d);
+		}
+	}
+	best.0
+}
+
+/// Group points into up to `k` clusters, returning the centroid of each cluster
+fn kmeans(points: &[&[f64]], k: usize) -> Vec<Vec<f64>> {
+	// Start from distinct points of the sample
+	let mut centroids: Vec<Vec<f64>> = Vec::with_capacity(k);
+	for p in points {
+		if centroids.len() == k {
+			break;
+		}
+		if !centroids.iter().any(|c| c.as_slice() == *p) {
+			centroids.push(p.to_vec());
+		}
+	}
+	for _ in 0..ITERATIONS {
+		let mut sums = vec![vec![0.0; centroids[0].len()]; centroids.len()];
+		let mut counts = vec![0usize; centroids.len()];
+		for p in points {
+			let c = nearest(p, &centroids);
+			counts[c] += 1;
+			for (s, x) in sums[c].iter_mut().zip(p.iter()) {
+				*s += x;
+			}
+		}
+		// A centroid without any point is kept as it is
+		for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+			if count > 0 {
+				*centroid = sum.into_iter().map(|s| s / count as f64).collect();
+			}
+		}
+	}
+	centroids
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::trees::quantizer::{Quantizer, Vector};
+	use crate::sql::index::Quantization;
+
+	#[test]
+	fn scalar_quantization() {
+		let q = Quantizer::new(Some(&Quantization::Scalar), 4);
+		let v = q.encode(vec![-1.0, 0.0, 0.5, 1.0]);
+		let Vector::Scalar {
+			codes,
+			..
+		} = &v
+		else {
+			panic!("{v:?}");
+		};
+		assert_eq!(codes.len(), 4);
+		let d = q.decode(&v).unwrap();
+		for (a, b) in d.iter().zip([-1.0, 0.0, 0.5, 1.0]) {
+			assert!((a - b).abs() < 0.01, "{a} {b}");
+		}
+		// A vector whose components are all equal
+		let v = q.encode(vec![3.0; 4]);
+		assert_eq!(q.decode(&v).unwrap().as_ref(), &[3.0; 4]);
+	}
+
+	#[test]
+	fn product_quantization() {
+		let mut q = Quantizer::new(Some(&Quantization::Product(2)), 4);
+		assert!(q.needs_training());
+		// Before training, vectors are kept in full precision
+		assert_eq!(q.encode(vec![1.0; 4]), Vector::Full(vec![1.0; 4]));
+		let vectors: Vec<Vec<f64>> =
+			(0..100).map(|i| vec![(i % 2) as f64, (i % 2) as f64, (i % 5) as f64, 0.0]).collect();
+		q.train(&vectors).unwrap();
+		assert!(!q.needs_training());
+		let v = q.encode(vec![1.0, 1.0, 4.0, 0.0]);
+		assert!(matches!(&v, Vector::Product(codes) if codes.len() == 2));
+		assert_eq!(q.decode(&v).unwrap().as_ref(), &[1.0, 1.0, 4.0, 0.0]);
+	}
+}
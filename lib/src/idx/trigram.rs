@@ -0,0 +1,75 @@
+//! The trigrams of strings, which resolve substring and regex searches.
+//! A string is indexed under each of the distinct sequences of three characters it holds.
+//! A substring can only be found within the strings holding every one of its trigrams,
+//! so the records found through the index are then filtered by the condition.
+use crate::sql::Value;
+use std::collections::BTreeSet;
+
+/// Collect the distinct trigrams of the strings of a value
+pub(crate) fn trigrams(v: &Value, res: &mut BTreeSet<String>) {
+	match v {
+		Value::Strand(s) => {
+			let chars: Vec<char> = s.chars().collect();
+			res.extend(chars.windows(3).map(|w| w.iter().collect()));
+		}
+		Value::Array(a) => a.iter().for_each(|v| trigrams(v, res)),
+		_ => {}
+	}
+}
+
+/// The trigrams of a searched string, which is too short to use the index without any
+pub(crate) fn query_trigrams(s: &str) -> Vec<String> {
+	let mut res = BTreeSet::new();
+	trigrams(&Value::from(s), &mut res);
+	res.into_iter().collect()
+}
+
+/// The characters every string matched by a regex starts with,
+/// when the regex is anchored at the start of the strings
+pub(crate) fn literal_prefix(re: &str) -> Option<String> {
+	let mut chars = re.strip_prefix('^')?.chars().peekable();
+	// The alternatives of the regex may not start with the same characters
+	if re.contains('|') {
+		return None;
+	}
+	let mut prefix = String::new();
+	while let Some(c) = chars.next() {
+		let c = match c {
+			'\\' => match chars.next() {
+				Some(e) if e.is_ascii_punctuation() => e,
+				_ => break,
+			},
+			'.' | '[' | ']' | '(' | ')' | '{' | '}' | '*' | '+' | '?' | '^' | '$' => break,
+			c => c,
+		};
+		// A character which may not be repeated at least once is optional
+		if matches!(chars.peek(), Some('*' | '?' | '{')) {
+			break;
+		}
+		prefix.push(c);
+	}
+	Some(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::trigram::{literal_prefix, query_trigrams};
+
+	#[test]
+	fn test_query_trigrams() {
+		assert_eq!(query_trigrams("hello"), vec!["ell", "hel", "llo"]);
+		assert_eq!(query_trigrams("ééé"), vec!["ééé"]);
+		assert!(query_trigrams("hi").is_empty());
+	}
+
+	#[test]
+	fn test_literal_prefix() {
+		assert_eq!(literal_prefix("^hello.*"), Some("hello".to_string()));
+		assert_eq!(literal_prefix(r"^v1\.2\d"), Some("v1.2".to_string()));
+		assert_eq!(literal_prefix("^abcd?e"), Some("abc".to_string()));
+		assert_eq!(literal_prefix("^ab+c"), Some("ab".to_string()));
+		assert_eq!(literal_prefix("^(?i)abc"), Some("".to_string()));
+		assert_eq!(literal_prefix("^abc|def"), None);
+		assert_eq!(literal_prefix("abc"), None);
+	}
+}
@@ -0,0 +1,80 @@
+//! Stores a chunk of a large record document
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Chunk<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	pub id: Id,
+	pub nr: u32,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, id: &Id, nr: u32) -> Chunk<'a> {
+	Chunk::new(ns, db, tb, id.to_owned(), nr)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	Chunk::new(ns, db, tb, id.to_owned(), 0).encode().unwrap()
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Chunk::new(ns, db, tb, id.to_owned(), u32::MAX).encode().unwrap();
+	k.push(0xff);
+	k
+}
+
+impl<'a> Chunk<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, id: Id, nr: u32) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'&',
+			id,
+			nr,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Chunk::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testid".into(),
+			3,
+		);
+		let enc = Chunk::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0&\0\0\0\x01testid\0\0\0\0\x03");
+
+		let dec = Chunk::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let id = Id::from("testid");
+		let beg = prefix("testns", "testdb", "testtb", &id);
+		let end = suffix("testns", "testdb", "testtb", &id);
+		let key = new("testns", "testdb", "testtb", &id, 7).encode().unwrap();
+		assert!(beg <= key && key < end);
+	}
+}
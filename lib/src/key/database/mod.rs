@@ -1,9 +1,12 @@
 pub mod all;
 pub mod az;
+pub mod co;
 pub mod fc;
 pub mod lg;
 pub mod pa;
 pub mod sc;
+pub mod sq;
+pub mod sv;
 pub mod tb;
 pub mod ti;
 pub mod tk;
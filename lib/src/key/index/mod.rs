@@ -11,13 +11,19 @@ pub mod bp;
 pub mod bs;
 pub mod bt;
 pub mod bu;
+pub mod bw;
+pub mod he;
+pub mod hq;
+pub mod hs;
+pub mod hv;
 
 use crate::sql::array::Array;
 use crate::sql::id::Id;
+use crate::sql::value::Value;
 use derive::Key;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
-use std::ops::Range;
+use std::ops::{Bound, Range};
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
 struct Prefix<'a> {
@@ -139,6 +145,52 @@ impl<'a> Index<'a> {
 		end.extend_from_slice(&[0xff]);
 		(beg, end)
 	}
+
+	/// The keys whose leading fields are `fd`, and whose next field is within the bounds
+	pub fn range_composite(
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+		fd: &Array,
+		from: Bound<&Value>,
+		to: Bound<&Value>,
+	) -> (Vec<u8>, Vec<u8>) {
+		let fields = |v: Option<&Value>| {
+			let mut fd = fd.clone();
+			if let Some(v) = v {
+				fd.push(v.clone());
+			}
+			// The fields of an array are followed by a terminator, which is removed
+			// so that the key is a prefix of the keys of the arrays starting with these fields
+			let mut k = PrefixIds::new(ns, db, tb, ix, &fd).encode().unwrap();
+			k.pop();
+			k
+		};
+		let beg = match from {
+			Bound::Included(v) => fields(Some(v)),
+			Bound::Excluded(v) => {
+				let mut k = fields(Some(v));
+				k.push(0xff);
+				k
+			}
+			Bound::Unbounded => fields(None),
+		};
+		let end = match to {
+			Bound::Included(v) => {
+				let mut k = fields(Some(v));
+				k.push(0xff);
+				k
+			}
+			Bound::Excluded(v) => fields(Some(v)),
+			Bound::Unbounded => {
+				let mut k = fields(None);
+				k.push(0xff);
+				k
+			}
+		};
+		(beg, end)
+	}
 }
 
 #[cfg(test)]
@@ -159,4 +211,40 @@ mod tests {
 		let dec = Index::decode(&enc).unwrap();
 		assert_eq!(val, dec);
 	}
+
+	#[test]
+	fn range_composite() {
+		use super::*;
+		let key = |a: i64, b: i64| {
+			let fd = vec![Value::from(a), Value::from(b)].into();
+			let id = "id".into();
+			Index::new("ns", "db", "tb", "ix", &fd, Some(&id)).encode().unwrap()
+		};
+		let fd = vec![Value::from(1)].into();
+		let v5 = Value::from(5);
+		let (beg, end) = Index::range_composite(
+			"ns",
+			"db",
+			"tb",
+			"ix",
+			&fd,
+			Bound::Excluded(&v5),
+			Bound::Unbounded,
+		);
+		assert!(key(1, 5) < beg);
+		assert!(beg <= key(1, 6) && key(1, 6) < end);
+		assert!(end <= key(2, 0));
+		let (beg, end) = Index::range_composite(
+			"ns",
+			"db",
+			"tb",
+			"ix",
+			&fd,
+			Bound::Unbounded,
+			Bound::Included(&v5),
+		);
+		assert!(key(0, 9) < beg);
+		assert!(beg <= key(1, 5) && key(1, 5) < end);
+		assert!(end <= key(1, 6));
+	}
 }
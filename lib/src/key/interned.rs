@@ -0,0 +1,203 @@
+//! Stores the interned key prefix for all keys under a table
+//!
+//! Keys under a table repeat the names of the namespace, database, and table
+//! in every key. Tables which are created with a numeric id store their keys
+//! under the ids instead, so `/*{ns}\0*{db}\0*{tb}\0*{id}` is stored as
+//! `/+{ns id}*{db id}*{tb id}*{id}`. The names are swapped for the ids by the
+//! transaction, so that keys are always encoded and decoded using the names.
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+/// The length of an interned table prefix
+pub const LEN: usize = 16;
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Interned {
+	__: u8,
+	_a: u8,
+	pub ns: u32,
+	_b: u8,
+	pub db: u32,
+	_c: u8,
+	pub tb: u32,
+}
+
+pub fn new(ns: u32, db: u32, tb: u32) -> Interned {
+	Interned::new(ns, db, tb)
+}
+
+/// The prefix of the interned tables of a namespace
+pub fn namespace(ns: u32) -> Vec<u8> {
+	let mut k = vec![b'/', b'+'];
+	k.extend_from_slice(&ns.to_be_bytes());
+	k.push(b'*');
+	k
+}
+
+/// The prefix of the interned tables of a database
+pub fn database(ns: u32, db: u32) -> Vec<u8> {
+	let mut k = namespace(ns);
+	k.extend_from_slice(&db.to_be_bytes());
+	k.push(b'*');
+	k
+}
+
+/// The range of the keys of every interned table
+pub fn range() -> (Vec<u8>, Vec<u8>) {
+	(vec![b'/', b'+'], vec![b'/', b'+' + 1])
+}
+
+impl Interned {
+	pub fn new(ns: u32, db: u32, tb: u32) -> Self {
+		Self {
+			__: b'/',
+			_a: b'+',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+		}
+	}
+}
+
+/// Returns the length of the interned table prefix of a key
+pub fn interned(key: &[u8]) -> Option<usize> {
+	match key.len() >= LEN && key.starts_with(b"/+") && key[6] == b'*' && key[11] == b'*' {
+		true => Some(LEN),
+		false => None,
+	}
+}
+
+/// Returns the length of the `/*{ns}\0*{db}\0*{tb}\0` table prefix of a key
+pub fn named(key: &[u8]) -> Option<usize> {
+	if key.first() != Some(&b'/') {
+		return None;
+	}
+	let db = section(key, 1)?;
+	let tb = section(key, db)?;
+	section(key, tb)
+}
+
+/// Returns the namespace, database, and table names of a table prefix
+pub fn names(prefix: &[u8]) -> Option<(String, String, String)> {
+	let mut names = prefix[1..].split(|v| *v == 0).map(|v| v.get(1..).map(String::from_utf8_lossy));
+	let ns = names.next()??.into_owned();
+	let db = names.next()??.into_owned();
+	let tb = names.next()??.into_owned();
+	Some((ns, db, tb))
+}
+
+/// Check whether a range may hold the keys of more than a single table. A range
+/// within a single section of the keys, which does not hold tables, such as the
+/// definitions of a database, can not hold the keys of any interned table.
+pub fn spans_tables(beg: &[u8], end: &[u8]) -> bool {
+	if beg.first() != Some(&b'/') || end.first() != Some(&b'/') {
+		return true;
+	}
+	// Descend through the namespace and database which the range is within
+	let mut pos = 1;
+	for level in 0..3 {
+		match (beg.get(pos), end.get(pos)) {
+			(Some(b'*'), Some(b'*')) if level < 2 => match (section(beg, pos), section(end, pos)) {
+				(Some(a), Some(b)) if beg[..a] == end[..b] => pos = a,
+				_ => return true,
+			},
+			// The range is entirely before or after the sections which hold tables
+			(Some(a), Some(b)) if (*a < b'*' && *b < b'*') || (*a > b'*' && *b > b'*') => {
+				return false
+			}
+			_ => return true,
+		}
+	}
+	true
+}
+
+/// Check whether a key is the definition of a namespace, database,
+/// or table, which the interned prefix of a table depends on
+pub fn catalog(key: &[u8]) -> bool {
+	if key.starts_with(b"/!ns") {
+		return true;
+	}
+	if key.first() != Some(&b'/') {
+		return false;
+	}
+	let Some(db) = section(key, 1) else {
+		return false;
+	};
+	if key[db..].starts_with(b"!db") {
+		return true;
+	}
+	match section(key, db) {
+		Some(tb) => key[tb..].starts_with(b"!tb"),
+		None => false,
+	}
+}
+
+/// Returns the end of a null-terminated `*` section starting at the position
+fn section(key: &[u8], pos: usize) -> Option<usize> {
+	match key.get(pos) {
+		Some(b'*') => key[pos + 1..].iter().position(|v| *v == 0).map(|v| pos + v + 2),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Interned::new(
+			1,
+			2,
+			3,
+		);
+		let enc = Interned::encode(&val).unwrap();
+		assert_eq!(enc, b"/+\0\0\0\x01*\0\0\0\x02*\0\0\0\x03");
+		assert_eq!(interned(&enc), Some(LEN));
+		assert!(enc.starts_with(&database(1, 2)));
+		assert!(enc.starts_with(&namespace(1)));
+
+		let dec = Interned::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn table_prefix() {
+		use super::*;
+		assert_eq!(named(b"/*ns\0*db\0*tb\0*id"), Some(13));
+		assert_eq!(named(b"/*ns\0*db\0*tb\0"), Some(13));
+		assert_eq!(named(b"/*ns\0*db\0!tbtb\0"), None);
+		assert_eq!(named(b"/!nsns\0"), None);
+		assert_eq!(interned(&crate::key::database::ti::new(1, 2).encode().unwrap()), None);
+		assert_eq!(interned(&crate::key::namespace::di::new(1).encode().unwrap()), None);
+		let names = names(b"/*ns\0*db\0*tb\0").unwrap();
+		assert_eq!(names, ("ns".to_owned(), "db".to_owned(), "tb".to_owned()));
+	}
+
+	#[test]
+	fn table_ranges() {
+		use super::*;
+		assert!(spans_tables(b"", b"\xff"));
+		assert!(spans_tables(b"/*ns\0", b"/*ns\0\xff"));
+		assert!(spans_tables(b"/*ns\0*db\0", b"/*ns\0*db\0\xff"));
+		assert!(spans_tables(b"/*ns\0*db\0*", b"/*ns\0*db\0*\xff"));
+		assert!(!spans_tables(b"/!ns", b"/!ns\xff"));
+		assert!(!spans_tables(b"/*ns\0!db", b"/*ns\0!db\xff"));
+		assert!(!spans_tables(b"/*ns\0*db\0!tb", b"/*ns\0*db\0!tb\xff"));
+		let (beg, end) = range();
+		assert!(!spans_tables(&beg, &end));
+	}
+
+	#[test]
+	fn catalog_keys() {
+		use super::*;
+		assert!(catalog(b"/!nsns\0"));
+		assert!(catalog(b"/*ns\0!dbdb\0"));
+		assert!(catalog(b"/*ns\0*db\0!tbtb\0"));
+		assert!(!catalog(b"/*ns\0*db\0!fnfc\0"));
+		assert!(!catalog(b"/*ns\0*db\0*tb\0*id"));
+		assert!(!catalog(b"/!nd"));
+	}
+}
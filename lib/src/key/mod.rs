@@ -2,6 +2,7 @@
 ///
 /// crate::key::root::all                /
 /// crate::key::root::hb                 /!hb{ts}/{nd}
+/// crate::key::root::ib                 /!ib
 /// crate::key::root::nd                 /!nd{nd}
 /// crate::key::root::ni                 /!ni
 /// crate::key::root::ns                 /!ns{ns}
@@ -17,10 +18,13 @@
 ///
 /// crate::key::database::all            /*{ns}*{db}
 /// crate::key::database::az             /*{ns}*{db}!az{az}
+/// crate::key::database::co             /*{ns}*{db}!co{co}
 /// crate::key::database::fc             /*{ns}*{db}!fn{fc}
 /// crate::key::database::lg             /*{ns}*{db}!lg{lg}
 /// crate::key::database::pa             /*{ns}*{db}!pa{pa}
 /// crate::key::database::sc             /*{ns}*{db}!sc{sc}
+/// crate::key::database::sq             /*{ns}*{db}!sq{sq}
+/// crate::key::database::sv             /*{ns}*{db}!sv{sq}
 /// crate::key::database::tb             /*{ns}*{db}!tb{tb}
 /// crate::key::database::ti             /+{ns id}*{db id}!ti
 /// crate::key::database::tk             /*{ns}*{db}!tk{tk}
@@ -31,11 +35,16 @@
 /// crate::key::scope::tk                /*{ns}*{db}±{sc}!tk{tk}
 ///
 /// crate::key::table::all               /*{ns}*{db}*{tb}
+/// crate::key::table::co                /*{ns}*{db}*{tb}!co{co}
+/// crate::key::table::dr                /*{ns}*{db}*{tb}!dr{fd}
+/// crate::key::table::eq                /*{ns}*{db}*{tb}!eq{ts}{id}
 /// crate::key::table::ev                /*{ns}*{db}*{tb}!ev{ev}
+/// crate::key::table::ex                /*{ns}*{db}*{tb}!ex{id}
 /// crate::key::table::fd                /*{ns}*{db}*{tb}!fd{fd}
 /// crate::key::table::ft                /*{ns}*{db}*{tb}!ft{ft}
 /// crate::key::table::ix                /*{ns}*{db}*{tb}!ix{ix}
 /// crate::key::table::lq                /*{ns}*{db}*{tb}!lq{lq}
+/// crate::key::table::rf                /*{ns}*{db}*{tb}!rf
 ///
 /// crate::key::index::all               /*{ns}*{db}*{tb}+{ix}
 /// crate::key::index::bc                /*{ns}*{db}*{tb}+{ix}!bc{id}
@@ -49,19 +58,30 @@
 /// crate::key::index::bs                /*{ns}*{db}*{tb}+{ix}!bs
 /// crate::key::index::bt                /*{ns}*{db}*{tb}+{ix}!bt{id}
 /// crate::key::index::bu                /*{ns}*{db}*{tb}+{ix}!bu{id}
+/// crate::key::index::bw                /*{ns}*{db}*{tb}+{ix}!bw{id}
+/// crate::key::index::he                /*{ns}*{db}*{tb}+{ix}!he{id}
+/// crate::key::index::hq                /*{ns}*{db}*{tb}+{ix}!hq
+/// crate::key::index::hs                /*{ns}*{db}*{tb}+{ix}!hs
+/// crate::key::index::hv                /*{ns}*{db}*{tb}+{ix}!hv{id}
 /// crate::key::index                    /*{ns}*{db}*{tb}+{ix}*{fd}{id}
 ///
 /// crate::key::change                   /*{ns}*{db}#{ts}
 ///
 /// crate::key::thing                    /*{ns}*{db}*{tb}*{id}
 ///
+/// crate::key::chunk                    /*{ns}*{db}*{tb}&{id}{nr}
+///
 /// crate::key::graph                    /*{ns}*{db}*{tb}~{id}{eg}{fk}
 ///
+/// crate::key::interned                 /+{ns id}*{db id}*{tb id}
+///
 pub mod change;
+pub mod chunk;
 pub mod database;
 pub mod debug;
 pub mod graph;
 pub mod index;
+pub mod interned;
 pub mod namespace;
 pub mod node;
 pub mod root;
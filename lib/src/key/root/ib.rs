@@ -0,0 +1,40 @@
+//! Stores the lease of the node which builds the indexes in the background
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ib {
+	__: u8,
+	_a: u8,
+	_b: u8,
+	_c: u8,
+}
+
+impl Default for Ib {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Ib {
+	pub fn new() -> Self {
+		Self {
+			__: b'/',
+			_a: b'!',
+			_b: b'i',
+			_c: b'b',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		let val = Ib::new();
+		let enc = Ib::encode(&val).unwrap();
+		let dec = Ib::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
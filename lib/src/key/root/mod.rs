@@ -1,5 +1,6 @@
 pub mod all;
 pub mod hb;
+pub mod ib;
 pub mod nd;
 pub mod ni;
 pub mod ns;
@@ -0,0 +1,63 @@
+//! Stores the offset of a table change feed consumer
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+/// Co is used to store how far a consumer has read the change feed of a table.
+///
+/// The value of the co is the versionstamp of the last change which the consumer has read.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Co<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub co: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, co: &'a str) -> Co<'a> {
+	Co::new(ns, db, tb, co)
+}
+
+impl<'a> Co<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, co: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'c',
+			_f: b'o',
+			co,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Co::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testco",
+		);
+		let enc = Co::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!cotestco\0");
+
+		let dec = Co::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,89 @@
+//! Stores a field which was dropped, and whose data is yet to be removed from the records
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+/// Dr is set by `ALTER TABLE ... DROP FIELD`, and is removed once the background
+/// task has removed the field data from every record of the table, or once a field
+/// which overlaps the dropped field is defined again.
+///
+/// The value of the dr is the dropped field, as an idiom value.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Dr<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub fd: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, fd: &'a str) -> Dr<'a> {
+	Dr::new(ns, db, tb, fd)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'd', b'r', 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'd', b'r', 0xff]);
+	k
+}
+
+impl<'a> Dr<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, fd: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'd',
+			_f: b'r',
+			fd,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Dr::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testfd",
+		);
+		let enc = Dr::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\x00*testdb\x00*testtb\x00!drtestfd\x00");
+
+		let dec = Dr::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn test_prefix() {
+		let val = super::prefix("testns", "testdb", "testtb");
+		assert_eq!(val, b"/*testns\0*testdb\0*testtb\0!dr\0");
+	}
+
+	#[test]
+	fn test_suffix() {
+		let val = super::suffix("testns", "testdb", "testtb");
+		assert_eq!(val, b"/*testns\0*testdb\0*testtb\0!dr\xff");
+	}
+}
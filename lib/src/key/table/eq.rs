@@ -0,0 +1,86 @@
+//! Stores the queue of records which expire
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+/// Eq orders the records of a table with a TTL by their expiry, so that they can be purged in order.
+///
+/// The expiry is in milliseconds since the unix epoch. The value of the eq is empty.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Eq<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub ts: u64,
+	pub id: Id,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, ts: u64, id: &Id) -> Eq<'a> {
+	Eq::new(ns, db, tb, ts, id.to_owned())
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'e', b'q']);
+	k
+}
+
+/// Returns the key before which all the records which expire before `ts` are queued
+pub fn until(ns: &str, db: &str, tb: &str, ts: u64) -> Vec<u8> {
+	let mut k = prefix(ns, db, tb);
+	k.extend_from_slice(&ts.to_be_bytes());
+	k
+}
+
+impl<'a> Eq<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ts: u64, id: Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'e',
+			_f: b'q',
+			ts,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Eq::new(
+			"testns",
+			"testdb",
+			"testtb",
+			7,
+			"testid".into(),
+		);
+		let enc = Eq::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!eq\0\0\0\0\0\0\0\x07\0\0\0\x01testid\0");
+
+		let dec = Eq::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn until() {
+		let val = super::until("testns", "testdb", "testtb", 7);
+		assert_eq!(val, b"/*testns\0*testdb\0*testtb\0!eq\0\0\0\0\0\0\0\x07");
+	}
+}
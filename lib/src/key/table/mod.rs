@@ -1,6 +1,11 @@
 pub mod all;
+pub mod co;
+pub mod dr;
+pub mod eq;
 pub mod ev;
+pub mod ex;
 pub mod fd;
 pub mod ft;
 pub mod ix;
 pub mod lq;
+pub mod rf;
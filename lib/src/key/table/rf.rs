@@ -0,0 +1,61 @@
+//! Stores the time at which a scheduled view was last refreshed
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+/// Rf is used to share when a view which is refreshed on an interval was
+/// last refreshed, so that only one node refreshes the view each interval.
+///
+/// The value of the rf is the time of the refresh, in seconds since the unix epoch, as big-endian bytes.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Rf<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str) -> Rf<'a> {
+	Rf::new(ns, db, tb)
+}
+
+impl<'a> Rf<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'r',
+			_f: b'f',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Rf::new(
+			"testns",
+			"testdb",
+			"testtb",
+		);
+		let enc = Rf::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!rf");
+
+		let dec = Rf::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,348 @@
+#![cfg(all(feature = "archive", not(target_arch = "wasm32")))]
+
+use crate::cnf::ARCHIVE_INCREMENTAL_BACKUPS;
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::kvs::BackupInfo;
+use crate::kvs::Datastore;
+use crate::sql::Ident;
+use futures::lock::Mutex;
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The extension of the objects which contain a full backup
+const FULL: &str = "full.surql";
+/// The extension of the objects which contain an incremental backup
+const INCREMENTAL: &str = "incremental.surql";
+
+/// Ships backups of every database to S3-compatible, or Google Cloud
+/// Storage, object storage, so that a datastore can be recovered from
+/// the bucket alone. The backups of each database are stored under the
+/// `{prefix}/{ns}/{db}` path, as a full backup followed by incremental
+/// backups of the changes made since then, and are named after their
+/// position in that sequence, and the versionstamp at which they were
+/// taken. Tables which are not defined with a `CHANGEFEED` can not be
+/// backed up incrementally, so databases containing such tables are
+/// shipped as full backups every time.
+pub(crate) struct Archive {
+	/// The remote object storage bucket
+	remote: Arc<dyn ObjectStore>,
+	/// The path prefix under which the backups are stored
+	prefix: Path,
+	/// How often the backups are shipped
+	interval: Duration,
+	/// The backups which have been shipped
+	state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+	/// The time at which the backups were last shipped
+	shipped: Option<u64>,
+	/// The latest backup of each database
+	latest: HashMap<(String, String), Latest>,
+}
+
+/// The latest backup which was shipped for a database
+#[derive(Clone, Copy)]
+struct Latest {
+	/// The position of the backup in the sequence of backups
+	sequence: u64,
+	/// The versionstamp at which the backup was taken
+	versionstamp: u64,
+	/// How many incremental backups followed the last full backup
+	incrementals: u32,
+}
+
+impl Archive {
+	/// Connect to the object storage bucket specified in
+	/// an `s3://bucket/prefix` or `gcs://bucket/prefix` path,
+	/// using the credentials configured in the environment
+	pub(crate) fn new(path: &str, interval: Duration) -> Result<Archive, Error> {
+		let (remote, rest): (Arc<dyn ObjectStore>, &str) = match path {
+			s if s.starts_with("s3:") => {
+				let s = s.trim_start_matches("s3://");
+				let s = s.trim_start_matches("s3:");
+				let (bucket, rest) = s.split_once('/').unwrap_or((s, ""));
+				let remote = AmazonS3Builder::from_env().with_bucket_name(bucket).build()?;
+				(Arc::new(remote), rest)
+			}
+			s if s.starts_with("gcs:") => {
+				let s = s.trim_start_matches("gcs://");
+				let s = s.trim_start_matches("gcs:");
+				let (bucket, rest) = s.split_once('/').unwrap_or((s, ""));
+				let remote =
+					GoogleCloudStorageBuilder::from_env().with_bucket_name(bucket).build()?;
+				(Arc::new(remote), rest)
+			}
+			_ => return Err(Error::Ds("Unable to load the specified archive".into())),
+		};
+		Ok(Self::open(remote, Path::from(rest), interval))
+	}
+	/// Open an archive stored under a path prefix
+	fn open(remote: Arc<dyn ObjectStore>, prefix: Path, interval: Duration) -> Archive {
+		Archive {
+			remote,
+			prefix,
+			interval,
+			state: Mutex::new(State::default()),
+		}
+	}
+	/// Ship the backups if they have not been shipped within the interval
+	pub(crate) async fn ship_on_schedule(&self, ds: &Datastore, ts: u64) -> Result<(), Error> {
+		let due = {
+			let mut state = self.state.lock().await;
+			match state.shipped {
+				Some(v) if ts < v.saturating_add(self.interval.as_secs()) => false,
+				_ => {
+					state.shipped = Some(ts);
+					true
+				}
+			}
+		};
+		if due {
+			self.ship(ds).await?;
+		}
+		Ok(())
+	}
+	/// Ship a backup of every database
+	pub(crate) async fn ship(&self, ds: &Datastore) -> Result<(), Error> {
+		// Find the databases to back up
+		let mut dbs = Vec::new();
+		let mut tx = ds.transaction(false, false).await?;
+		for ns in tx.all_ns().await?.iter() {
+			for db in tx.all_db(ns.name.as_str()).await?.iter() {
+				dbs.push((ns.name.to_raw(), db.name.to_raw()));
+			}
+		}
+		tx.cancel().await?;
+		// Back up each database in turn
+		let mut state = self.state.lock().await;
+		for (ns, db) in dbs {
+			if let Err(e) = self.ship_db(ds, &mut state, &ns, &db).await {
+				warn!("Failed to archive the database {ns}/{db}: {e}");
+			}
+		}
+		Ok(())
+	}
+	/// Ship a backup of a database, which is incremental when possible
+	async fn ship_db(
+		&self,
+		ds: &Datastore,
+		state: &mut State,
+		ns: &str,
+		db: &str,
+	) -> Result<(), Error> {
+		let key = (ns.to_owned(), db.to_owned());
+		let dir = self.prefix.child(ns).child(db);
+		// Continue from the backups which were shipped before a restart
+		let latest = match state.latest.get(&key) {
+			Some(v) => Some(*v),
+			None => self.latest(&dir).await?,
+		};
+		let sess = Session::owner();
+		// Back up the changes since the latest backup
+		if let Some(latest) = latest.filter(|v| v.incrementals < *ARCHIVE_INCREMENTAL_BACKUPS) {
+			let (snd, rcv) = channel::unbounded();
+			let res = match ds
+				.backup_since(&sess, ns.to_owned(), db.to_owned(), latest.versionstamp, snd)
+				.await
+			{
+				Ok(backup) => backup.await,
+				Err(e) => Err(e),
+			};
+			match res {
+				Ok(()) => {
+					let sequence = latest.sequence + 1;
+					let versionstamp = self.put(&dir, sequence, INCREMENTAL, rcv).await?;
+					state.latest.insert(
+						key,
+						Latest {
+							sequence,
+							versionstamp,
+							incrementals: latest.incrementals + 1,
+						},
+					);
+					return Ok(());
+				}
+				// Fall back to a full backup
+				Err(Error::BackupChangefeedRequired {
+					..
+				})
+				| Err(Error::BackupExpired {
+					..
+				}) => (),
+				Err(e) => return Err(e),
+			}
+		}
+		// Back up the whole database
+		let (snd, rcv) = channel::unbounded();
+		ds.backup(&sess, ns.to_owned(), Some(db.to_owned()), snd).await?.await?;
+		let sequence = latest.map_or(0, |v| v.sequence + 1);
+		let versionstamp = self.put(&dir, sequence, FULL, rcv).await?;
+		state.latest.insert(
+			key,
+			Latest {
+				sequence,
+				versionstamp,
+				incrementals: 0,
+			},
+		);
+		Ok(())
+	}
+	/// Upload a backup, returning the versionstamp at which it was taken
+	async fn put(
+		&self,
+		dir: &Path,
+		sequence: u64,
+		kind: &str,
+		rcv: channel::Receiver<Vec<u8>>,
+	) -> Result<u64, Error> {
+		let mut data = Vec::new();
+		while let Ok(v) = rcv.try_recv() {
+			data.extend(v);
+		}
+		let info = BackupInfo::read(&String::from_utf8_lossy(&data))
+			.ok_or_else(|| Error::Internal("the backup has no versionstamp".to_string()))?;
+		let path = dir.child(format!("{sequence:020}.{}.{kind}", info.versionstamp));
+		self.remote.put(&path, data.into()).await?;
+		Ok(info.versionstamp)
+	}
+	/// Find the latest backup which was shipped for a database
+	async fn latest(&self, dir: &Path) -> Result<Option<Latest>, Error> {
+		let mut latest: Option<Latest> = None;
+		for (sequence, versionstamp, kind) in
+			list(&*self.remote, dir).await?.iter().filter_map(parse)
+		{
+			latest = Some(Latest {
+				sequence,
+				versionstamp,
+				incrementals: match (kind, latest) {
+					(INCREMENTAL, Some(v)) => v.incrementals + 1,
+					_ => 0,
+				},
+			});
+		}
+		Ok(latest)
+	}
+	/// Restore every database from its latest full backup, and the incremental backups which followed it
+	pub(crate) async fn restore(&self, ds: &Datastore) -> Result<(), Error> {
+		// Group the backups by database
+		let mut dbs: BTreeMap<(String, String), Vec<(Path, &str)>> = BTreeMap::new();
+		for path in list(&*self.remote, &self.prefix).await? {
+			let Some(parts) = path.prefix_match(&self.prefix) else {
+				continue;
+			};
+			let parts: Vec<_> = parts.map(|v| v.as_ref().to_owned()).collect();
+			if let [ns, db, _] = parts.as_slice() {
+				if let Some((_, _, kind)) = parse(&path) {
+					dbs.entry((ns.clone(), db.clone())).or_default().push((path, kind));
+				}
+			}
+		}
+		for ((ns, db), paths) in dbs {
+			// Only the backups since the latest full backup are needed
+			let Some(first) = paths.iter().rposition(|(_, kind)| *kind == FULL) else {
+				warn!("Unable to restore the database {ns}/{db}, as the archive has no full backup of it");
+				continue;
+			};
+			let mut backups = Vec::new();
+			for (path, _) in &paths[first..] {
+				let data = self.remote.get(path).await?.bytes().await?;
+				backups.push(String::from_utf8_lossy(&data).into_owned());
+			}
+			// Define the namespace and database before they are restored
+			let sql = format!(
+				"DEFINE NAMESPACE {ns}; USE NS {ns}; DEFINE DATABASE {db};",
+				ns = Ident::from(ns.as_str()),
+				db = Ident::from(db.as_str())
+			);
+			for res in ds.execute(&sql, &Session::owner(), None).await? {
+				res.result?;
+			}
+			let sess = Session::owner().with_ns(&ns).with_db(&db);
+			ds.restore(backups.as_slice(), &sess).await?;
+		}
+		Ok(())
+	}
+}
+
+/// List the objects under a path prefix, in name order
+async fn list(remote: &dyn ObjectStore, prefix: &Path) -> Result<Vec<Path>, Error> {
+	let mut res: Vec<Path> =
+		remote.list(Some(prefix)).await?.map_ok(|v| v.location).try_collect().await?;
+	res.sort();
+	Ok(res)
+}
+
+/// Parse the sequence, the versionstamp, and the kind of a backup from its name
+fn parse(path: &Path) -> Option<(u64, u64, &'static str)> {
+	let mut parts = path.filename()?.splitn(3, '.');
+	let sequence = parts.next()?.parse().ok()?;
+	let versionstamp = parts.next()?.parse().ok()?;
+	let kind = match parts.next()? {
+		FULL => FULL,
+		INCREMENTAL => INCREMENTAL,
+		_ => return None,
+	};
+	Some((sequence, versionstamp, kind))
+}
+
+#[cfg(all(test, feature = "kv-mem"))]
+mod tests {
+	use super::*;
+	use crate::sql::Value;
+	use object_store::memory::InMemory;
+
+	async fn execute(ds: &Datastore, sql: &str) -> Vec<Value> {
+		let sess = Session::owner().with_ns("test").with_db("test");
+		let res = ds.execute(sql, &sess, None).await.unwrap();
+		res.into_iter().map(|v| v.result.unwrap()).collect()
+	}
+
+	#[tokio::test]
+	async fn archived_databases_are_restored() {
+		let remote: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+		let archive = Archive::open(remote.clone(), Path::from("test"), Duration::ZERO);
+		let ds = Datastore::new("memory").await.unwrap();
+		execute(&ds, "DEFINE TABLE person CHANGEFEED 1h; CREATE person:tobie;").await;
+		archive.ship(&ds).await.unwrap();
+		execute(&ds, "CREATE person:jaime; DELETE person:tobie;").await;
+		archive.ship(&ds).await.unwrap();
+		// The changes were shipped as an incremental backup
+		let paths = list(&*remote, &Path::from("test/test/test")).await.unwrap();
+		let kinds: Vec<_> = paths.iter().filter_map(parse).map(|(_, _, kind)| kind).collect();
+		assert_eq!(kinds, vec![FULL, INCREMENTAL]);
+		// Restore the archive into a new datastore
+		let ds = Datastore::new("memory").await.unwrap();
+		archive.restore(&ds).await.unwrap();
+		let res = execute(&ds, "SELECT * FROM person").await;
+		assert_eq!(res, vec![Value::parse("[{ id: person:jaime }]")]);
+	}
+
+	#[tokio::test]
+	async fn tables_without_changefeeds_are_archived_in_full() {
+		let remote: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+		let archive = Archive::open(remote.clone(), Path::from("test"), Duration::ZERO);
+		let ds = Datastore::new("memory").await.unwrap();
+		execute(&ds, "CREATE person:tobie;").await;
+		archive.ship(&ds).await.unwrap();
+		execute(&ds, "CREATE person:jaime;").await;
+		archive.ship(&ds).await.unwrap();
+		let paths = list(&*remote, &Path::from("test/test/test")).await.unwrap();
+		let kinds: Vec<_> = paths.iter().filter_map(parse).map(|(_, _, kind)| kind).collect();
+		assert_eq!(kinds, vec![FULL, FULL]);
+		// Only the latest full backup is restored
+		let ds = Datastore::new("memory").await.unwrap();
+		archive.restore(&ds).await.unwrap();
+		let res = execute(&ds, "SELECT * FROM person").await;
+		assert_eq!(res, vec![Value::parse("[{ id: person:jaime }, { id: person:tobie }]")]);
+	}
+}
@@ -9,6 +9,7 @@ use crate::sql::statements::DefineIndexStatement;
 use crate::sql::statements::DefineNamespaceStatement;
 use crate::sql::statements::DefineParamStatement;
 use crate::sql::statements::DefineScopeStatement;
+use crate::sql::statements::DefineSequenceStatement;
 use crate::sql::statements::DefineTableStatement;
 use crate::sql::statements::DefineTokenStatement;
 use crate::sql::statements::DefineUserStatement;
@@ -41,13 +42,14 @@ pub enum Entry {
 	Nus(Arc<[DefineUserStatement]>),
 	Pas(Arc<[DefineParamStatement]>),
 	Scs(Arc<[DefineScopeStatement]>),
+	Sqs(Arc<[DefineSequenceStatement]>),
 	Sts(Arc<[DefineTokenStatement]>),
 	Tbs(Arc<[DefineTableStatement]>),
 	// Sequences
 	Seq(U32),
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Cache(pub HashMap<Key, Entry>);
 
 impl Cache {
@@ -67,4 +69,9 @@ impl Cache {
 	pub fn clear(&mut self) {
 		self.0.clear()
 	}
+	/// Clears all cached definitions, keeping any id
+	/// sequences, as these are only stored on commit
+	pub fn clear_definitions(&mut self) {
+		self.0.retain(|_, v| matches!(v, Entry::Seq(_)))
+	}
 }
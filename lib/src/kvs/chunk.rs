@@ -0,0 +1,192 @@
+//! Splits the values of large records into chunks, which are stored under
+//! separate keys next to the record, so that a single large value does not
+//! exceed the value size limit of the storage engine. The record key stores
+//! a small header in place of the value, which records how many chunks the
+//! value was split into, and which is replaced with the joined chunks when
+//! the record is read, or which is read one batch of chunks at a time with a
+//! [`ValueReader`].
+use crate::err::Error;
+use crate::key::interned;
+use crate::kvs::{Key, Transaction, Val};
+use std::ops::Range;
+
+/// The number of chunks of a large value which are fetched in each request
+const CHUNK_BATCH_SIZE: u32 = 16;
+
+/// The bytes with which the header of a chunked value starts. Record values
+/// start with their revision number, so they never start with these bytes.
+const MAGIC: &[u8] = b"\xff\x00chunked\x00";
+
+/// The header which is stored in place of a value split into chunks
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) struct Header {
+	/// The number of chunks which the value was split into
+	pub(super) count: u32,
+	/// The length of the value once the chunks are joined
+	pub(super) len: u64,
+}
+
+impl Header {
+	/// Returns the header of a value which needs to be split into chunks
+	pub(super) fn new(key: &[u8], val: &[u8], size: usize) -> Option<Self> {
+		match size > 0 && val.len() > size && record(key).is_some() {
+			true => Some(Self {
+				count: ((val.len() + size - 1) / size) as u32,
+				len: val.len() as u64,
+			}),
+			false => None,
+		}
+	}
+	/// Returns the header of a value, if it has been split into chunks
+	pub(super) fn read(key: &[u8], val: &[u8]) -> Option<Self> {
+		let rest = val.strip_prefix(MAGIC)?;
+		if rest.len() != 12 || record(key).is_none() {
+			return None;
+		}
+		Some(Self {
+			count: u32::from_be_bytes(rest[..4].try_into().ok()?),
+			len: u64::from_be_bytes(rest[4..].try_into().ok()?),
+		})
+	}
+	/// Encodes the header, so that it can be stored in place of the value
+	pub(super) fn encode(&self) -> Val {
+		[MAGIC, self.count.to_be_bytes().as_slice(), self.len.to_be_bytes().as_slice()].concat()
+	}
+}
+
+/// Reads the value of a record one part at a time. The chunks of a large
+/// value are fetched in batches, so that the whole value does not need to
+/// be held in memory, while any other value is read as a single part.
+pub struct ValueReader {
+	/// The key of the record
+	key: Key,
+	/// The header of the value, if it has been split into chunks
+	head: Option<Header>,
+	/// The value, if it has not been split into chunks
+	val: Option<Val>,
+	/// The number of the next chunk to read
+	next: u32,
+	/// The number of bytes of the chunks read so far
+	read: u64,
+}
+
+impl ValueReader {
+	pub(super) fn new(key: Key, val: Val) -> Self {
+		let head = Header::read(&key, &val);
+		Self {
+			key,
+			val: head.is_none().then_some(val),
+			head,
+			next: 0,
+			read: 0,
+		}
+	}
+	/// Returns the length of the whole value
+	pub fn len(&self) -> u64 {
+		match (&self.head, &self.val) {
+			(Some(head), _) => head.len,
+			(None, Some(val)) => val.len() as u64,
+			(None, None) => self.read,
+		}
+	}
+	/// Returns whether the whole value is empty
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+	/// Reads the next part of the value, checking that no chunks are
+	/// missing, or returns `None` once the whole value has been read
+	pub async fn next(&mut self, tx: &mut Transaction) -> Result<Option<Val>, Error> {
+		let Some(head) = self.head else {
+			let val = self.val.take();
+			self.read += val.as_ref().map_or(0, |v| v.len() as u64);
+			return Ok(val);
+		};
+		if self.next >= head.count {
+			return Ok(None);
+		}
+		let rng = key(&self.key, self.next)..key(&self.key, head.count);
+		let batch = tx.fetch_range(rng, CHUNK_BATCH_SIZE).await?;
+		if batch.is_empty() {
+			return Err(Error::CorruptedChunks);
+		}
+		let mut out = Vec::new();
+		for (k, v) in batch {
+			if record_of(&k).map(|(_, nr)| nr) != Some(self.next) {
+				return Err(Error::CorruptedChunks);
+			}
+			self.next += 1;
+			self.read += v.len() as u64;
+			out.extend(v);
+		}
+		if self.next == head.count && self.read != head.len {
+			return Err(Error::CorruptedChunks);
+		}
+		Ok(Some(out))
+	}
+}
+
+/// Returns the key of a chunk of the value of a record
+pub(super) fn key(key: &[u8], nr: u32) -> Key {
+	let mut k = key.to_vec();
+	if let Some(len) = record(key) {
+		k[len] = b'&';
+	}
+	k.extend_from_slice(&nr.to_be_bytes());
+	k
+}
+
+/// Returns the key range of any chunks of the value of a record
+pub(super) fn range(key: &[u8]) -> Option<Range<Key>> {
+	record(key)?;
+	Some(self::key(key, 0)..[self::key(key, u32::MAX).as_slice(), &[0xff]].concat())
+}
+
+/// Returns the record key, and the chunk number, of the key of a chunk
+pub(super) fn record_of(key: &[u8]) -> Option<(Key, u32)> {
+	let len = interned::named(key)?;
+	if key.get(len) != Some(&b'&') || key.len() < len + 5 {
+		return None;
+	}
+	let (rid, nr) = key.split_at(key.len() - 4);
+	let mut rid = rid.to_vec();
+	rid[len] = b'*';
+	Some((rid, u32::from_be_bytes(nr.try_into().ok()?)))
+}
+
+/// Returns the length of the table prefix of a record key
+fn record(key: &[u8]) -> Option<usize> {
+	interned::named(key).filter(|len| key.get(*len) == Some(&b'*'))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::Id;
+
+	#[test]
+	fn chunk_keys() {
+		let id = Id::from("test");
+		let rid: Key = crate::key::thing::new("ns", "db", "tb", &id).into();
+		let enc: Key = crate::key::chunk::new("ns", "db", "tb", &id, 3).into();
+		assert_eq!(key(&rid, 3), enc);
+		assert_eq!(record_of(&enc), Some((rid.clone(), 3)));
+		assert_eq!(record_of(&rid), None);
+		let rng = range(&rid).unwrap();
+		assert!(rng.start <= key(&rid, 1) && key(&rid, 1) < rng.end);
+		assert!(rng.start <= key(&rid, u32::MAX) && key(&rid, u32::MAX) < rng.end);
+		assert_eq!(range(b"/*ns\0*db\0!tbtb\0"), None);
+	}
+
+	#[test]
+	fn split() {
+		let rid: Key = crate::key::thing::new("ns", "db", "tb", &Id::from("test")).into();
+		let val: Val = (0..250u8).collect();
+		// Only record values larger than the chunk size are split
+		assert_eq!(Header::new(&rid, &val, 1000), None);
+		assert_eq!(Header::new(b"/*ns\0*db\0!tbtb\0", &val, 100), None);
+		let head = Header::new(&rid, &val, 100).unwrap();
+		assert_eq!(head.count, 3);
+		assert_eq!(Header::read(&rid, &head.encode()), Some(head));
+		assert_eq!(Header::read(&rid, &val), None);
+	}
+}
@@ -0,0 +1,236 @@
+//! Encrypts the values persisted by the RocksDB and SpeeDB storage engines
+//! using AES-256-GCM. Each encrypted value is prefixed with an identifier of
+//! the key it was encrypted with, followed by a random nonce. Values are always
+//! encrypted with the current key, but can be decrypted with any previous key
+//! which has been specified, so that the encryption key can be rotated, with
+//! existing values being re-encrypted as they are next written, or in batches
+//! in the background until no value is encrypted with a previous key.
+//!
+//! The key under which a value is stored is authenticated along with the value,
+//! so that an encrypted value can not be moved to another key without this being
+//! detected when it is read. The keys themselves are not encrypted, as they need
+//! to be ordered for range scans. This means that the names of namespaces,
+//! databases, and tables, the record ids, and the field values of index entries
+//! are stored in plaintext. Fields holding sensitive data should not be indexed,
+//! unless the storage is also encrypted at the filesystem level.
+//!
+//! A store which was persisted before encryption was enabled can be migrated by
+//! allowing unencrypted values to be read. These values are then encrypted in the
+//! same way as values which were encrypted with a previous key.
+#![cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use sha2::{Digest, Sha256};
+
+/// The length of the key identifier prepended to encrypted values
+const ID_LEN: usize = 4;
+
+/// The length of the nonce prepended to encrypted values
+const NONCE_LEN: usize = 12;
+
+#[derive(Clone)]
+pub(super) struct Cipher {
+	/// The key used for encryption, followed by any previous keys
+	keys: Vec<([u8; ID_LEN], Aes256Gcm)>,
+	/// Whether values which were persisted without encryption can be read
+	unencrypted: bool,
+}
+
+impl Cipher {
+	/// Create a cipher which encrypts values with the specified key
+	pub(super) fn new(key: &[u8; 32]) -> Self {
+		Self {
+			keys: vec![(identify(key), Aes256Gcm::new(key.into()))],
+			unencrypted: false,
+		}
+	}
+	/// Allow values encrypted with a previous key to be decrypted
+	pub(super) fn add_previous_key(&mut self, key: &[u8; 32]) {
+		let id = identify(key);
+		if !self.keys.iter().any(|(v, _)| v == &id) {
+			self.keys.push((id, Aes256Gcm::new(key.into())));
+		}
+	}
+	/// Allow values which were persisted before encryption was enabled to be read
+	pub(super) fn allow_unencrypted(&mut self) {
+		self.unencrypted = true;
+	}
+	/// Encrypt a value before it is persisted under the key
+	pub(super) fn seal(&self, key: &[u8], val: &[u8]) -> Result<Val, Error> {
+		let (id, cipher) = &self.keys[0];
+		let nonce: [u8; NONCE_LEN] = rand::random();
+		let payload = Payload {
+			msg: val,
+			aad: key,
+		};
+		let data = cipher
+			.encrypt(Nonce::from_slice(&nonce), payload)
+			.map_err(|_| Error::Ds("Unable to encrypt the persisted data".to_owned()))?;
+		Ok([id.as_slice(), nonce.as_slice(), data.as_slice()].concat())
+	}
+	/// Whether values may still be encrypted with a previous key, or not encrypted
+	pub(super) fn has_previous_keys(&self) -> bool {
+		self.keys.len() > 1 || self.unencrypted
+	}
+	/// Encrypt a value with the current key, if it was encrypted with a previous
+	/// key, or if it was not encrypted and unencrypted values can be read
+	///
+	/// Values which are encrypted with the current key, or which can not be read
+	/// with any of the specified keys, are left as they are.
+	pub(super) fn reseal(&self, key: &[u8], val: &[u8]) -> Result<Option<Val>, Error> {
+		match self.decrypt(key, val) {
+			Some((0, _)) => Ok(None),
+			Some((_, v)) => self.seal(key, &v).map(Some),
+			None if self.unencrypted => self.seal(key, val).map(Some),
+			None => Ok(None),
+		}
+	}
+	/// Decrypt a value after it has been read from the key
+	pub(super) fn open(&self, key: &[u8], val: &[u8]) -> Result<Val, Error> {
+		let err = || {
+			Error::Ds(
+				"Unable to decrypt the persisted data with the specified encryption keys"
+					.to_owned(),
+			)
+		};
+		match self.decrypt(key, val) {
+			Some((_, v)) => Ok(v),
+			None if self.unencrypted => Ok(val.to_vec()),
+			None => Err(err()),
+		}
+	}
+	/// Decrypt a value with the key it was encrypted with, returning the
+	/// position of the key, or `None` if it can not be decrypted with any key
+	fn decrypt(&self, key: &[u8], val: &[u8]) -> Option<(usize, Val)> {
+		if val.len() < ID_LEN + NONCE_LEN {
+			return None;
+		}
+		let (id, val) = val.split_at(ID_LEN);
+		let (nonce, data) = val.split_at(NONCE_LEN);
+		let (pos, (_, cipher)) = self.keys.iter().enumerate().find(|(_, (v, _))| v == id)?;
+		let payload = Payload {
+			msg: data,
+			aad: key,
+		};
+		cipher.decrypt(Nonce::from_slice(nonce), payload).ok().map(|v| (pos, v))
+	}
+}
+
+/// The position from which values are next re-encrypted with the current key
+#[derive(Clone, Debug, Default)]
+pub(super) struct Reencryption {
+	/// The column family being scanned, or `None` for the default column family
+	pub(super) family: Option<String>,
+	/// The last key which was scanned in the column family
+	pub(super) after: Option<Key>,
+}
+
+impl Reencryption {
+	/// The position at the start of the column family following this one, if any
+	pub(super) fn next<'a>(&self, families: impl Iterator<Item = &'a String>) -> Option<Self> {
+		families.filter(|v| self.family.as_ref().map_or(true, |f| *v > f)).min().map(|v| Self {
+			family: Some(v.clone()),
+			after: None,
+		})
+	}
+}
+
+/// Returns the identifier which is stored alongside values encrypted with the key
+fn identify(key: &[u8; 32]) -> [u8; ID_LEN] {
+	let hash = Sha256::digest(key);
+	let mut id = [0; ID_LEN];
+	id.copy_from_slice(&hash[..ID_LEN]);
+	id
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn seal_and_open() {
+		let cipher = Cipher::new(&[1; 32]);
+		let val = cipher.seal(b"key", b"test").unwrap();
+		assert_ne!(val, b"test");
+		assert_eq!(cipher.open(b"key", &val).unwrap(), b"test");
+	}
+
+	#[test]
+	fn open_under_another_key() {
+		let cipher = Cipher::new(&[1; 32]);
+		let val = cipher.seal(b"key", b"test").unwrap();
+		// A value moved to another key can not be decrypted
+		assert!(cipher.open(b"other", &val).is_err());
+	}
+
+	#[test]
+	fn open_with_previous_key() {
+		let old = Cipher::new(&[1; 32]);
+		let val = old.seal(b"key", b"test").unwrap();
+		// The new key can not decrypt the value
+		let mut new = Cipher::new(&[2; 32]);
+		assert!(new.open(b"key", &val).is_err());
+		// The previous key can decrypt the value
+		new.add_previous_key(&[1; 32]);
+		assert_eq!(new.open(b"key", &val).unwrap(), b"test");
+		// Values are encrypted with the new key
+		let val = new.seal(b"key", b"test").unwrap();
+		assert!(old.open(b"key", &val).is_err());
+		assert_eq!(new.open(b"key", &val).unwrap(), b"test");
+	}
+
+	#[test]
+	fn reseal_with_current_key() {
+		let old = Cipher::new(&[1; 32]);
+		let val = old.seal(b"key", b"test").unwrap();
+		let mut new = Cipher::new(&[2; 32]);
+		new.add_previous_key(&[1; 32]);
+		assert!(new.has_previous_keys());
+		// Values encrypted with the previous key are re-encrypted
+		let val = new.reseal(b"key", &val).unwrap().unwrap();
+		assert!(old.open(b"key", &val).is_err());
+		assert_eq!(Cipher::new(&[2; 32]).open(b"key", &val).unwrap(), b"test");
+		// Values encrypted with the current key are left as they are
+		assert!(new.reseal(b"key", &val).unwrap().is_none());
+		// Values which are not encrypted are left as they are
+		assert!(new.reseal(b"key", b"test").unwrap().is_none());
+		assert!(new.reseal(b"key", b"not encrypted with any key").unwrap().is_none());
+	}
+
+	#[test]
+	fn reencryption_visits_each_family() {
+		let families = vec!["b".to_owned(), "a".to_owned(), "c".to_owned()];
+		let pos = Reencryption::default().next(families.iter()).unwrap();
+		assert_eq!(pos.family.as_deref(), Some("a"));
+		let pos = pos.next(families.iter()).unwrap();
+		assert_eq!(pos.family.as_deref(), Some("b"));
+		let pos = pos.next(families.iter()).unwrap();
+		assert_eq!(pos.family.as_deref(), Some("c"));
+		assert!(pos.next(families.iter()).is_none());
+	}
+
+	#[test]
+	fn open_unencrypted_value() {
+		let cipher = Cipher::new(&[1; 32]);
+		assert!(cipher.open(b"key", b"test").is_err());
+	}
+
+	#[test]
+	fn reseal_unencrypted_value() {
+		let mut cipher = Cipher::new(&[1; 32]);
+		assert!(!cipher.has_previous_keys());
+		cipher.allow_unencrypted();
+		assert!(cipher.has_previous_keys());
+		// Values which are not encrypted are read as they are
+		assert_eq!(cipher.open(b"key", b"test").unwrap(), b"test");
+		// Values which are not encrypted are encrypted with the current key
+		let val = cipher.reseal(b"key", b"test").unwrap().unwrap();
+		assert_ne!(val, b"test");
+		assert_eq!(Cipher::new(&[1; 32]).open(b"key", &val).unwrap(), b"test");
+		assert!(cipher.reseal(b"key", &val).unwrap().is_none());
+	}
+}
@@ -0,0 +1,449 @@
+#![cfg(feature = "kv-custom")]
+
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use futures::future::BoxFuture;
+use std::ops::Range;
+
+/// A key-value storage engine, which can be implemented outside of this crate
+///
+/// A [`Datastore`](crate::kvs::Datastore) can be created on top of any store with
+/// [`Datastore::from_store`](crate::kvs::Datastore::from_store). The store only needs
+/// to provide transactions over ordered byte keys, which are isolated from one another,
+/// and which are only applied when they are committed.
+pub trait Store: Send + Sync + 'static {
+	/// Start a new transaction, which is writeable if `write` is set
+	///
+	/// When `lock` is set, the transaction should use pessimistic locking if the store
+	/// supports it, rather than failing with a conflict when it is committed.
+	fn transaction(
+		&self,
+		write: bool,
+		lock: bool,
+	) -> BoxFuture<'_, Result<Box<dyn StoreTransaction>, Error>>;
+	/// Flush any buffered writes to durable storage
+	fn flush(&self) -> BoxFuture<'_, Result<(), Error>> {
+		Box::pin(async { Ok(()) })
+	}
+}
+
+/// A transaction within a custom [`Store`]
+///
+/// The methods should fail with [`Error::TxFinished`] once the transaction has been
+/// committed or cancelled, and methods which write should fail with
+/// [`Error::TxReadonly`] when the transaction is not writeable.
+pub trait StoreTransaction: Send + Sync {
+	/// Check if the transaction has been committed or cancelled
+	fn closed(&self) -> bool;
+	/// Cancel the transaction, discarding its writes
+	fn cancel(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+	/// Commit the transaction, applying its writes
+	fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+	/// Check if a key exists
+	fn exi(&mut self, key: Key) -> BoxFuture<'_, Result<bool, Error>>;
+	/// Fetch a key
+	fn get(&mut self, key: Key) -> BoxFuture<'_, Result<Option<Val>, Error>>;
+	/// Insert or update a key
+	fn set(&mut self, key: Key, val: Val) -> BoxFuture<'_, Result<(), Error>>;
+	/// Delete a key
+	fn del(&mut self, key: Key) -> BoxFuture<'_, Result<(), Error>>;
+	/// Retrieve up to `limit` keys within a range, in key order
+	fn scan(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> BoxFuture<'_, Result<Vec<(Key, Val)>, Error>>;
+	/// Fetch many keys, returning their values in the same order as the keys
+	///
+	/// Stores which can read several keys in one request should override this, as it is
+	/// used to resolve many records at once. By default the keys are fetched one by one.
+	fn multi_get(&mut self, keys: Vec<Key>) -> BoxFuture<'_, Result<Vec<Option<Val>>, Error>> {
+		Box::pin(async move {
+			let mut res = Vec::with_capacity(keys.len());
+			for key in keys {
+				res.push(self.get(key).await?);
+			}
+			Ok(res)
+		})
+	}
+	/// Insert a key if it doesn't exist, failing with [`Error::TxKeyAlreadyExists`] otherwise
+	fn put(&mut self, key: Key, val: Val) -> BoxFuture<'_, Result<(), Error>> {
+		Box::pin(async move {
+			match self.exi(key.clone()).await? {
+				true => Err(Error::TxKeyAlreadyExists),
+				false => self.set(key, val).await,
+			}
+		})
+	}
+	/// Insert or update a key if its current value matches the check value
+	///
+	/// A check value of `None` expects the key not to exist. Fails with
+	/// [`Error::TxConditionNotMet`] when the current value does not match.
+	fn putc(&mut self, key: Key, val: Val, chk: Option<Val>) -> BoxFuture<'_, Result<(), Error>> {
+		Box::pin(async move {
+			match self.get(key.clone()).await? == chk {
+				true => self.set(key, val).await,
+				false => Err(Error::TxConditionNotMet),
+			}
+		})
+	}
+	/// Delete a key if its current value matches the check value
+	///
+	/// A check value of `None` expects the key not to exist. Fails with
+	/// [`Error::TxConditionNotMet`] when the current value does not match.
+	fn delc(&mut self, key: Key, chk: Option<Val>) -> BoxFuture<'_, Result<(), Error>> {
+		Box::pin(async move {
+			match self.get(key.clone()).await? == chk {
+				true => self.del(key).await,
+				false => Err(Error::TxConditionNotMet),
+			}
+		})
+	}
+}
+
+pub struct Datastore {
+	store: Box<dyn Store>,
+}
+
+pub struct Transaction {
+	/// Is the transaction writeable?
+	write: bool,
+	/// The transaction of the custom store
+	inner: Box<dyn StoreTransaction>,
+}
+
+impl Datastore {
+	/// Open a database on top of a custom store
+	pub(crate) fn new(store: impl Store) -> Datastore {
+		Datastore {
+			store: Box::new(store),
+		}
+	}
+	/// Flush any buffered writes to durable storage
+	pub(crate) async fn flush(&self) -> Result<(), Error> {
+		self.store.flush().await
+	}
+	/// Start a new transaction
+	pub(crate) async fn transaction(&self, write: bool, lock: bool) -> Result<Transaction, Error> {
+		Ok(Transaction {
+			write,
+			inner: self.store.transaction(write, lock).await?,
+		})
+	}
+}
+
+impl Transaction {
+	/// Check if closed
+	pub(crate) fn closed(&self) -> bool {
+		self.inner.closed()
+	}
+	/// Cancel a transaction
+	pub(crate) async fn cancel(&mut self) -> Result<(), Error> {
+		self.inner.cancel().await
+	}
+	/// Commit a transaction
+	pub(crate) async fn commit(&mut self) -> Result<(), Error> {
+		self.inner.commit().await
+	}
+	/// Check if a key exists
+	pub(crate) async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
+	where
+		K: Into<Key>,
+	{
+		self.inner.exi(key.into()).await
+	}
+	/// Fetch a key from the database
+	pub(crate) async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
+	where
+		K: Into<Key>,
+	{
+		self.inner.get(key.into()).await
+	}
+	/// Fetch many keys from the database
+	pub(crate) async fn multi_get(&mut self, keys: Vec<Key>) -> Result<Vec<Option<Val>>, Error> {
+		self.inner.multi_get(keys).await
+	}
+	/// Obtain a new change timestamp for a key
+	/// which is replaced with the current timestamp when the transaction is committed.
+	pub(crate) async fn get_timestamp<K>(&mut self, key: K) -> Result<Versionstamp, Error>
+	where
+		K: Into<Key>,
+	{
+		// Write the timestamp to the "last-write-timestamp" key
+		// to ensure that no other transactions can commit with older timestamps.
+		let k: Key = key.into();
+		let ver = match self.inner.get(k.clone()).await? {
+			Some(prev) => {
+				let array: [u8; 10] =
+					prev.as_slice().try_into().map_err(|e| Error::Ds(format!("{e}")))?;
+				try_to_u64_be(array)? + 1
+			}
+			None => 1,
+		};
+		let verbytes = u64_to_versionstamp(ver);
+		self.inner.set(k, verbytes.to_vec()).await?;
+		// Return the uint64 representation of the timestamp as the result
+		Ok(verbytes)
+	}
+	/// Obtain a new key that is suffixed with the change timestamp
+	pub(crate) async fn get_versionstamped_key<K>(
+		&mut self,
+		ts_key: K,
+		prefix: K,
+		suffix: K,
+	) -> Result<Vec<u8>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		let ts = self.get_timestamp(ts_key).await?;
+		let mut k: Vec<u8> = prefix.into();
+		k.extend_from_slice(&ts);
+		k.append(&mut suffix.into());
+		Ok(k)
+	}
+	/// Insert or update a key in the database
+	pub(crate) async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		self.inner.set(key.into(), val.into()).await
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		self.inner.put(key.into(), val.into()).await
+	}
+	/// Insert a key if the current value matches a condition
+	pub(crate) async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		self.inner.putc(key.into(), val.into(), chk.map(Into::into)).await
+	}
+	/// Delete a key
+	pub(crate) async fn del<K>(&mut self, key: K) -> Result<(), Error>
+	where
+		K: Into<Key>,
+	{
+		self.inner.del(key.into()).await
+	}
+	/// Delete a key if the current value matches a condition
+	pub(crate) async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		self.inner.delc(key.into(), chk.map(Into::into)).await
+	}
+	/// Retrieve a range of keys from the databases
+	pub(crate) async fn scan<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		let rng = rng.start.into()..rng.end.into();
+		self.inner.scan(rng, limit).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dbs::Session;
+	use crate::sql::Isolation;
+	use crate::sql::Value;
+	use std::collections::BTreeMap;
+	use std::sync::atomic::AtomicU32;
+	use std::sync::atomic::Ordering;
+	use std::sync::Arc;
+	use std::sync::Mutex;
+
+	/// A store which keeps its data in a map, and applies transactions as a whole,
+	/// failing the given number of commits as if they conflicted with other transactions
+	#[derive(Clone, Default)]
+	struct MapStore(Arc<Mutex<BTreeMap<Key, Val>>>, Arc<AtomicU32>);
+
+	struct MapTransaction {
+		done: bool,
+		write: bool,
+		data: BTreeMap<Key, Val>,
+		store: MapStore,
+	}
+
+	impl Store for MapStore {
+		fn transaction(
+			&self,
+			write: bool,
+			_: bool,
+		) -> BoxFuture<'_, Result<Box<dyn StoreTransaction>, Error>> {
+			Box::pin(async move {
+				let tx: Box<dyn StoreTransaction> = Box::new(MapTransaction {
+					done: false,
+					write,
+					data: self.0.lock().unwrap().clone(),
+					store: self.clone(),
+				});
+				Ok(tx)
+			})
+		}
+	}
+
+	impl MapTransaction {
+		fn check(&self, write: bool) -> Result<(), Error> {
+			if self.done {
+				return Err(Error::TxFinished);
+			}
+			if write && !self.write {
+				return Err(Error::TxReadonly);
+			}
+			Ok(())
+		}
+	}
+
+	impl StoreTransaction for MapTransaction {
+		fn closed(&self) -> bool {
+			self.done
+		}
+		fn cancel(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+			Box::pin(async move {
+				self.check(false)?;
+				self.done = true;
+				Ok(())
+			})
+		}
+		fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+			Box::pin(async move {
+				self.check(true)?;
+				self.done = true;
+				let conflicts = &self.store.1;
+				if conflicts
+					.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| v.checked_sub(1))
+					.is_ok()
+				{
+					return Err(Error::TxRetryable);
+				}
+				*self.store.0.lock().unwrap() = std::mem::take(&mut self.data);
+				Ok(())
+			})
+		}
+		fn exi(&mut self, key: Key) -> BoxFuture<'_, Result<bool, Error>> {
+			Box::pin(async move {
+				self.check(false)?;
+				Ok(self.data.contains_key(&key))
+			})
+		}
+		fn get(&mut self, key: Key) -> BoxFuture<'_, Result<Option<Val>, Error>> {
+			Box::pin(async move {
+				self.check(false)?;
+				Ok(self.data.get(&key).cloned())
+			})
+		}
+		fn set(&mut self, key: Key, val: Val) -> BoxFuture<'_, Result<(), Error>> {
+			Box::pin(async move {
+				self.check(true)?;
+				self.data.insert(key, val);
+				Ok(())
+			})
+		}
+		fn del(&mut self, key: Key) -> BoxFuture<'_, Result<(), Error>> {
+			Box::pin(async move {
+				self.check(true)?;
+				self.data.remove(&key);
+				Ok(())
+			})
+		}
+		fn scan(
+			&mut self,
+			rng: Range<Key>,
+			limit: u32,
+		) -> BoxFuture<'_, Result<Vec<(Key, Val)>, Error>> {
+			Box::pin(async move {
+				self.check(false)?;
+				let res = self.data.range(rng).take(limit as usize);
+				Ok(res.map(|(k, v)| (k.clone(), v.clone())).collect())
+			})
+		}
+	}
+
+	#[tokio::test]
+	async fn queries_run_on_a_custom_store() {
+		let store = MapStore::default();
+		let ds = crate::kvs::Datastore::from_store(store.clone());
+		let ses = Session::owner().with_ns("test").with_db("test");
+		ds.execute("CREATE person:tobie SET name = 'Tobie'", &ses, None).await.unwrap();
+		// The data is written to the custom store
+		assert!(!store.0.lock().unwrap().is_empty());
+		let res = ds.execute("SELECT VALUE name FROM person", &ses, None).await.unwrap();
+		let val = res.into_iter().next().unwrap().result.unwrap();
+		assert_eq!(val, Value::from(vec![Value::from("Tobie")]));
+		// The default methods check the existing values
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		assert!(matches!(tx.put("key", "one").await, Ok(())));
+		assert!(matches!(tx.put("key", "two").await, Err(Error::TxKeyAlreadyExists)));
+		assert!(matches!(tx.putc("key", "two", Some("two")).await, Err(Error::TxConditionNotMet)));
+		assert!(matches!(tx.putc("key", "two", Some("one")).await, Ok(())));
+		tx.commit().await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn serializable_transactions_are_not_supported() {
+		let ds = crate::kvs::Datastore::from_store(MapStore::default());
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let res = ds.transaction_with_isolation(true, false, Isolation::Serializable).await;
+		assert!(matches!(res, Err(Error::TxIsolationNotSupported { .. })));
+		// The statements of the transaction are not executed
+		let sql = "
+			BEGIN TRANSACTION ISOLATION LEVEL SERIALIZABLE;
+			CREATE person:tobie;
+			COMMIT;
+			SELECT * FROM person;
+		";
+		let res = ds.execute(sql, &ses, None).await.unwrap();
+		assert_eq!(res.len(), 2);
+		assert!(matches!(res[0].result, Err(Error::QueryNotExecutedDetail { .. })));
+		assert_eq!(res[1].result.as_ref().unwrap(), &Value::from(Vec::<Value>::new()));
+		// Snapshot isolation is used by custom stores
+		let sql = "BEGIN TRANSACTION ISOLATION LEVEL SNAPSHOT; CREATE person:tobie; COMMIT;";
+		let res = ds.execute(sql, &ses, None).await.unwrap();
+		assert!(res[0].result.is_ok());
+	}
+	#[tokio::test]
+	async fn conflicting_statements_are_retried() {
+		let store = MapStore::default();
+		let ds = crate::kvs::Datastore::from_store(store.clone()).with_transaction_retries(3);
+		let ses = Session::owner().with_ns("test").with_db("test");
+		// The statement is retried until its transaction commits
+		store.1.store(2, Ordering::SeqCst);
+		let res = ds.execute("CREATE person:tobie SET name = 'Tobie'", &ses, None).await.unwrap();
+		assert!(res[0].result.is_ok());
+		assert_eq!(store.1.load(Ordering::SeqCst), 0);
+		let res = ds.execute("SELECT VALUE name FROM person", &ses, None).await.unwrap();
+		let val = res.into_iter().next().unwrap().result.unwrap();
+		assert_eq!(val, Value::from(vec![Value::from("Tobie")]));
+		// The conflict is returned once the retries run out
+		store.1.store(5, Ordering::SeqCst);
+		let res = ds.execute("CREATE person:jaime", &ses, None).await.unwrap();
+		assert!(res[0].result.as_ref().unwrap_err().is_retryable());
+		assert_eq!(store.1.load(Ordering::SeqCst), 1);
+		// Statements within an explicit transaction are not retried
+		store.1.store(1, Ordering::SeqCst);
+		let sql = "BEGIN; CREATE person:jaime; COMMIT;";
+		let res = ds.execute(sql, &ses, None).await.unwrap();
+		assert!(res[0].result.is_err());
+		assert_eq!(store.1.load(Ordering::SeqCst), 0);
+	}
+}
@@ -1,14 +1,30 @@
+#[cfg(all(feature = "archive", not(target_arch = "wasm32")))]
+use super::archive::Archive;
+use super::export::{self, json_to_sql, BackupInfo, ExportConfig, ExportFormat, ExportProgress};
+use super::inflight::{sleep, Inflight};
 use super::tx::Transaction;
+use super::vacuum::{Sweep, Vacuum};
+#[cfg(not(target_arch = "wasm32"))]
+use super::{Key, Val};
 use crate::cf;
+use crate::cnf;
 use crate::ctx::Context;
 use crate::dbs::node::Timestamp;
 use crate::dbs::Attach;
 use crate::dbs::Capabilities;
+use crate::dbs::Compactions;
 use crate::dbs::Executor;
 use crate::dbs::Notification;
 use crate::dbs::Options;
+use crate::dbs::QueryType;
+use crate::dbs::Quotas;
+use crate::dbs::Rebuilds;
 use crate::dbs::Response;
 use crate::dbs::Session;
+use crate::dbs::Sessions;
+use crate::dbs::Stats;
+use crate::dbs::TableUsage;
+use crate::dbs::Usage;
 use crate::dbs::Variables;
 use crate::err::Error;
 use crate::iam::ResourceKind;
@@ -16,26 +32,37 @@ use crate::iam::{Action, Auth, Error as IamError, Role};
 use crate::key::root::hb::Hb;
 use crate::opt::auth::Root;
 use crate::sql;
+use crate::sql::statements::DefineIndexStatement;
 use crate::sql::statements::DefineUserStatement;
+use crate::sql::statements::RefreshStatement;
+use crate::sql::view::Refresh;
 use crate::sql::Base;
+use crate::sql::Isolation;
 use crate::sql::Value;
-use crate::sql::{Query, Uuid};
+use crate::sql::{Query, Statement, Statements, Uuid};
 use crate::vs::Oracle;
 use channel::Receiver;
 use channel::Sender;
 use futures::lock::Mutex;
 use futures::Future;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::ops::Range;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::instrument;
 use tracing::trace;
+use trice::Instant;
 #[cfg(target_arch = "wasm32")]
 use wasmtimer::std::{SystemTime, UNIX_EPOCH};
 
+/// The number of expired records which are purged in each batch
+const EXPIRY_BATCH_SIZE: u32 = 1000;
+
 /// Used for cluster logic to move LQ data to LQ cleanup code
 /// Not a stored struct; Used only in this module
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -56,6 +83,14 @@ pub struct Datastore {
 	id: Uuid,
 	// Whether this datastore runs in strict mode by default
 	strict: bool,
+	// The isolation level of query transactions, when not set by the storage engine
+	isolation: Option<Isolation>,
+	// How many key-value pairs queries fetch in each batch when scanning
+	batch_size: u32,
+	// How many times statements are retried when their transactions conflict
+	retries: u32,
+	// The size in bytes above which import transactions are split
+	split_size: Option<u64>,
 	// Whether authentication is enabled on this datastore.
 	auth_enabled: bool,
 	// The maximum duration timeout for running multiple statements in a query
@@ -69,6 +104,25 @@ pub struct Datastore {
 	versionstamp_oracle: Arc<Mutex<Oracle>>,
 	// Whether this datastore enables live query notifications to subscribers
 	notification_channel: Option<(Sender<Notification>, Receiver<Notification>)>,
+	// The registry of sessions which have run queries on this datastore
+	sessions: Sessions,
+	// The registry of table access statistics on this datastore
+	stats: Stats,
+	// The registry of index rebuilds on this datastore
+	rebuilds: Rebuilds,
+	// The status of the compactions of the storage engine
+	compactions: Compactions,
+	// The registry of the storage used by each table on this datastore
+	usage: Usage,
+	// The storage quotas of the namespaces and databases on this datastore
+	quotas: Quotas,
+	// How often the storage engine is compacted, if at all
+	compaction_interval: Option<Duration>,
+	// The object storage which backups are archived to, if any
+	#[cfg(all(feature = "archive", not(target_arch = "wasm32")))]
+	archive: Option<Archive>,
+	// The queries which are currently running on this datastore
+	inflight: Inflight,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -79,6 +133,12 @@ pub(super) enum Inner {
 	RocksDB(super::rocksdb::Datastore),
 	#[cfg(feature = "kv-speedb")]
 	SpeeDB(super::speedb::Datastore),
+	#[cfg(feature = "kv-redb")]
+	ReDB(super::redb::Datastore),
+	#[cfg(feature = "kv-object")]
+	Object(super::object::Datastore),
+	#[cfg(feature = "kv-custom")]
+	Custom(super::custom::Datastore),
 	#[cfg(feature = "kv-indxdb")]
 	IndxDB(super::indxdb::Datastore),
 	#[cfg(feature = "kv-tikv")]
@@ -97,6 +157,12 @@ impl fmt::Display for Datastore {
 			Inner::RocksDB(_) => write!(f, "rocksdb"),
 			#[cfg(feature = "kv-speedb")]
 			Inner::SpeeDB(_) => write!(f, "speedb"),
+			#[cfg(feature = "kv-redb")]
+			Inner::ReDB(_) => write!(f, "redb"),
+			#[cfg(feature = "kv-object")]
+			Inner::Object(_) => write!(f, "object"),
+			#[cfg(feature = "kv-custom")]
+			Inner::Custom(_) => write!(f, "custom"),
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(_) => write!(f, "indxdb"),
 			#[cfg(feature = "kv-tikv")]
@@ -203,6 +269,32 @@ impl Datastore {
 				#[cfg(not(feature = "kv-speedb"))]
 				return Err(Error::Ds("Cannot connect to the `speedb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
 			}
+			// Parse and initiate an ReDB database
+			s if s.starts_with("redb:") => {
+				#[cfg(feature = "kv-redb")]
+				{
+					info!("Starting kvs store at {}", path);
+					let s = s.trim_start_matches("redb://");
+					let s = s.trim_start_matches("redb:");
+					let v = super::redb::Datastore::new(s).await.map(Inner::ReDB);
+					info!("Started kvs store at {}", path);
+					v
+				}
+				#[cfg(not(feature = "kv-redb"))]
+				return Err(Error::Ds("Cannot connect to the `redb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
+			}
+			// Parse and initiate an object storage database
+			s if s.starts_with("s3:") || s.starts_with("gcs:") => {
+				#[cfg(feature = "kv-object")]
+				{
+					info!("Starting kvs store at {}", path);
+					let v = super::object::Datastore::new(s).await.map(Inner::Object);
+					info!("Started kvs store at {}", path);
+					v
+				}
+				#[cfg(not(feature = "kv-object"))]
+				return Err(Error::Ds("Cannot connect to the object storage engine as it is not enabled in this build of SurrealDB".to_owned()));
+			}
 			// Parse and initiate an IndxDB database
 			s if s.starts_with("indxdb:") => {
 				#[cfg(feature = "kv-indxdb")]
@@ -224,7 +316,8 @@ impl Datastore {
 					info!("Connecting to kvs store at {}", path);
 					let s = s.trim_start_matches("tikv://");
 					let s = s.trim_start_matches("tikv:");
-					let v = super::tikv::Datastore::new(s).await.map(Inner::TiKV);
+					let v =
+						super::tikv::Datastore::new(s, &Default::default()).await.map(Inner::TiKV);
 					info!("Connected to kvs store at {}", path);
 					v
 				}
@@ -238,7 +331,9 @@ impl Datastore {
 					info!("Connecting to kvs store at {}", path);
 					let s = s.trim_start_matches("fdb://");
 					let s = s.trim_start_matches("fdb:");
-					let v = super::fdb::Datastore::new(s).await.map(Inner::FoundationDB);
+					let v = super::fdb::Datastore::new(s, &Default::default())
+						.await
+						.map(Inner::FoundationDB);
 					info!("Connected to kvs store at {}", path);
 					v
 				}
@@ -252,17 +347,109 @@ impl Datastore {
 			}
 		};
 		// Set the properties on the datastore
-		inner.map(|inner| Self {
+		inner.map(Self::from_inner)
+	}
+
+	/// Connects to a TiKV cluster, using the specified connection options
+	///
+	/// This allows connecting to clusters which require TLS, or tuning how the
+	/// client talks to the cluster.
+	///
+	/// ```rust,no_run
+	/// # use std::time::Duration;
+	/// # use surrealdb::kvs::{Datastore, TikvOptions};
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let opts = TikvOptions::default()
+	///     .with_tls("ca.pem", "client.pem", "client-key.pem")
+	///     .with_timeout(Duration::from_secs(5));
+	/// let ds = Datastore::new_tikv("tikv://127.0.0.1:2379", opts).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "kv-tikv")]
+	pub async fn new_tikv(path: &str, options: super::TikvOptions) -> Result<Datastore, Error> {
+		info!("Connecting to kvs store at {}", path);
+		let s = path.trim_start_matches("tikv://");
+		let s = s.trim_start_matches("tikv:");
+		let v = super::tikv::Datastore::new(s, &options).await.map(Inner::TiKV)?;
+		info!("Connected to kvs store at {}", path);
+		Ok(Self::from_inner(v))
+	}
+
+	/// Connects to a FoundationDB cluster, using the specified connection options
+	///
+	/// ```rust,no_run
+	/// # use std::time::Duration;
+	/// # use surrealdb::kvs::{Datastore, FdbOptions};
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let opts = FdbOptions::default()
+	///     .with_timeout(Duration::from_secs(5))
+	///     .with_datacenter_id("dc1");
+	/// let ds = Datastore::new_fdb("fdb:///etc/foundationdb/fdb.cluster", opts).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "kv-fdb")]
+	pub async fn new_fdb(path: &str, options: super::FdbOptions) -> Result<Datastore, Error> {
+		info!("Connecting to kvs store at {}", path);
+		let s = path.trim_start_matches("fdb://");
+		let s = s.trim_start_matches("fdb:");
+		let v = super::fdb::Datastore::new(s, &options).await.map(Inner::FoundationDB)?;
+		info!("Connected to kvs store at {}", path);
+		let ds = Self::from_inner(v);
+		Ok(match options.retry_limit {
+			Some(v) => ds.with_transaction_retries(v),
+			None => ds,
+		})
+	}
+
+	/// Creates a new datastore on top of a custom storage engine
+	///
+	/// This allows storage engines to be implemented outside of this crate, by
+	/// implementing the [`Store`](super::Store) trait.
+	#[cfg(feature = "kv-custom")]
+	pub fn from_store(store: impl super::Store) -> Datastore {
+		info!("Started kvs store on a custom storage engine");
+		Self::from_inner(Inner::Custom(super::custom::Datastore::new(store)))
+	}
+
+	fn from_inner(inner: Inner) -> Datastore {
+		// Imports are split to stay within the transaction size limit of FoundationDB
+		let split_size = match inner {
+			#[cfg(feature = "kv-fdb")]
+			Inner::FoundationDB(_) => Some(*cnf::FDB_IMPORT_SPLIT_SIZE),
+			#[allow(unreachable_patterns)]
+			_ => None,
+		};
+		Self {
 			id: Uuid::new_v4(),
 			inner,
 			strict: false,
+			isolation: None,
+			batch_size: *cnf::SCAN_BATCH_SIZE,
+			retries: *cnf::TRANSACTION_RETRY_ATTEMPTS,
+			split_size,
 			auth_enabled: false,
 			query_timeout: None,
 			transaction_timeout: None,
 			notification_channel: None,
+			sessions: Sessions::default(),
+			stats: Stats::default(),
+			rebuilds: Rebuilds::default(),
+			compactions: Compactions::default(),
+			usage: Usage::default(),
+			quotas: Quotas::default(),
+			compaction_interval: None,
+			#[cfg(all(feature = "archive", not(target_arch = "wasm32")))]
+			archive: None,
+			inflight: Inflight::default(),
 			capabilities: Capabilities::default(),
-			versionstamp_oracle: Arc::new(Mutex::new(Oracle::systime_counter())),
-		})
+			versionstamp_oracle: Arc::new(Mutex::new(Oracle::hybrid_logical_clock())),
+		}
 	}
 
 	/// Specify whether this Datastore should run in strict mode
@@ -277,6 +464,86 @@ impl Datastore {
 		self
 	}
 
+	/// Specify the isolation level of the transactions which run queries
+	///
+	/// Transactions started with `BEGIN TRANSACTION ISOLATION LEVEL` override this.
+	pub fn with_isolation(mut self, isolation: Isolation) -> Self {
+		self.isolation = Some(isolation);
+		self
+	}
+
+	/// Specify how many key-value pairs queries fetch in each batch when scanning
+	///
+	/// Only one batch is held in memory at a time, when iterating over a table, a record range or an index.
+	pub fn with_batch_size(mut self, batch_size: u32) -> Self {
+		self.batch_size = batch_size.max(1);
+		self
+	}
+
+	/// Specify how many times a statement is retried, when its transaction conflicts with another transaction
+	///
+	/// Only statements which run in their own transaction are retried, after waiting
+	/// for an exponentially growing duration. Statements within a `BEGIN` and `COMMIT`
+	/// block are never retried, and return the conflict to the client.
+	pub fn with_transaction_retries(mut self, retries: u32) -> Self {
+		self.retries = retries;
+		self
+	}
+
+	/// Specify the size in bytes above which imports are split into several transactions
+	///
+	/// When an import within a `BEGIN` and `COMMIT` block has written more than this
+	/// many bytes, its transaction is committed, and the import continues in a new
+	/// transaction. This keeps large imports within the transaction size limit of the
+	/// storage engine, at the cost of the import no longer being atomic. It defaults
+	/// to 8MB for FoundationDB, and is disabled for every other storage engine.
+	pub fn with_import_split_size(mut self, bytes: Option<u64>) -> Self {
+		self.split_size = bytes;
+		self
+	}
+
+	/// Specify how often the storage engine should be compacted
+	///
+	/// Compaction discards deleted keys and their tombstones, which otherwise slow down
+	/// scans on workloads which delete many records. The first compaction runs one
+	/// interval after the datastore is started.
+	pub fn with_compaction_interval(mut self, interval: Duration) -> Self {
+		self.compaction_interval = Some(interval);
+		self
+	}
+
+	/// Specify the maximum storage in bytes which the tables of a namespace can use
+	///
+	/// Writes which would take the namespace over its quota fail with an error, while
+	/// deletes are always allowed. The storage used is approximate, combining the latest
+	/// collection of the storage used by each table with the bytes written since then.
+	pub fn with_namespace_quota(self, ns: &str, bytes: u64) -> Self {
+		self.quotas.set_namespace(ns, Some(bytes));
+		self
+	}
+
+	/// Specify the maximum storage in bytes which the tables of a database can use
+	///
+	/// Writes which would take the database over its quota fail with an error, while
+	/// deletes are always allowed. The storage used is approximate, combining the latest
+	/// collection of the storage used by each table with the bytes written since then.
+	pub fn with_database_quota(self, ns: &str, db: &str, bytes: u64) -> Self {
+		self.quotas.set_database(ns, db, Some(bytes));
+		self
+	}
+
+	/// Specify an object storage bucket to which backups of every database are archived
+	///
+	/// Backups are shipped to an `s3://bucket/prefix` or `gcs://bucket/prefix` path on
+	/// every interval, using the credentials configured in the environment, and can be
+	/// restored with [`Datastore::restore_archive`]. Databases are backed up incrementally
+	/// when all of their tables are defined with a `CHANGEFEED`.
+	#[cfg(all(feature = "archive", not(target_arch = "wasm32")))]
+	pub fn with_archive(mut self, path: &str, interval: Duration) -> Result<Self, Error> {
+		self.archive = Some(Archive::new(path, interval)?);
+		Ok(self)
+	}
+
 	/// Specify whether this datastore should enable live query notifications
 	pub fn with_notifications(mut self) -> Self {
 		self.notification_channel = Some(channel::bounded(100));
@@ -307,6 +574,145 @@ impl Datastore {
 		self
 	}
 
+	/// Set the store, within the IndexedDB database, in which the data is persisted
+	#[cfg(feature = "kv-indxdb")]
+	pub fn with_store_name(mut self, name: &str) -> Self {
+		#[allow(irrefutable_let_patterns)]
+		if let Inner::IndxDB(v) = &mut self.inner {
+			v.set_store(name);
+		}
+		self
+	}
+
+	/// Encrypt the data persisted by the IndexedDB, RocksDB, or SpeeDB storage engines with a 256-bit key
+	///
+	/// Only the values are encrypted. The keys, which hold the names of the namespaces, databases,
+	/// and tables, the record ids, and the indexed field values, are stored in plaintext so that
+	/// they can be scanned in order. Sensitive fields should not be indexed in an encrypted store.
+	#[cfg(any(feature = "kv-indxdb", feature = "kv-rocksdb", feature = "kv-speedb"))]
+	pub fn with_encryption_key(mut self, key: [u8; 32]) -> Self {
+		match &mut self.inner {
+			#[cfg(feature = "kv-indxdb")]
+			Inner::IndxDB(v) => v.set_encryption_key(&key),
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.set_encryption_key(&key),
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.set_encryption_key(&key),
+			#[allow(unreachable_patterns)]
+			_ => {}
+		}
+		self
+	}
+
+	/// Allow data encrypted with a previous key to be read, after the encryption key has been
+	/// rotated. Values are encrypted with the current key when they are next written, and
+	/// the remaining values are re-encrypted in batches on each tick, or by [`Datastore::reencrypt`].
+	#[cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+	pub fn with_previous_encryption_key(mut self, key: [u8; 32]) -> Self {
+		match &mut self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.add_previous_encryption_key(&key),
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.add_previous_encryption_key(&key),
+			#[allow(unreachable_patterns)]
+			_ => {}
+		}
+		self
+	}
+
+	/// Allow data persisted before the encryption key was set to be read, in order to migrate an
+	/// existing unencrypted store. Values are encrypted when they are next written, and the
+	/// remaining values are encrypted in batches on each tick, or by [`Datastore::reencrypt`].
+	#[cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+	pub fn with_unencrypted_data(mut self) -> Self {
+		match &mut self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.allow_unencrypted_data(),
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.allow_unencrypted_data(),
+			#[allow(unreachable_patterns)]
+			_ => {}
+		}
+		self
+	}
+
+	/// Replicate the writes made to a RocksDB datastore to the other nodes of a replication group
+	///
+	/// The nodes elect a leader, which is the only node to accept writes. The other nodes
+	/// apply the writes made on the leader, and can serve reads which may lag behind it.
+	/// Writes made on a node which is not the leader fail with [`Error::ReplicaNotLeader`].
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::kvs::{Datastore, ReplicationOptions};
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let opts = ReplicationOptions::new(1, "10.0.0.1:7000".parse().unwrap(), "/var/lib/surrealdb/raft")
+	///     .with_peer(2, "10.0.0.2:7000".parse().unwrap())
+	///     .with_peer(3, "10.0.0.3:7000".parse().unwrap());
+	/// let ds = Datastore::new("rocksdb:/var/lib/surrealdb/data").await?.with_replication(opts).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(feature = "replication")]
+	pub async fn with_replication(
+		mut self,
+		options: super::ReplicationOptions,
+	) -> Result<Self, Error> {
+		match &mut self.inner {
+			Inner::RocksDB(v) => {
+				let replica = super::raft::start(v.clone(), options).await?;
+				v.set_replica(replica);
+				Ok(self)
+			}
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::Ds(
+				"Replication is only supported by the RocksDB storage engine".to_owned(),
+			)),
+		}
+	}
+
+	/// Get the status of this node within its replication group, if the writes are replicated
+	#[cfg(feature = "replication")]
+	pub fn replication_status(&self) -> Option<super::ReplicationStatus> {
+		match &self.inner {
+			Inner::RocksDB(v) => v.replica().map(|r| r.status()),
+			#[allow(unreachable_patterns)]
+			_ => None,
+		}
+	}
+
+	/// Wait until the replication group has a leader, or until the timeout elapses
+	#[cfg(feature = "replication")]
+	pub async fn wait_for_leader(&self, timeout: Duration) {
+		#[allow(irrefutable_let_patterns)]
+		if let Inner::RocksDB(v) = &self.inner {
+			if let Some(replica) = v.replica() {
+				replica.wait_for_leader(timeout).await;
+			}
+		}
+	}
+
+	/// Get the registry of connected sessions for this Datastore
+	pub fn sessions(&self) -> &Sessions {
+		&self.sessions
+	}
+
+	/// Get the registry of table access statistics for this Datastore
+	pub fn stats(&self) -> &Stats {
+		&self.stats
+	}
+
+	/// Get the registry of the storage used by each table for this Datastore
+	pub fn usage(&self) -> &Usage {
+		&self.usage
+	}
+
+	/// Get the registry of the storage quotas for this Datastore
+	pub fn quotas(&self) -> &Quotas {
+		&self.quotas
+	}
+
 	/// Is authentication enabled for this Datastore?
 	pub fn is_auth_enabled(&self) -> bool {
 		self.auth_enabled
@@ -351,9 +757,65 @@ impl Datastore {
 		}
 	}
 
+	/// Store the keys of each table under the interned prefix of the table
+	///
+	/// Tables which held keys before they were defined, or which were defined before keys were
+	/// interned, store their keys under the names of their namespace, database, and table. The
+	/// keys of each of these tables are moved to the interned prefix in a transaction of its own.
+	pub(crate) async fn intern_tables(&self) -> Result<(), Error> {
+		// Find the tables which are not interned
+		let mut tables = Vec::new();
+		let mut tx = self.transaction(false, false).await?;
+		for ns in tx.all_ns().await?.iter() {
+			for db in tx.all_db(&ns.name).await?.iter() {
+				for tb in tx.all_tb(&ns.name, &db.name).await?.iter() {
+					if !tb.interned {
+						tables.push((ns.name.to_raw(), db.name.to_raw(), tb.name.to_raw()));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Move the keys of each table in turn
+		for (ns, db, tb) in tables {
+			loop {
+				let mut tx = self.transaction(true, false).await?;
+				if let Err(e) = tx.intern_tb(&ns, &db, &tb).await {
+					tx.cancel().await?;
+					return Err(e);
+				}
+				match tx.commit().await {
+					Ok(_) => break,
+					// The table conflicted with another transaction, so move it again
+					Err(Error::TxRetryable) => continue,
+					Err(e) => return Err(e),
+				}
+			}
+		}
+		Ok(())
+	}
+
 	// Initialise bootstrap with implicit values intended for runtime
 	pub async fn bootstrap(&self) -> Result<(), Error> {
 		trace!("Bootstrapping {}", self.id);
+		// Assign ids to any definitions stored without them
+		let mut tx = self.transaction(true, false).await?;
+		match tx.ensure_all_ids().await {
+			Ok(_) => {
+				tx.complete_changes(false).await?;
+				tx.commit().await?;
+			}
+			Err(e) => {
+				error!("Error bootstrapping id mapping: {:?}", e);
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		// Move the keys of tables which are stored under their names
+		if let Err(e) = self.intern_tables().await {
+			error!("Error bootstrapping interned tables: {:?}", e);
+			return Err(e);
+		}
 		let mut tx = self.transaction(true, false).await?;
 		let now = tx.clock();
 		let archived = match self.register_remove_and_archive(&mut tx, &self.id, now).await {
@@ -590,6 +1052,13 @@ impl Datastore {
 	pub async fn tick_at(&self, ts: u64) -> Result<(), Error> {
 		self.save_timestamp_for_versionstamp(ts).await?;
 		self.garbage_collect_stale_change_feeds(ts).await?;
+		self.refresh_scheduled_views(ts).await?;
+		self.purge_expired_records(ts).await?;
+		self.compact_on_schedule(ts).await?;
+		self.collect_usage_on_schedule(ts).await?;
+		self.reencrypt_in_background().await?;
+		self.expire_idle_sessions().await?;
+		self.archive_on_schedule(ts).await?;
 		// TODO Add LQ GC
 		// TODO Add Node GC?
 		Ok(())
@@ -622,6 +1091,207 @@ impl Datastore {
 		Ok(())
 	}
 
+	// refresh_scheduled_views recalculates any views which are refreshed on an interval, and are now due.
+	// The time of the last refresh of each view is stored, so that a view is only refreshed by one node.
+	pub async fn refresh_scheduled_views(&self, ts: u64) -> Result<(), Error> {
+		// Find the views which are refreshed on an interval
+		let mut views = Vec::new();
+		let mut tx = self.transaction(false, false).await?;
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				for tb in tx.all_tb(ns, db).await?.iter() {
+					if let Some(Refresh::Every(every)) =
+						tb.view.as_ref().and_then(|v| v.refresh.as_ref())
+					{
+						views.push((
+							ns.to_owned(),
+							db.to_owned(),
+							tb.name.clone(),
+							every.as_secs(),
+						));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Claim the refresh of each view which is due
+		let mut due = Vec::new();
+		for (ns, db, name, every) in views {
+			let mut tx = self.transaction(true, false).await?;
+			let key = crate::key::table::rf::new(&ns, &db, &name);
+			let last = tx.get(key.clone()).await?.and_then(|v| v.try_into().ok());
+			if matches!(last.map(u64::from_be_bytes), Some(v) if ts < v.saturating_add(every)) {
+				tx.cancel().await?;
+				continue;
+			}
+			tx.set(key, ts.to_be_bytes().to_vec()).await?;
+			match tx.commit().await {
+				Ok(_) => due.push((ns, db, name)),
+				// Another node claimed the refresh at the same time
+				Err(Error::TxRetryable) => (),
+				Err(e) => return Err(e),
+			}
+		}
+		// Refresh each view in its own transaction
+		for (ns, db, name) in due {
+			let sess = Session::owner().with_ns(&ns).with_db(&db);
+			let ast = Query(Statements(vec![Statement::Refresh(RefreshStatement {
+				name: name.clone(),
+			})]));
+			for res in self.process(ast, &sess, None).await? {
+				if let Err(e) = res.result {
+					warn!("Failed to refresh the view {name} in {ns}/{db}: {e}");
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// compact_on_schedule compacts the storage engine, if a compaction interval is set, and a compaction is due.
+	pub async fn compact_on_schedule(&self, ts: u64) -> Result<(), Error> {
+		if let Some(interval) = self.compaction_interval {
+			if self.compactions.due(ts, interval.as_secs()) {
+				match self.compact().await {
+					// A manually started compaction is still running
+					Err(Error::DsCompactionRunning) => (),
+					Err(e) => warn!("Failed to compact the datastore: {e}"),
+					Ok(_) => (),
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// collect_usage_on_schedule collects the storage used by each table, if a collection is due.
+	pub async fn collect_usage_on_schedule(&self, ts: u64) -> Result<(), Error> {
+		if self.usage.due(ts, *cnf::STORAGE_USAGE_INTERVAL) {
+			if let Err(e) = self.collect_usage().await {
+				warn!("Failed to collect the storage used by each table: {e}");
+			}
+		}
+		Ok(())
+	}
+
+	// expire_idle_sessions removes the sessions which have been idle for too long from the session registry.
+	pub async fn expire_idle_sessions(&self) -> Result<(), Error> {
+		self.sessions.expire(Duration::from_secs(*cnf::SESSION_IDLE_TIMEOUT));
+		Ok(())
+	}
+
+	// reencrypt_in_background re-encrypts a batch of the values which are still encrypted with a previous key.
+	pub async fn reencrypt_in_background(&self) -> Result<(), Error> {
+		match self.reencrypt_batch(*cnf::REENCRYPTION_BATCH_SIZE).await {
+			// The batch conflicted with another transaction, and is retried on the next tick
+			Err(Error::TxRetryable) => (),
+			Err(e) => warn!("Failed to re-encrypt the datastore: {e}"),
+			Ok(_) => (),
+		}
+		Ok(())
+	}
+
+	// build_indexes_in_background populates the indexes which were defined on tables which already held records.
+	// This is run by a task of its own, separately from the tick, as building an index can take a long time.
+	pub async fn build_indexes_in_background(&self) -> Result<(), Error> {
+		if let Err(e) = self.build_indexes().await {
+			warn!("Failed to build the indexes in the background: {e}");
+		}
+		Ok(())
+	}
+
+	// archive_on_schedule ships backups to the archive, if an archive is set, and the backups are due.
+	pub async fn archive_on_schedule(&self, ts: u64) -> Result<(), Error> {
+		#![allow(unused_variables)]
+		#[cfg(all(feature = "archive", not(target_arch = "wasm32")))]
+		if let Some(archive) = &self.archive {
+			if let Err(e) = archive.ship_on_schedule(self, ts).await {
+				warn!("Failed to archive the datastore: {e}");
+			}
+		}
+		Ok(())
+	}
+
+	// purge_expired_records deletes the records of tables with a TTL, which expired before the timestamp.
+	pub async fn purge_expired_records(&self, ts: u64) -> Result<(), Error> {
+		// Find the tables which have a TTL
+		let mut due = Vec::new();
+		let mut tx = self.transaction(false, false).await?;
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				for tb in tx.all_tb(ns, db).await?.iter() {
+					if tb.ttl.is_some() {
+						due.push((ns.to_owned(), db.to_owned(), tb.name.to_raw()));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Delete the expired records of each table in batches
+		for (ns, db, tb) in due {
+			let sess = Session::owner().with_ns(&ns).with_db(&db);
+			let opt = Options::default()
+				.with_id(self.id.0)
+				.with_ns(sess.ns())
+				.with_db(sess.db())
+				.with_auth(sess.au.clone())
+				.with_auth_enabled(self.auth_enabled)
+				.with_strict(self.strict)
+				.new_with_force(true);
+			let mut ctx = Context::default();
+			ctx.add_capabilities(self.capabilities.clone());
+			let ctx = sess.context(ctx);
+			let mut nxt = crate::key::table::eq::prefix(&ns, &db, &tb);
+			let end = crate::key::table::eq::until(&ns, &db, &tb, ts.saturating_mul(1000));
+			loop {
+				// Remove each batch of records in a transaction of its own
+				let txn = self.transaction(true, false).await?.enclose();
+				let res = txn.lock().await.scan(nxt.clone()..end.clone(), EXPIRY_BATCH_SIZE).await;
+				let res = match res {
+					Ok(res) => {
+						let mut out = Ok(());
+						for (k, _) in res.iter() {
+							let key = crate::key::table::eq::Eq::decode(k)?;
+							let rid = sql::Thing::from((key.tb, key.id));
+							// Remove the record, along with its index entries and edges
+							out = crate::doc::purge_expired(&ctx, &opt, &txn, &rid, key.ts).await;
+							if out.is_err() {
+								break;
+							}
+						}
+						out.map(|_| res)
+					}
+					Err(e) => Err(e),
+				};
+				let mut run = txn.lock().await;
+				let res = match res {
+					Ok(res) => match run.commit().await {
+						Ok(_) => res,
+						// The batch conflicted with another transaction, so remove it again
+						Err(Error::TxRetryable) => continue,
+						Err(e) => return Err(e),
+					},
+					Err(e) => {
+						run.cancel().await?;
+						warn!("Failed to purge the expired records of {tb} in {ns}/{db}: {e}");
+						break;
+					}
+				};
+				// Ready the next batch
+				match res.last() {
+					Some((k, _)) if res.len() == EXPIRY_BATCH_SIZE as usize => {
+						nxt = k.clone();
+						nxt.push(0x00);
+					}
+					_ => break,
+				}
+			}
+		}
+		Ok(())
+	}
+
 	// Creates a heartbeat entry for the member indicating to the cluster
 	// that the node is alive.
 	// This is the preferred way of creating heartbeats inside the database, so try to use this.
@@ -664,6 +1334,92 @@ impl Datastore {
 	/// }
 	/// ```
 	pub async fn transaction(&self, write: bool, lock: bool) -> Result<Transaction, Error> {
+		self.start_transaction(write, lock, None).await
+	}
+
+	/// Create a new transaction on this datastore with the given isolation level
+	///
+	/// An error is returned if the storage engine does not support the isolation level.
+	/// The in-memory, object storage, IndxDB and ReDB engines only run one writeable transaction at a
+	/// time, so their transactions are serializable at either level.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	/// use surrealdb::sql::Isolation;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("file://database.db").await?;
+	///     let mut tx = ds.transaction_with_isolation(true, false, Isolation::Serializable).await?;
+	///     tx.cancel().await?;
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn transaction_with_isolation(
+		&self,
+		write: bool,
+		lock: bool,
+		isolation: Isolation,
+	) -> Result<Transaction, Error> {
+		self.start_transaction(write, lock, Some(isolation)).await
+	}
+
+	/// Increment a sequence in a separate transaction, which is committed straight away
+	///
+	/// This ensures that concurrent transactions which use the same sequence do not
+	/// conflict with each other, although the values used by a cancelled transaction
+	/// are skipped. Returns `None` for the storage engines which only run one writeable
+	/// transaction at a time, in which case the sequence is incremented in the current
+	/// transaction instead.
+	pub(crate) async fn next_sequence(
+		&self,
+		ns: &str,
+		db: &str,
+		sq: &str,
+		init: i64,
+	) -> Result<Option<i64>, Error> {
+		match &self.inner {
+			#[cfg(feature = "kv-mem")]
+			Inner::Mem(_) => return Ok(None),
+			#[cfg(feature = "kv-redb")]
+			Inner::ReDB(_) => return Ok(None),
+			#[cfg(feature = "kv-object")]
+			Inner::Object(_) => return Ok(None),
+			#[cfg(feature = "kv-custom")]
+			Inner::Custom(_) => return Ok(None),
+			#[cfg(feature = "kv-indxdb")]
+			Inner::IndxDB(_) => return Ok(None),
+			#[allow(unreachable_patterns)]
+			_ => {}
+		}
+		#[allow(unreachable_code)]
+		loop {
+			let mut tx = self.transaction(true, false).await?;
+			let key = crate::key::database::sv::new(ns, db, sq);
+			let val = match tx.incr(key, init).await {
+				Ok(val) => val,
+				Err(e) => {
+					tx.cancel().await?;
+					return Err(e);
+				}
+			};
+			match tx.commit().await {
+				Ok(_) => return Ok(Some(val)),
+				// The sequence was incremented by another transaction, so increment it again
+				Err(Error::TxRetryable) => continue,
+				Err(e) => return Err(e),
+			}
+		}
+	}
+
+	/// Create a new transaction, using the default isolation level of the storage engine if none is given
+	pub(crate) async fn start_transaction(
+		&self,
+		write: bool,
+		lock: bool,
+		isolation: Option<Isolation>,
+	) -> Result<Transaction, Error> {
 		#![allow(unused_variables)]
 		let inner = match &self.inner {
 			#[cfg(feature = "kv-mem")]
@@ -673,14 +1429,35 @@ impl Datastore {
 			}
 			#[cfg(feature = "kv-rocksdb")]
 			Inner::RocksDB(v) => {
-				let tx = v.transaction(write, lock).await?;
+				let tx = v.transaction(write, lock, isolation.unwrap_or_default()).await?;
 				super::tx::Inner::RocksDB(tx)
 			}
 			#[cfg(feature = "kv-speedb")]
 			Inner::SpeeDB(v) => {
-				let tx = v.transaction(write, lock).await?;
+				let tx = v.transaction(write, lock, isolation.unwrap_or_default()).await?;
 				super::tx::Inner::SpeeDB(tx)
 			}
+			#[cfg(feature = "kv-redb")]
+			Inner::ReDB(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::ReDB(tx)
+			}
+			#[cfg(feature = "kv-object")]
+			Inner::Object(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::Object(tx)
+			}
+			#[cfg(feature = "kv-custom")]
+			Inner::Custom(v) => {
+				// Custom engines don't report the isolation level of their transactions
+				if let Some(isolation @ Isolation::Serializable) = isolation {
+					return Err(Error::TxIsolationNotSupported {
+						value: isolation.to_string(),
+					});
+				}
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::Custom(tx)
+			}
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(v) => {
 				let tx = v.transaction(write, lock).await?;
@@ -688,12 +1465,18 @@ impl Datastore {
 			}
 			#[cfg(feature = "kv-tikv")]
 			Inner::TiKV(v) => {
+				// Optimistic transactions in TiKV don't check reads for conflicts
+				if let Some(isolation @ Isolation::Serializable) = isolation {
+					return Err(Error::TxIsolationNotSupported {
+						value: isolation.to_string(),
+					});
+				}
 				let tx = v.transaction(write, lock).await?;
 				super::tx::Inner::TiKV(tx)
 			}
 			#[cfg(feature = "kv-fdb")]
 			Inner::FoundationDB(v) => {
-				let tx = v.transaction(write, lock).await?;
+				let tx = v.transaction(write, lock, isolation).await?;
 				super::tx::Inner::FoundationDB(tx)
 			}
 			#[allow(unreachable_patterns)]
@@ -707,9 +1490,925 @@ impl Datastore {
 			cf: cf::Writer::new(),
 			write_buffer: HashMap::new(),
 			vso: self.versionstamp_oracle.clone(),
+			savepoints: Vec::new(),
+			events: Vec::new(),
+			interned: HashMap::new(),
+			usage: self.usage.clone(),
+			quotas: self.quotas.clone(),
+			written: HashMap::new(),
+			size: 0,
 		})
 	}
 
+	/// Shutdown the datastore, stopping any new queries from being run
+	///
+	/// Running queries are given the grace period to complete, after which
+	/// they are cancelled, and are given a further grace period to stop.
+	/// Any pending live query notifications are then given time to be
+	/// received, before the notification channel is closed, the final
+	/// changefeed timestamp is recorded, and the storage engine is flushed.
+	///
+	/// ```rust,no_run
+	/// use std::time::Duration;
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     ds.shutdown(Duration::from_secs(10)).await?;
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn shutdown(&self, grace_period: Duration) -> Result<(), Error> {
+		trace!("Shutting down {}", self.id);
+		// Wait for, or cancel, the running queries
+		if !self.inflight.drain(grace_period).await {
+			warn!("Some queries were still running when shutting down {}", self.id);
+		}
+		// Wait for pending notifications to be received
+		if let Some(channel) = &self.notification_channel {
+			let deadline = Instant::now() + grace_period;
+			while !channel.0.is_empty() && Instant::now() < deadline {
+				sleep(Duration::from_millis(10)).await;
+			}
+			channel.0.close();
+		}
+		// Record the final changefeed timestamp
+		self.tick().await?;
+		// Flush the underlying storage engine
+		self.flush().await?;
+		// Stop taking part in the replication group
+		#[cfg(feature = "replication")]
+		#[allow(irrefutable_let_patterns)]
+		if let Inner::RocksDB(v) = &self.inner {
+			if let Some(replica) = v.replica() {
+				replica.stop();
+			}
+		}
+		Ok(())
+	}
+
+	/// Flush any buffered writes in the underlying storage engine to durable storage
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     ds.flush().await?;
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn flush(&self) -> Result<(), Error> {
+		#[allow(unused_variables)]
+		match &self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.flush().await,
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.flush().await,
+			#[cfg(feature = "kv-redb")]
+			Inner::ReDB(v) => v.flush().await,
+			#[cfg(feature = "kv-object")]
+			Inner::Object(v) => v.flush().await,
+			#[cfg(feature = "kv-custom")]
+			Inner::Custom(v) => v.flush().await,
+			#[cfg(feature = "kv-indxdb")]
+			Inner::IndxDB(v) => v.flush().await,
+			#[allow(unreachable_patterns)]
+			_ => Ok(()),
+		}
+	}
+
+	/// Compact the underlying storage engine, discarding deleted keys and their tombstones
+	///
+	/// Storage engines which can not be compacted manually ignore this. The status of
+	/// the latest compaction is reported by `INFO FOR ROOT`.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("rocksdb://data.db").await?;
+	///     ds.compact().await?;
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn compact(&self) -> Result<(), Error> {
+		#[allow(unused_variables)]
+		match &self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => {
+				let compaction = self.compactions.start().ok_or(Error::DsCompactionRunning)?;
+				let res = v.compact().await;
+				compaction.finish(res.as_ref().err().map(ToString::to_string));
+				res
+			}
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => {
+				let compaction = self.compactions.start().ok_or(Error::DsCompactionRunning)?;
+				let res = v.compact().await;
+				compaction.finish(res.as_ref().err().map(ToString::to_string));
+				res
+			}
+			#[allow(unreachable_patterns)]
+			_ => Ok(()),
+		}
+	}
+
+	/// Collect the approximate number of records, and the storage used by each table and index
+	///
+	/// This runs in the background on each tick, once the previous collection is older than
+	/// `SURREAL_STORAGE_USAGE_INTERVAL`. Each batch of keys is read in a short transaction of
+	/// its own, so the statistics are approximate while the tables are being written to. The
+	/// latest statistics are reported by `INFO FOR TABLE ... STATS`.
+	pub async fn collect_usage(&self) -> Result<(), Error> {
+		// Find the tables and their indexes
+		let mut tables = Vec::new();
+		let mut tx = self.transaction(false, false).await?;
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				for tb in tx.all_tb(ns, db).await?.iter() {
+					let tb = tb.name.as_str();
+					let ix = tx.all_tb_indexes(ns, db, tb).await?;
+					let ix = ix.iter().map(|v| v.name.to_raw()).collect::<Vec<_>>();
+					tables.push((ns.to_owned(), db.to_owned(), tb.to_owned(), ix));
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Measure the records, graph edges, and indexes of each table
+		let mut usage = BTreeMap::new();
+		for (ns, db, tb, ixs) in tables {
+			let beg = crate::key::thing::prefix(&ns, &db, &tb);
+			let end = crate::key::thing::suffix(&ns, &db, &tb);
+			let (records, size) = self.measure(beg..end).await?;
+			let beg = crate::key::graph::tbprefix(&ns, &db, &tb);
+			let end = crate::key::graph::tbsuffix(&ns, &db, &tb);
+			let (_, edges) = self.measure(beg..end).await?;
+			let mut indexes = BTreeMap::new();
+			for ix in ixs {
+				let beg: Key = crate::key::index::all::new(&ns, &db, &tb, &ix).into();
+				let end = [beg.as_slice(), &[0xff]].concat();
+				let (_, size) = self.measure(beg..end).await?;
+				indexes.insert(ix, size);
+			}
+			let table = TableUsage {
+				records,
+				size: size + edges,
+				indexes,
+				collected: Default::default(),
+			};
+			usage.insert((ns, db, tb), table);
+		}
+		self.usage.replace(usage);
+		Ok(())
+	}
+
+	/// Count the keys in a range, and the size of their keys and values
+	async fn measure(&self, rng: Range<Key>) -> Result<(u64, u64), Error> {
+		let mut count = 0;
+		let mut size = 0;
+		let mut beg = rng.start;
+		loop {
+			let mut tx = self.transaction(false, false).await?;
+			let batch = tx.scan(beg..rng.end.clone(), *cnf::SCAN_BATCH_SIZE).await?;
+			tx.cancel().await?;
+			for (k, v) in batch.iter() {
+				count += 1;
+				size += (k.len() + v.len()) as u64;
+			}
+			match batch.last() {
+				// Continue from the key following the last key in the batch
+				Some((k, _)) if batch.len() as u32 >= *cnf::SCAN_BATCH_SIZE => {
+					beg = [k.as_slice(), &[0x00]].concat();
+				}
+				_ => return Ok((count, size)),
+			}
+		}
+	}
+
+	/// Populate the indexes which were defined on tables which already held records
+	///
+	/// Defining an index on a table which holds more than a single batch of records does
+	/// not populate the index within the statement. The index is instead marked as building,
+	/// and is populated here, by a background task, with each batch of records indexed in a
+	/// transaction of its own. The index is only used by queries once it is complete. Only
+	/// the node which holds the build lease builds indexes, so that the nodes of a cluster do
+	/// not build the same index at once. Indexes whose build has failed, for instance due to
+	/// a duplicate value in a unique index, store the error on their definition, and are not
+	/// retried until they are defined again or rebuilt. The data of the fields which were
+	/// dropped with `ALTER TABLE ... DROP FIELD` is removed from the records beforehand, so
+	/// that it is not indexed again. Returns the number of indexes which were built.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("rocksdb://data.db").await?;
+	///     ds.build_indexes().await?;
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn build_indexes(&self) -> Result<u64, Error> {
+		// Take the lease to build the indexes, unless another node holds it
+		let mut tx = self.transaction(true, false).await?;
+		if !self.renew_build_lease(&mut tx).await? {
+			tx.cancel().await?;
+			return Ok(0);
+		}
+		match tx.commit().await {
+			Ok(_) => (),
+			// Another node took the lease at the same time
+			Err(Error::TxRetryable) => return Ok(0),
+			Err(e) => return Err(e),
+		}
+		// Remove the data of the dropped fields before building any indexes
+		match self.drop_fields().await {
+			Ok(_) => (),
+			// The lease was taken by another node
+			Err(Error::DsBuildLeaseLost) => return Ok(0),
+			Err(e) => {
+				self.release_build_lease().await?;
+				return Err(e);
+			}
+		}
+		// Find the indexes which are waiting to be built
+		let mut pending = Vec::new();
+		let mut tx = self.transaction(false, false).await?;
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				for tb in tx.all_tb(ns, db).await?.iter() {
+					let tb = tb.name.as_str();
+					for ix in tx.all_tb_indexes(ns, db, tb).await?.iter() {
+						if ix.building && ix.failure.is_none() {
+							pending.push((ns.to_owned(), db.to_owned(), ix.clone()));
+						}
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Build each of the indexes in turn
+		let mut count = 0;
+		let mut res = Ok(());
+		for (ns, db, ix) in pending {
+			match self.build_index(&ns, &db, &ix).await {
+				Ok(true) => count += 1,
+				Ok(false) => (),
+				// The lease was taken by another node
+				Err(Error::DsBuildLeaseLost) => return Ok(count),
+				// A conflict does not fail the build, which is retried later
+				Err(Error::TxRetryable) => res = Err(Error::TxRetryable),
+				// Store the error, so that the build is not retried
+				Err(e) => {
+					warn!("Failed to build the index {} on {} in {ns}/{db}: {e}", ix.name, ix.what);
+					if let Err(e) = self.fail_index(&ns, &db, &ix, &e).await {
+						res = Err(e);
+					}
+				}
+			}
+		}
+		// Release the lease, so that other nodes can build the indexes defined on them
+		self.release_build_lease().await?;
+		res.map(|_| count)
+	}
+
+	/// Release the lease to build the indexes, if it is held by this node
+	async fn release_build_lease(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		if self.renew_build_lease(&mut tx).await? {
+			tx.del(crate::key::root::ib::new()).await?;
+		}
+		match tx.commit().await {
+			// The lease expires if it can not be released
+			Ok(_) | Err(Error::TxRetryable) => Ok(()),
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Remove the data of the fields which were dropped with `ALTER TABLE ... DROP FIELD`
+	/// from the records of their tables, while the build lease is held by this node
+	async fn drop_fields(&self) -> Result<(), Error> {
+		// Find the fields which are waiting to be removed
+		let mut pending = Vec::new();
+		let mut tx = self.transaction(false, false).await?;
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				for tb in tx.all_tb(ns, db).await?.iter() {
+					let tb = tb.name.as_str();
+					let beg = crate::key::table::dr::prefix(ns, db, tb);
+					let end = crate::key::table::dr::suffix(ns, db, tb);
+					for (k, v) in tx.scan(beg..end, u32::MAX).await? {
+						if let Value::Idiom(fd) = Value::from(v) {
+							pending.push((ns.to_owned(), db.to_owned(), tb.to_owned(), k, fd));
+						}
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Remove each of the fields in turn
+		for (ns, db, tb, key, fd) in pending {
+			self.drop_field(&ns, &db, &tb, key, fd).await?;
+		}
+		Ok(())
+	}
+
+	/// Remove the data of a dropped field from the records of a table in batches,
+	/// stopping if the field is defined again while its data is being removed
+	async fn drop_field(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		key: Key,
+		fd: sql::Idiom,
+	) -> Result<(), Error> {
+		let sess = Session::owner().with_ns(ns).with_db(db);
+		let opt = Options::default()
+			.with_id(self.id.0)
+			.with_ns(sess.ns())
+			.with_db(sess.db())
+			.with_auth(sess.au.clone())
+			.with_auth_enabled(self.auth_enabled)
+			.with_strict(self.strict)
+			.with_batch_size(self.batch_size)
+			.new_with_perms(false)
+			.new_with_fields(false)
+			.new_with_events(false);
+		let mut ctx = Context::default();
+		ctx.add_capabilities(self.capabilities.clone());
+		let ctx = sess.context(ctx);
+		let beg = crate::key::thing::prefix(ns, db, tb);
+		let end = crate::key::thing::suffix(ns, db, tb);
+		// Rewrite each batch of records in a transaction of its own
+		let mut beg = beg;
+		loop {
+			let txn = self.transaction(true, false).await?.enclose();
+			let mut run = txn.lock().await;
+			// Stop if the field has been defined again, or the table removed
+			if !run.exi(key.clone()).await? {
+				run.cancel().await?;
+				return Ok(());
+			}
+			// Stop if the lease has been taken by another node
+			match self.renew_build_lease(&mut run).await {
+				Ok(true) => (),
+				res => {
+					run.cancel().await?;
+					return res.and(Err(Error::DsBuildLeaseLost));
+				}
+			}
+			let batch = run.scan(beg.clone()..end.clone(), self.batch_size).await;
+			drop(run);
+			let res = match batch {
+				Ok(batch) => {
+					let what = batch
+						.iter()
+						.map(|(k, _)| {
+							let key: crate::key::thing::Thing = k.into();
+							Value::from(sql::Thing::from((key.tb, key.id)))
+						})
+						.collect();
+					let stm = sql::statements::UpdateStatement {
+						what: sql::Values(what),
+						data: Some(sql::Data::UnsetExpression(vec![fd.clone()])),
+						..Default::default()
+					};
+					stm.compute(&ctx, &opt, &txn, None).await.map(|_| batch)
+				}
+				Err(e) => Err(e),
+			};
+			let mut run = txn.lock().await;
+			let batch = match res {
+				Ok(batch) => match run.commit().await {
+					Ok(_) => batch,
+					// The batch conflicted with another transaction, so rewrite it again
+					Err(Error::TxRetryable) => continue,
+					Err(e) => return Err(e),
+				},
+				Err(e) => {
+					run.cancel().await?;
+					return Err(e);
+				}
+			};
+			match batch.last() {
+				// Continue from the key following the last key in the batch
+				Some((k, _)) if batch.len() as u32 >= self.batch_size => {
+					beg = [k.as_slice(), &[0x00]].concat();
+				}
+				_ => break,
+			}
+		}
+		// The field data has been removed from every record
+		loop {
+			let mut tx = self.transaction(true, false).await?;
+			tx.del(key.clone()).await?;
+			match tx.commit().await {
+				Ok(_) => return Ok(()),
+				Err(Error::TxRetryable) => continue,
+				Err(e) => return Err(e),
+			}
+		}
+	}
+
+	/// Take or renew the lease to build the indexes, returning whether it is held by this node
+	///
+	/// The lease is held by a single node, until it is released or it expires. This ensures
+	/// that the nodes of a cluster, which each run a background task to build indexes, do
+	/// not build the same index at once.
+	async fn renew_build_lease(&self, tx: &mut Transaction) -> Result<bool, Error> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).map_err(|e| {
+			Error::Internal(format!("Clock may have gone backwards: {:?}", e.duration()))
+		})?;
+		let now = now.as_secs();
+		let key = crate::key::root::ib::new();
+		// The lease holds the id of the node, followed by when the lease expires
+		if let Some(val) = tx.get(key.clone()).await? {
+			if let (Some(nd), Some(ts)) = (val.get(..16), val.get(16..24)) {
+				let ts = u64::from_be_bytes(ts.try_into().map_err(|_| Error::Unreachable)?);
+				if nd != self.id.0.as_bytes() && ts > now {
+					return Ok(false);
+				}
+			}
+		}
+		let mut val = self.id.0.as_bytes().to_vec();
+		val.extend_from_slice(&now.saturating_add(*cnf::INDEX_BUILD_LEASE).to_be_bytes());
+		tx.set(key, val).await?;
+		Ok(true)
+	}
+
+	/// Store the error which stopped an index from being built on its definition, so
+	/// that it is reported by `INFO FOR INDEX`, and so that the build is not retried
+	async fn fail_index(
+		&self,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+		err: &Error,
+	) -> Result<(), Error> {
+		loop {
+			let mut tx = self.transaction(true, false).await?;
+			match unchanged(&mut tx, ns, db, ix).await {
+				Ok(true) => (),
+				res => {
+					tx.cancel().await?;
+					return res.map(|_| ());
+				}
+			}
+			let def = DefineIndexStatement {
+				failure: Some(err.to_string().into()),
+				..ix.clone()
+			};
+			tx.set(crate::key::table::ix::new(ns, db, &ix.what, &ix.name), def).await?;
+			match tx.commit().await {
+				Ok(_) => return Ok(()),
+				// The definition conflicted with another transaction, so check it again
+				Err(Error::TxRetryable) => continue,
+				Err(e) => return Err(e),
+			}
+		}
+	}
+
+	/// Populate an index in batches, returning whether it was built,
+	/// or whether it was removed or redefined while it was being built
+	async fn build_index(
+		&self,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<bool, Error> {
+		let tb = ix.what.as_str();
+		let sess = Session::owner().with_ns(ns).with_db(db);
+		let opt = Options::default()
+			.with_id(self.id.0)
+			.with_ns(sess.ns())
+			.with_db(sess.db())
+			.with_auth(sess.au.clone())
+			.with_auth_enabled(self.auth_enabled)
+			.with_strict(self.strict)
+			.with_batch_size(self.batch_size)
+			.new_with_force(true);
+		let mut ctx = Context::default();
+		ctx.add_capabilities(self.capabilities.clone());
+		let ctx = sess.context(ctx);
+		// Remove any data left by a build which was interrupted
+		let mut tx = self.transaction(true, false).await?;
+		tx.delp(crate::key::index::all::new(ns, db, tb, &ix.name), u32::MAX).await?;
+		tx.commit().await?;
+		// Register the progress of the build
+		let beg = crate::key::thing::prefix(ns, db, tb);
+		let end = crate::key::thing::suffix(ns, db, tb);
+		let (total, _) = self.measure(beg.clone()..end.clone()).await?;
+		let build = self.rebuilds.start(ns, db, tb, &ix.name, total);
+		// Index each batch of records in a transaction of its own
+		let mut beg = beg;
+		loop {
+			let txn = self.transaction(true, false).await?.enclose();
+			let mut run = txn.lock().await;
+			// Stop if the index has been removed or redefined
+			match unchanged(&mut run, ns, db, ix).await {
+				Ok(true) => (),
+				res => {
+					run.cancel().await?;
+					if res.is_ok() {
+						self.rebuilds.remove(ns, db, tb, &ix.name);
+					}
+					return res;
+				}
+			}
+			// Stop if the lease has been taken by another node
+			match self.renew_build_lease(&mut run).await {
+				Ok(true) => (),
+				res => {
+					run.cancel().await?;
+					self.rebuilds.remove(ns, db, tb, &ix.name);
+					return res.and(Err(Error::DsBuildLeaseLost));
+				}
+			}
+			let batch = run.scan(beg.clone()..end.clone(), self.batch_size).await;
+			drop(run);
+			let res = match batch {
+				Ok(batch) => {
+					let mut res = Ok(());
+					for (k, v) in batch.iter() {
+						let key: crate::key::thing::Thing = k.into();
+						let val: Value = v.into();
+						let rid = sql::Thing::from((key.tb, key.id));
+						res = crate::doc::reindex(&ctx, &opt, &txn, ix, &rid, &val).await;
+						if res.is_err() {
+							break;
+						}
+					}
+					res.map(|_| batch)
+				}
+				Err(e) => Err(e),
+			};
+			let mut run = txn.lock().await;
+			let batch = match res {
+				Ok(batch) => match run.commit().await {
+					Ok(_) => batch,
+					// The batch conflicted with another transaction, so index it again
+					Err(Error::TxRetryable) => continue,
+					Err(e) => return Err(e),
+				},
+				Err(e) => {
+					run.cancel().await?;
+					return Err(e);
+				}
+			};
+			build.progress(batch.len() as u64);
+			match batch.last() {
+				// Continue from the key following the last key in the batch
+				Some((k, _)) if batch.len() as u32 >= self.batch_size => {
+					beg = [k.as_slice(), &[0x00]].concat();
+				}
+				_ => break,
+			}
+		}
+		// Mark the index as complete, so that it can be used by queries
+		loop {
+			let mut tx = self.transaction(true, false).await?;
+			match unchanged(&mut tx, ns, db, ix).await {
+				Ok(true) => (),
+				res => {
+					tx.cancel().await?;
+					if res.is_ok() {
+						self.rebuilds.remove(ns, db, tb, &ix.name);
+					}
+					return res;
+				}
+			}
+			let def = DefineIndexStatement {
+				building: false,
+				..ix.clone()
+			};
+			tx.set(crate::key::table::ix::new(ns, db, tb, &ix.name), def).await?;
+			match tx.commit().await {
+				Ok(_) => break,
+				// The definition conflicted with another transaction, so check it again
+				Err(Error::TxRetryable) => continue,
+				Err(e) => return Err(e),
+			}
+		}
+		build.finish();
+		Ok(true)
+	}
+
+	/// Remove the keys which have been left inconsistent, for instance by a crash
+	///
+	/// This removes the entries of unique and non-unique indexes which point to records
+	/// which no longer exist, the data left behind by indexes which have been removed, the
+	/// live query registrations of nodes which are no longer running, the graph edge
+	/// pointers to relations which no longer exist, and the chunks of large values which
+	/// no longer belong to a record. Each batch of keys is checked in a transaction of its
+	/// own. Returns what was removed.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("rocksdb://data.db").await?;
+	///     let res = ds.vacuum().await?;
+	///     println!("The vacuum {res}");
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn vacuum(&self) -> Result<Vacuum, Error> {
+		let mut res = Vacuum::default();
+		// Find the tables and their indexes
+		let mut tables = Vec::new();
+		let mut tx = self.transaction(false, false).await?;
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				for tb in tx.all_tb(ns, db).await?.iter() {
+					let tb = tb.name.as_str();
+					let ix = tx.all_tb_indexes(ns, db, tb).await?;
+					tables.push((ns.to_owned(), db.to_owned(), tb.to_owned(), ix));
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Check the indexes and graph edges of each table
+		for (ns, db, tb, ixs) in tables.iter() {
+			let (ns, db, tb) = (ns.as_str(), db.as_str(), tb.as_str());
+			for ix in ixs.iter() {
+				if matches!(ix.index, sql::index::Index::Idx | sql::index::Index::Uniq) {
+					let rng = crate::key::index::Index::range(ns, db, tb, &ix.name);
+					let sweep = Sweep::Index {
+						ns,
+						db,
+					};
+					res.index_entries += self.sweep(rng, sweep).await?;
+				}
+			}
+			// Find the data of any indexes which have been removed
+			let mut beg = crate::key::table::all::new(ns, db, tb).encode()?;
+			beg.push(b'+');
+			let end = [beg.as_slice(), &[0xff]].concat();
+			let len = beg.len();
+			loop {
+				let mut tx = self.transaction(false, false).await?;
+				let key = tx.scan(beg.clone()..end.clone(), 1).await;
+				tx.cancel().await?;
+				let Some((k, _)) = key?.pop() else {
+					break;
+				};
+				let name = k[len..].split(|c| *c == 0).next().unwrap_or_default();
+				let name = String::from_utf8_lossy(name).into_owned();
+				let all: Key = crate::key::index::all::new(ns, db, tb, &name).into();
+				let next = [all.as_slice(), &[0xff]].concat();
+				if !ixs.iter().any(|ix| ix.name.as_str() == name) {
+					res.index_entries += self.sweep(all..next.clone(), Sweep::All).await?;
+				}
+				beg = next;
+			}
+			// Find the graph edge pointers which do not belong to a relation
+			let beg = crate::key::graph::tbprefix(ns, db, tb);
+			let end = crate::key::graph::tbsuffix(ns, db, tb);
+			let sweep = Sweep::Edges {
+				ns,
+				db,
+			};
+			res.graph_edges += self.sweep(beg..end, sweep).await?;
+			// Find the chunks of large values which do not belong to a record
+			let mut beg = crate::key::table::all::new(ns, db, tb).encode()?;
+			beg.push(b'&');
+			let end = [beg.as_slice(), &[0xff]].concat();
+			res.value_chunks += self.sweep(beg..end, Sweep::Chunks).await?;
+		}
+		// Remove the live queries of nodes which are no longer running
+		let mut tx = self.transaction(true, false).await?;
+		match self.vacuum_live_queries(&mut tx, &tables).await {
+			Ok(v) => {
+				tx.commit().await?;
+				res.live_queries = v;
+			}
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		Ok(res)
+	}
+
+	/// Remove the orphaned keys of a key range, checking each batch in its own transaction
+	async fn sweep(&self, rng: Range<Key>, sweep: Sweep<'_>) -> Result<u64, Error> {
+		let limit = *cnf::SCAN_BATCH_SIZE;
+		let mut count = 0;
+		let mut beg = rng.start;
+		loop {
+			let mut tx = self.transaction(true, false).await?;
+			let batch = match tx.scan(beg.clone()..rng.end.clone(), limit).await {
+				Ok(v) => v,
+				Err(e) => {
+					tx.cancel().await?;
+					return Err(e);
+				}
+			};
+			// Continue from the key following the last key in the batch
+			let next = match batch.last() {
+				Some((k, _)) if batch.len() as u32 >= limit => {
+					Some([k.as_slice(), &[0x00]].concat())
+				}
+				_ => None,
+			};
+			match sweep.sweep(&mut tx, batch).await {
+				Ok(v) => {
+					tx.commit().await?;
+					count += v;
+				}
+				Err(e) => {
+					tx.cancel().await?;
+					return Err(e);
+				}
+			}
+			match next {
+				Some(v) => beg = v,
+				None => return Ok(count),
+			}
+		}
+	}
+
+	/// Remove the live query registrations of nodes which are no longer running
+	async fn vacuum_live_queries(
+		&self,
+		tx: &mut Transaction,
+		tables: &[(String, String, String, Arc<[DefineIndexStatement]>)],
+	) -> Result<u64, Error> {
+		let mut count = 0;
+		let mut nodes = HashMap::new();
+		// Check the live queries registered on each node
+		let beg = vec![b'/', b'$'];
+		let end = vec![b'/', b'$' + 1];
+		for (k, v) in tx.getr(beg..end, u32::MAX).await? {
+			if k.get(18..21) != Some(b"!lq".as_slice()) {
+				continue;
+			}
+			let lq = crate::key::node::lq::Lq::decode(&k)?;
+			if !self.is_node_alive(tx, &mut nodes, lq.nd).await? {
+				let tb = String::from_utf8_lossy(&v);
+				tx.del(crate::key::table::lq::new(lq.ns, lq.db, &tb, lq.lq)).await?;
+				tx.del(k.clone()).await?;
+				count += 1;
+			}
+		}
+		// Check the live queries registered on each table
+		for (ns, db, tb, _) in tables.iter() {
+			for lq in tx.scan_tblq(ns, db, tb, u32::MAX).await? {
+				if !self.is_node_alive(tx, &mut nodes, lq.nd.0).await? {
+					tx.del(crate::key::table::lq::new(ns, db, tb, lq.lq.0)).await?;
+					tx.del(crate::key::node::lq::new(lq.nd.0, lq.lq.0, ns, db)).await?;
+					count += 1;
+				}
+			}
+		}
+		Ok(count)
+	}
+
+	/// Check whether a node is this node, or is still registered in the cluster
+	async fn is_node_alive(
+		&self,
+		tx: &mut Transaction,
+		nodes: &mut HashMap<uuid::Uuid, bool>,
+		nd: uuid::Uuid,
+	) -> Result<bool, Error> {
+		if nd == self.id.0 {
+			return Ok(true);
+		}
+		if let Some(v) = nodes.get(&nd) {
+			return Ok(*v);
+		}
+		let alive = tx.get_nd(nd).await?.is_some();
+		nodes.insert(nd, alive);
+		Ok(alive)
+	}
+
+	/// Re-encrypt all of the values which are still encrypted with a previous key
+	///
+	/// After the encryption key has been rotated with [`Datastore::with_previous_encryption_key`],
+	/// values are otherwise re-encrypted when they are next written, or in batches on each tick.
+	/// Once this completes, the previous keys are no longer needed to read the data. Returns
+	/// how many values were re-encrypted.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("rocksdb://data.db")
+	///         .await?
+	///         .with_encryption_key([2; 32])
+	///         .with_previous_encryption_key([1; 32]);
+	///     ds.reencrypt().await?;
+	///     Ok(())
+	/// }
+	/// ```
+	#[cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+	pub async fn reencrypt(&self) -> Result<u64, Error> {
+		let mut count = 0;
+		loop {
+			match self.reencrypt_batch(*cnf::REENCRYPTION_BATCH_SIZE).await {
+				Ok(Some(v)) => count += v,
+				Ok(None) => return Ok(count),
+				// The batch conflicted with another transaction, so scan it again
+				Err(Error::TxRetryable) => (),
+				Err(e) => return Err(e),
+			}
+		}
+	}
+
+	/// Re-encrypt the next batch of the values which are still encrypted with a previous key,
+	/// returning `None` once every value has been scanned
+	async fn reencrypt_batch(&self, limit: usize) -> Result<Option<u64>, Error> {
+		#![allow(unused_variables)]
+		match &self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.reencrypt(limit).await,
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.reencrypt(limit).await,
+			#[allow(unreachable_patterns)]
+			_ => Ok(None),
+		}
+	}
+
+	/// Save a snapshot of all of the data in the datastore to a file
+	///
+	/// This allows an in-memory datastore to be reloaded with [`Datastore::load_snapshot`]
+	/// when it is next started. The snapshot is written to a temporary file which then
+	/// replaces the previous snapshot, so that a crash never leaves a partial snapshot behind.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     ds.save_snapshot("data.snapshot").await?;
+	///     Ok(())
+	/// }
+	/// ```
+	#[cfg(not(target_arch = "wasm32"))]
+	pub async fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+		// Read all of the keys in a single snapshot of the datastore
+		let mut tx = self.transaction(false, false).await?;
+		let res = tx.getr(vec![]..vec![0xff], u32::MAX).await;
+		tx.cancel().await?;
+		let data = bincode::serialize(&res?)?;
+		// Replace the previous snapshot once the new one is written
+		let path = path.as_ref();
+		let tmp = path.with_extension("tmp");
+		tokio::fs::write(&tmp, data).await?;
+		tokio::fs::rename(&tmp, path).await?;
+		Ok(())
+	}
+
+	/// Load a snapshot which was saved with [`Datastore::save_snapshot`] into the datastore
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     ds.load_snapshot("data.snapshot").await?;
+	///     Ok(())
+	/// }
+	/// ```
+	#[cfg(not(target_arch = "wasm32"))]
+	pub async fn load_snapshot(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+		let data = tokio::fs::read(path).await?;
+		let data: Vec<(Key, Val)> = bincode::deserialize(&data)?;
+		let mut tx = self.transaction(true, false).await?;
+		for (k, v) in data {
+			if let Err(e) = tx.set(k, v).await {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+		tx.commit().await
+	}
+
 	/// Parse and execute an SQL query
 	///
 	/// ```rust,no_run
@@ -763,9 +2462,11 @@ impl Datastore {
 		sess: &Session,
 		vars: Variables,
 	) -> Result<Vec<Response>, Error> {
+		// The capabilities of the session override those of the datastore
+		let capabilities = sess.capabilities.as_deref().unwrap_or(&self.capabilities);
 		// Check if anonymous actors can execute queries when auth is enabled
 		// TODO(sgirones): Check this as part of the authoritzation layer
-		if self.auth_enabled && sess.au.is_anon() && !self.capabilities.allows_guest_access() {
+		if self.auth_enabled && sess.au.is_anon() && !capabilities.allows_guest_access() {
 			return Err(IamError::NotAllowed {
 				actor: "anonymous".to_string(),
 				action: "process".to_string(),
@@ -781,12 +2482,18 @@ impl Datastore {
 			.with_live(sess.live())
 			.with_auth(sess.au.clone())
 			.with_auth_enabled(self.auth_enabled)
-			.with_strict(self.strict);
+			.with_strict(sess.strict.unwrap_or(self.strict))
+			.with_isolation(self.isolation)
+			.with_batch_size(self.batch_size)
+			.with_retries(self.retries)
+			.with_split_size(self.split_size);
+		// Register the session activity
+		self.sessions.touch(sess)?;
 		// Create a new query executor
 		let mut exe = Executor::new(self);
 		// Create a default context
 		let mut ctx = Context::default();
-		ctx.add_capabilities(self.capabilities.clone());
+		ctx.add_capabilities(capabilities.clone());
 		// Set the global query timeout
 		if let Some(timeout) = self.query_timeout {
 			ctx.add_timeout(timeout);
@@ -795,12 +2502,36 @@ impl Datastore {
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
 		}
+		// Setup the session registry
+		ctx.add_sessions(Some(&self.sessions));
+		// Setup the access statistics registry
+		ctx.add_stats(Some(&self.stats));
+		// Setup the index rebuild registry
+		ctx.add_rebuilds(Some(&self.rebuilds));
+		// Setup the storage compaction status
+		ctx.add_compactions(Some(&self.compactions));
+		// Setup the storage usage registry
+		ctx.add_usage(Some(&self.usage));
+		// Setup the datastore for separate transactions
+		ctx.add_datastore(self);
+		// Register the running query
+		let _inflight = self.inflight.enter(ctx.add_cancel())?;
 		// Start an execution context
 		let ctx = sess.context(ctx);
 		// Store the query variables
 		let ctx = vars.attach(ctx)?;
 		// Process all statements
-		exe.execute(ctx, opt, ast).await
+		let res = exe.execute(ctx, opt, ast).await?;
+		// Track any live queries started by the session
+		if let Some(id) = &sess.id {
+			for v in res.iter() {
+				if let (QueryType::Live, Ok(Value::Uuid(lq))) = (&v.query_type, &v.result) {
+					self.sessions.add_live(id, lq.clone());
+				}
+			}
+		}
+		// Return the responses
+		Ok(res)
 	}
 
 	/// Ensure a SQL [`Value`] is fully computed
@@ -828,9 +2559,11 @@ impl Datastore {
 		sess: &Session,
 		vars: Variables,
 	) -> Result<Value, Error> {
+		// The capabilities of the session override those of the datastore
+		let capabilities = sess.capabilities.as_deref().unwrap_or(&self.capabilities);
 		// Check if anonymous actors can compute values when auth is enabled
 		// TODO(sgirones): Check this as part of the authoritzation layer
-		if self.auth_enabled && !self.capabilities.allows_guest_access() {
+		if self.auth_enabled && !capabilities.allows_guest_access() {
 			return Err(IamError::NotAllowed {
 				actor: "anonymous".to_string(),
 				action: "compute".to_string(),
@@ -838,6 +2571,8 @@ impl Datastore {
 			}
 			.into());
 		}
+		// Register the session activity
+		self.sessions.touch(sess)?;
 		// Create a new query options
 		let opt = Options::default()
 			.with_id(self.id.0)
@@ -846,11 +2581,14 @@ impl Datastore {
 			.with_live(sess.live())
 			.with_auth(sess.au.clone())
 			.with_auth_enabled(self.auth_enabled)
-			.with_strict(self.strict);
+			.with_strict(sess.strict.unwrap_or(self.strict))
+			.with_isolation(self.isolation)
+			.with_batch_size(self.batch_size)
+			.with_retries(self.retries);
 		// Create a default context
 		let mut ctx = Context::default();
 		// Set context capabilities
-		ctx.add_capabilities(self.capabilities.clone());
+		ctx.add_capabilities(capabilities.clone());
 		// Set the global query timeout
 		if let Some(timeout) = self.query_timeout {
 			ctx.add_timeout(timeout);
@@ -859,6 +2597,20 @@ impl Datastore {
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
 		}
+		// Setup the session registry
+		ctx.add_sessions(Some(&self.sessions));
+		// Setup the access statistics registry
+		ctx.add_stats(Some(&self.stats));
+		// Setup the index rebuild registry
+		ctx.add_rebuilds(Some(&self.rebuilds));
+		// Setup the storage compaction status
+		ctx.add_compactions(Some(&self.compactions));
+		// Setup the storage usage registry
+		ctx.add_usage(Some(&self.usage));
+		// Setup the datastore for separate transactions
+		ctx.add_datastore(self);
+		// Register the running query
+		let _inflight = self.inflight.enter(ctx.add_cancel())?;
 		// Start an execution context
 		let ctx = sess.context(ctx);
 		// Store the query variables
@@ -910,23 +2662,249 @@ impl Datastore {
 		ns: String,
 		db: String,
 		chn: Sender<Vec<u8>>,
-	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+	) -> Result<impl Future<Output = Result<(), Error>> + '_, Error> {
+		self.export_with(sess, ns, db, ExportConfig::default(), chn).await
+	}
+
+	/// Prepares a database export, in the format and for the tables of the export config
+	#[instrument(skip(self, sess, chn))]
+	pub async fn export_with(
+		&self,
+		sess: &Session,
+		ns: String,
+		db: String,
+		cfg: ExportConfig,
+		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>> + '_, Error> {
+		self.export_with_progress(sess, ns, db, cfg, chn, |_| {}).await
+	}
+
+	/// Prepares a database export, which reports its progress after every batch of records
+	///
+	/// The export never keeps a transaction open while it waits for the channel. The
+	/// definitions of the database are read in one short transaction, and the records
+	/// are read in batches, each in a short snapshot transaction of its own, so exports
+	/// of large databases do not hold up concurrent writes. Each batch is consistent in
+	/// itself, but records which change while the export runs may be exported in either
+	/// state. Use [`Datastore::backup`] for a backup of a single point in time.
+	#[instrument(skip(self, sess, chn, progress))]
+	pub async fn export_with_progress<F>(
+		&self,
+		sess: &Session,
+		ns: String,
+		db: String,
+		cfg: ExportConfig,
+		chn: Sender<Vec<u8>>,
+		mut progress: F,
+	) -> Result<impl Future<Output = Result<(), Error>> + '_, Error>
+	where
+		F: FnMut(&ExportProgress) + Send + 'static,
+	{
 		// Skip auth for Anonymous users if auth is disabled
 		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
 		if !skip_auth {
 			sess.au.is_allowed(Action::View, &ResourceKind::Any.on_db(&ns, &db))?;
 		}
-		// Create a new readonly transaction
-		let mut txn = self.transaction(false, false).await?;
 		// Return an async export job
 		Ok(async move {
-			// Process the export
-			txn.export(&ns, &db, chn).await?;
+			// Only export the changes since the specified time
+			if cfg.since.is_some() {
+				let mut txn = self.transaction(false, false).await?;
+				let res = txn.export_with(&ns, &db, &cfg, chn).await;
+				txn.cancel().await?;
+				return res;
+			}
+			let sql = cfg.format == ExportFormat::Sql;
+			// Read the definitions before writing any of them
+			let tbs = {
+				let (snd, rcv) = channel::unbounded();
+				let mut txn = self.transaction(false, false).await?;
+				let res = match sql {
+					true => txn.export_definitions(&ns, &db, &cfg, &snd).await,
+					false => txn.all_tb(&ns, &db).await.map(|tbs| {
+						tbs.iter()
+							.filter(|tb| cfg.includes(&tb.name))
+							.map(|tb| tb.name.to_raw())
+							.collect()
+					}),
+				};
+				txn.cancel().await?;
+				let tbs = res?;
+				while let Ok(v) = rcv.try_recv() {
+					chn.send(v).await?;
+				}
+				tbs
+			};
+			// Output TABLE data
+			let mut state = ExportProgress {
+				tables_total: tbs.len(),
+				..Default::default()
+			};
+			if sql && !tbs.is_empty() {
+				chn.send(export::section("TRANSACTION")).await?;
+				chn.send(bytes!("BEGIN TRANSACTION;")).await?;
+				chn.send(bytes!("")).await?;
+			}
+			for tb in tbs {
+				if sql {
+					chn.send(export::section(&format!("TABLE DATA: {tb}"))).await?;
+				}
+				state.table_records = 0;
+				let mut nxt = None;
+				loop {
+					// Close the transaction before the batch is written
+					let mut txn =
+						self.start_transaction(false, false, Some(Isolation::Snapshot)).await?;
+					let res = txn
+						.export_batch(&ns, &db, &tb, cfg.format, nxt, *cnf::EXPORT_BATCH_SIZE)
+						.await;
+					txn.cancel().await?;
+					let (lines, next) = res?;
+					state.table = tb.clone();
+					state.table_records += lines.len() as u64;
+					state.records += lines.len() as u64;
+					for line in lines {
+						chn.send(line).await?;
+					}
+					progress(&state);
+					// Exit when settled
+					match next {
+						Some(k) => nxt = Some(k),
+						None => break,
+					}
+				}
+				if sql {
+					chn.send(bytes!("")).await?;
+				}
+				state.tables_done += 1;
+			}
+			if sql && state.tables_total > 0 {
+				chn.send(export::section("TRANSACTION")).await?;
+				chn.send(bytes!("COMMIT TRANSACTION;")).await?;
+				chn.send(bytes!("")).await?;
+			}
 			// Everything ok
 			Ok(())
 		})
 	}
 
+	/// Prepares a point-in-time backup of a database, or of a whole namespace when no database is given
+	///
+	/// The backup reads from a snapshot of the datastore which is taken before this function
+	/// returns, so writes which happen while the backup runs are not included in it.
+	#[instrument(skip(self, sess, chn))]
+	pub async fn backup(
+		&self,
+		sess: &Session,
+		ns: String,
+		db: Option<String>,
+		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			let res = match &db {
+				Some(db) => ResourceKind::Any.on_db(&ns, db),
+				None => ResourceKind::Any.on_ns(&ns),
+			};
+			sess.au.is_allowed(Action::View, &res)?;
+		}
+		// Create a new readonly snapshot transaction
+		let mut txn = self.start_transaction(false, false, Some(Isolation::Snapshot)).await?;
+		// Return an async backup job
+		Ok(async move {
+			// Process the backup
+			let res = txn.backup(&ns, db.as_deref(), chn).await;
+			// Release the snapshot
+			txn.cancel().await?;
+			res
+		})
+	}
+
+	/// Prepares an incremental backup of a database, containing the changes made since an earlier backup
+	///
+	/// The versionstamp of the earlier backup can be read from it with [`BackupInfo::read`].
+	/// The changes are read from the change feeds of the tables, so every table needs to be
+	/// defined with a `CHANGEFEED` which keeps the changes for longer than the time between backups.
+	#[instrument(skip(self, sess, chn))]
+	pub async fn backup_since(
+		&self,
+		sess: &Session,
+		ns: String,
+		db: String,
+		since: u64,
+		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::View, &ResourceKind::Any.on_db(&ns, &db))?;
+		}
+		// Create a new readonly snapshot transaction
+		let mut txn = self.start_transaction(false, false, Some(Isolation::Snapshot)).await?;
+		// Return an async backup job
+		Ok(async move {
+			// Process the backup
+			let res = txn.backup_since(&ns, &db, since, chn).await;
+			// Release the snapshot
+			txn.cancel().await?;
+			res
+		})
+	}
+
+	/// Writes a point-in-time backup of a database, or of a whole namespace, to a file
+	///
+	/// The file is only replaced once the backup has been written completely.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::dbs::Session;
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let ses = Session::owner();
+	///     ds.backup_to_file(&ses, "test".into(), Some("test".into()), "backup.surql").await?;
+	///     Ok(())
+	/// }
+	/// ```
+	#[cfg(not(target_arch = "wasm32"))]
+	#[instrument(skip(self, sess, path))]
+	pub async fn backup_to_file(
+		&self,
+		sess: &Session,
+		ns: String,
+		db: Option<String>,
+		path: impl AsRef<Path>,
+	) -> Result<(), Error> {
+		use tokio::io::AsyncWriteExt;
+		// Start the backup from the current snapshot
+		let (snd, rcv) = channel::bounded(10);
+		let backup = self.backup(sess, ns, db, snd).await?;
+		// Write the backup next to the previous one
+		let path = path.as_ref();
+		let tmp = path.with_extension("tmp");
+		let mut file = tokio::fs::File::create(&tmp).await?;
+		let write = async {
+			// Stop the backup if the file can not be written
+			let rcv = rcv;
+			while let Ok(v) = rcv.recv().await {
+				file.write_all(&v).await?;
+			}
+			file.flush().await?;
+			Ok::<(), Error>(())
+		};
+		let (res, out) = futures::join!(backup, write);
+		if let Err(e) = res.and(out) {
+			let _ = tokio::fs::remove_file(&tmp).await;
+			return Err(e);
+		}
+		// Replace the previous backup once the new one is written
+		tokio::fs::rename(&tmp, path).await?;
+		Ok(())
+	}
+
 	/// Performs a database import from SQL
 	#[instrument(skip(self, sess, sql))]
 	pub async fn import(&self, sql: &str, sess: &Session) -> Result<Vec<Response>, Error> {
@@ -941,4 +2919,84 @@ impl Datastore {
 		// Execute the SQL import
 		self.execute(sql, sess, None).await
 	}
+
+	/// Restores a full database backup, followed by the incremental backups which were taken after it
+	///
+	/// The backups are checked to follow on from one another before any of them is imported.
+	#[instrument(skip(self, backups, sess))]
+	pub async fn restore(&self, backups: &[impl AsRef<str>], sess: &Session) -> Result<(), Error> {
+		let invalid = |message: String| Error::InvalidBackupChain {
+			message,
+		};
+		// Check that the backups form a chain
+		let mut last: Option<u64> = None;
+		for (i, backup) in backups.iter().enumerate() {
+			let Some(info) = BackupInfo::read(backup.as_ref()) else {
+				return Err(invalid(format!("backup {} is not a database backup", i + 1)));
+			};
+			match (last, info.since) {
+				(None, None) => (),
+				(None, Some(_)) => {
+					return Err(invalid("the first backup is not a full backup".to_string()))
+				}
+				(Some(_), None) => {
+					return Err(invalid(format!("backup {} is not an incremental backup", i + 1)))
+				}
+				(Some(last), Some(since)) if last != since => {
+					return Err(invalid(format!(
+						"backup {} continues from versionstamp {since}, not from versionstamp {last}",
+						i + 1
+					)))
+				}
+				_ => (),
+			}
+			last = Some(info.versionstamp);
+		}
+		// Import the backups in order
+		for backup in backups {
+			for res in self.import(backup.as_ref(), sess).await? {
+				res.result?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Restores every database from the backups archived to an object storage bucket
+	///
+	/// Each database is restored from its latest full backup in the `s3://bucket/prefix`
+	/// or `gcs://bucket/prefix` path, followed by the incremental backups archived after it.
+	#[cfg(all(feature = "archive", not(target_arch = "wasm32")))]
+	pub async fn restore_archive(&self, path: &str) -> Result<(), Error> {
+		Archive::new(path, Duration::ZERO)?.restore(self).await
+	}
+
+	/// Performs a database import, from SQL or from JSON lines
+	#[instrument(skip(self, sess, text))]
+	pub async fn import_with(
+		&self,
+		text: &str,
+		sess: &Session,
+		format: ExportFormat,
+	) -> Result<Vec<Response>, Error> {
+		match format {
+			ExportFormat::Sql => self.import(text, sess).await,
+			ExportFormat::Json => self.import(&json_to_sql(text)?, sess).await,
+		}
+	}
+}
+
+/// Check whether an index is still defined as it was when its build was started
+async fn unchanged(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	ix: &DefineIndexStatement,
+) -> Result<bool, Error> {
+	match tx.get_and_cache_tb_index(ns, db, &ix.what, &ix.name).await {
+		Ok(v) => Ok(v.as_ref() == ix),
+		Err(Error::IxNotFound {
+			..
+		}) => Ok(false),
+		Err(e) => Err(e),
+	}
 }
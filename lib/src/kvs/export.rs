@@ -0,0 +1,187 @@
+use crate::err::Error;
+use crate::sql::paths::EDGE;
+use crate::sql::paths::ID;
+use crate::sql::paths::IN;
+use crate::sql::paths::OUT;
+use crate::sql::Datetime;
+use crate::sql::Thing;
+use crate::sql::Value;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The format in which the contents of a database are exported or imported
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+	/// SurrealQL statements, which define the database and create its records
+	#[default]
+	Sql,
+	/// JSON lines, with one record per line
+	///
+	/// Only the records of the tables are exported, without the definitions of the database.
+	Json,
+}
+
+/// Which contents of a database are exported, and in which format
+///
+/// The default exports the full database as SurrealQL.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ExportConfig {
+	/// The tables to export, or all tables when not specified
+	///
+	/// The definitions of the database, such as its users and functions, are always exported.
+	pub tables: Option<Vec<String>>,
+	/// The format of the export
+	pub format: ExportFormat,
+	/// Only exports the changes which were made to the tables since this time
+	///
+	/// The changes are read from the change feeds of the tables, so only tables
+	/// which are defined with a `CHANGEFEED` are included, and the definitions of
+	/// the database are not exported.
+	pub since: Option<Datetime>,
+}
+
+impl ExportConfig {
+	/// Checks whether a table is included in the export
+	pub(crate) fn includes(&self, tb: &str) -> bool {
+		match &self.tables {
+			Some(tables) => tables.iter().any(|t| t == tb),
+			None => true,
+		}
+	}
+}
+
+/// The progress of an export, which is reported after every batch of records
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportProgress {
+	/// The table whose records are being exported
+	pub table: String,
+	/// How many records of the table have been exported
+	pub table_records: u64,
+	/// How many records have been exported from all of the tables
+	pub records: u64,
+	/// How many tables have been exported completely
+	pub tables_done: usize,
+	/// How many tables are exported
+	pub tables_total: usize,
+}
+
+/// The position of a database backup in the change feeds of the database
+///
+/// Written at the start of every database backup, so that an incremental backup
+/// can continue from it, and so that a chain of backups can be restored in order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct BackupInfo {
+	/// The versionstamp of the backup which an incremental backup continues from,
+	/// or `None` for a full backup
+	pub since: Option<u64>,
+	/// The versionstamp of the database when the backup was taken
+	pub versionstamp: u64,
+}
+
+impl BackupInfo {
+	const SINCE: &'static str = "-- SINCE: ";
+	const VERSIONSTAMP: &'static str = "-- VERSIONSTAMP: ";
+
+	/// Reads the position from the comments at the start of a backup
+	pub fn read(sql: &str) -> Option<Self> {
+		let mut since = None;
+		// The position is written before any statements
+		for line in sql.lines().map(str::trim).take_while(|l| l.is_empty() || l.starts_with("--")) {
+			if let Some(v) = line.strip_prefix(Self::SINCE) {
+				since = Some(v.parse().ok()?);
+			} else if let Some(v) = line.strip_prefix(Self::VERSIONSTAMP) {
+				return Some(Self {
+					since,
+					versionstamp: v.parse().ok()?,
+				});
+			}
+		}
+		None
+	}
+
+	/// Returns the comments which are written at the start of a backup
+	pub(crate) fn lines(&self) -> Vec<String> {
+		let mut lines = vec![
+			"-- ------------------------------".to_string(),
+			"-- BACKUP".to_string(),
+			"-- ------------------------------".to_string(),
+			String::new(),
+		];
+		if let Some(since) = self.since {
+			lines.push(format!("{}{since}", Self::SINCE));
+		}
+		lines.push(format!("{}{}", Self::VERSIONSTAMP, self.versionstamp));
+		lines.push(String::new());
+		lines
+	}
+}
+
+/// Returns the comment which starts a section of an export
+pub(crate) fn section(title: &str) -> Vec<u8> {
+	format!("-- ------------------------------\n-- {title}\n-- ------------------------------\n\n")
+		.into_bytes()
+}
+
+/// Returns the SurrealQL statement which creates a record
+pub(crate) fn record_sql(t: &Thing, v: &Value) -> String {
+	// Check if this is a graph edge
+	match (v.pick(&*EDGE), v.pick(&*IN), v.pick(&*OUT)) {
+		// This is a graph edge record
+		(Value::Bool(true), Value::Thing(l), Value::Thing(r)) => {
+			format!("RELATE {l} -> {t} -> {r} CONTENT {v};")
+		}
+		// This is a normal record
+		_ => format!("UPDATE {t} CONTENT {v};"),
+	}
+}
+
+/// Converts an export in JSON lines into SurrealQL statements
+///
+/// Each line is either a record, or a change which was exported from a change feed.
+pub(crate) fn json_to_sql(json: &str) -> Result<String, Error> {
+	let mut sql = String::from("OPTION IMPORT;\nBEGIN TRANSACTION;\n");
+	for (i, line) in json.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+		let invalid = |message: String| Error::InvalidImport {
+			line: i + 1,
+			message,
+		};
+		let value = crate::sql::json(line).map_err(|e| invalid(e.to_string()))?;
+		let statement = match (value.pick(&["update".into()]), value.pick(&["delete".into()])) {
+			// This is a change which updated a record
+			(v @ Value::Object(_), Value::None) => record(v),
+			// This is a change which deleted a record
+			(Value::None, v @ Value::Object(_)) => {
+				thing(v.pick(&*ID)).map(|t| format!("DELETE {t};"))
+			}
+			// This is a record
+			_ => record(value),
+		};
+		sql.push_str(&statement.map_err(invalid)?);
+		sql.push('\n');
+	}
+	sql.push_str("COMMIT TRANSACTION;\n");
+	Ok(sql)
+}
+
+/// Returns the statement which creates a record exported as JSON, where record ids are strings
+fn record(mut v: Value) -> Result<String, String> {
+	let t = thing(v.pick(&*ID))?;
+	v.put(&*ID, Value::Thing(t.clone()));
+	// Graph edges also link their records by id
+	if v.pick(&*EDGE).is_true() {
+		let l = thing(v.pick(&*IN))?;
+		let r = thing(v.pick(&*OUT))?;
+		v.put(&*IN, Value::Thing(l));
+		v.put(&*OUT, Value::Thing(r));
+	}
+	Ok(record_sql(&t, &v))
+}
+
+fn thing(v: Value) -> Result<Thing, String> {
+	match v {
+		Value::Strand(s) => crate::sql::thing(s.as_str()).map_err(|e| e.to_string()),
+		Value::Thing(t) => Ok(t),
+		v => Err(format!("Expected a record id, but found {v}")),
+	}
+}
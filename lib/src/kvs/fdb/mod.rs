@@ -4,10 +4,12 @@ use crate::err::Error;
 use crate::kvs::Check;
 use crate::kvs::Key;
 use crate::kvs::Val;
+use crate::sql::Isolation;
 use crate::vs::{u64_to_versionstamp, Versionstamp};
 use futures::TryStreamExt;
 use std::ops::Range;
 use std::sync::Arc;
+use std::time::Duration;
 // We use it to work-around the fact that foundationdb-rs' Transaction
 // have incompatible lifetimes for the cancel and the commit methods.
 // More concretely, fdb-rs's cancel/commit takes the receiver as just `self`,
@@ -16,7 +18,7 @@ use std::sync::Arc;
 // self or the fdb-rs Transaction it contains.
 //
 // We use mutex from the futures crate instead of the std's due to https://rust-lang.github.io/wg-async/vision/submitted_stories/status_quo/alan_thinks_he_needs_async_locks.html.
-use foundationdb::options::MutationType;
+use foundationdb::options::{ConflictRangeType, DatabaseOption, MutationType, TransactionOption};
 use futures::lock::Mutex;
 use once_cell::sync::Lazy;
 
@@ -27,6 +29,41 @@ use once_cell::sync::Lazy;
 pub struct Datastore {
 	db: foundationdb::Database,
 	_fdbnet: Arc<foundationdb::api::NetworkAutoStop>,
+	// The timeout of each transaction
+	timeout: Option<Duration>,
+}
+
+/// The options used when connecting to a FoundationDB cluster
+#[derive(Clone, Debug, Default)]
+pub struct FdbOptions {
+	// The timeout of each transaction
+	pub(crate) timeout: Option<Duration>,
+	// How many times a conflicting statement is retried
+	pub(crate) retry_limit: Option<u32>,
+	// The datacenter in which this node runs
+	pub(crate) datacenter_id: Option<String>,
+}
+
+impl FdbOptions {
+	/// Specify how long a transaction may run before it is cancelled
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Specify how many times a statement is retried, when its transaction conflicts with another transaction
+	pub fn with_retry_limit(mut self, limit: u32) -> Self {
+		self.retry_limit = Some(limit);
+		self
+	}
+
+	/// Specify the datacenter in which this node runs
+	///
+	/// This allows the client to prefer reading from the storage servers in the same datacenter.
+	pub fn with_datacenter_id(mut self, id: impl Into<String>) -> Self {
+		self.datacenter_id = Some(id.into());
+		self
+	}
 }
 
 pub struct Transaction {
@@ -36,6 +73,8 @@ pub struct Transaction {
 	lock: bool,
 	/// Is the transaction writeable?
 	write: bool,
+	/// The isolation level requested for this transaction
+	isolation: Option<Isolation>,
 	/// Should we check unhandled transactions?
 	check: Check,
 	/// The underlying datastore transaction
@@ -79,37 +118,52 @@ impl Datastore {
 	/// An empty string results in using the default cluster file placed
 	/// at a system-dependent location defined by FDB.
 	/// See https://apple.github.io/foundationdb/administration.html#default-cluster-file for more information on that.
-	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+	pub(crate) async fn new(path: &str, options: &FdbOptions) -> Result<Datastore, Error> {
 		static FDBNET: Lazy<Arc<foundationdb::api::NetworkAutoStop>> =
 			Lazy::new(|| Arc::new(unsafe { foundationdb::boot() }));
 		let _fdbnet = (*FDBNET).clone();
 
-		match foundationdb::Database::from_path(path) {
-			Ok(db) => Ok(Datastore {
-				db,
-				_fdbnet,
-			}),
-			Err(e) => Err(Error::Ds(e.to_string())),
+		let db = foundationdb::Database::from_path(path).map_err(|e| Error::Ds(e.to_string()))?;
+		// Set the datacenter in which this node runs
+		if let Some(id) = &options.datacenter_id {
+			db.set_option(DatabaseOption::DatacenterId(id.clone()))
+				.map_err(|e| Error::Ds(format!("Unable to set the datacenter id: {}", e)))?;
 		}
+		Ok(Datastore {
+			db,
+			_fdbnet,
+			timeout: options.timeout,
+		})
 	}
 	/// Start a new transaction
-	pub(crate) async fn transaction(&self, write: bool, lock: bool) -> Result<Transaction, Error> {
+	pub(crate) async fn transaction(
+		&self,
+		write: bool,
+		lock: bool,
+		isolation: Option<Isolation>,
+	) -> Result<Transaction, Error> {
 		// Specify the check level
 		#[cfg(not(debug_assertions))]
 		let check = Check::Warn;
 		#[cfg(debug_assertions)]
 		let check = Check::Panic;
 		// Create a new transaction
-		match self.db.create_trx() {
-			Ok(inner) => Ok(Transaction {
-				done: false,
-				check,
-				write,
-				lock,
-				inner: Arc::new(Mutex::new(Some(inner))),
-			}),
-			Err(e) => Err(Error::Tx(e.to_string())),
+		let inner = self.db.create_trx().map_err(|e| Error::Tx(e.to_string()))?;
+		// Set the timeout of the transaction
+		if let Some(timeout) = self.timeout {
+			let ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+			inner
+				.set_option(TransactionOption::Timeout(ms))
+				.map_err(|e| Error::Tx(format!("Unable to set the transaction timeout: {}", e)))?;
 		}
+		Ok(Transaction {
+			done: false,
+			check,
+			write,
+			lock,
+			isolation,
+			inner: Arc::new(Mutex::new(Some(inner))),
+		})
 	}
 }
 
@@ -130,8 +184,25 @@ impl Transaction {
 	/// use the standard fdb inner to make transactions Serializable.
 	/// In case the inner is write, we assume the user never wants to lose serializability
 	/// so we go with the standard fdb serializable inner in that case too.
+	/// An isolation level requested for the transaction overrides both of these.
 	fn snapshot(&self) -> bool {
-		!self.write && !self.lock
+		match self.isolation {
+			Some(Isolation::Snapshot) => true,
+			Some(Isolation::Serializable) => false,
+			None => !self.write && !self.lock,
+		}
+	}
+	/// Snapshot reads don't conflict with concurrent writes, so with snapshot
+	/// isolation we check the keys which are written for conflicts instead.
+	fn conflict(&self, inner: &foundationdb::Transaction, key: &[u8]) -> Result<(), Error> {
+		if self.isolation == Some(Isolation::Snapshot) {
+			let mut end = key.to_vec();
+			end.push(0x00);
+			inner.add_conflict_range(key, &end, ConflictRangeType::Read).map_err(|e| {
+				Error::Tx(format!("Unable to add a conflict range in FoundationDB: {}", e))
+			})?;
+		}
+		Ok(())
 	}
 	/// Cancel a transaction
 	pub(crate) async fn cancel(&mut self) -> Result<(), Error> {
@@ -184,6 +255,13 @@ impl Transaction {
 		};
 		match r {
 			Ok(_r) => {}
+			Err(e) if e.is_retryable_not_committed() => {
+				return Err(Error::TxRetryable);
+			}
+			// The transaction exceeded the 10MB size limit
+			Err(e) if e.code() == 2101 => {
+				return Err(Error::TxTooLarge);
+			}
 			Err(e) => {
 				return Err(Error::Tx(format!("Transaction commit error: {}", e)));
 			}
@@ -241,6 +319,25 @@ impl Transaction {
 			.map(|v| v.as_ref().map(|v| v.to_vec()))
 			.map_err(|e| Error::Tx(format!("Unable to get kv from FoundationDB: {}", e)))
 	}
+	/// Fetch many keys from the database concurrently
+	pub(crate) async fn multi_get(&mut self, keys: Vec<Key>) -> Result<Vec<Option<Val>>, Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the keys
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		let snapshot = self.snapshot();
+		futures::future::try_join_all(keys.iter().map(|key| async move {
+			inner
+				.get(key, snapshot)
+				.await
+				.map(|v| v.as_ref().map(|v| v.to_vec()))
+				.map_err(|e| Error::Tx(format!("Unable to get kv from FoundationDB: {}", e)))
+		}))
+		.await
+	}
 	/// Obtain a new change timestamp for a key
 	/// which is replaced with the current timestamp when the transaction is committed.
 	/// NOTE: This should be called when composing the change feed entries for this transaction,
@@ -285,6 +382,7 @@ impl Transaction {
 		let val = &val[..];
 		let inner = self.inner.lock().await;
 		let inner = inner.as_ref().unwrap();
+		self.conflict(inner, key)?;
 		inner.set(key, val);
 		// Return result
 		Ok(())
@@ -322,6 +420,7 @@ impl Transaction {
 		let val: &[u8] = &val[..];
 		let inner = self.inner.lock().await;
 		let inner = inner.as_ref().unwrap();
+		self.conflict(inner, key)?;
 		inner.set(key, val);
 		// Return result
 		Ok(())
@@ -428,10 +527,33 @@ impl Transaction {
 		let key: &[u8] = key.as_slice();
 		let inner = self.inner.lock().await;
 		let inner = inner.as_ref().unwrap();
+		self.conflict(inner, key)?;
 		inner.clear(key);
 		// Return result
 		Ok(())
 	}
+	/// Delete every key within a range
+	pub(crate) async fn delr(&mut self, rng: Range<Key>) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Delete the keys
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		if self.isolation == Some(Isolation::Snapshot) {
+			inner.add_conflict_range(&rng.start, &rng.end, ConflictRangeType::Read).map_err(
+				|e| Error::Tx(format!("Unable to add a conflict range in FoundationDB: {}", e)),
+			)?;
+		}
+		inner.clear_range(&rng.start, &rng.end);
+		// Return result
+		Ok(())
+	}
 	/// Delete a key
 	pub(crate) async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
 	where
@@ -5,10 +5,23 @@ use crate::kvs::Check;
 use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
 use std::ops::Range;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_futures::JsFuture;
+
+/// The length of the nonce prepended to encrypted values
+const NONCE_LEN: usize = 12;
 
 pub struct Datastore {
 	db: indxdb::Db,
+	/// The key prefix of the selected store
+	prefix: Key,
+	/// The cipher used to encrypt the persisted values
+	cipher: Option<ChaCha20Poly1305>,
 }
 
 pub struct Transaction {
@@ -20,6 +33,10 @@ pub struct Transaction {
 	check: Check,
 	/// The underlying datastore transaction
 	inner: indxdb::Tx,
+	/// The key prefix of the selected store
+	prefix: Key,
+	/// The cipher used to encrypt the persisted values
+	cipher: Option<ChaCha20Poly1305>,
 }
 
 impl Drop for Transaction {
@@ -56,12 +73,35 @@ impl Datastore {
 	/// Open a new database
 	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
 		match indxdb::db::new(path).await {
-			Ok(db) => Ok(Datastore {
-				db,
-			}),
+			Ok(db) => {
+				// Ask the browser not to evict the data
+				#[cfg(target_arch = "wasm32")]
+				persist().await;
+				// Return the datastore
+				Ok(Datastore {
+					db,
+					prefix: Key::new(),
+					cipher: None,
+				})
+			}
 			Err(e) => Err(Error::Ds(e.to_string())),
 		}
 	}
+	/// Select the store, within the database, in which the data is persisted
+	pub(crate) fn set_store(&mut self, name: &str) {
+		// Keys always start with a `/`, so keys prefixed
+		// with a null byte never overlap the default store
+		self.prefix = [&[0x00], name.as_bytes(), &[0x00]].concat();
+	}
+	/// Encrypt the persisted values with the specified key
+	pub(crate) fn set_encryption_key(&mut self, key: &[u8; 32]) {
+		self.cipher = Some(ChaCha20Poly1305::new(key.into()));
+	}
+	/// Flush any buffered writes to disk
+	pub(crate) async fn flush(&self) -> Result<(), Error> {
+		// Each transaction is persisted when it is committed
+		Ok(())
+	}
 	/// Start a new transaction
 	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
 		// Specify the check level
@@ -76,6 +116,8 @@ impl Datastore {
 				check,
 				write,
 				inner,
+				prefix: self.prefix.clone(),
+				cipher: self.cipher.clone(),
 			}),
 			Err(e) => Err(Error::Tx(e.to_string())),
 		}
@@ -83,6 +125,44 @@ impl Datastore {
 }
 
 impl Transaction {
+	/// Prefix a key with the selected store
+	fn key(&self, key: Key) -> Key {
+		match self.prefix.is_empty() {
+			true => key,
+			false => [self.prefix.as_slice(), key.as_slice()].concat(),
+		}
+	}
+	/// Encrypt a value before it is persisted
+	fn seal(&self, val: Val) -> Result<Val, Error> {
+		match &self.cipher {
+			Some(cipher) => {
+				let nonce: [u8; NONCE_LEN] = rand::random();
+				let data = cipher
+					.encrypt(Nonce::from_slice(&nonce), val.as_slice())
+					.map_err(|_| Error::Ds("Unable to encrypt the persisted data".to_owned()))?;
+				Ok([nonce.as_slice(), data.as_slice()].concat())
+			}
+			None => Ok(val),
+		}
+	}
+	/// Decrypt a value after it has been read
+	fn open(&self, val: Val) -> Result<Val, Error> {
+		match &self.cipher {
+			Some(cipher) if val.len() >= NONCE_LEN => {
+				let (nonce, data) = val.split_at(NONCE_LEN);
+				cipher.decrypt(Nonce::from_slice(nonce), data).map_err(|_| {
+					Error::Ds(
+						"Unable to decrypt the persisted data with the specified encryption key"
+							.to_owned(),
+					)
+				})
+			}
+			Some(_) => Err(Error::Ds(
+				"Unable to decrypt the persisted data with the specified encryption key".to_owned(),
+			)),
+			None => Ok(val),
+		}
+	}
 	/// Behaviour if unclosed
 	pub(crate) fn check_level(&mut self, check: Check) {
 		self.check = check;
@@ -131,7 +211,7 @@ impl Transaction {
 			return Err(Error::TxFinished);
 		}
 		// Check the key
-		let res = self.inner.exi(key.into()).await?;
+		let res = self.inner.exi(self.key(key.into())).await?;
 		// Return result
 		Ok(res)
 	}
@@ -145,7 +225,10 @@ impl Transaction {
 			return Err(Error::TxFinished);
 		}
 		// Get the key
-		let res = self.inner.get(key.into()).await?;
+		let res = match self.inner.get(self.key(key.into())).await? {
+			Some(v) => Some(self.open(v)?),
+			None => None,
+		};
 		// Return result
 		Ok(res)
 	}
@@ -166,7 +249,7 @@ impl Transaction {
 		// Write the timestamp to the "last-write-timestamp" key
 		// to ensure that no other transactions can commit with older timestamps.
 		let k: Key = key.into();
-		let prev = self.inner.get(k.clone()).await?;
+		let prev = self.get(k.clone()).await?;
 		let ver = match prev {
 			Some(prev) => {
 				let slice = prev.as_slice();
@@ -183,7 +266,7 @@ impl Transaction {
 
 		let verbytes = u64_to_versionstamp(ver);
 
-		self.inner.put(k, verbytes.to_vec()).await?;
+		self.inner.put(self.key(k), self.seal(verbytes.to_vec())?).await?;
 		// Return the uint64 representation of the timestamp as the result
 		Ok(verbytes)
 	}
@@ -226,7 +309,7 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Set the key
-		self.inner.set(key.into(), val.into()).await?;
+		self.inner.set(self.key(key.into()), self.seal(val.into())?).await?;
 		// Return result
 		Ok(())
 	}
@@ -245,7 +328,7 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Set the key
-		self.inner.put(key.into(), val.into()).await?;
+		self.inner.put(self.key(key.into()), self.seal(val.into())?).await?;
 		// Return result
 		Ok(())
 	}
@@ -263,8 +346,20 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Set the key
-		self.inner.putc(key.into(), val.into(), chk.map(Into::into)).await?;
+		// Get the arguments
+		let key = self.key(key.into());
+		let val = self.seal(val.into())?;
+		let chk = chk.map(Into::into);
+		// Set the key if valid
+		match self.cipher {
+			// Encrypted values can only be compared once decrypted
+			Some(_) => match (self.inner.get(key.clone()).await?, chk) {
+				(Some(v), Some(w)) if self.open(v.clone())? == w => self.inner.set(key, val).await?,
+				(None, None) => self.inner.set(key, val).await?,
+				_ => return Err(Error::TxConditionNotMet),
+			},
+			None => self.inner.putc(key, val, chk).await?,
+		};
 		// Return result
 		Ok(())
 	}
@@ -282,7 +377,7 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Remove the key
-		let res = self.inner.del(key.into()).await?;
+		let res = self.inner.del(self.key(key.into())).await?;
 		// Return result
 		Ok(res)
 	}
@@ -300,10 +395,21 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Remove the key
-		let res = self.inner.delc(key.into(), chk.map(Into::into)).await?;
+		// Get the arguments
+		let key = self.key(key.into());
+		let chk = chk.map(Into::into);
+		// Remove the key if valid
+		match self.cipher {
+			// Encrypted values can only be compared once decrypted
+			Some(_) => match (self.inner.get(key.clone()).await?, chk) {
+				(Some(v), Some(w)) if self.open(v.clone())? == w => self.inner.del(key).await?,
+				(None, None) => self.inner.del(key).await?,
+				_ => return Err(Error::TxConditionNotMet),
+			},
+			None => self.inner.delc(key, chk).await?,
+		};
 		// Return result
-		Ok(res)
+		Ok(())
 	}
 	/// Retrieve a range of keys from the databases
 	pub(crate) async fn scan<K>(
@@ -320,12 +426,40 @@ impl Transaction {
 		}
 		// Convert the range to bytes
 		let rng: Range<Key> = Range {
-			start: rng.start.into(),
-			end: rng.end.into(),
+			start: self.key(rng.start.into()),
+			end: self.key(rng.end.into()),
 		};
 		// Scan the keys
 		let res = self.inner.scan(rng, limit).await?;
+		// Remove the store prefix, and decrypt the values
+		let res = res
+			.into_iter()
+			.map(|(k, v)| Ok((k[self.prefix.len()..].to_vec(), self.open(v)?)))
+			.collect::<Result<_, Error>>()?;
 		// Return result
 		Ok(res)
 	}
 }
+
+/// Request that the browser persists the data, so
+/// that it is not evicted under storage pressure
+#[cfg(target_arch = "wasm32")]
+async fn persist() {
+	// Fetch the storage manager, from a window or a worker
+	let storage = match web_sys::window() {
+		Some(window) => window.navigator().storage(),
+		None => match js_sys::global().dyn_into::<web_sys::WorkerGlobalScope>() {
+			Ok(worker) => worker.navigator().storage(),
+			Err(_) => return,
+		},
+	};
+	// Request persistent storage for this origin
+	if let Ok(promise) = storage.persist() {
+		match JsFuture::from(promise).await.map(|v| v.as_bool()) {
+			Ok(Some(true)) => trace!("Persistent storage was granted by the browser"),
+			_ => warn!(
+				"Persistent storage was not granted, so the data may be evicted by the browser"
+			),
+		}
+	}
+}
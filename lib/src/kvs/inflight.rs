@@ -0,0 +1,88 @@
+use crate::ctx::Canceller;
+use crate::err::Error;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use trice::Instant;
+
+/// Tracks the queries which are currently running on a datastore
+#[derive(Default)]
+pub(super) struct Inflight(Mutex<State>);
+
+#[derive(Default)]
+struct State {
+	// Whether new queries are refused
+	closed: bool,
+	// The id given to the next running query
+	next: u64,
+	// The cancellers of the running queries
+	running: HashMap<u64, Canceller>,
+}
+
+/// Removes a running query from the tracker when dropped
+pub(super) struct Guard<'a> {
+	inflight: &'a Inflight,
+	id: u64,
+}
+
+impl Drop for Guard<'_> {
+	fn drop(&mut self) {
+		self.inflight.0.lock().unwrap().running.remove(&self.id);
+	}
+}
+
+impl Inflight {
+	/// Register a running query, unless the datastore is shutting down
+	pub(super) fn enter(&self, canceller: Canceller) -> Result<Guard<'_>, Error> {
+		let mut state = self.0.lock().unwrap();
+		if state.closed {
+			return Err(Error::DsShutdown);
+		}
+		let id = state.next;
+		state.next += 1;
+		state.running.insert(id, canceller);
+		Ok(Guard {
+			inflight: self,
+			id,
+		})
+	}
+
+	/// Stop accepting new queries, and wait for the running queries to
+	/// complete, cancelling any which are still running after the grace
+	/// period. Returns whether all of the running queries have stopped.
+	pub(super) async fn drain(&self, grace_period: Duration) -> bool {
+		// Stop accepting new queries
+		self.0.lock().unwrap().closed = true;
+		// Wait for the running queries to complete
+		if self.wait(Instant::now() + grace_period).await {
+			return true;
+		}
+		// Cancel any queries which are still running
+		for v in self.0.lock().unwrap().running.values() {
+			v.cancel();
+		}
+		// Give the cancelled queries time to stop
+		self.wait(Instant::now() + grace_period).await
+	}
+
+	/// Wait until no queries are running, or until the deadline passes
+	async fn wait(&self, deadline: Instant) -> bool {
+		loop {
+			if self.0.lock().unwrap().running.is_empty() {
+				return true;
+			}
+			if Instant::now() >= deadline {
+				return false;
+			}
+			sleep(Duration::from_millis(10)).await;
+		}
+	}
+}
+
+/// Sleep for the specified duration
+pub(super) async fn sleep(dur: Duration) {
+	#[cfg(target_arch = "wasm32")]
+	wasmtimer::tokio::sleep(dur).await;
+	#[cfg(not(target_arch = "wasm32"))]
+	tokio::time::sleep(dur).await;
+}
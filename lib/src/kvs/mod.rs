@@ -10,22 +10,49 @@
 //! - `indxdb`: WASM based database to store data in the browser
 //! - `rocksdb`: [RocksDB](https://github.com/facebook/rocksdb) an embeddable persistent key-value store for fast storage
 //! - `speedb`: [SpeedyDB](https://github.com/speedb-io/speedb) fork of rocksDB making it faster (Redis is using speedb but this is not acid transactions)
+//! - `redb`: [ReDB](https://github.com/cberner/redb) a pure-Rust embedded persistent key-value store, with no C dependencies
+//! - `s3` / `gcs`: an S3-compatible, or Google Cloud Storage, object storage bucket, cached locally in memory, for stateless deployments
 //! - `tikv`: [TiKV](https://github.com/tikv/tikv) a distributed, and transactional key-value database
 //! - `mem`: in-memory database
+//! - `custom`: a storage engine implemented outside of this crate, using the [`Store`] trait
+mod archive;
 mod cache;
+mod chunk;
+mod cipher;
+mod custom;
 mod ds;
+mod export;
 mod fdb;
 mod indxdb;
+mod inflight;
 mod kv;
 mod mem;
+mod object;
+mod partition;
+mod raft;
+mod redb;
 mod rocksdb;
+mod scanner;
 mod speedb;
 mod tikv;
 mod tx;
+mod vacuum;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::chunk::ValueReader;
+#[cfg(feature = "kv-custom")]
+pub use self::custom::{Store, StoreTransaction};
 pub use self::ds::*;
+pub use self::export::*;
+#[cfg(feature = "kv-fdb")]
+pub use self::fdb::FdbOptions;
 pub use self::kv::*;
+#[cfg(feature = "replication")]
+pub use self::raft::{ReplicationOptions, ReplicationStatus, Role as ReplicationRole};
+pub(crate) use self::scanner::Scanner;
+#[cfg(feature = "kv-tikv")]
+pub use self::tikv::TikvOptions;
 pub use self::tx::*;
+pub use self::vacuum::Vacuum;
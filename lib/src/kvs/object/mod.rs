@@ -0,0 +1,590 @@
+#![cfg(feature = "kv-object")]
+
+use crate::cnf::OBJECT_STORE_COMPACTION_THRESHOLD;
+use crate::err::Error;
+use crate::kvs::Check;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use futures::lock::Mutex;
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+use ulid::Ulid;
+
+/// The folder in which the compacted snapshots are stored
+const SNAPSHOTS: &str = "snapshots";
+/// The folder in which the batched write segments are stored
+const SEGMENTS: &str = "segments";
+
+/// A set of writes, where deleted keys are stored as `None`
+type Batch = BTreeMap<Key, Option<Val>>;
+
+/// A datastore which persists its data to S3-compatible, or Google
+/// Cloud Storage, object storage. The full dataset is cached locally
+/// in memory, so reads never touch the object storage. The writes of
+/// each transaction are persisted as an immutable segment before the
+/// transaction is acknowledged, and the segments are periodically
+/// compacted into a single snapshot. When the datastore
+/// is opened the latest snapshot, and any later segments, are loaded,
+/// so that nodes can remain stateless. Only one node should write to
+/// a given bucket and path prefix at any one time.
+pub struct Datastore {
+	/// The local cache of the persisted data
+	db: echodb::Db<Key, Val>,
+	/// The remote object storage
+	store: Arc<Mutex<Store>>,
+}
+
+/// The state of the data persisted to the object storage
+struct Store {
+	/// The remote object storage bucket
+	remote: Arc<dyn ObjectStore>,
+	/// The path prefix under which the data is stored
+	prefix: Path,
+	/// The segments persisted since the last compaction
+	segments: Vec<Path>,
+	/// The most recently compacted snapshot
+	snapshot: Option<Path>,
+}
+
+pub struct Transaction {
+	/// Is the transaction complete?
+	done: bool,
+	/// Is the transaction writeable?
+	write: bool,
+	/// Should we check unhandled transactions?
+	check: Check,
+	/// The underlying datastore transaction
+	inner: echodb::Tx<Key, Val>,
+	/// The writes made within this transaction
+	writes: Batch,
+	/// The remote object storage
+	store: Arc<Mutex<Store>>,
+}
+
+impl Drop for Transaction {
+	fn drop(&mut self) {
+		if !self.done && self.write {
+			// Check if already panicking
+			if std::thread::panicking() {
+				return;
+			}
+			// Handle the behaviour
+			match self.check {
+				Check::None => {
+					trace!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Warn => {
+					warn!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Panic => {
+					#[cfg(debug_assertions)]
+					{
+						let backtrace = std::backtrace::Backtrace::force_capture();
+						if let std::backtrace::BacktraceStatus::Captured = backtrace.status() {
+							println!("{}", backtrace);
+						}
+					}
+					panic!("A transaction was dropped without being committed or cancelled");
+				}
+			}
+		}
+	}
+}
+
+impl Datastore {
+	/// Open a new database
+	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+		// Connect to the object storage bucket
+		let (remote, prefix) = connect(path)?;
+		// Load the data from the bucket
+		Self::open(remote, prefix).await
+	}
+	/// Open a database stored under a path prefix
+	async fn open(remote: Arc<dyn ObjectStore>, prefix: Path) -> Result<Datastore, Error> {
+		// Find the latest snapshot
+		let mut snapshots = list(&*remote, &prefix.child(SNAPSHOTS)).await?;
+		let snapshot = snapshots.pop();
+		// Find the segments persisted after the snapshot
+		let mut segments = list(&*remote, &prefix.child(SEGMENTS)).await?;
+		if let Some(name) = snapshot.as_ref().and_then(Path::filename) {
+			segments.retain(|v| v.filename().is_some_and(|v| v > name));
+		}
+		// Load the persisted data into the local cache
+		let db = echodb::db::new();
+		let mut tx = db.begin(true).await?;
+		for path in snapshot.iter().chain(segments.iter()) {
+			for (k, v) in read(&*remote, path).await? {
+				match v {
+					Some(v) => tx.set(k, v)?,
+					None => tx.del(k)?,
+				}
+			}
+		}
+		tx.commit()?;
+		// Return the datastore
+		Ok(Datastore {
+			db,
+			store: Arc::new(Mutex::new(Store {
+				remote,
+				prefix,
+				segments,
+				snapshot,
+			})),
+		})
+	}
+	/// Flush any buffered writes to the object storage
+	pub(crate) async fn flush(&self) -> Result<(), Error> {
+		// The writes are persisted when they are committed
+		Ok(())
+	}
+	/// Start a new transaction
+	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
+		// Specify the check level
+		#[cfg(not(debug_assertions))]
+		let check = Check::Warn;
+		#[cfg(debug_assertions)]
+		let check = Check::Panic;
+		// Create a new transaction
+		match self.db.begin(write).await {
+			Ok(inner) => Ok(Transaction {
+				done: false,
+				check,
+				write,
+				inner,
+				writes: Batch::new(),
+				store: self.store.clone(),
+			}),
+			Err(e) => Err(Error::Tx(e.to_string())),
+		}
+	}
+}
+
+impl Store {
+	/// Persist the writes of a transaction as a new segment
+	async fn persist(&mut self, batch: &Batch) -> Result<(), Error> {
+		if !batch.is_empty() {
+			let path = self.prefix.child(SEGMENTS).child(Ulid::new().to_string());
+			write(&*self.remote, &path, batch).await?;
+			self.segments.push(path);
+		}
+		Ok(())
+	}
+	/// Merge the snapshot and segments into a new snapshot
+	async fn compact(&mut self) -> Result<(), Error> {
+		// The snapshot is named after the latest segment
+		let name = match self.segments.last().and_then(Path::filename) {
+			Some(name) => name.to_owned(),
+			None => return Ok(()),
+		};
+		// Merge the snapshot with the segments in order
+		let mut data = Batch::new();
+		for path in self.snapshot.iter().chain(self.segments.iter()) {
+			data.extend(read(&*self.remote, path).await?);
+		}
+		// Deleted keys no longer need to be stored
+		data.retain(|_, v| v.is_some());
+		// Write the new snapshot
+		let path = self.prefix.child(SNAPSHOTS).child(name);
+		write(&*self.remote, &path, &data).await?;
+		// Remove the previous snapshot and the merged segments
+		let old = self.snapshot.replace(path);
+		for path in old.into_iter().chain(self.segments.drain(..)) {
+			self.remote.delete(&path).await?;
+		}
+		// Continue
+		Ok(())
+	}
+}
+
+impl Transaction {
+	/// Behaviour if unclosed
+	pub(crate) fn check_level(&mut self, check: Check) {
+		self.check = check;
+	}
+	/// Check if closed
+	pub(crate) fn closed(&self) -> bool {
+		self.done
+	}
+	/// Cancel a transaction
+	pub(crate) async fn cancel(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Cancel this transaction
+		self.inner.cancel()?;
+		// Continue
+		Ok(())
+	}
+	/// Commit a transaction
+	pub(crate) async fn commit(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Lock the store, so that writes are persisted
+		// in the same order as they are committed
+		let mut store = self.store.lock().await;
+		// Persist the writes before they are applied, so
+		// that an acknowledged transaction is never lost,
+		// and a transaction which fails is never applied
+		if let Err(e) = store.persist(&self.writes).await {
+			self.inner.cancel()?;
+			return Err(e);
+		}
+		// Commit this transaction
+		self.inner.commit()?;
+		// Compact the segments if there are too many,
+		// which the transaction no longer depends on
+		if store.segments.len() >= *OBJECT_STORE_COMPACTION_THRESHOLD {
+			if let Err(e) = store.compact().await {
+				warn!("Unable to compact the object storage segments: {e}");
+			}
+		}
+		// Continue
+		Ok(())
+	}
+	/// Check if a key exists
+	pub(crate) async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check the key
+		let res = self.inner.exi(key.into())?;
+		// Return result
+		Ok(res)
+	}
+	/// Fetch a key from the database
+	pub(crate) async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the key
+		let res = self.inner.get(key.into())?;
+		// Return result
+		Ok(res)
+	}
+	/// Obtain a new change timestamp for a key
+	/// which is replaced with the current timestamp when the transaction is committed.
+	/// NOTE: This should be called when composing the change feed entries for this transaction,
+	/// which should be done immediately before the transaction commit.
+	/// That is to keep other transactions commit delay(pessimistic) or conflict(optimistic) as less as possible.
+	#[allow(unused)]
+	pub(crate) async fn get_timestamp<K>(&mut self, key: K) -> Result<Versionstamp, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Write the timestamp to the "last-write-timestamp" key
+		// to ensure that no other transactions can commit with older timestamps.
+		let k: Key = key.into();
+		let prev = self.inner.get(k.clone())?;
+		let ver = match prev {
+			Some(prev) => {
+				let slice = prev.as_slice();
+				let res: Result<[u8; 10], Error> = match slice.try_into() {
+					Ok(ba) => Ok(ba),
+					Err(e) => Err(Error::Ds(e.to_string())),
+				};
+				let array = res?;
+				let prev = try_to_u64_be(array)?;
+				prev + 1
+			}
+			None => 1,
+		};
+
+		let verbytes = u64_to_versionstamp(ver);
+
+		self.inner.set(k.clone(), verbytes.to_vec())?;
+		self.writes.insert(k, Some(verbytes.to_vec()));
+		// Return the uint64 representation of the timestamp as the result
+		Ok(verbytes)
+	}
+	/// Obtain a new key that is suffixed with the change timestamp
+	pub(crate) async fn get_versionstamped_key<K>(
+		&mut self,
+		ts_key: K,
+		prefix: K,
+		suffix: K,
+	) -> Result<Vec<u8>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		let ts = self.get_timestamp(ts_key).await?;
+		let mut k: Vec<u8> = prefix.into();
+		k.append(&mut ts.to_vec());
+		k.append(&mut suffix.into());
+		Ok(k)
+	}
+	/// Insert or update a key in the database
+	pub(crate) async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		// Set the key
+		self.inner.set(key.clone(), val.clone())?;
+		self.writes.insert(key, Some(val));
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		// Set the key
+		self.inner.put(key.clone(), val.clone())?;
+		self.writes.insert(key, Some(val));
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		// Set the key
+		self.inner.putc(key.clone(), val.clone(), chk.map(Into::into))?;
+		self.writes.insert(key, Some(val));
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn del<K>(&mut self, key: K) -> Result<(), Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		// Remove the key
+		self.inner.del(key.clone())?;
+		self.writes.insert(key, None);
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		// Remove the key
+		self.inner.delc(key.clone(), chk.map(Into::into))?;
+		self.writes.insert(key, None);
+		// Return result
+		Ok(())
+	}
+	/// Retrieve a range of keys from the databases
+	pub(crate) async fn scan<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Scan the keys
+		let res = self.inner.scan(rng, limit)?;
+		// Return result
+		Ok(res)
+	}
+}
+
+/// Connect to the object storage bucket specified in
+/// an `s3://bucket/prefix` or `gcs://bucket/prefix` path,
+/// using the credentials configured in the environment
+fn connect(path: &str) -> Result<(Arc<dyn ObjectStore>, Path), Error> {
+	let (remote, rest): (Arc<dyn ObjectStore>, &str) = match path {
+		s if s.starts_with("s3:") => {
+			let s = s.trim_start_matches("s3://");
+			let s = s.trim_start_matches("s3:");
+			let (bucket, rest) = s.split_once('/').unwrap_or((s, ""));
+			let remote = AmazonS3Builder::from_env().with_bucket_name(bucket).build()?;
+			(Arc::new(remote), rest)
+		}
+		s if s.starts_with("gcs:") => {
+			let s = s.trim_start_matches("gcs://");
+			let s = s.trim_start_matches("gcs:");
+			let (bucket, rest) = s.split_once('/').unwrap_or((s, ""));
+			let remote = GoogleCloudStorageBuilder::from_env().with_bucket_name(bucket).build()?;
+			(Arc::new(remote), rest)
+		}
+		_ => return Err(Error::Ds("Unable to load the specified datastore".into())),
+	};
+	Ok((remote, Path::from(rest)))
+}
+
+/// List the objects under a path prefix, in name order
+async fn list(remote: &dyn ObjectStore, prefix: &Path) -> Result<Vec<Path>, Error> {
+	let mut res: Vec<Path> =
+		remote.list(Some(prefix)).await?.map_ok(|v| v.location).try_collect().await?;
+	res.sort();
+	Ok(res)
+}
+
+/// Read a set of writes from an object
+async fn read(remote: &dyn ObjectStore, path: &Path) -> Result<Batch, Error> {
+	let data = remote.get(path).await?.bytes().await?;
+	Ok(bincode::deserialize(&data)?)
+}
+
+/// Write a set of writes to an object
+async fn write(remote: &dyn ObjectStore, path: &Path, batch: &Batch) -> Result<(), Error> {
+	let data = bincode::serialize(batch)?;
+	remote.put(path, data.into()).await?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use object_store::memory::InMemory;
+
+	async fn get(ds: &Datastore, key: &str) -> Option<Val> {
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let val = tx.get(key).await.unwrap();
+		tx.cancel().await.unwrap();
+		val
+	}
+
+	#[tokio::test]
+	async fn persisted_writes_are_reloaded() {
+		let remote: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+		let ds = Datastore::open(remote.clone(), Path::from("test")).await.unwrap();
+		// Write and delete some keys
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		tx.set("one", "1").await.unwrap();
+		tx.set("two", "2").await.unwrap();
+		tx.commit().await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		tx.del("one").await.unwrap();
+		tx.commit().await.unwrap();
+		// Cancelled writes are not persisted
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		tx.set("three", "3").await.unwrap();
+		tx.cancel().await.unwrap();
+		// Reload the data from the object storage
+		let ds = Datastore::open(remote, Path::from("test")).await.unwrap();
+		assert_eq!(get(&ds, "one").await, None);
+		assert_eq!(get(&ds, "two").await, Some(b"2".to_vec()));
+		assert_eq!(get(&ds, "three").await, None);
+	}
+
+	#[tokio::test]
+	async fn segments_are_compacted() {
+		let remote: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+		let ds = Datastore::open(remote.clone(), Path::from("test")).await.unwrap();
+		// Persist enough segments to trigger a compaction
+		for i in 0..*OBJECT_STORE_COMPACTION_THRESHOLD {
+			let mut tx = ds.transaction(true, false).await.unwrap();
+			tx.set(format!("key{i}"), i.to_string()).await.unwrap();
+			tx.del("key0").await.unwrap();
+			tx.commit().await.unwrap();
+		}
+		// The segments have been merged into one snapshot
+		let prefix = Path::from("test");
+		assert_eq!(list(&*remote, &prefix.child(SEGMENTS)).await.unwrap().len(), 0);
+		assert_eq!(list(&*remote, &prefix.child(SNAPSHOTS)).await.unwrap().len(), 1);
+		// Reload the data from the object storage
+		let ds = Datastore::open(remote, prefix).await.unwrap();
+		assert_eq!(get(&ds, "key0").await, None);
+		assert_eq!(get(&ds, "key1").await, Some(b"1".to_vec()));
+	}
+}
@@ -0,0 +1,247 @@
+//! Partitions the data of each table, and of each index, into a separate column
+//! family in the RocksDB and SpeeDB storage engines. This allows a table or an
+//! index to be removed by dropping its column family, instead of writing a
+//! tombstone for every key, and allows each column family to be configured and
+//! compacted separately. The column family of each partition is recorded under
+//! the `/!cf` key prefix, so that it changes atomically with the transaction
+//! which drops and recreates the partition.
+#![cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+
+use crate::key::interned;
+use crate::kvs::Key;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use ulid::Ulid;
+
+/// The key prefix under which the column family of each partition is recorded
+pub(super) const MAPPING: &[u8] = b"/!cf";
+
+/// The column family in which each partition, by key prefix, is stored
+pub(super) type Registry = BTreeMap<Key, String>;
+
+/// Where a key is stored within a transaction
+pub(super) enum Route {
+	/// The key is not within a partition
+	Default,
+	/// The key is within a partition which has not been created yet
+	Missing(Key),
+	/// The key is within a partition dropped in this transaction
+	Dropped(Key),
+	/// The key is within a partition stored in the column family. If
+	/// the partition existed before this transaction, then the key may
+	/// have been written to the default column family before partitions
+	/// were introduced, and should be looked up there if it is missing.
+	Partition(String, bool),
+}
+
+/// The changes to the partitions made within a transaction
+#[derive(Default)]
+pub(super) struct Changes {
+	/// The prefixes under which all partitions have been dropped
+	dropped: Vec<Key>,
+	/// The partitions which have been recreated after being dropped
+	created: Registry,
+	/// The column families to drop once the transaction is complete
+	garbage: Vec<String>,
+}
+
+impl Changes {
+	/// Find where a key is stored within this transaction
+	pub(super) fn route(&self, registry: &Registry, key: &[u8]) -> Route {
+		match partition(key) {
+			None => Route::Default,
+			Some(prefix) => match self.created.get(prefix) {
+				Some(name) => Route::Partition(name.clone(), false),
+				None if self.is_dropped(prefix) => Route::Dropped(prefix.to_vec()),
+				None => match registry.get(prefix) {
+					Some(name) => Route::Partition(name.clone(), true),
+					None => Route::Missing(prefix.to_vec()),
+				},
+			},
+		}
+	}
+	/// Find the column families which may contain keys within the range
+	pub(super) fn sources(&self, registry: &Registry, beg: &[u8], end: &[u8]) -> Vec<String> {
+		let mut res = vec![];
+		for (prefix, name) in overlapping(registry, beg, end) {
+			if !self.is_dropped(prefix) {
+				res.push(name.clone());
+			}
+		}
+		for (_, name) in overlapping(&self.created, beg, end) {
+			res.push(name.clone());
+		}
+		res
+	}
+	/// Record a partition recreated after it was dropped
+	pub(super) fn create(&mut self, prefix: Key, name: String) {
+		self.created.insert(prefix, name);
+	}
+	/// Drop all partitions under a prefix, returning the prefixes whose mappings should be removed
+	pub(super) fn drop(&mut self, registry: &Registry, prefix: &[u8]) -> Vec<Key> {
+		let mut res = vec![];
+		// Partitions recreated in this transaction are discarded
+		let created = under(&self.created, prefix).cloned().collect::<Vec<_>>();
+		for key in created {
+			if let Some(name) = self.created.remove(&key) {
+				self.garbage.push(name);
+				res.push(key);
+			}
+		}
+		// Existing partitions are dropped when committed
+		let existing = under(registry, prefix).cloned().collect::<Vec<_>>();
+		if !existing.is_empty() {
+			self.dropped.push(prefix.to_vec());
+			res.extend(existing);
+		}
+		res
+	}
+	/// Apply the changes to the registry, returning the column families to drop
+	pub(super) fn commit(self, registry: &mut Registry) -> Vec<String> {
+		let mut res = self.garbage;
+		for prefix in self.dropped.iter() {
+			let keys = under(registry, prefix).cloned().collect::<Vec<_>>();
+			for key in keys {
+				res.extend(registry.remove(&key));
+			}
+		}
+		registry.extend(self.created);
+		res
+	}
+	/// Discard the changes, returning the column families to drop
+	pub(super) fn cancel(self) -> Vec<String> {
+		let mut res = self.garbage;
+		res.extend(self.created.into_values());
+		res
+	}
+	/// Check if a partition was dropped within this transaction
+	fn is_dropped(&self, prefix: &[u8]) -> bool {
+		self.dropped.iter().any(|v| prefix.starts_with(v))
+	}
+}
+
+/// Returns the key under which the column family of a partition is recorded
+pub(super) fn mapping(prefix: &[u8]) -> Key {
+	[MAPPING, prefix].concat()
+}
+
+/// Returns a new unique column family name for a partition
+pub(super) fn name() -> String {
+	format!("partition-{}", Ulid::new())
+}
+
+/// Returns the prefix of the innermost partition which contains the key. Table
+/// data is stored under `/*{ns}\0*{db}\0*{tb}\0`, or the interned table prefix,
+/// and index data is stored under the table prefix followed by `+{ix}\0`, so
+/// each of these is a separate partition.
+pub(super) fn partition(key: &[u8]) -> Option<&[u8]> {
+	// Check that this is a table key
+	let tb = interned::interned(key).or_else(|| interned::named(key))?;
+	// Check if this is an index key
+	match key.get(tb) {
+		Some(b'+') => match key[tb + 1..].iter().position(|v| *v == 0) {
+			Some(v) => Some(&key[..tb + v + 2]),
+			None => Some(&key[..tb]),
+		},
+		_ => Some(&key[..tb]),
+	}
+}
+
+/// Returns the partitions which may contain keys within the range
+fn overlapping<'a>(
+	registry: &'a Registry,
+	beg: &[u8],
+	end: &[u8],
+) -> impl Iterator<Item = (&'a Key, &'a String)> {
+	// Partitions which contain the start of the range
+	let outer = match partition(beg) {
+		Some(v) => {
+			// An index partition is within a table partition
+			let tb = partition(&v[..v.len() - 1]);
+			[tb, Some(v)]
+				.into_iter()
+				.flatten()
+				.filter(|v| v.len() < beg.len())
+				.filter_map(|v| registry.get_key_value(v))
+				.collect::<Vec<_>>()
+		}
+		None => vec![],
+	};
+	// Partitions which start within the range
+	let inner = match beg < end {
+		true => registry.range::<[u8], _>((Bound::Included(beg), Bound::Excluded(end))).collect(),
+		false => vec![],
+	};
+	outer.into_iter().chain(inner)
+}
+
+/// Returns the partitions whose prefixes start with the prefix
+fn under<'a>(registry: &'a Registry, prefix: &'a [u8]) -> impl Iterator<Item = &'a Key> {
+	registry
+		.range::<[u8], _>((Bound::Included(prefix), Bound::Unbounded))
+		.map(|(k, _)| k)
+		.take_while(move |k| k.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn partition_of_key() {
+		assert_eq!(partition(b"/!ns"), None);
+		assert_eq!(partition(b"/*ns\0*db\0!tbtest\0"), None);
+		assert_eq!(partition(b"/*ns\0*db\0*tb\0"), Some(&b"/*ns\0*db\0*tb\0"[..]));
+		assert_eq!(partition(b"/*ns\0*db\0*tb\0*id"), Some(&b"/*ns\0*db\0*tb\0"[..]));
+		assert_eq!(partition(b"/*ns\0*db\0*tb\0+ix\0*fd"), Some(&b"/*ns\0*db\0*tb\0+ix\0"[..]));
+		assert_eq!(partition(b"/*ns\0*db\0*tb\0+ix"), Some(&b"/*ns\0*db\0*tb\0"[..]));
+		let tb: Key = interned::new(1, 2, 3).into();
+		assert_eq!(partition(&[tb.as_slice(), b"*id"].concat()), Some(tb.as_slice()));
+		let ix = [tb.as_slice(), b"+ix\0"].concat();
+		assert_eq!(partition(&[ix.as_slice(), b"*fd"].concat()), Some(ix.as_slice()));
+		assert_eq!(partition(&crate::key::database::ti::new(1, 2).encode().unwrap()), None);
+	}
+
+	#[test]
+	fn dropped_partitions_are_replaced() {
+		let mut registry = Registry::new();
+		registry.insert(b"/*ns\0*db\0*tb\0".to_vec(), "a".to_owned());
+		registry.insert(b"/*ns\0*db\0*tb\0+ix\0".to_vec(), "b".to_owned());
+		registry.insert(b"/*ns\0*db\0*other\0".to_vec(), "c".to_owned());
+		// Drop the table, and all of its indexes
+		let mut changes = Changes::default();
+		let removed = changes.drop(&registry, b"/*ns\0*db\0*tb\0");
+		assert_eq!(removed.len(), 2);
+		assert!(matches!(changes.route(&registry, b"/*ns\0*db\0*tb\0*id"), Route::Dropped(_)));
+		assert!(matches!(
+			changes.route(&registry, b"/*ns\0*db\0*other\0*id"),
+			Route::Partition(_, true)
+		));
+		// Recreate the table partition
+		changes.create(b"/*ns\0*db\0*tb\0".to_vec(), "d".to_owned());
+		assert!(
+			matches!(changes.route(&registry, b"/*ns\0*db\0*tb\0*id"), Route::Partition(n, false) if n == "d")
+		);
+		assert_eq!(changes.sources(&registry, b"/*ns\0*db\0", b"/*ns\0*db\0\xff"), vec!["c", "d"]);
+		// Apply the changes
+		let mut dropped = changes.commit(&mut registry);
+		dropped.sort();
+		assert_eq!(dropped, vec!["a", "b"]);
+		assert_eq!(registry.values().collect::<Vec<_>>(), vec!["c", "d"]);
+	}
+
+	#[test]
+	fn sources_include_containing_partitions() {
+		let mut registry = Registry::new();
+		registry.insert(b"/*ns\0*db\0*tb\0".to_vec(), "a".to_owned());
+		registry.insert(b"/*ns\0*db\0*tb\0+ix\0".to_vec(), "b".to_owned());
+		let changes = Changes::default();
+		let sources = changes.sources(&registry, b"/*ns\0*db\0*tb\0*", b"/*ns\0*db\0*tb\0*\xff");
+		assert_eq!(sources, vec!["a"]);
+		let sources =
+			changes.sources(&registry, b"/*ns\0*db\0*tb\0+ix\0*", b"/*ns\0*db\0*tb\0+ix\0*\xff");
+		assert_eq!(sources, vec!["a", "b"]);
+		let sources = changes.sources(&registry, b"/*ns\0*db\0*tb\0", b"/*ns\0*db\0*tb\0\xff");
+		assert_eq!(sources, vec!["a", "b"]);
+	}
+}
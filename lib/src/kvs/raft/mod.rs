@@ -0,0 +1,380 @@
+#![cfg(feature = "replication")]
+//! Replicates a RocksDB datastore across several embedded nodes, which form a Raft group.
+//! The leader of the group handles every write. The changes of a write transaction are
+//! appended to the replicated log, instead of being committed to the datastore, and are
+//! only applied once a majority of the nodes have stored them in their logs. Every node,
+//! including the leader, applies the committed entries to its datastore in the order of
+//! the log, and the write is acknowledged once the leader has applied it. Followers serve
+//! reads, which may lag behind the leader.
+//!
+//! As the changes are not committed by the datastore itself, the leader checks that the
+//! keys written by a transaction have not been changed by another write since the
+//! transaction started, before its changes are appended to the log.
+
+mod net;
+mod node;
+mod storage;
+
+pub use self::node::Role;
+
+use self::net::Peers;
+use self::node::{Message, Node, NodeId};
+use self::storage::{State, Storage};
+use crate::err::Error;
+use crate::kvs::{Key, Val};
+use channel::{Receiver, Sender};
+use futures::lock::{Mutex, MutexGuard};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The number of ticks after which a follower, which has not heard from a leader, starts an election
+const ELECTION_TICKS: u32 = 10;
+
+/// The number of ticks between the heartbeats sent by the leader
+const HEARTBEAT_TICKS: u32 = 2;
+
+/// A change made by a write transaction, which is replicated to the other nodes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) enum Op {
+	/// A key was inserted or updated
+	Set(Key, Val),
+	/// A key was deleted
+	Del(Key),
+	/// The partitions of the tables and indexes under a key prefix were dropped
+	Drop(Key),
+}
+
+/// The options of a node in a replication group
+#[derive(Clone, Debug)]
+pub struct ReplicationOptions {
+	id: NodeId,
+	addr: SocketAddr,
+	path: PathBuf,
+	peers: BTreeMap<NodeId, SocketAddr>,
+	tick: Duration,
+	timeout: Duration,
+}
+
+impl ReplicationOptions {
+	/// Create the options of a node, which listens for the other nodes on an address,
+	/// and stores its replication log in a directory
+	pub fn new(id: u64, addr: SocketAddr, path: impl Into<PathBuf>) -> Self {
+		Self {
+			id,
+			addr,
+			path: path.into(),
+			peers: BTreeMap::from([(id, addr)]),
+			tick: Duration::from_millis(100),
+			timeout: Duration::from_secs(5),
+		}
+	}
+
+	/// Add another node to the replication group
+	///
+	/// Every node of the group has to be configured with the same set of nodes.
+	pub fn with_peer(mut self, id: u64, addr: SocketAddr) -> Self {
+		self.peers.insert(id, addr);
+		self
+	}
+
+	/// Specify how often the node ticks its clock
+	///
+	/// The leader sends heartbeats every 2 ticks, while followers start an election
+	/// when they have not heard from a leader in 10 to 20 ticks.
+	pub fn with_tick_interval(mut self, interval: Duration) -> Self {
+		self.tick = interval;
+		self
+	}
+
+	/// Specify how long a write waits to be replicated to a majority of the nodes
+	pub fn with_write_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+}
+
+/// The status of a node within its replication group
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ReplicationStatus {
+	/// The id of this node
+	pub id: u64,
+	/// The role of this node
+	pub role: Role,
+	/// The current term of the replication group
+	pub term: u64,
+	/// The id of the current leader, if known
+	pub leader: Option<u64>,
+	/// The index of the latest committed entry
+	pub commit: u64,
+	/// The index of the latest entry applied to the datastore
+	pub applied: u64,
+	// Whether the node is the leader and has applied the entries of previous leaders
+	ready: bool,
+}
+
+/// The progress of a write, which is reported to the transaction which made it
+enum Progress {
+	/// The changes were appended to the log of the leader, at an index
+	Appended(u64),
+	/// The changes were committed, and applied to the datastore of the leader
+	Applied,
+	/// The changes were replaced by a new leader, or could not be appended
+	Failed(Error),
+}
+
+/// A request to replicate the changes of a transaction
+type Proposal = (Vec<u8>, Sender<Progress>);
+
+/// The handle through which the transactions of a node replicate their changes
+#[derive(Clone)]
+pub(crate) struct Replica {
+	proposals: Sender<Proposal>,
+	status: Arc<RwLock<ReplicationStatus>>,
+	// Serialises the commits of write transactions, so that each transaction is checked
+	// for conflicts once the writes of the previous transactions have been applied
+	commits: Arc<Mutex<()>>,
+	timeout: Duration,
+}
+
+impl Replica {
+	/// The status of this node within its replication group
+	pub(crate) fn status(&self) -> ReplicationStatus {
+		self.status.read().unwrap_or_else(PoisonError::into_inner).clone()
+	}
+
+	/// Wait for the previous write transactions to be applied, checking that this node
+	/// can accept writes. The returned guard has to be held until the changes are applied.
+	pub(crate) async fn lock(&self) -> Result<MutexGuard<'_, ()>, Error> {
+		let guard = self.commits.lock().await;
+		match self.status().ready {
+			true => Ok(guard),
+			false => Err(Error::ReplicaNotLeader),
+		}
+	}
+
+	/// Replicate the changes of a transaction, waiting for a majority of the nodes to
+	/// store them, and for this node to apply them to its datastore
+	pub(crate) async fn replicate(&self, ops: &[Op]) -> Result<(), Error> {
+		let data = bincode::serialize(ops)?;
+		let (send, recv) = channel::bounded(2);
+		self.proposals.send((data, send)).await.map_err(|_| Error::ReplicationFailed {
+			message: "the replication group has stopped".to_owned(),
+		})?;
+		let deadline = Instant::now() + self.timeout;
+		let mut index = None;
+		loop {
+			// A committed write is durable, so it is waited for until it is applied
+			let durable = index.map_or(false, |i| self.status().commit >= i);
+			let progress = match durable {
+				true => Ok(recv.recv().await),
+				false => tokio::time::timeout_at(deadline, recv.recv()).await,
+			};
+			match progress {
+				Ok(Ok(Progress::Appended(i))) => index = Some(i),
+				Ok(Ok(Progress::Applied)) => return Ok(()),
+				Ok(Ok(Progress::Failed(e))) => return Err(e),
+				Ok(Err(_)) => {
+					return Err(Error::ReplicationFailed {
+						message: "the replication group has stopped".to_owned(),
+					})
+				}
+				// The write may have been committed while waiting
+				Err(_) if index.map_or(false, |i| self.status().commit >= i) => continue,
+				Err(_) => {
+					return Err(Error::ReplicationFailed {
+						message: "a majority of the nodes did not respond in time, so the write may or may not be applied".to_owned(),
+					})
+				}
+			}
+		}
+	}
+
+	/// Wait until the replication group has elected a leader, or until the timeout elapses
+	pub(crate) async fn wait_for_leader(&self, timeout: Duration) {
+		let wait = async {
+			loop {
+				let status = self.status();
+				if status.ready || (status.leader.is_some() && status.role == Role::Follower) {
+					break;
+				}
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		};
+		let _ = tokio::time::timeout(timeout, wait).await;
+	}
+
+	/// Stop this node from taking part in its replication group
+	pub(crate) fn stop(&self) {
+		self.proposals.close();
+	}
+}
+
+/// Start replicating a datastore, returning the handle used by its transactions
+pub(crate) async fn start(
+	ds: super::rocksdb::Datastore,
+	opts: ReplicationOptions,
+) -> Result<Replica, Error> {
+	let (storage, state, log) = Storage::open(opts.path.clone())?;
+	let node = Node::new(
+		opts.id,
+		opts.peers.keys().copied().collect(),
+		state.term,
+		state.vote,
+		log,
+		ELECTION_TICKS,
+		HEARTBEAT_TICKS,
+	);
+	let status = Arc::new(RwLock::new(ReplicationStatus {
+		id: opts.id,
+		role: node.role,
+		term: node.term,
+		leader: None,
+		commit: 0,
+		applied: state.applied,
+		ready: false,
+	}));
+	// Make sure that the state is persisted before anything is sent
+	storage.save_state(&state)?;
+	// Start listening for the other nodes
+	let (inbox_send, inbox) = channel::bounded(1024);
+	net::listen(opts.addr, inbox_send).await?;
+	let peers = Peers::new(opts.id, &opts.peers);
+	// Start driving the node
+	let (proposals, recv) = channel::bounded(1024);
+	let driver = Driver {
+		ds,
+		node,
+		storage,
+		state,
+		peers,
+		status: status.clone(),
+		pending: BTreeMap::new(),
+	};
+	tokio::spawn(driver.run(opts.tick, inbox, recv));
+	Ok(Replica {
+		proposals,
+		status,
+		commits: Arc::new(Mutex::new(())),
+		timeout: opts.timeout,
+	})
+}
+
+struct Driver {
+	ds: super::rocksdb::Datastore,
+	node: Node,
+	storage: Storage,
+	// The state which was last persisted
+	state: State,
+	peers: Peers,
+	status: Arc<RwLock<ReplicationStatus>>,
+	// The transactions waiting for their entries to be applied, by the index and term of the entries
+	pending: BTreeMap<u64, (u64, Sender<Progress>)>,
+}
+
+impl Driver {
+	async fn run(
+		mut self,
+		tick: Duration,
+		inbox: Receiver<(NodeId, Message)>,
+		proposals: Receiver<Proposal>,
+	) {
+		let mut interval = tokio::time::interval(tick);
+		loop {
+			tokio::select! {
+				_ = interval.tick() => self.node.tick(),
+				msg = inbox.recv() => match msg {
+					Ok((from, msg)) => self.node.step(from, msg),
+					Err(_) => break,
+				},
+				proposal = proposals.recv() => match proposal {
+					Ok((data, reply)) => self.propose(data, reply),
+					Err(_) => break,
+				},
+			}
+			if let Err(e) = self.advance().await {
+				error!("Error replicating the datastore: {e}");
+			}
+		}
+	}
+
+	fn propose(&mut self, data: Vec<u8>, reply: Sender<Progress>) {
+		match self.node.propose(data) {
+			Some(index) => {
+				let _ = reply.try_send(Progress::Appended(index));
+				self.pending.insert(index, (self.node.term, reply));
+			}
+			None => {
+				let _ = reply.try_send(Progress::Failed(Error::ReplicaNotLeader));
+			}
+		}
+	}
+
+	/// Persist the changes to the node, send its messages, and apply the committed entries
+	async fn advance(&mut self) -> Result<(), Error> {
+		// The state and log have to be persisted before any messages are sent
+		if let Some(index) = self.node.take_unstable() {
+			let entries = &self.node.log[index as usize - 1..];
+			self.storage.save_entries(index, entries)?;
+		}
+		if (self.node.term, self.node.vote) != (self.state.term, self.state.vote) {
+			self.state.term = self.node.term;
+			self.state.vote = self.node.vote;
+			self.storage.save_state(&self.state)?;
+		}
+		for (to, msg) in self.node.take_messages() {
+			self.peers.send(to, msg);
+		}
+		// Apply the committed entries to the datastore, in the order of the log
+		let commit = self.node.commit.min(self.node.last_index());
+		let applied = self.state.applied;
+		let mut res = Ok(());
+		while self.state.applied < commit {
+			let entry = &self.node.log[self.state.applied as usize];
+			if !entry.data.is_empty() {
+				let ops: Vec<Op> = match bincode::deserialize(&entry.data) {
+					Ok(v) => v,
+					Err(e) => {
+						res = Err(e.into());
+						break;
+					}
+				};
+				if let Err(e) = self.ds.apply(ops).await {
+					res = Err(e);
+					break;
+				}
+			}
+			self.state.applied += 1;
+		}
+		if self.state.applied > applied {
+			self.storage.save_state(&self.state)?;
+		}
+		// Answer the transactions whose entries were applied, or replaced
+		while let Some(entry) = self.pending.first_entry() {
+			let index = *entry.key();
+			let term = entry.get().0;
+			let progress = match self.node.term_at(index) == term {
+				true if index <= self.state.applied => Progress::Applied,
+				true => break,
+				false => Progress::Failed(Error::ReplicationFailed {
+					message: "the leader changed before the write was replicated".to_owned(),
+				}),
+			};
+			let _ = entry.remove().1.try_send(progress);
+		}
+		// Update the status of the node
+		let mut status = self.status.write().unwrap_or_else(PoisonError::into_inner);
+		status.role = self.node.role;
+		status.term = self.node.term;
+		status.leader = self.node.leader;
+		status.commit = commit;
+		status.applied = self.state.applied;
+		status.ready = self.node.role == Role::Leader && self.state.applied >= self.node.start;
+		res
+	}
+}
@@ -0,0 +1,119 @@
+//! Sends the messages of a replication group between its nodes over TCP.
+//! Each message is sent as a length-prefixed frame, containing the id of the
+//! sending node and the message. Messages which can not be delivered are
+//! dropped, as the Raft algorithm retries them with later messages.
+use super::node::{Message, NodeId};
+use crate::err::Error;
+use channel::{Receiver, Sender};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// The number of messages which are queued for each node before messages are dropped
+const QUEUE_SIZE: usize = 1024;
+
+/// The largest frame which is accepted from another node
+const MAX_FRAME_SIZE: usize = 256 * 1024 * 1024;
+
+/// Listen for the messages sent by the other nodes, passing them to the inbox
+pub(super) async fn listen(
+	addr: SocketAddr,
+	inbox: Sender<(NodeId, Message)>,
+) -> Result<(), Error> {
+	let listener = TcpListener::bind(addr).await?;
+	tokio::spawn(async move {
+		loop {
+			let (stream, _) = match listener.accept().await {
+				Ok(v) => v,
+				Err(e) => {
+					warn!("Unable to accept a replication connection: {e}");
+					continue;
+				}
+			};
+			// Stop listening once the node has stopped
+			if inbox.is_closed() {
+				break;
+			}
+			let inbox = inbox.clone();
+			tokio::spawn(async move {
+				if let Err(e) = receive(stream, inbox).await {
+					debug!("Replication connection closed: {e}");
+				}
+			});
+		}
+	});
+	Ok(())
+}
+
+async fn receive(mut stream: TcpStream, inbox: Sender<(NodeId, Message)>) -> Result<(), Error> {
+	loop {
+		let len = stream.read_u32().await? as usize;
+		if len > MAX_FRAME_SIZE {
+			return Err(Error::Ds(format!("Replication message of {len} bytes is too large")));
+		}
+		let mut buf = vec![0; len];
+		stream.read_exact(&mut buf).await?;
+		if inbox.send(bincode::deserialize(&buf)?).await.is_err() {
+			return Ok(());
+		}
+	}
+}
+
+/// The connections to the other nodes of a replication group
+pub(super) struct Peers {
+	queues: HashMap<NodeId, Sender<Message>>,
+}
+
+impl Peers {
+	/// Start sending messages to the other nodes
+	pub(super) fn new(id: NodeId, peers: &BTreeMap<NodeId, SocketAddr>) -> Self {
+		let mut queues = HashMap::new();
+		for (peer, addr) in peers.iter().filter(|(peer, _)| **peer != id) {
+			let (send, recv) = channel::bounded(QUEUE_SIZE);
+			tokio::spawn(deliver(id, *addr, recv));
+			queues.insert(*peer, send);
+		}
+		Self {
+			queues,
+		}
+	}
+
+	/// Queue a message to another node, dropping it if the queue is full
+	pub(super) fn send(&self, to: NodeId, msg: Message) {
+		if let Some(queue) = self.queues.get(&to) {
+			let _ = queue.try_send(msg);
+		}
+	}
+}
+
+async fn deliver(id: NodeId, addr: SocketAddr, queue: Receiver<Message>) {
+	let mut stream: Option<TcpStream> = None;
+	while let Ok(msg) = queue.recv().await {
+		// Connect to the node, if not already connected
+		if stream.is_none() {
+			match TcpStream::connect(addr).await {
+				Ok(v) => {
+					let _ = v.set_nodelay(true);
+					stream = Some(v);
+				}
+				Err(e) => {
+					trace!("Unable to connect to replication node at {addr}: {e}");
+					continue;
+				}
+			}
+		}
+		let Ok(buf) = bincode::serialize(&(id, msg)) else {
+			continue;
+		};
+		let mut frame = (buf.len() as u32).to_be_bytes().to_vec();
+		frame.extend_from_slice(&buf);
+		if let Some(v) = stream.as_mut() {
+			if let Err(e) = v.write_all(&frame).await {
+				trace!("Unable to send to replication node at {addr}: {e}");
+				stream = None;
+			}
+		}
+	}
+}
@@ -0,0 +1,566 @@
+//! The Raft consensus algorithm, without any I/O.
+//! A node is driven by ticking its clock, stepping the messages received from the
+//! other nodes, and proposing new entries when it is the leader. The messages to
+//! send, and the entries to persist, are collected on the node, so that they can
+//! be handled by the caller once the node has been updated.
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// The id of a node within a replication group
+pub(crate) type NodeId = u64;
+
+/// The maximum number of entries sent in each append message
+const MAX_APPEND_ENTRIES: usize = 64;
+
+/// An entry in the replicated log
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Entry {
+	/// The term in which the entry was created by a leader
+	pub(crate) term: u64,
+	/// The changes made by the entry, which are empty for the entry appended by a new leader
+	pub(crate) data: Vec<u8>,
+}
+
+/// A message sent between the nodes of a replication group
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Message {
+	/// A candidate asks for the vote of a node
+	Vote {
+		term: u64,
+		last_index: u64,
+		last_term: u64,
+	},
+	/// A node answers whether it voted for a candidate
+	VoteReply {
+		term: u64,
+		granted: bool,
+	},
+	/// The leader replicates its log to a follower, and tells it which entries are committed
+	Append {
+		term: u64,
+		prev_index: u64,
+		prev_term: u64,
+		entries: Vec<Entry>,
+		commit: u64,
+	},
+	/// A follower answers whether it appended the entries, and up to which index its log
+	/// matches the log of the leader, or otherwise from which index to send the entries again
+	AppendReply {
+		term: u64,
+		success: bool,
+		index: u64,
+	},
+}
+
+impl Message {
+	fn term(&self) -> u64 {
+		match self {
+			Message::Vote {
+				term,
+				..
+			}
+			| Message::VoteReply {
+				term,
+				..
+			}
+			| Message::Append {
+				term,
+				..
+			}
+			| Message::AppendReply {
+				term,
+				..
+			} => *term,
+		}
+	}
+}
+
+/// The role of a node within its replication group
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+	/// The node replicates the writes of the leader, and serves reads
+	Follower,
+	/// The node is asking the other nodes to elect it as the leader
+	Candidate,
+	/// The node handles the writes of the replication group
+	Leader,
+}
+
+pub(crate) struct Node {
+	id: NodeId,
+	peers: Vec<NodeId>,
+	/// The latest term which this node has seen
+	pub(crate) term: u64,
+	/// The node which this node voted for in the current term
+	pub(crate) vote: Option<NodeId>,
+	/// The log, where the entry at index `i` is stored at position `i - 1`
+	pub(crate) log: Vec<Entry>,
+	/// The index of the latest entry known to be committed
+	pub(crate) commit: u64,
+	pub(crate) role: Role,
+	/// The leader of the current term, if known
+	pub(crate) leader: Option<NodeId>,
+	/// The index of the first entry appended by this node, when it is the leader
+	pub(crate) start: u64,
+	// The nodes which voted for this node in the current term
+	votes: HashSet<NodeId>,
+	// The index of the next entry to send to each follower
+	next: HashMap<NodeId, u64>,
+	// The index up to which the log of each follower matches the log of the leader
+	matched: HashMap<NodeId, u64>,
+	// The ticks since the leader was last heard from, or since heartbeats were last sent
+	elapsed: u32,
+	// The randomised number of ticks after which an election is started
+	timeout: u32,
+	// The minimum number of ticks after which an election is started
+	election: u32,
+	// The number of ticks between the heartbeats sent by the leader
+	heartbeat: u32,
+	// The lowest index of the entries which changed since the log was last persisted
+	unstable: Option<u64>,
+	// The messages to send to the other nodes
+	outbox: Vec<(NodeId, Message)>,
+}
+
+impl Node {
+	/// Create a node, restoring the state which it persisted
+	pub(crate) fn new(
+		id: NodeId,
+		peers: Vec<NodeId>,
+		term: u64,
+		vote: Option<NodeId>,
+		log: Vec<Entry>,
+		election: u32,
+		heartbeat: u32,
+	) -> Self {
+		let mut node = Self {
+			id,
+			peers: peers.into_iter().filter(|v| *v != id).collect(),
+			term,
+			vote,
+			log,
+			commit: 0,
+			role: Role::Follower,
+			leader: None,
+			start: 0,
+			votes: HashSet::new(),
+			next: HashMap::new(),
+			matched: HashMap::new(),
+			elapsed: 0,
+			timeout: 0,
+			election: election.max(1),
+			heartbeat: heartbeat.max(1),
+			unstable: None,
+			outbox: Vec::new(),
+		};
+		node.reset_timeout();
+		node
+	}
+
+	/// Advance the clock of the node by one tick
+	pub(crate) fn tick(&mut self) {
+		self.elapsed += 1;
+		match self.role {
+			Role::Leader => {
+				if self.elapsed >= self.heartbeat {
+					self.elapsed = 0;
+					self.broadcast();
+				}
+			}
+			_ => {
+				if self.elapsed >= self.timeout {
+					self.campaign();
+				}
+			}
+		}
+	}
+
+	/// Append an entry to the log, when this node is the leader, returning its index
+	pub(crate) fn propose(&mut self, data: Vec<u8>) -> Option<u64> {
+		if self.role != Role::Leader {
+			return None;
+		}
+		self.append(Entry {
+			term: self.term,
+			data,
+		});
+		self.maybe_commit();
+		self.broadcast();
+		Some(self.last_index())
+	}
+
+	/// Handle a message received from another node
+	pub(crate) fn step(&mut self, from: NodeId, msg: Message) {
+		if !self.peers.contains(&from) {
+			return;
+		}
+		if msg.term() > self.term {
+			self.become_follower(msg.term(), None);
+		}
+		match msg {
+			Message::Vote {
+				term,
+				last_index,
+				last_term,
+			} => {
+				let current = (last_term, last_index) >= (self.last_term(), self.last_index());
+				let granted = term == self.term && current && self.vote.map_or(true, |v| v == from);
+				if granted {
+					self.vote = Some(from);
+					self.elapsed = 0;
+				}
+				self.send(
+					from,
+					Message::VoteReply {
+						term: self.term,
+						granted,
+					},
+				);
+			}
+			Message::VoteReply {
+				term,
+				granted,
+			} => {
+				if self.role == Role::Candidate && term == self.term && granted {
+					self.votes.insert(from);
+					if self.votes.len() >= self.quorum() {
+						self.become_leader();
+					}
+				}
+			}
+			Message::Append {
+				term,
+				prev_index,
+				prev_term,
+				entries,
+				commit,
+			} => {
+				if term < self.term {
+					self.send(
+						from,
+						Message::AppendReply {
+							term: self.term,
+							success: false,
+							index: self.last_index(),
+						},
+					);
+					return;
+				}
+				self.become_follower(term, Some(from));
+				// Check that the log matches the log of the leader before the entries
+				if prev_index > self.last_index() || self.term_at(prev_index) != prev_term {
+					let index = prev_index.saturating_sub(1).min(self.last_index());
+					self.send(
+						from,
+						Message::AppendReply {
+							term: self.term,
+							success: false,
+							index,
+						},
+					);
+					return;
+				}
+				// Append the entries, replacing any conflicting entries
+				let last = prev_index + entries.len() as u64;
+				for (index, entry) in (prev_index + 1..).zip(entries) {
+					if index <= self.last_index() {
+						if self.term_at(index) == entry.term {
+							continue;
+						}
+						self.truncate(index);
+					}
+					self.append(entry);
+				}
+				if commit > self.commit {
+					self.commit = commit.min(last);
+				}
+				self.send(
+					from,
+					Message::AppendReply {
+						term: self.term,
+						success: true,
+						index: last,
+					},
+				);
+			}
+			Message::AppendReply {
+				term,
+				success,
+				index,
+			} => {
+				if self.role != Role::Leader || term != self.term {
+					return;
+				}
+				if success {
+					let matched = self.matched.entry(from).or_default();
+					*matched = index.max(*matched);
+					let next = *matched + 1;
+					self.next.insert(from, next);
+					self.maybe_commit();
+					if next <= self.last_index() {
+						self.send_append(from);
+					}
+				} else {
+					let next = self.next.get(&from).copied().unwrap_or(1);
+					self.next.insert(from, next.saturating_sub(1).min(index + 1).max(1));
+					self.send_append(from);
+				}
+			}
+		}
+	}
+
+	/// Take the messages which need to be sent to the other nodes
+	pub(crate) fn take_messages(&mut self) -> Vec<(NodeId, Message)> {
+		std::mem::take(&mut self.outbox)
+	}
+
+	/// Take the lowest index of the entries which changed since this was last called
+	pub(crate) fn take_unstable(&mut self) -> Option<u64> {
+		self.unstable.take()
+	}
+
+	/// The index of the last entry in the log
+	pub(crate) fn last_index(&self) -> u64 {
+		self.log.len() as u64
+	}
+
+	/// The term of the entry at an index, or 0 when there is no such entry
+	pub(crate) fn term_at(&self, index: u64) -> u64 {
+		match index {
+			0 => 0,
+			i => self.log.get(i as usize - 1).map_or(0, |e| e.term),
+		}
+	}
+
+	fn last_term(&self) -> u64 {
+		self.term_at(self.last_index())
+	}
+
+	fn quorum(&self) -> usize {
+		(self.peers.len() + 1) / 2 + 1
+	}
+
+	fn reset_timeout(&mut self) {
+		self.timeout = self.election + rand::thread_rng().gen_range(0..self.election);
+	}
+
+	fn send(&mut self, to: NodeId, msg: Message) {
+		self.outbox.push((to, msg));
+	}
+
+	fn append(&mut self, entry: Entry) {
+		self.log.push(entry);
+		let index = self.last_index();
+		self.unstable = Some(self.unstable.map_or(index, |v| v.min(index)));
+	}
+
+	fn truncate(&mut self, index: u64) {
+		self.log.truncate(index as usize - 1);
+		self.unstable = Some(self.unstable.map_or(index, |v| v.min(index)));
+	}
+
+	fn campaign(&mut self) {
+		self.term += 1;
+		self.role = Role::Candidate;
+		self.vote = Some(self.id);
+		self.leader = None;
+		self.votes = HashSet::from([self.id]);
+		self.elapsed = 0;
+		self.reset_timeout();
+		if self.votes.len() >= self.quorum() {
+			self.become_leader();
+			return;
+		}
+		let (last_index, last_term) = (self.last_index(), self.last_term());
+		for peer in self.peers.clone() {
+			self.send(
+				peer,
+				Message::Vote {
+					term: self.term,
+					last_index,
+					last_term,
+				},
+			);
+		}
+	}
+
+	fn become_follower(&mut self, term: u64, leader: Option<NodeId>) {
+		if term > self.term {
+			self.term = term;
+			self.vote = None;
+		}
+		if self.role != Role::Follower || leader.is_some() {
+			self.elapsed = 0;
+		}
+		self.role = Role::Follower;
+		self.leader = leader;
+	}
+
+	fn become_leader(&mut self) {
+		self.role = Role::Leader;
+		self.leader = Some(self.id);
+		self.elapsed = 0;
+		let next = self.last_index() + 1;
+		self.next = self.peers.iter().map(|p| (*p, next)).collect();
+		self.matched = self.peers.iter().map(|p| (*p, 0)).collect();
+		// Entries from previous terms are only committed along with an entry from this term
+		self.append(Entry {
+			term: self.term,
+			data: Vec::new(),
+		});
+		self.start = self.last_index();
+		self.maybe_commit();
+		self.broadcast();
+	}
+
+	fn maybe_commit(&mut self) {
+		let mut matched: Vec<u64> = self.matched.values().copied().collect();
+		matched.push(self.last_index());
+		matched.sort_unstable_by(|a, b| b.cmp(a));
+		let index = matched[self.quorum() - 1];
+		if index > self.commit && self.term_at(index) == self.term {
+			self.commit = index;
+		}
+	}
+
+	fn broadcast(&mut self) {
+		for peer in self.peers.clone() {
+			self.send_append(peer);
+		}
+	}
+
+	fn send_append(&mut self, to: NodeId) {
+		let next = self.next.get(&to).copied().unwrap_or(1).max(1);
+		let prev_index = next - 1;
+		let end = (prev_index as usize + MAX_APPEND_ENTRIES).min(self.log.len());
+		let entries = self.log[prev_index as usize..end].to_vec();
+		self.send(
+			to,
+			Message::Append {
+				term: self.term,
+				prev_index,
+				prev_term: self.term_at(prev_index),
+				entries,
+				commit: self.commit,
+			},
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn group(n: u64) -> Vec<Node> {
+		let ids: Vec<NodeId> = (1..=n).collect();
+		ids.iter().map(|id| Node::new(*id, ids.clone(), 0, None, Vec::new(), 10, 2)).collect()
+	}
+
+	// Deliver the messages between the nodes until there are none left
+	fn deliver(nodes: &mut [Node], down: &[NodeId]) {
+		loop {
+			let mut msgs = Vec::new();
+			for node in nodes.iter_mut() {
+				for (to, msg) in node.take_messages() {
+					msgs.push((node.id, to, msg));
+				}
+			}
+			if msgs.is_empty() {
+				break;
+			}
+			for (from, to, msg) in msgs {
+				if down.contains(&from) || down.contains(&to) {
+					continue;
+				}
+				nodes[to as usize - 1].step(from, msg);
+			}
+		}
+	}
+
+	fn elect(nodes: &mut [Node], id: NodeId, down: &[NodeId]) {
+		nodes[id as usize - 1].campaign();
+		deliver(nodes, down);
+	}
+
+	#[test]
+	fn single_node_elects_itself() {
+		let mut nodes = group(1);
+		for _ in 0..20 {
+			nodes[0].tick();
+		}
+		assert_eq!(nodes[0].role, Role::Leader);
+		assert_eq!(nodes[0].propose(b"a".to_vec()), Some(2));
+		assert_eq!(nodes[0].commit, 2);
+	}
+
+	#[test]
+	fn leader_replicates_entries() {
+		let mut nodes = group(3);
+		elect(&mut nodes, 1, &[]);
+		assert_eq!(nodes[0].role, Role::Leader);
+		assert_eq!(nodes[1].leader, Some(1));
+		let index = nodes[0].propose(b"a".to_vec()).unwrap();
+		deliver(&mut nodes, &[]);
+		assert_eq!(nodes[0].commit, index);
+		// The followers learn of the commit with the next heartbeat
+		nodes[0].tick();
+		nodes[0].tick();
+		deliver(&mut nodes, &[]);
+		for node in nodes.iter() {
+			assert_eq!(node.commit, index);
+			assert_eq!(node.log[index as usize - 1].data, b"a".to_vec());
+		}
+	}
+
+	#[test]
+	fn entries_commit_with_a_majority() {
+		let mut nodes = group(3);
+		elect(&mut nodes, 1, &[]);
+		// One follower is unreachable, which still leaves a majority
+		let index = nodes[0].propose(b"a".to_vec()).unwrap();
+		deliver(&mut nodes, &[3]);
+		assert_eq!(nodes[0].commit, index);
+		// Without a majority nothing is committed
+		let index = nodes[0].propose(b"b".to_vec()).unwrap();
+		deliver(&mut nodes, &[2, 3]);
+		assert!(nodes[0].commit < index);
+	}
+
+	#[test]
+	fn new_leader_overwrites_uncommitted_entries() {
+		let mut nodes = group(3);
+		elect(&mut nodes, 1, &[]);
+		// The leader appends an entry which is never replicated
+		nodes[0].propose(b"lost".to_vec()).unwrap();
+		nodes[0].take_messages();
+		nodes[0].take_unstable();
+		// Another node is elected without the old leader
+		elect(&mut nodes, 2, &[1]);
+		assert_eq!(nodes[1].role, Role::Leader);
+		let index = nodes[1].propose(b"kept".to_vec()).unwrap();
+		deliver(&mut nodes, &[1]);
+		assert_eq!(nodes[1].commit, index);
+		// The old leader rejoins, and its conflicting entry is replaced
+		nodes[1].tick();
+		nodes[1].tick();
+		deliver(&mut nodes, &[]);
+		assert_eq!(nodes[0].role, Role::Follower);
+		assert_eq!(nodes[0].log, nodes[1].log);
+		assert_eq!(nodes[0].commit, index);
+		assert_eq!(nodes[0].take_unstable(), Some(2));
+	}
+
+	#[test]
+	fn candidate_with_stale_log_is_not_elected() {
+		let mut nodes = group(3);
+		elect(&mut nodes, 1, &[]);
+		nodes[0].propose(b"a".to_vec()).unwrap();
+		deliver(&mut nodes, &[3]);
+		// Node 3 missed the entry, so node 2 refuses to vote for it
+		elect(&mut nodes, 3, &[1]);
+		assert_ne!(nodes[2].role, Role::Leader);
+		elect(&mut nodes, 2, &[1]);
+		assert_eq!(nodes[1].role, Role::Leader);
+	}
+}
@@ -0,0 +1,140 @@
+//! Persists the state of a node, and its log, in a directory next to the datastore.
+//! The log is stored as a single file of length-prefixed entries, which is appended to
+//! as entries are added, and truncated when conflicting entries are replaced.
+use super::node::{Entry, NodeId};
+use crate::err::Error;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+/// The name of the file which stores the state of the node
+const STATE: &str = "state";
+/// The name of the file which stores the log
+const LOG: &str = "log";
+
+/// The state of a node which has to survive a restart
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct State {
+	/// The latest term which the node has seen
+	pub(crate) term: u64,
+	/// The node which this node voted for in the latest term
+	pub(crate) vote: Option<NodeId>,
+	/// The index of the latest entry applied to the datastore
+	pub(crate) applied: u64,
+}
+
+pub(crate) struct Storage {
+	dir: PathBuf,
+	file: File,
+	// The offset in the log file at which each entry starts
+	offsets: Vec<u64>,
+	// The length of the log file
+	len: u64,
+}
+
+impl Storage {
+	/// Open the storage in a directory, returning the persisted state and log
+	pub(crate) fn open(dir: PathBuf) -> Result<(Self, State, Vec<Entry>), Error> {
+		fs::create_dir_all(&dir)?;
+		// Read the state of the node
+		let state = match fs::read(dir.join(STATE)) {
+			Ok(v) => bincode::deserialize(&v)?,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => State::default(),
+			Err(e) => return Err(e.into()),
+		};
+		// Read the entries of the log
+		let mut file =
+			OpenOptions::new().read(true).write(true).create(true).open(dir.join(LOG))?;
+		let mut buf = Vec::new();
+		file.read_to_end(&mut buf)?;
+		let mut entries = Vec::new();
+		let mut offsets = Vec::new();
+		let mut pos = 0;
+		while let Some(len) = buf.get(pos..pos + 4) {
+			let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+			// Ignore an entry which was only partially written
+			let Some(val) = buf.get(pos + 4..pos + 4 + len) else {
+				break;
+			};
+			entries.push(bincode::deserialize(val)?);
+			offsets.push(pos as u64);
+			pos += 4 + len;
+		}
+		file.set_len(pos as u64)?;
+		let storage = Self {
+			dir,
+			file,
+			offsets,
+			len: pos as u64,
+		};
+		Ok((storage, state, entries))
+	}
+
+	/// Persist the state of the node, replacing the previous state
+	pub(crate) fn save_state(&self, state: &State) -> Result<(), Error> {
+		let tmp = self.dir.join(format!("{STATE}.tmp"));
+		let mut file = File::create(&tmp)?;
+		file.write_all(&bincode::serialize(state)?)?;
+		file.sync_all()?;
+		fs::rename(tmp, self.dir.join(STATE))?;
+		Ok(())
+	}
+
+	/// Persist the entries from an index onwards, replacing any entries already stored from there
+	pub(crate) fn save_entries(&mut self, index: u64, entries: &[Entry]) -> Result<(), Error> {
+		// Remove the entries which were replaced
+		let keep = (index as usize).saturating_sub(1).min(self.offsets.len());
+		if keep < self.offsets.len() {
+			self.len = self.offsets[keep];
+			self.offsets.truncate(keep);
+			self.file.set_len(self.len)?;
+		}
+		// Append the new entries
+		let mut buf = Vec::new();
+		for entry in entries {
+			let val = bincode::serialize(entry)?;
+			self.offsets.push(self.len + buf.len() as u64);
+			buf.extend_from_slice(&(val.len() as u32).to_be_bytes());
+			buf.extend_from_slice(&val);
+		}
+		self.file.seek(SeekFrom::Start(self.len))?;
+		self.file.write_all(&buf)?;
+		self.file.sync_data()?;
+		self.len += buf.len() as u64;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use temp_dir::TempDir;
+
+	fn entry(term: u64, data: &[u8]) -> Entry {
+		Entry {
+			term,
+			data: data.to_vec(),
+		}
+	}
+
+	#[test]
+	fn entries_are_replaced() {
+		let dir = TempDir::new().unwrap();
+		let (mut storage, state, log) = Storage::open(dir.path().to_path_buf()).unwrap();
+		assert_eq!((state, log), (State::default(), vec![]));
+		storage.save_entries(1, &[entry(1, b"a"), entry(1, b"b"), entry(1, b"c")]).unwrap();
+		storage.save_entries(2, &[entry(2, b"d")]).unwrap();
+		let state = State {
+			term: 2,
+			vote: Some(3),
+			applied: 1,
+		};
+		storage.save_state(&state).unwrap();
+		drop(storage);
+		// The storage is reopened with the replaced entries
+		let (_, saved, log) = Storage::open(dir.path().to_path_buf()).unwrap();
+		assert_eq!(saved, state);
+		assert_eq!(log, vec![entry(1, b"a"), entry(2, b"d")]);
+	}
+}
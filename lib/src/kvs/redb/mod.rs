@@ -0,0 +1,488 @@
+#![cfg(feature = "kv-redb")]
+
+use crate::err::Error;
+use crate::kvs::Check;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use redb::{Database, ReadTransaction, ReadableTable, TableDefinition};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// The single table in which all keys are stored
+const TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("surrealdb");
+
+#[derive(Clone)]
+pub struct Datastore {
+	db: Pin<Arc<Database>>,
+}
+
+pub struct Transaction {
+	// Is the transaction complete?
+	done: bool,
+	// Is the transaction writeable?
+	write: bool,
+	/// Should we check unhandled transactions?
+	check: Check,
+	/// The read snapshot of the underlying datastore
+	inner: Option<ReadTransaction<'static>>,
+	/// The buffered writes, where deleted keys are
+	/// stored as `None`, to be applied on commit
+	writes: BTreeMap<Key, Option<Val>>,
+	// The above, supposedly 'static transaction
+	// actually points here, so we need to ensure
+	// the memory is kept alive. This pointer must
+	// be declared last, so that it is dropped last
+	_db: Pin<Arc<Database>>,
+}
+
+impl Drop for Transaction {
+	fn drop(&mut self) {
+		if !self.done && self.write {
+			// Check if already panicking
+			if std::thread::panicking() {
+				return;
+			}
+			// Handle the behaviour
+			match self.check {
+				Check::None => {
+					trace!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Warn => {
+					warn!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Panic => {
+					#[cfg(debug_assertions)]
+					{
+						let backtrace = std::backtrace::Backtrace::force_capture();
+						if let std::backtrace::BacktraceStatus::Captured = backtrace.status() {
+							println!("{}", backtrace);
+						}
+					}
+					panic!("A transaction was dropped without being committed or cancelled");
+				}
+			}
+		}
+	}
+}
+
+impl Datastore {
+	/// Open a new database
+	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+		// Open or create the database file
+		let db = Database::create(path)?;
+		// Ensure that the table exists, so
+		// that read transactions can open it
+		let tx = db.begin_write()?;
+		tx.open_table(TABLE)?;
+		tx.commit()?;
+		// Return the datastore
+		Ok(Datastore {
+			db: Arc::pin(db),
+		})
+	}
+	/// Flush any buffered writes to disk
+	pub(crate) async fn flush(&self) -> Result<(), Error> {
+		// Each commit is durably written to disk
+		Ok(())
+	}
+	/// Start a new transaction
+	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
+		// Create a new read snapshot
+		let inner = self.db.begin_read()?;
+		// The database reference must always outlive
+		// the transaction. If it doesn't then this
+		// is undefined behaviour. This unsafe block
+		// ensures that the transaction reference is
+		// static, but will cause a crash if the
+		// datastore is dropped prematurely.
+		let inner =
+			unsafe { std::mem::transmute::<ReadTransaction<'_>, ReadTransaction<'static>>(inner) };
+		// Specify the check level
+		#[cfg(not(debug_assertions))]
+		let check = Check::Warn;
+		#[cfg(debug_assertions)]
+		let check = Check::Panic;
+		// Create a new transaction
+		Ok(Transaction {
+			done: false,
+			check,
+			write,
+			inner: Some(inner),
+			writes: BTreeMap::new(),
+			_db: self.db.clone(),
+		})
+	}
+}
+
+impl Transaction {
+	/// Behaviour if unclosed
+	pub(crate) fn check_level(&mut self, check: Check) {
+		self.check = check;
+	}
+	/// Check if closed
+	pub(crate) fn closed(&self) -> bool {
+		self.done
+	}
+	/// Cancel a transaction
+	pub(crate) async fn cancel(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Cancel this transaction
+		match self.inner.take() {
+			Some(_) => self.writes.clear(),
+			None => unreachable!(),
+		};
+		// Continue
+		Ok(())
+	}
+	/// Commit a transaction
+	pub(crate) async fn commit(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Take the read snapshot
+		let inner = match self.inner.take() {
+			Some(inner) => inner,
+			None => unreachable!(),
+		};
+		// Take the buffered writes
+		let writes = std::mem::take(&mut self.writes);
+		// There is nothing to write
+		if writes.is_empty() {
+			return Ok(());
+		}
+		// Start the single write transaction
+		let tx = self._db.begin_write()?;
+		{
+			let snap = inner.open_table(TABLE)?;
+			let mut table = tx.open_table(TABLE)?;
+			// Check that the written keys have not been
+			// modified by another transaction since the
+			// snapshot of this transaction was taken
+			for key in writes.keys() {
+				let old = snap.get(key.as_slice())?.map(|v| v.value().to_vec());
+				let now = table.get(key.as_slice())?.map(|v| v.value().to_vec());
+				if old != now {
+					drop(table);
+					tx.abort()?;
+					return Err(Error::Tx("Transaction write conflict".to_owned()));
+				}
+			}
+			// Apply the buffered writes
+			for (key, val) in writes.iter() {
+				match val {
+					Some(val) => table.insert(key.as_slice(), val.as_slice())?,
+					None => table.remove(key.as_slice())?,
+				};
+			}
+		}
+		// Commit this transaction
+		tx.commit()?;
+		// Continue
+		Ok(())
+	}
+	/// Fetch a key from the snapshot, or from the buffered writes
+	fn fetch(&self, key: &Key) -> Result<Option<Val>, Error> {
+		// Check the buffered writes first
+		if let Some(val) = self.writes.get(key) {
+			return Ok(val.clone());
+		}
+		// Otherwise check the snapshot
+		let table = self.inner.as_ref().unwrap().open_table(TABLE)?;
+		let res = table.get(key.as_slice())?.map(|v| v.value().to_vec());
+		// Return result
+		Ok(res)
+	}
+	/// Check if a key exists
+	pub(crate) async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check the key
+		let res = self.fetch(&key.into())?.is_some();
+		// Return result
+		Ok(res)
+	}
+	/// Fetch a key from the database
+	pub(crate) async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the key
+		let res = self.fetch(&key.into())?;
+		// Return result
+		Ok(res)
+	}
+	/// Obtain a new change timestamp for a key
+	/// which is replaced with the current timestamp when the transaction is committed.
+	/// NOTE: This should be called when composing the change feed entries for this transaction,
+	/// which should be done immediately before the transaction commit.
+	/// That is to keep other transactions commit delay(pessimistic) or conflict(optimistic) as less as possible.
+	#[allow(unused)]
+	pub(crate) async fn get_timestamp<K>(&mut self, key: K) -> Result<Versionstamp, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Write the timestamp to the "last-write-timestamp" key
+		// to ensure that no other transactions can commit with older timestamps.
+		let k: Key = key.into();
+		let prev = self.fetch(&k)?;
+		let ver = match prev {
+			Some(prev) => {
+				let slice = prev.as_slice();
+				let res: Result<[u8; 10], Error> = match slice.try_into() {
+					Ok(ba) => Ok(ba),
+					Err(e) => Err(Error::Ds(e.to_string())),
+				};
+				let array = res?;
+				let prev = try_to_u64_be(array)?;
+				prev + 1
+			}
+			None => 1,
+		};
+
+		let verbytes = u64_to_versionstamp(ver);
+
+		self.writes.insert(k, Some(verbytes.to_vec()));
+		// Return the uint64 representation of the timestamp as the result
+		Ok(verbytes)
+	}
+	/// Obtain a new key that is suffixed with the change timestamp
+	pub(crate) async fn get_versionstamped_key<K>(
+		&mut self,
+		ts_key: K,
+		prefix: K,
+		suffix: K,
+	) -> Result<Vec<u8>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		let ts = self.get_timestamp(ts_key).await?;
+		let mut k: Vec<u8> = prefix.into();
+		k.append(&mut ts.to_vec());
+		k.append(&mut suffix.into());
+		Ok(k)
+	}
+	/// Insert or update a key in the database
+	pub(crate) async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Set the key
+		self.writes.insert(key.into(), Some(val.into()));
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		// Set the key if empty
+		match self.fetch(&key)? {
+			None => self.writes.insert(key, Some(val)),
+			_ => return Err(Error::TxKeyAlreadyExists),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		let chk = chk.map(Into::into);
+		// Set the key if valid
+		match (self.fetch(&key)?, chk) {
+			(Some(v), Some(w)) if v == w => self.writes.insert(key, Some(val)),
+			(None, None) => self.writes.insert(key, Some(val)),
+			_ => return Err(Error::TxConditionNotMet),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn del<K>(&mut self, key: K) -> Result<(), Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Remove the key
+		self.writes.insert(key.into(), None);
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let chk = chk.map(Into::into);
+		// Delete the key if valid
+		match (self.fetch(&key)?, chk) {
+			(Some(v), Some(w)) if v == w => self.writes.insert(key, None),
+			(None, None) => self.writes.insert(key, None),
+			_ => return Err(Error::TxConditionNotMet),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Retrieve a range of keys from the databases
+	pub(crate) async fn scan<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Create result set
+		let mut res = vec![];
+		// Check the range is not empty
+		if rng.start >= rng.end {
+			return Ok(res);
+		}
+		// Set the key range
+		let beg = rng.start.as_slice();
+		let end = rng.end.as_slice();
+		// Create the snapshot iterator
+		let table = self.inner.as_ref().unwrap().open_table(TABLE)?;
+		let mut snap = table.range(beg..end)?;
+		let mut head = next(&mut snap)?;
+		// Create the buffered writes iterator
+		let mut writes = self.writes.range(rng.clone()).peekable();
+		// Merge both iterators in key order, where
+		// the buffered writes take precedence
+		while res.len() < limit as usize {
+			let (read, write) = match (&head, writes.peek()) {
+				(Some((k, _)), Some((w, _))) if k < *w => (true, false),
+				(Some((k, _)), Some((w, _))) if k == *w => (true, true),
+				(_, Some(_)) => (false, true),
+				(Some(_), None) => (true, false),
+				(None, None) => break,
+			};
+			// Take the next key from the snapshot
+			if read {
+				let kv = std::mem::replace(&mut head, next(&mut snap)?);
+				if let (Some(kv), false) = (kv, write) {
+					res.push(kv);
+				}
+			}
+			// Take the next key from the buffered writes
+			if write {
+				if let Some((k, Some(v))) = writes.next() {
+					res.push((k.clone(), v.clone()));
+				}
+			}
+		}
+		// Return result
+		Ok(res)
+	}
+}
+
+/// Fetch the next key and value from a snapshot range
+fn next(
+	iter: &mut redb::Range<'_, &'static [u8], &'static [u8]>,
+) -> Result<Option<(Key, Val)>, Error> {
+	match iter.next() {
+		Some(v) => {
+			let (k, v) = v?;
+			Ok(Some((k.value().to_vec(), v.value().to_vec())))
+		}
+		None => Ok(None),
+	}
+}
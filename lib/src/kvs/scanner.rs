@@ -0,0 +1,57 @@
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::kvs::Transaction;
+use crate::kvs::Val;
+use std::ops::Range;
+
+/// Scans a range of keys in batches of a fixed size
+///
+/// Only one batch of key-value pairs is held in memory at a time, so
+/// that large tables and indexes can be iterated over without reading
+/// the whole key range into memory at once.
+pub(crate) struct Scanner {
+	/// The key from which the next batch starts
+	beg: Key,
+	/// The key at which the range ends
+	end: Key,
+	/// The maximum number of key-value pairs in a batch
+	batch: u32,
+	/// Whether the whole range has been scanned
+	done: bool,
+}
+
+impl Scanner {
+	/// Create a scanner over a range of keys
+	pub(crate) fn new<K>(rng: Range<K>, batch: u32) -> Self
+	where
+		K: Into<Key>,
+	{
+		Self {
+			beg: rng.start.into(),
+			end: rng.end.into(),
+			batch: batch.max(1),
+			done: false,
+		}
+	}
+	/// Fetch the next batch of key-value pairs
+	///
+	/// The batch is empty once the whole range has been scanned.
+	pub(crate) async fn next(&mut self, tx: &mut Transaction) -> Result<Vec<(Key, Val)>, Error> {
+		// Check if the range has been scanned
+		if self.done {
+			return Ok(vec![]);
+		}
+		// Get the next batch of key-value pairs
+		let res = tx.scan(self.beg.clone()..self.end.clone(), self.batch).await?;
+		// Ready the next batch
+		match res.last() {
+			Some((k, _)) if res.len() == self.batch as usize => {
+				self.beg = k.clone();
+				self.beg.push(0x00);
+			}
+			_ => self.done = true,
+		}
+		// Return result
+		Ok(res)
+	}
+}
@@ -1,19 +1,34 @@
 #![cfg(feature = "kv-speedb")]
 
 use crate::err::Error;
+use crate::kvs::cipher::{Cipher, Reencryption};
+use crate::kvs::partition::{self, Changes, Registry, Route};
 use crate::kvs::Check;
 use crate::kvs::Key;
 use crate::kvs::Val;
+use crate::sql::Isolation;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
 use futures::lock::Mutex;
-use speedb::{OptimisticTransactionDB, OptimisticTransactionOptions, ReadOptions, WriteOptions};
+use speedb::{
+	BoundColumnFamily, Direction, IteratorMode, MultiThreaded, OptimisticTransactionOptions,
+	Options, ReadOptions, WriteOptions, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
+use std::collections::BTreeMap;
 use std::ops::Range;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError, RwLock};
+
+type OptimisticTransactionDB = speedb::OptimisticTransactionDB<MultiThreaded>;
 
 #[derive(Clone)]
 pub struct Datastore {
 	db: Pin<Arc<OptimisticTransactionDB>>,
+	// The column family of each table and index partition
+	partitions: Arc<RwLock<Registry>>,
+	// The cipher used to encrypt the persisted values
+	cipher: Option<Arc<Cipher>>,
+	// The position from which values encrypted with a previous key are re-encrypted
+	reencryption: Arc<RwLock<Option<Reencryption>>>,
 }
 
 pub struct Transaction {
@@ -27,6 +42,14 @@ pub struct Transaction {
 	inner: Arc<Mutex<Option<speedb::Transaction<'static, OptimisticTransactionDB>>>>,
 	// The read options containing the Snapshot
 	ro: ReadOptions,
+	// Should reads be checked for conflicts when committing?
+	isolation: Isolation,
+	// The column family of each table and index partition
+	partitions: Arc<RwLock<Registry>>,
+	// The partitions dropped or recreated in this transaction
+	changes: Changes,
+	// The cipher used to encrypt the persisted values
+	cipher: Option<Arc<Cipher>>,
 	// The above, supposedly 'static transaction
 	// actually points here, so we need to ensure
 	// the memory is kept alive. This pointer must
@@ -67,12 +90,199 @@ impl Drop for Transaction {
 impl Datastore {
 	/// Open a new database
 	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+		// Configure the database options
+		let mut opts = Options::default();
+		opts.create_if_missing(true);
+		// Open the column families of any existing partitions
+		let names = DB::list_cf(&opts, path).unwrap_or_default();
+		let db = OptimisticTransactionDB::open_cf(&opts, path, &names)?;
+		// Load the column family of each partition
+		let mut registry = Registry::new();
+		for v in db.iterator(IteratorMode::From(partition::MAPPING, Direction::Forward)) {
+			let (k, v) = v?;
+			if !k.starts_with(partition::MAPPING) {
+				break;
+			}
+			let name = String::from_utf8_lossy(&v).into_owned();
+			registry.insert(k[partition::MAPPING.len()..].to_vec(), name);
+		}
+		// Drop any column families left behind by unfinished transactions
+		for name in names.iter() {
+			if name != DEFAULT_COLUMN_FAMILY_NAME && !registry.values().any(|v| v == name) {
+				db.drop_cf(name)?;
+			}
+		}
 		Ok(Datastore {
-			db: Arc::pin(OptimisticTransactionDB::open_default(path)?),
+			db: Arc::pin(db),
+			partitions: Arc::new(RwLock::new(registry)),
+			cipher: None,
+			reencryption: Arc::new(RwLock::new(None)),
 		})
 	}
+	/// Encrypt the persisted values with the specified key
+	pub(crate) fn set_encryption_key(&mut self, key: &[u8; 32]) {
+		self.cipher = Some(Arc::new(Cipher::new(key)));
+	}
+	/// Allow values encrypted with a previous key to be read
+	pub(crate) fn add_previous_encryption_key(&mut self, key: &[u8; 32]) {
+		if let Some(cipher) = &mut self.cipher {
+			Arc::make_mut(cipher).add_previous_key(key);
+			*self.reencryption.write().unwrap_or_else(PoisonError::into_inner) =
+				Some(Reencryption::default());
+		}
+	}
+	/// Allow values persisted before encryption was enabled to be read
+	pub(crate) fn allow_unencrypted_data(&mut self) {
+		if let Some(cipher) = &mut self.cipher {
+			Arc::make_mut(cipher).allow_unencrypted();
+			*self.reencryption.write().unwrap_or_else(PoisonError::into_inner) =
+				Some(Reencryption::default());
+		}
+	}
+	/// Re-encrypt the next batch of values which were encrypted with a previous key
+	///
+	/// Returns how many values were re-encrypted, or `None` once every value has
+	/// been scanned. Values which change while the batch is re-encrypted are left
+	/// to be re-encrypted when they are written, and a batch which conflicts with
+	/// another transaction is scanned again by the next call.
+	pub(crate) async fn reencrypt(&self, limit: usize) -> Result<Option<u64>, Error> {
+		let cipher = match &self.cipher {
+			Some(cipher) if cipher.has_previous_keys() => cipher,
+			_ => return Ok(None),
+		};
+		let Some(mut pos) =
+			self.reencryption.read().unwrap_or_else(PoisonError::into_inner).clone()
+		else {
+			return Ok(None);
+		};
+		let families = self
+			.partitions
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.values()
+			.cloned()
+			.collect::<Vec<_>>();
+		// Find the column family to scan, skipping any dropped partitions
+		let cf = loop {
+			match &pos.family {
+				None => break None,
+				Some(name) => match self.db.cf_handle(name) {
+					Some(cf) => break Some(cf),
+					None => match pos.next(families.iter()) {
+						Some(next) => pos = next,
+						None => {
+							self.finish_reencryption();
+							return Ok(None);
+						}
+					},
+				},
+			}
+		};
+		// Scan the next batch of values
+		let mut iter = match &cf {
+			Some(cf) => self.db.raw_iterator_cf(cf),
+			None => self.db.raw_iterator(),
+		};
+		match &pos.after {
+			Some(key) => {
+				iter.seek(key);
+				if iter.key() == Some(key.as_slice()) {
+					iter.next();
+				}
+			}
+			None => iter.seek_to_first(),
+		}
+		let mut stale = Vec::new();
+		let mut scanned = 0;
+		while scanned < limit {
+			let (Some(key), Some(val)) = (iter.key(), iter.value()) else {
+				break;
+			};
+			if let Some(new) = cipher.reseal(key, val)? {
+				stale.push((key.to_vec(), val.to_vec(), new));
+			}
+			pos.after = Some(key.to_vec());
+			scanned += 1;
+			iter.next();
+		}
+		iter.status()?;
+		let done = !iter.valid();
+		drop(iter);
+		// Rewrite the values which have not changed since they were scanned
+		let txn = self.db.transaction_opt(&WriteOptions::default(), &Default::default());
+		let mut count = 0;
+		for (key, old, new) in stale.iter() {
+			let val = match &cf {
+				Some(cf) => txn.get_for_update_cf(cf, key, true)?,
+				None => txn.get_for_update(key, true)?,
+			};
+			if val.as_deref() == Some(old.as_slice()) {
+				match &cf {
+					Some(cf) => txn.put_cf(cf, key, new)?,
+					None => txn.put(key, new)?,
+				}
+				count += 1;
+			}
+		}
+		txn.commit()?;
+		// Continue with the next column family
+		if done {
+			match pos.next(families.iter()) {
+				Some(next) => pos = next,
+				None => {
+					self.finish_reencryption();
+					return Ok(Some(count));
+				}
+			}
+		}
+		*self.reencryption.write().unwrap_or_else(PoisonError::into_inner) = Some(pos);
+		Ok(Some(count))
+	}
+	/// Stop re-encrypting values, once every value has been scanned
+	fn finish_reencryption(&self) {
+		*self.reencryption.write().unwrap_or_else(PoisonError::into_inner) = None;
+	}
+	/// Flush any buffered writes to disk
+	pub(crate) async fn flush(&self) -> Result<(), Error> {
+		self.db.flush()?;
+		let partitions = self.partitions.read().unwrap_or_else(PoisonError::into_inner);
+		for name in partitions.values() {
+			if let Some(cf) = self.db.cf_handle(name) {
+				self.db.flush_cf(&cf)?;
+			}
+		}
+		Ok(())
+	}
+	/// Compact the whole key range, discarding deleted keys and their tombstones
+	pub(crate) async fn compact(&self) -> Result<(), Error> {
+		let db = self.db.clone();
+		let partitions = self
+			.partitions
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.values()
+			.cloned()
+			.collect::<Vec<_>>();
+		// Compaction can take a long time, so it
+		// runs outside of the asynchronous runtime
+		tokio::task::spawn_blocking(move || {
+			db.compact_range(None::<&[u8]>, None::<&[u8]>);
+			for name in partitions.iter() {
+				if let Some(cf) = db.cf_handle(name) {
+					db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+				}
+			}
+		})
+		.await
+		.map_err(|e| Error::Ds(format!("Unable to compact the datastore: {e}")))
+	}
 	/// Start a new transaction
-	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
+	pub(crate) async fn transaction(
+		&self,
+		write: bool,
+		_: bool,
+		isolation: Isolation,
+	) -> Result<Transaction, Error> {
 		// Activate the snapshot options
 		let mut to = OptimisticTransactionOptions::default();
 		to.set_snapshot(true);
@@ -104,6 +314,10 @@ impl Datastore {
 			write,
 			inner: Arc::new(Mutex::new(Some(inner))),
 			ro,
+			isolation,
+			partitions: self.partitions.clone(),
+			changes: Changes::default(),
+			cipher: self.cipher.clone(),
 			_db: self.db.clone(),
 		})
 	}
@@ -131,6 +345,10 @@ impl Transaction {
 			Some(inner) => inner.rollback()?,
 			None => unreachable!(),
 		};
+		// Remove any partitions recreated in this transaction
+		for name in std::mem::take(&mut self.changes).cancel() {
+			self._db.drop_cf(&name)?;
+		}
 		// Continue
 		Ok(())
 	}
@@ -146,14 +364,57 @@ impl Transaction {
 		}
 		// Mark this transaction as done
 		self.done = true;
-		// Cancel this transaction
-		match self.inner.lock().await.take() {
-			Some(inner) => inner.commit()?,
+		// Commit this transaction
+		let res = match self.inner.lock().await.take() {
+			Some(inner) => inner.commit(),
 			None => unreachable!(),
 		};
+		let changes = std::mem::take(&mut self.changes);
+		let garbage = match res {
+			Ok(_) => {
+				let mut partitions =
+					self.partitions.write().unwrap_or_else(PoisonError::into_inner);
+				changes.commit(&mut partitions)
+			}
+			Err(e) => {
+				// Remove any partitions recreated in this transaction
+				for name in changes.cancel() {
+					let _ = self._db.drop_cf(&name);
+				}
+				return Err(e.into());
+			}
+		};
+		// Remove the dropped partitions
+		for name in garbage {
+			self._db.drop_cf(&name)?;
+		}
 		// Continue
 		Ok(())
 	}
+	/// Drop the partitions of any tables and indexes under a key prefix
+	pub(crate) async fn drop_partitions(&mut self, prefix: &[u8]) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Mark the partitions as dropped
+		let removed = {
+			let partitions = self.partitions.read().unwrap_or_else(PoisonError::into_inner);
+			self.changes.drop(&partitions, prefix)
+		};
+		// Remove the partition mappings
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		for key in removed {
+			inner.delete(partition::mapping(&key))?;
+		}
+		// Return result
+		Ok(())
+	}
 	/// Check if a key exists
 	pub(crate) async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
 	where
@@ -163,9 +424,12 @@ impl Transaction {
 		if self.done {
 			return Err(Error::TxFinished);
 		}
+		// Get the arguments
+		let key = key.into();
+		let route = self.route(&key, false).await?;
 		// Check the key
-		let res =
-			self.inner.lock().await.as_ref().unwrap().get_opt(key.into(), &self.ro)?.is_some();
+		let inner = self.inner.lock().await;
+		let res = self.fetch(inner.as_ref().unwrap(), &route, &key)?.is_some();
 		// Return result
 		Ok(res)
 	}
@@ -178,11 +442,64 @@ impl Transaction {
 		if self.done {
 			return Err(Error::TxFinished);
 		}
+		// Get the arguments
+		let key = key.into();
+		let route = self.route(&key, false).await?;
 		// Get the key
-		let res = self.inner.lock().await.as_ref().unwrap().get_opt(key.into(), &self.ro)?;
+		let inner = self.inner.lock().await;
+		let res = self.fetch(inner.as_ref().unwrap(), &route, &key)?;
 		// Return result
 		Ok(res)
 	}
+	/// Fetch many keys from the database in batched reads
+	pub(crate) async fn multi_get(&mut self, keys: Vec<Key>) -> Result<Vec<Option<Val>>, Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the partition of each key
+		let mut routes = Vec::with_capacity(keys.len());
+		for key in keys.iter() {
+			routes.push(self.route(key, false).await?);
+		}
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		// Serializable reads are tracked one key at a time
+		if self.isolation == Isolation::Serializable {
+			return keys.iter().zip(routes.iter()).map(|(k, r)| self.fetch(inner, r, k)).collect();
+		}
+		// Read the keys within partitions, and the other keys, in two batches
+		let cfs = routes
+			.iter()
+			.map(|r| match r {
+				Route::Partition(name, _) => self.cf(name).map(Some),
+				_ => Ok(None),
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+		let parted =
+			keys.iter().zip(cfs.iter()).filter_map(|(k, cf)| cf.as_ref().map(|cf| (cf, k)));
+		let mut parted = inner.multi_get_cf_opt(parted, &self.ro).into_iter();
+		let other = keys
+			.iter()
+			.zip(routes.iter())
+			.filter(|(_, r)| matches!(r, Route::Default | Route::Missing(_)))
+			.map(|(k, _)| k);
+		let mut other = inner.multi_get_opt(other, &self.ro).into_iter();
+		// Collect the values in the order of the keys
+		let mut res = Vec::with_capacity(keys.len());
+		for (key, route) in keys.iter().zip(routes.iter()) {
+			let val = match route {
+				Route::Default | Route::Missing(_) => other.next().transpose()?.flatten(),
+				Route::Dropped(_) => None,
+				Route::Partition(_, legacy) => match parted.next().transpose()?.flatten() {
+					None if *legacy => inner.get_opt(key, &self.ro)?,
+					val => val,
+				},
+			};
+			res.push(val.map(|v| self.open(key, v)).transpose()?);
+		}
+		Ok(res)
+	}
 	/// Obtain a new change timestamp for a key
 	/// which is replaced with the current timestamp when the transaction is committed.
 	/// NOTE: This should be called when composing the change feed entries for this transaction,
@@ -200,7 +517,10 @@ impl Transaction {
 		// Write the timestamp to the "last-write-timestamp" key
 		// to ensure that no other transactions can commit with older timestamps.
 		let k: Key = key.into();
-		let prev = self.inner.lock().await.as_ref().unwrap().get_opt(k.clone(), &self.ro)?;
+		let route = self.route(&k, true).await?;
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		let prev = self.fetch(inner, &route, &k)?;
 		let ver = match prev {
 			Some(prev) => {
 				let slice = prev.as_slice();
@@ -217,7 +537,7 @@ impl Transaction {
 
 		let verbytes = u64_to_versionstamp(ver);
 
-		self.inner.lock().await.as_ref().unwrap().put(k, verbytes)?;
+		self.store(inner, &route, k, verbytes.to_vec())?;
 		// Return the uint64 representation of the timestamp as the result
 		Ok(verbytes)
 	}
@@ -259,8 +579,12 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
+		// Get the arguments
+		let key = key.into();
+		let route = self.route(&key, true).await?;
 		// Set the key
-		self.inner.lock().await.as_ref().unwrap().put(key.into(), val.into())?;
+		let inner = self.inner.lock().await;
+		self.store(inner.as_ref().unwrap(), &route, key, val.into())?;
 		// Return result
 		Ok(())
 	}
@@ -278,15 +602,16 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Get the transaction
-		let inner = self.inner.lock().await;
-		let inner = inner.as_ref().unwrap();
 		// Get the arguments
 		let key = key.into();
 		let val = val.into();
+		let route = self.route(&key, true).await?;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
 		// Set the key if empty
-		match inner.get_opt(&key, &self.ro)? {
-			None => inner.put(key, val)?,
+		match self.fetch(inner, &route, &key)? {
+			None => self.store(inner, &route, key, val)?,
 			_ => return Err(Error::TxKeyAlreadyExists),
 		};
 		// Return result
@@ -306,17 +631,18 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Get the transaction
-		let inner = self.inner.lock().await;
-		let inner = inner.as_ref().unwrap();
 		// Get the arguments
 		let key = key.into();
 		let val = val.into();
 		let chk = chk.map(Into::into);
+		let route = self.route(&key, true).await?;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
 		// Set the key if valid
-		match (inner.get_opt(&key, &self.ro)?, chk) {
-			(Some(v), Some(w)) if v == w => inner.put(key, val)?,
-			(None, None) => inner.put(key, val)?,
+		match (self.fetch(inner, &route, &key)?, chk) {
+			(Some(v), Some(w)) if v == w => self.store(inner, &route, key, val)?,
+			(None, None) => self.store(inner, &route, key, val)?,
 			_ => return Err(Error::TxConditionNotMet),
 		};
 		// Return result
@@ -335,8 +661,12 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
+		// Get the arguments
+		let key = key.into();
+		let route = self.route(&key, false).await?;
 		// Remove the key
-		self.inner.lock().await.as_ref().unwrap().delete(key.into())?;
+		let inner = self.inner.lock().await;
+		self.erase(inner.as_ref().unwrap(), &route, key)?;
 		// Return result
 		Ok(())
 	}
@@ -354,16 +684,17 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Get the transaction
-		let inner = self.inner.lock().await;
-		let inner = inner.as_ref().unwrap();
 		// Get the arguments
 		let key = key.into();
 		let chk = chk.map(Into::into);
+		let route = self.route(&key, false).await?;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
 		// Delete the key if valid
-		match (inner.get_opt(&key, &self.ro)?, chk) {
-			(Some(v), Some(w)) if v == w => inner.delete(key)?,
-			(None, None) => inner.delete(key)?,
+		match (self.fetch(inner, &route, &key)?, chk) {
+			(Some(v), Some(w)) if v == w => self.erase(inner, &route, key)?,
+			(None, None) => self.erase(inner, &route, key)?,
 			_ => return Err(Error::TxConditionNotMet),
 		};
 		// Return result
@@ -382,45 +713,197 @@ impl Transaction {
 		if self.done {
 			return Err(Error::TxFinished);
 		}
-		// Get the transaction
-		let inner = self.inner.lock().await;
-		let inner = inner.as_ref().unwrap();
 		// Convert the range to bytes
 		let rng: Range<Key> = Range {
 			start: rng.start.into(),
 			end: rng.end.into(),
 		};
-		// Create result set
-		let mut res = vec![];
 		// Set the key range
 		let beg = rng.start.as_slice();
 		let end = rng.end.as_slice();
-		// Set the ReadOptions with the snapshot
+		// Find the partitions within the key range
+		let sources = {
+			let partitions = self.partitions.read().unwrap_or_else(PoisonError::into_inner);
+			self.changes.sources(&partitions, beg, end)
+		};
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		// Scan the default column family
+		let mut res = BTreeMap::new();
+		let iter = inner.raw_iterator_opt(self.snapshot(inner));
+		Self::iterate(iter, beg, end, limit, &mut res);
+		// Scan each of the partitions, which take
+		// precedence over any data written to the
+		// default column family before partitions
+		for name in sources {
+			let iter = inner.raw_iterator_cf_opt(&self.cf(&name)?, self.snapshot(inner));
+			Self::iterate(iter, beg, end, limit, &mut res);
+		}
+		// Return result
+		res.into_iter()
+			.take(limit as usize)
+			.map(|(k, v)| {
+				let v = self.open(&k, v)?;
+				Ok((k, v))
+			})
+			.collect()
+	}
+	/// Find where a key is stored, creating its partition if it is to be written
+	async fn route(&mut self, key: &[u8], write: bool) -> Result<Route, Error> {
+		let route = {
+			let partitions = self.partitions.read().unwrap_or_else(PoisonError::into_inner);
+			self.changes.route(&partitions, key)
+		};
+		match route {
+			// Create a new partition for the table or index
+			Route::Missing(prefix) if write => {
+				let mut partitions =
+					self.partitions.write().unwrap_or_else(PoisonError::into_inner);
+				// Check if another transaction created the partition
+				if let Some(name) = partitions.get(&prefix) {
+					return Ok(Route::Partition(name.clone(), true));
+				}
+				let name = partition::name();
+				self._db.create_cf(&name, &Options::default())?;
+				self._db.put(partition::mapping(&prefix), &name)?;
+				partitions.insert(prefix, name.clone());
+				Ok(Route::Partition(name, true))
+			}
+			// Recreate a partition dropped in this transaction
+			Route::Dropped(prefix) if write => {
+				let name = partition::name();
+				self._db.create_cf(&name, &Options::default())?;
+				let inner = self.inner.lock().await;
+				inner.as_ref().unwrap().put(partition::mapping(&prefix), &name)?;
+				self.changes.create(prefix, name.clone());
+				Ok(Route::Partition(name, false))
+			}
+			route => Ok(route),
+		}
+	}
+	/// Fetch a key from the transaction snapshot
+	fn fetch(
+		&self,
+		inner: &speedb::Transaction<'static, OptimisticTransactionDB>,
+		route: &Route,
+		key: &[u8],
+	) -> Result<Option<Val>, Error> {
+		let res = match route {
+			Route::Default | Route::Missing(_) => self.read(inner, None, key)?,
+			Route::Dropped(_) => None,
+			Route::Partition(name, legacy) => {
+				match self.read(inner, Some(&self.cf(name)?), key)? {
+					None if *legacy => self.read(inner, None, key)?,
+					res => res,
+				}
+			}
+		};
+		res.map(|v| self.open(key, v)).transpose()
+	}
+	/// Read a key, tracking it for conflicts when the transaction is serializable
+	fn read(
+		&self,
+		inner: &speedb::Transaction<'static, OptimisticTransactionDB>,
+		cf: Option<&Arc<BoundColumnFamily<'_>>>,
+		key: &[u8],
+	) -> Result<Option<Vec<u8>>, Error> {
+		Ok(match (cf, self.isolation) {
+			(None, Isolation::Snapshot) => inner.get_opt(key, &self.ro)?,
+			(None, Isolation::Serializable) => inner.get_for_update_opt(key, true, &self.ro)?,
+			(Some(cf), Isolation::Snapshot) => inner.get_cf_opt(cf, key, &self.ro)?,
+			(Some(cf), Isolation::Serializable) => {
+				inner.get_for_update_cf_opt(cf, key, true, &self.ro)?
+			}
+		})
+	}
+	/// Write a key to its partition
+	fn store(
+		&self,
+		inner: &speedb::Transaction<'static, OptimisticTransactionDB>,
+		route: &Route,
+		key: Key,
+		val: Val,
+	) -> Result<(), Error> {
+		let val = self.seal(&key, val)?;
+		match route {
+			Route::Partition(name, _) => Ok(inner.put_cf(&self.cf(name)?, key, val)?),
+			_ => Ok(inner.put(key, val)?),
+		}
+	}
+	/// Delete a key from its partition
+	fn erase(
+		&self,
+		inner: &speedb::Transaction<'static, OptimisticTransactionDB>,
+		route: &Route,
+		key: Key,
+	) -> Result<(), Error> {
+		match route {
+			Route::Dropped(_) => Ok(()),
+			Route::Partition(name, legacy) => {
+				// Remove any copy of the key written before partitions
+				if *legacy && inner.get_opt(&key, &self.ro)?.is_some() {
+					inner.delete(&key)?;
+				}
+				Ok(inner.delete_cf(&self.cf(name)?, key)?)
+			}
+			_ => Ok(inner.delete(key)?),
+		}
+	}
+	/// Encrypt a value before it is persisted
+	fn seal(&self, key: &[u8], val: Val) -> Result<Val, Error> {
+		match &self.cipher {
+			Some(cipher) => cipher.seal(key, &val),
+			None => Ok(val),
+		}
+	}
+	/// Decrypt a value after it has been read
+	fn open(&self, key: &[u8], val: Val) -> Result<Val, Error> {
+		match &self.cipher {
+			Some(cipher) => cipher.open(key, &val),
+			None => Ok(val),
+		}
+	}
+	/// Get the handle to the column family of a partition
+	fn cf(&self, name: &str) -> Result<Arc<BoundColumnFamily<'_>>, Error> {
+		self._db.cf_handle(name).ok_or_else(|| Error::Ds(format!("Unknown column family {name}")))
+	}
+	/// Create the read options for iterating over the transaction snapshot
+	fn snapshot(
+		&self,
+		inner: &speedb::Transaction<'static, OptimisticTransactionDB>,
+	) -> ReadOptions {
 		let mut ro = ReadOptions::default();
 		ro.set_snapshot(&inner.snapshot());
-		// Create the iterator
-		let mut iter = inner.raw_iterator_opt(ro);
+		ro
+	}
+	/// Collect the keys within the range from an iterator
+	fn iterate(
+		mut iter: speedb::DBRawIteratorWithThreadMode<
+			'_,
+			speedb::Transaction<'static, OptimisticTransactionDB>,
+		>,
+		beg: &[u8],
+		end: &[u8],
+		limit: u32,
+		res: &mut BTreeMap<Key, Val>,
+	) {
 		// Seek to the start key
-		iter.seek(&rng.start);
+		iter.seek(beg);
 		// Scan the keys in the iterator
-		while iter.valid() {
-			// Check the scan limit
-			if res.len() < limit as usize {
-				// Get the key and value
-				let (k, v) = (iter.key(), iter.value());
-				// Check the key and value
-				if let (Some(k), Some(v)) = (k, v) {
-					if k >= beg && k < end {
-						res.push((k.to_vec(), v.to_vec()));
-						iter.next();
-						continue;
-					}
+		let mut num = 0;
+		while iter.valid() && num < limit {
+			// Get the key and value
+			let (k, v) = (iter.key(), iter.value());
+			// Check the key and value
+			match (k, v) {
+				(Some(k), Some(v)) if k >= beg && k < end => {
+					res.insert(k.to_vec(), v.to_vec());
+					num += 1;
+					iter.next();
 				}
+				_ => break,
 			}
-			// Exit
-			break;
 		}
-		// Return result
-		Ok(res)
 	}
 }
@@ -0,0 +1,37 @@
+#[tokio::test]
+#[serial]
+async fn compact_discards_deleted_keys() {
+	// Create a new datastore
+	let node_id = Uuid::parse_str("2f7c9a41-3d8e-4b6a-9c15-7e0d4a8b1f62").unwrap();
+	let ds = new_ds(node_id).await;
+	// Write some keys, and delete most of them
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	for i in 0..100 {
+		tx.set(format!("test{i:03}"), "ok").await.unwrap();
+	}
+	tx.commit().await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.delr("test000".."test090", u32::MAX).await.unwrap();
+	tx.commit().await.unwrap();
+	// Compact the datastore
+	ds.compact().await.unwrap();
+	// Check the remaining keys can be scanned
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scan("test".."test\u{7f}", u32::MAX).await.unwrap();
+	assert_eq!(val.len(), 10);
+	assert_eq!(val[0].0, b"test090");
+	tx.cancel().await.unwrap();
+	// Check the compaction is reported
+	let ses = crate::dbs::Session::owner();
+	let res = &mut ds.execute("INFO FOR ROOT", &ses, None).await.unwrap();
+	let tmp = res.remove(0).result.unwrap();
+	let crate::sql::Value::Object(tmp) = tmp else {
+		panic!("expected an object");
+	};
+	let Some(crate::sql::Value::Object(compaction)) = tmp.get("compaction") else {
+		panic!("expected the compaction status");
+	};
+	assert_eq!(compaction.get("running"), Some(&crate::sql::Value::from(false)));
+	assert_eq!(compaction.get("completed"), Some(&crate::sql::Value::from(1u64)));
+	assert!(compaction.get("error").is_none());
+}
@@ -0,0 +1,63 @@
+#[tokio::test]
+#[serial]
+async fn encrypted_values_with_rotated_key() {
+	let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+	// Write values with an encryption key
+	{
+		let ds = open_ds(&path).await.with_encryption_key([1; 32]);
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		tx.set("test", "one").await.unwrap();
+		tx.set("/*ns\0*db\0*tb\0*id", "two").await.unwrap();
+		tx.commit().await.unwrap();
+	}
+	// The values can not be read without the key
+	{
+		let ds = open_ds(&path).await;
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let val = tx.get("test").await.unwrap();
+		assert!(!matches!(val.as_deref(), Some(b"one")));
+		tx.cancel().await.unwrap();
+	}
+	// The values can not be read with a different key
+	{
+		let ds = open_ds(&path).await.with_encryption_key([2; 32]);
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		assert!(tx.get("test").await.is_err());
+		tx.cancel().await.unwrap();
+	}
+	// The values can be read after rotating the key
+	{
+		let ds =
+			open_ds(&path).await.with_encryption_key([2; 32]).with_previous_encryption_key([1; 32]);
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let val = tx.get("test").await.unwrap();
+		assert!(matches!(val.as_deref(), Some(b"one")));
+		let val = tx.scan("/*ns\0".."/*ns\0\u{7f}", 10).await.unwrap();
+		assert_eq!(val, vec![(b"/*ns\0*db\0*tb\0*id".to_vec(), b"two".to_vec())]);
+		tx.set("test", "three").await.unwrap();
+		tx.commit().await.unwrap();
+	}
+	// Rewritten values only need the new key
+	{
+		let ds = open_ds(&path).await.with_encryption_key([2; 32]);
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let val = tx.get("test").await.unwrap();
+		assert!(matches!(val.as_deref(), Some(b"three")));
+		tx.cancel().await.unwrap();
+	}
+	// The remaining values are re-encrypted in the background
+	{
+		let ds =
+			open_ds(&path).await.with_encryption_key([2; 32]).with_previous_encryption_key([1; 32]);
+		assert_eq!(ds.reencrypt().await.unwrap(), 1);
+		assert_eq!(ds.reencrypt().await.unwrap(), 0);
+	}
+	// Every value can be read with only the new key
+	{
+		let ds = open_ds(&path).await.with_encryption_key([2; 32]);
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let val = tx.scan("/*ns\0".."/*ns\0\u{7f}", 10).await.unwrap();
+		assert_eq!(val, vec![(b"/*ns\0*db\0*tb\0*id".to_vec(), b"two".to_vec())]);
+		tx.cancel().await.unwrap();
+	}
+}
@@ -43,6 +43,10 @@ mod rocksdb {
 			.with_node_id(sql::Uuid::from(node_id))
 	}
 
+	async fn open_ds(path: &str) -> Datastore {
+		Datastore::new(format!("rocksdb:{path}").as_str()).await.unwrap()
+	}
+
 	async fn new_tx(write: bool, lock: bool) -> Transaction {
 		// Shared node id for one-off transactions
 		// We should delete this, node IDs should be known.
@@ -51,10 +55,15 @@ mod rocksdb {
 	}
 
 	include!("cluster_init.rs");
+	include!("compaction.rs");
+	include!("encryption.rs");
 	include!("helper.rs");
 	include!("lq.rs");
 	include!("nq.rs");
+	include!("partition.rs");
 	include!("raw.rs");
+	#[cfg(feature = "replication")]
+	include!("replication.rs");
 	include!("snapshot.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
@@ -79,6 +88,10 @@ mod speedb {
 			.with_node_id(sql::Uuid::from(node_id))
 	}
 
+	async fn open_ds(path: &str) -> Datastore {
+		Datastore::new(format!("speedb:{path}").as_str()).await.unwrap()
+	}
+
 	async fn new_tx(write: bool, lock: bool) -> Transaction {
 		// Shared node id for one-off transactions
 		// We should delete this, node IDs should be known.
@@ -86,6 +99,45 @@ mod speedb {
 		new_ds(new_tx_uuid).await.transaction(write, lock).await.unwrap()
 	}
 
+	include!("cluster_init.rs");
+	include!("compaction.rs");
+	include!("encryption.rs");
+	include!("helper.rs");
+	include!("lq.rs");
+	include!("nq.rs");
+	include!("partition.rs");
+	include!("raw.rs");
+	include!("snapshot.rs");
+	include!("tb.rs");
+	include!("multireader.rs");
+	include!("multiwriter_different_keys.rs");
+	include!("multiwriter_same_keys_conflict.rs");
+	include!("timestamp_to_versionstamp.rs");
+}
+
+#[cfg(feature = "kv-redb")]
+mod redb {
+
+	use crate::kvs::Datastore;
+	use crate::kvs::Transaction;
+	use serial_test::serial;
+	use temp_dir::TempDir;
+
+	async fn new_ds(node_id: Uuid) -> Datastore {
+		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+		Datastore::new(format!("redb:{path}").as_str())
+			.await
+			.unwrap()
+			.with_node_id(sql::Uuid::from(node_id))
+	}
+
+	async fn new_tx(write: bool, lock: bool) -> Transaction {
+		// Shared node id for one-off transactions
+		// We should delete this, node IDs should be known.
+		let new_tx_uuid = Uuid::parse_str("b1b07c3f-4d96-4a2b-8d9e-6b8a8f0b7a2c").unwrap();
+		new_ds(new_tx_uuid).await.transaction(write, lock).await.unwrap()
+	}
+
 	include!("cluster_init.rs");
 	include!("helper.rs");
 	include!("lq.rs");
@@ -99,6 +151,40 @@ mod speedb {
 	include!("timestamp_to_versionstamp.rs");
 }
 
+#[cfg(all(feature = "kv-indxdb", target_arch = "wasm32"))]
+mod indxdb {
+
+	use crate::kvs::Datastore;
+	use crate::kvs::Transaction;
+	use serial_test::serial;
+
+	async fn new_ds(node_id: Uuid) -> Datastore {
+		// Each datastore is stored in a new IndexedDB database
+		let name = Uuid::new_v4();
+		Datastore::new(format!("indxdb:{name}").as_str())
+			.await
+			.unwrap()
+			.with_node_id(sql::Uuid::from(node_id))
+	}
+
+	async fn new_tx(write: bool, lock: bool) -> Transaction {
+		// Shared node id for one-off transactions
+		// We should delete this, node IDs should be known.
+		let new_tx_uuid = Uuid::parse_str("c4a1e0d6-3f0b-4c61-9a5e-2d7f8b1e6a93").unwrap();
+		new_ds(new_tx_uuid).await.transaction(write, lock).await.unwrap()
+	}
+
+	include!("cluster_init.rs");
+	include!("helper.rs");
+	include!("lq.rs");
+	include!("nq.rs");
+	include!("raw.rs");
+	include!("snapshot.rs");
+	include!("tb.rs");
+	include!("multireader.rs");
+	include!("timestamp_to_versionstamp.rs");
+}
+
 #[cfg(feature = "kv-tikv")]
 mod tikv {
 
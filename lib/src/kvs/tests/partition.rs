@@ -0,0 +1,55 @@
+#[tokio::test]
+#[serial]
+async fn delp_drops_table_partition() {
+	// Create a new datastore
+	let node_id = Uuid::parse_str("5c0e3b6e-1f7a-4d8e-9b42-6a2f1c7d9e30").unwrap();
+	let ds = new_ds(node_id).await;
+	// Write records and index entries for two tables
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("/*ns\0*db\0*one\0*a", "1").await.unwrap();
+	tx.set("/*ns\0*db\0*one\0*b", "2").await.unwrap();
+	tx.set("/*ns\0*db\0*one\0+ix\0*a", "3").await.unwrap();
+	tx.set("/*ns\0*db\0*two\0*a", "4").await.unwrap();
+	tx.commit().await.unwrap();
+	// Check the table data can be scanned
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scan("/*ns\0*db\0*one\0".."/*ns\0*db\0*one\0\u{7f}", 10).await.unwrap();
+	assert_eq!(val.len(), 3);
+	tx.cancel().await.unwrap();
+	// Remove the first table, and write to it again
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.delp("/*ns\0*db\0*one\0", u32::MAX).await.unwrap();
+	assert!(tx.get("/*ns\0*db\0*one\0*a").await.unwrap().is_none());
+	tx.set("/*ns\0*db\0*one\0*c", "5").await.unwrap();
+	tx.commit().await.unwrap();
+	// Check only the new table data remains
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.scan("/*ns\0*db\0".."/*ns\0*db\0\u{7f}", 10).await.unwrap();
+	assert_eq!(val.len(), 2);
+	assert_eq!(val[0].0, b"/*ns\0*db\0*one\0*c");
+	assert_eq!(val[1].0, b"/*ns\0*db\0*two\0*a");
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn cancelled_delp_keeps_table_partition() {
+	// Create a new datastore
+	let node_id = Uuid::parse_str("a3d9f1c2-7b6e-4e05-8c1a-2f4b9d6e8a17").unwrap();
+	let ds = new_ds(node_id).await;
+	// Write a record to the table
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("/*ns\0*db\0*one\0*a", "1").await.unwrap();
+	tx.commit().await.unwrap();
+	// Remove the table, but cancel the transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.delp("/*ns\0*db\0*one\0", u32::MAX).await.unwrap();
+	tx.set("/*ns\0*db\0*one\0*b", "2").await.unwrap();
+	tx.cancel().await.unwrap();
+	// Check the table data remains
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("/*ns\0*db\0*one\0*a").await.unwrap();
+	assert!(matches!(val.as_deref(), Some(b"1")));
+	assert!(tx.get("/*ns\0*db\0*one\0*b").await.unwrap().is_none());
+	tx.cancel().await.unwrap();
+}
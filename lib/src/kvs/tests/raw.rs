@@ -44,6 +44,27 @@ async fn get() {
 	tx.cancel().await.unwrap();
 }
 
+#[tokio::test]
+#[serial]
+async fn multi_get() {
+	// Create a new datastore
+	let node_id = Uuid::parse_str("5a1b6f3e-2c4d-4e8f-9a0b-7c6d5e4f3a2b").unwrap();
+	let ds = new_ds(node_id).await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	assert!(tx.put("test1", "one").await.is_ok());
+	assert!(tx.put("test3", "three").await.is_ok());
+	tx.commit().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.multi_get(vec!["test3", "test2", "test1"]).await.unwrap();
+	assert_eq!(val.len(), 3);
+	assert!(matches!(val[0].as_deref(), Some(b"three")));
+	assert!(matches!(val[1].as_deref(), None));
+	assert!(matches!(val[2].as_deref(), Some(b"one")));
+	tx.cancel().await.unwrap();
+}
+
 #[tokio::test]
 #[serial]
 async fn set() {
@@ -230,3 +251,36 @@ async fn scan() {
 	assert_eq!(val[1].1, b"2");
 	tx.cancel().await.unwrap();
 }
+
+#[tokio::test]
+#[serial]
+async fn scanner() {
+	// Create a new datastore
+	let node_id = Uuid::parse_str("c1d8f0a2-6b3e-4f5a-8d7c-9e0f1a2b3c4d").unwrap();
+	let ds = new_ds(node_id).await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	assert!(tx.put("test1", "1").await.is_ok());
+	assert!(tx.put("test2", "2").await.is_ok());
+	assert!(tx.put("test3", "3").await.is_ok());
+	assert!(tx.put("test4", "4").await.is_ok());
+	assert!(tx.put("test5", "5").await.is_ok());
+	tx.commit().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let mut scanner = crate::kvs::Scanner::new("test1".."test9", 2);
+	let val = scanner.next(&mut tx).await.unwrap();
+	assert_eq!(val.len(), 2);
+	assert_eq!(val[0].0, b"test1");
+	assert_eq!(val[1].0, b"test2");
+	let val = scanner.next(&mut tx).await.unwrap();
+	assert_eq!(val.len(), 2);
+	assert_eq!(val[0].0, b"test3");
+	assert_eq!(val[1].0, b"test4");
+	let val = scanner.next(&mut tx).await.unwrap();
+	assert_eq!(val.len(), 1);
+	assert_eq!(val[0].0, b"test5");
+	let val = scanner.next(&mut tx).await.unwrap();
+	assert!(val.is_empty());
+	tx.cancel().await.unwrap();
+}
@@ -0,0 +1,114 @@
+use crate::kvs::{ReplicationOptions, ReplicationRole};
+use std::net::{SocketAddr, TcpListener};
+use std::time::Duration;
+
+// Start a replication group of datastores, which tick quickly
+async fn new_group(n: u64) -> Vec<Datastore> {
+	let addrs: Vec<SocketAddr> =
+		(0..n).map(|_| TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()).collect();
+	let mut nodes = Vec::new();
+	for (i, addr) in addrs.iter().enumerate() {
+		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+		let mut opts = ReplicationOptions::new(i as u64 + 1, *addr, format!("{path}-raft"))
+			.with_tick_interval(Duration::from_millis(20));
+		for (j, peer) in addrs.iter().enumerate().filter(|(j, _)| *j != i) {
+			opts = opts.with_peer(j as u64 + 1, *peer);
+		}
+		nodes.push(open_ds(&path).await.with_replication(opts).await.unwrap());
+	}
+	nodes
+}
+
+// Wait for the group to elect a single leader, which is ready to accept writes
+async fn leader_of(nodes: &[Datastore]) -> usize {
+	for _ in 0..500 {
+		let leaders: Vec<usize> = nodes
+			.iter()
+			.enumerate()
+			.filter(|(_, ds)| {
+				ds.replication_status().map_or(false, |s| s.role == ReplicationRole::Leader)
+			})
+			.map(|(i, _)| i)
+			.collect();
+		if let [i] = leaders[..] {
+			nodes[i].wait_for_leader(Duration::from_secs(5)).await;
+			return i;
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	panic!("The replication group did not elect a leader");
+}
+
+// Wait for a key to be applied to a datastore
+async fn applied(ds: &Datastore, key: &str) -> Option<Vec<u8>> {
+	for _ in 0..500 {
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let val = tx.get(key).await.unwrap();
+		tx.cancel().await.unwrap();
+		if val.is_some() {
+			return val;
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	None
+}
+
+#[tokio::test]
+#[serial]
+async fn replicated_writes_are_applied_on_every_node() {
+	let nodes = new_group(3).await;
+	let leader = leader_of(&nodes).await;
+	// The leader applies the write once it is replicated
+	let mut tx = nodes[leader].transaction(true, false).await.unwrap();
+	tx.set("test", "one").await.unwrap();
+	tx.commit().await.unwrap();
+	let mut tx = nodes[leader].transaction(false, false).await.unwrap();
+	assert_eq!(tx.get("test").await.unwrap().as_deref(), Some(b"one".as_slice()));
+	tx.cancel().await.unwrap();
+	// The followers apply the write, and refuse writes of their own
+	for (i, ds) in nodes.iter().enumerate().filter(|(i, _)| *i != leader) {
+		assert_eq!(applied(ds, "test").await.as_deref(), Some(b"one".as_slice()), "node {i}");
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		tx.set("other", "two").await.unwrap();
+		assert!(matches!(tx.commit().await, Err(Error::ReplicaNotLeader)));
+	}
+}
+
+#[tokio::test]
+#[serial]
+async fn replicated_writes_conflict_with_concurrent_writes() {
+	let nodes = new_group(3).await;
+	let leader = leader_of(&nodes).await;
+	let mut tx1 = nodes[leader].transaction(true, false).await.unwrap();
+	let mut tx2 = nodes[leader].transaction(true, false).await.unwrap();
+	tx1.set("test", "one").await.unwrap();
+	tx2.set("test", "two").await.unwrap();
+	tx1.commit().await.unwrap();
+	assert!(matches!(tx2.commit().await, Err(Error::TxRetryable)));
+	let mut tx = nodes[leader].transaction(false, false).await.unwrap();
+	assert_eq!(tx.get("test").await.unwrap().as_deref(), Some(b"one".as_slice()));
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn replication_fails_over_to_a_new_leader() {
+	let mut nodes = new_group(3).await;
+	let leader = leader_of(&nodes).await;
+	let mut tx = nodes[leader].transaction(true, false).await.unwrap();
+	tx.set("before", "one").await.unwrap();
+	tx.commit().await.unwrap();
+	// Stop the leader, leaving a majority of the nodes
+	let old = nodes.remove(leader);
+	old.shutdown(Duration::from_secs(1)).await.unwrap();
+	drop(old);
+	// The remaining nodes elect a new leader, which accepts writes
+	let leader = leader_of(&nodes).await;
+	let mut tx = nodes[leader].transaction(true, false).await.unwrap();
+	tx.set("after", "two").await.unwrap();
+	tx.commit().await.unwrap();
+	for ds in nodes.iter() {
+		assert_eq!(applied(ds, "before").await.as_deref(), Some(b"one".as_slice()));
+		assert_eq!(applied(ds, "after").await.as_deref(), Some(b"two".as_slice()));
+	}
+}
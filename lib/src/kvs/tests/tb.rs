@@ -23,7 +23,9 @@ async fn table_definitions_can_be_scanned() {
 		view: None,
 		permissions: Default::default(),
 		changefeed: None,
+		ttl: None,
 		comment: None,
+		interned: false,
 	};
 	tx.set(&key, &value).await.unwrap();
 
@@ -60,7 +62,9 @@ async fn table_definitions_can_be_deleted() {
 		view: None,
 		permissions: Default::default(),
 		changefeed: None,
+		ttl: None,
 		comment: None,
+		interned: false,
 	};
 	tx.set(&key, &value).await.unwrap();
 
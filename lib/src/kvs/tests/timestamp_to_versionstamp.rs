@@ -3,7 +3,7 @@
 //
 // FAQ:
 // Q: What’s the difference between database TS and database VS?
-// A: Timestamps are basically seconds since the unix epoch.
+// A: Timestamps are basically seconds since the unix epoch, which are saved as hybrid logical clock timestamps.
 //    Versionstamps can be anything that is provided by our TSO.
 // Q: Why do we need to translate timestamps to versionstamps?
 // A: The garbage collector needs to know which change feed entries to delete.
@@ -41,3 +41,28 @@ async fn timestamp_to_versionstamp() {
 	assert!(vs1 < vs2);
 	assert!(vs2 < vs3);
 }
+
+#[tokio::test]
+#[serial]
+async fn timestamp_to_versionstamp_with_clock_going_backwards() {
+	// Create a new datastore
+	let ds = new_ds(Uuid::parse_str("5d8e2a43-6c0e-4c8e-9a1b-0e1f2f3a4b5c").unwrap()).await;
+	// Give the current versionstamp a timestamp of 10
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set_timestamp_for_versionstamp(10, "myns", "mydb", true).await.unwrap();
+	tx.commit().await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let vs1 = tx.get_versionstamp_from_timestamp(10, "myns", "mydb", true).await.unwrap().unwrap();
+	tx.commit().await.unwrap();
+	// Give the current versionstamp an earlier timestamp of 5, as a node whose clock is behind would
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set_timestamp_for_versionstamp(5, "myns", "mydb", true).await.unwrap();
+	tx.commit().await.unwrap();
+	// The later versionstamp is ordered after the earlier one
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	let vs2 = tx.get_versionstamp_from_timestamp(10, "myns", "mydb", true).await.unwrap().unwrap();
+	let none = tx.get_versionstamp_from_timestamp(9, "myns", "mydb", true).await.unwrap();
+	tx.commit().await.unwrap();
+	assert!(vs1 < vs2);
+	assert_eq!(none, None);
+}
@@ -5,13 +5,92 @@ use crate::kvs::Check;
 use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use std::collections::HashMap;
 use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
+use tikv::Backoff;
 use tikv::CheckLevel;
+use tikv::RetryOptions;
 use tikv::TimestampExt;
 use tikv::TransactionOptions;
 
 pub struct Datastore {
 	db: tikv::TransactionClient,
+	// The backoff used when the cached location of a region is out of date
+	region_backoff: Option<Backoff>,
+}
+
+/// The options used when connecting to a TiKV cluster
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TikvOptions {
+	// The CA certificate, client certificate, and client key used for TLS
+	tls: Option<(PathBuf, PathBuf, PathBuf)>,
+	// The timeout of requests to the placement driver and the TiKV nodes
+	timeout: Option<Duration>,
+	// The backoff used when the cached location of a region is out of date
+	region_backoff: Option<(Duration, Duration, u32)>,
+}
+
+impl TikvOptions {
+	/// Connect to the cluster using mutual TLS
+	///
+	/// The paths point to the PEM encoded CA certificate which signed the certificates
+	/// of the cluster, and to the PEM encoded certificate and private key of the client.
+	pub fn with_tls(
+		mut self,
+		ca_path: impl Into<PathBuf>,
+		cert_path: impl Into<PathBuf>,
+		key_path: impl Into<PathBuf>,
+	) -> Self {
+		self.tls = Some((ca_path.into(), cert_path.into(), key_path.into()));
+		self
+	}
+
+	/// Specify how long requests to the placement driver and the TiKV nodes may take
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Specify how requests are retried when the cached location of a region is out of date
+	///
+	/// The client caches which nodes hold each region. When a region has been split, merged,
+	/// or moved, the cached location is refreshed from the placement driver, and the request
+	/// is retried up to `attempts` times, waiting from `base` up to `max` between attempts.
+	pub fn with_region_backoff(mut self, base: Duration, max: Duration, attempts: u32) -> Self {
+		self.region_backoff = Some((base, max, attempts));
+		self
+	}
+
+	// The configuration of the connection to the cluster
+	fn config(&self) -> tikv::Config {
+		let mut config = tikv::Config::default();
+		if let Some((ca, cert, key)) = &self.tls {
+			config = config.with_security(ca.clone(), cert.clone(), key.clone());
+		}
+		if let Some(timeout) = self.timeout {
+			config = config.with_timeout(timeout);
+		}
+		config
+	}
+
+	// The backoff used when the cached location of a region is out of date
+	fn region_backoff(&self) -> Option<Backoff> {
+		self.region_backoff.map(|(base, max, attempts)| {
+			Backoff::no_jitter_backoff(base.as_millis() as u64, max.as_millis() as u64, attempts)
+		})
+	}
+}
+
+// The retry options of a transaction, using the specified region backoff
+fn retry_options(region_backoff: &Backoff, lock: bool) -> RetryOptions {
+	let mut retry = match lock {
+		true => RetryOptions::default_pessimistic(),
+		false => RetryOptions::default_optimistic(),
+	};
+	retry.region_backoff = region_backoff.clone();
+	retry
 }
 
 pub struct Transaction {
@@ -57,10 +136,15 @@ impl Drop for Transaction {
 
 impl Datastore {
 	/// Open a new database
-	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
-		match tikv::TransactionClient::new(vec![path]).await {
+	pub(crate) async fn new(path: &str, options: &TikvOptions) -> Result<Datastore, Error> {
+		// Configure the connection to the cluster
+		let config = options.config();
+		// Configure how region errors are retried
+		let region_backoff = options.region_backoff();
+		match tikv::TransactionClient::new_with_config(vec![path], config).await {
 			Ok(db) => Ok(Datastore {
 				db,
+				region_backoff,
 			}),
 			Err(e) => Err(Error::Ds(e.to_string())),
 		}
@@ -80,6 +164,10 @@ impl Datastore {
 		};
 		// Set the behaviour when dropping an unfinished transaction
 		opt = opt.drop_check(CheckLevel::Warn);
+		// Set how region errors are retried
+		if let Some(backoff) = &self.region_backoff {
+			opt = opt.retry_options(retry_options(backoff, lock));
+		}
 		// Set this transaction as read only if possible
 		if !write {
 			opt = opt.read_only();
@@ -246,6 +334,18 @@ impl Transaction {
 		// Return result
 		Ok(res)
 	}
+	/// Fetch many keys from the database in one request
+	pub(crate) async fn multi_get(&mut self, keys: Vec<Key>) -> Result<Vec<Option<Val>>, Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the keys
+		let res = self.inner.batch_get(keys.clone()).await?;
+		let res = res.map(|kv| (Key::from(kv.0), kv.1)).collect::<HashMap<_, _>>();
+		// Return result
+		Ok(keys.iter().map(|k| res.get(k).cloned()).collect())
+	}
 	/// Insert or update a key in the database
 	pub(crate) async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
 	where
@@ -390,3 +490,69 @@ impl Transaction {
 		Ok(res)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::opt::Config;
+
+	#[test]
+	fn default_options() {
+		let options = TikvOptions::default();
+		let config = options.config();
+		let default = tikv::Config::default();
+		assert_eq!(config.ca_path, None);
+		assert_eq!(config.cert_path, None);
+		assert_eq!(config.key_path, None);
+		assert_eq!(config.timeout, default.timeout);
+		assert_eq!(options.region_backoff(), None);
+	}
+
+	#[test]
+	fn tls_options() {
+		let options = TikvOptions::default().with_tls("ca.pem", "client.pem", "client-key.pem");
+		let config = options.config();
+		assert_eq!(config.ca_path, Some(PathBuf::from("ca.pem")));
+		assert_eq!(config.cert_path, Some(PathBuf::from("client.pem")));
+		assert_eq!(config.key_path, Some(PathBuf::from("client-key.pem")));
+	}
+
+	#[test]
+	fn timeout_options() {
+		let options = TikvOptions::default().with_timeout(Duration::from_secs(5));
+		let config = options.config();
+		assert_eq!(config.timeout, Duration::from_secs(5));
+		assert_eq!(config.ca_path, None);
+	}
+
+	#[test]
+	fn region_backoff_options() {
+		let options = TikvOptions::default().with_region_backoff(
+			Duration::from_millis(10),
+			Duration::from_secs(1),
+			5,
+		);
+		let backoff = options.region_backoff().unwrap();
+		assert_eq!(backoff, Backoff::no_jitter_backoff(10, 1000, 5));
+		// Only the region backoff of the transaction is changed
+		let retry = retry_options(&backoff, false);
+		assert_eq!(retry.region_backoff, backoff);
+		assert_eq!(retry.lock_backoff, RetryOptions::default_optimistic().lock_backoff);
+		let retry = retry_options(&backoff, true);
+		assert_eq!(retry.region_backoff, backoff);
+		assert_eq!(retry.lock_backoff, RetryOptions::default_pessimistic().lock_backoff);
+	}
+
+	#[test]
+	fn config_options() {
+		let config = Config::new()
+			.tikv_tls("ca.pem", "client.pem", "client-key.pem")
+			.tikv_timeout(Duration::from_secs(5))
+			.tikv_region_backoff(Duration::from_millis(10), Duration::from_secs(1), 5);
+		let options = TikvOptions::default()
+			.with_tls("ca.pem", "client.pem", "client-key.pem")
+			.with_timeout(Duration::from_secs(5))
+			.with_region_backoff(Duration::from_millis(10), Duration::from_secs(1), 5);
+		assert_eq!(config.tikv, options);
+	}
+}
@@ -1,23 +1,32 @@
+use super::chunk::{self, Header, ValueReader};
 use super::kv::Add;
 use super::kv::Convert;
 use super::Key;
 use super::Val;
 use crate::cf;
+use crate::cf::TableMutation;
+use crate::cf::TableMutations;
+use crate::cnf;
 use crate::dbs::node::ClusterMembership;
 use crate::dbs::node::Timestamp;
+use crate::dbs::{Quotas, Usage};
+use crate::doc::AfterEvent;
 use crate::err::Error;
 use crate::idg::u32::U32;
 use crate::kvs::cache::Cache;
 use crate::kvs::cache::Entry;
+use crate::kvs::export;
+use crate::kvs::BackupInfo;
 use crate::kvs::Check;
+use crate::kvs::ExportConfig;
+use crate::kvs::ExportFormat;
 use crate::kvs::LqValue;
 use crate::sql;
-use crate::sql::paths::EDGE;
-use crate::sql::paths::IN;
-use crate::sql::paths::OUT;
 use crate::sql::thing::Thing;
 use crate::sql::Strand;
 use crate::sql::Value;
+use crate::vs;
+use crate::vs::hlc;
 use crate::vs::Oracle;
 use crate::vs::Versionstamp;
 use channel::Sender;
@@ -32,6 +41,7 @@ use sql::statements::DefineIndexStatement;
 use sql::statements::DefineNamespaceStatement;
 use sql::statements::DefineParamStatement;
 use sql::statements::DefineScopeStatement;
+use sql::statements::DefineSequenceStatement;
 use sql::statements::DefineTableStatement;
 use sql::statements::DefineTokenStatement;
 use sql::statements::DefineUserStatement;
@@ -56,6 +66,31 @@ pub struct Transaction {
 	pub(super) cf: cf::Writer,
 	pub(super) write_buffer: HashMap<Key, ()>,
 	pub(super) vso: Arc<Mutex<Oracle>>,
+	pub(super) savepoints: Vec<Savepoint>,
+	pub(super) events: Vec<AfterEvent>,
+	// The interned prefix of each table, by the name prefix of its keys
+	pub(super) interned: HashMap<Key, Option<Key>>,
+	// The storage used by each table, and the quotas of the namespaces and databases
+	pub(super) usage: Usage,
+	pub(super) quotas: Quotas,
+	// The bytes written to each database in this transaction
+	pub(super) written: HashMap<(String, String), u64>,
+	// The approximate number of bytes written in this transaction
+	pub(super) size: u64,
+}
+
+/// A named point within a transaction which can be rolled back to.
+pub(super) struct Savepoint {
+	name: String,
+	// The original values of the keys modified since the savepoint
+	undo: HashMap<Key, Option<Val>>,
+	// The transaction state when the savepoint was created
+	cf: cf::writer::Mark,
+	events: usize,
+	// The cache and write buffer, which are not copied for
+	// a lightweight savepoint, as the cached definitions are
+	// cleared instead when rolling back to the savepoint
+	state: Option<(Cache, HashMap<Key, ()>)>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -66,6 +101,12 @@ pub(super) enum Inner {
 	RocksDB(super::rocksdb::Transaction),
 	#[cfg(feature = "kv-speedb")]
 	SpeeDB(super::speedb::Transaction),
+	#[cfg(feature = "kv-redb")]
+	ReDB(super::redb::Transaction),
+	#[cfg(feature = "kv-object")]
+	Object(super::object::Transaction),
+	#[cfg(feature = "kv-custom")]
+	Custom(super::custom::Transaction),
 	#[cfg(feature = "kv-indxdb")]
 	IndxDB(super::indxdb::Transaction),
 	#[cfg(feature = "kv-tikv")]
@@ -84,6 +125,12 @@ impl fmt::Display for Transaction {
 			Inner::RocksDB(_) => write!(f, "rocksdb"),
 			#[cfg(feature = "kv-speedb")]
 			Inner::SpeeDB(_) => write!(f, "speedb"),
+			#[cfg(feature = "kv-redb")]
+			Inner::ReDB(_) => write!(f, "redb"),
+			#[cfg(feature = "kv-object")]
+			Inner::Object(_) => write!(f, "object"),
+			#[cfg(feature = "kv-custom")]
+			Inner::Custom(_) => write!(f, "custom"),
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(_) => write!(f, "indxdb"),
 			#[cfg(feature = "kv-tikv")]
@@ -149,6 +196,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.closed(),
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.closed(),
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.closed(),
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.closed(),
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -191,6 +253,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.cancel().await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.cancel().await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.cancel().await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.cancel().await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -217,7 +294,7 @@ impl Transaction {
 	pub async fn commit(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Commit");
-		match self {
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -233,6 +310,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.commit().await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.commit().await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.commit().await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.commit().await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -250,17 +342,33 @@ impl Transaction {
 			} => v.commit().await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
+		};
+		// Count the bytes written towards the storage used by each database
+		if res.is_ok() {
+			self.usage.written(std::mem::take(&mut self.written));
 		}
+		res
 	}
 
 	/// Delete a key from the datastore.
-	#[allow(unused_variables)]
 	pub async fn del<K>(&mut self, key: K) -> Result<(), Error>
 	where
 		K: Into<Key> + Debug + Into<Vec<u8>> + Clone,
 	{
 		#[cfg(debug_assertions)]
 		trace!("Del {:?}", crate::key::debug::sprint_key(&key.clone().into()));
+		let key: Key = key.into();
+		self.del_chunks(&key).await?;
+		self.remove(key).await
+	}
+
+	/// Delete a key from the datastore, without deleting the chunks of its value.
+	#[allow(unused_variables)]
+	async fn remove(&mut self, key: Key) -> Result<(), Error> {
+		self.size += key.len() as u64;
+		self.undo(&key).await?;
+		self.forget(&key);
+		let key = self.intern(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -277,6 +385,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.del(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.del(key).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.del(key).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.del(key).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -305,6 +428,7 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Exi {:?}", key);
+		let key = self.intern(key.into()).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -321,6 +445,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.exi(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.exi(key).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.exi(key).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.exi(key).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -342,13 +481,38 @@ impl Transaction {
 	}
 
 	/// Fetch a key from the datastore.
-	#[allow(unused_variables)]
 	pub async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
 	where
 		K: Into<Key> + Debug,
 	{
 		#[cfg(debug_assertions)]
 		trace!("Get {:?}", key);
+		let key: Key = key.into();
+		let tmp = self.intern(key.clone()).await?;
+		let val = self.fetch(tmp).await?;
+		match val {
+			Some(v) => Ok(Some(self.join_chunks(&key, v).await?)),
+			None => Ok(None),
+		}
+	}
+
+	/// Fetch a key from the datastore as a reader, which reads a large value
+	/// one batch of chunks at a time, instead of joining its chunks in memory.
+	pub async fn get_reader<K>(&mut self, key: K) -> Result<Option<ValueReader>, Error>
+	where
+		K: Into<Key> + Debug,
+	{
+		#[cfg(debug_assertions)]
+		trace!("GetReader {:?}", key);
+		let key: Key = key.into();
+		let tmp = self.intern(key.clone()).await?;
+		let val = self.fetch(tmp).await?;
+		Ok(val.map(|v| ValueReader::new(key, v)))
+	}
+
+	/// Fetch a key from the datastore, without interning its prefix.
+	#[allow(unused_variables)]
+	async fn fetch(&mut self, key: Key) -> Result<Option<Val>, Error> {
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -365,6 +529,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.get(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.get(key).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.get(key).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.get(key).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -385,8 +564,73 @@ impl Transaction {
 		}
 	}
 
-	/// Insert or update a key in the datastore.
+	/// Fetch many keys from the datastore.
+	///
+	/// The values are returned in the same order as the keys. Storage engines
+	/// which support batched reads fetch all of the keys in one request, while
+	/// the other storage engines fetch the keys one by one.
 	#[allow(unused_variables)]
+	pub async fn multi_get<K>(&mut self, keys: Vec<K>) -> Result<Vec<Option<Val>>, Error>
+	where
+		K: Into<Key> + Debug,
+	{
+		#[cfg(debug_assertions)]
+		trace!("MultiGet {:?}", keys);
+		let names: Vec<Key> = keys.into_iter().map(Into::into).collect();
+		let mut keys = Vec::with_capacity(names.len());
+		for key in names.iter() {
+			keys.push(self.intern(key.clone()).await?);
+		}
+		let res = match self {
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(v),
+				..
+			} => v.multi_get(keys).await,
+			#[cfg(feature = "kv-speedb")]
+			Transaction {
+				inner: Inner::SpeeDB(v),
+				..
+			} => v.multi_get(keys).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.multi_get(keys).await,
+			#[cfg(feature = "kv-tikv")]
+			Transaction {
+				inner: Inner::TiKV(v),
+				..
+			} => v.multi_get(keys).await,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FoundationDB(v),
+				..
+			} => v.multi_get(keys).await,
+			#[allow(unreachable_patterns)]
+			_ => {
+				let mut res = Vec::with_capacity(keys.len());
+				for key in keys {
+					res.push(self.fetch(key).await?);
+				}
+				Ok(res)
+			}
+		}?;
+		// Join the chunks of any large values
+		let mut out = Vec::with_capacity(res.len());
+		for (key, val) in names.iter().zip(res) {
+			match val {
+				Some(v) => out.push(Some(self.join_chunks(key, v).await?)),
+				None => out.push(None),
+			}
+		}
+		Ok(out)
+	}
+
+	/// Insert or update a key in the datastore.
+	///
+	/// The value of a record which is larger than the chunk size is split
+	/// into chunks, which are joined together again when the record is read.
 	pub async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
 	where
 		K: Into<Key> + Debug,
@@ -394,6 +638,26 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Set {:?} => {:?}", key, val);
+		let key: Key = key.into();
+		let val: Val = val.into();
+		self.charge(&key, &val)?;
+		self.del_chunks(&key).await?;
+		match Header::new(&key, &val, *cnf::VALUE_CHUNK_SIZE) {
+			Some(head) => {
+				self.store(key.clone(), head.encode()).await?;
+				self.set_chunks(key, &val).await
+			}
+			None => self.store(key, val).await,
+		}
+	}
+
+	/// Insert or update a key in the datastore, without splitting its value into chunks.
+	#[allow(unused_variables)]
+	async fn store(&mut self, key: Key, val: Val) -> Result<(), Error> {
+		self.size += (key.len() + val.len()) as u64;
+		self.undo(&key).await?;
+		self.forget(&key);
+		let key = self.intern(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -410,6 +674,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.set(key, val).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.set(key, val).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.set(key, val).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.set(key, val).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -473,6 +752,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.get_timestamp(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.get_timestamp(key).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.get_timestamp(key).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.get_timestamp(key).await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		}
@@ -562,13 +856,39 @@ impl Transaction {
 				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
 				v.set(k, val).await
 			}
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => {
+				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
+				v.set(k, val).await
+			}
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => {
+				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
+				v.set(k, val).await
+			}
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => {
+				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
+				v.set(k, val).await
+			}
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		}
 	}
 
 	/// Insert a key if it doesn't exist in the datastore.
-	#[allow(unused_variables)]
+	///
+	/// The value of a record which is larger than the chunk size is split
+	/// into chunks, which are joined together again when the record is read.
 	pub async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
 	where
 		K: Into<Key> + Debug,
@@ -576,6 +896,25 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Put {:?} => {:?}", key, val);
+		let key: Key = key.into();
+		let val: Val = val.into();
+		self.charge(&key, &val)?;
+		match Header::new(&key, &val, *cnf::VALUE_CHUNK_SIZE) {
+			Some(head) => {
+				self.insert(key.clone(), head.encode()).await?;
+				self.set_chunks(key, &val).await
+			}
+			None => self.insert(key, val).await,
+		}
+	}
+
+	/// Insert a key if it doesn't exist in the datastore, without splitting its value into chunks.
+	#[allow(unused_variables)]
+	async fn insert(&mut self, key: Key, val: Val) -> Result<(), Error> {
+		self.size += (key.len() + val.len()) as u64;
+		self.undo(&key).await?;
+		self.forget(&key);
+		let key = self.intern(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -592,6 +931,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.put(key, val).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.put(key, val).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.put(key, val).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.put(key, val).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -615,13 +969,85 @@ impl Transaction {
 	/// Retrieve a specific range of keys from the datastore.
 	///
 	/// This function fetches the full range of key-value pairs, in a single request to the underlying datastore.
-	#[allow(unused_variables)]
 	pub async fn scan<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
 	where
 		K: Into<Key> + Debug + Clone,
 	{
 		#[cfg(debug_assertions)]
 		trace!("Scan {:?} - {:?}", rng.start, rng.end);
+		let mut res = self.fetch_range(rng.start.into()..rng.end.into(), limit).await?;
+		// Join the chunks of any large values
+		for (k, v) in res.iter_mut() {
+			if Header::read(k, v).is_some() {
+				*v = self.join_chunks(k, std::mem::take(v)).await?;
+			}
+		}
+		Ok(res)
+	}
+
+	/// Retrieve a specific range of keys from the datastore, without joining the chunks of their values.
+	///
+	/// The keys of interned tables are returned under the names of their tables, so that a range
+	/// which spans a whole database or namespace holds the keys of every one of its tables.
+	pub(super) async fn fetch_range(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error> {
+		// A range within a single table is read from the prefix which the table is stored under
+		let (rng, name) = self.intern_range(rng.start, rng.end).await?;
+		if let Some((name, len)) = name {
+			let res = self.fetch_raw(rng, limit).await?;
+			return Ok(res
+				.into_iter()
+				.map(|(k, v)| ([name.as_slice(), &k[len..]].concat(), v))
+				.collect());
+		}
+		let tables = self.interned_within(&rng).await?;
+		if tables.is_empty() {
+			return self.fetch_raw(rng, limit).await;
+		}
+		// A wider range is read from each of the interned tables within it as well
+		let mut res = self.fetch_raw(rng.clone(), limit).await?;
+		// The keys past the end of a full batch may not have been read from every table
+		let mut until = match res.len() as u32 >= limit {
+			true => res.last().map(|(k, _)| k.clone()),
+			false => None,
+		};
+		res.retain(|(k, _)| crate::key::interned::interned(k).is_none());
+		for (name, prefix) in tables {
+			let beg = match rng.start.starts_with(&name) {
+				true => [prefix.as_slice(), &rng.start[name.len()..]].concat(),
+				false => prefix.clone(),
+			};
+			let end = match rng.end.starts_with(&name) {
+				true => [prefix.as_slice(), &rng.end[name.len()..]].concat(),
+				false => [prefix.as_slice(), &[0xff]].concat(),
+			};
+			let tmp = self.fetch_raw(beg..end, limit).await?;
+			let full = tmp.len() as u32 >= limit;
+			res.extend(
+				tmp.into_iter().map(|(k, v)| ([name.as_slice(), &k[prefix.len()..]].concat(), v)),
+			);
+			if full {
+				let last = res.last().map(|(k, _)| k.clone());
+				until = match (until, last) {
+					(Some(a), Some(b)) => Some(a.min(b)),
+					(a, b) => a.or(b),
+				};
+			}
+		}
+		res.sort_by(|a, b| a.0.cmp(&b.0));
+		if let Some(until) = until {
+			res.retain(|(k, _)| *k <= until);
+		}
+		res.truncate(limit as usize);
+		Ok(res)
+	}
+
+	/// Retrieve a specific range of keys from the underlying datastore, as they are stored.
+	#[allow(unused_variables)]
+	async fn fetch_raw(&mut self, rng: Range<Key>, limit: u32) -> Result<Vec<(Key, Val)>, Error> {
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -638,6 +1064,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.scan(rng, limit).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.scan(rng, limit).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.scan(rng, limit).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.scan(rng, limit).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -667,6 +1108,10 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Putc {:?} if {:?} => {:?}", key, chk, val);
+		let key: Key = key.into();
+		self.undo(&key).await?;
+		self.forget(&key);
+		let key = self.intern(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -683,6 +1128,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.putc(key, val, chk).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.putc(key, val, chk).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.putc(key, val, chk).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.putc(key, val, chk).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -712,6 +1172,10 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Delc {:?} if {:?}", key, chk);
+		let key: Key = key.into();
+		self.undo(&key).await?;
+		self.forget(&key);
+		let key = self.intern(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -728,6 +1192,21 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.delc(key, chk).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(v),
+				..
+			} => v.delc(key, chk).await,
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(v),
+				..
+			} => v.delc(key, chk).await,
+			#[cfg(feature = "kv-custom")]
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.delc(key, chk).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
@@ -748,6 +1227,177 @@ impl Transaction {
 		}
 	}
 
+	/// Atomically increment a counter in the datastore, returning the new value.
+	/// The counter is created with the initial value if it does not exist yet.
+	pub async fn incr<K>(&mut self, key: K, init: i64) -> Result<i64, Error>
+	where
+		K: Into<Key> + Debug,
+	{
+		#[cfg(debug_assertions)]
+		trace!("Incr {:?} from {:?}", key, init);
+		let key: Key = key.into();
+		// Fetch the current value of the counter
+		let chk = self.get(key.clone()).await?;
+		// Increment the current value of the counter
+		let val = match &chk {
+			Some(v) => {
+				let v: [u8; 8] = v.as_slice().try_into().map_err(|_| Error::Unreachable)?;
+				let v = i64::from_be_bytes(v);
+				v.checked_add(1).ok_or_else(|| Error::TryAdd(v.to_string(), 1.to_string()))?
+			}
+			None => init,
+		};
+		// Store the new value, if the counter is unchanged
+		self.putc(key, val.to_be_bytes().to_vec(), chk).await?;
+		Ok(val)
+	}
+
+	// --------------------------------------------------
+	// Event methods
+	// --------------------------------------------------
+
+	/// Defer an AFTER event until this transaction has been committed.
+	pub(crate) fn defer_event(&mut self, ev: AfterEvent) {
+		self.events.push(ev);
+	}
+
+	/// Take the AFTER events which were deferred in this transaction.
+	pub(crate) fn take_events(&mut self) -> Vec<AfterEvent> {
+		std::mem::take(&mut self.events)
+	}
+
+	/// Returns the approximate number of bytes written in this transaction.
+	pub(crate) fn size(&self) -> u64 {
+		self.size
+	}
+
+	// --------------------------------------------------
+	// Savepoint methods
+	// --------------------------------------------------
+
+	/// Create a named savepoint within this transaction.
+	pub fn savepoint(&mut self, name: &str) {
+		#[cfg(debug_assertions)]
+		trace!("Savepoint {}", name);
+		self.savepoints.push(Savepoint {
+			name: name.to_owned(),
+			undo: HashMap::new(),
+			cf: self.cf.mark(),
+			events: self.events.len(),
+			state: Some((self.cache.clone(), self.write_buffer.clone())),
+		});
+	}
+
+	/// Create a named savepoint which only records the keys modified
+	/// since it was created, without copying the transaction cache.
+	///
+	/// This is used for short-lived savepoints, such as those created
+	/// for each record of an INSERT PARTIAL statement, so that the cost
+	/// of a savepoint does not grow with the size of the transaction.
+	pub(crate) fn lightweight_savepoint(&mut self, name: &str) {
+		#[cfg(debug_assertions)]
+		trace!("Lightweight savepoint {}", name);
+		self.savepoints.push(Savepoint {
+			name: name.to_owned(),
+			undo: HashMap::new(),
+			cf: self.cf.mark(),
+			events: self.events.len(),
+			state: None,
+		});
+	}
+
+	/// Undo all changes made since a savepoint was created.
+	///
+	/// The savepoint remains, and any savepoints created
+	/// after the specified savepoint are removed.
+	pub async fn rollback_to_savepoint(&mut self, name: &str) -> Result<(), Error> {
+		#[cfg(debug_assertions)]
+		trace!("Rollback to savepoint {}", name);
+		// Find the most recent savepoint with this name
+		let pos = match self.savepoints.iter().rposition(|v| v.name == name) {
+			Some(pos) => pos,
+			None => {
+				return Err(Error::SpNotFound {
+					value: name.to_owned(),
+				})
+			}
+		};
+		// Take the savepoints so that restored keys are not recorded
+		let mut savepoints = std::mem::take(&mut self.savepoints);
+		// Restore the keys modified since the savepoint, newest first
+		for sp in savepoints.drain(pos + 1..).rev() {
+			self.restore(sp.undo).await?;
+		}
+		let sp = &mut savepoints[pos];
+		self.restore(std::mem::take(&mut sp.undo)).await?;
+		// Restore the transaction state
+		self.cf.truncate(&sp.cf);
+		self.events.truncate(sp.events);
+		match &sp.state {
+			Some((cache, write_buffer)) => {
+				self.cache = cache.clone();
+				self.write_buffer = write_buffer.clone();
+			}
+			None => self.cache.clear_definitions(),
+		}
+		self.savepoints = savepoints;
+		Ok(())
+	}
+
+	/// Remove a savepoint, keeping all changes made since it was created.
+	///
+	/// Any savepoints created after the specified savepoint are also removed.
+	pub fn release_savepoint(&mut self, name: &str) -> Result<(), Error> {
+		#[cfg(debug_assertions)]
+		trace!("Release savepoint {}", name);
+		// Find the most recent savepoint with this name
+		let pos = match self.savepoints.iter().rposition(|v| v.name == name) {
+			Some(pos) => pos,
+			None => {
+				return Err(Error::SpNotFound {
+					value: name.to_owned(),
+				})
+			}
+		};
+		// Merge the undo records into the enclosing savepoint
+		let released: Vec<_> = self.savepoints.drain(pos..).collect();
+		if let Some(sp) = self.savepoints.last_mut() {
+			for v in released {
+				for (k, v) in v.undo {
+					sp.undo.entry(k).or_insert(v);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Record the original value of a key before it is modified, so
+	/// that the change can be undone when rolling back to a savepoint.
+	async fn undo(&mut self, key: &Key) -> Result<(), Error> {
+		match self.savepoints.last() {
+			Some(sp) if !sp.undo.contains_key(key) => {
+				let tmp = self.intern(key.clone()).await?;
+				let val = self.fetch(tmp).await?;
+				if let Some(sp) = self.savepoints.last_mut() {
+					sp.undo.insert(key.clone(), val);
+				}
+				Ok(())
+			}
+			_ => Ok(()),
+		}
+	}
+
+	/// Restore the original values of the modified keys.
+	async fn restore(&mut self, undo: HashMap<Key, Option<Val>>) -> Result<(), Error> {
+		for (k, v) in undo {
+			match v {
+				Some(v) => self.store(k, v).await?,
+				None => self.remove(k).await?,
+			}
+		}
+		Ok(())
+	}
+
 	// --------------------------------------------------
 	// Superjacent methods
 	// --------------------------------------------------
@@ -816,44 +1466,38 @@ impl Transaction {
 		trace!("Delr {:?}..{:?} (limit: {limit})", rng.start, rng.end);
 		let beg: Key = rng.start.into();
 		let end: Key = rng.end.into();
-		let mut nxt: Option<Key> = None;
-		let mut num = limit;
-		// Start processing
-		while num > 0 {
-			// Get records batch
-			let res = match nxt {
-				None => {
-					let min = beg.clone();
-					let max = end.clone();
-					let num = std::cmp::min(1000, num);
-					self.scan(min..max, num).await?
-				}
-				Some(ref mut beg) => {
-					beg.push(0x00);
-					let min = beg.clone();
-					let max = end.clone();
-					let num = std::cmp::min(1000, num);
-					self.scan(min..max, num).await?
-				}
-			};
-			// Get total results
-			let n = res.len();
-			// Exit when settled
-			if n == 0 {
-				break;
-			}
-			// Loop over results
-			for (i, (k, _)) in res.into_iter().enumerate() {
-				// Ready the next
-				if n == i + 1 {
-					nxt = Some(k.clone());
-				}
-				// Delete
-				self.del(k).await?;
-				// Count
-				num -= 1;
+		self.delete_range(beg..end, limit).await?;
+		Ok(())
+	}
+	/// Delete every key within a range of a single table.
+	///
+	/// Where the storage engine supports range deletes within a transaction, the keys are deleted
+	/// with a single range delete, instead of being read and deleted in batches of 1000. The keys
+	/// are always deleted in batches while a savepoint needs to be able to restore each key.
+	#[allow(unused_variables)]
+	pub async fn delr_all<K>(&mut self, rng: Range<K>) -> Result<(), Error>
+	where
+		K: Into<Key> + Debug,
+	{
+		#[cfg(debug_assertions)]
+		trace!("DelrAll {:?}..{:?}", rng.start, rng.end);
+		let beg: Key = rng.start.into();
+		let end: Key = rng.end.into();
+		let single =
+			crate::key::interned::named(&beg).map_or(false, |n| end.starts_with(&beg[..n]));
+		if single && self.savepoints.is_empty() {
+			let (rng, _) = self.intern_range(beg.clone(), end.clone()).await?;
+			match self {
+				#[cfg(feature = "kv-fdb")]
+				Transaction {
+					inner: Inner::FoundationDB(v),
+					..
+				} => return v.delr(rng).await,
+				#[allow(unreachable_patterns)]
+				_ => (),
 			}
 		}
+		self.delete_range(beg..end, u32::MAX).await?;
 		Ok(())
 	}
 	/// Retrieve a specific prefix of keys from the datastore.
@@ -919,6 +1563,33 @@ impl Transaction {
 		trace!("Delp {:?} (limit: {limit})", key);
 		let beg: Key = key.into();
 		let end: Key = beg.clone().add(0xff);
+		// Drop whole table and index partitions at once, unless
+		// a savepoint needs to be able to restore each key
+		if limit == u32::MAX && self.savepoints.is_empty() {
+			self.drop_partitions(&beg).await?;
+		}
+		self.delete_range(beg..end, limit).await?;
+		Ok(())
+	}
+
+	/// Delete the keys within a range, in batches of 1000, returning the number of keys deleted.
+	///
+	/// The keys of the interned tables within a wider range are deleted first, while the
+	/// definitions which their interned prefixes depend on are still stored.
+	async fn delete_range(&mut self, rng: Range<Key>, limit: u32) -> Result<u32, Error> {
+		let mut num = limit;
+		for (name, _) in self.interned_within(&rng).await? {
+			let beg = rng.start.clone().max(name.clone());
+			let end = rng.end.clone().min(name.add(0xff));
+			num -= self.delete_keys(beg..end, num).await?;
+		}
+		num -= self.delete_keys(rng, num).await?;
+		Ok(limit - num)
+	}
+
+	/// Delete the keys within a range, as they are read by `fetch_range`, returning the number of keys deleted.
+	async fn delete_keys(&mut self, rng: Range<Key>, limit: u32) -> Result<u32, Error> {
+		let (beg, end) = (rng.start, rng.end);
 		let mut nxt: Option<Key> = None;
 		let mut num = limit;
 		// Start processing
@@ -929,14 +1600,14 @@ impl Transaction {
 					let min = beg.clone();
 					let max = end.clone();
 					let num = std::cmp::min(1000, num);
-					self.scan(min..max, num).await?
+					self.fetch_range(min..max, num).await?
 				}
 				Some(ref mut beg) => {
-					beg.push(0);
+					beg.push(0x00);
 					let min = beg.clone();
 					let max = end.clone();
 					let num = std::cmp::min(1000, num);
-					self.scan(min..max, num).await?
+					self.fetch_range(min..max, num).await?
 				}
 			};
 			// Get total results
@@ -951,15 +1622,243 @@ impl Transaction {
 				if n == i + 1 {
 					nxt = Some(k.clone());
 				}
-				// Delete
+				// Delete, along with the chunks of any large value
 				self.del(k).await?;
 				// Count
 				num -= 1;
 			}
 		}
+		Ok(limit - num)
+	}
+
+	/// Drop the storage partitions of any tables and indexes under a key prefix.
+	///
+	/// This is a no-op for storage engines which do not partition their data.
+	#[allow(unused_variables)]
+	async fn drop_partitions(&mut self, prefix: &[u8]) -> Result<(), Error> {
+		// A database or namespace also holds the partitions of its interned tables
+		let rng = prefix.to_vec()..[prefix, &[0xff]].concat();
+		let mut prefixes = vec![self.intern(prefix.to_vec()).await?];
+		if crate::key::interned::named(prefix).is_none() {
+			prefixes.extend(self.interned_within(&rng).await?.into_iter().map(|(_, v)| v));
+		}
+		for prefix in prefixes {
+			match self {
+				#[cfg(feature = "kv-rocksdb")]
+				Transaction {
+					inner: Inner::RocksDB(v),
+					..
+				} => v.drop_partitions(&prefix).await?,
+				#[cfg(feature = "kv-speedb")]
+				Transaction {
+					inner: Inner::SpeeDB(v),
+					..
+				} => v.drop_partitions(&prefix).await?,
+				#[allow(unreachable_patterns)]
+				_ => (),
+			}
+		}
 		Ok(())
 	}
 
+	// --------------------------------------------------
+	// Interning methods
+	// --------------------------------------------------
+
+	/// Replace the name prefix of a table key with the
+	/// interned prefix of the table, if it has one.
+	async fn intern(&mut self, key: Key) -> Result<Key, Error> {
+		if let Some(len) = crate::key::interned::named(&key) {
+			if let Some(prefix) = self.interned(&key[..len]).await? {
+				return Ok([prefix.as_slice(), &key[len..]].concat());
+			}
+		}
+		Ok(key)
+	}
+
+	/// Replace the name prefix of a range within a table with the interned
+	/// prefix of the table, returning the name prefix and the length of the
+	/// interned prefix, so that the name prefix can be restored on the keys.
+	async fn intern_range(
+		&mut self,
+		beg: Key,
+		end: Key,
+	) -> Result<(Range<Key>, Option<(Key, usize)>), Error> {
+		if let Some(len) = crate::key::interned::named(&beg) {
+			if end.starts_with(&beg[..len]) {
+				if let Some(prefix) = self.interned(&beg[..len]).await? {
+					let name = beg[..len].to_vec();
+					let beg = [prefix.as_slice(), &beg[len..]].concat();
+					let end = [prefix.as_slice(), &end[len..]].concat();
+					return Ok((beg..end, Some((name, prefix.len()))));
+				}
+			}
+		}
+		Ok((beg..end, None))
+	}
+
+	/// Retrieve the name prefix and the interned prefix of each interned table whose keys
+	/// fall within a range which is wider than a single table.
+	async fn interned_within(&mut self, rng: &Range<Key>) -> Result<Vec<(Key, Key)>, Error> {
+		let mut res = Vec::new();
+		if !crate::key::interned::spans_tables(&rng.start, &rng.end) {
+			return Ok(res);
+		}
+		// The catalog is only read if any table is interned
+		let (beg, end) = crate::key::interned::range();
+		if self.fetch_raw(beg..end, 1).await?.is_empty() {
+			return Ok(res);
+		}
+		let overlaps = |p: &[u8]| {
+			p < rng.end.as_slice() && rng.start.as_slice() < [p, &[0xff]].concat().as_slice()
+		};
+		// The definitions are fetched directly, as they are never interned
+		let (beg, end) = (crate::key::root::ns::prefix(), crate::key::root::ns::suffix());
+		for (_, ns) in self.fetch_raw(beg..end, u32::MAX).await? {
+			let ns = DefineNamespaceStatement::from(ns);
+			if !overlaps(&crate::key::namespace::all::new(&ns.name).encode()?) {
+				continue;
+			}
+			let beg = crate::key::namespace::db::prefix(&ns.name);
+			let end = crate::key::namespace::db::suffix(&ns.name);
+			for (_, db) in self.fetch_raw(beg..end, u32::MAX).await? {
+				let db = DefineDatabaseStatement::from(db);
+				if !overlaps(&crate::key::database::all::new(&ns.name, &db.name).encode()?) {
+					continue;
+				}
+				let beg = crate::key::database::tb::prefix(&ns.name, &db.name);
+				let end = crate::key::database::tb::suffix(&ns.name, &db.name);
+				for (_, tb) in self.fetch_raw(beg..end, u32::MAX).await? {
+					let tb = DefineTableStatement::from(tb);
+					let name: Key =
+						crate::key::table::all::new(&ns.name, &db.name, &tb.name).into();
+					if tb.interned && overlaps(&name) {
+						if let Some(prefix) = self.interned(&name).await? {
+							res.push((name, prefix));
+						}
+					}
+				}
+			}
+		}
+		Ok(res)
+	}
+
+	/// Retrieve the interned prefix of a table, by the name prefix of its keys.
+	async fn interned(&mut self, name: &[u8]) -> Result<Option<Key>, Error> {
+		if let Some(v) = self.interned.get(name) {
+			return Ok(v.clone());
+		}
+		let Some((ns, db, tb)) = crate::key::interned::names(name) else {
+			return Ok(None);
+		};
+		// The definitions are fetched directly, as they are never interned
+		let key = crate::key::database::tb::new(&ns, &db, &tb).into();
+		let val = match self.fetch(key).await? {
+			Some(v) => DefineTableStatement::from(v),
+			None => {
+				self.interned.insert(name.to_vec(), None);
+				return Ok(None);
+			}
+		};
+		let res = match (val.interned, val.id) {
+			(true, Some(tb)) => {
+				let key = crate::key::namespace::db::new(&ns, &db).into();
+				let db = self.fetch(key).await?.map(DefineDatabaseStatement::from);
+				let key = crate::key::root::ns::new(&ns).into();
+				let ns = self.fetch(key).await?.map(DefineNamespaceStatement::from);
+				match (ns.and_then(|v| v.id), db.and_then(|v| v.id)) {
+					(Some(ns), Some(db)) => Some(crate::key::interned::new(ns, db, tb).into()),
+					_ => None,
+				}
+			}
+			_ => None,
+		};
+		self.interned.insert(name.to_vec(), res.clone());
+		Ok(res)
+	}
+
+	// --------------------------------------------------
+	// Quota methods
+	// --------------------------------------------------
+
+	/// Count a record, index entry, or graph edge which is written towards the storage
+	/// used by its database, checking that it stays within the configured quotas.
+	fn charge(&mut self, key: &[u8], val: &[u8]) -> Result<(), Error> {
+		let Some(len) = crate::key::interned::named(key) else {
+			return Ok(());
+		};
+		let Some((ns, db, _)) = crate::key::interned::names(&key[..len]) else {
+			return Ok(());
+		};
+		let bytes = (key.len() + val.len()) as u64;
+		if !self.quotas.is_empty() {
+			self.quotas.check(&self.usage, &self.written, &ns, &db, bytes)?;
+		}
+		*self.written.entry((ns, db)).or_default() += bytes;
+		Ok(())
+	}
+
+	// --------------------------------------------------
+	// Chunking methods
+	// --------------------------------------------------
+
+	/// Store the chunks of a large value, one chunk at a time.
+	async fn set_chunks(&mut self, key: Key, val: &[u8]) -> Result<(), Error> {
+		let size = *cnf::VALUE_CHUNK_SIZE;
+		for (nr, v) in val.chunks(size).enumerate() {
+			self.store(chunk::key(&key, nr as u32), v.to_vec()).await?;
+		}
+		Ok(())
+	}
+
+	/// Delete any chunks stored for the value of a record, by deleting the
+	/// whole key range of its chunks, so that no chunks are left behind by
+	/// values which were written without being read in this transaction.
+	async fn del_chunks(&mut self, key: &[u8]) -> Result<(), Error> {
+		let Some(mut rng) = chunk::range(key) else {
+			return Ok(());
+		};
+		loop {
+			let batch = self.fetch_range(rng.clone(), 1000).await?;
+			let Some((last, _)) = batch.last() else {
+				break;
+			};
+			rng.start = [last.as_slice(), &[0x00]].concat();
+			for (k, _) in batch {
+				self.remove(k).await?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Join the chunks of a value, if it has been split into chunks.
+	async fn join_chunks(&mut self, key: &[u8], val: Val) -> Result<Val, Error> {
+		let Some(head) = Header::read(key, &val) else {
+			return Ok(val);
+		};
+		let mut out = Vec::with_capacity(head.len as usize);
+		let mut reader = ValueReader::new(key.to_vec(), val);
+		while let Some(v) = reader.next(self).await? {
+			out.extend(v);
+		}
+		Ok(out)
+	}
+
+	/// Retrieve the number of chunks of the value of a record, if it has been split into chunks.
+	pub(super) async fn get_chunk_count(&mut self, key: Key) -> Result<Option<u32>, Error> {
+		let tmp = self.intern(key.clone()).await?;
+		let val = self.fetch(tmp).await?;
+		Ok(val.and_then(|v| Header::read(&key, &v)).map(|v| v.count))
+	}
+
+	/// Forget the interned prefixes when a namespace, database, or table is
+	/// defined or removed, as the ids which they are made from may change.
+	fn forget(&mut self, key: &[u8]) {
+		if crate::key::interned::catalog(key) {
+			self.interned.clear();
+		}
+	}
+
 	// --------------------------------------------------
 	// Superimposed methods
 	// --------------------------------------------------
@@ -1437,6 +2336,29 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve all sequence definitions for a specific database.
+	pub async fn all_db_sequences(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineSequenceStatement]>, Error> {
+		let key = crate::key::database::sq::prefix(ns, db);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Sqs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::database::sq::prefix(ns, db);
+			let end = crate::key::database::sq::suffix(ns, db);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Sqs(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all scope definitions for a specific database.
 	pub async fn all_sc(
 		&mut self,
@@ -1748,6 +2670,27 @@ impl Transaction {
 		Ok(val.into())
 	}
 
+	/// Retrieve a specific sequence definition.
+	pub async fn get_db_sequence(
+		&mut self,
+		ns: &str,
+		db: &str,
+		sq: &str,
+	) -> Result<DefineSequenceStatement, Error> {
+		let key = crate::key::database::sq::new(ns, db, sq);
+		let val = self.get(key).await?.ok_or(Error::SqNotFound {
+			value: sq.to_owned(),
+		})?;
+		Ok(val.into())
+	}
+
+	/// Retrieve the next value of a specific sequence.
+	pub async fn next_db_sequence(&mut self, ns: &str, db: &str, sq: &str) -> Result<i64, Error> {
+		let def = self.get_db_sequence(ns, db, sq).await?;
+		let key = crate::key::database::sv::new(ns, db, sq);
+		self.incr(key, def.first()).await
+	}
+
 	/// Retrieve a specific scope definition.
 	pub async fn get_sc(
 		&mut self,
@@ -1836,6 +2779,7 @@ impl Transaction {
 				false => {
 					let key = crate::key::root::ns::new(ns);
 					let val = DefineNamespaceStatement {
+						id: Some(self.get_next_ns_id().await?),
 						name: ns.to_owned().into(),
 						..Default::default()
 					};
@@ -1864,7 +2808,15 @@ impl Transaction {
 			}) => match strict {
 				false => {
 					let key = crate::key::namespace::db::new(ns, db);
+					let id = match self.get_ns(ns).await {
+						Ok(_) => {
+							let ns = self.ensure_ns_id(ns).await?;
+							Some(self.get_next_db_id(ns).await?)
+						}
+						Err(_) => None,
+					};
 					let val = DefineDatabaseStatement {
+						id,
 						name: db.to_owned().into(),
 						..Default::default()
 					};
@@ -1924,9 +2876,22 @@ impl Transaction {
 			}) => match strict {
 				false => {
 					let key = crate::key::database::tb::new(ns, db, tb);
+					let id = match self.get_db(ns, db).await {
+						Ok(_) => {
+							let (ns, db) = self.ensure_db_id(ns, db).await?;
+							Some(self.get_next_tb_id(ns, db).await?)
+						}
+						Err(_) => None,
+					};
+					let interned = match id {
+						Some(_) => self.can_intern_tb(ns, db, tb).await?,
+						None => false,
+					};
 					let val = DefineTableStatement {
+						id,
 						name: tb.to_owned().into(),
 						permissions: Permissions::none(),
+						interned,
 						..Default::default()
 					};
 					self.put(key, &val).await?;
@@ -1941,6 +2906,128 @@ impl Transaction {
 		}
 	}
 
+	/// Retrieve the numeric id of a namespace, assigning one if it has none.
+	pub(crate) async fn ensure_ns_id(&mut self, ns: &str) -> Result<u32, Error> {
+		let mut val = self.get_ns(ns).await?;
+		match val.id {
+			Some(id) => Ok(id),
+			None => {
+				let id = self.get_next_ns_id().await?;
+				val.id = Some(id);
+				self.set(crate::key::root::ns::new(ns), val).await?;
+				Ok(id)
+			}
+		}
+	}
+
+	/// Retrieve the numeric ids of a database, assigning any which are missing.
+	pub(crate) async fn ensure_db_id(&mut self, ns: &str, db: &str) -> Result<(u32, u32), Error> {
+		let nid = self.ensure_ns_id(ns).await?;
+		let mut val = self.get_db(ns, db).await?;
+		match val.id {
+			Some(id) => Ok((nid, id)),
+			None => {
+				let id = self.get_next_db_id(nid).await?;
+				val.id = Some(id);
+				self.set(crate::key::namespace::db::new(ns, db), val).await?;
+				Ok((nid, id))
+			}
+		}
+	}
+
+	/// Retrieve the numeric ids of a table, assigning any which are missing.
+	pub(crate) async fn ensure_tb_id(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<(u32, u32, u32), Error> {
+		let (nid, did) = self.ensure_db_id(ns, db).await?;
+		let mut val = self.get_tb(ns, db, tb).await?;
+		match val.id {
+			Some(id) => Ok((nid, did, id)),
+			None => {
+				let id = self.get_next_tb_id(nid, did).await?;
+				val.id = Some(id);
+				self.set(crate::key::database::tb::new(ns, db, tb), val).await?;
+				Ok((nid, did, id))
+			}
+		}
+	}
+
+	/// Check whether a new table can store its keys under an interned prefix.
+	///
+	/// A table which has had keys written under the names of the namespace,
+	/// database, and table, before it was defined, keeps using the names.
+	pub(crate) async fn can_intern_tb(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<bool, Error> {
+		let beg: Key = crate::key::table::all::new(ns, db, tb).into();
+		let end: Key = beg.clone().add(0xff);
+		Ok(self.scan(beg..end, 1).await?.is_empty())
+	}
+
+	/// Move the keys of a table, which are stored under the names of the namespace,
+	/// database, and table, to the interned prefix of the table. Returns whether
+	/// any keys were moved.
+	pub(crate) async fn intern_tb(&mut self, ns: &str, db: &str, tb: &str) -> Result<bool, Error> {
+		self.ensure_tb_id(ns, db, tb).await?;
+		let def = self.get_tb(ns, db, tb).await?;
+		if def.interned {
+			return Ok(false);
+		}
+		// Read the keys as they are stored, before the table is interned
+		let beg: Key = crate::key::table::all::new(ns, db, tb).into();
+		let end: Key = beg.clone().add(0xff);
+		let mut rng = beg..end;
+		let mut keys = Vec::new();
+		loop {
+			let batch = self.fetch_raw(rng.clone(), 1000).await?;
+			let Some((last, _)) = batch.last() else {
+				break;
+			};
+			rng.start = [last.as_slice(), &[0x00]].concat();
+			keys.extend(batch);
+		}
+		for (k, _) in keys.iter() {
+			self.remove(k.clone()).await?;
+		}
+		// The keys are stored under the interned prefix once the table is interned
+		let key = crate::key::database::tb::new(ns, db, tb);
+		self.set(
+			key,
+			DefineTableStatement {
+				interned: true,
+				..def
+			},
+		)
+		.await?;
+		self.clr(crate::key::database::tb::new(ns, db, tb)).await?;
+		let moved = !keys.is_empty();
+		for (k, v) in keys {
+			self.store(k, v).await?;
+		}
+		Ok(moved)
+	}
+
+	/// Assign numeric ids to any namespace, database, or table definitions
+	/// which were stored before the id mapping was maintained.
+	pub(crate) async fn ensure_all_ids(&mut self) -> Result<(), Error> {
+		for ns in self.all_ns().await?.iter() {
+			self.ensure_ns_id(&ns.name).await?;
+			for db in self.all_db(&ns.name).await?.iter() {
+				self.ensure_db_id(&ns.name, &db.name).await?;
+				for tb in self.all_tb(&ns.name, &db.name).await?.iter() {
+					self.ensure_tb_id(&ns.name, &db.name, &tb.name).await?;
+				}
+			}
+		}
+		Ok(())
+	}
+
 	/// Retrieve and cache a specific namespace definition.
 	pub async fn get_and_cache_ns(
 		&mut self,
@@ -2154,9 +3241,19 @@ impl Transaction {
 			}) => match strict {
 				false => {
 					let key = crate::key::database::tb::new(ns, db, tb);
+					let (id, interned) = match self.get_db(ns, db).await {
+						Ok(_) => {
+							let (ns_id, db_id) = self.ensure_db_id(ns, db).await?;
+							let id = self.get_next_tb_id(ns_id, db_id).await?;
+							(Some(id), self.can_intern_tb(ns, db, tb).await?)
+						}
+						Err(_) => (None, false),
+					};
 					let val = DefineTableStatement {
+						id,
 						name: tb.to_owned().into(),
 						permissions: Permissions::none(),
+						interned,
 						..Default::default()
 					};
 					self.put(key, &val).await?;
@@ -2198,6 +3295,209 @@ impl Transaction {
 
 	/// Writes the full database contents as binary SQL.
 	pub async fn export(&mut self, ns: &str, db: &str, chn: Sender<Vec<u8>>) -> Result<(), Error> {
+		self.export_with(ns, db, &ExportConfig::default(), chn).await
+	}
+
+	/// Writes the database contents, in the format and for the tables of the export config.
+	pub async fn export_with(
+		&mut self,
+		ns: &str,
+		db: &str,
+		cfg: &ExportConfig,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		// Only export the changes since the specified time
+		if let Some(since) = &cfg.since {
+			return self.export_changes(ns, db, cfg, since, chn).await;
+		}
+		match cfg.format {
+			ExportFormat::Sql => self.export_sql(ns, db, cfg, true, chn).await,
+			ExportFormat::Json => {
+				let tbs = self.all_tb(ns, db).await?;
+				for tb in tbs.iter().filter(|tb| cfg.includes(&tb.name)) {
+					self.export_records(ns, db, &tb.name, ExportFormat::Json, &chn).await?;
+				}
+				Ok(())
+			}
+		}
+	}
+
+	/// Writes a backup of a database, or of a whole namespace, as binary SQL.
+	///
+	/// Everything is read through this transaction, so the backup is consistent
+	/// when the transaction runs at snapshot isolation.
+	pub async fn backup(
+		&mut self,
+		ns: &str,
+		db: Option<&str>,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		// Back up a single database
+		if let Some(db) = db {
+			let info = BackupInfo {
+				since: None,
+				versionstamp: self.backup_versionstamp(ns, db).await?,
+			};
+			for line in info.lines() {
+				chn.send(bytes!(line)).await?;
+			}
+			return self.export_sql(ns, db, &ExportConfig::default(), true, chn).await;
+		}
+		// Output USERS
+		{
+			let nus = self.all_ns_users(ns).await?;
+			if !nus.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- USERS")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for us in nus.iter() {
+					chn.send(bytes!(format!("{us};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
+		// Output TOKENS
+		{
+			let nts = self.all_ns_tokens(ns).await?;
+			if !nts.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- TOKENS")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for nt in nts.iter() {
+					chn.send(bytes!(format!("{nt};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
+		// Output DATABASES
+		let dbs = self.all_db(ns).await?;
+		for db in dbs.iter() {
+			chn.send(bytes!("-- ------------------------------")).await?;
+			chn.send(bytes!(format!("-- DATABASE: {}", db.name))).await?;
+			chn.send(bytes!("-- ------------------------------")).await?;
+			chn.send(bytes!("")).await?;
+			chn.send(bytes!(format!("{db};"))).await?;
+			chn.send(bytes!(format!("USE DB {};", db.name))).await?;
+			chn.send(bytes!("")).await?;
+			self.export_sql(ns, &db.name, &ExportConfig::default(), true, chn.clone()).await?;
+		}
+		// Everything ok
+		Ok(())
+	}
+
+	/// Writes an incremental backup of a database as binary SQL.
+	///
+	/// The backup contains the definitions of the database, and the changes which
+	/// were made to its tables after the backup with the specified versionstamp was
+	/// taken. The changes are read from the change feeds, so every table needs to
+	/// be defined with a `CHANGEFEED` which retains the changes since then.
+	pub async fn backup_since(
+		&mut self,
+		ns: &str,
+		db: &str,
+		since: u64,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		// Check that the changes to every table are kept
+		let mut expiry =
+			self.get_db(ns, db).await?.changefeed.as_ref().map_or(0, |cf| cf.expiry.as_secs());
+		for tb in self.all_tb(ns, db).await?.iter() {
+			match &tb.changefeed {
+				Some(cf) => expiry = expiry.max(cf.expiry.as_secs()),
+				// The records of views are computed from their source tables
+				None if tb.view.is_some() => continue,
+				None => {
+					return Err(Error::BackupChangefeedRequired {
+						value: tb.name.to_raw(),
+					})
+				}
+			}
+		}
+		// Check that the changes have not been removed from the change feeds
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+		if let Some(watermark) = now.checked_sub(expiry) {
+			if let Some(oldest) =
+				self.get_versionstamp_from_timestamp(watermark, ns, db, false).await?
+			{
+				if vs::try_to_u64_be(oldest)? > since {
+					return Err(Error::BackupExpired {
+						since,
+					});
+				}
+			}
+		}
+		// Output the position of the backup
+		let info = BackupInfo {
+			since: Some(since),
+			versionstamp: self.backup_versionstamp(ns, db).await?,
+		};
+		for line in info.lines() {
+			chn.send(bytes!(line)).await?;
+		}
+		// Output the definitions
+		self.export_sql(ns, db, &ExportConfig::default(), false, chn.clone()).await?;
+		// Output the changes made after the previous backup
+		let beg = crate::key::change::prefix_ts(ns, db, vs::u64_to_versionstamp(since + 1));
+		self.export_mutations(ns, db, &ExportConfig::default(), beg, chn).await
+	}
+
+	/// Returns the versionstamp of the latest change which is visible to this transaction.
+	async fn backup_versionstamp(&mut self, ns: &str, db: &str) -> Result<u64, Error> {
+		match self.get(crate::key::database::vs::new(ns, db)).await? {
+			Some(v) => match <[u8; 10]>::try_from(v.as_slice()) {
+				Ok(vs) => vs::try_to_u64_be(vs),
+				Err(_) => Err(Error::Internal("versionstamp is not 10 bytes".to_string())),
+			},
+			None => Ok(0),
+		}
+	}
+
+	/// Writes the database definitions, and the contents of the tables, as binary SQL.
+	///
+	/// The contents of the tables are only written when `records` is set.
+	async fn export_sql(
+		&mut self,
+		ns: &str,
+		db: &str,
+		cfg: &ExportConfig,
+		records: bool,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		// Output the definitions
+		let tbs = self.export_definitions(ns, db, cfg, &chn).await?;
+		// Output TABLE data
+		if records && !tbs.is_empty() {
+			// Start transaction
+			chn.send(export::section("TRANSACTION")).await?;
+			chn.send(bytes!("BEGIN TRANSACTION;")).await?;
+			chn.send(bytes!("")).await?;
+			for tb in tbs.iter() {
+				// Start records
+				chn.send(export::section(&format!("TABLE DATA: {tb}"))).await?;
+				self.export_records(ns, db, tb, ExportFormat::Sql, &chn).await?;
+				chn.send(bytes!("")).await?;
+			}
+			// Commit transaction
+			chn.send(export::section("TRANSACTION")).await?;
+			chn.send(bytes!("COMMIT TRANSACTION;")).await?;
+			chn.send(bytes!("")).await?;
+		}
+		// Everything exported
+		Ok(())
+	}
+
+	/// Writes the database definitions as binary SQL.
+	///
+	/// Returns the names of the tables which are included in the export.
+	pub(crate) async fn export_definitions(
+		&mut self,
+		ns: &str,
+		db: &str,
+		cfg: &ExportConfig,
+		chn: &Sender<Vec<u8>>,
+	) -> Result<Vec<String>, Error> {
 		// Output OPTIONS
 		{
 			chn.send(bytes!("-- ------------------------------")).await?;
@@ -2303,121 +3603,196 @@ impl Transaction {
 			}
 		}
 		// Output TABLES
-		{
-			let tbs = self.all_tb(ns, db).await?;
-			if !tbs.is_empty() {
-				for tb in tbs.iter() {
-					// Output TABLE
-					chn.send(bytes!("-- ------------------------------")).await?;
-					chn.send(bytes!(format!("-- TABLE: {}", tb.name))).await?;
-					chn.send(bytes!("-- ------------------------------")).await?;
-					chn.send(bytes!("")).await?;
-					chn.send(bytes!(format!("{tb};"))).await?;
-					chn.send(bytes!("")).await?;
-					// Output FIELDS
-					let fds = self.all_tb_fields(ns, db, &tb.name).await?;
-					if !fds.is_empty() {
-						for fd in fds.iter() {
-							chn.send(bytes!(format!("{fd};"))).await?;
-						}
-						chn.send(bytes!("")).await?;
-					}
-					// Output INDEXES
-					let ixs = self.all_tb_indexes(ns, db, &tb.name).await?;
-					if !ixs.is_empty() {
-						for ix in ixs.iter() {
-							chn.send(bytes!(format!("{ix};"))).await?;
-						}
-						chn.send(bytes!("")).await?;
-					}
-					// Output EVENTS
-					let evs = self.all_tb_events(ns, db, &tb.name).await?;
-					if !evs.is_empty() {
-						for ev in evs.iter() {
-							chn.send(bytes!(format!("{ev};"))).await?;
-						}
-						chn.send(bytes!("")).await?;
-					}
+		let tbs = self.all_tb(ns, db).await?;
+		let tbs: Vec<_> = tbs.iter().filter(|tb| cfg.includes(&tb.name)).collect();
+		for tb in tbs.iter() {
+			// Output TABLE
+			chn.send(bytes!("-- ------------------------------")).await?;
+			chn.send(bytes!(format!("-- TABLE: {}", tb.name))).await?;
+			chn.send(bytes!("-- ------------------------------")).await?;
+			chn.send(bytes!("")).await?;
+			chn.send(bytes!(format!("{tb};"))).await?;
+			chn.send(bytes!("")).await?;
+			// Output FIELDS
+			let fds = self.all_tb_fields(ns, db, &tb.name).await?;
+			if !fds.is_empty() {
+				for fd in fds.iter() {
+					chn.send(bytes!(format!("{fd};"))).await?;
 				}
-				// Start transaction
-				chn.send(bytes!("-- ------------------------------")).await?;
-				chn.send(bytes!("-- TRANSACTION")).await?;
-				chn.send(bytes!("-- ------------------------------")).await?;
-				chn.send(bytes!("")).await?;
-				chn.send(bytes!("BEGIN TRANSACTION;")).await?;
 				chn.send(bytes!("")).await?;
-				// Output TABLE data
-				for tb in tbs.iter() {
-					// Start records
-					chn.send(bytes!("-- ------------------------------")).await?;
-					chn.send(bytes!(format!("-- TABLE DATA: {}", tb.name))).await?;
-					chn.send(bytes!("-- ------------------------------")).await?;
-					chn.send(bytes!("")).await?;
-					// Fetch records
-					let beg = crate::key::thing::prefix(ns, db, &tb.name);
-					let end = crate::key::thing::suffix(ns, db, &tb.name);
-					let mut nxt: Option<Vec<u8>> = None;
-					loop {
-						let res = match nxt {
-							None => {
-								let min = beg.clone();
-								let max = end.clone();
-								self.scan(min..max, 1000).await?
-							}
-							Some(ref mut beg) => {
-								beg.push(0x00);
-								let min = beg.clone();
-								let max = end.clone();
-								self.scan(min..max, 1000).await?
-							}
-						};
-						if !res.is_empty() {
-							// Get total results
-							let n = res.len();
-							// Exit when settled
-							if n == 0 {
-								break;
-							}
-							// Loop over results
-							for (i, (k, v)) in res.into_iter().enumerate() {
-								// Ready the next
-								if n == i + 1 {
-									nxt = Some(k.clone());
-								}
-								// Parse the key and the value
-								let k: crate::key::thing::Thing = (&k).into();
-								let v: Value = (&v).into();
-								let t = Thing::from((k.tb, k.id));
-								// Check if this is a graph edge
-								match (v.pick(&*EDGE), v.pick(&*IN), v.pick(&*OUT)) {
-									// This is a graph edge record
-									(Value::Bool(true), Value::Thing(l), Value::Thing(r)) => {
-										let sql = format!("RELATE {l} -> {t} -> {r} CONTENT {v};",);
-										chn.send(bytes!(sql)).await?;
-									}
-									// This is a normal record
-									_ => {
-										let sql = format!("UPDATE {t} CONTENT {v};");
-										chn.send(bytes!(sql)).await?;
-									}
-								}
-							}
-							continue;
-						}
-						break;
-					}
-					chn.send(bytes!("")).await?;
+			}
+			// Output INDEXES
+			let ixs = self.all_tb_indexes(ns, db, &tb.name).await?;
+			if !ixs.is_empty() {
+				for ix in ixs.iter() {
+					chn.send(bytes!(format!("{ix};"))).await?;
 				}
-				// Commit transaction
-				chn.send(bytes!("-- ------------------------------")).await?;
-				chn.send(bytes!("-- TRANSACTION")).await?;
-				chn.send(bytes!("-- ------------------------------")).await?;
 				chn.send(bytes!("")).await?;
-				chn.send(bytes!("COMMIT TRANSACTION;")).await?;
+			}
+			// Output EVENTS
+			let evs = self.all_tb_events(ns, db, &tb.name).await?;
+			if !evs.is_empty() {
+				for ev in evs.iter() {
+					chn.send(bytes!(format!("{ev};"))).await?;
+				}
 				chn.send(bytes!("")).await?;
 			}
 		}
-		// Everything exported
+		Ok(tbs.iter().map(|tb| tb.name.to_raw()).collect())
+	}
+
+	/// Writes the records of a table, one statement or JSON object per line.
+	async fn export_records(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		format: ExportFormat,
+		chn: &Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		let mut nxt: Option<Key> = None;
+		loop {
+			let (lines, next) = self.export_batch(ns, db, tb, format, nxt, 1000).await?;
+			for line in lines {
+				chn.send(line).await?;
+			}
+			// Exit when settled
+			match next {
+				Some(k) => nxt = Some(k),
+				None => break,
+			}
+		}
+		Ok(())
+	}
+
+	/// Reads a batch of the records of a table, one statement or JSON object per line,
+	/// starting after the specified key.
+	///
+	/// Returns the key to continue from, or `None` when all records have been read.
+	pub(crate) async fn export_batch(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		format: ExportFormat,
+		after: Option<Key>,
+		limit: u32,
+	) -> Result<(Vec<Vec<u8>>, Option<Key>), Error> {
+		let beg = match after {
+			Some(mut k) => {
+				k.push(0x00);
+				k
+			}
+			None => crate::key::thing::prefix(ns, db, tb),
+		};
+		let end = crate::key::thing::suffix(ns, db, tb);
+		let res = self.scan(beg..end, limit).await?;
+		// Continue after the last key if the batch is full
+		let next = match res.len() < limit as usize {
+			true => None,
+			false => res.last().map(|(k, _)| k.clone()),
+		};
+		let lines = res
+			.into_iter()
+			.map(|(k, v)| {
+				// Parse the key and the value
+				let k: crate::key::thing::Thing = (&k).into();
+				let v: Value = (&v).into();
+				let t = Thing::from((k.tb, k.id));
+				match format {
+					ExportFormat::Sql => bytes!(export::record_sql(&t, &v)),
+					ExportFormat::Json => bytes!(v.into_json().to_string()),
+				}
+			})
+			.collect();
+		Ok((lines, next))
+	}
+
+	/// Writes the changes which were made to the tables since the specified time.
+	///
+	/// The changes are read from the change feeds, so only the tables which
+	/// are defined with a `CHANGEFEED` are included.
+	async fn export_changes(
+		&mut self,
+		ns: &str,
+		db: &str,
+		cfg: &ExportConfig,
+		since: &sql::Datetime,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		let ts = since.0.timestamp() as u64;
+		let Some(vs) = self.get_versionstamp_from_timestamp(ts, ns, db, false).await? else {
+			return Err(Error::Internal(
+				"no versionstamp associated to this timestamp exists yet".to_string(),
+			));
+		};
+		let beg = crate::key::change::prefix_ts(ns, db, vs);
+		self.export_mutations(ns, db, cfg, beg, chn).await
+	}
+
+	/// Writes the changes which were made to the tables, starting from the specified change key.
+	async fn export_mutations(
+		&mut self,
+		ns: &str,
+		db: &str,
+		cfg: &ExportConfig,
+		beg: Key,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		if cfg.format == ExportFormat::Sql {
+			chn.send(bytes!("OPTION IMPORT;")).await?;
+			chn.send(bytes!("BEGIN TRANSACTION;")).await?;
+		}
+		let end = crate::key::change::suffix(ns, db);
+		let mut nxt: Option<Vec<u8>> = None;
+		loop {
+			let min = match nxt {
+				None => beg.clone(),
+				Some(ref mut beg) => {
+					beg.push(0x00);
+					beg.clone()
+				}
+			};
+			let res = self.scan(min..end.clone(), 1000).await?;
+			// Exit when settled
+			if res.is_empty() {
+				break;
+			}
+			// Get total results
+			let n = res.len();
+			// Loop over results
+			for (i, (k, v)) in res.into_iter().enumerate() {
+				// Ready the next
+				if n == i + 1 {
+					nxt = Some(k.clone());
+				}
+				// Skip the tables which were not selected
+				let cf = crate::key::change::Cf::decode(&k)?;
+				if !cfg.includes(cf.tb) {
+					continue;
+				}
+				let TableMutations(_, mutations) = v.into();
+				for mutation in mutations {
+					let line = match (cfg.format, mutation) {
+						(
+							ExportFormat::Sql,
+							TableMutation::Set(t, v) | TableMutation::SetWithOriginal(t, v, _),
+						) => export::record_sql(&t, &v),
+						(
+							ExportFormat::Sql,
+							TableMutation::Del(t) | TableMutation::DelWithOriginal(t, _),
+						) => format!("DELETE {t};"),
+						(ExportFormat::Json, mutation) => {
+							mutation.into_value().into_json().to_string()
+						}
+					};
+					chn.send(bytes!(line)).await?;
+				}
+			}
+		}
+		if cfg.format == ExportFormat::Sql {
+			chn.send(bytes!("COMMIT TRANSACTION;")).await?;
+		}
 		Ok(())
 	}
 
@@ -2425,7 +3800,7 @@ impl Transaction {
 	// To actually persist the record changes into the underlying kvs,
 	// you must call the `complete_changes` function and then commit the transaction.
 	pub(crate) fn clear_cache(&mut self) {
-		self.cache.clear()
+		self.cache.clear_definitions()
 	}
 
 	// change will record the change in the changefeed if enabled.
@@ -2439,7 +3814,22 @@ impl Transaction {
 		id: &Thing,
 		v: Cow<'_, Value>,
 	) {
-		self.cf.update(ns, db, tb, id.clone(), v)
+		self.cf.update(ns, db, tb, id.clone(), v, None)
+	}
+
+	// record_change_with_original records the change in the changefeed along
+	// with the value which the record had before the change, so that the
+	// state of the table at an earlier version can be reconstructed.
+	pub(crate) fn record_change_with_original(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		id: &Thing,
+		v: Cow<'_, Value>,
+		original: Cow<'_, Value>,
+	) {
+		self.cf.update(ns, db, tb, id.clone(), v, Some(original))
 	}
 
 	pub(crate) async fn get_idg(&mut self, key: Key) -> Result<U32, Error> {
@@ -2479,7 +3869,7 @@ impl Transaction {
 			}
 		};
 
-		let id = seq.get_next_id();
+		let id = seq.get_next_fresh_id();
 
 		self.cache.set(key.clone(), Entry::Seq(seq));
 
@@ -2508,7 +3898,7 @@ impl Transaction {
 		let key = crate::key::database::ti::new(ns, db).encode().unwrap();
 		let mut seq = self.get_idg(key.clone()).await?;
 
-		let id = seq.get_next_id();
+		let id = seq.get_next_fresh_id();
 
 		self.cache.set(key.clone(), Entry::Seq(seq));
 
@@ -2550,7 +3940,7 @@ impl Transaction {
 			}
 		};
 
-		let id = seq.get_next_id();
+		let id = seq.get_next_fresh_id();
 
 		self.cache.set(key.clone(), Entry::Seq(seq));
 
@@ -2614,6 +4004,8 @@ impl Transaction {
 
 	// set_timestamp_for_versionstamp correlates the given timestamp with the current versionstamp.
 	// This allows get_versionstamp_from_timestamp to obtain the versionstamp from the timestamp later.
+	// The timestamp in seconds is saved as a hybrid logical clock timestamp, which is ordered after
+	// the timestamps saved before it, even when they were saved by a node whose clock is ahead.
 	pub(crate) async fn set_timestamp_for_versionstamp(
 		&mut self,
 		ts: u64,
@@ -2625,23 +4017,19 @@ impl Transaction {
 		// on other concurrent transactions that can write to the ts_key or the keys after it.
 		let vs = self.get_timestamp(crate::key::database::vs::new(ns, db), lock).await?;
 
-		// Ensure there are no keys after the ts_key
-		// Otherwise we can go back in time!
-		let ts_key = crate::key::database::ts::new(ns, db, ts);
-		let begin = ts_key.encode()?;
+		// Observe the latest timestamp saved after this time, for instance by a node
+		// whose clock is ahead, so that this timestamp is ordered after it
+		let physical = ts.saturating_mul(1000);
+		let begin = crate::key::database::ts::new(ns, db, hlc::pack(physical, 0)).encode()?;
 		let end = crate::key::database::ts::suffix(ns, db);
 		let ts_pairs: Vec<(Vec<u8>, Vec<u8>)> = self.getr(begin..end, u32::MAX).await?;
-		let latest_ts_pair = ts_pairs.last();
-		if let Some((k, _)) = latest_ts_pair {
-			let k = crate::key::database::ts::Ts::decode(k)?;
-			let latest_ts = k.ts;
-			if latest_ts >= ts {
-				return Err(Error::Internal(
-					"ts is less than or equal to the latest ts".to_string(),
-				));
-			}
+		let mut vso = self.vso.lock().await;
+		if let Some((k, _)) = ts_pairs.last() {
+			vso.observe(crate::key::database::ts::Ts::decode(k)?.ts);
 		}
-		self.set(ts_key, vs).await?;
+		let ts = vso.timestamp_at(physical);
+		drop(vso);
+		self.set(crate::key::database::ts::new(ns, db, ts), vs).await?;
 		Ok(())
 	}
 
@@ -2653,7 +4041,8 @@ impl Transaction {
 		_lock: bool,
 	) -> Result<Option<Versionstamp>, Error> {
 		let start = crate::key::database::ts::prefix(ns, db);
-		let ts_key = crate::key::database::ts::new(ns, db, ts + 1);
+		let physical = ts.saturating_add(1).saturating_mul(1000);
+		let ts_key = crate::key::database::ts::new(ns, db, hlc::pack(physical, 0));
 		let end = ts_key.encode()?;
 		let ts_pairs = self.getr(start..end, u32::MAX).await?;
 		let latest_ts_pair = ts_pairs.last();
@@ -2692,6 +4081,16 @@ impl Transaction {
 				inner: Inner::SpeeDB(ref mut v),
 				..
 			} => v.check_level(check),
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::ReDB(ref mut v),
+				..
+			} => v.check_level(check),
+			#[cfg(feature = "kv-object")]
+			Transaction {
+				inner: Inner::Object(ref mut v),
+				..
+			} => v.check_level(check),
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(ref mut v),
@@ -2718,7 +4117,10 @@ impl Transaction {
 mod tests {
 	use crate::{
 		kvs::Datastore,
-		sql::{statements::DefineUserStatement, Base},
+		sql::{
+			statements::{DefineTableStatement, DefineUserStatement},
+			Base,
+		},
 	};
 
 	#[tokio::test]
@@ -2925,4 +4327,35 @@ mod tests {
 		txn.complete_changes(false).await.unwrap();
 		txn.commit().await.unwrap();
 	}
+
+	#[tokio::test]
+	async fn test_ensure_ids() {
+		let ds = Datastore::new("memory").await.unwrap();
+
+		// Implicitly created definitions are assigned ids
+		let mut txn = ds.transaction(true, false).await.unwrap();
+		assert_eq!(txn.add_ns("ns", false).await.unwrap().id, Some(0));
+		assert_eq!(txn.add_db("ns", "db", false).await.unwrap().id, Some(0));
+		assert_eq!(txn.add_tb("ns", "db", "tb1", false).await.unwrap().id, Some(0));
+		txn.complete_changes(false).await.unwrap();
+		txn.commit().await.unwrap();
+
+		// Definitions stored without an id are assigned one
+		let mut txn = ds.transaction(true, false).await.unwrap();
+		let data = DefineTableStatement {
+			name: "tb2".into(),
+			..Default::default()
+		};
+		let key = crate::key::database::tb::new("ns", "db", "tb2");
+		txn.set(key, data).await.unwrap();
+		txn.clear_cache();
+		txn.ensure_all_ids().await.unwrap();
+		assert_eq!(txn.get_tb("ns", "db", "tb1").await.unwrap().id, Some(0));
+		assert_eq!(txn.get_tb("ns", "db", "tb2").await.unwrap().id, Some(1));
+		// Pending sequences are kept when the cache is cleared
+		txn.clear_cache();
+		assert_eq!(txn.get_next_tb_id(0, 0).await.unwrap(), 2);
+		txn.complete_changes(false).await.unwrap();
+		txn.commit().await.unwrap();
+	}
 }
@@ -0,0 +1,109 @@
+use super::chunk;
+use crate::err::Error;
+use crate::kvs::{Key, Transaction, Val};
+use crate::sql::paths::{IN, OUT};
+use crate::sql::{Thing, Value};
+use std::fmt;
+
+/// The keys which were removed by a vacuum of a datastore
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Vacuum {
+	/// The number of index entries which pointed to missing records, or which
+	/// were left behind by an index which has since been removed
+	pub index_entries: u64,
+	/// The number of live query registrations of nodes which are no longer running
+	pub live_queries: u64,
+	/// The number of graph edge pointers to relations which no longer exist
+	pub graph_edges: u64,
+	/// The number of chunks of large values which no longer belong to a record
+	pub value_chunks: u64,
+}
+
+impl fmt::Display for Vacuum {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"removed {} orphaned index entries, {} dead live queries, {} dangling graph edges, and {} orphaned value chunks",
+			self.index_entries, self.live_queries, self.graph_edges, self.value_chunks
+		)
+	}
+}
+
+/// The keys of a range which a vacuum removes
+pub(super) enum Sweep<'a> {
+	/// The entries of a unique or non-unique index which point to missing records
+	Index {
+		ns: &'a str,
+		db: &'a str,
+	},
+	/// The graph edge pointers which do not belong to an existing relation
+	Edges {
+		ns: &'a str,
+		db: &'a str,
+	},
+	/// The chunks of large values which no longer belong to a record
+	Chunks,
+	/// Every key, such as the data of an index which has been removed
+	All,
+}
+
+impl<'a> Sweep<'a> {
+	/// Remove the orphaned keys of a batch, returning how many were removed
+	pub(super) async fn sweep(
+		&self,
+		tx: &mut Transaction,
+		batch: Vec<(Key, Val)>,
+	) -> Result<u64, Error> {
+		let mut count = 0;
+		for (k, v) in batch {
+			let orphaned = match self {
+				Self::Index {
+					ns,
+					db,
+				} => {
+					let rid: Thing = v.into();
+					!tx.exi(crate::key::thing::new(ns, db, &rid.tb, &rid.id)).await?
+				}
+				Self::Edges {
+					ns,
+					db,
+				} => {
+					let edge = crate::key::graph::Graph::decode(&k)?;
+					let this = Thing::from((edge.tb, edge.id));
+					let that = Thing::from((edge.ft, edge.fk));
+					// Either side of the pointer has to be the relation between them
+					!related(tx, ns, db, &this, &that).await?
+						&& !related(tx, ns, db, &that, &this).await?
+				}
+				Self::Chunks => match chunk::record_of(&k) {
+					Some((rid, nr)) => tx.get_chunk_count(rid).await?.map_or(true, |n| nr >= n),
+					None => false,
+				},
+				Self::All => true,
+			};
+			if orphaned {
+				tx.del(k).await?;
+				count += 1;
+			}
+		}
+		Ok(count)
+	}
+}
+
+/// Check whether a record exists, and is a relation to or from another record
+async fn related(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	rel: &Thing,
+	rid: &Thing,
+) -> Result<bool, Error> {
+	match tx.get(crate::key::thing::new(ns, db, &rel.tb, &rel.id)).await? {
+		Some(v) => {
+			let v: Value = v.into();
+			let rid = Value::from(rid.clone());
+			Ok(v.pick(&*IN) == rid || v.pick(&*OUT) == rid)
+		}
+		None => Ok(false),
+	}
+}
@@ -12,7 +12,7 @@ use nom::character::complete::char;
 use nom::sequence::terminated;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Display, Formatter, Write};
 use std::ops;
 use std::ops::Deref;
@@ -274,13 +274,10 @@ pub(crate) trait Complement<T> {
 
 impl Complement<Array> for Array {
 	fn complement(self, other: Self) -> Array {
-		let mut out = Array::new();
-		for v in self.into_iter() {
-			if !other.contains(&v) {
-				out.push(v)
-			}
-		}
-		out
+		let set: HashSet<&Value> = other.iter().collect();
+		let keep: Vec<bool> = self.iter().map(|v| !set.contains(v)).collect();
+		drop(set);
+		self.retain_by(keep)
 	}
 }
 
@@ -300,20 +297,50 @@ impl Concat<Array> for Array {
 // ------------------------------
 
 pub(crate) trait Difference<T> {
+	/// Returns the symmetric difference of two arrays, treating both
+	/// arrays as multisets. Each value in `self` cancels out at most one
+	/// matching value in `other`. The unmatched values of `self` are
+	/// returned in their original order, followed by the unmatched values
+	/// of `other` in their original order.
 	fn difference(self, other: T) -> T;
 }
 
 impl Difference<Array> for Array {
-	fn difference(self, mut other: Array) -> Array {
-		let mut out = Array::new();
-		for v in self.into_iter() {
-			if let Some(pos) = other.iter().position(|w| v == *w) {
-				other.remove(pos);
-			} else {
-				out.push(v);
+	fn difference(self, other: Array) -> Array {
+		// Count the occurences of each value in the other array
+		let mut counts = other.counts();
+		// Keep any values which are not matched in the other array
+		let keep: Vec<bool> = self
+			.iter()
+			.map(|v| match counts.get_mut(v) {
+				Some(n) if *n > 0 => {
+					*n -= 1;
+					false
+				}
+				_ => true,
+			})
+			.collect();
+		// Count how many of each value were matched
+		let mut matched = other.counts();
+		for (v, n) in counts {
+			if let Some(m) = matched.get_mut(v) {
+				*m -= n;
 			}
 		}
-		out.append(&mut other);
+		// Skip the first matched occurences in the other array
+		let others: Vec<bool> = other
+			.iter()
+			.map(|v| match matched.get_mut(v) {
+				Some(n) if *n > 0 => {
+					*n -= 1;
+					false
+				}
+				_ => true,
+			})
+			.collect();
+		drop(matched);
+		let mut out = self.retain_by(keep);
+		out.append(&mut other.retain_by(others));
 		out
 	}
 }
@@ -340,19 +367,30 @@ impl Flatten<Array> for Array {
 // ------------------------------
 
 pub(crate) trait Intersect<T> {
+	/// Returns the values of `self` which also appear in `other`, treating
+	/// both arrays as multisets. Each value in `other` matches at most one
+	/// value in `self`, and the values are returned in the order in which
+	/// they appear in `self`.
 	fn intersect(self, other: T) -> T;
 }
 
 impl Intersect<Self> for Array {
-	fn intersect(self, mut other: Self) -> Self {
-		let mut out = Self::new();
-		for v in self.0.into_iter() {
-			if let Some(pos) = other.iter().position(|w| v == *w) {
-				other.remove(pos);
-				out.push(v);
-			}
-		}
-		out
+	fn intersect(self, other: Self) -> Self {
+		// Count the occurences of each value in the other array
+		let mut counts = other.counts();
+		// Keep any values which are matched in the other array
+		let keep: Vec<bool> = self
+			.iter()
+			.map(|v| match counts.get_mut(v) {
+				Some(n) if *n > 0 => {
+					*n -= 1;
+					true
+				}
+				_ => false,
+			})
+			.collect();
+		drop(counts);
+		self.retain_by(keep)
 	}
 }
 
@@ -446,6 +484,8 @@ impl Transpose<Array> for Array {
 // ------------------------------
 
 pub(crate) trait Union<T> {
+	/// Returns the distinct values which appear in either array, in the
+	/// order in which they are first seen in `self` and then in `other`.
 	fn union(self, other: T) -> T;
 }
 
@@ -463,23 +503,36 @@ pub(crate) trait Uniq<T> {
 }
 
 impl Uniq<Array> for Array {
-	fn uniq(mut self) -> Array {
-		let mut set: HashSet<&Value> = HashSet::new();
-		let mut to_remove: Vec<usize> = Vec::new();
-		for (i, item) in self.iter().enumerate() {
-			if !set.insert(item) {
-				to_remove.push(i);
-			}
-		}
-		for i in to_remove.iter().rev() {
-			self.remove(*i);
-		}
-		self
+	fn uniq(self) -> Array {
+		let mut set: HashSet<&Value> = HashSet::with_capacity(self.len());
+		let keep: Vec<bool> = self.iter().map(|v| set.insert(v)).collect();
+		drop(set);
+		self.retain_by(keep)
 	}
 }
 
 // ------------------------------
 
+impl Array {
+	/// Counts the number of occurences of each value in this array
+	pub(crate) fn counts(&self) -> HashMap<&Value, usize> {
+		let mut out = HashMap::with_capacity(self.len());
+		for v in self.iter() {
+			*out.entry(v).or_insert(0) += 1;
+		}
+		out
+	}
+	/// Keeps only the values whose corresponding flag is set
+	fn retain_by(self, keep: Vec<bool>) -> Array {
+		self.0.into_iter().zip(keep).filter_map(|(v, k)| k.then_some(v)).collect()
+	}
+	/// Checks whether this array contains any values which are compared
+	/// using loose equality, and so can not be matched using a hash lookup
+	pub(crate) fn has_loose_values(&self) -> bool {
+		self.iter().any(|v| matches!(v, Value::Regex(_)))
+	}
+}
+
 pub fn array(i: &str) -> IResult<&str, Array> {
 	let (i, v) =
 		delimited_list0(openbracket, commas, terminated(value, mightbespace), char(']'))(i)?;
@@ -565,4 +618,18 @@ mod tests {
 		assert_eq!("[1, 2, 3, 4]", format!("{}", out));
 		assert_eq!(out.0.len(), 4);
 	}
+
+	#[test]
+	fn array_fnc_set_operations() {
+		fn test(res: Array, expected_result: &str) {
+			assert_eq!(expected_result, format!("{}", res));
+		}
+		fn arr(sql: &str) -> Array {
+			array(sql).unwrap().1
+		}
+		test(arr("[1, 2, 2, 3]").union(arr("[3, 4, 1.0, 5]")), "[1, 2, 3, 4, 5]");
+		test(arr("[1, 2, 2, 3, 2]").intersect(arr("[2, 3, 2, 6]")), "[2, 2, 3]");
+		test(arr("[1, 2, 2, 3]").difference(arr("[2, 3, 3, 4]")), "[1, 2, 3, 4]");
+		test(arr("[1, 2, 2, 3]").complement(arr("[2, 4]")), "[1, 3]");
+	}
 }
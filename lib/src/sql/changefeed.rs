@@ -3,6 +3,8 @@ use crate::sql::duration::{duration, Duration};
 use crate::sql::error::IResult;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
+use nom::combinator::opt;
+use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
@@ -10,14 +12,20 @@ use std::str;
 use std::time;
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct ChangeFeed {
 	pub expiry: time::Duration,
+	/// Whether the changes include the values of the records before they changed
+	#[revision(start = 2)]
+	pub store_original: bool,
 }
 
 impl Display for ChangeFeed {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		write!(f, "CHANGEFEED {}", Duration(self.expiry))?;
+		if self.store_original {
+			f.write_str(" INCLUDE ORIGINAL")?;
+		}
 		Ok(())
 	}
 }
@@ -26,10 +34,17 @@ pub fn changefeed(i: &str) -> IResult<&str, ChangeFeed> {
 	let (i, _) = tag_no_case("CHANGEFEED")(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, v) = cut(duration)(i)?;
+	let (i, o) = opt(tuple((
+		shouldbespace,
+		tag_no_case("INCLUDE"),
+		shouldbespace,
+		cut(tag_no_case("ORIGINAL")),
+	)))(i)?;
 	Ok((
 		i,
 		ChangeFeed {
 			expiry: v.0,
+			store_original: o.is_some(),
 		},
 	))
 }
@@ -38,6 +53,7 @@ impl Default for ChangeFeed {
 	fn default() -> Self {
 		Self {
 			expiry: time::Duration::from_secs(0),
+			store_original: false,
 		}
 	}
 }
@@ -62,7 +78,23 @@ mod tests {
 		assert_eq!(
 			out,
 			ChangeFeed {
-				expiry: time::Duration::from_secs(3600)
+				expiry: time::Duration::from_secs(3600),
+				store_original: false,
+			}
+		);
+	}
+
+	#[test]
+	fn changefeed_include_original() {
+		let sql = "CHANGEFEED 1d INCLUDE ORIGINAL";
+		let res = changefeed(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(
+			out,
+			ChangeFeed {
+				expiry: time::Duration::from_secs(86400),
+				store_original: true,
 			}
 		);
 	}
@@ -32,6 +32,13 @@ pub fn cond(i: &str) -> IResult<&str, Cond> {
 	Ok((i, Cond(v)))
 }
 
+pub fn having(i: &str) -> IResult<&str, Cond> {
+	let (i, _) = tag_no_case("HAVING")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(value)(i)?;
+	Ok((i, Cond(v)))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -52,4 +59,12 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("WHERE field = true AND other.field = false", format!("{}", out));
 	}
+
+	#[test]
+	fn having_statement() {
+		let sql = "HAVING count > 10";
+		let res = having(sql);
+		let out = res.unwrap().1;
+		assert_eq!("count > 10", format!("{}", out.0));
+	}
 }
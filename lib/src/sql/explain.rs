@@ -8,13 +8,22 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
-pub struct Explain(pub bool);
+#[revisioned(revision = 2)]
+pub struct Explain {
+	/// Whether the query should also be executed
+	pub full: bool,
+	/// Whether to output the structured query plan
+	#[revision(start = 2)]
+	pub plan: bool,
+}
 
 impl fmt::Display for Explain {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		f.write_str("EXPLAIN")?;
-		if self.0 {
+		if self.plan {
+			f.write_str(" PLAN")?;
+		}
+		if self.full {
 			f.write_str(" FULL")?;
 		}
 		Ok(())
@@ -23,8 +32,15 @@ impl fmt::Display for Explain {
 
 pub fn explain(i: &str) -> IResult<&str, Explain> {
 	let (i, _) = tag_no_case("EXPLAIN")(i)?;
+	let (i, plan) = opt(tuple((shouldbespace, tag_no_case("PLAN"))))(i)?;
 	let (i, full) = opt(tuple((shouldbespace, tag_no_case("FULL"))))(i)?;
-	Ok((i, Explain(full.is_some())))
+	Ok((
+		i,
+		Explain {
+			full: full.is_some(),
+			plan: plan.is_some(),
+		},
+	))
 }
 
 #[cfg(test)]
@@ -37,7 +53,7 @@ mod tests {
 		let sql = "EXPLAIN";
 		let res = explain(sql);
 		let out = res.unwrap().1;
-		assert_eq!(out, Explain(false));
+		assert_eq!(out, Explain::default());
 		assert_eq!("EXPLAIN", format!("{}", out));
 	}
 
@@ -46,7 +62,28 @@ mod tests {
 		let sql = "EXPLAIN FULL";
 		let res = explain(sql);
 		let out = res.unwrap().1;
-		assert_eq!(out, Explain(true));
+		assert!(out.full);
+		assert!(!out.plan);
 		assert_eq!("EXPLAIN FULL", format!("{}", out));
 	}
+
+	#[test]
+	fn explain_plan_statement() {
+		let sql = "EXPLAIN PLAN";
+		let res = explain(sql);
+		let out = res.unwrap().1;
+		assert!(!out.full);
+		assert!(out.plan);
+		assert_eq!("EXPLAIN PLAN", format!("{}", out));
+	}
+
+	#[test]
+	fn explain_plan_full_statement() {
+		let sql = "EXPLAIN PLAN FULL";
+		let res = explain(sql);
+		let out = res.unwrap().1;
+		assert!(out.full);
+		assert!(out.plan);
+		assert_eq!("EXPLAIN PLAN FULL", format!("{}", out));
+	}
 }
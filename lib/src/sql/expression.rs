@@ -7,6 +7,8 @@ use crate::sql::comment::mightbespace;
 use crate::sql::error::IResult;
 use crate::sql::operator::{self, Operator};
 use crate::sql::value::{single, value, Value};
+use nom::character::complete::char;
+use nom::character::complete::u8 as uint8;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -190,7 +192,10 @@ impl Expression {
 			Operator::NoneInside => fnc::operate::inside_none(&l, &r),
 			Operator::Outside => fnc::operate::outside(&l, &r),
 			Operator::Intersects => fnc::operate::intersects(&l, &r),
-			Operator::Matches(_) => fnc::operate::matches(ctx, txn, doc, self).await,
+			Operator::Matches(..) | Operator::Fuzzy(..) => {
+				fnc::operate::matches(ctx, txn, doc, self).await
+			}
+			Operator::Knn(_) => fnc::operate::knn(ctx, doc, self),
 			_ => unreachable!(),
 		}
 	}
@@ -203,6 +208,11 @@ impl fmt::Display for Expression {
 				o,
 				v,
 			} => write!(f, "{o}{v}"),
+			Self::Binary {
+				l,
+				o: o @ Operator::Fuzzy(_, d),
+				r,
+			} => write!(f, "{l} {o} {r}~{d}"),
 			Self::Binary {
 				l,
 				o,
@@ -230,6 +240,22 @@ pub fn binary(i: &str) -> IResult<&str, Expression> {
 	let (i, o) = operator::binary(i)?;
 	// Make sure to dive if the query is a right-deep binary tree.
 	let _diving = crate::sql::parser::depth::dive()?;
+	// A full-text query may be followed by the edit distance of its terms
+	if let Operator::Matches(mr) = o {
+		if let Ok((i, (q, d))) = fuzzy(i) {
+			let e = Expression::new(l, Operator::Fuzzy(mr, d), q);
+			// The fuzzy query binds tighter than any operator which follows it
+			let Ok((i, o)) = operator::binary(i) else {
+				return Ok((i, e));
+			};
+			let (i, r) = value(i)?;
+			let v = match r {
+				Value::Expression(r) => r.augment(e.into(), o),
+				_ => Expression::new(e.into(), o, r),
+			};
+			return Ok((i, v));
+		}
+	}
 	let (i, r) = value(i)?;
 	let v = match r {
 		Value::Expression(r) => r.augment(l, o),
@@ -238,6 +264,13 @@ pub fn binary(i: &str) -> IResult<&str, Expression> {
 	Ok((i, v))
 }
 
+fn fuzzy(i: &str) -> IResult<&str, (Value, u8)> {
+	let (i, q) = single(i)?;
+	let (i, _) = char('~')(i)?;
+	let (i, d) = uint8(i)?;
+	Ok((i, (q, d)))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -323,4 +356,28 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!(sql, format!("{}", out));
 	}
+
+	#[test]
+	fn expression_with_fuzzy_matches() {
+		let sql = "title @1@ 'surrealdb'~2 AND id > 0";
+		let res = binary(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		let Expression::Binary {
+			l: Value::Expression(l),
+			o: Operator::And,
+			..
+		} = out
+		else {
+			panic!("{out:?}");
+		};
+		assert!(matches!(
+			*l,
+			Expression::Binary {
+				o: Operator::Fuzzy(Some(1), 2),
+				..
+			}
+		));
+	}
 }
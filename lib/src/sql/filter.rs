@@ -1,10 +1,13 @@
 use crate::sql::common::{closeparentheses, commas, openparentheses};
 use crate::sql::error::IResult;
+use crate::sql::escape::quote_str;
 use crate::sql::language::{language, Language};
+use crate::sql::strand::{strand, Strand};
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
 use nom::character::complete::u16;
 use nom::combinator::cut;
+use nom::combinator::verify;
 use nom::multi::separated_list1;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -20,6 +23,12 @@ pub enum Filter {
 	Ngram(u16, u16),
 	Snowball(Language),
 	Uppercase,
+	/// Removes the terms of the list, whatever their case
+	Stopwords(Vec<String>),
+	/// Stems each term in every language, keeping each distinct stem
+	Stemmers(Vec<Language>),
+	/// Rules such as `us=>usa, car=>automobile`, expanding the terms of a query
+	Synonyms(String),
 }
 
 impl Display for Filter {
@@ -31,10 +40,34 @@ impl Display for Filter {
 			Self::Ngram(min, max) => write!(f, "NGRAM({},{})", min, max),
 			Self::Snowball(lang) => write!(f, "SNOWBALL({})", lang),
 			Self::Uppercase => f.write_str("UPPERCASE"),
+			Self::Stopwords(words) => {
+				let words: Vec<String> = words.iter().map(|w| quote_str(w)).collect();
+				write!(f, "STOPWORDS({})", words.join(","))
+			}
+			Self::Stemmers(langs) => {
+				let langs: Vec<String> = langs.iter().map(|l| l.to_string()).collect();
+				write!(f, "SNOWBALL({})", langs.join(","))
+			}
+			Self::Synonyms(rules) => write!(f, "SYNONYMS({})", quote_str(rules)),
 		}
 	}
 }
 
+/// Split synonym rules into the terms and their synonyms.
+/// Returns `None` if any of the rules is not of the form `term=>synonym`.
+pub(crate) fn synonym_rules(rules: &str) -> Option<Vec<(&str, &str)>> {
+	let mut res = vec![];
+	for rule in rules.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+		let (term, synonym) = rule.split_once("=>")?;
+		let (term, synonym) = (term.trim(), synonym.trim());
+		if term.is_empty() || synonym.is_empty() {
+			return None;
+		}
+		res.push((term, synonym));
+	}
+	Some(res)
+}
+
 fn ascii(i: &str) -> IResult<&str, Filter> {
 	let (i, _) = tag_no_case("ASCII")(i)?;
 	Ok((i, Filter::Ascii))
@@ -73,9 +106,34 @@ fn snowball(i: &str) -> IResult<&str, Filter> {
 	let (i, _) = tag_no_case("SNOWBALL")(i)?;
 	cut(|i| {
 		let (i, _) = openparentheses(i)?;
-		let (i, language) = language(i)?;
+		let (i, mut languages) = separated_list1(commas, language)(i)?;
+		let (i, _) = closeparentheses(i)?;
+		// Several languages are stemmed together
+		let filter = match languages.len() {
+			1 => Filter::Snowball(languages.remove(0)),
+			_ => Filter::Stemmers(languages),
+		};
+		Ok((i, filter))
+	})(i)
+}
+
+fn stopwords(i: &str) -> IResult<&str, Filter> {
+	let (i, _) = tag_no_case("STOPWORDS")(i)?;
+	cut(|i| {
+		let (i, _) = openparentheses(i)?;
+		let (i, words) = separated_list1(commas, strand)(i)?;
+		let (i, _) = closeparentheses(i)?;
+		Ok((i, Filter::Stopwords(words.into_iter().map(|w| w.0).collect())))
+	})(i)
+}
+
+fn synonyms(i: &str) -> IResult<&str, Filter> {
+	let (i, _) = tag_no_case("SYNONYMS")(i)?;
+	cut(|i| {
+		let (i, _) = openparentheses(i)?;
+		let (i, rules) = verify(strand, |r: &Strand| synonym_rules(&r.0).is_some())(i)?;
 		let (i, _) = closeparentheses(i)?;
-		Ok((i, Filter::Snowball(language)))
+		Ok((i, Filter::Synonyms(rules.0)))
 	})(i)
 }
 
@@ -85,7 +143,7 @@ fn uppercase(i: &str) -> IResult<&str, Filter> {
 }
 
 fn filter(i: &str) -> IResult<&str, Filter> {
-	alt((ascii, edgengram, lowercase, ngram, snowball, uppercase))(i)
+	alt((ascii, edgengram, lowercase, ngram, snowball, stopwords, synonyms, uppercase))(i)
 }
 
 pub(super) fn filters(i: &str) -> IResult<&str, Vec<Filter>> {
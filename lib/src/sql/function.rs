@@ -100,10 +100,21 @@ impl Function {
 		matches!(self, Self::Script(_, _))
 	}
 
+	/// Check if this function modifies the datastore
+	pub fn is_writeable(&self) -> bool {
+		matches!(self, Self::Normal(f, _) if f.starts_with("sequence::"))
+	}
+
+	/// Check if this function indicates whether a group field is rolled up
+	pub fn is_grouping(&self) -> bool {
+		matches!(self, Self::Normal(f, _) if f == "grouping")
+	}
+
 	/// Check if this function is a rolling function
 	pub fn is_rolling(&self) -> bool {
 		match self {
 			Self::Normal(f, _) if f == "count" => true,
+			Self::Normal(f, _) if f == "count::distinct_approx" => true,
 			Self::Normal(f, _) if f == "math::max" => true,
 			Self::Normal(f, _) if f == "math::mean" => true,
 			Self::Normal(f, _) if f == "math::min" => true,
@@ -121,6 +132,7 @@ impl Function {
 			Self::Normal(f, _) if f == "array::group" => true,
 			Self::Normal(f, _) if f == "array::last" => true,
 			Self::Normal(f, _) if f == "count" => true,
+			Self::Normal(f, _) if f == "count::distinct_approx" => true,
 			Self::Normal(f, _) if f == "math::bottom" => true,
 			Self::Normal(f, _) if f == "math::interquartile" => true,
 			Self::Normal(f, _) if f == "math::max" => true,
@@ -158,6 +170,8 @@ impl Function {
 	) -> Result<Value, Error> {
 		// Prevent long function chains
 		let opt = &opt.dive(1)?;
+		// Check if the context is finished
+		ctx.check()?;
 		// Ensure futures are run
 		let opt = &opt.new_with_futures(true);
 		// Process the function type
@@ -306,6 +320,7 @@ pub(crate) fn function_names(i: &str) -> IResult<&str, &str> {
 		alt((
 			preceded(tag("array::"), cut(function_array)),
 			preceded(tag("bytes::"), cut(function_bytes)),
+			preceded(tag("count::"), cut(function_count)),
 			preceded(tag("crypto::"), cut(function_crypto)),
 			preceded(tag("duration::"), cut(function_duration)),
 			preceded(tag("encoding::"), cut(function_encoding)),
@@ -316,8 +331,10 @@ pub(crate) fn function_names(i: &str) -> IResult<&str, &str> {
 			preceded(tag("math::"), function_math),
 			preceded(tag("meta::"), cut(function_meta)),
 			preceded(tag("parse::"), cut(function_parse)),
+			preceded(tag("path::"), cut(function_path)),
 			preceded(tag("rand::"), cut(function_rand)),
 			preceded(tag("search::"), cut(function_search)),
+			preceded(tag("sequence::"), cut(function_sequence)),
 			preceded(tag("session::"), cut(function_session)),
 			preceded(tag("string::"), cut(function_string)),
 			// Don't cut in time and math for now since there are also constant's with the same
@@ -326,7 +343,7 @@ pub(crate) fn function_names(i: &str) -> IResult<&str, &str> {
 			preceded(tag("type::"), cut(function_type)),
 			preceded(tag("vector::"), cut(function_vector)),
 		)),
-		alt((tag("count"), tag("not"), tag("rand"), tag("sleep"))),
+		alt((tag("count"), tag("grouping"), tag("not"), tag("rand"), tag("sleep"))),
 	)))(i)
 }
 
@@ -387,6 +404,10 @@ fn function_bytes(i: &str) -> IResult<&str, &str> {
 	alt((tag("len"),))(i)
 }
 
+fn function_count(i: &str) -> IResult<&str, &str> {
+	alt((tag("distinct_approx"),))(i)
+}
+
 fn function_crypto(i: &str) -> IResult<&str, &str> {
 	alt((
 		preceded(tag("argon2::"), alt((tag("compare"), tag("generate")))),
@@ -500,6 +521,10 @@ fn function_parse(i: &str) -> IResult<&str, &str> {
 	))(i)
 }
 
+fn function_path(i: &str) -> IResult<&str, &str> {
+	tag("shortest")(i)
+}
+
 fn function_rand(i: &str) -> IResult<&str, &str> {
 	alt((
 		tag("bool"),
@@ -517,7 +542,11 @@ fn function_rand(i: &str) -> IResult<&str, &str> {
 }
 
 fn function_search(i: &str) -> IResult<&str, &str> {
-	alt((tag("score"), tag("highlight"), tag("offsets")))(i)
+	alt((tag("facets"), tag("score"), tag("highlight"), tag("offsets")))(i)
+}
+
+fn function_sequence(i: &str) -> IResult<&str, &str> {
+	tag("next")(i)
 }
 
 fn function_session(i: &str) -> IResult<&str, &str> {
@@ -615,6 +644,7 @@ fn function_type(i: &str) -> IResult<&str, &str> {
 		tag("string"),
 		tag("table"),
 		tag("thing"),
+		tag("validate"),
 		preceded(
 			tag("is::"),
 			alt((
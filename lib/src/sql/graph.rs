@@ -1,26 +1,34 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::{closeparentheses, openparentheses};
 use crate::sql::cond::{cond, Cond};
 use crate::sql::dir::{dir, Dir};
 use crate::sql::error::IResult;
 use crate::sql::field::Fields;
+use crate::sql::function::{normal, Function};
 use crate::sql::group::Groups;
 use crate::sql::idiom::{plain as idiom, Idiom};
 use crate::sql::limit::Limit;
 use crate::sql::order::Orders;
+use crate::sql::param::param;
 use crate::sql::split::Splits;
 use crate::sql::start::Start;
-use crate::sql::table::{table, tables, Tables};
+use crate::sql::table::{table, tables, Table, Tables};
+use crate::sql::thing::thing;
+use crate::sql::value::Value;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
-use nom::character::complete::char;
-use nom::combinator::{map, opt};
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::character::complete::{char, u32};
+use nom::combinator::{fail, map, opt, verify};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 3)]
 pub struct Graph {
 	pub dir: Dir,
 	pub expr: Fields,
@@ -32,6 +40,36 @@ pub struct Graph {
 	pub limit: Option<Limit>,
 	pub start: Option<Start>,
 	pub alias: Option<Idiom>,
+	#[revision(start = 2)]
+	pub recurse: Option<Recurse>,
+	/// A parameterized edge table name, such as `type::table($tb)`
+	#[revision(start = 3)]
+	pub dynamic: Option<Value>,
+}
+
+/// The depth bounds of a recursive graph traversal
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Recurse {
+	/// The minimum number of hops before records are output
+	pub min: u32,
+	/// The maximum number of hops which are traversed
+	pub max: u32,
+	/// The record to find the shortest path to
+	pub shortest: Option<Value>,
+}
+
+impl Display for Recurse {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self.min == self.max {
+			true => write!(f, "{{{}", self.max)?,
+			false => write!(f, "{{{}..{}", self.min, self.max)?,
+		}
+		if let Some(ref v) = self.shortest {
+			write!(f, "+shortest={v}")?
+		}
+		f.write_char('}')
+	}
 }
 
 impl Graph {
@@ -39,22 +77,40 @@ impl Graph {
 	pub fn to_raw(&self) -> String {
 		self.to_string()
 	}
+	/// Get the edge tables to traverse, computing any parameterized table name
+	pub(crate) async fn tables(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Tables, Error> {
+		match &self.dynamic {
+			Some(v) => match v.compute(ctx, opt, txn, doc).await? {
+				Value::Table(v) => Ok(Tables::from(v)),
+				v => Ok(Tables::from(Table(v.as_raw_string()))),
+			},
+			None => Ok(self.what.clone()),
+		}
+	}
+	/// Write the edge tables which are traversed
+	fn fmt_what(&self, f: &mut Formatter) -> fmt::Result {
+		match (&self.dynamic, self.what.len()) {
+			(Some(v), _) => Display::fmt(v, f),
+			(None, 0) => f.write_char('?'),
+			(None, _) => Display::fmt(&self.what, f),
+		}
+	}
 }
 
 impl Display for Graph {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		if self.what.0.len() <= 1 && self.cond.is_none() && self.alias.is_none() {
 			Display::fmt(&self.dir, f)?;
-			match self.what.len() {
-				0 => f.write_char('?'),
-				_ => Display::fmt(&self.what, f),
-			}
+			self.fmt_what(f)?;
 		} else {
 			write!(f, "{}(", self.dir)?;
-			match self.what.len() {
-				0 => f.write_char('?'),
-				_ => Display::fmt(&self.what, f),
-			}?;
+			self.fmt_what(f)?;
 			if let Some(ref v) = self.cond {
 				write!(f, " {v}")?
 			}
@@ -76,14 +132,19 @@ impl Display for Graph {
 			if let Some(ref v) = self.alias {
 				write!(f, " AS {v}")?
 			}
-			f.write_char(')')
+			f.write_char(')')?;
 		}
+		if let Some(ref v) = self.recurse {
+			Display::fmt(v, f)?
+		}
+		Ok(())
 	}
 }
 
 pub fn graph(i: &str) -> IResult<&str, Graph> {
 	let (i, dir) = dir(i)?;
-	let (i, (what, cond, alias)) = alt((simple, custom))(i)?;
+	let (i, ((what, dynamic), cond, alias)) = alt((simple, custom))(i)?;
+	let (i, recurse) = opt(recurse)(i)?;
 	Ok((
 		i,
 		Graph {
@@ -97,18 +158,22 @@ pub fn graph(i: &str) -> IResult<&str, Graph> {
 			order: None,
 			limit: None,
 			start: None,
+			recurse,
+			dynamic,
 		},
 	))
 }
 
-fn simple(i: &str) -> IResult<&str, (Tables, Option<Cond>, Option<Idiom>)> {
-	let (i, w) = alt((any, one))(i)?;
+type What = (Tables, Option<Value>);
+
+fn simple(i: &str) -> IResult<&str, (What, Option<Cond>, Option<Idiom>)> {
+	let (i, w) = alt((dynamic, map(alt((any, one)), |v| (v, None))))(i)?;
 	Ok((i, (w, None, None)))
 }
 
-fn custom(i: &str) -> IResult<&str, (Tables, Option<Cond>, Option<Idiom>)> {
+fn custom(i: &str) -> IResult<&str, (What, Option<Cond>, Option<Idiom>)> {
 	let (i, _) = openparentheses(i)?;
-	let (i, w) = alt((any, tables))(i)?;
+	let (i, w) = alt((dynamic, map(alt((any, tables)), |v| (v, None))))(i)?;
 	let (i, c) = opt(|i| {
 		let (i, _) = shouldbespace(i)?;
 		let (i, v) = cond(i)?;
@@ -125,6 +190,36 @@ fn custom(i: &str) -> IResult<&str, (Tables, Option<Cond>, Option<Idiom>)> {
 	Ok((i, (w, c, a)))
 }
 
+fn recurse(i: &str) -> IResult<&str, Recurse> {
+	let (i, _) = char('{')(i)?;
+	let (i, (min, max)) = alt((
+		|i| {
+			let (i, min) = opt(u32)(i)?;
+			let (i, _) = tag("..")(i)?;
+			let (i, max) = u32(i)?;
+			Ok((i, (min.unwrap_or(1), max)))
+		},
+		map(u32, |v| (v, v)),
+	))(i)?;
+	let (i, shortest) = opt(|i| {
+		let (i, _) = tag_no_case("+shortest=")(i)?;
+		alt((map(thing, Value::from), map(param, Value::from)))(i)
+	})(i)?;
+	let (i, _) = char('}')(i)?;
+	// The depth bounds must be a valid range
+	if max == 0 || min > max {
+		return fail(i);
+	}
+	Ok((
+		i,
+		Recurse {
+			min,
+			max,
+			shortest,
+		},
+	))
+}
+
 fn one(i: &str) -> IResult<&str, Tables> {
 	let (i, v) = table(i)?;
 	Ok((i, Tables::from(v)))
@@ -134,6 +229,11 @@ fn any(i: &str) -> IResult<&str, Tables> {
 	map(char('?'), |_| Tables::default())(i)
 }
 
+fn dynamic(i: &str) -> IResult<&str, What> {
+	let (i, v) = verify(normal, |f| matches!(f, Function::Normal(n, _) if n == "type::table"))(i)?;
+	Ok((i, (Tables::default(), Some(Value::from(v)))))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -163,6 +263,24 @@ mod tests {
 		assert_eq!("<->likes", format!("{}", out));
 	}
 
+	#[test]
+	fn graph_dynamic() {
+		let sql = "->type::table($edge)";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert!(out.dynamic.is_some());
+		assert_eq!("->type::table($edge)", format!("{}", out));
+	}
+
+	#[test]
+	fn graph_dynamic_conditions() {
+		let sql = "->(type::table($edge) WHERE influencer = true)";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert!(out.dynamic.is_some());
+		assert_eq!("->(type::table($edge) WHERE influencer = true)", format!("{}", out));
+	}
+
 	#[test]
 	fn graph_multiple() {
 		let sql = "->(likes, follows)";
@@ -194,4 +312,53 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("->(likes, follows WHERE influencer = true AS connections)", format!("{}", out));
 	}
+
+	#[test]
+	fn graph_recurse() {
+		let sql = "->knows{1..5}";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert_eq!("->knows{1..5}", format!("{}", out));
+		assert_eq!(
+			out.recurse,
+			Some(Recurse {
+				min: 1,
+				max: 5,
+				shortest: None,
+			})
+		);
+	}
+
+	#[test]
+	fn graph_recurse_max() {
+		let sql = "->knows{..3}";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert_eq!("->knows{1..3}", format!("{}", out));
+	}
+
+	#[test]
+	fn graph_recurse_exact() {
+		let sql = "<-(knows WHERE since > 2020){2}";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert_eq!("<-(knows WHERE since > 2020){2}", format!("{}", out));
+	}
+
+	#[test]
+	fn graph_recurse_shortest() {
+		let sql = "->knows{..10+shortest=person:jaime}";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert_eq!("->knows{1..10+shortest=person:jaime}", format!("{}", out));
+	}
+
+	#[test]
+	fn graph_recurse_invalid() {
+		let sql = "->knows{5..1}";
+		let res = graph(sql);
+		let out = res.unwrap();
+		assert_eq!("{5..1}", out.0);
+		assert_eq!(None, out.1.recurse);
+	}
 }
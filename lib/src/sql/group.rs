@@ -1,11 +1,11 @@
-use crate::sql::comment::shouldbespace;
-use crate::sql::common::commas;
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::{closeparentheses, commas, openparentheses};
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::idiom::{basic, Idiom};
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
-use nom::combinator::{cut, opt};
+use nom::combinator::{cut, fail, map, opt, value};
 use nom::multi::separated_list1;
 use nom::sequence::terminated;
 use revision::revisioned;
@@ -59,12 +59,62 @@ impl Display for Group {
 	}
 }
 
+/// The maximum number of fields which can be specified in a CUBE clause
+const MAX_CUBE_FIELDS: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Grouping {
+	/// Group by all of the fields
+	#[default]
+	Simple,
+	/// Group by each leading subset of the fields, down to a grand total
+	Rollup,
+	/// Group by every combination of the fields
+	Cube,
+}
+
+impl Grouping {
+	/// Returns the grouping sets to compute, specifying
+	/// which of the group fields are used in each set
+	pub(crate) fn sets(&self, len: usize) -> Vec<Vec<bool>> {
+		match self {
+			Self::Simple => vec![vec![true; len]],
+			Self::Rollup => (0..=len).rev().map(|n| (0..len).map(|i| i < n).collect()).collect(),
+			Self::Cube => (0..1usize << len)
+				.rev()
+				.map(|m| (0..len).map(|i| m & (1 << (len - 1 - i)) != 0).collect())
+				.collect(),
+		}
+	}
+}
+
+impl Display for Grouping {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Simple => Ok(()),
+			Self::Rollup => f.write_str("ROLLUP"),
+			Self::Cube => f.write_str("CUBE"),
+		}
+	}
+}
+
 pub fn group(i: &str) -> IResult<&str, Groups> {
 	let (i, _) = tag_no_case("GROUP")(i)?;
 	let (i, _) = shouldbespace(i)?;
 	cut(alt((group_all, group_any)))(i)
 }
 
+pub fn grouping(i: &str) -> IResult<&str, (Groups, Grouping)> {
+	let (i, _) = tag_no_case("GROUP")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(alt((
+		map(group_all, |v| (v, Grouping::Simple)),
+		group_sets,
+		map(group_any, |v| (v, Grouping::Simple)),
+	)))(i)
+}
+
 fn group_all(i: &str) -> IResult<&str, Groups> {
 	let (i, _) = tag_no_case("ALL")(i)?;
 	Ok((i, Groups(vec![])))
@@ -76,6 +126,23 @@ fn group_any(i: &str) -> IResult<&str, Groups> {
 	Ok((i, Groups(v)))
 }
 
+fn group_sets(i: &str) -> IResult<&str, (Groups, Grouping)> {
+	let (i, _) = opt(terminated(tag_no_case("BY"), shouldbespace))(i)?;
+	let (i, kind) = alt((
+		value(Grouping::Rollup, tag_no_case("ROLLUP")),
+		value(Grouping::Cube, tag_no_case("CUBE")),
+	))(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = openparentheses(i)?;
+	let (i, v) = separated_list1(commas, group_raw)(i)?;
+	let (i, _) = closeparentheses(i)?;
+	// Limit the number of grouping sets for a CUBE
+	if kind == Grouping::Cube && v.len() > MAX_CUBE_FIELDS {
+		return fail(i);
+	}
+	Ok((i, (Groups(v), kind)))
+}
+
 fn group_raw(i: &str) -> IResult<&str, Group> {
 	let (i, v) = basic(i)?;
 	Ok((i, Group(v)))
@@ -117,6 +184,41 @@ mod tests {
 		assert_eq!("GROUP BY field, other.field", format!("{}", out));
 	}
 
+	#[test]
+	fn group_statement_rollup() {
+		let sql = "GROUP BY ROLLUP(country, city)";
+		let (groups, kind) = grouping(sql).unwrap().1;
+		assert_eq!(
+			groups,
+			Groups(vec![Group(Idiom::parse("country")), Group(Idiom::parse("city"))])
+		);
+		assert_eq!(kind, Grouping::Rollup);
+		assert_eq!(kind.sets(2), vec![vec![true, true], vec![true, false], vec![false, false]]);
+	}
+
+	#[test]
+	fn group_statement_cube() {
+		let sql = "GROUP CUBE (country, city)";
+		let (groups, kind) = grouping(sql).unwrap().1;
+		assert_eq!(groups.len(), 2);
+		assert_eq!(kind, Grouping::Cube);
+		assert_eq!(
+			kind.sets(2),
+			vec![vec![true, true], vec![true, false], vec![false, true], vec![false, false]]
+		);
+	}
+
+	#[test]
+	fn group_statement_rollup_field() {
+		let sql = "GROUP BY rollup, cube";
+		let (groups, kind) = grouping(sql).unwrap().1;
+		assert_eq!(
+			groups,
+			Groups(vec![Group(Idiom::parse("rollup")), Group(Idiom::parse("cube"))])
+		);
+		assert_eq!(kind, Grouping::Simple);
+	}
+
 	#[test]
 	fn group_statement_all() {
 		let sql = "GROUP ALL";
@@ -447,6 +447,8 @@ mod tests {
 					order: None,
 					limit: None,
 					start: None,
+					recurse: None,
+					dynamic: None,
 				}),
 				Part::Graph(Graph {
 					dir: Dir::Out,
@@ -459,6 +461,8 @@ mod tests {
 					order: None,
 					limit: None,
 					start: None,
+					recurse: None,
+					dynamic: None,
 				}),
 			])
 		);
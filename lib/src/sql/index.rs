@@ -1,14 +1,24 @@
 use crate::idx::ft::analyzer::Analyzers;
 use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::commas;
 use crate::sql::error::IResult;
+use crate::sql::fmt::Fmt;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::language::{language, Language};
+use crate::sql::number::number;
 use crate::sql::scoring::{scoring, Scoring};
+use crate::sql::value::Value;
+use crate::sql::Error::Parser;
 use crate::sql::Number;
+use deunicode::deunicode;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case};
 use nom::character::complete::u16 as uint16;
 use nom::character::complete::u32 as uint32;
 use nom::combinator::{cut, map, opt};
+use nom::multi::separated_list1;
+use nom::sequence::tuple;
+use nom::Err::Failure;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -26,14 +36,21 @@ pub enum Index {
 	Search(SearchParams),
 	/// M-Tree index for distance based metrics
 	MTree(MTreeParams),
+	/// HNSW index for approximate nearest neighbour search
+	Hnsw(HnswParams),
+	/// Index of the trigrams of strings, for substring and regex searches
+	Trigram,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct SearchParams {
 	pub az: Ident,
 	pub hl: bool,
 	pub sc: Scoring,
+	/// The weight of the terms of each field, in the order of the fields, which defaults to 1
+	#[revision(start = 2)]
+	pub boosts: Vec<Number>,
 	pub doc_ids_order: u32,
 	pub doc_lengths_order: u32,
 	pub postings_order: u32,
@@ -49,6 +66,38 @@ pub struct MTreeParams {
 	pub doc_ids_order: u32,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct HnswParams {
+	pub dimension: u16,
+	pub distance: Distance,
+	/// The number of neighbours each node is linked to, on each layer of the graph
+	pub m: u16,
+	/// The number of candidates considered when inserting a node into the graph
+	pub ef_construction: u16,
+	pub doc_ids_order: u32,
+	/// How the vectors are compressed in the graph, if at all
+	pub quantization: Option<Quantization>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Quantization {
+	/// Each component of a vector is stored as a byte
+	Scalar,
+	/// A vector is split into a number of subvectors, each stored as the nearest of 256 centroids
+	Product(u16),
+}
+
+impl Display for Quantization {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Scalar => f.write_str("SCALAR"),
+			Self::Product(subvectors) => write!(f, "PRODUCT {}", subvectors),
+		}
+	}
+}
+
 #[derive(Default, Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[revisioned(revision = 1)]
 pub enum Distance {
@@ -74,21 +123,73 @@ impl Display for Distance {
 	}
 }
 
+/// How the strings of an index are compared, so that a `UNIQUE` index can reject
+/// values which only differ by their case or their diacritics
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Collation {
+	/// Letters are equal regardless of their case
+	pub nocase: bool,
+	/// Letters are equal regardless of their diacritics
+	pub noaccent: bool,
+	/// The language whose rules change the case of the letters
+	pub language: Option<Language>,
+}
+
+impl Collation {
+	/// The value which is indexed in place of a string
+	pub(crate) fn fold(&self, v: Value) -> Value {
+		match v {
+			Value::Strand(s) => Value::from(self.fold_str(&s)),
+			Value::Array(a) => Value::Array(a.into_iter().map(|v| self.fold(v)).collect()),
+			v => v,
+		}
+	}
+
+	fn fold_str(&self, s: &str) -> String {
+		let s = match (self.nocase, &self.language) {
+			(false, _) => s.to_owned(),
+			// The dotted and the dotless i are distinct letters
+			(true, Some(Language::Turkish)) => s.replace('I', "ı").replace('İ', "i").to_lowercase(),
+			(true, _) => s.to_lowercase(),
+		};
+		match self.noaccent {
+			true => deunicode(&s),
+			false => s,
+		}
+	}
+}
+
+impl Display for Collation {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.write_str("COLLATE")?;
+		if self.nocase {
+			f.write_str(" NOCASE")?;
+		}
+		if self.noaccent {
+			f.write_str(" NOACCENT")?;
+		}
+		if let Some(l) = &self.language {
+			write!(f, " LANGUAGE {l}")?;
+		}
+		Ok(())
+	}
+}
+
 impl Display for Index {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		match self {
 			Self::Idx => Ok(()),
 			Self::Uniq => f.write_str("UNIQUE"),
 			Self::Search(p) => {
+				write!(f, "SEARCH ANALYZER {} {}", p.az, p.sc)?;
+				if !p.boosts.is_empty() {
+					write!(f, " BOOSTS {}", Fmt::comma_separated(&p.boosts))?
+				}
 				write!(
 					f,
-					"SEARCH ANALYZER {} {} DOC_IDS_ORDER {} DOC_LENGTHS_ORDER {} POSTINGS_ORDER {} TERMS_ORDER {}",
-					p.az,
-					p.sc,
-					p.doc_ids_order,
-					p.doc_lengths_order,
-					p.postings_order,
-					p.terms_order
+					" DOC_IDS_ORDER {} DOC_LENGTHS_ORDER {} POSTINGS_ORDER {} TERMS_ORDER {}",
+					p.doc_ids_order, p.doc_lengths_order, p.postings_order, p.terms_order
 				)?;
 				if p.hl {
 					f.write_str(" HIGHLIGHTS")?
@@ -102,12 +203,24 @@ impl Display for Index {
 					p.dimension, p.distance, p.capacity, p.doc_ids_order
 				)
 			}
+			Self::Hnsw(p) => {
+				write!(
+					f,
+					"HNSW DIMENSION {} DIST {} M {} EFC {} DOC_IDS_ORDER {}",
+					p.dimension, p.distance, p.m, p.ef_construction, p.doc_ids_order
+				)?;
+				if let Some(q) = &p.quantization {
+					write!(f, " QUANTIZATION {}", q)?
+				}
+				Ok(())
+			}
+			Self::Trigram => f.write_str("TRIGRAM"),
 		}
 	}
 }
 
 pub fn index(i: &str) -> IResult<&str, Index> {
-	alt((unique, search, mtree))(i)
+	alt((unique, search, mtree, hnsw, trigram))(i)
 }
 
 pub fn unique(i: &str) -> IResult<&str, Index> {
@@ -115,6 +228,33 @@ pub fn unique(i: &str) -> IResult<&str, Index> {
 	Ok((i, Index::Uniq))
 }
 
+pub fn trigram(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("TRIGRAM")(i)?;
+	Ok((i, Index::Trigram))
+}
+
+/// A bare `COLLATE` ignores both the case and the diacritics of the letters
+pub fn collate(i: &str) -> IResult<&str, Collation> {
+	let (i, _) = tag_no_case("COLLATE")(i)?;
+	let (i, nocase) = opt(tuple((shouldbespace, tag_no_case("NOCASE"))))(i)?;
+	let (i, noaccent) = opt(tuple((shouldbespace, tag_no_case("NOACCENT"))))(i)?;
+	let (i, language) = opt(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("LANGUAGE")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		cut(language)(i)
+	})(i)?;
+	let all = nocase.is_none() && noaccent.is_none();
+	Ok((
+		i,
+		Collation {
+			nocase: all || nocase.is_some(),
+			noaccent: all || noaccent.is_some(),
+			language,
+		},
+	))
+}
+
 pub fn analyzer(i: &str) -> IResult<&str, Ident> {
 	let (i, _) = mightbespace(i)?;
 	let (i, _) = tag_no_case("ANALYZER")(i)?;
@@ -147,6 +287,18 @@ pub fn terms_order(i: &str) -> IResult<&str, u32> {
 	order("TERMS_ORDER", i)
 }
 
+pub fn boosts(i: &str) -> IResult<&str, Vec<Number>> {
+	let (i, _) = mightbespace(i)?;
+	let (i, _) = tag_no_case("BOOSTS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, boosts) = cut(separated_list1(commas, number))(i)?;
+	// A boost which is not positive would invert or cancel the weight of a field
+	if !boosts.iter().all(Number::is_positive) {
+		return Err(Failure(Parser(i)));
+	}
+	Ok((i, boosts))
+}
+
 pub fn highlights(i: &str) -> IResult<&str, bool> {
 	let (i, _) = mightbespace(i)?;
 	map(opt(tag("HIGHLIGHTS")), |x| x.is_some())(i)
@@ -159,6 +311,7 @@ pub fn search(i: &str) -> IResult<&str, Index> {
 		let (i, az) = opt(analyzer)(i)?;
 		let (i, _) = shouldbespace(i)?;
 		let (i, sc) = scoring(i)?;
+		let (i, boosts) = opt(boosts)(i)?;
 		let (i, o1) = opt(doc_ids_order)(i)?;
 		let (i, o2) = opt(doc_lengths_order)(i)?;
 		let (i, o3) = opt(postings_order)(i)?;
@@ -169,6 +322,7 @@ pub fn search(i: &str) -> IResult<&str, Index> {
 			Index::Search(SearchParams {
 				az: az.unwrap_or_else(|| Ident::from(Analyzers::LIKE)),
 				sc,
+				boosts: boosts.unwrap_or_default(),
 				hl,
 				doc_ids_order: o1.unwrap_or(100),
 				doc_lengths_order: o2.unwrap_or(100),
@@ -182,12 +336,13 @@ pub fn search(i: &str) -> IResult<&str, Index> {
 pub fn distance(i: &str) -> IResult<&str, Distance> {
 	let (i, _) = mightbespace(i)?;
 	let (i, _) = tag_no_case("DIST")(i)?;
+	let (i, _) = shouldbespace(i)?;
 	alt((
 		map(tag_no_case("EUCLIDEAN"), |_| Distance::Euclidean),
 		map(tag_no_case("MANHATTAN"), |_| Distance::Manhattan),
-		map(tag_no_case("COSINE"), |_| Distance::Manhattan),
-		map(tag_no_case("HAMMING"), |_| Distance::Manhattan),
-		map(tag_no_case("MAHALANOBIS"), |_| Distance::Manhattan),
+		map(tag_no_case("COSINE"), |_| Distance::Cosine),
+		map(tag_no_case("HAMMING"), |_| Distance::Hamming),
+		map(tag_no_case("MAHALANOBIS"), |_| Distance::Mahalanobis),
 		minkowski,
 	))(i)
 }
@@ -234,3 +389,57 @@ pub fn mtree(i: &str) -> IResult<&str, Index> {
 		))
 	})(i)
 }
+
+pub fn m(i: &str) -> IResult<&str, u16> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("M")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, m) = cut(uint16)(i)?;
+	Ok((i, m))
+}
+
+pub fn ef_construction(i: &str) -> IResult<&str, u16> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("EFC")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, efc) = cut(uint16)(i)?;
+	Ok((i, efc))
+}
+
+pub fn quantization(i: &str) -> IResult<&str, Quantization> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("QUANTIZATION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(alt((map(tag_no_case("SCALAR"), |_| Quantization::Scalar), product)))(i)
+}
+
+pub fn product(i: &str) -> IResult<&str, Quantization> {
+	let (i, _) = tag_no_case("PRODUCT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, subvectors) = cut(uint16)(i)?;
+	Ok((i, Quantization::Product(subvectors)))
+}
+
+pub fn hnsw(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("HNSW")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, dimension) = dimension(i)?;
+		let (i, distance) = opt(distance)(i)?;
+		let (i, m) = opt(m)(i)?;
+		let (i, ef_construction) = opt(ef_construction)(i)?;
+		let (i, doc_ids_order) = opt(doc_ids_order)(i)?;
+		let (i, quantization) = opt(quantization)(i)?;
+		Ok((
+			i,
+			Index::Hnsw(HnswParams {
+				dimension,
+				distance: distance.unwrap_or(Distance::Euclidean),
+				m: m.unwrap_or(12),
+				ef_construction: ef_construction.unwrap_or(150),
+				doc_ids_order: doc_ids_order.unwrap_or(100),
+				quantization,
+			}),
+		))
+	})(i)
+}
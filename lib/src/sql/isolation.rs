@@ -0,0 +1,64 @@
+use crate::sql::error::IResult;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::value;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The isolation level of a transaction
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Isolation {
+	/// Reads see a consistent snapshot of the datastore, and
+	/// the transaction fails if it writes a key which a
+	/// concurrent transaction has written since
+	Snapshot,
+	/// The transaction also fails if a concurrent transaction
+	/// has written a key which this transaction has read
+	Serializable,
+}
+
+impl Default for Isolation {
+	fn default() -> Self {
+		Self::Snapshot
+	}
+}
+
+impl fmt::Display for Isolation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			Self::Snapshot => "SNAPSHOT",
+			Self::Serializable => "SERIALIZABLE",
+		})
+	}
+}
+
+pub fn isolation(i: &str) -> IResult<&str, Isolation> {
+	alt((
+		value(Isolation::Snapshot, tag_no_case("SNAPSHOT")),
+		value(Isolation::Serializable, tag_no_case("SERIALIZABLE")),
+	))(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn isolation_snapshot() {
+		let sql = "SNAPSHOT";
+		let res = isolation(sql);
+		let out = res.unwrap().1;
+		assert_eq!("SNAPSHOT", format!("{}", out));
+	}
+
+	#[test]
+	fn isolation_serializable() {
+		let sql = "serializable";
+		let res = isolation(sql);
+		let out = res.unwrap().1;
+		assert_eq!("SERIALIZABLE", format!("{}", out));
+	}
+}
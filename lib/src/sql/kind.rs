@@ -49,6 +49,16 @@ impl Default for Kind {
 }
 
 impl Kind {
+	/// Check if the values of this kind are never arrays
+	pub(crate) fn is_scalar(&self) -> bool {
+		match self {
+			Kind::Any | Kind::Set(_, _) | Kind::Array(_, _) => false,
+			Kind::Option(k) => k.is_scalar(),
+			Kind::Either(k) => k.iter().all(Kind::is_scalar),
+			_ => true,
+		}
+	}
+
 	fn is_any(&self) -> bool {
 		matches!(self, Kind::Any)
 	}
@@ -34,6 +34,7 @@ pub(crate) mod id;
 pub(crate) mod ident;
 pub(crate) mod idiom;
 pub(crate) mod index;
+pub(crate) mod isolation;
 pub(crate) mod kind;
 pub(crate) mod language;
 pub(crate) mod limit;
@@ -105,12 +106,14 @@ pub use self::future::Future;
 pub use self::geometry::Geometry;
 pub use self::graph::Graph;
 pub use self::group::Group;
+pub use self::group::Grouping;
 pub use self::group::Groups;
 pub use self::id::Id;
 pub use self::ident::Ident;
 pub use self::idiom::Idiom;
 pub use self::idiom::Idioms;
 pub use self::index::Index;
+pub use self::isolation::Isolation;
 pub use self::kind::Kind;
 pub use self::limit::Limit;
 pub use self::model::Model;
@@ -433,14 +433,26 @@ impl Ord for Number {
 	}
 }
 
-// Warning: Equal numbers may have different hashes, which violates
-// the invariants of certain collections!
+// Equal numbers must have the same hash, regardless of whether
+// they are stored as an integer, a float, or a decimal, so that
+// numbers can be used as keys in hash-based collections.
 impl hash::Hash for Number {
 	fn hash<H: hash::Hasher>(&self, state: &mut H) {
 		match self {
 			Number::Int(v) => v.hash(state),
-			Number::Float(v) => v.to_bits().hash(state),
-			Number::Decimal(v) => v.hash(state),
+			Number::Float(v) => {
+				match *v == v.trunc() && *v >= i64::MIN as f64 && *v < i64::MAX as f64 {
+					true => (*v as i64).hash(state),
+					false => v.to_bits().hash(state),
+				}
+			}
+			Number::Decimal(v) => match v.is_integer() {
+				true => match v.to_i64() {
+					Some(v) => v.hash(state),
+					None => v.to_f64().unwrap_or_default().to_bits().hash(state),
+				},
+				false => v.to_f64().unwrap_or_default().to_bits().hash(state),
+			},
 		}
 	}
 }
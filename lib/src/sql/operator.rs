@@ -6,6 +6,7 @@ use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
+use nom::character::complete::u32 as uint32;
 use nom::character::complete::u8 as uint8;
 use nom::combinator::cut;
 use nom::combinator::opt;
@@ -67,6 +68,10 @@ pub enum Operator {
 	//
 	Outside,
 	Intersects,
+	//
+	Knn(u32), // <|k|>
+	//
+	Fuzzy(Option<MatchRef>, u8), // @{ref}@ '{query}'~{distance}
 }
 
 impl Default for Operator {
@@ -134,7 +139,8 @@ impl fmt::Display for Operator {
 			Self::NoneInside => f.write_str("NONEINSIDE"),
 			Self::Outside => f.write_str("OUTSIDE"),
 			Self::Intersects => f.write_str("INTERSECTS"),
-			Self::Matches(reference) => {
+			Self::Knn(k) => write!(f, "<|{}|>", k),
+			Self::Matches(reference) | Self::Fuzzy(reference, _) => {
 				if let Some(r) = reference {
 					write!(f, "@{}@", r)
 				} else {
@@ -193,6 +199,7 @@ pub fn binary_symbols(i: &str) -> IResult<&str, Operator> {
 			matches,
 		)),
 		alt((
+			knn,
 			value(Operator::LessThanOrEqual, tag("<=")),
 			value(Operator::LessThan, char('<')),
 			value(Operator::MoreThanOrEqual, tag(">=")),
@@ -265,6 +272,17 @@ pub fn matches(i: &str) -> IResult<&str, Operator> {
 	})(i)
 }
 
+pub fn knn(i: &str) -> IResult<&str, Operator> {
+	let (i, _) = tag("<|")(i)?;
+	cut(|i| {
+		let (i, _) = mightbespace(i)?;
+		let (i, k) = uint32(i)?;
+		let (i, _) = mightbespace(i)?;
+		let (i, _) = tag("|>")(i)?;
+		Ok((i, Operator::Knn(k)))
+	})(i)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -290,4 +308,12 @@ mod tests {
 		let res = matches("@256@");
 		res.unwrap_err();
 	}
+
+	#[test]
+	fn knn_with_k() {
+		let res = knn("<|10|>");
+		let out = res.unwrap().1;
+		assert_eq!("<|10|>", format!("{}", out));
+		assert_eq!(out, Operator::Knn(10));
+	}
 }
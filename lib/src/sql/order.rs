@@ -1,11 +1,19 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
+use crate::sql::function::{normal, Function};
 use crate::sql::idiom::{basic, Idiom};
+use crate::sql::parser;
+use crate::sql::part::Part;
+use crate::sql::value::Value;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
-use nom::combinator::{cut, opt, value};
+use nom::combinator::{cut, opt, value, verify};
 use nom::multi::separated_list1;
 use nom::sequence::tuple;
 use revision::revisioned;
@@ -32,6 +40,42 @@ impl IntoIterator for Orders {
 	}
 }
 
+impl Orders {
+	/// Check if any ORDER BY field is parameterized
+	pub(crate) fn is_dynamic(&self) -> bool {
+		self.iter().any(Order::is_dynamic)
+	}
+	/// Compute any parameterized ORDER BY fields into field paths
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Self, Error> {
+		// Ensure the field names are not projected
+		let opt = &opt.new_with_projections(false);
+		// Compute each of the ORDER BY fields
+		let mut out = Vec::with_capacity(self.len());
+		for v in self.iter() {
+			let order = match v.order.first() {
+				Some(Part::Start(f)) if v.is_dynamic() => {
+					match f.compute(ctx, opt, txn, doc).await? {
+						Value::Idiom(i) => i,
+						v => parser::idiom(&v.as_raw_string())?,
+					}
+				}
+				_ => v.order.clone(),
+			};
+			out.push(Order {
+				order,
+				..v.clone()
+			});
+		}
+		Ok(Self(out))
+	}
+}
+
 impl fmt::Display for Orders {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "ORDER BY {}", Fmt::comma_separated(&self.0))
@@ -48,6 +92,18 @@ pub struct Order {
 	pub direction: bool,
 }
 
+impl Order {
+	/// Check if this ORDER BY field is a `type::field()` parameter
+	pub(crate) fn is_dynamic(&self) -> bool {
+		match self.order.first() {
+			Some(Part::Start(Value::Function(f))) => {
+				matches!(f.as_ref(), Function::Normal(n, _) if n == "type::field")
+			}
+			_ => false,
+		}
+	}
+}
+
 impl Deref for Order {
 	type Target = Idiom;
 	fn deref(&self) -> &Self::Target {
@@ -100,7 +156,7 @@ fn order_rand(i: &str) -> IResult<&str, Vec<Order>> {
 }
 
 fn order_raw(i: &str) -> IResult<&str, Order> {
-	let (i, v) = basic(i)?;
+	let (i, v) = alt((order_dynamic, basic))(i)?;
 	let (i, c) = opt(tuple((shouldbespace, tag_no_case("COLLATE"))))(i)?;
 	let (i, n) = opt(tuple((shouldbespace, tag_no_case("NUMERIC"))))(i)?;
 	let (i, d) = opt(alt((
@@ -119,6 +175,11 @@ fn order_raw(i: &str) -> IResult<&str, Order> {
 	))
 }
 
+fn order_dynamic(i: &str) -> IResult<&str, Idiom> {
+	let (i, v) = verify(normal, |f| matches!(f, Function::Normal(n, _) if n == "type::field"))(i)?;
+	Ok((i, Idiom::from(vec![Part::Start(Value::from(v))])))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -277,4 +338,14 @@ mod tests {
 		);
 		assert_eq!("ORDER BY field COLLATE NUMERIC DESC", format!("{}", out));
 	}
+
+	#[test]
+	fn order_statement_dynamic() {
+		let sql = "ORDER type::field($field) DESC, name";
+		let res = order(sql);
+		let out = res.unwrap().1;
+		assert!(out[0].is_dynamic());
+		assert!(!out[1].is_dynamic());
+		assert_eq!("ORDER BY type::field($field) DESC, name", format!("{}", out));
+	}
 }
@@ -1,9 +1,11 @@
 use crate::sql::comment::shouldbespace;
+use crate::sql::common::commas;
 use crate::sql::error::IResult;
 use crate::sql::field::{fields, Fields};
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::{cut, map, value};
+use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
@@ -17,6 +19,7 @@ pub enum Output {
 	After,
 	Before,
 	Fields(Fields),
+	BeforeAfter,
 }
 
 impl Default for Output {
@@ -35,6 +38,7 @@ impl Display for Output {
 			Self::After => f.write_str("AFTER"),
 			Self::Before => f.write_str("BEFORE"),
 			Self::Fields(v) => Display::fmt(v, f),
+			Self::BeforeAfter => f.write_str("BEFORE, AFTER"),
 		}
 	}
 }
@@ -48,6 +52,10 @@ pub fn output(i: &str) -> IResult<&str, Output> {
 			value(Output::Null, tag_no_case("NULL")),
 			value(Output::Diff, tag_no_case("DIFF")),
 			value(Output::After, tag_no_case("AFTER")),
+			value(
+				Output::BeforeAfter,
+				tuple((tag_no_case("BEFORE"), commas, tag_no_case("AFTER"))),
+			),
 			value(Output::Before, tag_no_case("BEFORE")),
 			map(fields, Output::Fields),
 		))(i)?;
@@ -67,4 +75,22 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("RETURN field, other.field", format!("{}", out));
 	}
+
+	#[test]
+	fn output_statement_before_after() {
+		let sql = "RETURN BEFORE,AFTER";
+		let res = output(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, Output::BeforeAfter);
+		assert_eq!("RETURN BEFORE, AFTER", format!("{}", out));
+	}
+
+	#[test]
+	fn output_statement_before() {
+		let sql = "RETURN BEFORE";
+		let res = output(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, Output::Before);
+		assert_eq!("RETURN BEFORE", format!("{}", out));
+	}
 }
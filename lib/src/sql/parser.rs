@@ -3,6 +3,7 @@ use crate::iam::Error as IamError;
 use crate::sql::error::Error::{ExcessiveDepth, Field, Group, Order, Parser, Role, Split};
 use crate::sql::error::IResult;
 use crate::sql::idiom::Idiom;
+use crate::sql::kind::Kind;
 use crate::sql::query::{query, Query};
 use crate::sql::subquery::Subquery;
 use crate::sql::thing::Thing;
@@ -38,6 +39,12 @@ pub fn idiom(input: &str) -> Result<Idiom, Error> {
 	parse_impl(input, super::idiom::plain)
 }
 
+/// Parses a SurrealQL [`Kind`]
+#[instrument(name = "parser", skip_all, fields(length = input.len()))]
+pub fn kind(input: &str) -> Result<Kind, Error> {
+	parse_impl(input, super::kind::kind)
+}
+
 /// Parses a SurrealQL [`Value`].
 #[instrument(name = "parser", skip_all, fields(length = input.len()))]
 pub fn value(input: &str) -> Result<Value, Error> {
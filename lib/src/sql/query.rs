@@ -1,6 +1,8 @@
+use crate::err::Error;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Pretty;
 use crate::sql::statement::{statements, Statement, Statements};
+use crate::sql::statements::OptionStatement;
 use crate::sql::Value;
 use derive::Store;
 use nom::combinator::all_consuming;
@@ -25,6 +27,28 @@ impl Deref for Query {
 	}
 }
 
+impl Query {
+	/// Run these statements as a single atomic batch, so that the changes
+	/// are either all committed, or are all cancelled if any statement
+	/// fails. A response is still returned for each individual statement.
+	pub fn atomic(self) -> Result<Self, Error> {
+		let mut out = Vec::with_capacity(self.len() + 1);
+		out.push(Statement::Option(OptionStatement {
+			name: "ATOMIC".into(),
+			what: true,
+		}));
+		for stm in self {
+			match stm {
+				Statement::Begin(_) | Statement::Commit(_) | Statement::Cancel(_) => {
+					return Err(Error::TxAtomic)
+				}
+				stm => out.push(stm),
+			}
+		}
+		Ok(Query(Statements(out)))
+	}
+}
+
 impl IntoIterator for Query {
 	type Item = Statement;
 	type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -99,4 +123,18 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("CREATE test;\nCREATE temp;", format!("{}", out))
 	}
+
+	#[test]
+	fn atomic_query() {
+		let sql = "CREATE test; CREATE temp;";
+		let out = query(sql).unwrap().1.atomic().unwrap();
+		assert_eq!("OPTION ATOMIC;\nCREATE test;\nCREATE temp;", format!("{}", out))
+	}
+
+	#[test]
+	fn atomic_query_nested_transaction() {
+		let sql = "CREATE test; COMMIT;";
+		let res = query(sql).unwrap().1.atomic();
+		assert!(matches!(res, Err(Error::TxAtomic)));
+	}
 }
@@ -64,6 +64,10 @@ pub fn check_order_by_fields<'a>(
 	if let Some(orders) = orders {
 		// Loop over each of the expressions in the ORDER BY clause
 		for order in orders.iter() {
+			// Parameterized fields can only be checked once computed
+			if order.is_dynamic() {
+				continue;
+			}
 			if !contains_idiom(fields, order) {
 				// If the expression isn't specified in the SELECT clause, then error
 				return Err(Failure(Error::Order(i, order.to_string())));
@@ -74,6 +78,23 @@ pub fn check_order_by_fields<'a>(
 	Ok(())
 }
 
+/// Check that the computed parameterized ORDER BY fields are in the SELECT clause
+pub(crate) fn check_computed_order_by_fields(
+	fields: &Fields,
+	orders: &Orders,
+) -> Result<(), crate::err::Error> {
+	for order in orders.iter() {
+		if !contains_idiom(fields, order) {
+			// If the expression isn't specified in the SELECT clause, then error
+			return Err(crate::err::Error::InvalidOrderField {
+				field: order.to_string(),
+			});
+		}
+	}
+	// This query is ok to run
+	Ok(())
+}
+
 pub fn check_group_by_fields<'a>(
 	i: &'a str,
 	fields: &Fields,
@@ -109,6 +130,13 @@ pub fn check_group_by_fields<'a>(
 								Value::Idiom(i) if i == &group.0 => continue 'outer,
 								// If the expression in the SELECT clause is a function, check to see if it is an aggregate function
 								Value::Function(f) if f.is_aggregate() => continue 'outer,
+								// If the expression in the SELECT clause is a GROUPING() function, check its field is in the GROUP BY
+								Value::Function(f)
+									if f.is_grouping()
+										&& matches!(f.args(), [Value::Idiom(i)] if i == &group.0) =>
+								{
+									continue 'outer
+								}
 								// Otherwise check if the expression itself exists in the GROUP BY clause
 								v if v.to_idiom() == group.0 => continue 'outer,
 								// Check if this is a static value which can be used in the GROUP BY clause
@@ -7,9 +7,12 @@ use crate::sql::common::colons;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::fmt::Pretty;
+use crate::sql::statements::alter::{alter, AlterStatement};
 use crate::sql::statements::analyze::{analyze, AnalyzeStatement};
+use crate::sql::statements::backup::{backup, BackupStatement};
 use crate::sql::statements::begin::{begin, BeginStatement};
 use crate::sql::statements::cancel::{cancel, CancelStatement};
+use crate::sql::statements::combine::{combine, CombineStatement};
 use crate::sql::statements::commit::{commit, CommitStatement};
 use crate::sql::statements::create::{create, CreateStatement};
 use crate::sql::statements::define::{define, DefineStatement};
@@ -25,13 +28,19 @@ use crate::sql::statements::output::{output, OutputStatement};
 use crate::sql::statements::r#break::{r#break, BreakStatement};
 use crate::sql::statements::r#continue::{r#continue, ContinueStatement};
 use crate::sql::statements::r#use::{r#use, UseStatement};
+use crate::sql::statements::rebuild::{rebuild, RebuildStatement};
+use crate::sql::statements::refresh::{refresh, RefreshStatement};
 use crate::sql::statements::relate::{relate, RelateStatement};
+use crate::sql::statements::release::{release, ReleaseStatement};
 use crate::sql::statements::remove::{remove, RemoveStatement};
+use crate::sql::statements::rollback::{rollback, RollbackStatement};
+use crate::sql::statements::savepoint::{savepoint, SavepointStatement};
 use crate::sql::statements::select::{select, SelectStatement};
 use crate::sql::statements::set::{set, SetStatement};
 use crate::sql::statements::show::{show, ShowStatement};
 use crate::sql::statements::sleep::{sleep, SleepStatement};
 use crate::sql::statements::throw::{throw, ThrowStatement};
+use crate::sql::statements::truncate::{truncate, TruncateStatement};
 use crate::sql::statements::update::{update, UpdateStatement};
 use crate::sql::value::{value, Value};
 use derive::Store;
@@ -110,6 +119,15 @@ pub enum Statement {
 	Update(UpdateStatement),
 	Throw(ThrowStatement),
 	Use(UseStatement),
+	Truncate(TruncateStatement),
+	Combine(CombineStatement),
+	Savepoint(SavepointStatement),
+	Rollback(RollbackStatement),
+	Release(ReleaseStatement),
+	Rebuild(RebuildStatement),
+	Refresh(RefreshStatement),
+	Alter(AlterStatement),
+	Backup(BackupStatement),
 }
 
 impl Statement {
@@ -122,6 +140,7 @@ impl Statement {
 			Self::Relate(v) => v.timeout.as_ref().map(|v| *v.0),
 			Self::Select(v) => v.timeout.as_ref().map(|v| *v.0),
 			Self::Update(v) => v.timeout.as_ref().map(|v| *v.0),
+			Self::Combine(v) => v.timeout(),
 			_ => None,
 		}
 	}
@@ -129,9 +148,12 @@ impl Statement {
 	pub(crate) fn writeable(&self) -> bool {
 		match self {
 			Self::Value(v) => v.writeable(),
+			Self::Alter(_) => true,
 			Self::Analyze(_) => false,
+			Self::Backup(_) => false,
 			Self::Break(_) => false,
 			Self::Continue(_) => false,
+			Self::Combine(v) => v.writeable(),
 			Self::Create(v) => v.writeable(),
 			Self::Define(_) => true,
 			Self::Delete(v) => v.writeable(),
@@ -143,18 +165,46 @@ impl Statement {
 			Self::Live(_) => true,
 			Self::Output(v) => v.writeable(),
 			Self::Option(_) => false,
+			Self::Rebuild(v) => v.writeable(),
+			Self::Refresh(_) => true,
 			Self::Relate(v) => v.writeable(),
 			Self::Remove(_) => true,
 			Self::Select(v) => v.writeable(),
 			Self::Set(v) => v.writeable(),
-			Self::Show(_) => false,
+			Self::Show(v) => v.writeable(),
 			Self::Sleep(_) => false,
 			Self::Throw(_) => false,
+			Self::Truncate(_) => true,
 			Self::Update(v) => v.writeable(),
 			Self::Use(_) => false,
 			_ => unreachable!(),
 		}
 	}
+	/// Check if the statement can run again when its transaction
+	/// conflicts with another transaction, without any side effects
+	/// outside of the transaction being repeated
+	pub(crate) fn retryable(&self) -> bool {
+		matches!(
+			self,
+			Self::Value(_)
+				| Self::Alter(_)
+				| Self::Combine(_)
+				| Self::Create(_)
+				| Self::Define(_)
+				| Self::Delete(_)
+				| Self::Foreach(_)
+				| Self::Ifelse(_)
+				| Self::Insert(_)
+				| Self::Output(_)
+				| Self::Rebuild(_)
+				| Self::Refresh(_)
+				| Self::Relate(_)
+				| Self::Remove(_)
+				| Self::Select(_)
+				| Self::Truncate(_)
+				| Self::Update(_)
+		)
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -164,9 +214,12 @@ impl Statement {
 		doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
 		match self {
+			Self::Alter(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Analyze(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Backup(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Break(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Continue(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Combine(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Create(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Delete(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Define(v) => v.compute(ctx, opt, txn, doc).await,
@@ -177,6 +230,8 @@ impl Statement {
 			Self::Kill(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Live(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Output(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Rebuild(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Refresh(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Relate(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Remove(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Select(v) => v.compute(ctx, opt, txn, doc).await,
@@ -184,6 +239,7 @@ impl Statement {
 			Self::Show(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Sleep(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Throw(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Truncate(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Update(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Value(v) => {
 				// Ensure futures are processed
@@ -200,10 +256,13 @@ impl Display for Statement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		match self {
 			Self::Value(v) => write!(Pretty::from(f), "{v}"),
+			Self::Alter(v) => write!(Pretty::from(f), "{v}"),
 			Self::Analyze(v) => write!(Pretty::from(f), "{v}"),
+			Self::Backup(v) => write!(Pretty::from(f), "{v}"),
 			Self::Begin(v) => write!(Pretty::from(f), "{v}"),
 			Self::Break(v) => write!(Pretty::from(f), "{v}"),
 			Self::Cancel(v) => write!(Pretty::from(f), "{v}"),
+			Self::Combine(v) => write!(Pretty::from(f), "{v}"),
 			Self::Commit(v) => write!(Pretty::from(f), "{v}"),
 			Self::Continue(v) => write!(Pretty::from(f), "{v}"),
 			Self::Create(v) => write!(Pretty::from(f), "{v}"),
@@ -217,13 +276,19 @@ impl Display for Statement {
 			Self::Live(v) => write!(Pretty::from(f), "{v}"),
 			Self::Option(v) => write!(Pretty::from(f), "{v}"),
 			Self::Output(v) => write!(Pretty::from(f), "{v}"),
+			Self::Rebuild(v) => write!(Pretty::from(f), "{v}"),
+			Self::Refresh(v) => write!(Pretty::from(f), "{v}"),
 			Self::Relate(v) => write!(Pretty::from(f), "{v}"),
+			Self::Release(v) => write!(Pretty::from(f), "{v}"),
 			Self::Remove(v) => write!(Pretty::from(f), "{v}"),
+			Self::Rollback(v) => write!(Pretty::from(f), "{v}"),
+			Self::Savepoint(v) => write!(Pretty::from(f), "{v}"),
 			Self::Select(v) => write!(Pretty::from(f), "{v}"),
 			Self::Set(v) => write!(Pretty::from(f), "{v}"),
 			Self::Show(v) => write!(Pretty::from(f), "{v}"),
 			Self::Sleep(v) => write!(Pretty::from(f), "{v}"),
 			Self::Throw(v) => write!(Pretty::from(f), "{v}"),
+			Self::Truncate(v) => write!(Pretty::from(f), "{v}"),
 			Self::Update(v) => write!(Pretty::from(f), "{v}"),
 			Self::Use(v) => write!(Pretty::from(f), "{v}"),
 		}
@@ -235,7 +300,9 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 		mightbespace,
 		alt((
 			alt((
+				map(alter, Statement::Alter),
 				map(analyze, Statement::Analyze),
+				map(backup, Statement::Backup),
 				map(begin, Statement::Begin),
 				map(r#break, Statement::Break),
 				map(cancel, Statement::Cancel),
@@ -254,13 +321,20 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 				map(live, Statement::Live),
 				map(option, Statement::Option),
 				map(output, Statement::Output),
+				map(rebuild, Statement::Rebuild),
+				map(refresh, Statement::Refresh),
 				map(relate, Statement::Relate),
+				map(release, Statement::Release),
 				map(remove, Statement::Remove),
+				map(rollback, Statement::Rollback),
+				map(savepoint, Statement::Savepoint),
+				map(combine, Statement::Combine),
 				map(select, Statement::Select),
 				map(set, Statement::Set),
 				map(show, Statement::Show),
 				map(sleep, Statement::Sleep),
 				map(throw, Statement::Throw),
+				map(truncate, Statement::Truncate),
 				map(update, Statement::Update),
 				map(r#use, Statement::Use),
 			)),
@@ -299,6 +373,50 @@ mod tests {
 		assert_eq!("CREATE test;\nCREATE temp;", format!("{}", out))
 	}
 
+	#[test]
+	fn truncate_table() {
+		let sql = "TRUNCATE person";
+		let res = statement(sql);
+		let out = res.unwrap().1;
+		assert_eq!("TRUNCATE TABLE person", format!("{}", out))
+	}
+
+	#[test]
+	fn combine_selects() {
+		let sql = "SELECT * FROM person UNION ALL SELECT * FROM animal ORDER BY name LIMIT 5";
+		let res = statement(sql);
+		let out = res.unwrap().1;
+		assert!(matches!(out, Statement::Combine(_)));
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn savepoint_statements() {
+		let sql = "BEGIN; SAVEPOINT before; ROLLBACK TO SAVEPOINT before; RELEASE SAVEPOINT before; COMMIT;";
+		let res = statements(sql);
+		let out = res.unwrap().1;
+		assert!(matches!(out[1], Statement::Savepoint(_)));
+		assert!(matches!(out[2], Statement::Rollback(_)));
+		assert!(matches!(out[3], Statement::Release(_)));
+	}
+
+	#[test]
+	fn rebuild_index() {
+		let sql = "REBUILD INDEX idx ON TABLE person";
+		let res = statement(sql);
+		let out = res.unwrap().1;
+		assert_eq!("REBUILD INDEX idx ON person", format!("{}", out))
+	}
+
+	#[test]
+	fn refresh_view() {
+		let sql = "REFRESH VIEW person_by_age";
+		let res = statement(sql);
+		let out = res.unwrap().1;
+		assert!(matches!(out, Statement::Refresh(_)));
+		assert_eq!(sql, format!("{}", out))
+	}
+
 	#[test]
 	fn show_table_changes() {
 		let sql = "SHOW CHANGES FOR TABLE test SINCE 123456";
@@ -0,0 +1,284 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::data::Data;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::idiom::{self, Idiom};
+use crate::sql::operator::Operator;
+use crate::sql::statements::{DefineIndexStatement, UpdateStatement};
+use crate::sql::value::{Value, Values};
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, value};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub enum AlterStatement {
+	/// Remove a field definition, and the field data from all records in the background
+	DropField(Ident, Idiom),
+	/// Rename a field definition, and the field data on all records
+	RenameField(Ident, Idiom, Idiom),
+	/// Change whether a table is SCHEMAFULL or SCHEMALESS
+	Schema(Ident, bool),
+}
+
+impl AlterStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Valid options?
+		opt.valid_for_db()?;
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
+		// Get the table name
+		let tb = match self {
+			Self::DropField(tb, _) => tb,
+			Self::RenameField(tb, _, _) => tb,
+			Self::Schema(tb, _) => tb,
+		};
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Ensure the table exists
+		let mut def = run.get_tb(opt.ns(), opt.db(), tb).await?;
+		// Check if index entries should be updated
+		let mut indexes = true;
+		// Process the statement
+		let data = match self {
+			Self::DropField(_, fd) => {
+				// Remove the field, and any nested field definitions
+				for v in run.all_tb_fields(opt.ns(), opt.db(), tb).await?.iter() {
+					if v.name.starts_with(fd) {
+						let key =
+							crate::key::table::fd::new(opt.ns(), opt.db(), tb, &v.name.to_string());
+						run.del(key).await?;
+					}
+				}
+				// Remove the indexes which only cover the field, and
+				// rebuild the indexes which also cover other fields
+				for v in run.all_tb_indexes(opt.ns(), opt.db(), tb).await?.iter() {
+					if !v.cols.iter().any(|c| c.starts_with(fd)) {
+						continue;
+					}
+					let key = crate::key::table::ix::new(opt.ns(), opt.db(), tb, &v.name);
+					if v.cols.iter().all(|c| c.starts_with(fd)) {
+						run.del(key).await?;
+						let key = crate::key::index::all::new(opt.ns(), opt.db(), tb, &v.name);
+						run.delp(key, u32::MAX).await?;
+						if let Some(rebuilds) = ctx.get_rebuilds() {
+							rebuilds.remove(opt.ns(), opt.db(), tb, &v.name);
+						}
+					} else {
+						let ix = DefineIndexStatement {
+							building: true,
+							failure: None,
+							..v.clone()
+						};
+						run.set(key, ix).await?;
+					}
+				}
+				// Remove the field data from every record in the
+				// background, before the indexes are rebuilt
+				let key = crate::key::table::dr::new(opt.ns(), opt.db(), tb, &fd.to_string());
+				run.set(key, Value::Idiom(fd.clone())).await?;
+				None
+			}
+			Self::RenameField(_, from, to) => {
+				// Check that the new field is not already defined
+				let key = crate::key::table::fd::new(opt.ns(), opt.db(), tb, &to.to_string());
+				if run.exi(key).await? {
+					return Err(Error::FdAlreadyExists {
+						value: to.to_string(),
+					});
+				}
+				// Rename the field, and any nested field definitions
+				for v in run.all_tb_fields(opt.ns(), opt.db(), tb).await?.iter() {
+					if v.name.starts_with(from) {
+						let key =
+							crate::key::table::fd::new(opt.ns(), opt.db(), tb, &v.name.to_string());
+						run.del(key).await?;
+						let mut fd = v.clone();
+						fd.name = rename(&v.name, from, to);
+						let key = crate::key::table::fd::new(
+							opt.ns(),
+							opt.db(),
+							tb,
+							&fd.name.to_string(),
+						);
+						run.set(key, fd).await?;
+					}
+				}
+				// Point any indexes at the renamed field
+				for v in run.all_tb_indexes(opt.ns(), opt.db(), tb).await?.iter() {
+					if v.cols.iter().any(|c| c.starts_with(from)) {
+						let mut ix = v.clone();
+						ix.cols.0 = v.cols.iter().map(|c| rename(c, from, to)).collect();
+						let key = crate::key::table::ix::new(opt.ns(), opt.db(), tb, &ix.name);
+						run.set(key, ix).await?;
+					}
+				}
+				// Move the field data on every record, without
+				// updating the index entries, as the indexed
+				// values themselves are left unchanged
+				indexes = false;
+				Some(Data::SetExpression(vec![
+					(to.clone(), Operator::Equal, Value::Idiom(from.clone())),
+					(from.clone(), Operator::Equal, Value::None),
+				]))
+			}
+			Self::Schema(_, full) => {
+				// Update the table definition
+				def.full = *full;
+				let key = crate::key::database::tb::new(opt.ns(), opt.db(), tb);
+				run.set(key, &def).await?;
+				// Only a SCHEMAFULL table needs existing records cleaning
+				match full {
+					true => Some(Data::EmptyExpression),
+					false => None,
+				}
+			}
+		};
+		// Clear the cache
+		let key = crate::key::table::fd::prefix(opt.ns(), opt.db(), tb);
+		run.clr(key).await?;
+		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), tb);
+		run.clr(key).await?;
+		// Release the transaction
+		drop(run);
+		// Rewrite the existing records
+		if let Some(data) = data {
+			// Disable permissions
+			let opt = &opt.new_with_perms(false);
+			// Don't process field queries
+			let opt = &opt.new_with_fields(false);
+			// Don't process event queries
+			let opt = &opt.new_with_events(false);
+			// Only process index queries if needed
+			let opt = &opt.new_with_indexes(indexes);
+			// Process the table data
+			let stm = UpdateStatement {
+				what: Values(vec![Value::Table(tb.clone().into())]),
+				data: Some(data),
+				..UpdateStatement::default()
+			};
+			stm.compute(ctx, opt, txn, doc).await?;
+		}
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+/// Replaces the leading `from` parts of a field path with `to`
+fn rename(name: &Idiom, from: &Idiom, to: &Idiom) -> Idiom {
+	Idiom(to.iter().chain(name[from.len()..].iter()).cloned().collect())
+}
+
+impl Display for AlterStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::DropField(tb, fd) => write!(f, "ALTER TABLE {tb} DROP FIELD {fd}"),
+			Self::RenameField(tb, from, to) => {
+				write!(f, "ALTER TABLE {tb} RENAME FIELD {from} TO {to}")
+			}
+			Self::Schema(tb, true) => write!(f, "ALTER TABLE {tb} SCHEMAFULL"),
+			Self::Schema(tb, false) => write!(f, "ALTER TABLE {tb} SCHEMALESS"),
+		}
+	}
+}
+
+pub fn alter(i: &str) -> IResult<&str, AlterStatement> {
+	let (i, _) = tag_no_case("ALTER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TABLE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, tb) = ident(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(alt((|i| alter_drop(i, &tb), |i| alter_rename(i, &tb), |i| alter_schema(i, &tb))))(i)
+}
+
+fn alter_drop<'a>(i: &'a str, tb: &Ident) -> IResult<&'a str, AlterStatement> {
+	let (i, _) = tag_no_case("DROP")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("FIELD")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, fd) = idiom::local(i)?;
+	Ok((i, AlterStatement::DropField(tb.clone(), fd)))
+}
+
+fn alter_rename<'a>(i: &'a str, tb: &Ident) -> IResult<&'a str, AlterStatement> {
+	let (i, _) = tag_no_case("RENAME")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("FIELD")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, from) = idiom::local(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TO")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, to) = idiom::local(i)?;
+	Ok((i, AlterStatement::RenameField(tb.clone(), from, to)))
+}
+
+fn alter_schema<'a>(i: &'a str, tb: &Ident) -> IResult<&'a str, AlterStatement> {
+	let (i, full) = alt((
+		value(true, alt((tag_no_case("SCHEMAFULL"), tag_no_case("SCHEMAFUL")))),
+		value(false, tag_no_case("SCHEMALESS")),
+	))(i)?;
+	Ok((i, AlterStatement::Schema(tb.clone(), full)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn alter_drop_field() {
+		let sql = "ALTER TABLE person DROP FIELD name.first";
+		let res = alter(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn alter_rename_field() {
+		let sql = "ALTER TABLE person RENAME FIELD name TO fullname";
+		let res = alter(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn alter_schema() {
+		let sql = "ALTER TABLE person SCHEMAFUL";
+		let res = alter(sql);
+		let out = res.unwrap().1;
+		assert_eq!("ALTER TABLE person SCHEMAFULL", format!("{}", out));
+		let sql = "ALTER TABLE person SCHEMALESS";
+		let res = alter(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn alter_invalid() {
+		let sql = "ALTER TABLE person CHANGE FIELD name";
+		let res = alter(sql);
+		assert!(res.is_err());
+	}
+}
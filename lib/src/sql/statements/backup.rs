@@ -0,0 +1,111 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::value::Value;
+use crate::sql::Base;
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, value};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// BackupStatement is used to produce a backup of the current namespace or
+// database as SurrealQL, which can be imported to restore the backup.
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub enum BackupStatement {
+	Ns,
+	#[default]
+	Db,
+}
+
+impl BackupStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		let db = match self {
+			BackupStatement::Ns => {
+				opt.valid_for_ns()?;
+				opt.is_allowed(Action::View, ResourceKind::Any, &Base::Ns)?;
+				None
+			}
+			BackupStatement::Db => {
+				opt.valid_for_db()?;
+				opt.is_allowed(Action::View, ResourceKind::Any, &Base::Db)?;
+				Some(opt.db())
+			}
+		};
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Write the backup from the snapshot of the transaction
+		let (snd, rcv) = channel::unbounded();
+		run.backup(opt.ns(), db, snd).await?;
+		// Collect the backup into a string
+		let mut out = Vec::new();
+		while let Ok(v) = rcv.try_recv() {
+			out.extend(v);
+		}
+		// Ok all good
+		Ok(String::from_utf8_lossy(&out).into_owned().into())
+	}
+}
+
+impl fmt::Display for BackupStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Ns => f.write_str("BACKUP NAMESPACE"),
+			Self::Db => f.write_str("BACKUP DATABASE"),
+		}
+	}
+}
+
+pub fn backup(i: &str) -> IResult<&str, BackupStatement> {
+	let (i, _) = tag_no_case("BACKUP")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(alt((
+		value(BackupStatement::Ns, alt((tag_no_case("NAMESPACE"), tag_no_case("NS")))),
+		value(BackupStatement::Db, alt((tag_no_case("DATABASE"), tag_no_case("DB")))),
+	)))(i)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn backup_namespace() {
+		let sql = "BACKUP NS";
+		let res = backup(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, BackupStatement::Ns);
+		assert_eq!("BACKUP NAMESPACE", format!("{}", out));
+	}
+
+	#[test]
+	fn backup_database() {
+		let sql = "BACKUP DATABASE";
+		let res = backup(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, BackupStatement::Db);
+		assert_eq!(sql, format!("{}", out));
+	}
+
+	#[test]
+	fn backup_invalid() {
+		let sql = "BACKUP TABLE person";
+		backup(sql).unwrap_err();
+	}
+}
@@ -1,5 +1,6 @@
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::IResult;
+use crate::sql::isolation::{isolation, Isolation};
 use derive::Store;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::opt;
@@ -9,19 +10,41 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
-pub struct BeginStatement;
+#[revisioned(revision = 2)]
+pub struct BeginStatement {
+	#[revision(start = 2)]
+	pub isolation: Option<Isolation>,
+}
 
 impl fmt::Display for BeginStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		f.write_str("BEGIN TRANSACTION")
+		f.write_str("BEGIN TRANSACTION")?;
+		if let Some(ref v) = self.isolation {
+			write!(f, " ISOLATION LEVEL {v}")?
+		}
+		Ok(())
 	}
 }
 
 pub fn begin(i: &str) -> IResult<&str, BeginStatement> {
 	let (i, _) = tag_no_case("BEGIN")(i)?;
 	let (i, _) = opt(tuple((shouldbespace, tag_no_case("TRANSACTION"))))(i)?;
-	Ok((i, BeginStatement))
+	let (i, isolation) = opt(begin_isolation)(i)?;
+	Ok((
+		i,
+		BeginStatement {
+			isolation,
+		},
+	))
+}
+
+fn begin_isolation(i: &str) -> IResult<&str, Isolation> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ISOLATION")(i)?;
+	let (i, _) = opt(tuple((shouldbespace, tag_no_case("LEVEL"))))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = isolation(i)?;
+	Ok((i, v))
 }
 
 #[cfg(test)]
@@ -44,4 +67,21 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("BEGIN TRANSACTION", format!("{}", out))
 	}
+
+	#[test]
+	fn begin_isolation_level() {
+		let sql = "BEGIN TRANSACTION ISOLATION LEVEL SERIALIZABLE";
+		let res = begin(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.isolation, Some(Isolation::Serializable));
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn begin_isolation_short() {
+		let sql = "BEGIN ISOLATION SNAPSHOT";
+		let res = begin(sql);
+		let out = res.unwrap().1;
+		assert_eq!("BEGIN TRANSACTION ISOLATION LEVEL SNAPSHOT", format!("{}", out))
+	}
 }
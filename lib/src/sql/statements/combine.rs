@@ -0,0 +1,358 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::common::{closeparentheses, openparentheses};
+use crate::sql::error::IResult;
+use crate::sql::limit::{limit, Limit};
+use crate::sql::order::{order, Orders};
+use crate::sql::special::check_order_by_fields;
+use crate::sql::start::{start, Start};
+use crate::sql::statements::select::{select, SelectStatement};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, map, opt, value};
+use nom::multi::many1;
+use nom::sequence::{delimited, preceded};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct CombineStatement {
+	pub what: SelectStatement,
+	pub with: Vec<Combine>,
+	pub order: Option<Orders>,
+	pub limit: Option<Limit>,
+	pub start: Option<Start>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Combine {
+	pub kind: Combinator,
+	pub all: bool,
+	pub what: SelectStatement,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Combinator {
+	#[default]
+	Union,
+	Intersect,
+	Except,
+}
+
+impl Combinator {
+	/// Combine two result sets, removing any duplicate
+	/// values, unless all values should be retained
+	fn combine(&self, all: bool, lhs: Vec<Value>, rhs: Vec<Value>) -> Vec<Value> {
+		let res: Vec<Value> = match (self, all) {
+			// Append all of the values
+			(Self::Union, _) => lhs.into_iter().chain(rhs).collect(),
+			// Retain the values found in both result sets
+			(Self::Intersect, false) => {
+				let rhs: HashSet<Value> = rhs.into_iter().collect();
+				lhs.into_iter().filter(|v| rhs.contains(v)).collect()
+			}
+			// Retain each value as many times as it is found in both result sets
+			(Self::Intersect, true) => {
+				let mut rhs = counts(rhs);
+				lhs.into_iter().filter(|v| consume(&mut rhs, v)).collect()
+			}
+			// Retain the values which are not found in the second result set
+			(Self::Except, false) => {
+				let rhs: HashSet<Value> = rhs.into_iter().collect();
+				lhs.into_iter().filter(|v| !rhs.contains(v)).collect()
+			}
+			// Remove each value as many times as it is found in the second result set
+			(Self::Except, true) => {
+				let mut rhs = counts(rhs);
+				lhs.into_iter().filter(|v| !consume(&mut rhs, v)).collect()
+			}
+		};
+		match all {
+			true => res,
+			false => {
+				let mut seen = HashSet::new();
+				res.into_iter().filter(|v| seen.insert(v.clone())).collect()
+			}
+		}
+	}
+}
+
+// Count the number of times each value is found
+fn counts(v: Vec<Value>) -> HashMap<Value, usize> {
+	let mut res = HashMap::new();
+	for v in v {
+		*res.entry(v).or_insert(0) += 1;
+	}
+	res
+}
+
+// Remove a single occurence of a value, returning whether it was found
+fn consume(counts: &mut HashMap<Value, usize>, v: &Value) -> bool {
+	match counts.get_mut(v) {
+		Some(n) if *n > 0 => {
+			*n -= 1;
+			true
+		}
+		_ => false,
+	}
+}
+
+// Convert a SELECT statement output into a result set
+fn rows(v: Value) -> Vec<Value> {
+	match v {
+		Value::Array(v) => v.0,
+		Value::None => vec![],
+		v => vec![v],
+	}
+}
+
+impl CombineStatement {
+	/// Check if we require a writeable transaction
+	pub(crate) fn writeable(&self) -> bool {
+		self.what.writeable() || self.with.iter().any(|v| v.what.writeable())
+	}
+	/// Get the shortest timeout of the combined statements, if any
+	pub(crate) fn timeout(&self) -> Option<Duration> {
+		std::iter::once(&self.what)
+			.chain(self.with.iter().map(|v| &v.what))
+			.filter_map(|v| v.timeout.as_ref().map(|v| *v.0))
+			.min()
+	}
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Process the first result set
+		let mut res = rows(self.what.compute(ctx, opt, txn, doc).await?);
+		// Combine the subsequent result sets in order
+		for v in self.with.iter() {
+			let rhs = rows(v.what.compute(ctx, opt, txn, doc).await?);
+			res = v.kind.combine(v.all, res, rhs);
+		}
+		// Sort the combined result set
+		if let Some(orders) = &self.order {
+			res.sort_by(|a, b| {
+				for order in orders.iter() {
+					let o = match order.random {
+						true => {
+							let a = rand::random::<f64>();
+							let b = rand::random::<f64>();
+							a.partial_cmp(&b)
+						}
+						false => match order.direction {
+							true => a.compare(b, order, order.collate, order.numeric),
+							false => b.compare(a, order, order.collate, order.numeric),
+						},
+					};
+					match o {
+						Some(Ordering::Equal) | None => continue,
+						Some(o) => return o,
+					}
+				}
+				Ordering::Equal
+			});
+		}
+		// Skip the specified number of values
+		if let Some(v) = &self.start {
+			let v = v.process(ctx, opt, txn, doc).await?;
+			res = res.into_iter().skip(v).collect();
+		}
+		// Limit the number of values
+		if let Some(v) = &self.limit {
+			let v = v.process(ctx, opt, txn, doc).await?;
+			res.truncate(v);
+		}
+		// Output the combined result set
+		Ok(res.into())
+	}
+}
+
+impl Display for Combinator {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Union => f.write_str("UNION"),
+			Self::Intersect => f.write_str("INTERSECT"),
+			Self::Except => f.write_str("EXCEPT"),
+		}
+	}
+}
+
+impl Display for CombineStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		// Each statement is parenthesised, so that its clauses
+		// are not parsed as the clauses of the combined output
+		write!(f, "({})", self.what)?;
+		for v in self.with.iter() {
+			write!(f, " {}", v.kind)?;
+			if v.all {
+				f.write_str(" ALL")?
+			}
+			write!(f, " ({})", v.what)?;
+		}
+		if let Some(ref v) = self.order {
+			write!(f, " {v}")?
+		}
+		if let Some(ref v) = self.limit {
+			write!(f, " {v}")?
+		}
+		if let Some(ref v) = self.start {
+			write!(f, " {v}")?
+		}
+		Ok(())
+	}
+}
+
+pub fn combine(i: &str) -> IResult<&str, CombineStatement> {
+	let (i, (_, what)) = operand(i)?;
+	let (i, with) = many1(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, kind) = combinator(i)?;
+		let (i, all) = opt(preceded(shouldbespace, tag_no_case("ALL")))(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, (bare, what)) = cut(operand)(i)?;
+		Ok((
+			i,
+			(
+				bare,
+				Combine {
+					kind,
+					all: all.is_some(),
+					what,
+				},
+			),
+		))
+	})(i)?;
+	let (i, mut order) = opt(preceded(shouldbespace, order))(i)?;
+	check_order_by_fields(i, &what.expr, &order)?;
+	let (i, mut limit) = opt(preceded(shouldbespace, limit))(i)?;
+	let (i, mut start) = opt(preceded(shouldbespace, start))(i)?;
+	let (bare, mut with): (Vec<bool>, Vec<Combine>) = with.into_iter().unzip();
+	// The clauses of an unparenthesised last statement apply to the combined output
+	if let (Some(true), Some(v)) = (bare.last(), with.last_mut()) {
+		order = order.or_else(|| v.what.order.take());
+		limit = limit.or_else(|| v.what.limit.take());
+		start = start.or_else(|| v.what.start.take());
+	}
+	Ok((
+		i,
+		CombineStatement {
+			what,
+			with,
+			order,
+			limit,
+			start,
+		},
+	))
+}
+
+fn operand(i: &str) -> IResult<&str, (bool, SelectStatement)> {
+	alt((
+		map(select, |v| (true, v)),
+		map(delimited(openparentheses, select, closeparentheses), |v| (false, v)),
+	))(i)
+}
+
+fn combinator(i: &str) -> IResult<&str, Combinator> {
+	alt((
+		value(Combinator::Union, tag_no_case("UNION")),
+		value(Combinator::Intersect, tag_no_case("INTERSECT")),
+		value(Combinator::Except, tag_no_case("EXCEPT")),
+	))(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn combine_statement_union() {
+		let sql = "SELECT * FROM person UNION SELECT * FROM animal";
+		let res = combine(sql);
+		let out = res.unwrap().1;
+		assert_eq!("(SELECT * FROM person) UNION (SELECT * FROM animal)", format!("{}", out));
+		assert_eq!(out.with.len(), 1);
+	}
+
+	#[test]
+	fn combine_statement_all() {
+		let sql = "SELECT name FROM person INTERSECT ALL SELECT name FROM animal EXCEPT SELECT name FROM robot";
+		let res = combine(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			"(SELECT name FROM person) INTERSECT ALL (SELECT name FROM animal) EXCEPT (SELECT name FROM robot)",
+			format!("{}", out)
+		);
+		assert_eq!(out.with.len(), 2);
+		assert!(out.with[0].all);
+		assert_eq!(out.with[1].kind, Combinator::Except);
+	}
+
+	#[test]
+	fn combine_statement_clauses() {
+		let sql =
+			"SELECT name FROM person UNION SELECT name FROM animal ORDER BY name LIMIT 10 START 5";
+		let res = combine(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			"(SELECT name FROM person) UNION (SELECT name FROM animal) ORDER BY name LIMIT 10 START 5",
+			format!("{}", out)
+		);
+		assert!(out.order.is_some());
+		assert!(out.limit.is_some());
+		assert!(out.start.is_some());
+		assert!(out.with[0].what.order.is_none());
+	}
+
+	#[test]
+	fn combine_statement_parentheses() {
+		let sql =
+			"(SELECT name FROM person LIMIT 1) UNION (SELECT name FROM animal LIMIT 2) LIMIT 3";
+		let res = combine(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			"(SELECT name FROM person LIMIT 1) UNION (SELECT name FROM animal LIMIT 2) LIMIT 3",
+			format!("{}", out)
+		);
+		assert!(out.with[0].what.limit.is_some());
+		assert!(out.limit.is_some());
+	}
+
+	#[test]
+	fn combine_statement_display_parses() {
+		for sql in [
+			"SELECT * FROM person UNION SELECT * FROM animal",
+			"SELECT name FROM person ORDER BY name UNION ALL SELECT name FROM animal",
+			"(SELECT name FROM person LIMIT 1) EXCEPT (SELECT name FROM animal START 1) LIMIT 2",
+			"SELECT name FROM person INTERSECT SELECT name FROM animal ORDER BY name START 1",
+		] {
+			let out = combine(sql).unwrap().1;
+			let (rest, res) = combine(&out.to_string()).unwrap();
+			assert!(rest.is_empty());
+			assert_eq!(res, out);
+		}
+	}
+
+	#[test]
+	fn combine_statement_single() {
+		let sql = "SELECT * FROM person";
+		let res = combine(sql);
+		assert!(res.is_err());
+	}
+}
@@ -21,13 +21,15 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct CreateStatement {
 	pub what: Values,
 	pub data: Option<Data>,
 	pub output: Option<Output>,
 	pub timeout: Option<Timeout>,
 	pub parallel: bool,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl CreateStatement {
@@ -80,6 +82,9 @@ impl CreateStatement {
 impl fmt::Display for CreateStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "CREATE {}", self.what)?;
+		if self.if_not_exists {
+			f.write_str(" IF NOT EXISTS")?
+		}
 		if let Some(ref v) = self.data {
 			write!(f, " {v}")?
 		}
@@ -100,6 +105,7 @@ pub fn create(i: &str) -> IResult<&str, CreateStatement> {
 	let (i, _) = tag_no_case("CREATE")(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, what) = whats(i)?;
+	let (i, if_not_exists) = opt(preceded(shouldbespace, if_not_exists))(i)?;
 	let (i, (data, output, timeout, parallel)) = cut(|i| {
 		let (i, data) = opt(preceded(shouldbespace, data))(i)?;
 		let (i, output) = opt(preceded(shouldbespace, output))(i)?;
@@ -115,10 +121,20 @@ pub fn create(i: &str) -> IResult<&str, CreateStatement> {
 			output,
 			timeout,
 			parallel: parallel.is_some(),
+			if_not_exists: if_not_exists.is_some(),
 		},
 	))
 }
 
+fn if_not_exists(i: &str) -> IResult<&str, ()> {
+	let (i, _) = tag_no_case("IF")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("NOT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("EXISTS")(i)?;
+	Ok((i, ()))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -131,4 +147,13 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("CREATE test", format!("{}", out))
 	}
+
+	#[test]
+	fn create_statement_if_not_exists() {
+		let sql = "CREATE person:test IF NOT EXISTS SET name = 'Tester'";
+		let res = create(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert!(out.if_not_exists);
+	}
 }
@@ -46,17 +46,18 @@ impl DefineDatabaseStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::namespace::db::new(opt.ns(), &self.name);
-		let ns = run.add_ns(opt.ns(), opt.strict).await?;
-		// Set the id
-		if self.id.is_none() && ns.id.is_some() {
-			let mut db = self.clone();
-			db.id = Some(run.get_next_db_id(ns.id.unwrap()).await?);
-			// Store the db
-			run.set(key, db).await?;
-		} else {
-			// Store the db
-			run.set(key, self).await?;
+		run.add_ns(opt.ns(), opt.strict).await?;
+		let ns = run.ensure_ns_id(opt.ns()).await?;
+		// Set the id, keeping the id of any existing definition
+		let mut db = self.clone();
+		if db.id.is_none() {
+			db.id = match run.get_db(opt.ns(), &self.name).await.ok().and_then(|v| v.id) {
+				Some(id) => Some(id),
+				None => Some(run.get_next_db_id(ns).await?),
+			};
 		}
+		// Store the db
+		run.set(key, db).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
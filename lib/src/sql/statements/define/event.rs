@@ -14,21 +14,41 @@ use crate::sql::value::{value, values, Value, Values};
 use derive::Store;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
-use nom::combinator::opt;
+use nom::combinator::{map, opt};
 use nom::multi::many0;
-use nom::sequence::tuple;
+use nom::sequence::{preceded, tuple};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineEventStatement {
 	pub name: Ident,
 	pub what: Ident,
 	pub when: Value,
 	pub then: Values,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub trigger: Option<Trigger>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Trigger {
+	/// Runs before the document is stored, and can modify or reject the change
+	Before,
+	/// Runs once the transaction which made the change has been committed
+	After,
+}
+
+impl Display for Trigger {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Before => f.write_str("BEFORE"),
+			Self::After => f.write_str("AFTER"),
+		}
+	}
 }
 
 impl DefineEventStatement {
@@ -62,11 +82,11 @@ impl DefineEventStatement {
 
 impl Display for DefineEventStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(
-			f,
-			"DEFINE EVENT {} ON {} WHEN {} THEN {}",
-			self.name, self.what, self.when, self.then
-		)?;
+		write!(f, "DEFINE EVENT {} ON {}", self.name, self.what)?;
+		if let Some(ref v) = self.trigger {
+			write!(f, " {v}")?
+		}
+		write!(f, " WHEN {} THEN {}", self.when, self.then)?;
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
@@ -85,11 +105,13 @@ pub fn event(i: &str) -> IResult<&str, DefineEventStatement> {
 	let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, what) = ident(i)?;
+	let (i, trigger) = opt(preceded(shouldbespace, event_trigger))(i)?;
 	let (i, opts) = many0(event_opts)(i)?;
 	// Create the base statement
 	let mut res = DefineEventStatement {
 		name,
 		what,
+		trigger,
 		when: Value::Bool(true),
 		..Default::default()
 	};
@@ -115,6 +137,13 @@ pub fn event(i: &str) -> IResult<&str, DefineEventStatement> {
 	Ok((i, res))
 }
 
+fn event_trigger(i: &str) -> IResult<&str, Trigger> {
+	alt((
+		map(tag_no_case("BEFORE"), |_| Trigger::Before),
+		map(tag_no_case("AFTER"), |_| Trigger::After),
+	))(i)
+}
+
 enum DefineEventOption {
 	When(Value),
 	Then(Values),
@@ -148,3 +177,39 @@ fn event_comment(i: &str) -> IResult<&str, DefineEventOption> {
 	let (i, v) = strand(i)?;
 	Ok((i, DefineEventOption::Comment(v)))
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_event_statement() {
+		let sql = "DEFINE EVENT test ON user WHEN true THEN (CREATE log)";
+		let res = event(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.trigger, None);
+	}
+
+	#[test]
+	fn define_event_statement_before() {
+		let sql = "DEFINE EVENT test ON user BEFORE WHEN $event = 'CREATE' THEN { email: 'x' }";
+		let res = event(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.trigger, Some(Trigger::Before));
+	}
+
+	#[test]
+	fn define_event_statement_after() {
+		let sql = "DEFINE EVENT test ON TABLE user AFTER WHEN true THEN (CREATE log)";
+		let res = event(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			"DEFINE EVENT test ON user AFTER WHEN true THEN (CREATE log)",
+			format!("{}", out)
+		);
+		assert_eq!(out.trigger, Some(Trigger::After));
+	}
+}
@@ -63,6 +63,16 @@ impl DefineFieldStatement {
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
 		run.set(key, self).await?;
+		// Stop removing the data of a dropped field which overlaps this field
+		let beg = crate::key::table::dr::prefix(opt.ns(), opt.db(), &self.what);
+		let end = crate::key::table::dr::suffix(opt.ns(), opt.db(), &self.what);
+		for (k, v) in run.scan(beg..end, u32::MAX).await? {
+			if let Value::Idiom(v) = Value::from(v) {
+				if v.starts_with(&self.name) || self.name.starts_with(&v) {
+					run.del(k).await?;
+				}
+			}
+		}
 		// Clear the cache
 		let key = crate::key::table::fd::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
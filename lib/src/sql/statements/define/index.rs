@@ -7,12 +7,15 @@ use crate::iam::Action;
 use crate::iam::ResourceKind;
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
+use crate::sql::common::commas;
 use crate::sql::error::IResult;
+use crate::sql::function;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::idiom;
-use crate::sql::idiom::Idioms;
+use crate::sql::idiom::{Idiom, Idioms};
 use crate::sql::index;
-use crate::sql::index::Index;
+use crate::sql::index::{Collation, Index};
+use crate::sql::part::Part;
 use crate::sql::statements::UpdateStatement;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::value::{Value, Values};
@@ -20,20 +23,27 @@ use derive::Store;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::opt;
-use nom::multi::many0;
+use nom::multi::{many0, separated_list1};
 use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 4)]
 pub struct DefineIndexStatement {
 	pub name: Ident,
 	pub what: Ident,
 	pub cols: Idioms,
 	pub index: Index,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub building: bool,
+	#[revision(start = 3)]
+	pub collate: Option<Collation>,
+	/// The error which stopped the index from being built in the background
+	#[revision(start = 4)]
+	pub failure: Option<Strand>,
 }
 
 impl DefineIndexStatement {
@@ -56,7 +66,18 @@ impl DefineIndexStatement {
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
-		run.set(key, self).await?;
+		// Check if the table holds more than a single batch of records
+		let beg = crate::key::thing::prefix(opt.ns(), opt.db(), &self.what);
+		let end = crate::key::thing::suffix(opt.ns(), opt.db(), &self.what);
+		let building = run.scan(beg..end, opt.batch_size.saturating_add(1)).await?.len()
+			> opt.batch_size as usize;
+		// Populated tables are indexed in the background
+		let ix = DefineIndexStatement {
+			building,
+			failure: None,
+			..self.clone()
+		};
+		run.set(key, ix).await?;
 		// Remove the index data
 		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delp(key, u32::MAX).await?;
@@ -65,6 +86,14 @@ impl DefineIndexStatement {
 		run.clr(key).await?;
 		// Release the transaction
 		drop(run);
+		// Forget the progress of any previous build of the index
+		if let Some(rebuilds) = ctx.get_rebuilds() {
+			rebuilds.remove(opt.ns(), opt.db(), &self.what, &self.name);
+		}
+		// The index is populated by the datastore in the background
+		if building {
+			return Ok(Value::None);
+		}
 		// Force queries to run
 		let opt = &opt.new_with_force(true);
 		// Don't process field queries
@@ -90,6 +119,9 @@ impl Display for DefineIndexStatement {
 		if Index::Idx != self.index {
 			write!(f, " {}", self.index)?;
 		}
+		if let Some(ref v) = self.collate {
+			write!(f, " {v}")?
+		}
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
@@ -124,6 +156,9 @@ pub fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 			DefineIndexOption::Columns(v) => {
 				res.cols = v;
 			}
+			DefineIndexOption::Collate(v) => {
+				res.collate = Some(v);
+			}
 			DefineIndexOption::Comment(v) => {
 				res.comment = Some(v);
 			}
@@ -140,11 +175,12 @@ pub fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 enum DefineIndexOption {
 	Index(Index),
 	Columns(Idioms),
+	Collate(Collation),
 	Comment(Strand),
 }
 
 fn index_opts(i: &str) -> IResult<&str, DefineIndexOption> {
-	alt((index_kind, index_columns, index_comment))(i)
+	alt((index_kind, index_columns, index_collate, index_comment))(i)
 }
 
 fn index_kind(i: &str) -> IResult<&str, DefineIndexOption> {
@@ -157,8 +193,25 @@ fn index_columns(i: &str) -> IResult<&str, DefineIndexOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = alt((tag_no_case("COLUMNS"), tag_no_case("FIELDS")))(i)?;
 	let (i, _) = shouldbespace(i)?;
-	let (i, v) = idiom::locals(i)?;
-	Ok((i, DefineIndexOption::Columns(v)))
+	let (i, v) = separated_list1(commas, index_column)(i)?;
+	Ok((i, DefineIndexOption::Columns(Idioms(v))))
+}
+
+/// A field, or a function computed from the fields of the record
+fn index_column(i: &str) -> IResult<&str, Idiom> {
+	alt((
+		|i| {
+			let (i, f) = function::normal(i)?;
+			Ok((i, Idiom::from(vec![Part::Start(Value::from(f))])))
+		},
+		idiom::local,
+	))(i)
+}
+
+fn index_collate(i: &str) -> IResult<&str, DefineIndexOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = index::collate(i)?;
+	Ok((i, DefineIndexOption::Collate(v)))
 }
 
 fn index_comment(i: &str) -> IResult<&str, DefineIndexOption> {
@@ -173,11 +226,14 @@ fn index_comment(i: &str) -> IResult<&str, DefineIndexOption> {
 mod tests {
 
 	use super::*;
-	use crate::sql::index::SearchParams;
+	use crate::sql::index::{Distance, HnswParams, Quantization, SearchParams};
+	use crate::sql::language::Language;
+	use crate::sql::Function;
 	use crate::sql::Ident;
 	use crate::sql::Idiom;
 	use crate::sql::Idioms;
 	use crate::sql::Index;
+	use crate::sql::Number;
 	use crate::sql::Part;
 	use crate::sql::Scoring;
 
@@ -193,6 +249,9 @@ mod tests {
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Idx,
 				comment: None,
+				building: false,
+				collate: None,
+				failure: None,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col");
@@ -210,6 +269,9 @@ mod tests {
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Uniq,
 				comment: None,
+				building: false,
+				collate: None,
+				failure: None,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE");
@@ -232,12 +294,16 @@ mod tests {
 						k1: 1.2,
 						b: 0.75,
 					},
+					boosts: vec![],
 					doc_ids_order: 1000,
 					doc_lengths_order: 1000,
 					postings_order: 1000,
 					terms_order: 1000,
 				}),
 				comment: None,
+				building: false,
+				collate: None,
+				failure: None,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col SEARCH ANALYZER my_analyzer BM25(1.2,0.75) DOC_IDS_ORDER 1000 DOC_LENGTHS_ORDER 1000 POSTINGS_ORDER 1000 TERMS_ORDER 1000 HIGHLIGHTS");
@@ -258,12 +324,16 @@ mod tests {
 					az: Ident("my_analyzer".to_string()),
 					hl: false,
 					sc: Scoring::Vs,
+					boosts: vec![],
 					doc_ids_order: 100,
 					doc_lengths_order: 100,
 					postings_order: 100,
 					terms_order: 100,
 				}),
 				comment: None,
+				building: false,
+				collate: None,
+				failure: None,
 			}
 		);
 		assert_eq!(
@@ -271,4 +341,152 @@ mod tests {
 			"DEFINE INDEX my_index ON my_table FIELDS my_col SEARCH ANALYZER my_analyzer VS DOC_IDS_ORDER 100 DOC_LENGTHS_ORDER 100 POSTINGS_ORDER 100 TERMS_ORDER 100"
 		);
 	}
+
+	#[test]
+	fn check_create_search_index_with_boosts() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS title, content SEARCH ANALYZER my_analyzer BM25(1.5,0.5) BOOSTS 2.5, 1";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx.index,
+			Index::Search(SearchParams {
+				az: Ident("my_analyzer".to_string()),
+				hl: false,
+				sc: Scoring::Bm {
+					k1: 1.5,
+					b: 0.5,
+				},
+				boosts: vec![Number::Float(2.5), Number::Int(1)],
+				doc_ids_order: 100,
+				doc_lengths_order: 100,
+				postings_order: 100,
+				terms_order: 100,
+			})
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS title, content SEARCH ANALYZER my_analyzer BM25(1.5,0.5) BOOSTS 2.5f, 1 DOC_IDS_ORDER 100 DOC_LENGTHS_ORDER 100 POSTINGS_ORDER 100 TERMS_ORDER 100"
+		);
+	}
+
+	#[test]
+	fn check_create_search_index_with_invalid_boosts() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS title, content SEARCH ANALYZER my_analyzer BM25(1.5,0.5) BOOSTS 2, 0";
+		assert!(index(sql).is_err());
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS title, content SEARCH ANALYZER my_analyzer BM25(1.5,0.5) BOOSTS -1, 1";
+		assert!(index(sql).is_err());
+	}
+
+	#[test]
+	fn check_create_hnsw_index() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS embedding HNSW DIMENSION 768 DIST COSINE M 16 EFC 200";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("embedding".to_string()))])]),
+				index: Index::Hnsw(HnswParams {
+					dimension: 768,
+					distance: Distance::Cosine,
+					m: 16,
+					ef_construction: 200,
+					doc_ids_order: 100,
+					quantization: None,
+				}),
+				comment: None,
+				building: false,
+				collate: None,
+				failure: None,
+			}
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS embedding HNSW DIMENSION 768 DIST COSINE M 16 EFC 200 DOC_IDS_ORDER 100"
+		);
+	}
+
+	#[test]
+	fn check_create_quantized_hnsw_index() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS embedding HNSW DIMENSION 768 QUANTIZATION PRODUCT 96";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx.index,
+			Index::Hnsw(HnswParams {
+				dimension: 768,
+				distance: Distance::Euclidean,
+				m: 12,
+				ef_construction: 150,
+				doc_ids_order: 100,
+				quantization: Some(Quantization::Product(96)),
+			})
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS embedding HNSW DIMENSION 768 DIST EUCLIDEAN M 12 EFC 150 DOC_IDS_ORDER 100 QUANTIZATION PRODUCT 96"
+		);
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS embedding HNSW DIMENSION 4 QUANTIZATION SCALAR";
+		let (_, idx) = index(sql).unwrap();
+		assert!(idx.to_string().ends_with("QUANTIZATION SCALAR"));
+	}
+
+	#[test]
+	fn check_create_trigram_index() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS name TRIGRAM";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(idx.index, Index::Trigram);
+		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS name TRIGRAM");
+	}
+
+	#[test]
+	fn check_create_computed_index() {
+		let sql =
+			"DEFINE INDEX my_index ON TABLE my_table FIELDS string::lowercase(email), age UNIQUE";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx.cols,
+			Idioms(vec![
+				Idiom(vec![Part::Start(Value::from(Function::Normal(
+					"string::lowercase".to_string(),
+					vec![Value::Idiom(Idiom::from("email".to_string()))]
+				)))]),
+				Idiom(vec![Part::Field(Ident("age".to_string()))]),
+			])
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS string::lowercase(email), age UNIQUE"
+		);
+	}
+	#[test]
+	fn check_create_collated_index() {
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS email UNIQUE COLLATE";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx.collate,
+			Some(Collation {
+				nocase: true,
+				noaccent: true,
+				language: None,
+			})
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS email UNIQUE COLLATE NOCASE NOACCENT"
+		);
+		let sql = "DEFINE INDEX my_index ON TABLE my_table FIELDS name COLLATE NOCASE LANGUAGE TURKISH COMMENT 'names'";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx.collate,
+			Some(Collation {
+				nocase: true,
+				noaccent: false,
+				language: Some(Language::Turkish),
+			})
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS name COLLATE NOCASE LANGUAGE TURKISH COMMENT 'names'"
+		);
+	}
 }
@@ -7,19 +7,21 @@ mod index;
 mod namespace;
 mod param;
 mod scope;
+mod sequence;
 mod table;
 mod token;
 mod user;
 
 pub use analyzer::{analyzer, DefineAnalyzerStatement};
 pub use database::{database, DefineDatabaseStatement};
-pub use event::{event, DefineEventStatement};
+pub use event::{event, DefineEventStatement, Trigger};
 pub use field::{field, DefineFieldStatement};
 pub use function::{function, DefineFunctionStatement};
 pub use index::{index, DefineIndexStatement};
 pub use namespace::{namespace, DefineNamespaceStatement};
 pub use param::{param, DefineParamStatement};
 pub use scope::{scope, DefineScopeStatement};
+pub use sequence::{sequence, DefineSequenceStatement};
 pub use table::{table, DefineTableStatement};
 pub use token::{token, DefineTokenStatement};
 pub use user::{user, DefineUserStatement};
@@ -53,6 +55,7 @@ pub enum DefineStatement {
 	Field(DefineFieldStatement),
 	Index(DefineIndexStatement),
 	User(DefineUserStatement),
+	Sequence(DefineSequenceStatement),
 }
 
 impl DefineStatement {
@@ -81,6 +84,7 @@ impl DefineStatement {
 			Self::Index(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::User(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Sequence(ref v) => v.compute(ctx, opt, txn, doc).await,
 		}
 	}
 }
@@ -100,6 +104,7 @@ impl Display for DefineStatement {
 			Self::Field(v) => Display::fmt(v, f),
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
+			Self::Sequence(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -118,6 +123,7 @@ pub fn define(i: &str) -> IResult<&str, DefineStatement> {
 		map(field, DefineStatement::Field),
 		map(index, DefineStatement::Index),
 		map(analyzer, DefineStatement::Analyzer),
+		map(sequence, DefineStatement::Sequence),
 	))(i)
 }
 
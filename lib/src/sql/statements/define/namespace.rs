@@ -44,14 +44,15 @@ impl DefineNamespaceStatement {
 		let mut run = txn.lock().await;
 		// Clear the cache
 		run.clear_cache();
-		// Set the id
-		if self.id.is_none() {
-			let mut ns = self.clone();
-			ns.id = Some(run.get_next_ns_id().await?);
-			run.set(key, ns).await?;
-		} else {
-			run.set(key, self).await?;
+		// Set the id, keeping the id of any existing definition
+		let mut ns = self.clone();
+		if ns.id.is_none() {
+			ns.id = match run.get_ns(&self.name).await.ok().and_then(|v| v.id) {
+				Some(id) => Some(id),
+				None => Some(run.get_next_ns_id().await?),
+			};
 		}
+		run.set(key, ns).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -0,0 +1,155 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::number::integer;
+use crate::sql::strand::{strand, Strand};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::multi::many0;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct DefineSequenceStatement {
+	pub name: Ident,
+	pub start: Option<i64>,
+	pub comment: Option<Strand>,
+}
+
+impl DefineSequenceStatement {
+	/// The first value which is returned by this sequence
+	pub(crate) fn first(&self) -> i64 {
+		self.start.unwrap_or(1)
+	}
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Sequence, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Process the statement
+		let key = crate::key::database::sq::new(opt.ns(), opt.db(), &self.name);
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		run.set(key, self).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for DefineSequenceStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE SEQUENCE {}", self.name)?;
+		if let Some(v) = self.start {
+			write!(f, " START {v}")?
+		}
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		Ok(())
+	}
+}
+
+pub fn sequence(i: &str) -> IResult<&str, DefineSequenceStatement> {
+	let (i, _) = tag_no_case("DEFINE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SEQUENCE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	let (i, opts) = many0(sequence_opts)(i)?;
+	// Create the base statement
+	let mut res = DefineSequenceStatement {
+		name,
+		..Default::default()
+	};
+	// Assign any defined options
+	for opt in opts {
+		match opt {
+			DefineSequenceOption::Start(v) => {
+				res.start = Some(v);
+			}
+			DefineSequenceOption::Comment(v) => {
+				res.comment = Some(v);
+			}
+		}
+	}
+	// Return the statement
+	Ok((i, res))
+}
+
+enum DefineSequenceOption {
+	Start(i64),
+	Comment(Strand),
+}
+
+fn sequence_opts(i: &str) -> IResult<&str, DefineSequenceOption> {
+	alt((sequence_start, sequence_comment))(i)
+}
+
+fn sequence_start(i: &str) -> IResult<&str, DefineSequenceOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("START")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = integer(i)?;
+	Ok((i, DefineSequenceOption::Start(v)))
+}
+
+fn sequence_comment(i: &str) -> IResult<&str, DefineSequenceOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = strand(i)?;
+	Ok((i, DefineSequenceOption::Comment(v)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_sequence_statement() {
+		let sql = "DEFINE SEQUENCE invoice START 1000 COMMENT 'Invoice numbers'";
+		let res = sequence(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.first(), 1000);
+	}
+
+	#[test]
+	fn define_sequence_statement_negative_start() {
+		let sql = "DEFINE SEQUENCE offset START -10";
+		let res = sequence(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.first(), -10);
+	}
+
+	#[test]
+	fn define_sequence_statement_default_start() {
+		let sql = "DEFINE SEQUENCE invoice";
+		let res = sequence(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.first(), 1);
+	}
+}
@@ -1,21 +1,25 @@
 use crate::ctx::Context;
 use crate::dbs::Options;
 use crate::dbs::Transaction;
-use crate::doc::CursorDoc;
+use crate::dbs::Workable;
+use crate::doc::{backfill_expiry, CursorDoc, Document};
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::key::thing;
+use crate::kvs::Scanner;
 use crate::sql::base::Base;
 use crate::sql::changefeed::{changefeed, ChangeFeed};
 use crate::sql::comment::shouldbespace;
+use crate::sql::duration::{duration, Duration};
 use crate::sql::error::IResult;
 use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::permission::{permissions, Permissions};
-use crate::sql::statements::UpdateStatement;
 use crate::sql::strand::{strand, Strand};
-use crate::sql::value::{Value, Values};
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
 use crate::sql::view::{view, View};
 use derive::Store;
 use nom::branch::alt;
@@ -26,7 +30,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 3)]
 pub struct DefineTableStatement {
 	pub id: Option<u32>,
 	pub name: Ident,
@@ -35,7 +39,11 @@ pub struct DefineTableStatement {
 	pub view: Option<View>,
 	pub permissions: Permissions,
 	pub changefeed: Option<ChangeFeed>,
+	#[revision(start = 2)]
+	pub ttl: Option<Duration>,
 	pub comment: Option<Strand>,
+	#[revision(start = 3)]
+	pub interned: bool,
 }
 
 impl DefineTableStatement {
@@ -44,7 +52,7 @@ impl DefineTableStatement {
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
-		doc: Option<&CursorDoc<'_>>,
+		_doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
 		// Allowed to run?
 		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
@@ -54,14 +62,40 @@ impl DefineTableStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::database::tb::new(opt.ns(), opt.db(), &self.name);
-		let ns = run.add_ns(opt.ns(), opt.strict).await?;
-		let db = run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-		if self.id.is_none() && ns.id.is_some() && db.id.is_some() {
-			let mut tb = self.clone();
-			tb.id = Some(run.get_next_tb_id(ns.id.unwrap(), db.id.unwrap()).await?);
-			run.set(key, tb).await?;
-		} else {
-			run.set(key, self).await?;
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		let (ns, db) = run.ensure_db_id(opt.ns(), opt.db()).await?;
+		// Set the id, keeping the id and key prefix of any existing definition
+		let mut tb = self.clone();
+		if tb.id.is_none() {
+			match run.get_tb(opt.ns(), opt.db(), &self.name).await.ok() {
+				Some(v) if v.id.is_some() => {
+					tb.id = v.id;
+					tb.interned = v.interned;
+				}
+				_ => {
+					tb.id = Some(run.get_next_tb_id(ns, db).await?);
+					tb.interned = run.can_intern_tb(opt.ns(), opt.db(), &self.name).await?;
+				}
+			}
+		}
+		run.set(key, tb).await?;
+		// Queue the existing records for purging
+		if let Some(ttl) = &self.ttl {
+			let beg = thing::prefix(opt.ns(), opt.db(), &self.name);
+			let end = thing::suffix(opt.ns(), opt.db(), &self.name);
+			let mut scanner = Scanner::new(beg..end, opt.batch_size);
+			loop {
+				let res = scanner.next(&mut run).await?;
+				if res.is_empty() {
+					break;
+				}
+				for (k, _) in res.iter() {
+					let key: thing::Thing = k.into();
+					let rid = Thing::from((key.tb, key.id));
+					backfill_expiry(opt, &mut run, &rid, ttl).await?;
+				}
+			}
 		}
 		// Check if table is a view
 		if let Some(view) = &self.view {
@@ -79,26 +113,74 @@ impl DefineTableStatement {
 			}
 			// Release the transaction
 			drop(run);
-			// Force queries to run
-			let opt = &opt.new_with_force(true);
-			// Don't process field queries
-			let opt = &opt.new_with_fields(false);
-			// Don't process event queries
-			let opt = &opt.new_with_events(false);
-			// Don't process index queries
-			let opt = &opt.new_with_indexes(false);
+			// Populate the view from the source tables
+			self.populate(ctx, opt, txn).await?;
+		}
+		// Ok all good
+		Ok(Value::None)
+	}
+	/// Clear the records in this view and recalculate
+	/// them from the records in the source tables
+	pub(crate) async fn refresh(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<(), Error> {
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Remove the record data
+		let beg = crate::key::thing::prefix(opt.ns(), opt.db(), &self.name);
+		let end = crate::key::thing::suffix(opt.ns(), opt.db(), &self.name);
+		run.delr(beg..end, u32::MAX).await?;
+		// Remove the index data, keeping the index definitions
+		for ix in run.all_tb_indexes(opt.ns(), opt.db(), &self.name).await?.iter() {
+			let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.name, &ix.name);
+			run.delp(key, u32::MAX).await?;
+		}
+		// Release the transaction
+		drop(run);
+		// Populate the view from the source tables
+		self.populate(ctx, opt, txn).await
+	}
+	/// Add the records in the source tables to this view
+	async fn populate(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<(), Error> {
+		// Check if table is a view
+		if let Some(view) = &self.view {
 			// Process each foreign table
-			for v in view.what.0.iter() {
-				// Process the view data
-				let stm = UpdateStatement {
-					what: Values(vec![Value::Table(v.clone())]),
-					..UpdateStatement::default()
-				};
-				stm.compute(ctx, opt, txn, doc).await?;
+			for tb in view.what.0.iter() {
+				let beg = thing::prefix(opt.ns(), opt.db(), tb);
+				let end = thing::suffix(opt.ns(), opt.db(), tb);
+				let mut scanner = Scanner::new(beg..end, opt.batch_size);
+				loop {
+					// Check if the context is finished
+					if ctx.is_done() {
+						return Ok(());
+					}
+					// Get the next batch of records
+					let res = scanner.next(&mut *txn.lock().await).await?;
+					if res.is_empty() {
+						break;
+					}
+					for (k, v) in res.iter() {
+						// Parse the data from the store
+						let key: thing::Thing = k.into();
+						let val: Value = v.into();
+						let rid = Thing::from((key.tb, key.id));
+						// Add the record to the view
+						let doc = Document::new(None, Some(&rid), None, &val, Workable::Normal);
+						doc.populate(ctx, opt, txn, self).await?;
+					}
+				}
 			}
 		}
 		// Ok all good
-		Ok(Value::None)
+		Ok(())
 	}
 }
 
@@ -122,6 +204,9 @@ impl Display for DefineTableStatement {
 		if let Some(ref v) = self.changefeed {
 			write!(f, " {v}")?;
 		}
+		if let Some(ref v) = self.ttl {
+			write!(f, " TTL {v}")?;
+		}
 		if !self.permissions.is_full() {
 			let _indent = if is_pretty() {
 				Some(pretty_indent())
@@ -168,6 +253,9 @@ pub fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 			DefineTableOption::ChangeFeed(v) => {
 				res.changefeed = Some(v);
 			}
+			DefineTableOption::Ttl(v) => {
+				res.ttl = Some(v);
+			}
 			DefineTableOption::Permissions(v) => {
 				res.permissions = v;
 			}
@@ -185,6 +273,7 @@ enum DefineTableOption {
 	Comment(Strand),
 	Permissions(Permissions),
 	ChangeFeed(ChangeFeed),
+	Ttl(Duration),
 }
 
 fn table_opts(i: &str) -> IResult<&str, DefineTableOption> {
@@ -196,6 +285,7 @@ fn table_opts(i: &str) -> IResult<&str, DefineTableOption> {
 		table_schemafull,
 		table_permissions,
 		table_changefeed,
+		table_ttl,
 	))(i)
 }
 
@@ -211,6 +301,14 @@ fn table_changefeed(i: &str) -> IResult<&str, DefineTableOption> {
 	Ok((i, DefineTableOption::ChangeFeed(v)))
 }
 
+fn table_ttl(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TTL")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = duration(i)?;
+	Ok((i, DefineTableOption::Ttl(v)))
+}
+
 fn table_view(i: &str) -> IResult<&str, DefineTableOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, v) = view(i)?;
@@ -259,4 +357,17 @@ mod tests {
 		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
 		assert_eq!(out, deserialized);
 	}
+
+	#[test]
+	fn define_table_with_ttl() {
+		let sql = "DEFINE TABLE session SCHEMALESS TTL 30d";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.ttl, Some(Duration::from_days(30)));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
 }
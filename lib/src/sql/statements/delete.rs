@@ -8,7 +8,11 @@ use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
 use crate::sql::error::IResult;
+use crate::sql::limit::{limit, Limit};
+use crate::sql::order::{order, Orders};
 use crate::sql::output::{output, Output};
+use crate::sql::paths::ID;
+use crate::sql::statements::SelectStatement;
 use crate::sql::timeout::{timeout, Timeout};
 use crate::sql::value::{whats, Value, Values};
 use derive::Store;
@@ -22,13 +26,17 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DeleteStatement {
 	pub what: Values,
 	pub cond: Option<Cond>,
 	pub output: Option<Output>,
 	pub timeout: Option<Timeout>,
 	pub parallel: bool,
+	#[revision(start = 2)]
+	pub order: Option<Orders>,
+	#[revision(start = 2)]
+	pub limit: Option<Limit>,
 }
 
 impl DeleteStatement {
@@ -60,17 +68,28 @@ impl DeleteStatement {
 		let stm = Statement::from(self);
 		// Ensure futures are stored
 		let opt = &opt.new_with_futures(false).with_projections(false);
-		// Loop over the delete targets
-		for w in self.what.0.iter() {
-			let v = w.compute(ctx, opt, txn, doc).await?;
-			i.prepare(ctx, opt, txn, &stm, v).await.map_err(|e| match e {
-				Error::InvalidStatementTarget {
-					value: v,
-				} => Error::DeleteStatement {
-					value: v,
-				},
-				e => e,
-			})?;
+		// Check if the targets are ordered or limited
+		match self.order.is_some() || self.limit.is_some() {
+			// Select the records to delete in order, up to the limit
+			true => {
+				let sel = SelectStatement::ids(&self.what, &self.cond, &self.order, &self.limit);
+				let v = sel.compute(ctx, &opt.new_with_perms(false), txn, doc).await?;
+				i.prepare(ctx, opt, txn, &stm, v.pick(ID.as_ref())).await?;
+			}
+			// Loop over the delete targets
+			false => {
+				for w in self.what.0.iter() {
+					let v = w.compute(ctx, opt, txn, doc).await?;
+					i.prepare(ctx, opt, txn, &stm, v).await.map_err(|e| match e {
+						Error::InvalidStatementTarget {
+							value: v,
+						} => Error::DeleteStatement {
+							value: v,
+						},
+						e => e,
+					})?;
+				}
+			}
 		}
 		// Output the results
 		i.output(ctx, opt, txn, &stm).await
@@ -83,6 +102,12 @@ impl fmt::Display for DeleteStatement {
 		if let Some(ref v) = self.cond {
 			write!(f, " {v}")?
 		}
+		if let Some(ref v) = self.order {
+			write!(f, " {v}")?
+		}
+		if let Some(ref v) = self.limit {
+			write!(f, " {v}")?
+		}
 		if let Some(ref v) = self.output {
 			write!(f, " {v}")?
 		}
@@ -101,12 +126,14 @@ pub fn delete(i: &str) -> IResult<&str, DeleteStatement> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = opt(terminated(tag_no_case("FROM"), shouldbespace))(i)?;
 	let (i, what) = whats(i)?;
-	let (i, (cond, output, timeout, parallel)) = cut(|i| {
+	let (i, (cond, order, limit, output, timeout, parallel)) = cut(|i| {
 		let (i, cond) = opt(preceded(shouldbespace, cond))(i)?;
+		let (i, order) = opt(preceded(shouldbespace, order))(i)?;
+		let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
 		let (i, output) = opt(preceded(shouldbespace, output))(i)?;
 		let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
 		let (i, parallel) = opt(preceded(shouldbespace, tag_no_case("PARALLEL")))(i)?;
-		Ok((i, (cond, output, timeout, parallel)))
+		Ok((i, (cond, order, limit, output, timeout, parallel)))
 	})(i)?;
 	Ok((
 		i,
@@ -116,6 +143,8 @@ pub fn delete(i: &str) -> IResult<&str, DeleteStatement> {
 			output,
 			timeout,
 			parallel: parallel.is_some(),
+			order,
+			limit,
 		},
 	))
 }
@@ -132,4 +161,14 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("DELETE test", format!("{}", out))
 	}
+
+	#[test]
+	fn delete_statement_order_limit() {
+		let sql = "DELETE logs WHERE level = 'debug' ORDER BY time LIMIT 1000 RETURN NONE";
+		let res = delete(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert!(out.order.is_some());
+		assert!(out.limit.is_some());
+	}
 }
@@ -40,6 +40,8 @@ impl ForeachStatement {
 			Value::Array(arr) => {
 				// Loop over the values
 				'foreach: for v in arr.iter() {
+					// Check if the context is finished
+					ctx.check()?;
 					// Duplicate context
 					let mut ctx = Context::new(ctx);
 					// Set the current parameter
@@ -5,6 +5,7 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::sql::array::Array;
 use crate::sql::base::base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::IResult;
@@ -17,6 +18,7 @@ use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
+use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -30,13 +32,17 @@ pub enum InfoStatement {
 	Sc(Ident),
 	Tb(Ident),
 	User(Ident, Option<Base>),
+	Sessions,
+	Stats,
+	Ix(Ident, Ident),
+	TbStats(Ident),
 }
 
 impl InfoStatement {
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
-		_ctx: &Context<'_>,
+		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
 		_doc: Option<&CursorDoc<'_>>,
@@ -62,6 +68,10 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("users".to_owned(), tmp.into());
+				// Process the status of the storage compactions
+				if let Some(compactions) = ctx.get_compactions() {
+					res.insert("compaction".to_owned(), compactions.info());
+				}
 				// Ok all good
 				Value::from(res).ok()
 			}
@@ -130,6 +140,12 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("scopes".to_owned(), tmp.into());
+				// Process the sequences
+				let mut tmp = Object::default();
+				for v in run.all_db_sequences(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+				}
+				res.insert("sequences".to_owned(), tmp.into());
 				// Process the tables
 				let mut tmp = Object::default();
 				for v in run.all_tb(opt.ns(), opt.db()).await?.iter() {
@@ -212,6 +228,71 @@ impl InfoStatement {
 				// Ok all good
 				Value::from(res.to_string()).ok()
 			}
+			InfoStatement::Sessions => {
+				// Allowed to run?
+				opt.is_allowed(Action::View, ResourceKind::Any, &Base::Root)?;
+				// Process the connected sessions
+				match ctx.get_sessions() {
+					Some(sessions) => sessions.info().ok(),
+					None => Value::from(Array::default()).ok(),
+				}
+			}
+			InfoStatement::Stats => {
+				// Allowed to run?
+				opt.is_allowed(Action::View, ResourceKind::Any, &Base::Db)?;
+				// Process the table access statistics
+				match ctx.get_stats() {
+					Some(stats) => stats.info(opt.ns(), opt.db()).ok(),
+					None => Value::from(Object::default()).ok(),
+				}
+			}
+			InfoStatement::Ix(ix, tb) => {
+				// Allowed to run?
+				opt.is_allowed(Action::View, ResourceKind::Any, &Base::Db)?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Ensure the index exists
+				let def = run.get_and_cache_tb_index(opt.ns(), opt.db(), tb, ix).await?;
+				// Create the result set
+				let mut res = Object::default();
+				// Process the progress of any index rebuild
+				if let Some(rebuilds) = ctx.get_rebuilds() {
+					let tmp = rebuilds.info(opt.ns(), opt.db(), tb, ix);
+					if tmp.is_some() {
+						res.insert("building".to_owned(), tmp);
+					}
+				}
+				// Process an index whose build has failed, on this or another node
+				if let (true, Some(err)) = (def.building, &def.failure) {
+					let mut tmp = match res.remove("building") {
+						Some(Value::Object(v)) => v,
+						_ => Object::default(),
+					};
+					tmp.insert("status".to_owned(), Value::from("failed"));
+					tmp.insert("error".to_owned(), Value::from(err.clone()));
+					res.insert("building".to_owned(), Value::from(tmp));
+				}
+				// Process an index which is waiting to be built in the background
+				if def.building && !res.contains_key("building") {
+					let tmp = Value::from(map! {
+						"status".to_string() => Value::from("pending"),
+					});
+					res.insert("building".to_owned(), tmp);
+				}
+				// Ok all good
+				Value::from(res).ok()
+			}
+			InfoStatement::TbStats(tb) => {
+				// Allowed to run?
+				opt.is_allowed(Action::View, ResourceKind::Any, &Base::Db)?;
+				// Ensure the table exists
+				txn.lock().await.get_tb(opt.ns(), opt.db(), tb).await?;
+				// Process the storage used by the table
+				match ctx.get_usage() {
+					Some(usage) => usage.info(opt.ns(), opt.db(), tb).ok(),
+					None => Value::None.ok(),
+				}
+			}
 		}
 	}
 }
@@ -228,6 +309,10 @@ impl fmt::Display for InfoStatement {
 				Some(ref b) => write!(f, "INFO FOR USER {u} ON {b}"),
 				None => write!(f, "INFO FOR USER {u}"),
 			},
+			Self::Sessions => f.write_str("INFO FOR SESSIONS"),
+			Self::Stats => f.write_str("INFO FOR STATS"),
+			Self::Ix(ref i, ref t) => write!(f, "INFO FOR INDEX {i} ON {t}"),
+			Self::TbStats(ref t) => write!(f, "INFO FOR TABLE {t} STATS"),
 		}
 	}
 }
@@ -238,7 +323,7 @@ pub fn info(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = tag_no_case("FOR")(i)?;
 	cut(|i| {
 		let (i, _) = shouldbespace(i)?;
-		alt((root, ns, db, sc, tb, user))(i)
+		alt((root, ns, db, sc, tb, user, sessions, stats, ix))(i)
 	})(i)
 }
 
@@ -257,6 +342,16 @@ fn db(i: &str) -> IResult<&str, InfoStatement> {
 	Ok((i, InfoStatement::Db))
 }
 
+fn sessions(i: &str) -> IResult<&str, InfoStatement> {
+	let (i, _) = tag_no_case("SESSIONS")(i)?;
+	Ok((i, InfoStatement::Sessions))
+}
+
+fn stats(i: &str) -> IResult<&str, InfoStatement> {
+	let (i, _) = tag_no_case("STATS")(i)?;
+	Ok((i, InfoStatement::Stats))
+}
+
 fn sc(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = alt((tag_no_case("SCOPE"), tag_no_case("SC")))(i)?;
 	let (i, _) = shouldbespace(i)?;
@@ -271,7 +366,25 @@ fn tb(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = shouldbespace(i)?;
 	cut(|i| {
 		let (i, table) = ident(i)?;
-		Ok((i, InfoStatement::Tb(table)))
+		let (i, stats) = opt(tuple((shouldbespace, tag_no_case("STATS"))))(i)?;
+		match stats {
+			Some(_) => Ok((i, InfoStatement::TbStats(table))),
+			None => Ok((i, InfoStatement::Tb(table))),
+		}
+	})(i)
+}
+
+fn ix(i: &str) -> IResult<&str, InfoStatement> {
+	let (i, _) = tag_no_case("INDEX")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, index) = ident(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("ON")(i)?;
+		let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, table) = ident(i)?;
+		Ok((i, InfoStatement::Ix(index, table)))
 	})(i)
 }
 
@@ -344,6 +457,15 @@ mod tests {
 		assert_eq!("INFO FOR TABLE test", format!("{}", out));
 	}
 
+	#[test]
+	fn info_query_tb_stats() {
+		let sql = "INFO FOR TABLE test STATS";
+		let res = info(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::TbStats(Ident::from("test")));
+		assert_eq!("INFO FOR TABLE test STATS", format!("{}", out));
+	}
+
 	#[test]
 	fn info_query_user() {
 		let sql = "INFO FOR USER test ON ROOT";
@@ -370,4 +492,31 @@ mod tests {
 		assert_eq!(out, InfoStatement::User(Ident::from("test"), None));
 		assert_eq!("INFO FOR USER test", format!("{}", out));
 	}
+
+	#[test]
+	fn info_query_sessions() {
+		let sql = "INFO FOR SESSIONS";
+		let res = info(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::Sessions);
+		assert_eq!("INFO FOR SESSIONS", format!("{}", out));
+	}
+
+	#[test]
+	fn info_query_stats() {
+		let sql = "INFO FOR STATS";
+		let res = info(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::Stats);
+		assert_eq!("INFO FOR STATS", format!("{}", out));
+	}
+
+	#[test]
+	fn info_query_ix() {
+		let sql = "INFO FOR INDEX idx ON TABLE test";
+		let res = info(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::Ix(Ident::from("idx"), Ident::from("test")));
+		assert_eq!("INFO FOR INDEX idx ON test", format!("{}", out));
+	}
 }
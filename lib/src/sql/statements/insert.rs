@@ -1,3 +1,4 @@
+use crate::cnf::INSERT_BATCH_SIZE;
 use crate::ctx::Context;
 use crate::dbs::Iterator;
 use crate::dbs::Options;
@@ -25,7 +26,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct InsertStatement {
 	pub into: Value,
 	pub data: Data,
@@ -34,6 +35,8 @@ pub struct InsertStatement {
 	pub output: Option<Output>,
 	pub timeout: Option<Timeout>,
 	pub parallel: bool,
+	#[revision(start = 2)]
+	pub partial: bool,
 }
 
 impl InsertStatement {
@@ -59,8 +62,10 @@ impl InsertStatement {
 	) -> Result<Value, Error> {
 		// Valid options?
 		opt.valid_for_db()?;
-		// Create a new iterator
-		let mut i = Iterator::new();
+		// Assign the statement
+		let stm = Statement::from(self);
+		// The records are processed in batches as they are computed
+		let mut rows = Batches::default();
 		// Ensure futures are stored
 		let opt = &opt.new_with_futures(false).with_projections(false);
 		// Parse the expression
@@ -79,7 +84,7 @@ impl InsertStatement {
 						// Specify the new table record id
 						let id = o.rid().generate(&into, true)?;
 						// Pass the mergeable to the iterator
-						i.ingest(Iterable::Mergeable(id, o));
+						rows.push(ctx, opt, txn, &stm, Iterable::Mergeable(id, o)).await?;
 					}
 				}
 				// Check if this is a modern statement
@@ -91,14 +96,14 @@ impl InsertStatement {
 								// Specify the new table record id
 								let id = v.rid().generate(&into, true)?;
 								// Pass the mergeable to the iterator
-								i.ingest(Iterable::Mergeable(id, v));
+								rows.push(ctx, opt, txn, &stm, Iterable::Mergeable(id, v)).await?;
 							}
 						}
 						Value::Object(_) => {
 							// Specify the new table record id
 							let id = v.rid().generate(&into, true)?;
 							// Pass the mergeable to the iterator
-							i.ingest(Iterable::Mergeable(id, v));
+							rows.push(ctx, opt, txn, &stm, Iterable::Mergeable(id, v)).await?;
 						}
 						v => {
 							return Err(Error::InsertStatement {
@@ -115,10 +120,55 @@ impl InsertStatement {
 				})
 			}
 		}
-		// Assign the statement
-		let stm = Statement::from(self);
+		// Process the remaining records
+		rows.flush(ctx, opt, txn, &stm).await?;
 		// Output the results
-		i.output(ctx, opt, txn, &stm).await
+		Ok(rows.results.into())
+	}
+}
+
+/// The records of an INSERT statement, which are
+/// passed to the iterator in batches of records
+#[derive(Default)]
+struct Batches {
+	iterator: Iterator,
+	queued: usize,
+	results: Vec<Value>,
+}
+
+impl Batches {
+	/// Queue a record, processing the batch once it is full
+	async fn push(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		val: Iterable,
+	) -> Result<(), Error> {
+		self.iterator.ingest(val);
+		self.queued += 1;
+		match self.queued < INSERT_BATCH_SIZE {
+			true => Ok(()),
+			false => self.flush(ctx, opt, txn, stm).await,
+		}
+	}
+	/// Process the queued records
+	async fn flush(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		if self.queued > 0 {
+			let mut i = std::mem::take(&mut self.iterator);
+			self.queued = 0;
+			if let Value::Array(v) = i.output(ctx, opt, txn, stm).await? {
+				self.results.extend(v);
+			}
+		}
+		Ok(())
 	}
 }
 
@@ -141,6 +191,9 @@ impl fmt::Display for InsertStatement {
 		if self.parallel {
 			f.write_str(" PARALLEL")?
 		}
+		if self.partial {
+			f.write_str(" PARTIAL")?
+		}
 		Ok(())
 	}
 }
@@ -158,6 +211,7 @@ pub fn insert(i: &str) -> IResult<&str, InsertStatement> {
 	let (i, output) = opt(preceded(shouldbespace, output))(i)?;
 	let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
 	let (i, parallel) = opt(preceded(shouldbespace, tag_no_case("PARALLEL")))(i)?;
+	let (i, partial) = opt(preceded(shouldbespace, tag_no_case("PARTIAL")))(i)?;
 	Ok((
 		i,
 		InsertStatement {
@@ -168,6 +222,7 @@ pub fn insert(i: &str) -> IResult<&str, InsertStatement> {
 			output,
 			timeout,
 			parallel: parallel.is_some(),
+			partial: partial.is_some(),
 		},
 	))
 }
@@ -200,4 +255,12 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("INSERT IGNORE INTO test (field) VALUES ($value) ON DUPLICATE KEY UPDATE field = $value", format!("{}", out))
 	}
+
+	#[test]
+	fn insert_statement_partial() {
+		let sql = "INSERT INTO test [{ id: 1 }, { id: 2 }] RETURN NONE PARALLEL PARTIAL";
+		let res = insert(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
 }
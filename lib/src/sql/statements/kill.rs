@@ -3,25 +3,32 @@ use crate::dbs::Options;
 use crate::dbs::Transaction;
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::IResult;
 use crate::sql::param::param;
-use crate::sql::uuid::uuid;
+use crate::sql::strand::strand;
+use crate::sql::uuid::{uuid, Uuid};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
-use nom::combinator::into;
+use nom::combinator::{cut, into};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct KillStatement {
 	// Uuid of Live Query
 	// or Param resolving to Uuid of Live Query
 	pub id: Value,
+	// Whether the id refers to a session
+	// instead of a Live Query
+	#[revision(start = 2)]
+	pub session: bool,
 }
 
 impl KillStatement {
@@ -33,6 +40,10 @@ impl KillStatement {
 		txn: &Transaction,
 		_doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
+		// Terminate a session
+		if self.session {
+			return self.terminate(ctx, opt, txn).await;
+		}
 		// Is realtime enabled?
 		opt.realtime()?;
 		// Valid options?
@@ -82,25 +93,111 @@ impl KillStatement {
 				})
 			}
 		}
+		// Stop tracking the live query
+		if let Some(sessions) = ctx.get_sessions() {
+			sessions.del_live(&live_query_id);
+		}
 		// Return the query id
 		Ok(Value::None)
 	}
+
+	/// Terminate a session, and remove any live queries it started
+	async fn terminate(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Any, &Base::Root)?;
+		// Resolve the session id
+		let id = match &self.id {
+			Value::Strand(id) => id.0.clone(),
+			Value::Param(param) => match param.compute(ctx, opt, txn, None).await? {
+				Value::Strand(id) => id.0,
+				Value::Uuid(id) => id.to_raw(),
+				_ => {
+					return Err(Error::KillStatement {
+						value: self.id.to_string(),
+					})
+				}
+			},
+			_ => {
+				return Err(Error::KillStatement {
+					value: self.id.to_string(),
+				})
+			}
+		};
+		// Terminate the session
+		let lqs = match ctx.get_sessions() {
+			Some(sessions) => sessions.terminate(&id)?,
+			None => {
+				return Err(Error::SessionNotFound {
+					value: id,
+				})
+			}
+		};
+		// Remove the live queries of the session
+		if !lqs.is_empty() {
+			// Claim transaction
+			let mut run = txn.lock().await;
+			// Find all the live queries on this node
+			let nid = opt.id()?;
+			let beg = crate::key::node::lq::prefix_nd(&nid);
+			let end = crate::key::node::lq::suffix_nd(&nid);
+			for (k, v) in run.getr(beg..end, u32::MAX).await? {
+				let lq = crate::key::node::lq::Lq::decode(&k)?;
+				if lqs.contains(&Uuid::from(lq.lq)) {
+					let tb = String::from_utf8(v).map_err(|_| Error::Unreachable)?;
+					// Delete the node live query
+					let key = crate::key::node::lq::new(lq.nd, lq.lq, lq.ns, lq.db);
+					run.del(key).await?;
+					// Delete the table live query
+					let key = crate::key::table::lq::new(lq.ns, lq.db, &tb, lq.lq);
+					run.del(key).await?;
+				}
+			}
+		}
+		// Ok all good
+		Ok(Value::None)
+	}
 }
 
 impl fmt::Display for KillStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "KILL {}", self.id)
+		match self.session {
+			true => write!(f, "KILL SESSION {}", self.id),
+			false => write!(f, "KILL {}", self.id),
+		}
 	}
 }
 
 pub fn kill(i: &str) -> IResult<&str, KillStatement> {
 	let (i, _) = tag_no_case("KILL")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	alt((kill_session, kill_live))(i)
+}
+
+fn kill_live(i: &str) -> IResult<&str, KillStatement> {
 	let (i, v) = alt((into(uuid), into(param)))(i)?;
 	Ok((
 		i,
 		KillStatement {
 			id: v,
+			session: false,
+		},
+	))
+}
+
+fn kill_session(i: &str) -> IResult<&str, KillStatement> {
+	let (i, _) = tag_no_case("SESSION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(alt((into(strand), into(param))))(i)?;
+	Ok((
+		i,
+		KillStatement {
+			id: v,
+			session: true,
 		},
 	))
 }
@@ -108,7 +205,7 @@ pub fn kill(i: &str) -> IResult<&str, KillStatement> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::sql::{Ident, Param, Uuid};
+	use crate::sql::{Ident, Param};
 
 	#[test]
 	fn kill_uuid() {
@@ -121,7 +218,8 @@ mod tests {
 		assert_eq!(
 			out,
 			KillStatement {
-				id: Value::Uuid(Uuid::from(uuid::Uuid::parse_str(uuid_str).unwrap()))
+				id: Value::Uuid(Uuid::from(uuid::Uuid::parse_str(uuid_str).unwrap())),
+				session: false,
 			}
 		);
 		assert_eq!("KILL 'c005b8da-63a4-48bc-a371-07e95b39d58e'", format!("{}", out));
@@ -136,8 +234,24 @@ mod tests {
 			out,
 			KillStatement {
 				id: Value::Param(Param(Ident("id".to_string()))),
+				session: false,
 			}
 		);
 		assert_eq!("KILL $id", format!("{}", out));
 	}
+
+	#[test]
+	fn kill_session() {
+		let sql = "kill session 'a4b1f2e0'";
+		let res = kill(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			out,
+			KillStatement {
+				id: Value::from("a4b1f2e0"),
+				session: true,
+			}
+		);
+		assert_eq!("KILL SESSION 'a4b1f2e0'", format!("{}", out));
+	}
 }
@@ -1,7 +1,10 @@
+pub(crate) mod alter;
 pub(crate) mod analyze;
+pub(crate) mod backup;
 pub(crate) mod begin;
 pub(crate) mod r#break;
 pub(crate) mod cancel;
+pub(crate) mod combine;
 pub(crate) mod commit;
 pub(crate) mod r#continue;
 pub(crate) mod create;
@@ -15,18 +18,27 @@ pub(crate) mod kill;
 pub(crate) mod live;
 pub(crate) mod option;
 pub(crate) mod output;
+pub(crate) mod rebuild;
+pub(crate) mod refresh;
 pub(crate) mod relate;
+pub(crate) mod release;
 pub(crate) mod remove;
+pub(crate) mod rollback;
+pub(crate) mod savepoint;
 pub(crate) mod select;
 pub(crate) mod set;
 pub(crate) mod show;
 pub(crate) mod sleep;
 pub(crate) mod throw;
+pub(crate) mod truncate;
 pub(crate) mod update;
 pub(crate) mod r#use;
 
+pub use self::alter::AlterStatement;
+pub use self::backup::BackupStatement;
 pub use self::begin::BeginStatement;
 pub use self::cancel::CancelStatement;
+pub use self::combine::CombineStatement;
 pub use self::commit::CommitStatement;
 pub use self::create::CreateStatement;
 pub use self::delete::DeleteStatement;
@@ -41,10 +53,15 @@ pub use self::output::OutputStatement;
 pub use self::r#break::BreakStatement;
 pub use self::r#continue::ContinueStatement;
 pub use self::r#use::UseStatement;
+pub use self::refresh::RefreshStatement;
 pub use self::relate::RelateStatement;
+pub use self::release::ReleaseStatement;
+pub use self::rollback::RollbackStatement;
+pub use self::savepoint::SavepointStatement;
 pub use self::select::SelectStatement;
 pub use self::set::SetStatement;
 pub use self::throw::ThrowStatement;
+pub use self::truncate::TruncateStatement;
 pub use self::update::UpdateStatement;
 
 pub use self::define::DefineAnalyzerStatement;
@@ -56,11 +73,15 @@ pub use self::define::DefineIndexStatement;
 pub use self::define::DefineNamespaceStatement;
 pub use self::define::DefineParamStatement;
 pub use self::define::DefineScopeStatement;
+pub use self::define::DefineSequenceStatement;
 pub use self::define::DefineStatement;
 pub use self::define::DefineTableStatement;
 pub use self::define::DefineTokenStatement;
 pub use self::define::DefineUserStatement;
 
+pub use self::rebuild::RebuildIndexStatement;
+pub use self::rebuild::RebuildStatement;
+
 pub use self::remove::RemoveDatabaseStatement;
 pub use self::remove::RemoveEventStatement;
 pub use self::remove::RemoveFieldStatement;
@@ -69,6 +90,7 @@ pub use self::remove::RemoveIndexStatement;
 pub use self::remove::RemoveNamespaceStatement;
 pub use self::remove::RemoveParamStatement;
 pub use self::remove::RemoveScopeStatement;
+pub use self::remove::RemoveSequenceStatement;
 pub use self::remove::RemoveStatement;
 pub use self::remove::RemoveTableStatement;
 pub use self::remove::RemoveTokenStatement;
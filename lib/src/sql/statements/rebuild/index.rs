@@ -0,0 +1,118 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::value::Value;
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, opt};
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RebuildIndexStatement {
+	pub name: Ident,
+	pub what: Ident,
+}
+
+impl RebuildIndexStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Valid options?
+		opt.valid_for_db()?;
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Index, &Base::Db)?;
+		// Check if the context is finished
+		ctx.check()?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Fetch the index definition
+		let ix = run.get_and_cache_tb_index(opt.ns(), opt.db(), &self.what, &self.name).await?;
+		// Remove the index data
+		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.delp(key, u32::MAX).await?;
+		// Mark the index as waiting to be built in the background
+		let key = crate::key::table::ix::new(opt.ns(), opt.db(), &self.what, &self.name);
+		let ix = DefineIndexStatement {
+			building: true,
+			failure: None,
+			..ix.as_ref().clone()
+		};
+		run.set(key, ix).await?;
+		// Clear the cache
+		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
+		run.clr(key).await?;
+		// Release the transaction
+		drop(run);
+		// Forget the progress of any previous build of the index
+		if let Some(rebuilds) = ctx.get_rebuilds() {
+			rebuilds.remove(opt.ns(), opt.db(), &self.what, &self.name);
+		}
+		// The index is repopulated by the datastore in the background
+		Ok(Value::None)
+	}
+}
+
+impl Display for RebuildIndexStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REBUILD INDEX {} ON {}", self.name, self.what)
+	}
+}
+
+pub fn index(i: &str) -> IResult<&str, RebuildIndexStatement> {
+	let (i, _) = tag_no_case("REBUILD")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("INDEX")(i)?;
+	cut(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, name) = ident(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("ON")(i)?;
+		let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, what) = ident(i)?;
+		Ok((
+			i,
+			RebuildIndexStatement {
+				name,
+				what,
+			},
+		))
+	})(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn rebuild_index_statement() {
+		let sql = "REBUILD INDEX uniq_email ON TABLE user";
+		let res = index(sql);
+		let out = res.unwrap().1;
+		assert_eq!("REBUILD INDEX uniq_email ON user", format!("{}", out));
+	}
+
+	#[test]
+	fn rebuild_index_statement_without_table() {
+		let sql = "REBUILD INDEX uniq_email ON user";
+		let res = index(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+	}
+}
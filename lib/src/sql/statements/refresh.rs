@@ -0,0 +1,92 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RefreshStatement {
+	pub name: Ident,
+}
+
+impl RefreshStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Valid options?
+		opt.valid_for_db()?;
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Fetch the table definition
+		let tb = run.get_tb(opt.ns(), opt.db(), &self.name).await?;
+		// Release the transaction
+		drop(run);
+		// Ensure the table is a view
+		if tb.view.is_none() {
+			return Err(Error::TableIsNotView {
+				table: self.name.to_raw(),
+			});
+		}
+		// Don't run permissions
+		let opt = &opt.new_with_perms(false);
+		// Recalculate the view
+		tb.refresh(ctx, opt, txn).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl fmt::Display for RefreshStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "REFRESH VIEW {}", self.name)
+	}
+}
+
+pub fn refresh(i: &str) -> IResult<&str, RefreshStatement> {
+	let (i, _) = tag_no_case("REFRESH")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("VIEW")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = cut(ident)(i)?;
+	Ok((
+		i,
+		RefreshStatement {
+			name,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn refresh_statement() {
+		let sql = "REFRESH VIEW person_by_age";
+		let res = refresh(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+}
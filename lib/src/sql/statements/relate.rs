@@ -5,16 +5,18 @@ use crate::dbs::Statement;
 use crate::dbs::{Iterable, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::key::graph;
 use crate::sql::array::array;
 use crate::sql::comment::mightbespace;
 use crate::sql::comment::shouldbespace;
 use crate::sql::data::{data, Data};
+use crate::sql::dir::Dir;
 use crate::sql::error::IResult;
 use crate::sql::output::{output, Output};
 use crate::sql::param::param;
 use crate::sql::subquery::subquery;
-use crate::sql::table::table;
-use crate::sql::thing::thing;
+use crate::sql::table::{table, Table};
+use crate::sql::thing::{thing, Thing};
 use crate::sql::timeout::{timeout, Timeout};
 use crate::sql::value::Value;
 use derive::Store;
@@ -27,10 +29,11 @@ use nom::combinator::value;
 use nom::sequence::preceded;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RelateStatement {
 	pub kind: Value,
 	pub from: Value,
@@ -40,6 +43,8 @@ pub struct RelateStatement {
 	pub output: Option<Output>,
 	pub timeout: Option<Timeout>,
 	pub parallel: bool,
+	#[revision(start = 2)]
+	pub both: bool,
 }
 
 impl RelateStatement {
@@ -154,30 +159,20 @@ impl RelateStatement {
 			};
 			out
 		};
+		// The edges which have already been added
+		let mut seen = HashSet::new();
 		//
 		for f in from.iter() {
 			for w in with.iter() {
-				let f = f.clone();
-				let w = w.clone();
-				match &self.kind {
-					// The relation has a specific record id
-					Value::Thing(id) => i.ingest(Iterable::Relatable(f, id.to_owned(), w)),
-					// The relation does not have a specific record id
-					Value::Table(tb) => match &self.data {
-						// There is a data clause so check for a record id
-						Some(data) => {
-							let id = match data.rid(ctx, opt, txn).await? {
-								Some(id) => id.generate(tb, false)?,
-								None => tb.generate(),
-							};
-							i.ingest(Iterable::Relatable(f, id, w))
-						}
-						// There is no data clause so create a record id
-						None => i.ingest(Iterable::Relatable(f, tb.generate(), w)),
-					},
-					// The relation can not be any other type
-					_ => unreachable!(),
-				};
+				// Don't add the same unique edge twice
+				if self.uniq && !seen.insert((f, w)) {
+					continue;
+				}
+				i.ingest(self.relatable(ctx, opt, txn, f, w, false).await?);
+				// Maintain the reverse edge
+				if self.both && (!self.uniq || seen.insert((w, f))) {
+					i.ingest(self.relatable(ctx, opt, txn, w, f, true).await?);
+				}
 			}
 		}
 		// Assign the statement
@@ -185,6 +180,70 @@ impl RelateStatement {
 		// Output the results
 		i.output(ctx, opt, txn, &stm).await
 	}
+	/// Get the edge which should be stored between two records
+	async fn relatable(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		f: &Thing,
+		w: &Thing,
+		reverse: bool,
+	) -> Result<Iterable, Error> {
+		// Get the table of the relation
+		let tb = match &self.kind {
+			Value::Thing(id) => Table::from(id.tb.to_owned()),
+			Value::Table(tb) => tb.to_owned(),
+			// The relation can not be any other type
+			_ => unreachable!(),
+		};
+		// Reuse an existing edge between the records
+		if self.uniq {
+			if let Some(id) = edge(opt, txn, &tb, f, w).await? {
+				return Ok(Iterable::Relatable(f.clone(), id, w.clone()));
+			}
+		}
+		let id = match &self.kind {
+			// The reverse edge can not use the same record id
+			_ if reverse => tb.generate(),
+			// The relation has a specific record id
+			Value::Thing(id) => id.to_owned(),
+			// The relation does not have a specific record id
+			_ => match &self.data {
+				// There is a data clause so check for a record id
+				Some(data) => match data.rid(ctx, opt, txn).await? {
+					Some(id) => id.generate(&tb, false)?,
+					None => tb.generate(),
+				},
+				// There is no data clause so create a record id
+				None => tb.generate(),
+			},
+		};
+		Ok(Iterable::Relatable(f.clone(), id, w.clone()))
+	}
+}
+
+/// Find an existing edge in a table from one record to another
+async fn edge(
+	opt: &Options,
+	txn: &Transaction,
+	tb: &str,
+	f: &Thing,
+	w: &Thing,
+) -> Result<Option<Thing>, Error> {
+	let beg = graph::ftprefix(opt.ns(), opt.db(), &f.tb, &f.id, &Dir::Out, tb);
+	let end = graph::ftsuffix(opt.ns(), opt.db(), &f.tb, &f.id, &Dir::Out, tb);
+	let mut run = txn.lock().await;
+	for (k, _) in run.getr(beg..end, u32::MAX).await? {
+		let gra: graph::Graph = (&k).into();
+		let id = Thing::from((gra.ft, gra.fk));
+		// Check if this edge points to the other record
+		let key = graph::new(opt.ns(), opt.db(), &id.tb, &id.id, &Dir::Out, w);
+		if run.exi(key).await? {
+			return Ok(Some(id));
+		}
+	}
+	Ok(None)
 }
 
 impl fmt::Display for RelateStatement {
@@ -193,6 +252,9 @@ impl fmt::Display for RelateStatement {
 		if self.uniq {
 			f.write_str(" UNIQUE")?
 		}
+		if self.both {
+			f.write_str(" BIDIRECTIONAL")?
+		}
 		if let Some(ref v) = self.data {
 			write!(f, " {v}")?
 		}
@@ -214,6 +276,7 @@ pub fn relate(i: &str) -> IResult<&str, RelateStatement> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, path) = relate_oi(i)?;
 	let (i, uniq) = opt(preceded(shouldbespace, tag_no_case("UNIQUE")))(i)?;
+	let (i, both) = opt(preceded(shouldbespace, tag_no_case("BIDIRECTIONAL")))(i)?;
 	let (i, data) = opt(preceded(shouldbespace, data))(i)?;
 	let (i, output) = opt(preceded(shouldbespace, output))(i)?;
 	let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
@@ -229,6 +292,7 @@ pub fn relate(i: &str) -> IResult<&str, RelateStatement> {
 			output,
 			timeout,
 			parallel: parallel.is_some(),
+			both: both.is_some(),
 		},
 	))
 }
@@ -295,4 +359,17 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("RELATE $tobie -> like -> $koala", format!("{}", out))
 	}
+
+	#[test]
+	fn relate_statement_unique_bidirectional() {
+		let sql = "RELATE person:tobie->knows->person:jaime UNIQUE BIDIRECTIONAL";
+		let res = relate(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			"RELATE person:tobie -> knows -> person:jaime UNIQUE BIDIRECTIONAL",
+			format!("{}", out)
+		);
+		assert!(out.uniq);
+		assert!(out.both);
+	}
 }
@@ -35,6 +35,14 @@ impl RemoveDatabaseStatement {
 		let mut run = txn.lock().await;
 		// Clear the cache
 		run.clear_cache();
+		// Delete the data of the interned tables. The database
+		// id is not released, so that it is never used again
+		let ns = run.get_ns(opt.ns()).await.ok().and_then(|v| v.id);
+		let db = run.get_db(opt.ns(), &self.name).await.ok().and_then(|v| v.id);
+		if let (Some(ns), Some(db)) = (ns, db) {
+			let key = crate::key::interned::database(ns, db);
+			run.delp(key, u32::MAX).await?;
+		}
 		// Delete the definition
 		let key = crate::key::namespace::db::new(opt.ns(), &self.name);
 		run.del(key).await?;
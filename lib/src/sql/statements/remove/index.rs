@@ -27,7 +27,7 @@ impl RemoveIndexStatement {
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
-		_ctx: &Context<'_>,
+		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
 	) -> Result<Value, Error> {
@@ -43,6 +43,10 @@ impl RemoveIndexStatement {
 		// Remove the index data
 		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delp(key, u32::MAX).await?;
+		// Forget the progress of any index rebuilds
+		if let Some(rebuilds) = ctx.get_rebuilds() {
+			rebuilds.remove(opt.ns(), opt.db(), &self.what, &self.name);
+		}
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
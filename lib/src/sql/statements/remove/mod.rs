@@ -7,6 +7,7 @@ mod index;
 mod namespace;
 mod param;
 mod scope;
+mod sequence;
 mod table;
 mod token;
 mod user;
@@ -20,6 +21,7 @@ pub use index::{index, RemoveIndexStatement};
 pub use namespace::{namespace, RemoveNamespaceStatement};
 pub use param::{param, RemoveParamStatement};
 pub use scope::{scope, RemoveScopeStatement};
+pub use sequence::{sequence, RemoveSequenceStatement};
 pub use table::{table, RemoveTableStatement};
 pub use token::{token, RemoveTokenStatement};
 pub use user::{user, RemoveUserStatement};
@@ -53,6 +55,7 @@ pub enum RemoveStatement {
 	Field(RemoveFieldStatement),
 	Index(RemoveIndexStatement),
 	User(RemoveUserStatement),
+	Sequence(RemoveSequenceStatement),
 }
 
 impl RemoveStatement {
@@ -81,6 +84,7 @@ impl RemoveStatement {
 			Self::Index(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn).await,
 			Self::User(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Sequence(ref v) => v.compute(ctx, opt, txn).await,
 		}
 	}
 }
@@ -100,6 +104,7 @@ impl Display for RemoveStatement {
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
 			Self::User(v) => Display::fmt(v, f),
+			Self::Sequence(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -118,6 +123,7 @@ pub fn remove(i: &str) -> IResult<&str, RemoveStatement> {
 		map(index, RemoveStatement::Index),
 		map(analyzer, RemoveStatement::Analyzer),
 		map(user, RemoveStatement::User),
+		map(sequence, RemoveStatement::Sequence),
 	))(i)
 }
 
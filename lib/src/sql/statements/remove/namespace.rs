@@ -35,6 +35,12 @@ impl RemoveNamespaceStatement {
 		let mut run = txn.lock().await;
 		// Clear the cache
 		run.clear_cache();
+		// Delete the data of the interned tables. The namespace
+		// id is not released, so that it is never used again
+		if let Some(id) = run.get_ns(&self.name).await.ok().and_then(|v| v.id) {
+			let key = crate::key::interned::namespace(id);
+			run.delp(key, u32::MAX).await?;
+		}
 		// Delete the definition
 		let key = crate::key::root::ns::new(&self.name);
 		run.del(key).await?;
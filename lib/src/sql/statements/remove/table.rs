@@ -36,12 +36,14 @@ impl RemoveTableStatement {
 		run.clear_cache();
 		// Get the defined table
 		let tb = run.get_tb(opt.ns(), opt.db(), &self.name).await?;
+		// Remove the resource data, while the definition still points at the
+		// interned prefix of the table. The table id is not released, so that
+		// keys left under the prefix are never read as those of another table
+		let key = crate::key::table::all::new(opt.ns(), opt.db(), &self.name);
+		run.delp(key, u32::MAX).await?;
 		// Delete the definition
 		let key = crate::key::database::tb::new(opt.ns(), opt.db(), &self.name);
 		run.del(key).await?;
-		// Remove the resource data
-		let key = crate::key::table::all::new(opt.ns(), opt.db(), &self.name);
-		run.delp(key, u32::MAX).await?;
 		// Check if this is a foreign table
 		if let Some(view) = &tb.view {
 			// Process each foreign table
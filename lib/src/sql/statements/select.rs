@@ -5,38 +5,47 @@ use crate::dbs::Statement;
 use crate::dbs::{Iterable, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::iam::Action;
 use crate::idx::planner::QueryPlanner;
 use crate::sql::comment::shouldbespace;
-use crate::sql::cond::{cond, Cond};
+use crate::sql::cond::{cond, having, Cond};
 use crate::sql::error::IResult;
 use crate::sql::explain::{explain, Explain};
 use crate::sql::fetch::{fetch, Fetchs};
 use crate::sql::field::{fields, Field, Fields};
-use crate::sql::group::{group, Groups};
-use crate::sql::idiom::Idioms;
+use crate::sql::fmt::Fmt;
+use crate::sql::group::{grouping, Grouping, Groups};
+use crate::sql::idiom::{Idiom, Idioms};
 use crate::sql::limit::{limit, Limit};
 use crate::sql::omit::omit;
 use crate::sql::order::{order, Orders};
+use crate::sql::paths::ID;
+use crate::sql::range::Range;
+use crate::sql::special::check_computed_order_by_fields;
 use crate::sql::special::check_group_by_fields;
 use crate::sql::special::check_order_by_fields;
 use crate::sql::special::check_split_on_fields;
 use crate::sql::split::{split, Splits};
 use crate::sql::start::{start, Start};
+use crate::sql::thing::Thing;
 use crate::sql::timeout::{timeout, Timeout};
-use crate::sql::value::{selects, Value, Values};
+use crate::sql::value::{selects, value, Value, Values};
 use crate::sql::version::{version, Version};
 use crate::sql::with::{with, With};
+use async_recursion::async_recursion;
+use base64_lib::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use derive::Store;
 use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
-use nom::sequence::preceded;
+use nom::sequence::{preceded, tuple};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops::Bound;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 5)]
 pub struct SelectStatement {
 	pub expr: Fields,
 	pub omit: Option<Idioms>,
@@ -53,6 +62,14 @@ pub struct SelectStatement {
 	pub timeout: Option<Timeout>,
 	pub parallel: bool,
 	pub explain: Option<Explain>,
+	#[revision(start = 2)]
+	pub grouping: Grouping,
+	#[revision(start = 3)]
+	pub having: Option<Cond>,
+	#[revision(start = 4)]
+	pub lock: bool,
+	#[revision(start = 5)]
+	pub after: Option<Value>,
 }
 
 impl SelectStatement {
@@ -70,6 +87,9 @@ impl SelectStatement {
 		if self.what.iter().any(|v| v.writeable()) {
 			return true;
 		}
+		if self.lock {
+			return true;
+		}
 		self.cond.as_ref().map_or(false, |v| v.writeable())
 	}
 	/// Check if this statement is for a single record
@@ -80,26 +100,90 @@ impl SelectStatement {
 			_ => false,
 		}
 	}
+	/// Create a statement which selects the records to be modified by an
+	/// ordered or limited DELETE or UPDATE, with the fields they are sorted by
+	pub(crate) fn ids(
+		what: &Values,
+		cond: &Option<Cond>,
+		order: &Option<Orders>,
+		limit: &Option<Limit>,
+	) -> Self {
+		let mut fields = vec![Field::Single {
+			expr: Value::Idiom(ID.as_ref().into()),
+			alias: None,
+		}];
+		// The records can only be sorted by the projected fields
+		if let Some(order) = order {
+			match order.is_dynamic() {
+				true => fields.push(Field::All),
+				false => fields.extend(order.iter().map(|o| Field::Single {
+					expr: Value::Idiom(o.order.clone()),
+					alias: None,
+				})),
+			}
+		}
+		Self {
+			expr: Fields(fields, false),
+			what: what.clone(),
+			cond: cond.clone(),
+			order: order.clone(),
+			limit: limit.clone(),
+			..Default::default()
+		}
+	}
 	/// Process this type returning a computed simple Value
+	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
 	pub(crate) async fn compute(
 		&self,
 		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
-		doc: Option<&CursorDoc<'_>>,
+		doc: Option<&'async_recursion CursorDoc<'_>>,
 	) -> Result<Value, Error> {
 		// Valid options?
 		opt.valid_for_db()?;
+		// Paginate through the records with a cursor
+		if let Some(after) = &self.after {
+			return self.paginate(ctx, opt, txn, doc, after).await;
+		}
+		// Compute any parameterized field or index names
+		if self.is_dynamic() {
+			let stm = SelectStatement {
+				order: match &self.order {
+					Some(v) => {
+						let v = v.compute(ctx, opt, txn, doc).await?;
+						// The computed fields must be in the SELECT clause
+						check_computed_order_by_fields(&self.expr, &v)?;
+						Some(v)
+					}
+					None => None,
+				},
+				with: match &self.with {
+					Some(v) => Some(v.compute(ctx, opt, txn, doc).await?),
+					None => None,
+				},
+				..self.clone()
+			};
+			return stm.compute(ctx, opt, txn, doc).await;
+		}
 		// Create a new iterator
 		let mut i = Iterator::new();
 		// Ensure futures are stored
 		let opt = &opt.new_with_futures(false).with_projections(true);
 		// Get a query planner
-		let mut planner = QueryPlanner::new(opt, &self.with, &self.cond);
+		// The records are fetched to check the permissions
+		let idioms = match opt.check_perms(Action::View) {
+			true => None,
+			false => self.idioms(),
+		};
+		let mut planner = QueryPlanner::new(opt, &self.with, &self.cond, &self.order, idioms);
 		// Loop over the select targets
 		for w in self.what.0.iter() {
 			let v = w.compute(ctx, opt, txn, doc).await?;
 			match v {
+				// Indexes only contain the current values of the records
+				Value::Table(t) if self.version.is_some() => i.ingest(Iterable::Table(t)),
 				Value::Table(t) => {
 					planner.add_iterables(ctx, txn, t, &mut i).await?;
 				}
@@ -114,6 +198,9 @@ impl SelectStatement {
 				Value::Array(v) => {
 					for v in v {
 						match v {
+							Value::Table(t) if self.version.is_some() => {
+								i.ingest(Iterable::Table(t))
+							}
 							Value::Table(t) => {
 								planner.add_iterables(ctx, txn, t, &mut i).await?;
 							}
@@ -146,6 +233,132 @@ impl SelectStatement {
 	}
 }
 
+impl SelectStatement {
+	/// The fields of the records read by this statement, when they are known in advance
+	fn idioms(&self) -> Option<Vec<Idiom>> {
+		if self.omit.is_some()
+			|| self.split.is_some()
+			|| self.group.is_some()
+			|| self.fetch.is_some()
+		{
+			return None;
+		}
+		let mut res = Vec::new();
+		for f in self.expr.iter() {
+			let Field::Single {
+				expr,
+				..
+			} = f
+			else {
+				return None;
+			};
+			if !expr.idioms(&mut res) {
+				return None;
+			}
+		}
+		if let Some(cond) = &self.cond {
+			if !cond.0.idioms(&mut res) {
+				return None;
+			}
+		}
+		Some(res)
+	}
+
+	/// Check if any ORDER BY field or index name is parameterized
+	fn is_dynamic(&self) -> bool {
+		self.order.as_ref().map_or(false, Orders::is_dynamic)
+			|| matches!(self.with, Some(With::Dynamic(_)))
+	}
+	/// Select the next page of records from a single table, in record id
+	/// order, starting after the record which the cursor points to. The
+	/// output contains the records, and a cursor for the following page.
+	async fn paginate(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+		after: &Value,
+	) -> Result<Value, Error> {
+		let err = |message: &str| Error::InvalidCursor {
+			message: message.to_owned(),
+		};
+		// Pages always follow the order of the records in the table
+		if self.order.is_some() || self.group.is_some() || self.split.is_some() {
+			return Err(err("A cursor can not be used with ORDER BY, GROUP BY, or SPLIT"));
+		}
+		if self.start.is_some() {
+			return Err(err("A cursor can not be used with START"));
+		}
+		// Pages can only be selected from a single table
+		let tb = match &self.what.0[..] {
+			[v] => match v.compute(ctx, opt, txn, doc).await? {
+				Value::Table(v) => v,
+				_ => return Err(err("A cursor can only be used when selecting from a table")),
+			},
+			_ => return Err(err("A cursor can only be used when selecting from a single table")),
+		};
+		// Find the record after which this page starts
+		let beg = match after.compute(ctx, opt, txn, doc).await? {
+			Value::None | Value::Null => Bound::Unbounded,
+			Value::Strand(v) => match decode(&v) {
+				Some(v) if v.tb == tb.0 => Bound::Excluded(v.id),
+				_ => return Err(err("The cursor is not valid for this table")),
+			},
+			Value::Thing(v) if v.tb == tb.0 => Bound::Excluded(v.id),
+			_ => return Err(err("The cursor is not valid for this table")),
+		};
+		// Select the ids of the records in this page
+		let range = Range {
+			tb: tb.0,
+			beg,
+			end: Bound::Unbounded,
+		};
+		let what = Values(vec![Value::Range(Box::new(range))]);
+		let mut stm = Self::ids(&what, &self.cond, &None, &self.limit);
+		stm.version = self.version.clone();
+		let ids = match stm.compute(ctx, opt, txn, doc).await? {
+			Value::Array(v) => v,
+			_ => Default::default(),
+		};
+		// There may be more records if the page is full
+		let cursor = match &self.limit {
+			Some(v) if ids.len() == v.process(ctx, opt, txn, doc).await? => match ids.last() {
+				Some(Value::Thing(v)) => Value::from(encode(v)),
+				_ => Value::None,
+			},
+			_ => Value::None,
+		};
+		// Select the records in this page
+		let stm = SelectStatement {
+			what: Values(vec![Value::Array(ids)]),
+			with: None,
+			cond: None,
+			limit: None,
+			parallel: false,
+			after: None,
+			..self.clone()
+		};
+		let result = stm.compute(ctx, opt, txn, doc).await?;
+		// Output the page and the next cursor
+		Ok(Value::from(map! {
+			"result".to_string() => result,
+			"cursor".to_string() => cursor,
+		}))
+	}
+}
+
+/// Create an opaque cursor which points to a record
+fn encode(rid: &Thing) -> String {
+	URL_SAFE_NO_PAD.encode(rid.to_string())
+}
+
+/// Get the record which an opaque cursor points to
+fn decode(cursor: &str) -> Option<Thing> {
+	let rid = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+	Thing::try_from(String::from_utf8(rid).ok()?).ok()
+}
+
 impl fmt::Display for SelectStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "SELECT {}", self.expr)?;
@@ -163,11 +376,20 @@ impl fmt::Display for SelectStatement {
 			write!(f, " {v}")?
 		}
 		if let Some(ref v) = self.group {
-			write!(f, " {v}")?
+			match self.grouping {
+				Grouping::Simple => write!(f, " {v}")?,
+				ref g => write!(f, " GROUP BY {g}({})", Fmt::comma_separated(v.iter()))?,
+			}
+		}
+		if let Some(ref v) = self.having {
+			write!(f, " HAVING {}", v.0)?
 		}
 		if let Some(ref v) = self.order {
 			write!(f, " {v}")?
 		}
+		if let Some(ref v) = self.after {
+			write!(f, " AFTER {v}")?
+		}
 		if let Some(ref v) = self.limit {
 			write!(f, " {v}")?
 		}
@@ -180,6 +402,9 @@ impl fmt::Display for SelectStatement {
 		if let Some(ref v) = self.version {
 			write!(f, " {v}")?
 		}
+		if self.lock {
+			f.write_str(" FOR UPDATE")?
+		}
 		if let Some(ref v) = self.timeout {
 			write!(f, " {v}")?
 		}
@@ -206,14 +431,22 @@ pub fn select(i: &str) -> IResult<&str, SelectStatement> {
 	let (i, cond) = opt(preceded(shouldbespace, cond))(i)?;
 	let (i, split) = opt(preceded(shouldbespace, split))(i)?;
 	check_split_on_fields(i, &expr, &split)?;
-	let (i, group) = opt(preceded(shouldbespace, group))(i)?;
+	let (i, group) = opt(preceded(shouldbespace, grouping))(i)?;
+	let (group, grouping) = group.unzip();
 	check_group_by_fields(i, &expr, &group)?;
+	let (i, having) = match group {
+		Some(_) => opt(preceded(shouldbespace, having))(i)?,
+		None => (i, None),
+	};
 	let (i, order) = opt(preceded(shouldbespace, order))(i)?;
 	check_order_by_fields(i, &expr, &order)?;
+	let (i, after) = opt(preceded(shouldbespace, after))(i)?;
 	let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
 	let (i, start) = opt(preceded(shouldbespace, start))(i)?;
 	let (i, fetch) = opt(preceded(shouldbespace, fetch))(i)?;
 	let (i, version) = opt(preceded(shouldbespace, version))(i)?;
+	let (i, lock) =
+		opt(tuple((shouldbespace, tag_no_case("FOR"), shouldbespace, tag_no_case("UPDATE"))))(i)?;
 	let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
 	let (i, parallel) = opt(preceded(shouldbespace, tag_no_case("PARALLEL")))(i)?;
 	let (i, explain) = opt(preceded(shouldbespace, explain))(i)?;
@@ -235,10 +468,20 @@ pub fn select(i: &str) -> IResult<&str, SelectStatement> {
 			timeout,
 			parallel: parallel.is_some(),
 			explain,
+			grouping: grouping.unwrap_or_default(),
+			having,
+			lock: lock.is_some(),
+			after,
 		},
 	))
 }
 
+fn after(i: &str) -> IResult<&str, Value> {
+	let (i, _) = tag_no_case("AFTER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(value)(i)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -285,6 +528,51 @@ mod tests {
 		assert_eq!(sql, format!("{}", out))
 	}
 
+	#[test]
+	fn select_statement_rollup() {
+		let sql = "SELECT country, city, count() FROM person GROUP BY ROLLUP(country, city)";
+		let res = select(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.grouping, Grouping::Rollup);
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn select_statement_having() {
+		let sql = "SELECT city, count() FROM person GROUP BY city HAVING count > 10 ORDER BY city";
+		let res = select(sql);
+		let out = res.unwrap().1;
+		assert!(out.having.is_some());
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn select_statement_having_without_group() {
+		let sql = "SELECT * FROM person HAVING count > 10";
+		let res = select(sql);
+		let out = res.unwrap();
+		assert_eq!(" HAVING count > 10", out.0);
+	}
+
+	#[test]
+	fn select_statement_for_update() {
+		let sql = "SELECT * FROM person WHERE age > 18 FOR UPDATE TIMEOUT 5s";
+		let res = select(sql);
+		let out = res.unwrap().1;
+		assert!(out.lock);
+		assert!(out.writeable());
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn select_statement_after() {
+		let sql = "SELECT * FROM person WHERE age > 18 AFTER $cursor LIMIT 10";
+		let res = select(sql);
+		let out = res.unwrap().1;
+		assert!(out.after.is_some());
+		assert_eq!(sql, format!("{}", out))
+	}
+
 	#[test]
 	fn select_statement_table_thing() {
 		let sql = "SELECT *, ((1 + 3) / 4), 1.3999f AS tester FROM test, test:thingy";
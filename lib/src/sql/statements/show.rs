@@ -9,6 +9,7 @@ use crate::sql::comment::shouldbespace;
 use crate::sql::common::take_u64;
 use crate::sql::datetime::datetime;
 use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
 use crate::sql::table::{table, Table};
 use crate::sql::value::Value;
 use crate::sql::Base;
@@ -22,6 +23,7 @@ use nom::combinator::map;
 use nom::combinator::opt;
 use nom::combinator::value;
 use nom::sequence::preceded;
+use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -31,6 +33,8 @@ use std::fmt;
 pub enum ShowSince {
 	Timestamp(Datetime),
 	Versionstamp(u64),
+	/// The changes after the last change which the consumer has read
+	Consumer(Ident),
 }
 
 // ShowStatement is used to show changes in a table or database via
@@ -44,6 +48,11 @@ pub struct ShowStatement {
 }
 
 impl ShowStatement {
+	/// Check if we require a writeable transaction
+	pub(crate) fn writeable(&self) -> bool {
+		// Reading as a consumer advances its offset
+		matches!(self.since, ShowSince::Consumer(_))
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -90,6 +99,7 @@ impl fmt::Display for ShowStatement {
 		match self.since {
 			ShowSince::Timestamp(ref v) => write!(f, " SINCE {}", v)?,
 			ShowSince::Versionstamp(ref v) => write!(f, " SINCE {}", v)?,
+			ShowSince::Consumer(ref v) => write!(f, " SINCE CONSUMER {}", v)?,
 		}
 		if let Some(ref v) = self.limit {
 			write!(f, " LIMIT {}", v)?
@@ -110,7 +120,11 @@ pub fn since(i: &str) -> IResult<&str, ShowSince> {
 	let (i, _) = tag_no_case("SINCE")(i)?;
 	let (i, _) = shouldbespace(i)?;
 
-	cut(alt((map(take_u64, ShowSince::Versionstamp), map(datetime, ShowSince::Timestamp))))(i)
+	cut(alt((
+		map(take_u64, ShowSince::Versionstamp),
+		map(datetime, ShowSince::Timestamp),
+		map(preceded(tuple((tag_no_case("CONSUMER"), shouldbespace)), ident), ShowSince::Consumer),
+	)))(i)
 }
 
 pub fn limit(i: &str) -> IResult<&str, u32> {
@@ -197,6 +211,16 @@ mod tests {
 		assert_eq!(sql, format!("{}", out))
 	}
 
+	#[test]
+	fn show_table_changes_since_consumer_limit() {
+		let sql = "SHOW CHANGES FOR TABLE person SINCE CONSUMER sync LIMIT 10";
+		let res = show(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.since, ShowSince::Consumer(Ident::from("sync")));
+		assert!(out.writeable());
+	}
+
 	#[test]
 	fn show_database_changes() {
 		let sql = "SHOW CHANGES FOR DATABASE";
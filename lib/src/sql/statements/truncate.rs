@@ -0,0 +1,154 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Statement, Transaction, Workable};
+use crate::doc::{CursorDoc, Document};
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::key::thing;
+use crate::kvs::Scanner;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::DeleteStatement;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, opt};
+use nom::sequence::terminated;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct TruncateStatement {
+	pub name: Ident,
+}
+
+impl TruncateStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Valid options?
+		opt.valid_for_db()?;
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Ensure the table exists
+		let tb = run.get_tb(opt.ns(), opt.db(), &self.name).await?;
+		// Check whether anything depends on the records
+		let beg = crate::key::graph::tbprefix(opt.ns(), opt.db(), &self.name);
+		let end = crate::key::graph::tbsuffix(opt.ns(), opt.db(), &self.name);
+		let dependents = tb.changefeed.is_some()
+			|| !run.scan(beg..end, 1).await?.is_empty()
+			|| !run.all_tb_views(opt.ns(), opt.db(), &self.name).await?.is_empty()
+			|| !run.all_tb_lives(opt.ns(), opt.db(), &self.name).await?.is_empty();
+		// Release the transaction
+		drop(run);
+		// Clean up the graph edges, views, live queries, and changefeeds of each record
+		if dependents {
+			let stm = DeleteStatement::default();
+			let stm = Statement::from(&stm);
+			let beg = thing::prefix(opt.ns(), opt.db(), &self.name);
+			let end = thing::suffix(opt.ns(), opt.db(), &self.name);
+			let mut scanner = Scanner::new(beg..end, opt.batch_size);
+			loop {
+				// Check if the context is finished
+				ctx.check()?;
+				// Get the next batch of records
+				let res = scanner.next(&mut *txn.lock().await).await?;
+				if res.is_empty() {
+					break;
+				}
+				for (k, v) in res.iter() {
+					// Parse the data from the store
+					let key: thing::Thing = k.into();
+					let val: Value = v.into();
+					let rid = Thing::from((key.tb, key.id));
+					// Remove the record from everything which depends on it
+					let mut doc = Document::new(None, Some(&rid), None, &val, Workable::Normal);
+					doc.truncate(ctx, opt, txn, &stm).await?;
+				}
+			}
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Remove the record data
+		let beg = thing::prefix(opt.ns(), opt.db(), &self.name);
+		let end = thing::suffix(opt.ns(), opt.db(), &self.name);
+		run.delr_all(beg..end).await?;
+		// Remove the chunks of any large record values
+		let mut beg = crate::key::table::all::new(opt.ns(), opt.db(), &self.name).encode()?;
+		beg.push(b'&');
+		let end = [beg.as_slice(), &[0xff]].concat();
+		run.delr_all(beg..end).await?;
+		// Remove the graph edge data
+		let beg = crate::key::graph::tbprefix(opt.ns(), opt.db(), &self.name);
+		let end = crate::key::graph::tbsuffix(opt.ns(), opt.db(), &self.name);
+		run.delr_all(beg..end).await?;
+		// Remove the queue of records which expire
+		for suffix in [b"!ex", b"!eq"] {
+			let mut beg = crate::key::table::all::new(opt.ns(), opt.db(), &self.name).encode()?;
+			beg.extend_from_slice(suffix);
+			let end = [beg.as_slice(), &[0xff]].concat();
+			run.delr_all(beg..end).await?;
+		}
+		// Remove the index data, keeping the index definitions
+		for ix in run.all_tb_indexes(opt.ns(), opt.db(), &self.name).await?.iter() {
+			let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.name, &ix.name);
+			run.delp(key, u32::MAX).await?;
+		}
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl fmt::Display for TruncateStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "TRUNCATE TABLE {}", self.name)
+	}
+}
+
+pub fn truncate(i: &str) -> IResult<&str, TruncateStatement> {
+	let (i, _) = tag_no_case("TRUNCATE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = opt(terminated(tag_no_case("TABLE"), shouldbespace))(i)?;
+	let (i, name) = cut(ident)(i)?;
+	Ok((
+		i,
+		TruncateStatement {
+			name,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn truncate_statement() {
+		let sql = "TRUNCATE TABLE person";
+		let res = truncate(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn truncate_statement_without_table() {
+		let sql = "TRUNCATE person";
+		let res = truncate(sql);
+		let out = res.unwrap().1;
+		assert_eq!("TRUNCATE TABLE person", format!("{}", out))
+	}
+}
@@ -9,7 +9,11 @@ use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
 use crate::sql::data::{data, Data};
 use crate::sql::error::IResult;
+use crate::sql::limit::{limit, Limit};
+use crate::sql::order::{order, Orders};
 use crate::sql::output::{output, Output};
+use crate::sql::paths::ID;
+use crate::sql::statements::SelectStatement;
 use crate::sql::timeout::{timeout, Timeout};
 use crate::sql::value::{whats, Value, Values};
 use derive::Store;
@@ -21,7 +25,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct UpdateStatement {
 	pub what: Values,
 	pub data: Option<Data>,
@@ -29,6 +33,10 @@ pub struct UpdateStatement {
 	pub output: Option<Output>,
 	pub timeout: Option<Timeout>,
 	pub parallel: bool,
+	#[revision(start = 2)]
+	pub order: Option<Orders>,
+	#[revision(start = 2)]
+	pub limit: Option<Limit>,
 }
 
 impl UpdateStatement {
@@ -60,17 +68,28 @@ impl UpdateStatement {
 		let stm = Statement::from(self);
 		// Ensure futures are stored
 		let opt = &opt.new_with_futures(false).with_projections(false);
-		// Loop over the update targets
-		for w in self.what.0.iter() {
-			let v = w.compute(ctx, opt, txn, doc).await?;
-			i.prepare(ctx, opt, txn, &stm, v).await.map_err(|e| match e {
-				Error::InvalidStatementTarget {
-					value: v,
-				} => Error::UpdateStatement {
-					value: v,
-				},
-				e => e,
-			})?;
+		// Check if the targets are ordered or limited
+		match self.order.is_some() || self.limit.is_some() {
+			// Select the records to update in order, up to the limit
+			true => {
+				let sel = SelectStatement::ids(&self.what, &self.cond, &self.order, &self.limit);
+				let v = sel.compute(ctx, &opt.new_with_perms(false), txn, doc).await?;
+				i.prepare(ctx, opt, txn, &stm, v.pick(ID.as_ref())).await?;
+			}
+			// Loop over the update targets
+			false => {
+				for w in self.what.0.iter() {
+					let v = w.compute(ctx, opt, txn, doc).await?;
+					i.prepare(ctx, opt, txn, &stm, v).await.map_err(|e| match e {
+						Error::InvalidStatementTarget {
+							value: v,
+						} => Error::UpdateStatement {
+							value: v,
+						},
+						e => e,
+					})?;
+				}
+			}
 		}
 		// Output the results
 		i.output(ctx, opt, txn, &stm).await
@@ -86,6 +105,12 @@ impl fmt::Display for UpdateStatement {
 		if let Some(ref v) = self.cond {
 			write!(f, " {v}")?
 		}
+		if let Some(ref v) = self.order {
+			write!(f, " {v}")?
+		}
+		if let Some(ref v) = self.limit {
+			write!(f, " {v}")?
+		}
 		if let Some(ref v) = self.output {
 			write!(f, " {v}")?
 		}
@@ -105,6 +130,8 @@ pub fn update(i: &str) -> IResult<&str, UpdateStatement> {
 	let (i, what) = whats(i)?;
 	let (i, data) = opt(preceded(shouldbespace, data))(i)?;
 	let (i, cond) = opt(preceded(shouldbespace, cond))(i)?;
+	let (i, order) = opt(preceded(shouldbespace, order))(i)?;
+	let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
 	let (i, output) = opt(preceded(shouldbespace, output))(i)?;
 	let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
 	let (i, parallel) = opt(preceded(shouldbespace, tag_no_case("PARALLEL")))(i)?;
@@ -117,6 +144,8 @@ pub fn update(i: &str) -> IResult<&str, UpdateStatement> {
 			output,
 			timeout,
 			parallel: parallel.is_some(),
+			order,
+			limit,
 		},
 	))
 }
@@ -133,4 +162,14 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("UPDATE test", format!("{}", out))
 	}
+
+	#[test]
+	fn update_statement_order_limit() {
+		let sql = "UPDATE person SET active = false WHERE age > 30 ORDER BY age DESC LIMIT 10";
+		let res = update(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert!(out.order.is_some());
+		assert!(out.limit.is_some());
+	}
 }
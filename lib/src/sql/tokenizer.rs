@@ -1,8 +1,9 @@
-use crate::sql::common::commas;
+use crate::sql::common::{closeparentheses, commas, openparentheses};
 use crate::sql::error::IResult;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
-use nom::combinator::value;
+use nom::character::complete::u16;
+use nom::combinator::{cut, value};
 use nom::multi::separated_list1;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -16,16 +17,22 @@ pub enum Tokenizer {
 	Camel,
 	Class,
 	Punct,
+	/// Splits each word into its n-grams, from `min` to `max` characters
+	Ngram(u16, u16),
+	/// Splits each word into its prefixes, from `min` to `max` characters
+	EdgeNgram(u16, u16),
 }
 
 impl Display for Tokenizer {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		f.write_str(match self {
-			Self::Blank => "BLANK",
-			Self::Camel => "CAMEL",
-			Self::Class => "CLASS",
-			Self::Punct => "PUNCT",
-		})
+		match self {
+			Self::Blank => f.write_str("BLANK"),
+			Self::Camel => f.write_str("CAMEL"),
+			Self::Class => f.write_str("CLASS"),
+			Self::Punct => f.write_str("PUNCT"),
+			Self::Ngram(min, max) => write!(f, "NGRAM({},{})", min, max),
+			Self::EdgeNgram(min, max) => write!(f, "EDGENGRAM({},{})", min, max),
+		}
 	}
 }
 
@@ -35,10 +42,37 @@ fn tokenizer(i: &str) -> IResult<&str, Tokenizer> {
 		value(Tokenizer::Camel, tag_no_case("CAMEL")),
 		value(Tokenizer::Class, tag_no_case("CLASS")),
 		value(Tokenizer::Punct, tag_no_case("PUNCT")),
+		edgengram,
+		ngram,
 	))(i)?;
 	Ok((i, t))
 }
 
+fn edgengram(i: &str) -> IResult<&str, Tokenizer> {
+	let (i, _) = tag_no_case("EDGENGRAM")(i)?;
+	cut(|i| {
+		let (i, (min, max)) = ngram_sizes(i)?;
+		Ok((i, Tokenizer::EdgeNgram(min, max)))
+	})(i)
+}
+
+fn ngram(i: &str) -> IResult<&str, Tokenizer> {
+	let (i, _) = tag_no_case("NGRAM")(i)?;
+	cut(|i| {
+		let (i, (min, max)) = ngram_sizes(i)?;
+		Ok((i, Tokenizer::Ngram(min, max)))
+	})(i)
+}
+
+fn ngram_sizes(i: &str) -> IResult<&str, (u16, u16)> {
+	let (i, _) = openparentheses(i)?;
+	let (i, min) = u16(i)?;
+	let (i, _) = commas(i)?;
+	let (i, max) = u16(i)?;
+	let (i, _) = closeparentheses(i)?;
+	Ok((i, (min, max)))
+}
+
 pub(super) fn tokenizers(i: &str) -> IResult<&str, Vec<Tokenizer>> {
 	separated_list1(commas, tokenizer)(i)
 }
@@ -6,9 +6,11 @@ use crate::sql::field::{Field, Fields};
 use crate::sql::part::Next;
 use crate::sql::part::Part;
 use crate::sql::statements::select::SelectStatement;
+use crate::sql::thing::Thing;
 use crate::sql::value::{Value, Values};
 use async_recursion::async_recursion;
 use futures::future::try_join_all;
+use std::collections::HashMap;
 
 impl Value {
 	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
@@ -89,7 +91,7 @@ impl Value {
 								what: Values(vec![Value::from(Edges {
 									from: val,
 									dir: g.dir.clone(),
-									what: g.what.clone(),
+									what: g.tables(ctx, opt, txn, None).await?,
 								})]),
 								cond: g.cond.clone(),
 								..SelectStatement::default()
@@ -123,8 +125,41 @@ impl Value {
 			None => match self {
 				// Current path part is an array
 				Value::Array(v) => {
-					let futs = v.iter_mut().map(|v| v.fetch(ctx, opt, txn, path));
+					let futs = v
+						.iter_mut()
+						.filter(|v| !v.is_thing())
+						.map(|v| v.fetch(ctx, opt, txn, path));
 					try_join_all(futs).await?;
+					// Fetch the remote embedded records together
+					let things: Vec<Value> = v.iter().filter(|v| v.is_thing()).cloned().collect();
+					if !things.is_empty() {
+						let stm = SelectStatement {
+							expr: Fields(vec![Field::All], false),
+							what: Values(things),
+							..SelectStatement::default()
+						};
+						let res: HashMap<Thing, Value> =
+							match stm.compute(ctx, opt, txn, None).await? {
+								Value::Array(res) => res
+									.into_iter()
+									.filter_map(|v| {
+										let id = match &v {
+											Value::Object(o) => o.rid(),
+											_ => None,
+										};
+										id.map(|id| (id, v))
+									})
+									.collect(),
+								_ => HashMap::new(),
+							};
+						// Replace each record link with its record
+						for v in v.iter_mut() {
+							if let Value::Thing(id) = v {
+								let val = res.get(id).cloned().unwrap_or(Value::None);
+								*v = val;
+							}
+						}
+					}
 					Ok(())
 				}
 				// Current path part is a thing
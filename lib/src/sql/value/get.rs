@@ -5,6 +5,7 @@ use crate::err::Error;
 use crate::exe::try_join_all_buffered;
 use crate::sql::edges::Edges;
 use crate::sql::field::{Field, Fields};
+use crate::sql::graph::{Graph, Recurse};
 use crate::sql::id::Id;
 use crate::sql::part::Next;
 use crate::sql::part::Part;
@@ -13,6 +14,7 @@ use crate::sql::statements::select::SelectStatement;
 use crate::sql::thing::Thing;
 use crate::sql::value::{Value, Values};
 use async_recursion::async_recursion;
+use std::collections::{HashMap, HashSet};
 
 impl Value {
 	/// Asynchronous method for getting a local or remote field from a `Value`
@@ -175,6 +177,13 @@ impl Value {
 						0 => Ok(Value::Thing(val)),
 						// Remote embedded field, so fetch the thing
 						_ => match p {
+							// This is a recursive graph traversal expression
+							Part::Graph(
+								g @ Graph {
+									recurse: Some(r),
+									..
+								},
+							) => Self::recurse(ctx, opt, txn, doc, &val, g, r, path.next()).await,
 							// This is a graph traversal expression
 							Part::Graph(g) => {
 								let stm = SelectStatement {
@@ -182,7 +191,7 @@ impl Value {
 									what: Values(vec![Value::from(Edges {
 										from: val,
 										dir: g.dir.clone(),
-										what: g.what.clone(),
+										what: g.tables(ctx, opt, txn, doc).await?,
 									})]),
 									cond: g.cond.clone(),
 									..SelectStatement::default()
@@ -235,6 +244,115 @@ impl Value {
 			None => Ok(self.clone()),
 		}
 	}
+
+	/// Repeatedly traverse a graph hop from a record, between the
+	/// depth bounds, visiting each record at most once so that
+	/// cycles in the graph are not followed. A graph hop consists
+	/// of the edge traversal, and the subsequent node traversal
+	/// if one is specified. Records are output in breadth-first
+	/// order, at the depth of their shortest path from the record.
+	#[allow(clippy::too_many_arguments)]
+	async fn recurse(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+		from: &Thing,
+		graph: &Graph,
+		recurse: &Recurse,
+		path: &[Part],
+	) -> Result<Self, Error> {
+		// Traverse the edges without recursing
+		let mut hop = vec![Part::Graph(Graph {
+			recurse: None,
+			..graph.clone()
+		})];
+		// Traverse the subsequent node if specified
+		let path = match path.first() {
+			Some(p @ Part::Graph(_)) => {
+				hop.push(p.clone());
+				path.next()
+			}
+			_ => path,
+		};
+		// Compute the shortest path target
+		let target = match &recurse.shortest {
+			Some(v) => match v.compute(ctx, opt, txn, doc).await? {
+				Value::Thing(v) => Some(v),
+				v => {
+					return Err(Error::InvalidShortest {
+						value: v.to_string(),
+					})
+				}
+			},
+			None => None,
+		};
+		// The records which have been visited
+		let mut seen = HashSet::from([from.clone()]);
+		// The record from which each record was first reached
+		let mut prev: HashMap<Thing, Thing> = HashMap::new();
+		// The records reached at the current depth
+		let mut cur = vec![from.clone()];
+		// The records which are output
+		let mut out = Vec::new();
+		if recurse.min == 0 && target.is_none() {
+			out.push(Value::from(from.clone()));
+		}
+		for depth in 1..=recurse.max {
+			// Stop if there is nothing left to traverse
+			if cur.is_empty() || ctx.is_done() {
+				break;
+			}
+			let mut next = Vec::new();
+			for v in cur {
+				// Fetch the records reachable with one hop
+				let res =
+					match Value::Thing(v.clone()).get(ctx, opt, txn, None, &hop).await?.flatten() {
+						Value::Array(v) => v,
+						_ => continue,
+					};
+				for r in res {
+					// Skip any values which are not records
+					let Value::Thing(r) = r else {
+						continue;
+					};
+					// Skip any records which have been visited
+					if !seen.insert(r.clone()) {
+						continue;
+					}
+					// Check if this is the shortest path target
+					if let Some(t) = &target {
+						prev.insert(r.clone(), v.clone());
+						if &r == t {
+							// The path must be within the depth bounds
+							if depth < recurse.min {
+								return Ok(Value::from(Vec::<Value>::new()));
+							}
+							// Walk back along the path to the record
+							let mut res = vec![Value::from(r.clone())];
+							let mut cur = &r;
+							while let Some(v) = prev.get(cur).filter(|v| *v != from) {
+								res.push(Value::from(v.clone()));
+								cur = v;
+							}
+							res.reverse();
+							return Value::from(res).get(ctx, opt, txn, None, path).await;
+						}
+					} else if depth >= recurse.min {
+						out.push(Value::from(r.clone()));
+					}
+					next.push(r);
+				}
+			}
+			cur = next;
+		}
+		// The shortest path target was not reached
+		if target.is_some() {
+			return Ok(Value::from(Vec::<Value>::new()));
+		}
+		// Fetch the remaining path from the records
+		Value::from(out).get(ctx, opt, txn, None, path).await
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,75 @@
+use crate::sql::expression::Expression;
+use crate::sql::function::Function;
+use crate::sql::idiom::Idiom;
+use crate::sql::part::Part;
+use crate::sql::value::Value;
+
+impl Value {
+	/// Collect the fields of the current record which are read when computing this value.
+	/// Returns false when the value may read anything else, such as the whole record,
+	/// another record, or the result of a subquery.
+	pub(crate) fn idioms(&self, res: &mut Vec<Idiom>) -> bool {
+		match self {
+			Value::Idiom(i) => {
+				if !i.iter().all(|p| matches!(p, Part::Field(_))) {
+					return false;
+				}
+				res.push(i.clone());
+				true
+			}
+			Value::Expression(e) => match e.as_ref() {
+				Expression::Unary {
+					v,
+					..
+				} => v.idioms(res),
+				Expression::Binary {
+					l,
+					r,
+					..
+				} => l.idioms(res) && r.idioms(res),
+			},
+			Value::Function(f) => match f.as_ref() {
+				Function::Normal(_, a) => a.iter().all(|v| v.idioms(res)),
+				_ => false,
+			},
+			Value::Array(v) => v.iter().all(|v| v.idioms(res)),
+			Value::Object(v) => v.values().all(|v| v.idioms(res)),
+			// The current record is a parameter too
+			Value::Param(p) => !matches!(p.as_str(), "this" | "self"),
+			Value::None
+			| Value::Null
+			| Value::Bool(_)
+			| Value::Number(_)
+			| Value::Strand(_)
+			| Value::Duration(_)
+			| Value::Datetime(_)
+			| Value::Uuid(_)
+			| Value::Thing(_)
+			| Value::Constant(_) => true,
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn idioms_of_expression() {
+		let val = Value::parse("a = 1 AND string::lowercase(b.c) = $x");
+		let mut res = Vec::new();
+		assert!(val.idioms(&mut res));
+		assert_eq!(res, vec![Idiom::parse("a"), Idiom::parse("b.c")]);
+	}
+
+	#[test]
+	fn idioms_of_record() {
+		let mut res = Vec::new();
+		assert!(!Value::parse("$this.a = 1").idioms(&mut res));
+		assert!(!Value::parse("a[0] = 1").idioms(&mut res));
+		assert!(!Value::parse("a = (SELECT * FROM b)").idioms(&mut res));
+	}
+}
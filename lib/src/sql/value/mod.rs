@@ -22,6 +22,7 @@ mod first;
 mod flatten;
 mod generate;
 mod get;
+mod idioms;
 mod inc;
 mod increment;
 mod last;
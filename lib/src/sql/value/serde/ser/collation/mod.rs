@@ -0,0 +1,88 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::index::Collation;
+use crate::sql::language::Language;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Collation;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Collation, Error>;
+	type SerializeTuple = Impossible<Collation, Error>;
+	type SerializeTupleStruct = Impossible<Collation, Error>;
+	type SerializeTupleVariant = Impossible<Collation, Error>;
+	type SerializeMap = Impossible<Collation, Error>;
+	type SerializeStruct = SerializeCollation;
+	type SerializeStructVariant = Impossible<Collation, Error>;
+
+	const EXPECTED: &'static str = "a struct `Collation`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeCollation::default())
+	}
+}
+
+#[derive(Default)]
+pub(super) struct SerializeCollation {
+	nocase: bool,
+	noaccent: bool,
+	language: Option<Language>,
+}
+
+impl serde::ser::SerializeStruct for SerializeCollation {
+	type Ok = Collation;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"nocase" => {
+				self.nocase = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"noaccent" => {
+				self.noaccent = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"language" => {
+				self.language = value.serialize(ser::language::opt::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Collation {{ {key} }}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(Collation {
+			nocase: self.nocase,
+			noaccent: self.noaccent,
+			language: self.language,
+		})
+	}
+}
+
+#[test]
+fn collation() {
+	let collation = Collation {
+		nocase: true,
+		noaccent: false,
+		language: Some(Language::Turkish),
+	};
+	let serialized = collation.serialize(Serializer.wrap()).unwrap();
+	assert_eq!(collation, serialized);
+}
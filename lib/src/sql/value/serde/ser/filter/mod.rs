@@ -52,6 +52,13 @@ impl ser::Serializer for Serializer {
 	{
 		match variant {
 			"Snowball" => Ok(Filter::Snowball(value.serialize(ser::language::Serializer.wrap())?)),
+			"Stemmers" => {
+				Ok(Filter::Stemmers(value.serialize(ser::language::vec::Serializer.wrap())?))
+			}
+			"Stopwords" => {
+				Ok(Filter::Stopwords(value.serialize(ser::string::vec::Serializer.wrap())?))
+			}
+			"Synonyms" => Ok(Filter::Synonyms(value.serialize(ser::string::Serializer.wrap())?)),
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -151,4 +158,25 @@ mod tests {
 		let serialized = filter.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(filter, serialized);
 	}
+
+	#[test]
+	fn stemmers() {
+		let filter = Filter::Stemmers(vec![Language::English, Language::German]);
+		let serialized = filter.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(filter, serialized);
+	}
+
+	#[test]
+	fn stopwords() {
+		let filter = Filter::Stopwords(vec!["der".to_owned(), "the".to_owned()]);
+		let serialized = filter.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(filter, serialized);
+	}
+
+	#[test]
+	fn synonyms() {
+		let filter = Filter::Synonyms("us=>usa, car=>automobile".to_owned());
+		let serialized = filter.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(filter, serialized);
+	}
 }
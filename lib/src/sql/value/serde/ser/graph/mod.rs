@@ -1,5 +1,8 @@
+mod recurse;
+
 use crate::err::Error;
 use crate::sql::field::Fields;
+use crate::sql::graph::Recurse;
 use crate::sql::group::Groups;
 use crate::sql::limit::Limit;
 use crate::sql::order::Orders;
@@ -11,6 +14,7 @@ use crate::sql::Dir;
 use crate::sql::Graph;
 use crate::sql::Idiom;
 use crate::sql::Tables;
+use crate::sql::Value;
 use ser::Serializer as _;
 use serde::ser::Error as _;
 use serde::ser::Impossible;
@@ -54,6 +58,8 @@ pub(super) struct SerializeGraph {
 	limit: Option<Limit>,
 	start: Option<Start>,
 	alias: Option<Idiom>,
+	recurse: Option<Recurse>,
+	dynamic: Option<Value>,
 }
 
 impl serde::ser::SerializeStruct for SerializeGraph {
@@ -95,6 +101,12 @@ impl serde::ser::SerializeStruct for SerializeGraph {
 			"alias" => {
 				self.alias = value.serialize(ser::part::vec::opt::Serializer.wrap())?.map(Idiom);
 			}
+			"recurse" => {
+				self.recurse = value.serialize(recurse::opt::Serializer.wrap())?;
+			}
+			"dynamic" => {
+				self.dynamic = value.serialize(ser::value::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `Graph::{key}`")));
 			}
@@ -115,6 +127,8 @@ impl serde::ser::SerializeStruct for SerializeGraph {
 				limit: self.limit,
 				start: self.start,
 				alias: self.alias,
+				recurse: self.recurse,
+				dynamic: self.dynamic,
 			}),
 			_ => Err(Error::custom("`Graph` missing required field(s)")),
 		}
@@ -152,4 +166,24 @@ mod tests {
 		let serialized = graph.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(graph, serialized);
 	}
+
+	#[test]
+	fn with_recurse() {
+		let graph = Graph {
+			recurse: Some(Default::default()),
+			..Default::default()
+		};
+		let serialized = graph.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(graph, serialized);
+	}
+
+	#[test]
+	fn with_dynamic() {
+		let graph = Graph {
+			dynamic: Some(Default::default()),
+			..Default::default()
+		};
+		let serialized = graph.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(graph, serialized);
+	}
 }
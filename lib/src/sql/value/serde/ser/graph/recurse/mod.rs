@@ -0,0 +1,104 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::graph::Recurse;
+use crate::sql::value::serde::ser;
+use crate::sql::Value;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Recurse;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Recurse, Error>;
+	type SerializeTuple = Impossible<Recurse, Error>;
+	type SerializeTupleStruct = Impossible<Recurse, Error>;
+	type SerializeTupleVariant = Impossible<Recurse, Error>;
+	type SerializeMap = Impossible<Recurse, Error>;
+	type SerializeStruct = SerializeRecurse;
+	type SerializeStructVariant = Impossible<Recurse, Error>;
+
+	const EXPECTED: &'static str = "a struct `Recurse`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRecurse::default())
+	}
+}
+
+#[derive(Default)]
+pub(super) struct SerializeRecurse {
+	min: Option<u32>,
+	max: Option<u32>,
+	shortest: Option<Value>,
+}
+
+impl serde::ser::SerializeStruct for SerializeRecurse {
+	type Ok = Recurse;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"min" => {
+				self.min = Some(value.serialize(ser::primitive::u32::Serializer.wrap())?);
+			}
+			"max" => {
+				self.max = Some(value.serialize(ser::primitive::u32::Serializer.wrap())?);
+			}
+			"shortest" => {
+				self.shortest = value.serialize(ser::value::opt::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Recurse::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		match (self.min, self.max) {
+			(Some(min), Some(max)) => Ok(Recurse {
+				min,
+				max,
+				shortest: self.shortest,
+			}),
+			_ => Err(Error::custom("`Recurse` missing required field(s)")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde::Serialize;
+
+	#[test]
+	fn default() {
+		let recurse = Recurse::default();
+		let serialized = recurse.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(recurse, serialized);
+	}
+
+	#[test]
+	fn with_shortest() {
+		let recurse = Recurse {
+			min: 1,
+			max: 5,
+			shortest: Some(Default::default()),
+		};
+		let serialized = recurse.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(recurse, serialized);
+	}
+}
@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::graph::Recurse;
+use crate::sql::value::serde::ser;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Recurse>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Recurse>, Error>;
+	type SerializeTuple = Impossible<Option<Recurse>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Recurse>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Recurse>, Error>;
+	type SerializeMap = Impossible<Option<Recurse>, Error>;
+	type SerializeStruct = Impossible<Option<Recurse>, Error>;
+	type SerializeStructVariant = Impossible<Option<Recurse>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Recurse>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Recurse> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Recurse::default());
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
@@ -0,0 +1,65 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Grouping;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Grouping;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Grouping, Error>;
+	type SerializeTuple = Impossible<Grouping, Error>;
+	type SerializeTupleStruct = Impossible<Grouping, Error>;
+	type SerializeTupleVariant = Impossible<Grouping, Error>;
+	type SerializeMap = Impossible<Grouping, Error>;
+	type SerializeStruct = Impossible<Grouping, Error>;
+	type SerializeStructVariant = Impossible<Grouping, Error>;
+
+	const EXPECTED: &'static str = "an enum `Grouping`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Simple" => Ok(Grouping::Simple),
+			"Rollup" => Ok(Grouping::Rollup),
+			"Cube" => Ok(Grouping::Cube),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+	use serde::Serialize;
+
+	#[test]
+	fn simple() {
+		let grouping = Grouping::Simple;
+		let serialized = grouping.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(grouping, serialized);
+	}
+
+	#[test]
+	fn rollup() {
+		let grouping = Grouping::Rollup;
+		let serialized = grouping.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(grouping, serialized);
+	}
+
+	#[test]
+	fn cube() {
+		let grouping = Grouping::Cube;
+		let serialized = grouping.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(grouping, serialized);
+	}
+}
@@ -1 +1,2 @@
+pub(super) mod grouping;
 pub(super) mod vec;
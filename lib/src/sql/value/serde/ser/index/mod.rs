@@ -1,3 +1,4 @@
+mod hnswparams;
 mod mtreeparams;
 mod searchparams;
 
@@ -34,6 +35,7 @@ impl ser::Serializer for Serializer {
 		match variant {
 			"Idx" => Ok(Index::Idx),
 			"Uniq" => Ok(Index::Uniq),
+			"Trigram" => Ok(Index::Trigram),
 			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
 		}
 	}
@@ -52,6 +54,7 @@ impl ser::Serializer for Serializer {
 		match variant {
 			"Search" => Ok(Index::Search(value.serialize(searchparams::Serializer.wrap())?)),
 			"MTree" => Ok(Index::MTree(value.serialize(mtreeparams::Serializer.wrap())?)),
+			"Hnsw" => Ok(Index::Hnsw(value.serialize(hnswparams::Serializer.wrap())?)),
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -80,6 +83,13 @@ mod tests {
 		assert_eq!(idx, serialized);
 	}
 
+	#[test]
+	fn trigram() {
+		let idx = Index::Trigram;
+		let serialized = idx.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(idx, serialized);
+	}
+
 	#[test]
 	fn search() {
 		let idx = Index::Search(SearchParams {
@@ -89,6 +99,7 @@ mod tests {
 				k1: Default::default(),
 				b: Default::default(),
 			},
+			boosts: Default::default(),
 			doc_ids_order: Default::default(),
 			doc_lengths_order: Default::default(),
 			postings_order: Default::default(),
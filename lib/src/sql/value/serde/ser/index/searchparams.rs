@@ -1,7 +1,7 @@
 use crate::err::Error;
 use crate::sql::index::SearchParams;
 use crate::sql::value::serde::ser;
-use crate::sql::{Ident, Scoring};
+use crate::sql::{Ident, Number, Scoring};
 use ser::Serializer as _;
 use serde::ser::Error as _;
 use serde::ser::Impossible;
@@ -50,6 +50,7 @@ pub(super) struct SerializeSearch {
 	az: Ident,
 	hl: bool,
 	sc: Option<Scoring>,
+	boosts: Vec<Number>,
 	doc_ids_order: u32,
 	doc_lengths_order: u32,
 	postings_order: u32,
@@ -74,6 +75,9 @@ impl serde::ser::SerializeStruct for SerializeSearch {
 			"sc" => {
 				self.sc = Some(value.serialize(ser::scoring::Serializer.wrap())?);
 			}
+			"boosts" => {
+				self.boosts = value.serialize(ser::number::vec::Serializer.wrap())?;
+			}
 			"doc_ids_order" => {
 				self.doc_ids_order = value.serialize(ser::primitive::u32::Serializer.wrap())?;
 			}
@@ -99,6 +103,7 @@ impl serde::ser::SerializeStruct for SerializeSearch {
 				az: self.az,
 				hl: self.hl,
 				sc,
+				boosts: self.boosts,
 				doc_ids_order: self.doc_ids_order,
 				doc_lengths_order: self.doc_lengths_order,
 				postings_order: self.postings_order,
@@ -115,6 +120,7 @@ fn search_params() {
 		az: Default::default(),
 		hl: false,
 		sc: Scoring::Vs,
+		boosts: vec![Number::Int(2)],
 		doc_ids_order: 0,
 		doc_lengths_order: 0,
 		postings_order: 0,
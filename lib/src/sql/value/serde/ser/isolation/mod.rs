@@ -0,0 +1,59 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Isolation;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Isolation;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Isolation, Error>;
+	type SerializeTuple = Impossible<Isolation, Error>;
+	type SerializeTupleStruct = Impossible<Isolation, Error>;
+	type SerializeTupleVariant = Impossible<Isolation, Error>;
+	type SerializeMap = Impossible<Isolation, Error>;
+	type SerializeStruct = Impossible<Isolation, Error>;
+	type SerializeStructVariant = Impossible<Isolation, Error>;
+
+	const EXPECTED: &'static str = "an enum `Isolation`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Snapshot" => Ok(Isolation::Snapshot),
+			"Serializable" => Ok(Isolation::Serializable),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+	use serde::Serialize;
+
+	#[test]
+	fn snapshot() {
+		let isolation = Isolation::Snapshot;
+		let serialized = isolation.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(isolation, serialized);
+	}
+
+	#[test]
+	fn serializable() {
+		let isolation = Isolation::Serializable;
+		let serialized = isolation.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(isolation, serialized);
+	}
+}
@@ -1,3 +1,6 @@
+pub(super) mod opt;
+pub(super) mod vec;
+
 use crate::err::Error;
 use crate::sql::language::Language;
 use crate::sql::value::serde::ser;
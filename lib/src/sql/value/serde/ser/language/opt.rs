@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::language::Language;
+use crate::sql::value::serde::ser;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Language>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Language>, Error>;
+	type SerializeTuple = Impossible<Option<Language>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Language>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Language>, Error>;
+	type SerializeMap = Impossible<Option<Language>, Error>;
+	type SerializeStruct = Impossible<Option<Language>, Error>;
+	type SerializeStructVariant = Impossible<Option<Language>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Language>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Language> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Language::English);
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
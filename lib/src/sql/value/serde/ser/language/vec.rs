@@ -0,0 +1,77 @@
+use crate::err::Error;
+use crate::sql::language::Language;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Vec<Language>;
+	type Error = Error;
+
+	type SerializeSeq = SerializeLanguageVec;
+	type SerializeTuple = Impossible<Vec<Language>, Error>;
+	type SerializeTupleStruct = Impossible<Vec<Language>, Error>;
+	type SerializeTupleVariant = Impossible<Vec<Language>, Error>;
+	type SerializeMap = Impossible<Vec<Language>, Error>;
+	type SerializeStruct = Impossible<Vec<Language>, Error>;
+	type SerializeStructVariant = Impossible<Vec<Language>, Error>;
+
+	const EXPECTED: &'static str = "a `Vec<Language>`";
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+		Ok(SerializeLanguageVec(Vec::with_capacity(len.unwrap_or_default())))
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		value.serialize(self.wrap())
+	}
+}
+
+pub struct SerializeLanguageVec(Vec<Language>);
+
+impl serde::ser::SerializeSeq for SerializeLanguageVec {
+	type Ok = Vec<Language>;
+	type Error = Error;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		self.0.push(value.serialize(super::Serializer.wrap())?);
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn empty() {
+		let vec: Vec<Language> = Vec::new();
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+
+	#[test]
+	fn vec() {
+		let vec = vec![Language::English];
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+}
@@ -3,6 +3,7 @@ mod base;
 mod block;
 mod cast;
 mod changefeed;
+mod collation;
 mod cond;
 mod constant;
 mod data;
@@ -27,6 +28,7 @@ mod id;
 mod ident;
 mod idiom;
 mod index;
+mod isolation;
 mod kind;
 mod language;
 mod limit;
@@ -39,6 +41,7 @@ mod part;
 mod permission;
 mod permissions;
 mod primitive;
+mod quantization;
 mod range;
 mod scoring;
 mod split;
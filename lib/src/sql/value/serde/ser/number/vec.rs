@@ -0,0 +1,77 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Number;
+use ser::Serializer as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Vec<Number>;
+	type Error = Error;
+
+	type SerializeSeq = SerializeNumberVec;
+	type SerializeTuple = Impossible<Vec<Number>, Error>;
+	type SerializeTupleStruct = Impossible<Vec<Number>, Error>;
+	type SerializeTupleVariant = Impossible<Vec<Number>, Error>;
+	type SerializeMap = Impossible<Vec<Number>, Error>;
+	type SerializeStruct = Impossible<Vec<Number>, Error>;
+	type SerializeStructVariant = Impossible<Vec<Number>, Error>;
+
+	const EXPECTED: &'static str = "a `Vec<Number>`";
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+		Ok(SerializeNumberVec(Vec::with_capacity(len.unwrap_or_default())))
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		value.serialize(self.wrap())
+	}
+}
+
+pub struct SerializeNumberVec(Vec<Number>);
+
+impl serde::ser::SerializeSeq for SerializeNumberVec {
+	type Ok = Vec<Number>;
+	type Error = Error;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		self.0.push(value.serialize(ser::number::Serializer.wrap())?);
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn empty() {
+		let vec: Vec<Number> = Vec::new();
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+
+	#[test]
+	fn vec() {
+		let vec = vec![Number::Int(1), Number::Float(0.5)];
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+}
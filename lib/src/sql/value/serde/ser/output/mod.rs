@@ -35,6 +35,7 @@ impl ser::Serializer for Serializer {
 			"Diff" => Ok(Output::Diff),
 			"After" => Ok(Output::After),
 			"Before" => Ok(Output::Before),
+			"BeforeAfter" => Ok(Output::BeforeAfter),
 			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
 		}
 	}
@@ -106,4 +107,11 @@ mod tests {
 		let serialized = output.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(output, serialized);
 	}
+
+	#[test]
+	fn before_after() {
+		let output = Output::BeforeAfter;
+		let serialized = output.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(output, serialized);
+	}
 }
@@ -1,3 +1,5 @@
+pub use super::opt::i64 as opt;
+
 use crate::err::Error;
 use crate::sql::value::serde::ser;
 use serde::ser::Impossible;
@@ -1,2 +1,3 @@
+pub mod i64;
 pub mod u32;
 pub mod u64;
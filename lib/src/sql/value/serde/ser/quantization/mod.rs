@@ -0,0 +1,79 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::index::Quantization;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Quantization;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Quantization, Error>;
+	type SerializeTuple = Impossible<Quantization, Error>;
+	type SerializeTupleStruct = Impossible<Quantization, Error>;
+	type SerializeTupleVariant = Impossible<Quantization, Error>;
+	type SerializeMap = Impossible<Quantization, Error>;
+	type SerializeStruct = Impossible<Quantization, Error>;
+	type SerializeStructVariant = Impossible<Quantization, Error>;
+
+	const EXPECTED: &'static str = "an enum `Quantization`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Scalar" => Ok(Quantization::Scalar),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+
+	#[inline]
+	fn serialize_newtype_variant<T>(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match variant {
+			"Product" => {
+				Ok(Quantization::Product(value.serialize(ser::primitive::u16::Serializer.wrap())?))
+			}
+			variant => {
+				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn scalar() {
+		let quantization = Quantization::Scalar;
+		let serialized = quantization.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(quantization, serialized);
+	}
+
+	#[test]
+	fn product() {
+		let quantization = Quantization::Product(8);
+		let serialized = quantization.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(quantization, serialized);
+	}
+}
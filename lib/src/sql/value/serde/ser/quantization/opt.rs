@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::index::Quantization;
+use crate::sql::value::serde::ser;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Quantization>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Quantization>, Error>;
+	type SerializeTuple = Impossible<Option<Quantization>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Quantization>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Quantization>, Error>;
+	type SerializeMap = Impossible<Option<Quantization>, Error>;
+	type SerializeStruct = Impossible<Option<Quantization>, Error>;
+	type SerializeStructVariant = Impossible<Option<Quantization>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Quantization>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Quantization> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Quantization::Scalar);
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
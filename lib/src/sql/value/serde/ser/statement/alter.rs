@@ -0,0 +1,129 @@
+use crate::err::Error;
+use crate::sql::statements::AlterStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use crate::sql::Idiom;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = AlterStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<AlterStatement, Error>;
+	type SerializeTuple = Impossible<AlterStatement, Error>;
+	type SerializeTupleStruct = Impossible<AlterStatement, Error>;
+	type SerializeTupleVariant = SerializeAlterStatement;
+	type SerializeMap = Impossible<AlterStatement, Error>;
+	type SerializeStruct = Impossible<AlterStatement, Error>;
+	type SerializeStructVariant = Impossible<AlterStatement, Error>;
+
+	const EXPECTED: &'static str = "an enum `AlterStatement`";
+
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		let variant = match variant {
+			"DropField" | "RenameField" | "Schema" => variant,
+			variant => {
+				return Err(Error::custom(format!("unexpected tuple variant `{name}::{variant}`")));
+			}
+		};
+		Ok(SerializeAlterStatement {
+			variant,
+			index: 0,
+			table: None,
+			from: None,
+			to: None,
+			full: None,
+		})
+	}
+}
+
+pub(super) struct SerializeAlterStatement {
+	variant: &'static str,
+	index: usize,
+	table: Option<Ident>,
+	from: Option<Idiom>,
+	to: Option<Idiom>,
+	full: Option<bool>,
+}
+
+impl serde::ser::SerializeTupleVariant for SerializeAlterStatement {
+	type Ok = AlterStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		match (self.variant, self.index) {
+			(_, 0) => {
+				self.table = Some(Ident(value.serialize(ser::string::Serializer.wrap())?));
+			}
+			("DropField" | "RenameField", 1) => {
+				self.from = Some(Idiom(value.serialize(ser::part::vec::Serializer.wrap())?));
+			}
+			("RenameField", 2) => {
+				self.to = Some(Idiom(value.serialize(ser::part::vec::Serializer.wrap())?));
+			}
+			("Schema", 1) => {
+				self.full = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
+			(variant, index) => {
+				return Err(Error::custom(format!(
+					"unexpected `AlterStatement::{variant}` index `{index}`"
+				)));
+			}
+		}
+		self.index += 1;
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		match (self.variant, self.table, self.from, self.to, self.full) {
+			("DropField", Some(tb), Some(fd), None, None) => Ok(AlterStatement::DropField(tb, fd)),
+			("RenameField", Some(tb), Some(from), Some(to), None) => {
+				Ok(AlterStatement::RenameField(tb, from, to))
+			}
+			("Schema", Some(tb), None, None, Some(full)) => Ok(AlterStatement::Schema(tb, full)),
+			_ => Err(Error::custom("`AlterStatement` missing required value(s)")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde::Serialize;
+
+	#[test]
+	fn drop_field() {
+		let stmt = AlterStatement::DropField(Default::default(), Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
+	#[test]
+	fn rename_field() {
+		let stmt =
+			AlterStatement::RenameField(Default::default(), Default::default(), Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
+	#[test]
+	fn schema() {
+		let stmt = AlterStatement::Schema(Default::default(), true);
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+}
@@ -0,0 +1,57 @@
+use crate::err::Error;
+use crate::sql::statements::BackupStatement;
+use crate::sql::value::serde::ser;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = BackupStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<BackupStatement, Error>;
+	type SerializeTuple = Impossible<BackupStatement, Error>;
+	type SerializeTupleStruct = Impossible<BackupStatement, Error>;
+	type SerializeTupleVariant = Impossible<BackupStatement, Error>;
+	type SerializeMap = Impossible<BackupStatement, Error>;
+	type SerializeStruct = Impossible<BackupStatement, Error>;
+	type SerializeStructVariant = Impossible<BackupStatement, Error>;
+
+	const EXPECTED: &'static str = "an enum `BackupStatement`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Ns" => Ok(BackupStatement::Ns),
+			"Db" => Ok(BackupStatement::Db),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+	use serde::Serialize;
+
+	#[test]
+	fn ns() {
+		let stmt = BackupStatement::Ns;
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
+	#[test]
+	fn db() {
+		let stmt = BackupStatement::Db;
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+}
@@ -0,0 +1,65 @@
+use crate::err::Error;
+use crate::sql::statements::combine::Combinator;
+use crate::sql::value::serde::ser;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Combinator;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Combinator, Error>;
+	type SerializeTuple = Impossible<Combinator, Error>;
+	type SerializeTupleStruct = Impossible<Combinator, Error>;
+	type SerializeTupleVariant = Impossible<Combinator, Error>;
+	type SerializeMap = Impossible<Combinator, Error>;
+	type SerializeStruct = Impossible<Combinator, Error>;
+	type SerializeStructVariant = Impossible<Combinator, Error>;
+
+	const EXPECTED: &'static str = "an enum `Combinator`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Union" => Ok(Combinator::Union),
+			"Intersect" => Ok(Combinator::Intersect),
+			"Except" => Ok(Combinator::Except),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+	use serde::Serialize;
+
+	#[test]
+	fn union() {
+		let kind = Combinator::Union;
+		let serialized = kind.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(kind, serialized);
+	}
+
+	#[test]
+	fn intersect() {
+		let kind = Combinator::Intersect;
+		let serialized = kind.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(kind, serialized);
+	}
+
+	#[test]
+	fn except() {
+		let kind = Combinator::Except;
+		let serialized = kind.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(kind, serialized);
+	}
+}
@@ -0,0 +1,146 @@
+mod combinator;
+mod with;
+
+use crate::err::Error;
+use crate::sql::statements::combine::{Combine, CombineStatement};
+use crate::sql::statements::SelectStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Limit;
+use crate::sql::Orders;
+use crate::sql::Start;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = CombineStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<CombineStatement, Error>;
+	type SerializeTuple = Impossible<CombineStatement, Error>;
+	type SerializeTupleStruct = Impossible<CombineStatement, Error>;
+	type SerializeTupleVariant = Impossible<CombineStatement, Error>;
+	type SerializeMap = Impossible<CombineStatement, Error>;
+	type SerializeStruct = SerializeCombineStatement;
+	type SerializeStructVariant = Impossible<CombineStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `CombineStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeCombineStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeCombineStatement {
+	what: Option<SelectStatement>,
+	with: Option<Vec<Combine>>,
+	order: Option<Orders>,
+	limit: Option<Limit>,
+	start: Option<Start>,
+}
+
+impl serde::ser::SerializeStruct for SerializeCombineStatement {
+	type Ok = CombineStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"what" => {
+				self.what = Some(value.serialize(super::select::Serializer.wrap())?);
+			}
+			"with" => {
+				self.with = Some(value.serialize(with::vec::Serializer.wrap())?);
+			}
+			"order" => {
+				self.order = value.serialize(ser::order::vec::opt::Serializer.wrap())?.map(Orders);
+			}
+			"limit" => {
+				self.limit = value.serialize(ser::limit::opt::Serializer.wrap())?;
+			}
+			"start" => {
+				self.start = value.serialize(ser::start::opt::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `CombineStatement::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		match (self.what, self.with) {
+			(Some(what), Some(with)) => Ok(CombineStatement {
+				what,
+				with,
+				order: self.order,
+				limit: self.limit,
+				start: self.start,
+			}),
+			_ => Err(Error::custom("`CombineStatement` missing required field(s)")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = CombineStatement::default();
+		let value: CombineStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_with() {
+		let stmt = CombineStatement {
+			with: vec![Combine::default()],
+			..Default::default()
+		};
+		let value: CombineStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_order() {
+		let stmt = CombineStatement {
+			order: Some(Default::default()),
+			..Default::default()
+		};
+		let value: CombineStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_limit() {
+		let stmt = CombineStatement {
+			limit: Some(Default::default()),
+			..Default::default()
+		};
+		let value: CombineStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_start() {
+		let stmt = CombineStatement {
+			start: Some(Default::default()),
+			..Default::default()
+		};
+		let value: CombineStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -0,0 +1,103 @@
+pub(super) mod vec;
+
+use crate::err::Error;
+use crate::sql::statements::combine::{Combinator, Combine};
+use crate::sql::statements::SelectStatement;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Combine;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Combine, Error>;
+	type SerializeTuple = Impossible<Combine, Error>;
+	type SerializeTupleStruct = Impossible<Combine, Error>;
+	type SerializeTupleVariant = Impossible<Combine, Error>;
+	type SerializeMap = Impossible<Combine, Error>;
+	type SerializeStruct = SerializeCombine;
+	type SerializeStructVariant = Impossible<Combine, Error>;
+
+	const EXPECTED: &'static str = "a struct `Combine`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeCombine::default())
+	}
+}
+
+#[derive(Default)]
+pub(super) struct SerializeCombine {
+	kind: Option<Combinator>,
+	all: Option<bool>,
+	what: Option<SelectStatement>,
+}
+
+impl serde::ser::SerializeStruct for SerializeCombine {
+	type Ok = Combine;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"kind" => {
+				self.kind = Some(value.serialize(super::combinator::Serializer.wrap())?);
+			}
+			"all" => {
+				self.all = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
+			"what" => {
+				self.what = Some(value.serialize(ser::statement::select::Serializer.wrap())?);
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Combine::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		match (self.kind, self.all, self.what) {
+			(Some(kind), Some(all), Some(what)) => Ok(Combine {
+				kind,
+				all,
+				what,
+			}),
+			_ => Err(Error::custom("`Combine` missing required field(s)")),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let combine = Combine::default();
+		let serialized = combine.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(combine, serialized);
+	}
+
+	#[test]
+	fn with_all() {
+		let combine = Combine {
+			kind: Combinator::Except,
+			all: true,
+			..Default::default()
+		};
+		let serialized = combine.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(combine, serialized);
+	}
+}
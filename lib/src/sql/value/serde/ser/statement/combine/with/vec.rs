@@ -0,0 +1,65 @@
+use crate::err::Error;
+use crate::sql::statements::combine::Combine;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Vec<Combine>;
+	type Error = Error;
+
+	type SerializeSeq = SerializeCombineVec;
+	type SerializeTuple = Impossible<Vec<Combine>, Error>;
+	type SerializeTupleStruct = Impossible<Vec<Combine>, Error>;
+	type SerializeTupleVariant = Impossible<Vec<Combine>, Error>;
+	type SerializeMap = Impossible<Vec<Combine>, Error>;
+	type SerializeStruct = Impossible<Vec<Combine>, Error>;
+	type SerializeStructVariant = Impossible<Vec<Combine>, Error>;
+
+	const EXPECTED: &'static str = "a `Vec<Combine>`";
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+		Ok(SerializeCombineVec(Vec::with_capacity(len.unwrap_or_default())))
+	}
+}
+
+pub struct SerializeCombineVec(Vec<Combine>);
+
+impl serde::ser::SerializeSeq for SerializeCombineVec {
+	type Ok = Vec<Combine>;
+	type Error = Error;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		self.0.push(value.serialize(super::Serializer.wrap())?);
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn empty() {
+		let vec: Vec<Combine> = Vec::new();
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+
+	#[test]
+	fn vec() {
+		let vec = vec![Combine::default()];
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+}
@@ -44,6 +44,7 @@ pub struct SerializeCreateStatement {
 	output: Option<Output>,
 	timeout: Option<Timeout>,
 	parallel: Option<bool>,
+	if_not_exists: Option<bool>,
 }
 
 impl serde::ser::SerializeStruct for SerializeCreateStatement {
@@ -72,6 +73,10 @@ impl serde::ser::SerializeStruct for SerializeCreateStatement {
 			"parallel" => {
 				self.parallel = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
 			}
+			"if_not_exists" => {
+				self.if_not_exists =
+					Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `CreateStatement::{key}`")));
 			}
@@ -80,10 +85,11 @@ impl serde::ser::SerializeStruct for SerializeCreateStatement {
 	}
 
 	fn end(self) -> Result<Self::Ok, Error> {
-		match (self.what, self.parallel) {
-			(Some(what), Some(parallel)) => Ok(CreateStatement {
+		match (self.what, self.parallel, self.if_not_exists) {
+			(Some(what), Some(parallel), Some(if_not_exists)) => Ok(CreateStatement {
 				what,
 				parallel,
+				if_not_exists,
 				data: self.data,
 				output: self.output,
 				timeout: self.timeout,
@@ -133,4 +139,14 @@ mod tests {
 		let value: CreateStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = CreateStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: CreateStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
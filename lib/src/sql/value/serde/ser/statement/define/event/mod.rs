@@ -1,4 +1,7 @@
+mod trigger;
+
 use crate::err::Error;
+use crate::sql::statements::define::Trigger;
 use crate::sql::statements::DefineEventStatement;
 use crate::sql::value::serde::ser;
 use crate::sql::Ident;
@@ -43,6 +46,7 @@ pub struct SerializeDefineEventStatement {
 	when: Value,
 	then: Values,
 	comment: Option<Strand>,
+	trigger: Option<Trigger>,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineEventStatement {
@@ -69,6 +73,9 @@ impl serde::ser::SerializeStruct for SerializeDefineEventStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"trigger" => {
+				self.trigger = value.serialize(trigger::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineEventStatement::{key}`"
@@ -85,6 +92,7 @@ impl serde::ser::SerializeStruct for SerializeDefineEventStatement {
 			when: self.when,
 			then: self.then,
 			comment: self.comment,
+			trigger: self.trigger,
 		})
 	}
 }
@@ -99,4 +107,14 @@ mod tests {
 		let value: DefineEventStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_trigger() {
+		let stmt = DefineEventStatement {
+			trigger: Some(Trigger::Before),
+			..Default::default()
+		};
+		let value: DefineEventStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
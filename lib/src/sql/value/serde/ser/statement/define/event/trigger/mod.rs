@@ -0,0 +1,59 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::statements::define::Trigger;
+use crate::sql::value::serde::ser;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Trigger;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Trigger, Error>;
+	type SerializeTuple = Impossible<Trigger, Error>;
+	type SerializeTupleStruct = Impossible<Trigger, Error>;
+	type SerializeTupleVariant = Impossible<Trigger, Error>;
+	type SerializeMap = Impossible<Trigger, Error>;
+	type SerializeStruct = Impossible<Trigger, Error>;
+	type SerializeStructVariant = Impossible<Trigger, Error>;
+
+	const EXPECTED: &'static str = "an enum `Trigger`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Before" => Ok(Trigger::Before),
+			"After" => Ok(Trigger::After),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+	use serde::Serialize;
+
+	#[test]
+	fn before() {
+		let trigger = Trigger::Before;
+		let serialized = trigger.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(trigger, serialized);
+	}
+
+	#[test]
+	fn after() {
+		let trigger = Trigger::After;
+		let serialized = trigger.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(trigger, serialized);
+	}
+}
@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::statements::define::Trigger;
+use crate::sql::value::serde::ser;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Trigger>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Trigger>, Error>;
+	type SerializeTuple = Impossible<Option<Trigger>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Trigger>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Trigger>, Error>;
+	type SerializeMap = Impossible<Option<Trigger>, Error>;
+	type SerializeStruct = Impossible<Option<Trigger>, Error>;
+	type SerializeStructVariant = Impossible<Option<Trigger>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Trigger>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Trigger> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Trigger::After);
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
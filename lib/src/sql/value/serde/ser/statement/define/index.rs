@@ -1,5 +1,5 @@
 use crate::err::Error;
-use crate::sql::index::Index;
+use crate::sql::index::{Collation, Index};
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::value::serde::ser;
 use crate::sql::Ident;
@@ -43,6 +43,9 @@ pub struct SerializeDefineIndexStatement {
 	cols: Idioms,
 	index: Index,
 	comment: Option<Strand>,
+	building: bool,
+	collate: Option<Collation>,
+	failure: Option<Strand>,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
@@ -69,6 +72,15 @@ impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"building" => {
+				self.building = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"collate" => {
+				self.collate = value.serialize(ser::collation::opt::Serializer.wrap())?;
+			}
+			"failure" => {
+				self.failure = value.serialize(ser::strand::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineIndexStatement::{key}`"
@@ -85,6 +97,9 @@ impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
 			cols: self.cols,
 			index: self.index,
 			comment: self.comment,
+			building: self.building,
+			collate: self.collate,
+			failure: self.failure,
 		})
 	}
 }
@@ -99,4 +114,34 @@ mod tests {
 		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn building() {
+		let stmt = DefineIndexStatement {
+			building: true,
+			..Default::default()
+		};
+		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn collate() {
+		let stmt = DefineIndexStatement {
+			collate: Some(Collation::default()),
+			..Default::default()
+		};
+		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn failure() {
+		let stmt = DefineIndexStatement {
+			failure: Some(Default::default()),
+			..Default::default()
+		};
+		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -0,0 +1,100 @@
+use crate::err::Error;
+use crate::sql::statements::DefineSequenceStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use crate::sql::Strand;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = DefineSequenceStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<DefineSequenceStatement, Error>;
+	type SerializeTuple = Impossible<DefineSequenceStatement, Error>;
+	type SerializeTupleStruct = Impossible<DefineSequenceStatement, Error>;
+	type SerializeTupleVariant = Impossible<DefineSequenceStatement, Error>;
+	type SerializeMap = Impossible<DefineSequenceStatement, Error>;
+	type SerializeStruct = SerializeDefineSequenceStatement;
+	type SerializeStructVariant = Impossible<DefineSequenceStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `DefineSequenceStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeDefineSequenceStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeDefineSequenceStatement {
+	name: Ident,
+	start: Option<i64>,
+	comment: Option<Strand>,
+}
+
+impl serde::ser::SerializeStruct for SerializeDefineSequenceStatement {
+	type Ok = DefineSequenceStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"start" => {
+				self.start = value.serialize(ser::primitive::i64::opt::Serializer.wrap())?;
+			}
+			"comment" => {
+				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `DefineSequenceStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(DefineSequenceStatement {
+			name: self.name,
+			start: self.start,
+			comment: self.comment,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = DefineSequenceStatement::default();
+		let value: DefineSequenceStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_start() {
+		let stmt = DefineSequenceStatement {
+			start: Some(-10),
+			..Default::default()
+		};
+		let value: DefineSequenceStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
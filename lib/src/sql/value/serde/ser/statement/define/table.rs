@@ -2,6 +2,7 @@ use crate::err::Error;
 use crate::sql::changefeed::ChangeFeed;
 use crate::sql::statements::DefineTableStatement;
 use crate::sql::value::serde::ser;
+use crate::sql::Duration;
 use crate::sql::Ident;
 use crate::sql::Permissions;
 use crate::sql::Strand;
@@ -46,7 +47,9 @@ pub struct SerializeDefineTableStatement {
 	view: Option<View>,
 	permissions: Permissions,
 	changefeed: Option<ChangeFeed>,
+	ttl: Option<Duration>,
 	comment: Option<Strand>,
+	interned: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
@@ -79,9 +82,15 @@ impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
 			"changefeed" => {
 				self.changefeed = value.serialize(ser::changefeed::opt::Serializer.wrap())?;
 			}
+			"ttl" => {
+				self.ttl = value.serialize(ser::duration::opt::Serializer.wrap())?.map(Into::into);
+			}
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"interned" => {
+				self.interned = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineTableStatement::{key}`"
@@ -100,7 +109,9 @@ impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
 			view: self.view,
 			permissions: self.permissions,
 			changefeed: self.changefeed,
+			ttl: self.ttl,
 			comment: self.comment,
+			interned: self.interned,
 		})
 	}
 }
@@ -115,4 +126,15 @@ mod tests {
 		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn interned() {
+		let stmt = DefineTableStatement {
+			id: Some(1),
+			interned: true,
+			..Default::default()
+		};
+		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
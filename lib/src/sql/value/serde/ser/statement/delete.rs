@@ -2,6 +2,8 @@ use crate::err::Error;
 use crate::sql::statements::DeleteStatement;
 use crate::sql::value::serde::ser;
 use crate::sql::Cond;
+use crate::sql::Limit;
+use crate::sql::Orders;
 use crate::sql::Output;
 use crate::sql::Timeout;
 use crate::sql::Values;
@@ -43,6 +45,8 @@ pub struct SerializeDeleteStatement {
 	output: Option<Output>,
 	timeout: Option<Timeout>,
 	parallel: Option<bool>,
+	order: Option<Orders>,
+	limit: Option<Limit>,
 }
 
 impl serde::ser::SerializeStruct for SerializeDeleteStatement {
@@ -69,6 +73,12 @@ impl serde::ser::SerializeStruct for SerializeDeleteStatement {
 			"parallel" => {
 				self.parallel = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
 			}
+			"order" => {
+				self.order = value.serialize(ser::order::vec::opt::Serializer.wrap())?.map(Orders);
+			}
+			"limit" => {
+				self.limit = value.serialize(ser::limit::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `DeleteStatement::{key}`")));
 			}
@@ -84,6 +94,8 @@ impl serde::ser::SerializeStruct for SerializeDeleteStatement {
 				cond: self.cond,
 				output: self.output,
 				timeout: self.timeout,
+				order: self.order,
+				limit: self.limit,
 			}),
 			_ => Err(Error::custom("`DeleteStatement` missing required value(s)")),
 		}
@@ -130,4 +142,24 @@ mod tests {
 		let value: DeleteStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_order() {
+		let stmt = DeleteStatement {
+			order: Some(Default::default()),
+			..Default::default()
+		};
+		let value: DeleteStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_limit() {
+		let stmt = DeleteStatement {
+			limit: Some(Default::default()),
+			..Default::default()
+		};
+		let value: DeleteStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
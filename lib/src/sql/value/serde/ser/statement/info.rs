@@ -35,6 +35,8 @@ impl ser::Serializer for Serializer {
 			"Root" => Ok(InfoStatement::Root),
 			"Ns" => Ok(InfoStatement::Ns),
 			"Db" => Ok(InfoStatement::Db),
+			"Sessions" => Ok(InfoStatement::Sessions),
+			"Stats" => Ok(InfoStatement::Stats),
 			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
 		}
 	}
@@ -53,6 +55,9 @@ impl ser::Serializer for Serializer {
 		match variant {
 			"Sc" => Ok(InfoStatement::Sc(Ident(value.serialize(ser::string::Serializer.wrap())?))),
 			"Tb" => Ok(InfoStatement::Tb(Ident(value.serialize(ser::string::Serializer.wrap())?))),
+			"TbStats" => {
+				Ok(InfoStatement::TbStats(Ident(value.serialize(ser::string::Serializer.wrap())?)))
+			}
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -68,7 +73,10 @@ impl ser::Serializer for Serializer {
 		_len: usize,
 	) -> Result<Self::SerializeTupleVariant, Self::Error> {
 		match variant {
-			"User" => Ok(SerializeInfoStatement::default()),
+			"User" | "Ix" => Ok(SerializeInfoStatement {
+				variant,
+				..Default::default()
+			}),
 			variant => Err(Error::custom(format!("unexpected tuple variant `{name}::{variant}`"))),
 		}
 	}
@@ -76,8 +84,9 @@ impl ser::Serializer for Serializer {
 
 #[derive(Default)]
 pub(super) struct SerializeInfoStatement {
+	variant: &'static str,
 	index: usize,
-	tuple: (Option<Ident>, Option<Base>),
+	tuple: (Option<Ident>, Option<Base>, Option<Ident>),
 }
 
 impl serde::ser::SerializeTupleVariant for SerializeInfoStatement {
@@ -88,16 +97,19 @@ impl serde::ser::SerializeTupleVariant for SerializeInfoStatement {
 	where
 		T: Serialize + ?Sized,
 	{
-		match self.index {
-			0 => {
+		match (self.variant, self.index) {
+			(_, 0) => {
 				self.tuple.0 = Some(Ident(value.serialize(ser::string::Serializer.wrap())?));
 			}
-			1 => {
+			("User", 1) => {
 				self.tuple.1 = value.serialize(ser::base::opt::Serializer.wrap())?;
 			}
-			index => {
+			("Ix", 1) => {
+				self.tuple.2 = Some(Ident(value.serialize(ser::string::Serializer.wrap())?));
+			}
+			(variant, index) => {
 				return Err(Error::custom(format!(
-					"unexpected `InfoStatement::User` index `{index}`"
+					"unexpected `InfoStatement::{variant}` index `{index}`"
 				)));
 			}
 		}
@@ -106,9 +118,12 @@ impl serde::ser::SerializeTupleVariant for SerializeInfoStatement {
 	}
 
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		match self.tuple.0 {
-			Some(ident) => Ok(InfoStatement::User(ident, self.tuple.1)),
-			None => Err(Error::custom("`InfoStatement::User` missing required value(s)")),
+		match (self.variant, self.tuple) {
+			("User", (Some(ident), base, _)) => Ok(InfoStatement::User(ident, base)),
+			("Ix", (Some(ix), _, Some(tb))) => Ok(InfoStatement::Ix(ix, tb)),
+			(variant, _) => {
+				Err(Error::custom(format!("`InfoStatement::{variant}` missing required value(s)")))
+			}
 		}
 	}
 }
@@ -138,6 +153,20 @@ mod tests {
 		assert_eq!(stmt, serialized);
 	}
 
+	#[test]
+	fn sessions() {
+		let stmt = InfoStatement::Sessions;
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
+	#[test]
+	fn stats() {
+		let stmt = InfoStatement::Stats;
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
 	#[test]
 	fn sc() {
 		let stmt = InfoStatement::Sc(Default::default());
@@ -152,10 +181,24 @@ mod tests {
 		assert_eq!(stmt, serialized);
 	}
 
+	#[test]
+	fn tb_stats() {
+		let stmt = InfoStatement::TbStats(Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
 	#[test]
 	fn user() {
 		let stmt = InfoStatement::User(Default::default(), Default::default());
 		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(stmt, serialized);
 	}
+
+	#[test]
+	fn ix() {
+		let stmt = InfoStatement::Ix(Default::default(), Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
 }
@@ -45,6 +45,7 @@ pub struct SerializeInsertStatement {
 	output: Option<Output>,
 	timeout: Option<Timeout>,
 	parallel: Option<bool>,
+	partial: Option<bool>,
 }
 
 impl serde::ser::SerializeStruct for SerializeInsertStatement {
@@ -77,6 +78,9 @@ impl serde::ser::SerializeStruct for SerializeInsertStatement {
 			"parallel" => {
 				self.parallel = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
 			}
+			"partial" => {
+				self.partial = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `InsertStatement::{key}`")));
 			}
@@ -85,16 +89,19 @@ impl serde::ser::SerializeStruct for SerializeInsertStatement {
 	}
 
 	fn end(self) -> Result<Self::Ok, Error> {
-		match (self.into, self.data, self.ignore, self.parallel) {
-			(Some(into), Some(data), Some(ignore), Some(parallel)) => Ok(InsertStatement {
-				into,
-				data,
-				ignore,
-				parallel,
-				update: self.update,
-				output: self.output,
-				timeout: self.timeout,
-			}),
+		match (self.into, self.data, self.ignore, self.parallel, self.partial) {
+			(Some(into), Some(data), Some(ignore), Some(parallel), Some(partial)) => {
+				Ok(InsertStatement {
+					into,
+					data,
+					ignore,
+					parallel,
+					partial,
+					update: self.update,
+					output: self.output,
+					timeout: self.timeout,
+				})
+			}
 			_ => Err(Error::custom("`InsertStatement` missing required value(s)")),
 		}
 	}
@@ -140,4 +147,14 @@ mod tests {
 		let value: InsertStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_partial() {
+		let stmt = InsertStatement {
+			partial: true,
+			..Default::default()
+		};
+		let value: InsertStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -36,6 +36,7 @@ impl ser::Serializer for Serializer {
 #[derive(Default)]
 pub struct SerializeKillStatement {
 	id: Option<Value>,
+	session: Option<bool>,
 }
 
 impl serde::ser::SerializeStruct for SerializeKillStatement {
@@ -50,6 +51,9 @@ impl serde::ser::SerializeStruct for SerializeKillStatement {
 			"id" => {
 				self.id = Some(value.serialize(ser::value::Serializer.wrap())?);
 			}
+			"session" => {
+				self.session = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `KillStatement::{key}`")));
 			}
@@ -58,11 +62,12 @@ impl serde::ser::SerializeStruct for SerializeKillStatement {
 	}
 
 	fn end(self) -> Result<Self::Ok, Error> {
-		match self.id {
-			Some(id) => Ok(KillStatement {
+		match (self.id, self.session) {
+			(Some(id), Some(session)) => Ok(KillStatement {
 				id,
+				session,
 			}),
-			None => Err(Error::custom("`KillStatement` missing required field")),
+			_ => Err(Error::custom("`KillStatement` missing required field(s)")),
 		}
 	}
 }
@@ -77,4 +82,14 @@ mod tests {
 		let value: KillStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_session() {
+		let stmt = KillStatement {
+			id: Value::from("session"),
+			session: true,
+		};
+		let value: KillStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
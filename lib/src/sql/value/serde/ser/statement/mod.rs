@@ -1,7 +1,10 @@
+pub mod alter;
 pub mod analyze;
+pub mod backup;
 pub mod begin;
 pub mod r#break;
 pub mod cancel;
+pub mod combine;
 pub mod commit;
 pub mod r#continue;
 pub mod create;
@@ -14,13 +17,19 @@ pub mod kill;
 pub mod live;
 pub mod option;
 pub mod output;
+pub mod rebuild;
+pub mod refresh;
 pub mod relate;
+pub mod release;
 pub mod remove;
+pub mod rollback;
+pub mod savepoint;
 pub mod select;
 pub mod set;
 pub mod show;
 pub mod sleep;
 pub mod throw;
+pub mod truncate;
 pub mod update;
 pub mod vec;
 pub mod yuse;
@@ -60,10 +69,13 @@ impl ser::Serializer for Serializer {
 		T: ?Sized + Serialize,
 	{
 		match variant {
+			"Alter" => Ok(Statement::Alter(value.serialize(alter::Serializer.wrap())?)),
 			"Analyze" => Ok(Statement::Analyze(value.serialize(analyze::Serializer.wrap())?)),
+			"Backup" => Ok(Statement::Backup(value.serialize(backup::Serializer.wrap())?)),
 			"Begin" => Ok(Statement::Begin(value.serialize(begin::Serializer.wrap())?)),
 			"Break" => Ok(Statement::Break(value.serialize(r#break::Serializer.wrap())?)),
 			"Cancel" => Ok(Statement::Cancel(value.serialize(cancel::Serializer.wrap())?)),
+			"Combine" => Ok(Statement::Combine(value.serialize(combine::Serializer.wrap())?)),
 			"Commit" => Ok(Statement::Commit(value.serialize(commit::Serializer.wrap())?)),
 			"Continue" => Ok(Statement::Continue(value.serialize(r#continue::Serializer.wrap())?)),
 			"Create" => Ok(Statement::Create(value.serialize(create::Serializer.wrap())?)),
@@ -76,13 +88,19 @@ impl ser::Serializer for Serializer {
 			"Live" => Ok(Statement::Live(value.serialize(live::Serializer.wrap())?)),
 			"Option" => Ok(Statement::Option(value.serialize(option::Serializer.wrap())?)),
 			"Output" => Ok(Statement::Output(value.serialize(output::Serializer.wrap())?)),
+			"Rebuild" => Ok(Statement::Rebuild(value.serialize(rebuild::Serializer.wrap())?)),
+			"Refresh" => Ok(Statement::Refresh(value.serialize(refresh::Serializer.wrap())?)),
 			"Relate" => Ok(Statement::Relate(value.serialize(relate::Serializer.wrap())?)),
+			"Release" => Ok(Statement::Release(value.serialize(release::Serializer.wrap())?)),
 			"Remove" => Ok(Statement::Remove(value.serialize(remove::Serializer.wrap())?)),
+			"Rollback" => Ok(Statement::Rollback(value.serialize(rollback::Serializer.wrap())?)),
+			"Savepoint" => Ok(Statement::Savepoint(value.serialize(savepoint::Serializer.wrap())?)),
 			"Select" => Ok(Statement::Select(value.serialize(select::Serializer.wrap())?)),
 			"Set" => Ok(Statement::Set(value.serialize(set::Serializer.wrap())?)),
 			"Show" => Ok(Statement::Show(value.serialize(show::Serializer.wrap())?)),
 			"Sleep" => Ok(Statement::Sleep(value.serialize(sleep::Serializer.wrap())?)),
 			"Throw" => Ok(Statement::Throw(value.serialize(throw::Serializer.wrap())?)),
+			"Truncate" => Ok(Statement::Truncate(value.serialize(truncate::Serializer.wrap())?)),
 			"Update" => Ok(Statement::Update(value.serialize(update::Serializer.wrap())?)),
 			"Use" => Ok(Statement::Use(value.serialize(yuse::Serializer.wrap())?)),
 			variant => {
@@ -96,12 +114,22 @@ impl ser::Serializer for Serializer {
 mod tests {
 	use super::*;
 	use crate::sql::statements::analyze::AnalyzeStatement;
+	use crate::sql::statements::AlterStatement;
 	use crate::sql::statements::DefineStatement;
 	use crate::sql::statements::InfoStatement;
+	use crate::sql::statements::RebuildStatement;
 	use crate::sql::statements::RemoveStatement;
 	use ser::Serializer as _;
 	use serde::Serialize;
 
+	#[test]
+	fn alter() {
+		let statement =
+			Statement::Alter(AlterStatement::Schema(Default::default(), Default::default()));
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
 	#[test]
 	fn analyze() {
 		let statement =
@@ -110,6 +138,13 @@ mod tests {
 		assert_eq!(statement, serialized);
 	}
 
+	#[test]
+	fn backup() {
+		let statement = Statement::Backup(Default::default());
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
 	#[test]
 	fn begin() {
 		let statement = Statement::Begin(Default::default());
@@ -124,6 +159,13 @@ mod tests {
 		assert_eq!(statement, serialized);
 	}
 
+	#[test]
+	fn combine() {
+		let statement = Statement::Combine(Default::default());
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
 	#[test]
 	fn commit() {
 		let statement = Statement::Commit(Default::default());
@@ -201,6 +243,20 @@ mod tests {
 		assert_eq!(statement, serialized);
 	}
 
+	#[test]
+	fn rebuild() {
+		let statement = Statement::Rebuild(RebuildStatement::Index(Default::default()));
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
+	#[test]
+	fn refresh() {
+		let statement = Statement::Refresh(Default::default());
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
 	#[test]
 	fn relate() {
 		let statement = Statement::Relate(Default::default());
@@ -208,6 +264,13 @@ mod tests {
 		assert_eq!(statement, serialized);
 	}
 
+	#[test]
+	fn release() {
+		let statement = Statement::Release(Default::default());
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
 	#[test]
 	fn remove() {
 		let statement = Statement::Remove(RemoveStatement::Table(Default::default()));
@@ -215,6 +278,20 @@ mod tests {
 		assert_eq!(statement, serialized);
 	}
 
+	#[test]
+	fn rollback() {
+		let statement = Statement::Rollback(Default::default());
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
+	#[test]
+	fn savepoint() {
+		let statement = Statement::Savepoint(Default::default());
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
 	#[test]
 	fn select() {
 		let statement = Statement::Select(Default::default());
@@ -243,6 +320,13 @@ mod tests {
 		assert_eq!(statement, serialized);
 	}
 
+	#[test]
+	fn truncate() {
+		let statement = Statement::Truncate(Default::default());
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
+
 	#[test]
 	fn update() {
 		let statement = Statement::Update(Default::default());
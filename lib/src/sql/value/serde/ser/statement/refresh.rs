@@ -0,0 +1,77 @@
+use crate::err::Error;
+use crate::sql::statements::RefreshStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = RefreshStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<RefreshStatement, Error>;
+	type SerializeTuple = Impossible<RefreshStatement, Error>;
+	type SerializeTupleStruct = Impossible<RefreshStatement, Error>;
+	type SerializeTupleVariant = Impossible<RefreshStatement, Error>;
+	type SerializeMap = Impossible<RefreshStatement, Error>;
+	type SerializeStruct = SerializeRefreshStatement;
+	type SerializeStructVariant = Impossible<RefreshStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `RefreshStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRefreshStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRefreshStatement {
+	name: Ident,
+}
+
+impl serde::ser::SerializeStruct for SerializeRefreshStatement {
+	type Ok = RefreshStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `RefreshStatement::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(RefreshStatement {
+			name: self.name,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = RefreshStatement::default();
+		let value: RefreshStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -46,6 +46,7 @@ pub struct SerializeRelateStatement {
 	output: Option<Output>,
 	timeout: Option<Timeout>,
 	parallel: Option<bool>,
+	both: Option<bool>,
 }
 
 impl serde::ser::SerializeStruct for SerializeRelateStatement {
@@ -81,6 +82,9 @@ impl serde::ser::SerializeStruct for SerializeRelateStatement {
 			"parallel" => {
 				self.parallel = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
 			}
+			"both" => {
+				self.both = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `RelateStatement::{key}`",)));
 			}
@@ -89,14 +93,15 @@ impl serde::ser::SerializeStruct for SerializeRelateStatement {
 	}
 
 	fn end(self) -> Result<Self::Ok, Error> {
-		match (self.kind, self.from, self.with, self.uniq, self.parallel) {
-			(Some(kind), Some(from), Some(with), Some(uniq), Some(parallel)) => {
+		match (self.kind, self.from, self.with, self.uniq, self.parallel, self.both) {
+			(Some(kind), Some(from), Some(with), Some(uniq), Some(parallel), Some(both)) => {
 				Ok(RelateStatement {
 					kind,
 					from,
 					with,
 					uniq,
 					parallel,
+					both,
 					data: self.data,
 					output: self.output,
 					timeout: self.timeout,
@@ -147,4 +152,15 @@ mod tests {
 		let value: RelateStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_both() {
+		let stmt = RelateStatement {
+			uniq: true,
+			both: true,
+			..Default::default()
+		};
+		let value: RelateStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
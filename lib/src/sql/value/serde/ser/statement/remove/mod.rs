@@ -7,6 +7,7 @@ mod index;
 mod namespace;
 mod param;
 mod scope;
+mod sequence;
 mod table;
 mod token;
 mod user;
@@ -66,6 +67,9 @@ impl ser::Serializer for Serializer {
 			"Field" => Ok(RemoveStatement::Field(value.serialize(field::Serializer.wrap())?)),
 			"Index" => Ok(RemoveStatement::Index(value.serialize(index::Serializer.wrap())?)),
 			"User" => Ok(RemoveStatement::User(value.serialize(user::Serializer.wrap())?)),
+			"Sequence" => {
+				Ok(RemoveStatement::Sequence(value.serialize(sequence::Serializer.wrap())?))
+			}
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -161,4 +165,11 @@ mod tests {
 		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(stmt, serialized);
 	}
+
+	#[test]
+	fn sequence() {
+		let stmt = RemoveStatement::Sequence(Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
 }
@@ -0,0 +1,79 @@
+use crate::err::Error;
+use crate::sql::statements::RemoveSequenceStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = RemoveSequenceStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<RemoveSequenceStatement, Error>;
+	type SerializeTuple = Impossible<RemoveSequenceStatement, Error>;
+	type SerializeTupleStruct = Impossible<RemoveSequenceStatement, Error>;
+	type SerializeTupleVariant = Impossible<RemoveSequenceStatement, Error>;
+	type SerializeMap = Impossible<RemoveSequenceStatement, Error>;
+	type SerializeStruct = SerializeRemoveSequenceStatement;
+	type SerializeStructVariant = Impossible<RemoveSequenceStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `RemoveSequenceStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRemoveSequenceStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRemoveSequenceStatement {
+	name: Ident,
+}
+
+impl serde::ser::SerializeStruct for SerializeRemoveSequenceStatement {
+	type Ok = RemoveSequenceStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `RemoveSequenceStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(RemoveSequenceStatement {
+			name: self.name,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = RemoveSequenceStatement::default();
+		let value: RemoveSequenceStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -0,0 +1,77 @@
+use crate::err::Error;
+use crate::sql::statements::SavepointStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = SavepointStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<SavepointStatement, Error>;
+	type SerializeTuple = Impossible<SavepointStatement, Error>;
+	type SerializeTupleStruct = Impossible<SavepointStatement, Error>;
+	type SerializeTupleVariant = Impossible<SavepointStatement, Error>;
+	type SerializeMap = Impossible<SavepointStatement, Error>;
+	type SerializeStruct = SerializeSavepointStatement;
+	type SerializeStructVariant = Impossible<SavepointStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `SavepointStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeSavepointStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeSavepointStatement {
+	name: Ident,
+}
+
+impl serde::ser::SerializeStruct for SerializeSavepointStatement {
+	type Ok = SavepointStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `SavepointStatement::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(SavepointStatement {
+			name: self.name,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = SavepointStatement::default();
+		let value: SavepointStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
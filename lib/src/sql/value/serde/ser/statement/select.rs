@@ -6,6 +6,7 @@ use crate::sql::with::With;
 use crate::sql::Cond;
 use crate::sql::Fetchs;
 use crate::sql::Fields;
+use crate::sql::Grouping;
 use crate::sql::Groups;
 use crate::sql::Idioms;
 use crate::sql::Limit;
@@ -13,6 +14,7 @@ use crate::sql::Orders;
 use crate::sql::Splits;
 use crate::sql::Start;
 use crate::sql::Timeout;
+use crate::sql::Value;
 use crate::sql::Values;
 use crate::sql::Version;
 use ser::Serializer as _;
@@ -63,6 +65,10 @@ pub struct SerializeSelectStatement {
 	timeout: Option<Timeout>,
 	parallel: Option<bool>,
 	explain: Option<Explain>,
+	grouping: Option<Grouping>,
+	having: Option<Cond>,
+	lock: Option<bool>,
+	after: Option<Value>,
 }
 
 impl serde::ser::SerializeStruct for SerializeSelectStatement {
@@ -119,6 +125,18 @@ impl serde::ser::SerializeStruct for SerializeSelectStatement {
 			"explain" => {
 				self.explain = value.serialize(ser::explain::opt::Serializer.wrap())?;
 			}
+			"having" => {
+				self.having = value.serialize(ser::cond::opt::Serializer.wrap())?;
+			}
+			"grouping" => {
+				self.grouping = Some(value.serialize(ser::group::grouping::Serializer.wrap())?);
+			}
+			"lock" => {
+				self.lock = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
+			"after" => {
+				self.after = value.serialize(ser::value::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `SelectStatement::{key}`")));
 			}
@@ -127,24 +145,30 @@ impl serde::ser::SerializeStruct for SerializeSelectStatement {
 	}
 
 	fn end(self) -> Result<Self::Ok, Error> {
-		match (self.expr, self.what, self.parallel) {
-			(Some(expr), Some(what), Some(parallel)) => Ok(SelectStatement {
-				expr,
-				omit: self.omit,
-				what,
-				with: self.with,
-				parallel,
-				explain: self.explain,
-				cond: self.cond,
-				split: self.split,
-				group: self.group,
-				order: self.order,
-				limit: self.limit,
-				start: self.start,
-				fetch: self.fetch,
-				version: self.version,
-				timeout: self.timeout,
-			}),
+		match (self.expr, self.what, self.parallel, self.grouping, self.lock) {
+			(Some(expr), Some(what), Some(parallel), Some(grouping), Some(lock)) => {
+				Ok(SelectStatement {
+					expr,
+					omit: self.omit,
+					what,
+					with: self.with,
+					parallel,
+					explain: self.explain,
+					cond: self.cond,
+					split: self.split,
+					group: self.group,
+					order: self.order,
+					limit: self.limit,
+					start: self.start,
+					fetch: self.fetch,
+					version: self.version,
+					timeout: self.timeout,
+					grouping,
+					having: self.having,
+					lock,
+					after: self.after,
+				})
+			}
 			_ => Err(Error::custom("`SelectStatement` missing required field(s)")),
 		}
 	}
@@ -191,6 +215,38 @@ mod tests {
 		assert_eq!(value, stmt);
 	}
 
+	#[test]
+	fn with_grouping() {
+		let stmt = SelectStatement {
+			group: Some(Default::default()),
+			grouping: Grouping::Cube,
+			..Default::default()
+		};
+		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_having() {
+		let stmt = SelectStatement {
+			group: Some(Default::default()),
+			having: Some(Default::default()),
+			..Default::default()
+		};
+		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_lock() {
+		let stmt = SelectStatement {
+			lock: true,
+			..Default::default()
+		};
+		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
 	#[test]
 	fn with_order() {
 		let stmt = SelectStatement {
@@ -264,7 +320,10 @@ mod tests {
 	#[test]
 	fn with_explain_full() {
 		let stmt = SelectStatement {
-			explain: Some(Explain(true)),
+			explain: Some(Explain {
+				full: true,
+				plan: true,
+			}),
 			..Default::default()
 		};
 		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
@@ -290,4 +349,14 @@ mod tests {
 		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_after() {
+		let stmt = SelectStatement {
+			after: Some(Default::default()),
+			..Default::default()
+		};
+		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -0,0 +1,77 @@
+use crate::err::Error;
+use crate::sql::statements::TruncateStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = TruncateStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<TruncateStatement, Error>;
+	type SerializeTuple = Impossible<TruncateStatement, Error>;
+	type SerializeTupleStruct = Impossible<TruncateStatement, Error>;
+	type SerializeTupleVariant = Impossible<TruncateStatement, Error>;
+	type SerializeMap = Impossible<TruncateStatement, Error>;
+	type SerializeStruct = SerializeTruncateStatement;
+	type SerializeStructVariant = Impossible<TruncateStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `TruncateStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeTruncateStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeTruncateStatement {
+	name: Ident,
+}
+
+impl serde::ser::SerializeStruct for SerializeTruncateStatement {
+	type Ok = TruncateStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `TruncateStatement::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(TruncateStatement {
+			name: self.name,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = TruncateStatement::default();
+		let value: TruncateStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -4,6 +4,8 @@ use crate::sql::value::serde::ser;
 use crate::sql::Cond;
 use crate::sql::Data;
 use crate::sql::Duration;
+use crate::sql::Limit;
+use crate::sql::Orders;
 use crate::sql::Output;
 use crate::sql::Timeout;
 use crate::sql::Values;
@@ -46,6 +48,8 @@ pub struct SerializeUpdateStatement {
 	output: Option<Output>,
 	timeout: Option<Timeout>,
 	parallel: Option<bool>,
+	order: Option<Orders>,
+	limit: Option<Limit>,
 }
 
 impl serde::ser::SerializeStruct for SerializeUpdateStatement {
@@ -77,6 +81,12 @@ impl serde::ser::SerializeStruct for SerializeUpdateStatement {
 			"parallel" => {
 				self.parallel = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
 			}
+			"order" => {
+				self.order = value.serialize(ser::order::vec::opt::Serializer.wrap())?.map(Orders);
+			}
+			"limit" => {
+				self.limit = value.serialize(ser::limit::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `UpdateStatement::{key}`")));
 			}
@@ -93,6 +103,8 @@ impl serde::ser::SerializeStruct for SerializeUpdateStatement {
 				cond: self.cond,
 				output: self.output,
 				timeout: self.timeout,
+				order: self.order,
+				limit: self.limit,
 			}),
 			_ => Err(Error::custom("`UpdateStatement` missing required field(s)")),
 		}
@@ -149,4 +161,24 @@ mod tests {
 		let value: UpdateStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_order() {
+		let stmt = UpdateStatement {
+			order: Some(Default::default()),
+			..Default::default()
+		};
+		let value: UpdateStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_limit() {
+		let stmt = UpdateStatement {
+			limit: Some(Default::default()),
+			..Default::default()
+		};
+		let value: UpdateStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
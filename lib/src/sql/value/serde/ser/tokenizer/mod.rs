@@ -3,8 +3,10 @@ pub(super) mod vec;
 use crate::err::Error;
 use crate::sql::tokenizer::Tokenizer;
 use crate::sql::value::serde::ser;
+use ser::Serializer as _;
 use serde::ser::Error as _;
 use serde::ser::Impossible;
+use serde::ser::Serialize;
 
 pub(super) struct Serializer;
 
@@ -15,7 +17,7 @@ impl ser::Serializer for Serializer {
 	type SerializeSeq = Impossible<Tokenizer, Error>;
 	type SerializeTuple = Impossible<Tokenizer, Error>;
 	type SerializeTupleStruct = Impossible<Tokenizer, Error>;
-	type SerializeTupleVariant = Impossible<Tokenizer, Error>;
+	type SerializeTupleVariant = SerializeTokenizer;
 	type SerializeMap = Impossible<Tokenizer, Error>;
 	type SerializeStruct = Impossible<Tokenizer, Error>;
 	type SerializeStructVariant = Impossible<Tokenizer, Error>;
@@ -37,13 +39,78 @@ impl ser::Serializer for Serializer {
 			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
 		}
 	}
+
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		let inner = match variant {
+			"Ngram" => Inner::Ngram(Default::default(), Default::default()),
+			"EdgeNgram" => Inner::EdgeNgram(Default::default(), Default::default()),
+			variant => {
+				return Err(Error::custom(format!("unexpected tuple variant `{name}::{variant}`")));
+			}
+		};
+		Ok(SerializeTokenizer {
+			inner,
+			index: 0,
+		})
+	}
+}
+
+pub(super) struct SerializeTokenizer {
+	index: usize,
+	inner: Inner,
+}
+
+enum Inner {
+	Ngram(u16, u16),
+	EdgeNgram(u16, u16),
+}
+
+impl serde::ser::SerializeTupleVariant for SerializeTokenizer {
+	type Ok = Tokenizer;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		match (self.index, &mut self.inner) {
+			(0, Inner::Ngram(ref mut var, _) | Inner::EdgeNgram(ref mut var, _)) => {
+				*var = value.serialize(ser::primitive::u16::Serializer.wrap())?;
+			}
+			(1, Inner::Ngram(_, ref mut var) | Inner::EdgeNgram(_, ref mut var)) => {
+				*var = value.serialize(ser::primitive::u16::Serializer.wrap())?;
+			}
+			(index, inner) => {
+				let variant = match inner {
+					Inner::Ngram(..) => "Ngram",
+					Inner::EdgeNgram(..) => "EdgeNgram",
+				};
+				return Err(Error::custom(format!(
+					"unexpected `Tokenizer::{variant}` index `{index}`"
+				)));
+			}
+		}
+		self.index += 1;
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		match self.inner {
+			Inner::Ngram(one, two) => Ok(Tokenizer::Ngram(one, two)),
+			Inner::EdgeNgram(one, two) => Ok(Tokenizer::EdgeNgram(one, two)),
+		}
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use ser::Serializer as _;
-	use serde::Serialize;
 
 	#[test]
 	fn blank() {
@@ -72,4 +139,18 @@ mod tests {
 		let serialized = tokenizer.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(tokenizer, serialized);
 	}
+
+	#[test]
+	fn ngram() {
+		let tokenizer = Tokenizer::Ngram(2, 4);
+		let serialized = tokenizer.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(tokenizer, serialized);
+	}
+
+	#[test]
+	fn edgengram() {
+		let tokenizer = Tokenizer::EdgeNgram(1, 10);
+		let serialized = tokenizer.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(tokenizer, serialized);
+	}
 }
@@ -1,7 +1,9 @@
 pub(super) mod opt;
+mod refresh;
 
 use crate::err::Error;
 use crate::sql::value::serde::ser;
+use crate::sql::view::Refresh;
 use crate::sql::Cond;
 use crate::sql::Fields;
 use crate::sql::Groups;
@@ -44,6 +46,7 @@ pub struct SerializeView {
 	what: Tables,
 	cond: Option<Cond>,
 	group: Option<Groups>,
+	refresh: Option<Refresh>,
 }
 
 impl serde::ser::SerializeStruct for SerializeView {
@@ -67,6 +70,9 @@ impl serde::ser::SerializeStruct for SerializeView {
 			"group" => {
 				self.group = value.serialize(ser::group::vec::opt::Serializer.wrap())?.map(Groups);
 			}
+			"refresh" => {
+				self.refresh = value.serialize(refresh::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `View::{key}`")));
 			}
@@ -80,6 +86,7 @@ impl serde::ser::SerializeStruct for SerializeView {
 			what: self.what,
 			cond: self.cond,
 			group: self.group,
+			refresh: self.refresh,
 		})
 	}
 }
@@ -94,4 +101,14 @@ mod tests {
 		let value: View = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_refresh() {
+		let stmt = View {
+			refresh: Some(Refresh::Every(Default::default())),
+			..Default::default()
+		};
+		let value: View = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
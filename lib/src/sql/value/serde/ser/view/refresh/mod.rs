@@ -0,0 +1,88 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::view::Refresh;
+use crate::sql::Duration;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Refresh;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Refresh, Error>;
+	type SerializeTuple = Impossible<Refresh, Error>;
+	type SerializeTupleStruct = Impossible<Refresh, Error>;
+	type SerializeTupleVariant = Impossible<Refresh, Error>;
+	type SerializeMap = Impossible<Refresh, Error>;
+	type SerializeStruct = Impossible<Refresh, Error>;
+	type SerializeStructVariant = Impossible<Refresh, Error>;
+
+	const EXPECTED: &'static str = "an enum `Refresh`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Incremental" => Ok(Refresh::Incremental),
+			"Manual" => Ok(Refresh::Manual),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+
+	#[inline]
+	fn serialize_newtype_variant<T>(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match variant {
+			"Every" => {
+				Ok(Refresh::Every(Duration(value.serialize(ser::duration::Serializer.wrap())?)))
+			}
+			variant => {
+				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn incremental() {
+		let refresh = Refresh::Incremental;
+		let serialized = refresh.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(refresh, serialized);
+	}
+
+	#[test]
+	fn manual() {
+		let refresh = Refresh::Manual;
+		let serialized = refresh.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(refresh, serialized);
+	}
+
+	#[test]
+	fn every() {
+		let refresh = Refresh::Every(Default::default());
+		let serialized = refresh.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(refresh, serialized);
+	}
+}
@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::view::Refresh;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Refresh>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Refresh>, Error>;
+	type SerializeTuple = Impossible<Option<Refresh>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Refresh>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Refresh>, Error>;
+	type SerializeMap = Impossible<Option<Refresh>, Error>;
+	type SerializeStruct = Impossible<Option<Refresh>, Error>;
+	type SerializeStructVariant = Impossible<Option<Refresh>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Refresh>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Refresh> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Refresh::Manual);
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
@@ -49,6 +49,7 @@ impl ser::Serializer for Serializer {
 	{
 		match variant {
 			"Index" => Ok(With::Index(value.serialize(ser::string::vec::Serializer.wrap())?)),
+			"Dynamic" => Ok(With::Dynamic(value.serialize(ser::value::vec::Serializer.wrap())?)),
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -59,6 +60,7 @@ impl ser::Serializer for Serializer {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::sql::Param;
 	use ser::Serializer as _;
 	use serde::Serialize;
 
@@ -75,4 +77,11 @@ mod tests {
 		let serialized = with.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(with, serialized);
 	}
+
+	#[test]
+	fn with_dynamic() {
+		let with = With::Dynamic(vec![Param::from("index").into()]);
+		let serialized = with.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(with, serialized);
+	}
 }
@@ -58,6 +58,7 @@ use serde_json::Value as Json;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::{self, Display, Formatter, Write};
 use std::ops::Deref;
 use std::ops::Neg;
@@ -2448,11 +2449,14 @@ impl Value {
 	/// Check if all Values in an Array contain another Value
 	pub fn contains_all(&self, other: &Value) -> bool {
 		match other {
-			Value::Array(v) => v.iter().all(|v| match self {
-				Value::Array(w) => w.iter().any(|w| v.equal(w)),
-				Value::Geometry(_) => self.contains(v),
-				_ => false,
-			}),
+			Value::Array(v) => match self {
+				// Use a hash lookup when values can be compared exactly
+				Value::Array(w) if !w.has_loose_values() && !v.has_loose_values() => {
+					let set: HashSet<&Value> = w.iter().collect();
+					v.iter().all(|v| set.contains(v))
+				}
+				_ => self.contains_all_loose(v),
+			},
 			_ => false,
 		}
 	}
@@ -2460,15 +2464,36 @@ impl Value {
 	/// Check if any Values in an Array contain another Value
 	pub fn contains_any(&self, other: &Value) -> bool {
 		match other {
-			Value::Array(v) => v.iter().any(|v| match self {
-				Value::Array(w) => w.iter().any(|w| v.equal(w)),
-				Value::Geometry(_) => self.contains(v),
-				_ => false,
-			}),
+			Value::Array(v) => match self {
+				// Use a hash lookup when values can be compared exactly
+				Value::Array(w) if !w.has_loose_values() && !v.has_loose_values() => {
+					let set: HashSet<&Value> = w.iter().collect();
+					v.iter().any(|v| set.contains(v))
+				}
+				_ => self.contains_any_loose(v),
+			},
 			_ => false,
 		}
 	}
 
+	/// Check if all Values in an Array contain another Value, using loose equality
+	fn contains_all_loose(&self, other: &Array) -> bool {
+		other.iter().all(|v| match self {
+			Value::Array(w) => w.iter().any(|w| v.equal(w)),
+			Value::Geometry(_) => self.contains(v),
+			_ => false,
+		})
+	}
+
+	/// Check if any Values in an Array contain another Value, using loose equality
+	fn contains_any_loose(&self, other: &Array) -> bool {
+		other.iter().any(|v| match self {
+			Value::Array(w) => w.iter().any(|w| v.equal(w)),
+			Value::Geometry(_) => self.contains(v),
+			_ => false,
+		})
+	}
+
 	/// Check if this Value intersects another Value
 	pub fn intersects(&self, other: &Value) -> bool {
 		match self {
@@ -2554,7 +2579,10 @@ impl Value {
 			Value::Array(v) => v.iter().any(Value::writeable),
 			Value::Object(v) => v.iter().any(|(_, v)| v.writeable()),
 			Value::Function(v) => {
-				v.is_custom() || v.is_script() || v.args().iter().any(Value::writeable)
+				v.is_custom()
+					|| v.is_script()
+					|| v.is_writeable()
+					|| v.args().iter().any(Value::writeable)
 			}
 			Value::Subquery(v) => v.writeable(),
 			Value::Expression(v) => v.writeable(),
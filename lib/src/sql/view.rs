@@ -1,24 +1,46 @@
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
+use crate::sql::duration::{duration, Duration};
 use crate::sql::error::IResult;
 use crate::sql::field::{fields, Fields};
 use crate::sql::group::{group, Groups};
 use crate::sql::table::{tables, Tables};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case};
-use nom::combinator::{cut, opt};
+use nom::combinator::{cut, map, opt, value};
 use nom::sequence::preceded;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct View {
 	pub expr: Fields,
 	pub what: Tables,
 	pub cond: Option<Cond>,
 	pub group: Option<Groups>,
+	#[revision(start = 2)]
+	pub refresh: Option<Refresh>,
+}
+
+/// How the records in a view are kept up to date
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Refresh {
+	/// The view is updated whenever a source record is written
+	Incremental,
+	/// The view is only updated by a REFRESH VIEW statement
+	Manual,
+	/// The view is recalculated on a fixed interval
+	Every(Duration),
+}
+
+impl View {
+	/// Check if the view is updated whenever a source record is written
+	pub(crate) fn is_incremental(&self) -> bool {
+		matches!(self.refresh, None | Some(Refresh::Incremental))
+	}
 }
 
 impl fmt::Display for View {
@@ -30,10 +52,23 @@ impl fmt::Display for View {
 		if let Some(ref v) = self.group {
 			write!(f, " {v}")?
 		}
+		if let Some(ref v) = self.refresh {
+			write!(f, " {v}")?
+		}
 		Ok(())
 	}
 }
 
+impl fmt::Display for Refresh {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Incremental => f.write_str("REFRESH INCREMENTAL"),
+			Self::Manual => f.write_str("REFRESH MANUAL"),
+			Self::Every(v) => write!(f, "REFRESH EVERY {v}"),
+		}
+	}
+}
+
 pub fn view(i: &str) -> IResult<&str, View> {
 	let select_view = |i| {
 		let (i, _) = tag_no_case("SELECT")(i)?;
@@ -62,6 +97,7 @@ pub fn view(i: &str) -> IResult<&str, View> {
 	let (i, _) = tag_no_case("AS")(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, (expr, what, cond, group)) = alt((select_view, select_view_delimited))(i)?;
+	let (i, refresh) = opt(preceded(shouldbespace, refresh))(i)?;
 	Ok((
 		i,
 		View {
@@ -69,10 +105,21 @@ pub fn view(i: &str) -> IResult<&str, View> {
 			what,
 			cond,
 			group,
+			refresh,
 		},
 	))
 }
 
+pub fn refresh(i: &str) -> IResult<&str, Refresh> {
+	let (i, _) = tag_no_case("REFRESH")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(alt((
+		value(Refresh::Incremental, tag_no_case("INCREMENTAL")),
+		value(Refresh::Manual, tag_no_case("MANUAL")),
+		map(preceded(tag_no_case("EVERY"), preceded(shouldbespace, duration)), Refresh::Every),
+	)))(i)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -120,4 +167,27 @@ mod tests {
 		// pair.
 		assert_eq!(i, ")");
 	}
+
+	#[test]
+	fn view_refresh() {
+		let sql = "AS SELECT count() AS total FROM test GROUP BY temp REFRESH MANUAL";
+		let res = view(sql);
+		let out = res.unwrap().1;
+		assert_eq!(Some(Refresh::Manual), out.refresh);
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn view_brackets_refresh_every() {
+		let sql = "AS (SELECT * FROM test) REFRESH EVERY 1h";
+		let res = view(sql);
+		let out = res.unwrap().1;
+		assert_eq!("AS SELECT * FROM test REFRESH EVERY 1h", format!("{}", out))
+	}
+
+	#[test]
+	fn view_refresh_invalid() {
+		let sql = "AS SELECT * FROM test REFRESH SOMETIMES";
+		view(sql).unwrap_err();
+	}
 }
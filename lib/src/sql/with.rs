@@ -1,23 +1,58 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
 use crate::sql::error::IResult;
 use crate::sql::ident::ident_raw;
+use crate::sql::param::param;
+use crate::sql::table::Table;
+use crate::sql::value::Value;
 use nom::bytes::complete::tag_no_case;
+use nom::combinator::map;
 use nom::multi::separated_list1;
 use nom::{branch::alt, combinator::cut};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter, Result};
+use std::fmt::{self, Display, Formatter, Write};
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[revisioned(revision = 1)]
 pub enum With {
 	NoIndex,
 	Index(Vec<String>),
+	/// Index names which include parameters
+	Dynamic(Vec<Value>),
+}
+
+impl With {
+	/// Compute any parameterized index names
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Self, Error> {
+		match self {
+			With::Dynamic(v) => {
+				let mut out = Vec::with_capacity(v.len());
+				for v in v.iter() {
+					match v.compute(ctx, opt, txn, doc).await? {
+						Value::Array(v) => out.extend(v.into_iter().map(|v| v.as_raw_string())),
+						v => out.push(v.as_raw_string()),
+					}
+				}
+				Ok(With::Index(out))
+			}
+			v => Ok(v.clone()),
+		}
+	}
 }
 
 impl Display for With {
-	fn fmt(&self, f: &mut Formatter) -> Result {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		f.write_str("WITH")?;
 		match self {
 			With::NoIndex => f.write_str(" NOINDEX"),
@@ -25,6 +60,16 @@ impl Display for With {
 				f.write_str(" INDEX ")?;
 				f.write_str(&i.join(","))
 			}
+			With::Dynamic(i) => {
+				f.write_str(" INDEX ")?;
+				for (n, v) in i.iter().enumerate() {
+					if n > 0 {
+						f.write_char(',')?;
+					}
+					Display::fmt(v, f)?;
+				}
+				Ok(())
+			}
 		}
 	}
 }
@@ -37,8 +82,15 @@ fn no_index(i: &str) -> IResult<&str, With> {
 fn index(i: &str) -> IResult<&str, With> {
 	let (i, _) = tag_no_case("INDEX")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	let (i, v) = cut(separated_list1(commas, ident_raw))(i)?;
-	Ok((i, With::Index(v)))
+	let (i, v) = cut(separated_list1(
+		commas,
+		alt((map(param, Value::from), map(ident_raw, |v| Value::from(Table(v))))),
+	))(i)?;
+	// Only use parameterized index names when needed
+	match v.iter().any(|v| matches!(v, Value::Param(_))) {
+		true => Ok((i, With::Dynamic(v))),
+		false => Ok((i, With::Index(v.into_iter().map(|v| v.as_raw_string()).collect()))),
+	}
 }
 
 pub fn with(i: &str) -> IResult<&str, With> {
@@ -69,4 +121,13 @@ mod tests {
 		assert_eq!(out, With::Index(vec!["idx".to_string(), "uniq".to_string()]));
 		assert_eq!("WITH INDEX idx,uniq", format!("{}", out));
 	}
+
+	#[test]
+	fn with_index_dynamic() {
+		let sql = "WITH INDEX idx,$index";
+		let res = with(sql);
+		let out = res.unwrap().1;
+		assert!(matches!(out, With::Dynamic(_)));
+		assert_eq!("WITH INDEX idx,$index", format!("{}", out));
+	}
 }
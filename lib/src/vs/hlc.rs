@@ -0,0 +1,120 @@
+//! Hybrid logical clock based timestamps.
+//! A hybrid logical clock combines the physical time of a node with a logical counter, so that
+//! the timestamps issued by the nodes of a multi-node deployment stay monotonic and causally
+//! ordered, even when the clocks of the nodes are skewed, or when a clock goes backwards.
+//!
+//! A timestamp is encoded as a `u64`, with the milliseconds since the Unix epoch in the upper
+//! 48 bits, and the logical counter in the lower 16 bits, so that timestamps sort in the order
+//! in which they were issued.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::std::{SystemTime, UNIX_EPOCH};
+
+/// Encodes the physical time, in milliseconds, and the logical counter of a timestamp
+pub fn pack(physical: u64, logical: u16) -> u64 {
+	physical << 16 | logical as u64
+}
+
+/// Returns the physical time of a timestamp, in milliseconds since the Unix epoch
+pub fn physical(ts: u64) -> u64 {
+	ts >> 16
+}
+
+/// Returns the logical counter of a timestamp
+pub fn logical(ts: u64) -> u16 {
+	ts as u16
+}
+
+#[derive(Debug, Default)]
+pub struct HybridLogicalClock {
+	// The physical time of the latest timestamp issued or observed
+	physical: u64,
+	// The logical counter of the latest timestamp issued or observed
+	logical: u16,
+}
+
+impl HybridLogicalClock {
+	/// Issue a timestamp at the current system time
+	pub fn now(&mut self) -> u64 {
+		self.now_at(millis_since_unix_epoch())
+	}
+
+	/// Issue a timestamp at a physical time, in milliseconds since the Unix epoch,
+	/// which is greater than every timestamp issued or observed by this clock
+	pub fn now_at(&mut self, physical: u64) -> u64 {
+		if physical > self.physical {
+			self.physical = physical;
+			self.logical = 0;
+		} else {
+			// The clock is behind the latest timestamp, so count on from it
+			match self.logical.checked_add(1) {
+				Some(v) => self.logical = v,
+				None => {
+					self.physical += 1;
+					self.logical = 0;
+				}
+			}
+		}
+		pack(self.physical, self.logical)
+	}
+
+	/// Observe a timestamp issued by another node, so that
+	/// every timestamp issued after it is greater than it
+	pub fn observe(&mut self, ts: u64) {
+		if ts > pack(self.physical, self.logical) {
+			self.physical = physical(ts);
+			self.logical = logical(ts);
+		}
+	}
+}
+
+// Returns the number of milliseconds since the Unix Epoch (January 1st, 1970 at UTC).
+fn millis_since_unix_epoch() -> u64 {
+	match SystemTime::now().duration_since(UNIX_EPOCH) {
+		Ok(duration) => duration.as_millis() as u64,
+		Err(error) => panic!("Clock may have gone backwards: {:?}", error.duration()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn monotonic() {
+		let mut c = HybridLogicalClock::default();
+		let a = c.now_at(1000);
+		let b = c.now_at(1000);
+		// The clock goes backwards
+		let d = c.now_at(500);
+		let e = c.now_at(2000);
+		assert!(a < b && b < d && d < e);
+		assert_eq!((physical(d), logical(d)), (1000, 2));
+		assert_eq!((physical(e), logical(e)), (2000, 0));
+	}
+
+	#[test]
+	fn observe_skewed_clock() {
+		let mut c = HybridLogicalClock::default();
+		let a = c.now_at(1000);
+		// Another node, whose clock is ahead, issued a timestamp
+		let remote = pack(5000, 3);
+		c.observe(remote);
+		let b = c.now_at(1001);
+		assert!(a < remote && remote < b);
+		assert_eq!((physical(b), logical(b)), (5000, 4));
+		// Observing an older timestamp does not move the clock back
+		c.observe(pack(10, 0));
+		assert!(c.now_at(1002) > b);
+	}
+
+	#[test]
+	fn logical_overflow() {
+		let mut c = HybridLogicalClock::default();
+		c.observe(pack(1000, u16::MAX));
+		let a = c.now_at(1000);
+		assert_eq!((physical(a), logical(a)), (1001, 0));
+	}
+}
@@ -8,6 +8,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 #[cfg(target_arch = "wasm32")]
 use wasmtimer::std::{SystemTime, UNIX_EPOCH};
 
+use super::hlc::{self, HybridLogicalClock};
 use super::{u16_u64_to_versionstamp, u64_to_versionstamp, u64_u16_to_versionstamp, Versionstamp};
 
 // A versionstamp oracle is a source of truth for the current versionstamp of the database.
@@ -52,6 +53,13 @@ pub enum Oracle {
 	// EpochCounter is designed to be used instead of the SysTimeCounter when the runtime environment
 	// does not provide a monotonic system clock, and the database is running in a single-node mode.
 	EpochCounter(EpochCounter),
+	// HybridLogicalClock versionstamp oracle is a HLC based on system time in milliseconds as the physical time,
+	// and a logical counter which counts on from the latest timestamp when the system time has not moved past it.
+	//
+	// Unlike the SysTimeCounter, this stays monotonic when the system clock goes backwards, and when it observes
+	// the timestamps issued by the other nodes of a multi-node installation, it stays causally ordered with them,
+	// even when the clocks of the nodes are skewed.
+	HybridLogicalClock(HybridLogicalClock),
 }
 
 impl Oracle {
@@ -71,11 +79,37 @@ impl Oracle {
 		})
 	}
 
+	#[allow(unused)]
+	pub fn hybrid_logical_clock() -> Self {
+		Oracle::HybridLogicalClock(HybridLogicalClock::default())
+	}
+
 	#[allow(unused)]
 	pub fn now(&mut self) -> Versionstamp {
 		match self {
 			Oracle::SysTimeCounter(sys) => sys.now(),
 			Oracle::EpochCounter(epoch) => epoch.now(),
+			Oracle::HybridLogicalClock(hlc) => {
+				let ts = hlc.now();
+				u64_u16_to_versionstamp(hlc::physical(ts), hlc::logical(ts))
+			}
+		}
+	}
+
+	// Returns a hybrid logical clock timestamp at the physical time, in milliseconds since the Unix epoch.
+	// Only the HybridLogicalClock oracle guarantees that the timestamp is greater than any timestamp
+	// it has issued or observed before.
+	pub fn timestamp_at(&mut self, physical: u64) -> u64 {
+		match self {
+			Oracle::HybridLogicalClock(hlc) => hlc.now_at(physical),
+			_ => hlc::pack(physical, 0),
+		}
+	}
+
+	// Observes a hybrid logical clock timestamp issued by another node.
+	pub fn observe(&mut self, ts: u64) {
+		if let Oracle::HybridLogicalClock(hlc) = self {
+			hlc.observe(ts);
 		}
 	}
 }
@@ -167,6 +201,16 @@ mod tests {
 		assert!(a < b, "a = {}, b = {}", a, b);
 	}
 
+	#[test]
+	fn hybrid_logical_clock() {
+		let mut o = Oracle::hybrid_logical_clock();
+		let a = to_u128_be(o.now());
+		let b = to_u128_be(o.now());
+		assert!(a < b, "a = {}, b = {}", a, b);
+		o.observe(hlc::pack(u32::MAX as u64, 0));
+		assert!(o.timestamp_at(0) > hlc::pack(u32::MAX as u64, 0));
+	}
+
 	#[test]
 	fn epoch_counter() {
 		let mut o1 = Oracle::epoch_counter();
@@ -0,0 +1,142 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn alter_statement_drop_field() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE FIELD age ON person TYPE number;
+		DEFINE INDEX age ON person FIELDS age;
+		DEFINE INDEX name_age ON person FIELDS name, age;
+		CREATE person:tobie SET name = 'Tobie', age = 33;
+		ALTER TABLE person DROP FIELD age;
+		INFO FOR TABLE person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { name_age: 'DEFINE INDEX name_age ON person FIELDS name, age' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	// The field data is removed in the background, before the index is rebuilt
+	assert_eq!(dbs.build_indexes().await?, 1);
+	let sql = "
+		SELECT * FROM person;
+		SELECT * FROM person WHERE name = 'Tobie' AND age = 33;
+		SELECT * FROM person WHERE name = 'Tobie' AND age = NONE;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn alter_statement_rename_field() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMAFULL;
+		DEFINE FIELD name ON person TYPE string;
+		DEFINE INDEX name ON person FIELDS name;
+		CREATE person:tobie SET name = 'Tobie';
+		ALTER TABLE person RENAME FIELD name TO fullname;
+		SELECT * FROM person WHERE fullname = 'Tobie';
+		INFO FOR TABLE person;
+		ALTER TABLE person RENAME FIELD fullname TO fullname;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, fullname: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: { fullname: 'DEFINE FIELD fullname ON person TYPE string' },
+			tables: {},
+			indexes: { name: 'DEFINE INDEX name ON person FIELDS fullname' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The field 'fullname' already exists"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn alter_statement_schema() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE FIELD name ON person TYPE string;
+		CREATE person:tobie SET name = 'Tobie', age = 33;
+		ALTER TABLE person SCHEMAFULL;
+		SELECT * FROM person;
+		ALTER TABLE unknown SCHEMALESS;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The table 'unknown' does not exist"
+	));
+	//
+	Ok(())
+}
@@ -76,6 +76,21 @@ mod api_integration {
 		let _ = subscriber.with(test_writer).try_init();
 	}
 
+	// Forwards the connections made to a Unix domain socket to the server
+	#[cfg(unix)]
+	fn unix_socket_proxy(path: &std::path::Path) {
+		let listener = tokio::net::UnixListener::bind(path).unwrap();
+		tokio::spawn(async move {
+			while let Ok((mut socket, _)) = listener.accept().await {
+				tokio::spawn(async move {
+					let mut server =
+						tokio::net::TcpStream::connect("127.0.0.1:8000").await.unwrap();
+					let _ = tokio::io::copy_bidirectional(&mut socket, &mut server).await;
+				});
+			}
+		});
+	}
+
 	#[cfg(feature = "protocol-ws")]
 	mod ws {
 		use super::*;
@@ -95,6 +110,49 @@ mod api_integration {
 			db
 		}
 
+		#[cfg(unix)]
+		#[tokio::test]
+		async fn unix_socket() {
+			let dir = temp_dir::TempDir::new().unwrap();
+			let path = dir.child("surreal.sock");
+			unix_socket_proxy(&path);
+			let db = Surreal::new::<Ws>(format!("unix://{}", path.display())).await.unwrap();
+			db.signin(Root {
+				username: ROOT_USER,
+				password: ROOT_PASS,
+			})
+			.await
+			.unwrap();
+			db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+			let mut response = db.query("CREATE user:john SET name = 'John'").await.unwrap();
+			let created: Option<RecordName> = response.take(0).unwrap();
+			assert_eq!(created.unwrap().name, "John");
+			let selected: Option<RecordName> = db.select(("user", "john")).await.unwrap();
+			assert_eq!(selected.unwrap().name, "John");
+		}
+
+		include!("api/mod.rs");
+	}
+
+	#[cfg(feature = "protocol-grpc")]
+	mod grpc {
+		use super::*;
+		use surrealdb::engine::remote::grpc::Client;
+		use surrealdb::engine::remote::grpc::Grpc;
+
+		async fn new_db() -> Surreal<Client> {
+			let _guard = SETUP_MUTEX.lock().unwrap();
+			init_logger();
+			let db = Surreal::new::<Grpc>("127.0.0.1:8000").await.unwrap();
+			db.signin(Root {
+				username: ROOT_USER,
+				password: ROOT_PASS,
+			})
+			.await
+			.unwrap();
+			db
+		}
+
 		include!("api/mod.rs");
 	}
 
@@ -117,6 +175,85 @@ mod api_integration {
 			db
 		}
 
+		#[cfg(unix)]
+		#[tokio::test]
+		async fn unix_socket() {
+			let dir = temp_dir::TempDir::new().unwrap();
+			let path = dir.child("surreal.sock");
+			unix_socket_proxy(&path);
+			let db = Surreal::new::<Http>(format!("unix://{}", path.display())).await.unwrap();
+			db.signin(Root {
+				username: ROOT_USER,
+				password: ROOT_PASS,
+			})
+			.await
+			.unwrap();
+			db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+			let mut response = db.query("CREATE user:john SET name = 'John'").await.unwrap();
+			let created: Option<RecordName> = response.take(0).unwrap();
+			assert_eq!(created.unwrap().name, "John");
+			let selected: Option<RecordName> = db.select(("user", "john")).await.unwrap();
+			assert_eq!(selected.unwrap().name, "John");
+		}
+
+		// Mocks a server which compresses its version with gzip, when the client accepts it
+		async fn compression_server(compressed: u64, plain: u64) -> wiremock::MockServer {
+			use wiremock::matchers::{header_regex, method, path};
+			use wiremock::{Mock, MockServer, ResponseTemplate};
+			// The `surrealdb-1.1.0` version compressed with gzip
+			const GZIPPED: &[u8] = &[
+				0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x2b, 0x2e, 0x2d, 0x2a,
+				0x4a, 0x4d, 0xcc, 0x49, 0x49, 0xd2, 0x35, 0xd4, 0x33, 0xd4, 0x33, 0x00, 0x00, 0x43,
+				0x85, 0x77, 0x3e, 0x0f, 0x00, 0x00, 0x00,
+			];
+			let server = MockServer::start().await;
+			Mock::given(method("GET"))
+				.and(path("/health"))
+				.respond_with(ResponseTemplate::new(200))
+				.mount(&server)
+				.await;
+			Mock::given(method("GET"))
+				.and(path("/version"))
+				.and(header_regex("accept-encoding", "gzip"))
+				.and(header_regex("accept-encoding", "zstd"))
+				.respond_with(
+					ResponseTemplate::new(200)
+						.insert_header("content-encoding", "gzip")
+						.set_body_bytes(GZIPPED),
+				)
+				.with_priority(1)
+				.expect(compressed)
+				.mount(&server)
+				.await;
+			Mock::given(method("GET"))
+				.and(path("/version"))
+				.respond_with(ResponseTemplate::new(200).set_body_string("surrealdb-1.1.0"))
+				.expect(plain)
+				.mount(&server)
+				.await;
+			server
+		}
+
+		#[tokio::test]
+		async fn compression_enabled() {
+			let server = compression_server(2, 0).await;
+			let config = Config::new().compression();
+			let db = Surreal::new::<Http>((*server.address(), config)).await.unwrap();
+			// The compressed response is decoded by the client
+			let version = db.version().await.unwrap();
+			assert_eq!(version, semver::Version::new(1, 1, 0));
+			server.verify().await;
+		}
+
+		#[tokio::test]
+		async fn compression_disabled() {
+			let server = compression_server(0, 2).await;
+			let db = Surreal::new::<Http>(*server.address()).await.unwrap();
+			let version = db.version().await.unwrap();
+			assert_eq!(version, semver::Version::new(1, 1, 0));
+			server.verify().await;
+		}
+
 		include!("api/mod.rs");
 		include!("api/backup.rs");
 	}
@@ -210,6 +347,103 @@ mod api_integration {
 			db.use_ns("test").use_db("test").await.unwrap();
 		}
 
+		#[tokio::test]
+		async fn config_capabilities() {
+			use surrealdb::dbs::capabilities::Targets;
+
+			init_logger();
+			let denied = Targets::Some(["rand".parse().unwrap()].into());
+			let config =
+				Config::new().capabilities(Capabilities::default().without_functions(denied));
+			let db = Surreal::new::<Mem>(config).await.unwrap();
+			db.use_ns("namespace").use_db("database").await.unwrap();
+			assert!(db.query("RETURN rand::bool()").await.unwrap().check().is_err());
+			assert!(db.query("RETURN string::len('foo')").await.unwrap().check().is_ok());
+		}
+
+		#[tokio::test]
+		async fn snapshot_persistence() {
+			init_logger();
+			let path = std::env::temp_dir().join(format!("{}.snapshot", Ulid::new()));
+			let db = Surreal::new::<Mem>(Config::new().snapshot(&path)).await.unwrap();
+			db.use_ns("namespace").use_db("database").await.unwrap();
+			let _: Option<RecordId> = db.create(("item", "foo")).await.unwrap();
+			// The snapshot is saved once the datastore shuts down
+			drop(db);
+			for _ in 0..50 {
+				if path.exists() {
+					break;
+				}
+				tokio::time::sleep(Duration::from_millis(100)).await;
+			}
+			let db = Surreal::new::<Mem>(Config::new().snapshot(&path)).await.unwrap();
+			db.use_ns("namespace").use_db("database").await.unwrap();
+			let item: Option<RecordId> = db.select(("item", "foo")).await.unwrap();
+			assert!(item.is_some());
+			std::fs::remove_file(&path).unwrap();
+		}
+
+		#[tokio::test]
+		async fn isolated_strict_mode() {
+			use surrealdb::opt::Isolation;
+
+			init_logger();
+			let db = Surreal::new::<Mem>(()).await.unwrap();
+			let strict = db.isolated(Isolation::new().strict(true)).unwrap();
+			db.use_ns("namespace").use_db("database").await.unwrap();
+			strict.use_ns("namespace").use_db("database").await.unwrap();
+			// The datastore is relaxed, so the table is created on the fly
+			let _: Vec<RecordId> = db.create("item").await.unwrap();
+			let Error::Db(DbError::TbNotFound {
+				..
+			}) = strict.create::<Vec<RecordId>>("other").await.unwrap_err()
+			else {
+				panic!("expected the table to be undefined");
+			};
+		}
+
+		#[tokio::test]
+		async fn isolated_handles() {
+			use surrealdb::dbs::capabilities::Targets;
+			use surrealdb::opt::Isolation;
+
+			init_logger();
+			let db = Surreal::new::<Mem>(()).await.unwrap();
+			let acme = db.isolated(Isolation::new().namespace("acme")).unwrap();
+			let globex = db.isolated(Isolation::new().namespace("globex")).unwrap();
+			// The parent client can be dropped while its handles are in use
+			drop(db);
+			acme.use_db("app").await.unwrap();
+			globex.use_db("app").await.unwrap();
+			let _: Option<RecordId> = acme.create(("item", "foo")).await.unwrap();
+			let items: Vec<RecordId> = acme.select("item").await.unwrap();
+			assert_eq!(items.len(), 1);
+			let items: Vec<RecordId> = globex.select("item").await.unwrap();
+			assert!(items.is_empty());
+			// Handles can not leave their namespace
+			let Error::Api(ApiError::NamespaceNotAllowed(ns)) =
+				globex.use_ns("acme").await.unwrap_err()
+			else {
+				panic!("expected the namespace to be rejected");
+			};
+			assert_eq!(ns, "globex");
+			let response = globex.query("USE NS acme DB app; SELECT * FROM item").await.unwrap();
+			assert!(response.check().is_err());
+			// Capabilities only apply to the handle they were set on
+			let function = "RETURN rand::bool()";
+			let denied = Targets::Some(["rand".parse().unwrap()].into());
+			let restricted = acme
+				.isolated(
+					Isolation::new()
+						.namespace("acme")
+						.capabilities(Capabilities::default().without_functions(denied)),
+				)
+				.unwrap();
+			restricted.use_db("app").await.unwrap();
+			assert!(restricted.query(function).await.unwrap().check().is_err());
+			assert!(acme.query(function).await.unwrap().check().is_ok());
+		}
+
 		include!("api/mod.rs");
 		include!("api/backup.rs");
 	}
@@ -295,6 +529,33 @@ mod api_integration {
 		include!("api/backup.rs");
 	}
 
+	#[cfg(feature = "kv-redb")]
+	mod redb {
+		use super::*;
+		use surrealdb::engine::local::Db;
+		use surrealdb::engine::local::ReDb;
+
+		async fn new_db() -> Surreal<Db> {
+			let _guard = SETUP_MUTEX.lock().unwrap();
+			init_logger();
+			let path = format!("/tmp/{}.db", Ulid::new());
+			let root = Root {
+				username: ROOT_USER,
+				password: ROOT_PASS,
+			};
+			let config = Config::new()
+				.user(root)
+				.tick_interval(TICK_INTERVAL)
+				.capabilities(Capabilities::all());
+			let db = Surreal::new::<ReDb>((path, config)).await.unwrap();
+			db.signin(root).await.unwrap();
+			db
+		}
+
+		include!("api/mod.rs");
+		include!("api/backup.rs");
+	}
+
 	#[cfg(feature = "kv-tikv")]
 	mod tikv {
 		use super::*;
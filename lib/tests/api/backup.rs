@@ -1,6 +1,8 @@
 // Tests for exporting and importing data
 // Supported by the storage engines and the HTTP protocol
 
+use surrealdb::opt::ExportConfig;
+use surrealdb::opt::ExportFormat;
 use tokio::fs::remove_file;
 
 #[tokio::test]
@@ -22,3 +24,42 @@ async fn export_import() {
 	db.import(&file).await.unwrap();
 	remove_file(file).await.unwrap();
 }
+
+#[tokio::test]
+async fn export_import_json_tables() {
+	let db = new_db().await;
+	let db_name = Ulid::new().to_string();
+	db.use_ns(NS).use_db(&db_name).await.unwrap();
+	for i in 0..10 {
+		let _: Vec<RecordId> = db
+			.create("user")
+			.content(Record {
+				name: &format!("User {i}"),
+			})
+			.await
+			.unwrap();
+		let _: Vec<RecordId> = db
+			.create("post")
+			.content(Record {
+				name: &format!("Post {i}"),
+			})
+			.await
+			.unwrap();
+	}
+	let file = format!("{db_name}.jsonl");
+	let config = ExportConfig {
+		tables: Some(vec!["user".to_owned()]),
+		format: ExportFormat::Json,
+		..Default::default()
+	};
+	db.export_to(&file, config).await.unwrap();
+	let exported = tokio::fs::read_to_string(&file).await.unwrap();
+	assert_eq!(exported.lines().count(), 10);
+	db.query("DELETE user; DELETE post").await.unwrap().check().unwrap();
+	db.import_from(&file, ExportFormat::Json).await.unwrap();
+	let users: Vec<RecordName> = db.select("user").await.unwrap();
+	assert_eq!(users.len(), 10);
+	let posts: Vec<RecordName> = db.select("post").await.unwrap();
+	assert!(posts.is_empty());
+	remove_file(file).await.unwrap();
+}
@@ -223,12 +223,30 @@ async fn query_binds() {
 	assert_eq!(record.name, "John Doe");
 }
 
+#[tokio::test]
+async fn query_prepared() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let query = db.prepare("CREATE type::thing('user', $id) SET name = $name").unwrap();
+	for (id, name) in [("john", "John Doe"), ("jane", "Jane Doe")] {
+		let mut response = db.query(&query).bind(("id", id)).bind(("name", name)).await.unwrap();
+		let Some(record): Option<RecordName> = response.take(0).unwrap() else {
+			panic!("query returned no record");
+		};
+		assert_eq!(record.name, name);
+	}
+	let mut response = db.query("SELECT * FROM user").await.unwrap();
+	let users: Vec<RecordName> = response.take(0).unwrap();
+	assert_eq!(users.len(), 2);
+	assert!(db.prepare("CREATE user SET").is_err());
+}
+
 #[tokio::test]
 async fn query_chaining() {
 	let db = new_db().await;
 	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
 	let response = db
-		.query(BeginStatement)
+		.query(BeginStatement::default())
 		.query("CREATE account:one SET balance = 135605.16")
 		.query("CREATE account:two SET balance = 91031.31")
 		.query("UPDATE account:one SET balance += 300.00")
@@ -239,6 +257,36 @@ async fn query_chaining() {
 	response.check().unwrap();
 }
 
+#[tokio::test]
+async fn query_atomic() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let mut response = db
+		.query("CREATE account:one SET balance = 135605.16")
+		.query("CREATE account:one SET balance = 91031.31")
+		.atomic()
+		.await
+		.unwrap();
+	response.take::<Value>(0).unwrap_err();
+	response.take::<Value>(1).unwrap_err();
+	let mut response = db.query("SELECT * FROM account").await.unwrap();
+	let accounts: Vec<RecordId> = response.take(0).unwrap();
+	assert!(accounts.is_empty());
+	db.query("CREATE account:one").query(CommitStatement).atomic().await.unwrap_err();
+}
+
+#[tokio::test]
+async fn query_timeout() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let timeout = Duration::from_millis(100);
+	match db.query("SLEEP 2s").timeout(timeout).await.unwrap_err() {
+		Error::Api(ApiError::RequestTimedOut(duration)) => assert_eq!(duration, timeout),
+		error => panic!("{:?}", error),
+	}
+	db.query("SLEEP 10ms").timeout(Duration::from_secs(5)).await.unwrap();
+}
+
 #[tokio::test]
 async fn mixed_results_query() {
 	let db = new_db().await;
@@ -0,0 +1,259 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use std::sync::{Arc, Mutex};
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::{BackupInfo, ExportProgress};
+use surrealdb::sql::Value;
+
+fn collect(rcv: channel::Receiver<Vec<u8>>) -> String {
+	let mut out = Vec::new();
+	while let Ok(v) = rcv.try_recv() {
+		out.extend(v);
+	}
+	String::from_utf8(out).unwrap()
+}
+
+#[tokio::test]
+async fn backup_excludes_later_writes() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Start the backup before writing any further records
+	let (snd, rcv) = channel::unbounded();
+	let backup = dbs.backup(&ses, "test".into(), Some("test".into()), snd).await?;
+	let sql = "
+		CREATE person:john SET name = 'John';
+		UPDATE person:tobie SET name = 'Tobias';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	backup.await?;
+	let out = collect(rcv);
+	// Restore the backup into a new datastore
+	let dbs = new_ds().await?;
+	dbs.import(&out, &ses).await?;
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:jaime, name: 'Jaime' },
+			{ id: person:tobie, name: 'Tobie' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn backup_namespace_includes_all_databases() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test");
+	let sql = "
+		USE DB one;
+		CREATE person:tobie SET name = 'Tobie';
+		USE DB two;
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	let (snd, rcv) = channel::unbounded();
+	dbs.backup(&ses, "test".into(), None, snd).await?.await?;
+	let out = collect(rcv);
+	// Restore the backup into a new datastore
+	let dbs = new_ds().await?;
+	dbs.import(&out, &ses).await?;
+	let sql = "
+		USE DB one;
+		SELECT * FROM person;
+		USE DB two;
+		SELECT * FROM person;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime, name: 'Jaime' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn backup_statement_returns_sql() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		BACKUP DATABASE;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let Value::Strand(out) = tmp else {
+		panic!("expected a string, found {tmp}");
+	};
+	assert!(out.contains("DEFINE TABLE person"));
+	assert!(out.contains("person:tobie"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn incremental_backups_restore_in_order() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// Take a full backup
+	let (snd, rcv) = channel::unbounded();
+	dbs.backup(&ses, "test".into(), Some("test".into()), snd).await?.await?;
+	let full = collect(rcv);
+	let info = BackupInfo::read(&full).unwrap();
+	assert_eq!(info.since, None);
+	// Take an incremental backup of the later changes
+	let sql = "
+		UPDATE person:tobie SET name = 'Tobias';
+		DELETE person:jaime;
+		CREATE person:john SET name = 'John';
+	";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	let (snd, rcv) = channel::unbounded();
+	dbs.backup_since(&ses, "test".into(), "test".into(), info.versionstamp, snd).await?.await?;
+	let incremental = collect(rcv);
+	let next = BackupInfo::read(&incremental).unwrap();
+	assert_eq!(next.since, Some(info.versionstamp));
+	assert!(next.versionstamp > info.versionstamp);
+	// The backups can only be restored in order
+	let dbs = new_ds().await?;
+	let res = dbs.restore(&[&incremental, &full], &ses).await;
+	assert!(matches!(res, Err(Error::InvalidBackupChain { .. })));
+	// Restore the full backup, followed by the incremental backup
+	dbs.restore(&[&full, &incremental], &ses).await?;
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:john, name: 'John' },
+			{ id: person:tobie, name: 'Tobias' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn incremental_backup_requires_changefeeds() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person;
+		CREATE person:tobie SET name = 'Tobie';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	let (snd, _rcv) = channel::unbounded();
+	let res = dbs.backup_since(&ses, "test".into(), "test".into(), 0, snd).await?.await;
+	assert!(matches!(res, Err(Error::BackupChangefeedRequired { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn export_reports_progress() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		CREATE company:surrealdb SET name = 'SurrealDB';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	let reports = Arc::new(Mutex::new(Vec::new()));
+	let (snd, rcv) = channel::unbounded();
+	let progress = {
+		let reports = reports.clone();
+		move |p: &ExportProgress| reports.lock().unwrap().push(p.clone())
+	};
+	dbs.export_with_progress(&ses, "test".into(), "test".into(), Default::default(), snd, progress)
+		.await?
+		.await?;
+	let out = collect(rcv);
+	// Every table was reported
+	let reports = reports.lock().unwrap();
+	assert_eq!(reports.len(), 2);
+	assert_eq!(reports[0].table, "company");
+	assert_eq!(reports[0].table_records, 1);
+	assert_eq!(reports[1].table, "person");
+	assert_eq!(reports[1].table_records, 2);
+	assert_eq!(reports[1].records, 3);
+	assert_eq!(reports[1].tables_done, 1);
+	assert_eq!(reports[1].tables_total, 2);
+	// The export can be imported
+	let dbs = new_ds().await?;
+	dbs.import(&out, &ses).await?;
+	let res = &mut dbs.execute("SELECT * FROM person, company", &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:jaime, name: 'Jaime' },
+			{ id: person:tobie, name: 'Tobie' },
+			{ id: company:surrealdb, name: 'SurrealDB' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -1,5 +1,5 @@
 mod parse;
-use chrono::DateTime;
+use chrono::{DateTime, SecondsFormat, Utc};
 use parse::Parse;
 mod helpers;
 use helpers::new_ds;
@@ -385,3 +385,158 @@ async fn changefeed_with_ts() -> Result<(), Error> {
 	assert_eq!(array.len(), 0);
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_table_at_earlier_version() -> Result<(), Error> {
+	let db = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let now = Utc::now();
+	let ts1 = now - chrono::Duration::minutes(20);
+	let ts2 = now - chrono::Duration::minutes(10);
+	// Create the records, and save timestamp 1
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1d INCLUDE ORIGINAL;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+	";
+	for res in db.execute(sql, &ses, None).await? {
+		res.result?;
+	}
+	db.tick_at(ts1.timestamp().try_into().unwrap()).await?;
+	// Change the records, and save timestamp 2
+	let sql = "
+		UPDATE person:tobie SET name = 'Tobias';
+		DELETE person:jaime;
+		CREATE person:yusuke SET name = 'Yusuke';
+	";
+	for res in db.execute(sql, &ses, None).await? {
+		res.result?;
+	}
+	db.tick_at(ts2.timestamp().try_into().unwrap()).await?;
+	// Select the records as they were between the timestamps
+	let version = (ts1 + chrono::Duration::minutes(5)).to_rfc3339_opts(SecondsFormat::Secs, true);
+	let sql = format!(
+		"
+		SELECT * FROM person VERSION '{version}';
+		SELECT * FROM person:jaime VERSION '{version}';
+		SELECT * FROM person:yusuke VERSION '{version}';
+		SELECT * FROM person;
+	"
+	);
+	let res = &mut db.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:jaime, name: 'Jaime' },
+			{ id: person:tobie, name: 'Tobie' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime, name: 'Jaime' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:tobie, name: 'Tobias' },
+			{ id: person:yusuke, name: 'Yusuke' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Versions older than the changefeed retention are not available
+	let version = (now - chrono::Duration::days(2)).to_rfc3339_opts(SecondsFormat::Secs, true);
+	let sql = format!("SELECT * FROM person VERSION '{version}'");
+	let tmp = db.execute(&sql, &ses, None).await?.remove(0).result;
+	assert!(matches!(tmp, Err(Error::VersionExpired { .. })));
+	// Tables which do not include the original values have no history
+	let sql = format!(
+		"
+		DEFINE TABLE user CHANGEFEED 1d;
+		SELECT * FROM user VERSION '{version}';
+	"
+	);
+	let tmp = db.execute(&sql, &ses, None).await?.remove(1).result;
+	assert!(matches!(tmp, Err(Error::TbHistoryNotEnabled { .. })));
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_change_feed_consumers() -> Result<(), Error> {
+	let db = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	// Reads the changes of each change set which the consumer has not read yet
+	let consume = |co: &'static str| {
+		let db = &db;
+		let ses = &ses;
+		async move {
+			let sql = format!("SHOW CHANGES FOR TABLE person SINCE CONSUMER {co} LIMIT 10");
+			let value = db.execute(&sql, ses, None).await?.remove(0).result?;
+			let Value::Array(array) = value else {
+				unreachable!()
+			};
+			let changes: Vec<Value> = array
+				.into_iter()
+				.map(|v| {
+					let Value::Object(v) = v else {
+						unreachable!()
+					};
+					v.get("changes").unwrap().to_owned()
+				})
+				.collect();
+			Ok::<_, Error>(Value::from(changes))
+		}
+	};
+	let sql = "
+		DEFINE TABLE person CHANGEFEED 1h INCLUDE ORIGINAL;
+		DEFINE TABLE other CHANGEFEED 1h;
+		CREATE person:tobie SET name = 'Tobie';
+		UPDATE person:tobie SET name = 'Tobias';
+	";
+	for res in db.execute(sql, &ses, None).await? {
+		res.result?;
+	}
+	// The values before and after each change are included
+	let val = Value::parse(
+		"[
+			[{ create: { id: person:tobie, name: 'Tobie' } }],
+			[{
+				update: { id: person:tobie, name: 'Tobias' },
+				original: { id: person:tobie, name: 'Tobie' },
+			}],
+		]",
+	);
+	assert_eq!(consume("sync").await?, val);
+	// The consumer continues after the changes which it has read
+	assert_eq!(consume("sync").await?, Value::parse("[]"));
+	let sql = "
+		CREATE other:one;
+		DELETE person:tobie;
+	";
+	for res in db.execute(sql, &ses, None).await? {
+		res.result?;
+	}
+	let val = Value::parse(
+		"[
+			[{
+				delete: { id: person:tobie },
+				original: { id: person:tobie, name: 'Tobias' },
+			}],
+		]",
+	);
+	assert_eq!(consume("sync").await?, val);
+	assert_eq!(consume("sync").await?, Value::parse("[]"));
+	// Each consumer keeps its own offset
+	let Value::Array(array) = consume("audit").await? else {
+		unreachable!()
+	};
+	assert_eq!(array.len(), 3);
+	Ok(())
+}
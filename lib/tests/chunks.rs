@@ -0,0 +1,88 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::{Datastore, Key};
+use surrealdb::sql::Value;
+
+async fn chunk_keys(dbs: &Datastore) -> Result<usize, Error> {
+	let mut beg: Key = surrealdb::key::table::all::new("test", "test", "file").into();
+	beg.push(b'&');
+	let end: Key = [beg.as_slice(), &[0xff]].concat();
+	let mut tx = dbs.transaction(false, false).await?;
+	let res = tx.scan(beg..end, 10_000).await?;
+	tx.cancel().await?;
+	Ok(res.len())
+}
+
+#[tokio::test]
+async fn large_values_are_stored_in_chunks() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		CREATE file:one SET data = string::repeat('a', 300000);
+		CREATE file:two SET data = string::repeat('b', 10);
+	";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// Only the large value is split into chunks
+	assert!(chunk_keys(&dbs).await? >= 5);
+	// The chunks are joined when the record is read
+	let sql = "SELECT VALUE string::len(data) FROM file";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[300000, 10]"));
+	// The chunks are removed when the value becomes small again
+	let sql = "UPDATE file:one SET data = 'small'";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	assert_eq!(chunk_keys(&dbs).await?, 0);
+	// The chunks are removed along with the record
+	let sql = "
+		UPDATE file:two SET data = string::repeat('c', 200000);
+		DELETE file:two;
+	";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	assert_eq!(chunk_keys(&dbs).await?, 0);
+	let res = &mut dbs.execute("SELECT * FROM file", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[{ id: file:one, data: 'small' }]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn large_values_are_read_in_parts() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "CREATE file:one SET data = string::repeat('a', 2000000)";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	let key = surrealdb::key::thing::new("test", "test", "file", &"one".into());
+	let mut tx = dbs.transaction(false, false).await?;
+	let val = tx.get(key.clone()).await?.unwrap();
+	// The reader returns the same value, in more than one part
+	let mut reader = tx.get_reader(key.clone()).await?.unwrap();
+	assert_eq!(reader.len(), val.len() as u64);
+	let mut parts = vec![];
+	while let Some(v) = reader.next(&mut tx).await? {
+		parts.push(v);
+	}
+	assert!(parts.len() > 1);
+	assert_eq!(parts.concat(), val);
+	tx.cancel().await?;
+	// The chunks are removed when the value is replaced without being read
+	let mut tx = dbs.transaction(true, false).await?;
+	tx.set(key, vec![0u8]).await?;
+	tx.commit().await?;
+	assert_eq!(chunk_keys(&dbs).await?, 0);
+	//
+	Ok(())
+}
@@ -169,6 +169,45 @@ async fn create_with_id() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn create_if_not_exists() -> Result<(), Error> {
+	let sql = "
+		CREATE person:test IF NOT EXISTS SET name = 'Tester';
+		CREATE person:test IF NOT EXISTS SET name = 'Other';
+		CREATE person:test IF NOT EXISTS SET name = 'Other' RETURN NONE;
+		CREATE person:test SET name = 'Other';
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:test, name: 'Tester' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:test, name: 'Tester' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Database record `person:test` already exists"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:test, name: 'Tester' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn create_with_custom_function() -> Result<(), Error> {
 	let sql = "
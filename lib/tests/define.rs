@@ -91,6 +91,7 @@ async fn define_statement_function() -> Result<(), Error> {
 			scopes: {},
 			params: {},
 			scopes: {},
+			sequences: {},
 			tables: {},
 			users: {},
 		}",
@@ -122,6 +123,7 @@ async fn define_statement_table_drop() -> Result<(), Error> {
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
 			tables: { test: 'DEFINE TABLE test DROP SCHEMALESS' },
 			users: {},
 		}",
@@ -153,6 +155,7 @@ async fn define_statement_table_schemaless() -> Result<(), Error> {
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
 			tables: { test: 'DEFINE TABLE test SCHEMALESS' },
 			users: {},
 		}",
@@ -188,6 +191,7 @@ async fn define_statement_table_schemafull() -> Result<(), Error> {
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
 			tables: { test: 'DEFINE TABLE test SCHEMAFULL' },
 			users: {},
 		}",
@@ -219,6 +223,7 @@ async fn define_statement_table_schemaful() -> Result<(), Error> {
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
 			tables: { test: 'DEFINE TABLE test SCHEMAFULL' },
 			users: {},
 		}",
@@ -258,6 +263,7 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
 			tables: {
 				test: 'DEFINE TABLE test SCHEMAFULL',
 				view: 'DEFINE TABLE view SCHEMALESS AS SELECT count() FROM test GROUP ALL',
@@ -289,6 +295,7 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
 			tables: {
 				test: 'DEFINE TABLE test SCHEMAFULL',
 			},
@@ -482,6 +489,122 @@ async fn define_statement_event_when_logic() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_event_before() -> Result<(), Error> {
+	let sql = "
+		DEFINE EVENT test ON user BEFORE WHEN $event = 'CREATE' THEN {
+			email: string::lowercase($after.email), created: true
+		};
+		DEFINE EVENT reject ON user BEFORE WHEN $after.email = NONE THEN {
+			THROW 'An email address is required'
+		};
+		CREATE user:test SET email = 'INFO@surrealdb.com';
+		CREATE user:other;
+		SELECT * FROM user;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[{
+			id: user:test,
+			email: 'info@surrealdb.com',
+			created: true,
+		}]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "An error occurred: An email address is required"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[{
+			id: user:test,
+			email: 'info@surrealdb.com',
+			created: true,
+		}]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_event_after() -> Result<(), Error> {
+	let sql = "
+		DEFINE EVENT test ON user AFTER WHEN true THEN (
+			CREATE activity SET user = $value.id, value = $after.email, action = $event
+		);
+		DEFINE EVENT fail ON user AFTER WHEN $event = 'UPDATE' THEN {
+			THROW 'This is an error'
+		};
+		BEGIN;
+		CREATE user:one SET email = 'one@surrealdb.com';
+		CANCEL;
+		CREATE user:two SET email = 'two@surrealdb.com';
+		BEGIN;
+		UPDATE user:two SET email = 'three@surrealdb.com';
+		SELECT count() FROM activity WHERE action = 'UPDATE' GROUP ALL;
+		COMMIT;
+		SELECT user, value, action FROM activity ORDER BY action;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::QueryCancelled)));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				user: user:two,
+				value: 'two@surrealdb.com',
+				action: 'CREATE',
+			},
+			{
+				user: user:two,
+				value: 'three@surrealdb.com',
+				action: 'UPDATE',
+			},
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_field() -> Result<(), Error> {
 	let sql = "
@@ -1125,17 +1248,69 @@ async fn define_statement_index_multiple_unique_embedded_multiple() -> Result<()
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_index_single_unique_collate() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX test ON user FIELDS email UNIQUE COLLATE;
+		INFO FOR TABLE user;
+		CREATE user:1 SET email = 'Foo@x.com';
+		CREATE user:2 SET email = 'foo@x.com';
+		CREATE user:3 SET email = 'föo@X.com';
+		CREATE user:4 SET email = 'bar@x.com';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE COLLATE NOCASE NOACCENT' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1, email: 'Foo@x.com' }]");
+	assert_eq!(tmp, val);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(matches!(
+			tmp.err(),
+			Some(e) if e.to_string() == r#"Database index `test` already contains 'foo@x.com', with record `user:1`"#
+		));
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:4, email: 'bar@x.com' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_analyzer() -> Result<(), Error> {
 	let sql = "
 		DEFINE ANALYZER english TOKENIZERS blank,class FILTERS lowercase,snowball(english);
 		DEFINE ANALYZER autocomplete FILTERS lowercase,edgengram(2,10);
+		DEFINE ANALYZER mixed TOKENIZERS blank,class FILTERS lowercase,stopwords('der','the'),snowball(german,english);
 		INFO FOR DB;
 	";
 	let dbs = new_ds().await?;
 	let ses = Session::owner().with_ns("test").with_db("test");
 	let res = &mut dbs.execute(sql, &ses, None).await?;
-	assert_eq!(res.len(), 3);
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
 	//
 	let tmp = res.remove(0).result;
 	assert!(tmp.is_ok());
@@ -1150,11 +1325,64 @@ async fn define_statement_analyzer() -> Result<(), Error> {
 			analyzers: {
 				autocomplete: 'DEFINE ANALYZER autocomplete FILTERS LOWERCASE,EDGENGRAM(2,10)',
 				english: 'DEFINE ANALYZER english TOKENIZERS BLANK,CLASS FILTERS LOWERCASE,SNOWBALL(ENGLISH)',
+				mixed: \"DEFINE ANALYZER mixed TOKENIZERS BLANK,CLASS FILTERS LOWERCASE,STOPWORDS('der','the'),SNOWBALL(GERMAN,ENGLISH)\",
 			},
 			tokens: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			tables: {},
+			users: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_sequence() -> Result<(), Error> {
+	let sql = "
+		DEFINE SEQUENCE invoice START 1000;
+		INFO FOR DB;
+		REMOVE SEQUENCE invoice;
+		INFO FOR DB;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyzers: {},
+			tokens: {},
+			functions: {},
+			params: {},
+			scopes: {},
+			sequences: { invoice: 'DEFINE SEQUENCE invoice START 1000' },
+			tables: {},
+			users: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyzers: {},
+			tokens: {},
+			functions: {},
+			params: {},
+			scopes: {},
+			sequences: {},
 			tables: {},
 			users: {},
 		}",
@@ -1469,8 +1697,8 @@ async fn permissions_checks_define_function() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1511,8 +1739,8 @@ async fn permissions_checks_define_analyzer() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1595,8 +1823,8 @@ async fn permissions_checks_define_token_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1721,8 +1949,8 @@ async fn permissions_checks_define_user_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1763,8 +1991,8 @@ async fn permissions_checks_define_scope() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1805,8 +2033,8 @@ async fn permissions_checks_define_param() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1844,8 +2072,8 @@ async fn permissions_checks_define_table() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -43,6 +43,73 @@ async fn delete() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn delete_with_return_diff() -> Result<(), Error> {
+	let sql = "
+		CREATE person:test SET name = 'Tester';
+		DELETE person:test RETURN DIFF;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			[
+				{ op: 'test', path: '/id', value: person:test },
+				{ op: 'remove', path: '/id' },
+				{ op: 'test', path: '/name', value: 'Tester' },
+				{ op: 'remove', path: '/name' },
+			]
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn delete_with_order_and_limit() -> Result<(), Error> {
+	let sql = "
+		CREATE log:1 SET time = 3, level = 'debug';
+		CREATE log:2 SET time = 5, level = 'debug';
+		CREATE log:3 SET time = 1, level = 'debug';
+		CREATE log:4 SET time = 4, level = 'debug';
+		CREATE log:5 SET time = 2, level = 'debug';
+		DELETE log WHERE level = 'debug' ORDER BY time DESC LIMIT 2 RETURN BEFORE;
+		SELECT VALUE id FROM log;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: log:2, level: 'debug', time: 5 },
+			{ id: log:4, level: 'debug', time: 4 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[log:1, log:3, log:5]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 //
 // Permissions
 //
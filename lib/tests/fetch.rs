@@ -279,3 +279,45 @@ async fn create_relate_select() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn fetch_array_of_record_links() -> Result<(), Error> {
+	let sql = "
+		CREATE tag:one SET name = 'One';
+		CREATE tag:two SET name = 'Two';
+		CREATE post:test SET tags = [tag:two, tag:missing, 'other', tag:one, tag:two];
+		SELECT * FROM post FETCH tags;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: post:test,
+				tags: [
+					{ id: tag:two, name: 'Two' },
+					NONE,
+					'other',
+					{ id: tag:one, name: 'One' },
+					{ id: tag:two, name: 'Two' }
+				]
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -642,11 +642,12 @@ async fn function_array_intersect() -> Result<(), Error> {
 		RETURN array::intersect([], []);
 		RETURN array::intersect(3, true);
 		RETURN array::intersect([1,2,3,4], [3,4,5,6]);
+		RETURN array::intersect([1,2,2,3,2], [2,2,1.0,6]);
 	"#;
 	let dbs = new_ds().await?;
 	let ses = Session::owner().with_ns("test").with_db("test");
 	let res = &mut dbs.execute(sql, &ses, None).await?;
-	assert_eq!(res.len(), 3);
+	assert_eq!(res.len(), 4);
 	//
 	let tmp = res.remove(0).result?;
 	let val = Value::parse("[]");
@@ -665,6 +666,10 @@ async fn function_array_intersect() -> Result<(), Error> {
 	let val = Value::parse("[3,4]");
 	assert_eq!(tmp, val);
 	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[1,2,2]");
+	assert_eq!(tmp, val);
+	//
 	Ok(())
 }
 
@@ -1333,6 +1338,33 @@ async fn function_count() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_count_distinct_approx() -> Result<(), Error> {
+	let sql = r#"
+		RETURN count::distinct_approx([]);
+		RETURN count::distinct_approx([1, 2, 2, 3, NONE, 'a', 'a']);
+		RETURN count::distinct_approx('a');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(0);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(4);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(1);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // crypto
 // --------------------------------------------------
@@ -2928,6 +2960,82 @@ async fn function_parse_url_scheme() -> Result<(), Error> {
 	Ok(())
 }
 
+// --------------------------------------------------
+// path
+// --------------------------------------------------
+
+#[tokio::test]
+async fn function_path_shortest() -> Result<(), Error> {
+	let sql = r#"
+		RELATE person:a->knows->person:b CONTENT { id: knows:1 };
+		RELATE person:b->knows->person:c CONTENT { id: knows:2 };
+		RELATE person:c->knows->person:d CONTENT { id: knows:3 };
+		RELATE person:a->knows->person:c CONTENT { id: knows:4 };
+		RETURN path::shortest(person:a, person:d, 'knows');
+		RETURN path::shortest(person:d, person:a, 'knows');
+		RETURN path::shortest(person:d, person:a, 'knows', { dir: 'in' });
+		RETURN path::shortest(person:d, person:b, 'knows', { dir: 'both' });
+		RETURN path::shortest(person:a, person:d, 'knows', { depth: 1 });
+		RETURN path::shortest(person:a, person:a, 'knows');
+		RETURN path::shortest(person:a, person:d, 'likes');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:a, knows:4, person:c, knows:3, person:d]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:d, knows:3, person:c, knows:4, person:a]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:d, knows:3, person:c, knows:2, person:b]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:a]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_path_shortest_invalid_options() -> Result<(), Error> {
+	let sql = r#"
+		RETURN path::shortest(person:a, person:d, 'knows', { dir: 'sideways' });
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Incorrect arguments for function path::shortest(). The 'dir' option must be 'out', 'in', or 'both'."
+	));
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // rand
 // --------------------------------------------------
@@ -3152,6 +3260,80 @@ async fn function_rand_uuid_v7() -> Result<(), Error> {
 	Ok(())
 }
 
+// --------------------------------------------------
+// sequence
+// --------------------------------------------------
+
+#[tokio::test]
+async fn function_sequence_next() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE SEQUENCE invoice START 1000;
+		RETURN sequence::next('invoice');
+		RETURN sequence::next('invoice');
+		CREATE invoice SET number = sequence::next('invoice') RETURN number;
+		BEGIN;
+		RETURN sequence::next('invoice');
+		CANCEL;
+		RETURN sequence::next('invoice');
+		REMOVE SEQUENCE invoice;
+		DEFINE SEQUENCE invoice;
+		RETURN sequence::next('invoice');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::from(1000));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::from(1001));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ number: 1002 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_err());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::from(1003));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::from(1));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_sequence_next_not_defined() -> Result<(), Error> {
+	let sql = r#"
+		RETURN sequence::next('invoice');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The sequence 'invoice' does not exist"
+	));
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // string
 // --------------------------------------------------
@@ -5338,6 +5520,60 @@ async fn function_type_thing() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_type_validate() -> Result<(), Error> {
+	let sql = r#"
+		RETURN type::validate([person:one, person:two], 'array<record<person>>');
+		RETURN type::validate([person:one, user:two, 'three'], 'array<record<person>>');
+		RETURN type::validate([[1, 2], [3, 'four']], 'array<array<int>, 2>');
+		RETURN type::validate(NONE, 'option<string>');
+		RETURN type::validate(123, 'string | bool');
+		RETURN type::validate(123, 'array<int');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ path: '/1', expected: 'record<person>', found: user:two },
+			{ path: '/2', expected: 'record<person>', found: 'three' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ path: '/1/1', expected: 'int', found: 'four' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ path: '/', expected: 'string | bool', found: 123 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_err());
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_vector_add() -> Result<(), Error> {
 	test_queries(
@@ -0,0 +1,127 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn graph_recursive_traversal() -> Result<(), Error> {
+	let sql = "
+		RELATE person:a->knows->person:b;
+		RELATE person:b->knows->person:c;
+		RELATE person:c->knows->person:d;
+		RELATE person:c->knows->person:a;
+		RELATE person:a->knows->person:e;
+		RETURN array::sort(person:a->knows{1..5}->person);
+		RETURN array::sort(person:a->knows{2..3}->person);
+		RETURN array::sort(person:a->knows{1}->person);
+		RETURN array::sort(person:a<-knows{..2}<-person);
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:b, person:c, person:d, person:e]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:c, person:d]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:b, person:e]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:b, person:c]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_recursive_shortest_path() -> Result<(), Error> {
+	let sql = "
+		RELATE person:a->knows->person:b;
+		RELATE person:b->knows->person:c;
+		RELATE person:c->knows->person:d;
+		RELATE person:a->knows->person:e;
+		RELATE person:e->knows->person:d;
+		RETURN person:a->knows{..5+shortest=person:d}->person;
+		RETURN person:a->knows{..1+shortest=person:d}->person;
+		RETURN person:a->knows{..5+shortest=person:z}->person;
+		LET $target = 'test';
+		RETURN person:a->knows{..5+shortest=$target}->person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:e, person:d]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found 'test' but the shortest path target must evaluate to a record id"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn graph_parameterized_edge_table() -> Result<(), Error> {
+	let sql = "
+		RELATE person:a->knows->person:b;
+		RELATE person:a->likes->person:c;
+		LET $edge = 'likes';
+		RETURN person:a->type::table($edge)->person;
+		RETURN person:c<-(type::table($edge) WHERE in = person:a)<-person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:c]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:a]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
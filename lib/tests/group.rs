@@ -460,3 +460,116 @@ async fn select_multi_aggregate_composed() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_group_rollup_and_cube() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET country = 'GB', city = 'London', age = 30;
+		CREATE person:2 SET country = 'GB', city = 'Leeds', age = 40;
+		CREATE person:3 SET country = 'FR', city = 'Paris', age = 50;
+		SELECT country, city, count() AS total FROM person GROUP BY ROLLUP(country, city);
+		SELECT country, math::sum(age) AS age FROM person GROUP BY CUBE(country);
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ country: 'FR', city: 'Paris', total: 1 },
+			{ country: 'GB', city: 'Leeds', total: 1 },
+			{ country: 'GB', city: 'London', total: 1 },
+			{ country: 'FR', city: NULL, total: 1 },
+			{ country: 'GB', city: NULL, total: 2 },
+			{ country: NULL, city: NULL, total: 3 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ country: 'FR', age: 50 },
+			{ country: 'GB', age: 70 },
+			{ country: NULL, age: 120 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_group_having() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET country = 'GB', city = 'London', age = 30;
+		CREATE person:2 SET country = 'GB', city = 'Leeds', age = 40;
+		CREATE person:3 SET country = 'FR', city = 'Paris', age = 50;
+		SELECT country, count() AS total FROM person GROUP BY country HAVING total > 1;
+		SELECT country, math::sum(age) AS age FROM person GROUP BY country HAVING country != 'GB' AND age >= 50;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ country: 'GB', total: 2 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ country: 'FR', age: 50 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_group_rollup_grouping() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET country = 'GB', age = 30;
+		CREATE person:2 SET country = NULL, age = 40;
+		SELECT country, grouping(country) AS rolled, math::sum(age) AS age FROM person GROUP BY ROLLUP(country);
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The grand total can be told apart from the group of records without a country
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ country: NULL, rolled: 0, age: 40 },
+			{ country: 'GB', rolled: 0, age: 30 },
+			{ country: NULL, rolled: 1, age: 70 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
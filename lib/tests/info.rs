@@ -85,7 +85,7 @@ async fn info_for_db() {
 	let out = res.pop().unwrap().output();
 	assert!(out.is_ok(), "Unexpected error: {:?}", out);
 
-	let output_regex = Regex::new(r"\{ analyzers: \{ analyzer: .* \}, functions: \{ greet: .* \}, params: \{ param: .* \}, scopes: \{ account: .* \}, tables: \{ TB: .* \}, tokens: \{ token: .* \}, users: \{ user: .* \} \}").unwrap();
+	let output_regex = Regex::new(r"\{ analyzers: \{ analyzer: .* \}, functions: \{ greet: .* \}, params: \{ param: .* \}, scopes: \{ account: .* \}, sequences: \{  \}, tables: \{ TB: .* \}, tokens: \{ token: .* \}, users: \{ user: .* \} \}").unwrap();
 	let out_str = out.unwrap().to_string();
 	assert!(
 		output_regex.is_match(&out_str),
@@ -225,6 +225,123 @@ async fn info_for_user() {
 	);
 }
 
+#[tokio::test]
+async fn info_for_sessions() {
+	let dbs = new_ds().await.unwrap();
+	let ses = Session::owner().with_ns("ns").with_db("db");
+	// Register a connected session
+	let mut one = Session::owner().with_ns("ns").with_db("db").with_rt(true);
+	one.id = Some("one".to_string());
+	let res = dbs.execute("RETURN true", &one, None).await;
+	assert!(res.is_ok(), "Unexpected error: {:?}", res);
+	// A session without a connection is not registered
+	let mut two = Session::owner().with_ns("ns").with_db("db");
+	two.id = Some("two".to_string());
+	let res = dbs.execute("RETURN true", &two, None).await;
+	assert!(res.is_ok(), "Unexpected error: {:?}", res);
+	// The session is listed
+	let mut res = dbs.execute("INFO FOR SESSIONS", &ses, None).await.unwrap();
+	assert_eq!(res.len(), 1);
+	let out = res.remove(0).output();
+	assert!(out.is_ok(), "Unexpected error: {:?}", out);
+	let output_regex = Regex::new(
+		r"^\[\{ auth: \{ id: '.*', level: '/' \}, connected: '.*', db: 'db', id: 'one', idle: .*, ip: NONE, live: \[\], ns: 'ns', or: NONE \}\]$",
+	)
+	.unwrap();
+	let out_str = out.unwrap().to_string();
+	assert!(
+		output_regex.is_match(&out_str),
+		"Output '{}' doesn't match regex '{}'",
+		out_str,
+		output_regex
+	);
+	// Terminate the session
+	let mut res = dbs.execute("KILL SESSION 'one'", &ses, None).await.unwrap();
+	assert_eq!(res.len(), 1);
+	let out = res.remove(0).output();
+	assert!(out.is_ok(), "Unexpected error: {:?}", out);
+	// The session can no longer run queries
+	let res = dbs.execute("RETURN true", &one, None).await;
+	assert!(res.is_err());
+	// The session is no longer listed
+	let mut res = dbs.execute("INFO FOR SESSIONS", &ses, None).await.unwrap();
+	assert_eq!(res.remove(0).output().unwrap().to_string(), "[]");
+	// An unknown session can not be terminated
+	let mut res = dbs.execute("KILL SESSION 'one'", &ses, None).await.unwrap();
+	assert!(res.remove(0).output().is_err());
+}
+
+#[tokio::test]
+async fn info_for_stats() {
+	let sql = r#"
+		CREATE person:tobie;
+		CREATE person:jaime;
+		SELECT * FROM person:tobie;
+		SELECT * FROM person:tobie;
+		UPDATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime;
+		INFO FOR STATS;
+	"#;
+	let dbs = new_ds().await.unwrap();
+	let ses = Session::owner().with_ns("ns").with_db("db");
+
+	let mut res = dbs.execute(sql, &ses, None).await.unwrap();
+	assert_eq!(res.len(), 7);
+	// The record which failed to be written is not counted
+	let out = res.remove(5).output();
+	assert!(out.is_err());
+
+	let out = res.pop().unwrap().output();
+	assert!(out.is_ok(), "Unexpected error: {:?}", out);
+	let val = surrealdb::sql::value(
+		"{
+			person: {
+				reads: 2,
+				writes: 3,
+				hot: [
+					{ id: person:tobie, count: 4 },
+					{ id: person:jaime, count: 1 },
+				],
+			},
+		}",
+	)
+	.unwrap();
+	assert_eq!(out.unwrap(), val);
+}
+
+#[tokio::test]
+async fn info_for_table_stats() {
+	let sql = r#"
+		DEFINE INDEX name ON person FIELDS name;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		INFO FOR TABLE person STATS;
+	"#;
+	let dbs = new_ds().await.unwrap();
+	let ses = Session::owner().with_ns("ns").with_db("db");
+
+	let mut res = dbs.execute(sql, &ses, None).await.unwrap();
+	assert_eq!(res.len(), 4);
+	// The statistics have not been collected yet
+	let out = res.pop().unwrap().output();
+	assert_eq!(out.unwrap(), surrealdb::sql::Value::None);
+	// Collect the statistics
+	dbs.collect_usage().await.unwrap();
+	let usage = dbs.usage().table("ns", "db", "person").unwrap();
+	assert_eq!(usage.records, 2);
+	assert!(usage.size > 0);
+	assert!(usage.indexes["name"] > 0);
+	let mut res = dbs.execute("INFO FOR TABLE person STATS", &ses, None).await.unwrap();
+	let out = res.remove(0).output().unwrap().to_string();
+	let output_regex =
+		Regex::new(r"\{ collected: .*, indexes: \{ name: \d+ \}, records: 2, size: \d+ \}")
+			.unwrap();
+	assert!(output_regex.is_match(&out), "Output '{}' doesn't match regex '{}'", out, output_regex);
+	// The table must exist
+	let mut res = dbs.execute("INFO FOR TABLE missing STATS", &ses, None).await.unwrap();
+	assert!(res.remove(0).output().is_err());
+}
+
 //
 // Permissions
 //
@@ -312,8 +429,8 @@ async fn permissions_checks_info_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
     ];
 
 	let test_cases = [
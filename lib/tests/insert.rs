@@ -193,6 +193,191 @@ async fn insert_statement_duplicate_key_update() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn insert_statement_partial() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD age ON person TYPE number;
+		INSERT INTO person [
+			{ id: 1, age: 24 },
+			{ id: 2, age: 'unknown' },
+			{ id: 3, age: 36 },
+		] PARTIAL;
+		SELECT * FROM person;
+		INSERT INTO person [
+			{ id: 4, age: 48 },
+			{ id: 5, age: NULL },
+		] RETURN NONE PARTIAL;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:1, status: 'OK', result: { id: person:1, age: 24 } },
+			{ id: person:2, status: 'ERR', result: 'Found \\'unknown\\' for field `age`, with record `person:2`, but expected a number' },
+			{ id: person:3, status: 'OK', result: { id: person:3, age: 36 } },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:1, age: 24 },
+			{ id: person:3, age: 36 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:5, status: 'ERR', result: 'Found NULL for field `age`, with record `person:5`, but expected a number' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn insert_statement_partial_undoes_failed_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+		DEFINE INDEX name ON user FIELDS name UNIQUE;
+		INSERT INTO user [
+			{ id: 1, email: 'a@example.com', name: 'a' },
+			{ id: 2, email: 'b@example.com', name: 'a' },
+		] PARTIAL;
+		CREATE user:3 SET email = 'b@example.com', name = 'b';
+		SELECT * FROM user;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: user:1, status: 'OK', result: { id: user:1, email: 'a@example.com', name: 'a' } },
+			{ id: user:2, status: 'ERR', result: 'Database index `name` already contains \\'a\\', with record `user:1`' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The index entry written by the failed record was undone
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: user:1, email: 'a@example.com', name: 'a' },
+			{ id: user:3, email: 'b@example.com', name: 'b' },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn insert_statement_partial_batches() -> Result<(), Error> {
+	// Every hundredth record reuses the name of the record before it
+	let rows = (1..=2500)
+		.map(|v| {
+			let name = if v % 100 == 0 {
+				v - 1
+			} else {
+				v
+			};
+			format!("{{ id: {v}, email: 'user{v}@example.com', name: 'user{name}' }}")
+		})
+		.collect::<Vec<_>>();
+	let retry = (100..=2500)
+		.step_by(100)
+		.map(|v| format!("{{ id: {v}, email: 'user{v}@example.com', name: 'retry{v}' }}"))
+		.collect::<Vec<_>>();
+	let sql = format!(
+		"
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+		DEFINE INDEX name ON user FIELDS name UNIQUE;
+		INSERT INTO user [{}] PARTIAL;
+		SELECT count() FROM user GROUP ALL;
+		INSERT INTO user [{}];
+		SELECT count() FROM user GROUP ALL;
+	",
+		rows.join(", "),
+		retry.join(", ")
+	);
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let Value::Array(v) = tmp else {
+		panic!("expected an array of record outcomes");
+	};
+	assert_eq!(v.len(), 2500);
+	let failed = v.iter().filter(|v| v.pick(&[Part::from("status")]) == Value::from("ERR")).count();
+	assert_eq!(failed, 25);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 2475 }]");
+	assert_eq!(tmp, val);
+	// The email index entries of the failed records were undone
+	let tmp = res.remove(0).result?;
+	assert!(matches!(tmp, Value::Array(v) if v.len() == 25));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 2500 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn insert_statement_batches() -> Result<(), Error> {
+	let rows = (1..=2500).map(|v| format!("{{ id: {v}, age: {v} }}")).collect::<Vec<_>>();
+	let sql = format!(
+		"
+		INSERT INTO person [{}];
+		SELECT count() FROM person GROUP ALL;
+	",
+		rows.join(", ")
+	);
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	assert!(matches!(tmp, Value::Array(v) if v.len() == 2500));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ count: 2500 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 //
 // Permissions
 //
@@ -0,0 +1,172 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::{Datastore, Key};
+use surrealdb::sql::{Id, Value};
+
+async fn interned_keys(dbs: &Datastore, tb: &str, marker: &[u8]) -> Result<Vec<Key>, Error> {
+	let mut tx = dbs.transaction(false, false).await?;
+	let ns = tx.get_ns("test").await?.id.unwrap();
+	let db = tx.get_db("test", "test").await?.id.unwrap();
+	let tb = tx.get_tb("test", "test", tb).await?.id.unwrap();
+	let beg: Key = surrealdb::key::interned::new(ns, db, tb).into();
+	let beg: Key = [beg.as_slice(), marker].concat();
+	let end: Key = [beg.as_slice(), &[0xff]].concat();
+	let res = tx.scan(beg..end, 1000).await?;
+	tx.cancel().await?;
+	Ok(res.into_iter().map(|(k, _)| k).collect())
+}
+
+#[tokio::test]
+async fn interned_table_keys() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX name ON person FIELDS name;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		RELATE person:tobie->knows->person:jaime;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// The records, index entries, and graph edges are stored under the table ids
+	assert_eq!(interned_keys(&dbs, "person", b"*").await?.len(), 2);
+	assert_eq!(interned_keys(&dbs, "person", b"+").await?.len(), 2);
+	assert_eq!(interned_keys(&dbs, "person", b"~").await?.len(), 2);
+	// The keys are read using the names of the table
+	let mut tx = dbs.transaction(false, false).await?;
+	let key = surrealdb::key::thing::new("test", "test", "person", &Id::from("tobie"));
+	assert!(tx.exi(key).await?);
+	tx.cancel().await?;
+	let sql = "SELECT * FROM person WITH INDEX name WHERE name = 'Jaime'";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime, name: 'Jaime' }]");
+	assert_eq!(tmp, val);
+	let sql = "SELECT VALUE ->knows->person FROM person:tobie";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[person:jaime]]");
+	assert_eq!(tmp, val);
+	// Removing the table removes its interned keys
+	for res in dbs.execute("REMOVE TABLE person; DEFINE TABLE person", &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	assert!(interned_keys(&dbs, "person", b"").await?.is_empty());
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn interned_table_keys_removed_with_database() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute("CREATE person:tobie", &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	assert_eq!(interned_keys(&dbs, "person", b"*").await?.len(), 1);
+	let mut tx = dbs.transaction(false, false).await?;
+	let ns = tx.get_ns("test").await?.id.unwrap();
+	tx.cancel().await?;
+	// The database and table ids are not reused once the database is removed
+	let sql = "REMOVE DATABASE test; CREATE person:jaime";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	let beg = surrealdb::key::interned::namespace(ns);
+	let end: Key = [beg.as_slice(), &[0xff]].concat();
+	let mut tx = dbs.transaction(false, false).await?;
+	let keys = tx.scan(beg..end, 1000).await?;
+	tx.cancel().await?;
+	let keys = keys.iter().filter(|(k, _)| surrealdb::key::interned::interned(k).is_some());
+	assert_eq!(keys.count(), 1);
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[{ id: person:jaime }]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn tables_with_existing_keys_are_not_interned() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute("DEFINE DATABASE test", &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// Store a record before the table is defined
+	let mut tx = dbs.transaction(true, false).await?;
+	let key = surrealdb::key::thing::new("test", "test", "person", &Id::from("tobie"));
+	tx.set(key, Value::parse("{ id: person:tobie }")).await?;
+	tx.commit().await?;
+	for res in dbs.execute("DEFINE TABLE person", &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	let mut tx = dbs.transaction(false, false).await?;
+	assert!(!tx.get_tb("test", "test", "person").await?.interned);
+	tx.cancel().await?;
+	// The existing record can still be read
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[{ id: person:tobie }]"));
+	// The keys of the table are moved to the interned prefix when bootstrapping
+	dbs.bootstrap().await?;
+	let mut tx = dbs.transaction(false, false).await?;
+	assert!(tx.get_tb("test", "test", "person").await?.interned);
+	tx.cancel().await?;
+	assert_eq!(interned_keys(&dbs, "person", b"*").await?.len(), 1);
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[{ id: person:tobie }]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn interned_table_keys_in_database_ranges() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "CREATE person:tobie; CREATE person:jaime; CREATE animal:cat";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// A range over the whole database holds the keys of every table, under their names
+	let beg: Key = surrealdb::key::database::all::new("test", "test").into();
+	let end: Key = [beg.as_slice(), &[0xff]].concat();
+	let mut tx = dbs.transaction(false, false).await?;
+	let keys: Vec<Key> =
+		tx.getr(beg.clone()..end.clone(), u32::MAX).await?.into_iter().map(|(k, _)| k).collect();
+	tx.cancel().await?;
+	let things = [
+		surrealdb::key::thing::new("test", "test", "animal", &Id::from("cat")),
+		surrealdb::key::thing::new("test", "test", "person", &Id::from("jaime")),
+		surrealdb::key::thing::new("test", "test", "person", &Id::from("tobie")),
+	];
+	let things: Vec<Key> = things.into_iter().map(Into::into).collect();
+	assert_eq!(keys.iter().filter(|k| things.contains(k)).count(), 3);
+	assert!(keys.windows(2).all(|w| w[0] < w[1]));
+	assert!(keys.iter().all(|k| surrealdb::key::interned::interned(k).is_none()));
+	// The keys are read in batches, which continue from the last key of each batch
+	let mut tx = dbs.transaction(false, false).await?;
+	let first = tx.scan(beg.clone()..end.clone(), 1).await?;
+	tx.cancel().await?;
+	assert_eq!(first.len(), 1);
+	assert_eq!(first[0].0, keys[0]);
+	// Deleting the range deletes the interned keys
+	let mut tx = dbs.transaction(true, false).await?;
+	tx.delr(beg..end, u32::MAX).await?;
+	tx.commit().await?;
+	let (beg, end) = surrealdb::key::interned::range();
+	let mut tx = dbs.transaction(false, false).await?;
+	assert!(tx.scan(beg..end, 1000).await?.is_empty());
+	tx.cancel().await?;
+	//
+	Ok(())
+}
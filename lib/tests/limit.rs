@@ -94,3 +94,25 @@ async fn select_limit_fetch() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_limit_parallel() -> Result<(), Error> {
+	let sql = "
+		FOR $i IN [1, 2, 3, 4, 5, 6, 7, 8, 9, 10] {
+			CREATE type::thing('person', $i) SET age = $i;
+		};
+		SELECT * FROM person WHERE age > 2 LIMIT 3 PARALLEL;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert!(matches!(tmp, Value::Array(v) if v.len() == 3));
+	//
+	Ok(())
+}
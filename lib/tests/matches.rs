@@ -310,6 +310,283 @@ async fn select_where_matches_using_index_and_score() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn select_where_matches_using_index_with_boosted_fields() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'fox news', content = 'the weather is sunny';
+		CREATE blog:2 SET title = 'weather news', content = 'a fox crossed the road';
+		DEFINE ANALYZER simple TOKENIZERS blank,class;
+		DEFINE INDEX blog_search ON blog FIELDS title, content SEARCH ANALYZER simple BM25(1.2,0.75) BOOSTS 3, 1;
+		SELECT id, search::score(1) AS score FROM blog WHERE content @1@ 'fox' ORDER BY score DESC;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	// The index covers both fields, and a term in the title weighs more
+	let tmp = res.remove(0).result?.to_string();
+	let (Some(first), Some(second)) = (tmp.find("blog:1"), tmp.find("blog:2")) else {
+		panic!("{tmp}");
+	};
+	assert!(first < second, "{tmp}");
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_highlighting_fragments() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'the quick brown fox jumped over the lazy dog and the fox slept';
+		DEFINE ANALYZER simple TOKENIZERS blank,class;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id, search::highlight('<b>', '</b>', 1, 11) AS title FROM blog WHERE title @1@ 'fox';
+		SELECT id, search::highlight('<b>', '</b>', 1, 11, 1) AS title FROM blog WHERE title @1@ 'fox';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: blog:1,
+				title: ['own <b>fox</b> jum', 'the <b>fox</b> sle']
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: blog:1,
+				title: ['own <b>fox</b> jum']
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_highlighting_multiple_fields() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'fox news', content = 'a fox crossed the road';
+		DEFINE ANALYZER simple TOKENIZERS blank,class;
+		DEFINE INDEX blog_search ON blog FIELDS title, content SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id, search::highlight('<em>', '</em>', 1) AS hl FROM blog WHERE content @1@ 'fox';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: blog:1,
+				hl: {
+					content: 'a <em>fox</em> crossed the road',
+					title: '<em>fox</em> news'
+				}
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_fuzzy_matches_using_index() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'Hello SurrealDB!';
+		CREATE blog:2 SET title = 'Hello World!';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id, search::highlight('<em>', '</em>', 1) AS title FROM blog WHERE title @1@ 'surealdb'~2;
+		SELECT id FROM blog WHERE title @1@ 'surealdb';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: blog:1,
+				title: 'Hello <em>SurrealDB</em>!'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Without an edit distance, the misspelt term does not match
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_with_synonyms() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'Travelling across the USA by automobile';
+		CREATE blog:2 SET title = 'Travelling across Europe by train';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase,synonyms('us=>usa, car=>automobile');
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25;
+		SELECT id FROM blog WHERE title @@ 'US car';
+		SELECT id FROM blog WHERE title @@ 'travelling';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	// The terms of the query are expanded with their synonyms
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }, { id: blog:2 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_with_edgengram_tokenizer() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'SurrealDB is a database';
+		CREATE blog:2 SET title = 'Surfing the web';
+		CREATE blog:3 SET title = 'Another database';
+		DEFINE ANALYZER autocomplete TOKENIZERS blank,edgengram(1,10) FILTERS lowercase;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER autocomplete BM25;
+		SELECT id FROM blog WHERE title @@ 'sur';
+		SELECT id FROM blog WHERE title @@ 'surr';
+		SELECT id FROM blog WHERE title @@ 'data';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }, { id: blog:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }, { id: blog:3 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_with_phrases_and_proximity() -> Result<(), Error> {
+	let sql = r#"
+		CREATE blog:1 SET title = 'The quick brown fox jumps';
+		CREATE blog:2 SET title = 'The brown quick fox';
+		CREATE blog:3 SET title = 'Quick and lazy brown fox';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id FROM blog WHERE title @@ '"quick brown"';
+		SELECT id FROM blog WHERE title @@ 'quick NEAR/2 fox';
+		SELECT id FROM blog WHERE title @@ '"brown fox" jumps';
+		CREATE post:1 SET content = 'The quick brown fox';
+		DEFINE INDEX post_content ON post FIELDS content SEARCH ANALYZER simple BM25;
+		SELECT id FROM post WHERE content @@ '"quick brown"';
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	// The terms of a phrase follow each other
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }]");
+	assert_eq!(tmp, val);
+	// The operands of NEAR are at most 2 words apart, in any order
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }, { id: blog:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }]");
+	assert_eq!(tmp, val);
+	//
+	for _ in 0..2 {
+		let _ = res.remove(0).result?;
+	}
+	// The positions of the terms are only stored with HIGHLIGHTS
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::FtPositionsNotIndexed)));
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_and_facets() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'Rust database', category = 'db';
+		CREATE blog:2 SET title = 'Rust web framework', category = 'web';
+		CREATE blog:3 SET title = 'Rust embedded database', category = 'db';
+		CREATE blog:4 SET title = 'Python database', category = 'db';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25;
+		DEFINE INDEX blog_category ON blog FIELDS category;
+		SELECT search::facets(1, 'category') AS facets FROM blog WHERE title @1@ 'rust' LIMIT 1;
+		SELECT search::facets(1, 'category', 1) AS facets FROM blog WHERE title @1@ 'rust' LIMIT 1;
+		SELECT search::facets(1, 'title') AS facets FROM blog WHERE title @1@ 'rust' LIMIT 1;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..7 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ facets: [{ count: 2, value: 'db' }, { count: 1, value: 'web' }] }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ facets: [{ count: 2, value: 'db' }] }]");
+	assert_eq!(tmp, val);
+	// The facets are computed from an index on the field
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::NoIndexFoundForFacets { .. })));
+	Ok(())
+}
+
 #[tokio::test]
 async fn select_where_matches_without_using_index_and_score() -> Result<(), Error> {
 	let sql = r"
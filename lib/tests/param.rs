@@ -31,6 +31,7 @@ async fn define_global_param() -> Result<(), Error> {
 			functions: {},
 			params: { test: 'DEFINE PARAM $test VALUE 12345' },
 			scopes: {},
+			sequences: {},
 			tables: {},
 			users: {},
 		}",
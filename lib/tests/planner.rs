@@ -92,6 +92,20 @@ async fn select_where_iterate_two_multi_index_with_one_index() -> Result<(), Err
 	Ok(())
 }
 
+#[tokio::test]
+async fn select_where_iterate_two_multi_index_with_param_index() -> Result<(), Error> {
+	let sql =
+		format!("LET $index = 'idx_genre'; {}", two_multi_index_query("WITH INDEX $index", ""));
+	let mut res = execute_test(&sql, 10).await?;
+	// OR results
+	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Tobie' }]")?;
+	check_result(&mut res, &table_explain(2))?;
+	// AND results
+	check_result(&mut res, "[{name: 'Jaime'}]")?;
+	check_result(&mut res, SINGLE_INDEX_IDX_EXPLAIN)?;
+	Ok(())
+}
+
 #[tokio::test]
 async fn select_where_iterate_two_multi_index_with_two_index() -> Result<(), Error> {
 	let mut res =
@@ -117,6 +131,339 @@ async fn select_where_iterate_two_no_index() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn select_where_explain_plan() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', genre='m', company='SurrealDB';
+		CREATE person:jaime SET name = 'Jaime', genre='m', company='SurrealDB';
+		CREATE person:lizzie SET name = 'Lizzie', genre='f', company='SurrealDB';
+		DEFINE INDEX uniq_name ON TABLE person COLUMNS name UNIQUE;
+		SELECT name FROM person WHERE name = 'Jaime' ORDER BY name LIMIT 5 EXPLAIN PLAN;
+		SELECT name FROM person WHERE genre = 'm' EXPLAIN PLAN FULL;
+	";
+	let mut res = execute_test(sql, 6).await?;
+	res.remove(0);
+	res.remove(0);
+	check_result(
+		&mut res,
+		"[
+			{
+				access: [
+					{
+						detail: {
+							estimate: 1,
+							plan: {
+								index: 'uniq_name',
+								operator: '=',
+								value: 'Jaime'
+							},
+							table: 'person'
+						},
+						operation: 'Iterate Index'
+					}
+				],
+				pushdown: [
+					{
+						index: 'uniq_name',
+						operator: '=',
+						value: 'Jaime'
+					}
+				],
+				stages: [
+					{
+						detail: {
+							cond: name = 'Jaime'
+						},
+						operation: 'Filter'
+					},
+					{
+						detail: {
+							fields: ['name']
+						},
+						operation: 'Order'
+					},
+					{
+						detail: {
+							value: 5
+						},
+						operation: 'Limit'
+					}
+				]
+			}
+		]",
+	)?;
+	check_result(
+		&mut res,
+		"[
+			{
+				access: [
+					{
+						detail: {
+							estimate: 3,
+							reason: 'no index matches the condition',
+							table: 'person'
+						},
+						operation: 'Iterate Table'
+					}
+				],
+				pushdown: [],
+				rows: 2,
+				stages: [
+					{
+						detail: {
+							cond: genre = 'm'
+						},
+						operation: 'Filter'
+					}
+				]
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_index_hints() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', genre='m', company='SurrealDB';
+		CREATE person:jaime SET name = 'Jaime', genre='m', company='SurrealDB';
+		CREATE person:lizzie SET name = 'Lizzie', genre='f', company='SurrealDB';
+		DEFINE INDEX uniq_name ON TABLE person COLUMNS name UNIQUE;
+		DEFINE INDEX idx_genre ON TABLE person COLUMNS genre;
+		SELECT name FROM person WITH INDEX idx_genre WHERE name = 'Jaime' AND genre = 'm';
+		SELECT name FROM person WITH INDEX idx_genre WHERE name = 'Jaime' AND genre = 'm' EXPLAIN FULL;
+		SELECT name FROM person WITH INDEX uniq_name WHERE genre = 'm';
+		SELECT name FROM person WITH INDEX uniq_name WHERE genre = 'm' EXPLAIN PLAN;
+	";
+	let mut res = execute_test(sql, 9).await?;
+	// The hinted index is used, even if another index is a better match
+	check_result(&mut res, "[{ name: 'Jaime' }]")?;
+	check_result(&mut res, SINGLE_INDEX_IDX_EXPLAIN)?;
+	// The hinted index can't resolve the condition
+	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Tobie' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				access: [
+					{
+						detail: {
+							estimate: 3,
+							reason: 'WITH INDEX uniq_name does not match the condition',
+							table: 'person'
+						},
+						operation: 'Iterate Table'
+					}
+				],
+				pushdown: [],
+				stages: [
+					{
+						detail: {
+							cond: genre = 'm'
+						},
+						operation: 'Filter'
+					}
+				]
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_composite_index_range() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX idx_abc ON TABLE t COLUMNS a, b, c;
+		CREATE t:1 SET a = 1, b = 7, c = 1;
+		CREATE t:2 SET a = 1, b = 3, c = 2;
+		CREATE t:3 SET a = 1, b = 6, c = 3;
+		CREATE t:4 SET a = 2, b = 8, c = 4;
+		CREATE t:5 SET a = 1, b = 5.5, c = 5;
+		SELECT id FROM t WHERE a = 1 AND b > 5 ORDER BY b;
+		SELECT id FROM t WHERE a = 1 AND b > 5 ORDER BY b EXPLAIN;
+		SELECT id FROM t WHERE a = 1 AND b >= 3 AND b < 7 ORDER BY b;
+		SELECT id FROM t WHERE a = 1 AND b >= 3 AND b < 7 ORDER BY b EXPLAIN;
+	";
+	let mut res = execute_test(sql, 10).await?;
+	check_result(&mut res, "[{ id: t:5 }, { id: t:3 }, { id: t:1 }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						from: {
+							inclusive: false,
+							value: 5
+						},
+						index: 'idx_abc',
+						operator: '=',
+						to: {
+							inclusive: false,
+							value: NONE
+						},
+						value: 1
+					},
+					table: 't'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: t:2 }, { id: t:5 }, { id: t:3 }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						from: {
+							inclusive: true,
+							value: 3
+						},
+						index: 'idx_abc',
+						operator: '=',
+						to: {
+							inclusive: false,
+							value: 7
+						},
+						value: 1
+					},
+					table: 't'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_computed_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX idx_email ON TABLE user FIELDS string::lowercase(email) UNIQUE;
+		CREATE user:tobie SET email = 'Tobie@SurrealDB.com';
+		CREATE user:jaime SET email = 'jaime@surrealdb.com';
+		UPDATE user:jaime SET email = 'Jaime@SurrealDB.com';
+		SELECT id FROM user WHERE string::lowercase(email) = 'jaime@surrealdb.com';
+		SELECT id FROM user WHERE string::lowercase(email) = 'jaime@surrealdb.com' EXPLAIN;
+		CREATE user:other SET email = 'TOBIE@surrealdb.com';
+		SELECT id FROM user WHERE string::lowercase(email) = 'tobie@surrealdb.com';
+	";
+	let mut res = execute_test(sql, 8).await?;
+	check_result(&mut res, "[{ id: user:jaime }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_email',
+						operator: '=',
+						value: 'jaime@surrealdb.com'
+					},
+					table: 'user'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	// The computed value is unique
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IndexExists { .. })), "{tmp:?}");
+	check_result(&mut res, "[{ id: user:tobie }]")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_covering_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD name ON TABLE person TYPE string;
+		DEFINE FIELD age ON TABLE person TYPE int;
+		DEFINE INDEX idx_name_age ON TABLE person COLUMNS name, age;
+		CREATE person:tobie SET name = 'Tobie', age = 30, company = 'SurrealDB';
+		CREATE person:jaime SET name = 'Jaime', age = 25, company = 'SurrealDB';
+		SELECT name, age FROM person WHERE name = 'Tobie';
+		SELECT name, age FROM person WHERE name = 'Tobie' EXPLAIN;
+		SELECT name, company FROM person WHERE name = 'Tobie' EXPLAIN;
+		SELECT id, age FROM person WHERE name = 'Jaime' AND age > 20;
+	";
+	let mut res = execute_test(sql, 9).await?;
+	check_result(&mut res, "[{ age: 30, name: 'Tobie' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						covering: true,
+						index: 'idx_name_age',
+						operator: '=',
+						value: 'Tobie'
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	// The company is not held by the index
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_name_age',
+						operator: '=',
+						value: 'Tobie'
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ age: 25, id: person:jaime }]")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_trigram_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX trg_name ON TABLE product FIELDS name TRIGRAM;
+		CREATE product:1 SET name = 'Red shirt';
+		CREATE product:2 SET name = 'Green shirt';
+		CREATE product:3 SET name = 'Red hat';
+		UPDATE product:3 SET name = 'Blue hat';
+		SELECT id FROM product WHERE name CONTAINS 'shirt' ORDER BY id;
+		SELECT id FROM product WHERE string::contains(name, 'Red');
+		SELECT id FROM product WHERE string::contains(name, 'Red') EXPLAIN;
+		SELECT id FROM product WHERE name = /^Blue h/;
+	";
+	let mut res = execute_test(sql, 9).await?;
+	check_result(&mut res, "[{ id: product:1 }, { id: product:2 }]")?;
+	check_result(&mut res, "[{ id: product:1 }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'trg_name',
+						operator: 'CONTAINS',
+						value: 'Red'
+					},
+					table: 'product'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: product:3 }]")?;
+	Ok(())
+}
+
 async fn execute_test(sql: &str, expected_result: usize) -> Result<Vec<Response>, Error> {
 	let dbs = new_ds().await?;
 	let ses = Session::owner().with_ns("test").with_db("test");
@@ -0,0 +1,70 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn database_quota() -> Result<(), Error> {
+	let dbs = new_ds().await?.with_database_quota("test", "test", 100_000);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		CREATE file:one SET data = string::repeat('a', 40000);
+		CREATE file:two SET data = string::repeat('b', 40000);
+		CREATE file:three SET data = string::repeat('c', 40000);
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	assert!(res.remove(0).result.is_ok());
+	assert!(res.remove(0).result.is_ok());
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(tmp, Err(Error::DbQuotaExceeded { ref value, quota: 100_000 }) if value == "test")
+	);
+	// The writes are counted towards the storage used by the database
+	let used = dbs.usage().database("test", "test");
+	assert!(used > 80_000 && used <= 100_000);
+	assert_eq!(dbs.usage().namespace("test"), used);
+	// Other databases are not limited by the quota
+	let ses = Session::owner().with_ns("test").with_db("other");
+	let sql = "CREATE file:three SET data = string::repeat('c', 40000)";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// Removing the quota allows further writes
+	dbs.quotas().set_database("test", "test", None);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "CREATE file:three SET data = string::repeat('c', 40000); SELECT VALUE id FROM file";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert!(res.remove(0).result.is_ok());
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[file:one, file:three, file:two]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn namespace_quota() -> Result<(), Error> {
+	let dbs = new_ds().await?.with_namespace_quota("test", 60_000);
+	let ses = Session::owner().with_ns("test").with_db("one");
+	let sql = "CREATE file:one SET data = string::repeat('a', 40000)";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// The quota covers every database in the namespace
+	let ses = Session::owner().with_ns("test").with_db("two");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::NsQuotaExceeded { ref value, .. }) if value == "test"));
+	// A failed write is not counted towards the storage used
+	assert_eq!(dbs.usage().database("test", "two"), 0);
+	// Records can still be deleted once the quota is reached
+	let ses = Session::owner().with_ns("test").with_db("one");
+	for res in dbs.execute("DELETE file:one", &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	//
+	Ok(())
+}
@@ -0,0 +1,180 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn rebuild_index_repopulates_index() -> Result<(), Error> {
+	let sql = "
+		CREATE person:one SET name = 'Tobie';
+		CREATE person:two SET name = 'Jaime';
+		CREATE person:three SET name = 'Lizzie';
+		DEFINE INDEX uniq_name ON person FIELDS name UNIQUE;
+		INFO FOR INDEX uniq_name ON person;
+		REBUILD INDEX uniq_name ON TABLE person;
+		INFO FOR INDEX uniq_name ON person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{}");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ building: { status: 'pending' } }");
+	assert_eq!(tmp, val);
+	// The index is repopulated in batches in the background
+	assert_eq!(dbs.build_indexes().await?, 1);
+	let sql = "
+		INFO FOR INDEX uniq_name ON person;
+		CREATE person:four SET name = 'Tobie';
+		SELECT VALUE id FROM person WHERE name = 'Jaime';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let tmp = tmp.pick(&["building".into()]);
+	assert_eq!(tmp.pick(&["status".into()]), Value::from("ready"));
+	assert_eq!(tmp.pick(&["processed".into()]), Value::from(3));
+	assert_eq!(tmp.pick(&["total".into()]), Value::from(3));
+	assert!(tmp.pick(&["started".into()]).is_datetime());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database index `uniq_name` already contains 'Tobie', with record `person:one`"#
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:two]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_index_not_found() -> Result<(), Error> {
+	let sql = "
+		CREATE person:one SET name = 'Tobie';
+		REBUILD INDEX missing ON person;
+		INFO FOR INDEX missing ON person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The index 'missing' does not exist"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The index 'missing' does not exist"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_index_builds_in_background() -> Result<(), Error> {
+	let dbs = new_ds().await?.with_batch_size(2);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		CREATE person:one SET name = 'Tobie';
+		CREATE person:two SET name = 'Jaime';
+		CREATE person:three SET name = 'Lizzie';
+		CREATE person:four SET name = 'Tobie';
+		CREATE person:five SET name = 'Jaime';
+		DEFINE INDEX name ON person FIELDS name;
+	";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// The index is not used until it has been built
+	let sql = "
+		INFO FOR INDEX name ON person;
+		SELECT VALUE id FROM person WHERE name = 'Jaime' EXPLAIN;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("{ building: { status: 'pending' } }"));
+	let tmp = res.remove(0).result?;
+	assert!(tmp.to_string().contains("Iterate Table"));
+	// The index is built in batches in the background
+	assert_eq!(dbs.build_indexes().await?, 1);
+	assert_eq!(dbs.build_indexes().await?, 0);
+	let sql = "
+		INFO FOR INDEX name ON person;
+		SELECT VALUE id FROM person WHERE name = 'Jaime' EXPLAIN;
+		SELECT VALUE id FROM person WHERE name = 'Jaime';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let tmp = tmp.pick(&["building".into()]);
+	assert_eq!(tmp.pick(&["status".into()]), Value::from("ready"));
+	assert_eq!(tmp.pick(&["processed".into()]), Value::from(5));
+	assert_eq!(tmp.pick(&["total".into()]), Value::from(5));
+	let tmp = res.remove(0).result?;
+	assert!(tmp.to_string().contains("Iterate Index"));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[person:five, person:two]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_index_reports_failed_build() -> Result<(), Error> {
+	let dbs = new_ds().await?.with_batch_size(2);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		CREATE person:one SET name = 'Tobie';
+		CREATE person:two SET name = 'Jaime';
+		CREATE person:three SET name = 'Tobie';
+		DEFINE INDEX name ON person FIELDS name UNIQUE;
+	";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// The build fails, and is not retried
+	assert_eq!(dbs.build_indexes().await?, 0);
+	assert_eq!(dbs.build_indexes().await?, 0);
+	// The error is stored on the index definition
+	let sql = "
+		INFO FOR INDEX name ON person;
+		SELECT VALUE id FROM person WHERE name = 'Jaime' EXPLAIN;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let tmp = tmp.pick(&["building".into()]);
+	assert_eq!(tmp.pick(&["status".into()]), Value::from("failed"));
+	assert_eq!(
+		tmp.pick(&["error".into()]),
+		Value::from("Database index `name` already contains 'Tobie', with record `person:one`")
+	);
+	let tmp = res.remove(0).result?;
+	assert!(tmp.to_string().contains("Iterate Table"));
+	//
+	Ok(())
+}
@@ -105,3 +105,55 @@ async fn relate_and_overwrite() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn relate_unique_reuses_edge() -> Result<(), Error> {
+	let sql = "
+		RELATE person:tobie->knows->person:jaime UNIQUE SET since = 2020;
+		RELATE person:tobie->knows->person:jaime UNIQUE SET since = 2021;
+		SELECT VALUE since FROM knows;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let id = tmp.pick(&[0.into(), "id".into()]);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&[0.into(), "id".into()]), id);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[2021]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_bidirectional() -> Result<(), Error> {
+	let sql = "
+		RELATE person:tobie->knows->person:jaime BIDIRECTIONAL RETURN NONE;
+		SELECT VALUE ->knows->person FROM person:tobie;
+		SELECT VALUE ->knows->person FROM person:jaime;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[person:jaime]]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[person:tobie]]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -772,6 +772,122 @@ async fn select_where_explain() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn select_order_stable() -> Result<(), Error> {
+	let sql = "
+		CREATE person:c SET score = 1, name = 'c';
+		CREATE person:a SET score = 2, name = 'a';
+		CREATE person:d SET score = 1, name = 'd';
+		CREATE person:b SET score = 1, name = 'b';
+		OPTION STABLE;
+		SELECT id, score FROM person:d, person:b, person:a, person:c ORDER BY score;
+		SELECT id, score FROM person ORDER BY score DESC PARALLEL;
+		SELECT id, score FROM person ORDER BY score START 1 LIMIT 2;
+		SELECT name, score FROM person:d, person:b, person:a, person:c ORDER BY score;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:b, score: 1 },
+			{ id: person:c, score: 1 },
+			{ id: person:d, score: 1 },
+			{ id: person:a, score: 2 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:a, score: 2 },
+			{ id: person:b, score: 1 },
+			{ id: person:c, score: 1 },
+			{ id: person:d, score: 1 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:c, score: 1 },
+			{ id: person:d, score: 1 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The ties are broken on the record id, even if it is not projected
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'b', score: 1 },
+			{ name: 'c', score: 1 },
+			{ name: 'd', score: 1 },
+			{ name: 'a', score: 2 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_order_stable_without_id() -> Result<(), Error> {
+	// Create the records in an order unrelated to their ids
+	let ids = (1..=60).map(|v| (v * 37) % 61).collect::<Vec<_>>();
+	let rows = ids
+		.iter()
+		.map(|v| format!("CREATE person:{v} SET name = 'name{v}', score = {};", v % 3))
+		.collect::<Vec<_>>();
+	let targets = ids.iter().rev().map(|v| format!("person:{v}")).collect::<Vec<_>>();
+	let sql = format!(
+		"
+		{}
+		OPTION STABLE;
+		SELECT name, score FROM person ORDER BY score;
+		SELECT name, score FROM person ORDER BY score PARALLEL;
+		SELECT name, score FROM {} ORDER BY score;
+	",
+		rows.join(""),
+		targets.join(", ")
+	);
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 63);
+	//
+	for _ in 0..60 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The ties are broken on the record id, which is not projected
+	let mut sorted = ids.clone();
+	sorted.sort_by_key(|v| (v % 3, *v));
+	let val = Value::parse(&format!(
+		"[{}]",
+		sorted
+			.iter()
+			.map(|v| format!("{{ name: 'name{v}', score: {} }}", v % 3))
+			.collect::<Vec<_>>()
+			.join(", ")
+	));
+	for _ in 0..3 {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp, val);
+	}
+	//
+	Ok(())
+}
+
 //
 // Permissions
 //
@@ -994,3 +1110,242 @@ async fn check_permissions_auth_disabled() {
 		);
 	}
 }
+
+#[tokio::test]
+async fn select_combine_union_intersect_except() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET name = 'Tobie', city = 'London';
+		CREATE person:2 SET name = 'Jaime', city = 'Paris';
+		CREATE person:3 SET name = 'Tobie', city = 'Paris';
+		SELECT VALUE name FROM person WHERE city = 'London' UNION SELECT VALUE name FROM person WHERE city = 'Paris';
+		SELECT VALUE name FROM person WHERE city = 'London' UNION ALL SELECT VALUE name FROM person WHERE city = 'Paris';
+		SELECT VALUE name FROM person WHERE city = 'London' INTERSECT SELECT VALUE name FROM person WHERE city = 'Paris';
+		SELECT VALUE name FROM person EXCEPT SELECT VALUE name FROM person WHERE city = 'London';
+		SELECT VALUE name FROM person EXCEPT ALL SELECT VALUE name FROM person WHERE city = 'London';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("['Tobie', 'Jaime']");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("['Tobie', 'Jaime', 'Tobie']");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("['Tobie']");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("['Jaime']");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("['Jaime', 'Tobie']");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_combine_order_limit() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET name = 'Tobie', age = 33;
+		CREATE person:2 SET name = 'Jaime', age = 28;
+		CREATE animal:1 SET name = 'Rex', age = 5;
+		SELECT name, age FROM person UNION SELECT name, age FROM animal ORDER BY age DESC LIMIT 2;
+		(SELECT name FROM person ORDER BY name LIMIT 1) UNION (SELECT name FROM animal) ORDER BY name START 1;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'Tobie', age: 33 },
+			{ name: 'Jaime', age: 28 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ name: 'Rex' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_after_cursor() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET name = 'Tobie', age = 33;
+		CREATE person:2 SET name = 'Jaime', age = 28;
+		CREATE person:3 SET name = 'Lizzie', age = 12;
+		CREATE person:4 SET name = 'Marcus', age = 41;
+		CREATE person:5 SET name = 'Emma', age = 19;
+		LET $page = SELECT name FROM person WHERE age > 18 AFTER NONE LIMIT 2;
+		RETURN $page;
+		LET $page = SELECT name FROM person WHERE age > 18 AFTER $page.cursor LIMIT 2;
+		RETURN $page;
+		SELECT name FROM person WHERE age > 18 AFTER $page.cursor LIMIT 2;
+		SELECT name FROM person AFTER person:3;
+		SELECT name FROM person ORDER BY name AFTER NONE LIMIT 2;
+		SELECT name FROM person AFTER 'invalid' LIMIT 2;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 13);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			result: [{ name: 'Tobie' }, { name: 'Jaime' }],
+			cursor: 'cGVyc29uOjI',
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			result: [{ name: 'Marcus' }, { name: 'Emma' }],
+			cursor: 'cGVyc29uOjU',
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			result: [],
+			cursor: NONE,
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			result: [{ name: 'Marcus' }, { name: 'Emma' }],
+			cursor: NONE,
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Unable to paginate with the AFTER clause: A cursor can not be used with ORDER BY, GROUP BY, or SPLIT"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Unable to paginate with the AFTER clause: The cursor is not valid for this table"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_order_by_parameterized_field() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET name = 'Tobie', age = 33, meta.rank = 3;
+		CREATE person:2 SET name = 'Jaime', age = 28, meta.rank = 1;
+		CREATE person:3 SET name = 'Lizzie', age = 41, meta.rank = 2;
+		LET $field = 'age';
+		SELECT name, age FROM person ORDER BY type::field($field) DESC;
+		SELECT name FROM person ORDER BY type::field('name');
+		SELECT name, meta.rank FROM person ORDER BY type::field('meta.rank');
+		SELECT name FROM person ORDER BY type::field($field);
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'Lizzie', age: 41 },
+			{ name: 'Tobie', age: 33 },
+			{ name: 'Jaime', age: 28 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ name: 'Jaime' }, { name: 'Lizzie' }, { name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'Jaime', meta: { rank: 1 } },
+			{ name: 'Lizzie', meta: { rank: 2 } },
+			{ name: 'Tobie', meta: { rank: 3 } },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The computed field must be projected
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::InvalidOrderField { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_small_scan_batches() -> Result<(), Error> {
+	let sql = "
+		CREATE |person:1..5|;
+		SELECT VALUE id FROM person;
+		SELECT VALUE id FROM person:2..=4;
+	";
+	let dbs = new_ds().await?.with_batch_size(2);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:1, person:2, person:3, person:4, person:5]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:2, person:3, person:4]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
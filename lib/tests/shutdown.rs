@@ -0,0 +1,83 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn shutdown_refuses_new_queries() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	dbs.shutdown(Duration::from_secs(1)).await?;
+	let res = dbs.execute("CREATE person:test", &ses, None).await;
+	assert!(matches!(
+		res,
+		Err(e) if e.to_string() == "The datastore is shutting down"
+	));
+	Ok(())
+}
+
+#[tokio::test]
+async fn shutdown_waits_for_running_queries() -> Result<(), Error> {
+	let dbs = Arc::new(new_ds().await?);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	// Start a long running query
+	let run = {
+		let dbs = dbs.clone();
+		let ses = ses.clone();
+		tokio::spawn(
+			async move { dbs.execute("SLEEP 500ms; CREATE person:test;", &ses, None).await },
+		)
+	};
+	tokio::time::sleep(Duration::from_millis(100)).await;
+	// Shutdown once the query has completed
+	let now = Instant::now();
+	dbs.shutdown(Duration::from_secs(5)).await?;
+	assert!(now.elapsed() >= Duration::from_millis(300));
+	let res = &mut run.await.unwrap()?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:test }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn shutdown_cancels_long_running_queries() -> Result<(), Error> {
+	let dbs = Arc::new(new_ds().await?);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	// Start a query which iterates slowly over many values
+	let run = {
+		let dbs = dbs.clone();
+		let ses = ses.clone();
+		let sql = format!(
+			"SELECT * FROM [{}] WHERE sleep(50ms) = NONE;",
+			(0..40).map(|i| i.to_string()).collect::<Vec<_>>().join(", ")
+		);
+		tokio::spawn(async move { dbs.execute(&sql, &ses, None).await })
+	};
+	tokio::time::sleep(Duration::from_millis(100)).await;
+	// Shutdown, cancelling the query mid-iteration
+	let now = Instant::now();
+	dbs.shutdown(Duration::from_millis(200)).await?;
+	let res = &mut run.await.unwrap()?;
+	assert!(now.elapsed() < Duration::from_secs(1));
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp,
+		Err(e) if e.to_string() == "The query was not executed due to a cancelled transaction"
+	));
+	//
+	Ok(())
+}
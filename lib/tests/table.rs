@@ -105,3 +105,452 @@ async fn define_foreign_table() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn define_foreign_table_group_delete() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_country AS
+			SELECT
+				count() AS total,
+				country,
+				math::min(age) AS youngest,
+				math::max(age) AS oldest
+			FROM person
+			GROUP BY country
+		;
+		CREATE person:one SET country = 'GB', age = 20;
+		CREATE person:two SET country = 'GB', age = 30;
+		CREATE person:three SET country = 'US', age = 40;
+		DELETE person:one;
+		SELECT * FROM person_by_country;
+		UPDATE person:three SET country = 'GB';
+		SELECT * FROM person_by_country;
+		DELETE person;
+		SELECT * FROM person_by_country;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				country: 'GB',
+				id: person_by_country:['GB'],
+				oldest: 30,
+				total: 1,
+				youngest: 30
+			},
+			{
+				country: 'US',
+				id: person_by_country:['US'],
+				oldest: 40,
+				total: 1,
+				youngest: 40
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				country: 'GB',
+				id: person_by_country:['GB'],
+				oldest: 40,
+				total: 2,
+				youngest: 30
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_group_where_delete() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE adults_by_age AS
+			SELECT count() AS total, age, math::mean(score) AS average
+			FROM person
+			WHERE age >= 18
+			GROUP BY age
+		;
+		CREATE person:one SET age = 39, score = 70;
+		CREATE person:two SET age = 39, score = 80;
+		CREATE person:three SET age = 12, score = 90;
+		DELETE person:one;
+		SELECT * FROM adults_by_age;
+		UPDATE person:two SET age = 17;
+		SELECT * FROM adults_by_age;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				age: 39,
+				average: 80,
+				id: adults_by_age:[39],
+				total: 1
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_group_sum_move() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE score_by_team AS
+			SELECT count() AS total, team, math::sum(score) AS score
+			FROM person
+			GROUP BY team
+		;
+		CREATE person:one SET team = 'red', score = 10;
+		CREATE person:two SET team = 'red', score = 20;
+		UPDATE person:one SET team = 'blue';
+		UPDATE person:two SET score = 25;
+		SELECT * FROM score_by_team;
+		UPDATE person:two SET team = 'blue';
+		SELECT * FROM score_by_team;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: score_by_team:['blue'],
+				score: 10,
+				team: 'blue',
+				total: 1
+			},
+			{
+				id: score_by_team:['red'],
+				score: 25,
+				team: 'red',
+				total: 1
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// The group is removed once it no longer holds any records
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: score_by_team:['blue'],
+				score: 35,
+				team: 'blue',
+				total: 2
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_group_distinct_approx() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE visit SCHEMALESS;
+		DEFINE TABLE visitors_by_page AS
+			SELECT count() AS total, page, count::distinct_approx(user) AS users
+			FROM visit
+			GROUP BY page
+		;
+		CREATE visit:1 SET page = 'home', user = 'tobie';
+		CREATE visit:2 SET page = 'home', user = 'jaime';
+		CREATE visit:3 SET page = 'home', user = 'tobie';
+		SELECT * FROM visitors_by_page;
+		DELETE visit:2;
+		SELECT * FROM visitors_by_page;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: visitors_by_page:['home'],
+				page: 'home',
+				total: 3,
+				users: 2
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: visitors_by_page:['home'],
+				page: 'home',
+				total: 2,
+				users: 1
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_refresh_manual() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_age AS
+			SELECT count() AS total, age FROM person GROUP BY age REFRESH MANUAL
+		;
+		INFO FOR TABLE person;
+		CREATE person:one SET age = 39;
+		CREATE person:two SET age = 39;
+		SELECT * FROM person_by_age;
+		REFRESH VIEW person_by_age;
+		SELECT * FROM person_by_age;
+		REFRESH VIEW person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: {},
+			tables: { person_by_age: 'DEFINE TABLE person_by_age SCHEMALESS AS SELECT count() AS total, age FROM person GROUP BY age REFRESH MANUAL' },
+			indexes: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				age: 39,
+				id: person_by_age:[39],
+				total: 2
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Unable to refresh the `person` table as it is not setup as a view"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_foreign_table_refresh_every() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_age AS
+			SELECT count() AS total, age FROM person GROUP BY age REFRESH EVERY 1h
+		;
+		CREATE person:one SET age = 39;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The view is refreshed on the first tick
+	dbs.tick_at(1000).await?;
+	let res = &mut dbs.execute("SELECT * FROM person_by_age", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ age: 39, id: person_by_age:[39], total: 1 }]");
+	assert_eq!(tmp, val);
+	// The view is not refreshed until the interval has passed
+	dbs.execute("CREATE person:two SET age = 39", &ses, None).await?;
+	dbs.tick_at(1060).await?;
+	let res = &mut dbs.execute("SELECT * FROM person_by_age", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ age: 39, id: person_by_age:[39], total: 1 }]");
+	assert_eq!(tmp, val);
+	// The view is refreshed once the interval has passed
+	dbs.tick_at(4600).await?;
+	let res = &mut dbs.execute("SELECT * FROM person_by_age", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ age: 39, id: person_by_age:[39], total: 2 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_table_with_ttl() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE TABLE session SCHEMALESS TTL 200ms;
+		DEFINE INDEX user ON session FIELDS user UNIQUE;
+		CREATE session:one SET user = 'tobie';
+		SELECT * FROM session;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: session:one, user: 'tobie' }]");
+	assert_eq!(tmp, val);
+	// Expired records are hidden from reads
+	tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+	let res =
+		&mut dbs.execute("SELECT * FROM session; SELECT * FROM session:one", &ses, None).await?;
+	for _ in 0..2 {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp, Value::parse("[]"));
+	}
+	// Expired records keep their index entries until they are purged
+	let res = &mut dbs.execute("CREATE session:two SET user = 'tobie'", &ses, None).await?;
+	assert!(res.remove(0).result.is_err());
+	// Expired records are purged on the next tick
+	let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap();
+	dbs.tick_at(now.as_secs() + 1).await?;
+	let res = &mut dbs.execute("CREATE session:two SET user = 'tobie'", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: session:two, user: 'tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_table_with_ttl_ignores_expired_records_in_writes() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		CREATE person:one SET name = 'Tobie';
+		DEFINE TABLE session SCHEMALESS TTL 200ms;
+		DEFINE TABLE person SCHEMALESS TTL 200ms;
+		DEFINE INDEX user ON session FIELDS user UNIQUE;
+		CREATE session:one SET user = 'tobie';
+	";
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+	let sql = "
+		DELETE session:one RETURN BEFORE;
+		UPDATE session SET active = true;
+		CREATE session:one SET user = 'tobie';
+		SELECT * FROM session;
+		SELECT * FROM person;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	// Expired records are neither updated nor deleted
+	for _ in 0..2 {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp, Value::parse("[]"));
+	}
+	// Expired records are replaced as if they did not exist
+	for _ in 0..2 {
+		let tmp = res.remove(0).result?;
+		let val = Value::parse("[{ id: session:one, user: 'tobie' }]");
+		assert_eq!(tmp, val);
+	}
+	// Records which existed before the TTL was defined also expire
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	//
+	Ok(())
+}
@@ -4,6 +4,7 @@ mod helpers;
 use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
+use surrealdb::sql::Isolation;
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -185,3 +186,283 @@ async fn transaction_with_throw_and_return() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn transaction_select_for_update() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET balance = 10;
+		BEGIN;
+		SELECT * FROM person:tobie FOR UPDATE;
+		UPDATE person:tobie SET balance -= 5;
+		COMMIT;
+		SELECT * FROM person:tobie;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, balance: 10 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, balance: 5 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, balance: 5 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_rollback_to_savepoint() -> Result<(), Error> {
+	let sql = "
+		BEGIN;
+		CREATE person:tobie;
+		SAVEPOINT before;
+		CREATE person:jaime;
+		CREATE person:tobie;
+		ROLLBACK TO SAVEPOINT before;
+		CREATE person:lizzie;
+		COMMIT;
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"The query was not executed due to a cancelled transaction"#
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database record `person:tobie` already exists"#
+	));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:lizzie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:lizzie }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_release_savepoint() -> Result<(), Error> {
+	let sql = "
+		SAVEPOINT before;
+		BEGIN;
+		SAVEPOINT before;
+		CREATE person:tobie;
+		RELEASE SAVEPOINT before;
+		ROLLBACK TO SAVEPOINT before;
+		COMMIT;
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Savepoints can only be used within a transaction"#
+	));
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(matches!(
+			tmp.err(),
+			Some(e) if e.to_string() == r#"The query was not executed due to a failed transaction"#
+		));
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"The savepoint 'before' does not exist"#
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_atomic_batch() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie;
+		CREATE person:jaime;
+		CREATE person:tobie;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.process(surrealdb::sql::parse(sql)?.atomic()?, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"The query was not executed due to a failed transaction"#
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"The query was not executed due to a failed transaction"#
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database record `person:tobie` already exists"#
+	));
+	//
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let sql = "CREATE person:tobie; CANCEL;";
+	let res = surrealdb::sql::parse(sql)?.atomic();
+	assert!(matches!(
+		res.err(),
+		Some(e) if e.to_string() == r#"Transaction statements can not be used within an atomic batch"#
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_atomic_option() -> Result<(), Error> {
+	let sql = "
+		OPTION ATOMIC;
+		CREATE person:tobie;
+		RETURN 1;
+		COMMIT;
+		CREATE person:jaime;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(matches!(
+			tmp.err(),
+			Some(e) if e.to_string() == r#"The query was not executed due to a failed transaction"#
+		));
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Transaction statements can not be used within an atomic batch"#
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"The query was not executed due to a failed transaction"#
+	));
+	//
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_with_isolation_level() -> Result<(), Error> {
+	let sql = "
+		BEGIN TRANSACTION ISOLATION LEVEL SERIALIZABLE;
+		CREATE person:tobie;
+		COMMIT;
+		BEGIN TRANSACTION ISOLATION LEVEL SNAPSHOT;
+		CREATE person:jaime;
+		COMMIT;
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?.with_isolation(Isolation::Serializable);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_import_is_split() -> Result<(), Error> {
+	let sql = "
+		OPTION IMPORT;
+		BEGIN;
+		CREATE person:tobie;
+		CREATE person:jaime;
+		THROW 'failed';
+		COMMIT;
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?.with_import_split_size(Some(1));
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_err());
+	// The statements imported before the split were committed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -0,0 +1,136 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn truncate_statement_removes_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX uniq_name ON person FIELDS name UNIQUE;
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		RELATE person:tobie->knows->person:jaime;
+		TRUNCATE TABLE person;
+		SELECT * FROM person;
+		CREATE person:tobie SET name = 'Tobie';
+		SELECT ->knows->person AS knows FROM person:tobie;
+		SELECT * FROM knows;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ knows: [] }]");
+	assert_eq!(tmp, val);
+	//
+	// The relations between the records are removed along with them
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn truncate_statement_updates_views() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person_by_age AS SELECT count() AS total, age FROM person GROUP BY age;
+		DEFINE TABLE other;
+		CREATE person:tobie SET age = 30;
+		CREATE person:jaime SET age = 30;
+		CREATE other:one SET age = 30;
+		TRUNCATE TABLE person;
+		SELECT * FROM person_by_age;
+		SELECT * FROM other;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: other:one, age: 30 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn truncate_statement_keeps_definitions() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMAFULL;
+		DEFINE FIELD name ON person TYPE string;
+		DEFINE INDEX uniq_name ON person FIELDS name UNIQUE;
+		CREATE person:tobie SET name = 'Tobie';
+		TRUNCATE person;
+		INFO FOR TABLE person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: { name: 'DEFINE FIELD name ON person TYPE string' },
+			tables: {},
+			indexes: { uniq_name: 'DEFINE INDEX uniq_name ON person FIELDS name UNIQUE' },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn truncate_statement_missing_table() -> Result<(), Error> {
+	let sql = "
+		TRUNCATE TABLE person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The table 'person' does not exist"
+	));
+	//
+	Ok(())
+}
@@ -98,6 +98,93 @@ async fn update_simple_with_input() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn update_with_return_diff() -> Result<(), Error> {
+	let sql = "
+		CREATE person:test SET name = 'Tester', age = 30 RETURN DIFF;
+		UPDATE person:test SET age = 31, name = NONE, active = true RETURN DIFF;
+		UPDATE person:test SET age = 32 RETURN BEFORE, AFTER;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			[
+				{ op: 'add', path: '/age', value: 30 },
+				{ op: 'add', path: '/id', value: person:test },
+				{ op: 'add', path: '/name', value: 'Tester' },
+			]
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			[
+				{ op: 'test', path: '/name', value: 'Tester' },
+				{ op: 'remove', path: '/name' },
+				{ op: 'add', path: '/active', value: true },
+				{ op: 'test', path: '/age', value: 30 },
+				{ op: 'replace', path: '/age', value: 31 },
+			]
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				before: { id: person:test, active: true, age: 31 },
+				after: { id: person:test, active: true, age: 32 },
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn update_with_order_and_limit() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET age = 20;
+		CREATE person:2 SET age = 40;
+		CREATE person:3 SET age = 30;
+		UPDATE person SET oldest = true ORDER BY age DESC LIMIT 1;
+		SELECT VALUE id FROM person WHERE oldest = true;
+		UPDATE person SET youngest = true ORDER BY age LIMIT 2 RETURN VALUE id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:2, age: 40, oldest: true }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:2]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:1, person:3]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn update_complex_with_input() -> Result<(), Error> {
 	let sql = "
@@ -0,0 +1,121 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::kvs::{Key, Vacuum};
+use surrealdb::sql::{Id, Value};
+
+#[tokio::test]
+async fn vacuum_removes_orphaned_index_entries() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX name ON person FIELDS name;
+		DEFINE INDEX email ON person FIELDS email UNIQUE;
+		CREATE person:tobie SET name = 'Tobie', email = 'tobie@surrealdb.com';
+		CREATE person:jaime SET name = 'Jaime', email = 'jaime@surrealdb.com';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// Remove a record without its index entries, and leave the data of a removed index behind
+	let mut tx = dbs.transaction(true, false).await?;
+	tx.del(surrealdb::key::thing::new("test", "test", "person", &Id::from("jaime"))).await?;
+	let all: Key = surrealdb::key::index::all::new("test", "test", "person", "old").into();
+	tx.set([all.as_slice(), b"*data"].concat(), "data").await?;
+	tx.commit().await?;
+	// The orphaned entries are removed
+	let res = dbs.vacuum().await?;
+	assert_eq!(
+		res,
+		Vacuum {
+			index_entries: 3,
+			..Default::default()
+		}
+	);
+	assert_eq!(dbs.vacuum().await?, Vacuum::default());
+	// The index entries of existing records are kept
+	let sql = "SELECT * FROM person WITH INDEX name WHERE name = 'Tobie'";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:tobie, name: 'Tobie', email: 'tobie@surrealdb.com' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn vacuum_removes_dangling_graph_edges() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie, person:jaime;
+		RELATE person:tobie->knows->person:jaime;
+		RELATE person:one->likes->person:two;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute(sql, &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// Remove a relation without its graph edges
+	let res = &mut dbs.execute("SELECT VALUE id FROM knows", &ses, None).await?;
+	let Value::Thing(rel) = res.remove(0).result?.first() else {
+		panic!("expected a record id");
+	};
+	let mut tx = dbs.transaction(true, false).await?;
+	tx.del(surrealdb::key::thing::new("test", "test", "knows", &rel.id)).await?;
+	tx.commit().await?;
+	// The pointers from both people, and from the relation, are removed
+	let res = dbs.vacuum().await?;
+	assert_eq!(
+		res,
+		Vacuum {
+			graph_edges: 4,
+			..Default::default()
+		}
+	);
+	assert_eq!(dbs.vacuum().await?, Vacuum::default());
+	// The relations between records which do not exist are kept
+	let one = Id::from("one");
+	let beg = surrealdb::key::graph::prefix("test", "test", "person", &one);
+	let end = surrealdb::key::graph::suffix("test", "test", "person", &one);
+	let mut tx = dbs.transaction(false, false).await?;
+	assert_eq!(tx.scan(beg..end, 10).await?.len(), 1);
+	tx.cancel().await?;
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn vacuum_removes_dead_live_queries() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	for res in dbs.execute("DEFINE TABLE person", &ses, None).await? {
+		assert!(res.result.is_ok());
+	}
+	// Register a live query on a node which is not running
+	let nd = uuid::Uuid::new_v4();
+	let lq = uuid::Uuid::new_v4();
+	let mut tx = dbs.transaction(true, false).await?;
+	tx.set(surrealdb::key::node::lq::new(nd, lq, "test", "test"), "person").await?;
+	tx.commit().await?;
+	// The registration is removed
+	let res = dbs.vacuum().await?;
+	assert_eq!(
+		res,
+		Vacuum {
+			live_queries: 1,
+			..Default::default()
+		}
+	);
+	let mut tx = dbs.transaction(false, false).await?;
+	assert!(tx.scan_ndlq(&nd, 10).await?.is_empty());
+	tx.cancel().await?;
+	//
+	Ok(())
+}
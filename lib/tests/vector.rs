@@ -0,0 +1,93 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn select_where_knn_using_hnsw_index() -> Result<(), Error> {
+	let sql = r"
+		CREATE pts:1 SET point = [1,2,3,4];
+		CREATE pts:2 SET point = [4,5,6,7];
+		CREATE pts:3 SET point = [8,9,10,11];
+		CREATE pts:4 SET point = [12,13,14,15];
+		DEFINE INDEX hnsw_pts ON pts FIELDS point HNSW DIMENSION 4 DIST EUCLIDEAN M 4 EFC 20;
+		LET $pt = [2,3,4,5];
+		SELECT id FROM pts WHERE point <|2|> $pt ORDER BY id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: pts:1
+			},
+			{
+				id: pts:2
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_knn_rejects_invalid_dimension() -> Result<(), Error> {
+	let sql = r"
+		DEFINE INDEX hnsw_pts ON pts FIELDS point HNSW DIMENSION 4;
+		CREATE pts:1 SET point = [1,2,3];
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let _ = res.remove(0).result?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp,
+		Err(Error::InvalidVectorDimension {
+			current: 3,
+			expected: 4
+		})
+	));
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_knn_using_quantized_hnsw_index() -> Result<(), Error> {
+	let sql = r"
+		DEFINE INDEX hnsw_pts ON pts FIELDS point HNSW DIMENSION 4 DIST EUCLIDEAN QUANTIZATION SCALAR;
+		CREATE pts:1 SET point = [1,2,3,4];
+		CREATE pts:2 SET point = [4,5,6,7];
+		CREATE pts:3 SET point = [8,9,10,11];
+		SELECT id FROM pts WHERE point <|1|> [8,9,10,12];
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: pts:3
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
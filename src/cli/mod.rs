@@ -4,10 +4,14 @@ mod config;
 mod export;
 mod import;
 mod isready;
+#[cfg(all(feature = "has-storage", feature = "archive"))]
+mod restore;
 mod sql;
 #[cfg(feature = "has-storage")]
 mod start;
 mod upgrade;
+#[cfg(feature = "has-storage")]
+mod vacuum;
 mod validate;
 pub(crate) mod validator;
 mod version;
@@ -20,11 +24,15 @@ pub use config::CF;
 use export::ExportCommandArguments;
 use import::ImportCommandArguments;
 use isready::IsReadyCommandArguments;
+#[cfg(all(feature = "has-storage", feature = "archive"))]
+use restore::RestoreCommandArguments;
 use sql::SqlCommandArguments;
 #[cfg(feature = "has-storage")]
 use start::StartCommandArguments;
 use std::process::ExitCode;
 use upgrade::UpgradeCommandArguments;
+#[cfg(feature = "has-storage")]
+use vacuum::VacuumCommandArguments;
 use validate::ValidateCommandArguments;
 use version::VersionCommandArguments;
 
@@ -62,6 +70,12 @@ enum Commands {
 	Import(ImportCommandArguments),
 	#[command(about = "Export an existing database as a SurrealQL script")]
 	Export(ExportCommandArguments),
+	#[cfg(all(feature = "has-storage", feature = "archive"))]
+	#[command(about = "Restore the backups archived to object storage into a database")]
+	Restore(RestoreCommandArguments),
+	#[cfg(feature = "has-storage")]
+	#[command(about = "Remove the keys which a crash left inconsistent from a database")]
+	Vacuum(VacuumCommandArguments),
 	#[command(about = "Output the command-line tool and remote server version information")]
 	Version(VersionCommandArguments),
 	#[command(about = "Upgrade to the latest stable version")]
@@ -85,6 +99,10 @@ pub async fn init() -> ExitCode {
 		Commands::Backup(args) => backup::init(args).await,
 		Commands::Import(args) => import::init(args).await,
 		Commands::Export(args) => export::init(args).await,
+		#[cfg(all(feature = "has-storage", feature = "archive"))]
+		Commands::Restore(args) => restore::init(args).await,
+		#[cfg(feature = "has-storage")]
+		Commands::Vacuum(args) => vacuum::init(args).await,
 		Commands::Version(args) => version::init(args).await,
 		Commands::Upgrade(args) => upgrade::init(args).await,
 		Commands::Sql(args) => sql::init(args).await,
@@ -0,0 +1,30 @@
+use crate::err::Error;
+use clap::Args;
+use surrealdb::kvs::Datastore;
+
+#[derive(Args, Debug)]
+pub struct RestoreCommandArguments {
+	#[arg(help = "The S3-compatible or Google Cloud Storage path of the archived backups")]
+	#[arg(index = 1)]
+	archive: String,
+	#[arg(help = "Database path in which the backups are restored")]
+	#[arg(index = 2)]
+	#[arg(value_parser = super::validator::path_valid)]
+	path: String,
+}
+
+pub async fn init(
+	RestoreCommandArguments {
+		archive,
+		path,
+	}: RestoreCommandArguments,
+) -> Result<(), Error> {
+	// Initialize opentelemetry and logging
+	crate::telemetry::builder().with_log_level("info").init();
+	// Open the datastore in which the backups are restored
+	let dbs = Datastore::new(&path).await?;
+	// Restore every database from the archive
+	dbs.restore_archive(&archive).await?;
+	info!("The archived backups were restored successfully");
+	Ok(())
+}
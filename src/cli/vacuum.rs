@@ -0,0 +1,26 @@
+use crate::err::Error;
+use clap::Args;
+use surrealdb::kvs::Datastore;
+
+#[derive(Args, Debug)]
+pub struct VacuumCommandArguments {
+	#[arg(help = "Database path of the datastore to clean up")]
+	#[arg(index = 1)]
+	#[arg(value_parser = super::validator::path_valid)]
+	path: String,
+}
+
+pub async fn init(
+	VacuumCommandArguments {
+		path,
+	}: VacuumCommandArguments,
+) -> Result<(), Error> {
+	// Initialize opentelemetry and logging
+	crate::telemetry::builder().with_log_level("info").init();
+	// Open the datastore which is cleaned up
+	let dbs = Datastore::new(&path).await?;
+	// Remove any keys left inconsistent by a crash
+	let res = dbs.vacuum().await?;
+	info!("The vacuum {res}");
+	Ok(())
+}
@@ -33,6 +33,26 @@ pub const MAX_CONCURRENT_CALLS: usize = 24;
 #[cfg(feature = "has-storage")]
 pub const WEBSOCKET_PING_FREQUENCY: Duration = Duration::from_secs(5);
 
+/// The largest message which can be received on a WebSocket or gRPC stream
+#[cfg(feature = "has-storage")]
+pub const MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+/// How many prepared statements can be stored on a WebSocket connection
+#[cfg(feature = "has-storage")]
+pub const MAX_PREPARED_STATEMENTS: usize = 1000;
+
+/// How long the outcome of a request sent with an idempotency key is kept, to be returned when it is retried
+#[cfg(feature = "has-storage")]
+pub const IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(600);
+
+/// How many idempotency keys can be remembered at once
+#[cfg(feature = "has-storage")]
+pub const MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+
+/// How long running queries are given to complete when shutting down
+#[cfg(feature = "has-storage")]
+pub const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
 /// The version identifier of this build
 pub static PKG_VERSION: Lazy<String> = Lazy::new(|| match option_env!("SURREAL_BUILD_METADATA") {
 	Some(metadata) if !metadata.trim().is_empty() => {
@@ -27,6 +27,16 @@ pub struct StartCommandDbsOptions {
 	#[arg(env = "SURREAL_AUTH", long = "auth")]
 	#[arg(default_value_t = false)]
 	auth_enabled: bool,
+	#[cfg(feature = "archive")]
+	#[arg(help = "An S3-compatible or Google Cloud Storage path to which backups are archived")]
+	#[arg(env = "SURREAL_ARCHIVE", long)]
+	archive: Option<String>,
+	#[cfg(feature = "archive")]
+	#[arg(help = "How often backups are archived")]
+	#[arg(env = "SURREAL_ARCHIVE_INTERVAL", long)]
+	#[arg(default_value = "1h")]
+	#[arg(value_parser = super::cli::validator::duration)]
+	archive_interval: Duration,
 	#[command(flatten)]
 	#[command(next_help_heading = "Capabilities")]
 	caps: DbsCapabilities,
@@ -204,6 +214,10 @@ pub async fn init(
 		query_timeout,
 		transaction_timeout,
 		auth_enabled,
+		#[cfg(feature = "archive")]
+		archive,
+		#[cfg(feature = "archive")]
+		archive_interval,
 		caps,
 	}: StartCommandDbsOptions,
 ) -> Result<(), Error> {
@@ -239,6 +253,16 @@ pub async fn init(
 		.with_auth_enabled(auth_enabled)
 		.with_capabilities(caps);
 
+	// Archive backups to object storage
+	#[cfg(feature = "archive")]
+	let dbs = match archive {
+		Some(path) => {
+			info!("Archiving backups every {archive_interval:?}");
+			dbs.with_archive(&path, archive_interval)?
+		}
+		None => dbs,
+	};
+
 	dbs.bootstrap().await?;
 
 	if let Some(user) = opt.user.as_ref() {
@@ -57,6 +57,12 @@ pub enum Error {
 
 	#[error("There was an error with the node agent")]
 	NodeAgent,
+
+	#[error("The prepared statement '{0}' does not exist")]
+	PreparedNotFound(uuid::Uuid),
+
+	#[error("Too many prepared statements have been stored on this connection")]
+	TooManyPrepared,
 }
 
 impl From<Error> for String {
@@ -4,11 +4,22 @@ use axum::response::IntoResponse;
 use axum::routing::get;
 use axum::Router;
 use axum::{response::Response, Extension};
+use axum_extra::extract::Query;
 use bytes::Bytes;
 use http::StatusCode;
 use http_body::Body as HttpBody;
 use hyper::body::Body;
+use serde::Deserialize;
 use surrealdb::dbs::Session;
+use surrealdb::kvs::{ExportConfig, ExportFormat};
+use surrealdb::sql::Datetime;
+
+#[derive(Default, Deserialize, Debug, Clone)]
+struct ExportOptions {
+	pub tables: Option<String>,
+	pub format: Option<ExportFormat>,
+	pub since: Option<String>,
+}
 
 pub(super) fn router<S, B>() -> Router<S, B>
 where
@@ -20,6 +31,7 @@ where
 
 async fn handler(
 	Extension(session): Extension<Session>,
+	Query(options): Query<ExportOptions>,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
 	// Get the datastore reference
 	let db = DB.get().unwrap();
@@ -33,12 +45,21 @@ async fn handler(
 		Some(db) => db,
 		None => return Err(Error::NoDatabase),
 	};
+	// Select the contents and the format of the export
+	let config = ExportConfig {
+		tables: options.tables.map(|v| v.split(',').map(str::to_owned).collect()),
+		format: options.format.unwrap_or_default(),
+		since: match options.since {
+			Some(v) => Some(Datetime::try_from(v.as_str()).map_err(|_| Error::Request)?),
+			None => None,
+		},
+	};
 	// Create a chunked response
 	let (mut chn, bdy) = Body::channel();
 	// Create a new bounded channel
 	let (snd, rcv) = surrealdb::channel::new(1);
 
-	let export_job = db.export(&session, nsv, dbv, snd).await.map_err(Error::from)?;
+	let export_job = db.export_with(&session, nsv, dbv, config, snd).await.map_err(Error::from)?;
 	// Spawn a new database export job
 	tokio::spawn(export_job);
 	// Process all processed values
@@ -8,15 +8,23 @@ use axum::routing::post;
 use axum::Extension;
 use axum::Router;
 use axum::TypedHeader;
+use axum_extra::extract::Query;
 use bytes::Bytes;
 use http_body::Body as HttpBody;
+use serde::Deserialize;
 use surrealdb::dbs::Session;
+use surrealdb::kvs::ExportFormat;
 use tower_http::limit::RequestBodyLimitLayer;
 
 use super::headers::Accept;
 
 const MAX: usize = 1024 * 1024 * 1024 * 4; // 4 GiB
 
+#[derive(Default, Deserialize, Debug, Clone)]
+struct ImportOptions {
+	pub format: Option<ExportFormat>,
+}
+
 pub(super) fn router<S, B>() -> Router<S, B>
 where
 	B: HttpBody + Send + 'static,
@@ -33,14 +41,15 @@ where
 async fn handler(
 	Extension(session): Extension<Session>,
 	maybe_output: Option<TypedHeader<Accept>>,
+	Query(options): Query<ImportOptions>,
 	sql: Bytes,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
 	// Get the datastore reference
 	let db = DB.get().unwrap();
 	// Convert the body to a byte slice
 	let sql = bytes_to_utf8(&sql)?;
-	// Execute the sql query in the database
-	match db.import(sql, &session).await {
+	// Execute the sql query, or the JSON lines, in the database
+	match db.import_with(sql, &session, options.format.unwrap_or_default()).await {
 		Ok(res) => match maybe_output.as_deref() {
 			// Simple serialization
 			Some(Accept::ApplicationJson) => Ok(output::json(&output::simplify(res))),
@@ -29,6 +29,7 @@ use tokio_util::sync::CancellationToken;
 use tower::ServiceBuilder;
 use tower_http::add_extension::AddExtensionLayer;
 use tower_http::auth::AsyncRequireAuthorizationLayer;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::request_id::MakeRequestUuid;
 use tower_http::sensitive_headers::{
@@ -86,6 +87,8 @@ pub async fn init(ct: CancellationToken) -> Result<(), Error> {
 				.on_failure(tracer::HttpTraceLayerHooks),
 		)
 		.layer(HttpMetricsLayer)
+		// Compress responses, except for gRPC streams, when requested by the client
+		.layer(CompressionLayer::new())
 		.layer(SetSensitiveResponseHeadersLayer::from_shared(headers))
 		.layer(AsyncRequireAuthorizationLayer::new(auth::SurrealAuth))
 		.layer(headers::add_server_header())
@@ -1,23 +1,47 @@
+use crate::cnf::{MAX_CONCURRENT_CALLS, MAX_MESSAGE_SIZE};
 use crate::rpc::connection::Connection;
-use axum::routing::get;
+use axum::extract::BodyStream;
+use axum::routing::{get, post};
 use axum::Extension;
 use axum::Router;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt};
+use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use http_body::Body as HttpBody;
+use std::sync::{Arc, OnceLock};
 use surrealdb::dbs::Session;
+use tokio::sync::RwLock;
 use tower_http::request_id::RequestId;
 use uuid::Uuid;
 
 use axum::{
-	extract::ws::{WebSocket, WebSocketUpgrade},
+	extract::ws::{Message, WebSocket, WebSocketUpgrade},
 	response::IntoResponse,
 };
 
+/// The path of the bidirectional gRPC stream
+const GRPC_PATH: &str = "/surrealdb.rpc.Rpc/Stream";
+/// The content type of the gRPC stream
+const GRPC_CONTENT_TYPE: &str = "application/grpc+bincode";
+/// The length of the header which prefixes each gRPC message
+const GRPC_HEADER_LEN: usize = 5;
+/// The gRPC status of a message which is larger than the maximum message size
+const GRPC_RESOURCE_EXHAUSTED: &str = "8";
+/// The gRPC status of a compressed message, as no compression is supported
+const GRPC_UNIMPLEMENTED: &str = "12";
+
+/// The gRPC status and message with which a stream was aborted
+type GrpcError = Arc<OnceLock<(&'static str, &'static str)>>;
+
 pub(super) fn router<S, B>() -> Router<S, B>
 where
 	B: HttpBody + Send + 'static,
+	B::Data: Into<Bytes>,
+	B::Error: Into<axum::BoxError>,
 	S: Clone + Send + Sync + 'static,
 {
-	Router::new().route("/rpc", get(handler))
+	Router::new().route("/rpc", get(handler)).route(GRPC_PATH, post(grpc_handler))
 }
 
 async fn handler(
@@ -27,10 +51,121 @@ async fn handler(
 ) -> impl IntoResponse {
 	// finalize the upgrade process by returning upgrade callback.
 	// we can customize the callback by sending additional info such as address.
-	ws.on_upgrade(move |socket| handle_socket(socket, sess, req_id))
+	ws.max_message_size(MAX_MESSAGE_SIZE)
+		.on_upgrade(move |socket| handle_socket(socket, sess, req_id))
 }
 
 async fn handle_socket(ws: WebSocket, sess: Session, req_id: RequestId) {
+	let rpc = connection(sess, req_id).await;
+	// Split the socket into send and recv
+	let (sender, receiver) = ws.split();
+
+	Connection::serve(rpc, sender, receiver).await;
+}
+
+async fn grpc_handler(
+	Extension(sess): Extension<Session>,
+	Extension(req_id): Extension<RequestId>,
+	body: BodyStream,
+) -> impl IntoResponse {
+	// The messages received from the client
+	let (incoming_tx, incoming_rx) = mpsc::channel(MAX_CONCURRENT_CALLS);
+	// The messages sent to the client
+	let (outgoing_tx, outgoing_rx) = mpsc::channel(MAX_CONCURRENT_CALLS);
+	// The response body is streamed to the client
+	let (sender, response) = hyper::Body::channel();
+	// The error with which the request stream was aborted
+	let error = GrpcError::default();
+	tokio::spawn(grpc_decode(body, incoming_tx, error.clone()));
+	tokio::spawn(grpc_encode(outgoing_rx, sender, error));
+	tokio::spawn(async move {
+		let rpc = connection(sess, req_id).await;
+		Connection::serve(rpc, outgoing_tx, incoming_rx).await;
+	});
+	([(CONTENT_TYPE, GRPC_CONTENT_TYPE)], response)
+}
+
+/// Splits the request body into messages, each of which is prefixed
+/// by a header holding the compression flag and the message length.
+/// The stream is aborted if a message is compressed, or is larger
+/// than the largest message which can be sent on a WebSocket.
+async fn grpc_decode(
+	mut body: BodyStream,
+	mut sender: mpsc::Sender<Result<Message, axum::Error>>,
+	error: GrpcError,
+) {
+	let mut buffer = BytesMut::new();
+	while let Some(data) = body.next().await {
+		match data {
+			Ok(data) => buffer.extend_from_slice(&data),
+			Err(err) => {
+				let _ = sender.send(Err(err)).await;
+				return;
+			}
+		}
+		while buffer.len() >= GRPC_HEADER_LEN {
+			let len = u32::from_be_bytes([buffer[1], buffer[2], buffer[3], buffer[4]]) as usize;
+			// Check the message before buffering all of it
+			let abort = match (buffer[0], len) {
+				(0, len) if len <= MAX_MESSAGE_SIZE => None,
+				(0, _) => Some((GRPC_RESOURCE_EXHAUSTED, "The message is too large")),
+				_ => Some((GRPC_UNIMPLEMENTED, "Compressed messages are not supported")),
+			};
+			if let Some((status, message)) = abort {
+				let _ = error.set((status, message));
+				let _ = sender.send(Err(axum::Error::new(message))).await;
+				return;
+			}
+			if buffer.len() < GRPC_HEADER_LEN + len {
+				break;
+			}
+			buffer.advance(GRPC_HEADER_LEN);
+			let message = buffer.split_to(len).to_vec();
+			if sender.send(Ok(Message::Binary(message))).await.is_err() {
+				return;
+			}
+		}
+	}
+	// The client has closed the stream
+	let _ = sender.send(Ok(Message::Close(None))).await;
+}
+
+/// Frames the messages sent to the client, and completes the
+/// stream with the gRPC status once the connection is closed
+async fn grpc_encode(
+	mut receiver: mpsc::Receiver<Message>,
+	mut sender: hyper::body::Sender,
+	error: GrpcError,
+) {
+	while let Some(message) = receiver.next().await {
+		let message = match message {
+			Message::Binary(v) => v,
+			Message::Text(v) => v.into_bytes(),
+			// Pings and close frames are handled by HTTP/2
+			_ => continue,
+		};
+		let mut frame = BytesMut::with_capacity(GRPC_HEADER_LEN + message.len());
+		frame.put_u8(0);
+		frame.put_u32(message.len() as u32);
+		frame.put_slice(&message);
+		if sender.send_data(frame.freeze()).await.is_err() {
+			return;
+		}
+	}
+	let mut trailers = HeaderMap::new();
+	match error.get() {
+		Some(&(status, message)) => {
+			trailers.insert("grpc-status", HeaderValue::from_static(status));
+			trailers.insert("grpc-message", HeaderValue::from_static(message));
+		}
+		None => {
+			trailers.insert("grpc-status", HeaderValue::from_static("0"));
+		}
+	}
+	let _ = sender.send_trailers(trailers).await;
+}
+
+async fn connection(sess: Session, req_id: RequestId) -> Arc<RwLock<Connection>> {
 	let rpc = Connection::new(sess);
 
 	// Update the WebSocket ID with the Request ID
@@ -39,5 +174,5 @@ async fn handle_socket(ws: WebSocket, sess: Session, req_id: RequestId) {
 		let _ = rpc.write().await.update_ws_id(req_id).await;
 	}
 
-	Connection::serve(rpc, ws).await;
+	rpc
 }
@@ -2,6 +2,8 @@ use axum_server::Handle;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
+use crate::cnf::SHUTDOWN_GRACE_PERIOD;
+use crate::dbs::DB;
 use crate::{err::Error, rpc, telemetry};
 
 /// Start a graceful shutdown:
@@ -28,6 +30,13 @@ pub fn graceful_shutdown(ct: CancellationToken, http_handle: Handle) -> JoinHand
 
 				rpc::graceful_shutdown().await;
 
+				// Wait for running queries, and flush the datastore
+				if let Some(ds) = DB.get() {
+					if let Err(err) = ds.shutdown(SHUTDOWN_GRACE_PERIOD).await {
+						error!("Failed to shutdown the datastore: {}", err);
+					}
+				}
+
 				ct.cancel();
 
 				// Flush all telemetry data
@@ -19,6 +19,20 @@ pub fn init(ct: CancellationToken) -> JoinHandle<()> {
 	// This requires the nodes::init function to be called after the dbs::init function.
 	let dbs = crate::dbs::DB.get().unwrap();
 
+	// Indexes are built by a task of their own, as a build can outlast many ticks
+	let builds = ct.clone();
+	tokio::spawn(async move {
+		loop {
+			if let Err(e) = dbs.build_indexes_in_background().await {
+				error!("Error building indexes in the background: {}", e);
+			}
+			tokio::select! {
+				_ = builds.cancelled() => break,
+				_ = tokio::time::sleep(tick_interval) => {}
+			}
+		}
+	});
+
 	tokio::spawn(async move {
 		loop {
 			if let Err(e) = dbs.tick().await {
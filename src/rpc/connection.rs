@@ -1,9 +1,9 @@
-use axum::extract::ws::{Message, WebSocket};
-use futures_util::stream::{SplitSink, SplitStream};
-use futures_util::{SinkExt, StreamExt};
+use axum::extract::ws::Message;
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
 use opentelemetry::trace::FutureExt;
 use opentelemetry::Context as TelemetryContext;
 use std::collections::BTreeMap;
+use std::fmt::{Debug, Display};
 use std::sync::Arc;
 use surrealdb::channel::{self, Receiver, Sender};
 use tokio::sync::RwLock;
@@ -23,6 +23,7 @@ use crate::telemetry;
 use crate::telemetry::metrics::ws::RequestContext;
 use crate::telemetry::traces::rpc::span_for_request;
 
+use super::idempotency;
 use super::processor::Processor;
 use super::request::parse_request;
 use super::res::{failure, IntoRpcResponse, OutputFormat};
@@ -66,10 +67,15 @@ impl Connection {
 		Ok(())
 	}
 
-	/// Serve the RPC endpoint
-	pub async fn serve(rpc: Arc<RwLock<Connection>>, ws: WebSocket) {
-		// Split the socket into send and recv
-		let (sender, receiver) = ws.split();
+	/// Serve the RPC endpoint, sending messages to the client over the
+	/// sender, and receiving messages from the client over the receiver
+	pub async fn serve<S, R, E>(rpc: Arc<RwLock<Connection>>, sender: S, receiver: R)
+	where
+		S: Sink<Message> + Unpin + Send + 'static,
+		S::Error: Debug + Display,
+		R: Stream<Item = Result<Message, E>> + Unpin + Send + 'static,
+		E: Debug + Send + 'static,
+	{
 		// Create an internal channel between the receiver and the sender
 		let (internal_sender, internal_receiver) = channel::new(MAX_CONCURRENT_CALLS);
 
@@ -120,6 +126,11 @@ impl Connection {
 		// Remove this WebSocket from the list
 		WEBSOCKETS.write().await.remove(&ws_id);
 
+		// Remove this session from the registry
+		if let Some(id) = &rpc.read().await.processor.session().id {
+			DB.get().unwrap().sessions().remove(id);
+		}
+
 		trace!("WebSocket {} disconnected", ws_id);
 
 		if let Err(err) = telemetry::metrics::ws::on_disconnect() {
@@ -150,11 +161,14 @@ impl Connection {
 	}
 
 	/// Read messages sent from the client
-	async fn read(
+	async fn read<R, E>(
 		rpc: Arc<RwLock<Connection>>,
-		mut receiver: SplitStream<WebSocket>,
+		mut receiver: R,
 		internal_sender: Sender<Message>,
-	) {
+	) where
+		R: Stream<Item = Result<Message, E>> + Unpin,
+		E: Debug,
+	{
 		// Collect all spawned tasks so we can wait for them at the end
 		let mut tasks = JoinSet::new();
 		let cancel_token = rpc.read().await.graceful_shutdown.clone();
@@ -211,11 +225,14 @@ impl Connection {
 	}
 
 	/// Write messages to the client
-	async fn write(
+	async fn write<S>(
 		rpc: Arc<RwLock<Connection>>,
-		mut sender: SplitSink<WebSocket, Message>,
+		mut sender: S,
 		mut internal_receiver: Receiver<Message>,
-	) {
+	) where
+		S: Sink<Message> + Unpin,
+		S::Error: Debug + Display,
+	{
 		let cancel_token = rpc.read().await.graceful_shutdown.clone();
 		loop {
 			let is_shutdown = cancel_token.cancelled();
@@ -299,11 +316,37 @@ impl Connection {
 					);
 
 					// Process the request
-					let res =
-						rpc.write().await.processor.process_request(&req.method, req.params).await;
+					let res = match req.key {
+						// Process the request only once, however many times it is retried
+						Some(key) => {
+							idempotency::process(key, async move {
+								rpc.write()
+									.await
+									.processor
+									.process_request(&req.method, req.params)
+									.await
+							})
+							.await
+						}
+						None => {
+							rpc.write()
+								.await
+								.processor
+								.process_request(&req.method, req.params)
+								.await
+						}
+					};
 
 					// Process the response
-					res.into_response(req.id).send(out_fmt, chn).with_context(otel_cx).await
+					res.into_response(req.id).send(out_fmt, chn).with_context(otel_cx).await;
+
+					// Close the WebSocket connection if the session was terminated
+					let rpc = rpc.read().await;
+					if let Some(id) = &rpc.processor.session().id {
+						if DB.get().unwrap().sessions().is_terminated(id) {
+							rpc.graceful_shutdown.cancel();
+						}
+					}
 				}
 				Err(err) => {
 					// Process the response
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use once_cell::sync::Lazy;
+use tokio::sync::OnceCell;
+
+use crate::cnf::{IDEMPOTENCY_KEY_TTL, MAX_IDEMPOTENCY_KEYS};
+
+use super::res::{Data, Failure};
+
+type Outcome = Arc<OnceCell<Result<Data, Failure>>>;
+
+// Mapping of idempotency key to the time it was first seen, and the outcome of the request
+static OUTCOMES: Lazy<Mutex<HashMap<String, (Instant, Outcome)>>> = Lazy::new(Default::default);
+
+/// Processes a request which was sent with an idempotency key
+///
+/// The request is only processed the first time its key is seen. When a client retries
+/// the request, such as after losing its connection, the outcome of the first attempt is
+/// returned instead, waiting for it to complete if it is still being processed. The keys
+/// are shared by every connection, as a retried request is often sent over a new one.
+pub async fn process<F>(key: String, request: F) -> Result<Data, Failure>
+where
+	F: Future<Output = Result<Data, Failure>>,
+{
+	let outcome = {
+		let mut outcomes = OUTCOMES.lock().unwrap_or_else(|e| e.into_inner());
+		// Forget the keys which have expired, once there are too many
+		if outcomes.len() >= MAX_IDEMPOTENCY_KEYS {
+			outcomes.retain(|_, (time, _)| time.elapsed() < IDEMPOTENCY_KEY_TTL);
+		}
+		match outcomes.get(&key) {
+			Some((time, outcome)) if time.elapsed() < IDEMPOTENCY_KEY_TTL => outcome.clone(),
+			// Process the request without remembering its outcome, when too many keys are in use
+			_ if outcomes.len() >= MAX_IDEMPOTENCY_KEYS => {
+				drop(outcomes);
+				return request.await;
+			}
+			_ => {
+				let outcome = Outcome::default();
+				outcomes.insert(key, (Instant::now(), outcome.clone()));
+				outcome
+			}
+		}
+	};
+	match outcome.get_or_init(|| request).await {
+		Ok(data) => Ok(data.replay()),
+		Err(failure) => Err(failure.clone()),
+	}
+}
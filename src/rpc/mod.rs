@@ -1,5 +1,6 @@
 pub mod args;
 pub mod connection;
+pub mod idempotency;
 pub mod processor;
 pub mod request;
 pub mod res;
@@ -1,3 +1,4 @@
+use crate::cnf::MAX_PREPARED_STATEMENTS;
 use crate::cnf::PKG_NAME;
 use crate::cnf::PKG_VERSION;
 use crate::dbs::DB;
@@ -5,10 +6,13 @@ use crate::err::Error;
 use crate::rpc::args::Take;
 use crate::rpc::LIVE_QUERIES;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 
 use surrealdb::dbs::QueryType;
 use surrealdb::dbs::Response;
+use surrealdb::sql::Duration;
 use surrealdb::sql::Object;
+use surrealdb::sql::Query;
 use surrealdb::sql::Strand;
 use surrealdb::sql::Value;
 use surrealdb::{dbs::Session, sql::Array};
@@ -21,18 +25,29 @@ pub struct Processor {
 	session: Session,
 	pub format: OutputFormat,
 	vars: BTreeMap<String, Value>,
+	prepared: HashMap<Uuid, Query>,
 }
 
 impl Processor {
-	pub fn new(session: Session, format: OutputFormat, vars: BTreeMap<String, Value>) -> Self {
+	pub fn new(mut session: Session, format: OutputFormat, vars: BTreeMap<String, Value>) -> Self {
+		// Create a new WebSocket id
+		let ws_id = Uuid::new_v4();
+		// Identify the session by the WebSocket id, rather than by an id sent by the client
+		session.id = Some(ws_id.to_string());
 		Self {
-			ws_id: Uuid::new_v4(),
+			ws_id,
 			session,
 			format,
 			vars,
+			prepared: HashMap::new(),
 		}
 	}
 
+	/// Retrieve the current session
+	pub fn session(&self) -> &Session {
+		&self.session
+	}
+
 	pub async fn process_request(&mut self, method: &str, params: Array) -> Result<Data, Failure> {
 		info!("Process RPC request");
 
@@ -105,8 +120,11 @@ impl Processor {
 				_ => Err(Failure::INVALID_PARAMS),
 			},
 			// Select a value or values from the database
-			"select" => match params.needs_one() {
-				Ok(v) => self.select(v).await.map(Into::into).map_err(Into::into),
+			"select" => match params.needs_one_or_two() {
+				Ok((v, t)) => match timeout(t) {
+					Some(t) => self.select(v, t).await.map(Into::into).map_err(Into::into),
+					None => Err(Failure::INVALID_PARAMS),
+				},
 				_ => Err(Failure::INVALID_PARAMS),
 			},
 			// Insert a value or values in the database
@@ -115,8 +133,11 @@ impl Processor {
 				_ => Err(Failure::INVALID_PARAMS),
 			},
 			// Create a value or values in the database
-			"create" => match params.needs_one_or_two() {
-				Ok((v, o)) => self.create(v, o).await.map(Into::into).map_err(Into::into),
+			"create" => match params.needs_one_two_or_three() {
+				Ok((v, o, t)) => match timeout(t) {
+					Some(t) => self.create(v, o, t).await.map(Into::into).map_err(Into::into),
+					None => Err(Failure::INVALID_PARAMS),
+				},
 				_ => Err(Failure::INVALID_PARAMS),
 			},
 			// Update a value or values in the database using `CONTENT`
@@ -159,6 +180,30 @@ impl Processor {
 				}
 				_ => Err(Failure::INVALID_PARAMS),
 			},
+			// Parse a SurrealQL query, and store it for repeated execution
+			"prepare" => match params.needs_one() {
+				Ok(Value::Strand(v)) => self.prepare(v).await.map(Into::into).map_err(Into::into),
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Run a previously prepared SurrealQL query against the database
+			"execute" => match params.needs_one_or_two() {
+				Ok((v, o)) if o.is_none_or_null() => match handle(v) {
+					Some(v) => {
+						self.execute(v, Object::default()).await.map(Into::into).map_err(Into::into)
+					}
+					None => Err(Failure::INVALID_PARAMS),
+				},
+				Ok((v, Value::Object(o))) => match handle(v) {
+					Some(v) => self.execute(v, o).await.map(Into::into).map_err(Into::into),
+					None => Err(Failure::INVALID_PARAMS),
+				},
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Remove a previously prepared SurrealQL query
+			"unprepare" => match params.needs_one().map(handle) {
+				Ok(Some(v)) => self.unprepare(v).await.map(Into::into).map_err(Into::into),
+				_ => Err(Failure::INVALID_PARAMS),
+			},
 			_ => Err(Failure::METHOD_NOT_FOUND),
 		}
 	}
@@ -296,20 +341,23 @@ impl Processor {
 	// Methods for selecting
 	// ------------------------------
 
-	async fn select(&self, what: Value) -> Result<Value, Error> {
+	async fn select(&self, what: Value, timeout: Option<Duration>) -> Result<Value, Error> {
 		// Return a single result?
 		let one = what.is_thing();
 		// Get a database reference
 		let kvs = DB.get().unwrap();
 		// Specify the SQL query string
-		let sql = "SELECT * FROM $what";
+		let sql = match timeout {
+			Some(t) => format!("SELECT * FROM $what TIMEOUT {t}"),
+			None => "SELECT * FROM $what".to_owned(),
+		};
 		// Specify the query parameters
 		let var = Some(map! {
 			String::from("what") => what.could_be_table(),
 			=> &self.vars
 		});
 		// Execute the query on the database
-		let mut res = kvs.execute(sql, &self.session, var).await?;
+		let mut res = kvs.execute(&sql, &self.session, var).await?;
 		// Extract the first query result
 		let res = match one {
 			true => res.remove(0).result?.first(),
@@ -351,13 +399,21 @@ impl Processor {
 	// Methods for creating
 	// ------------------------------
 
-	async fn create(&self, what: Value, data: Value) -> Result<Value, Error> {
+	async fn create(
+		&self,
+		what: Value,
+		data: Value,
+		timeout: Option<Duration>,
+	) -> Result<Value, Error> {
 		// Return a single result?
 		let one = what.is_thing();
 		// Get a database reference
 		let kvs = DB.get().unwrap();
 		// Specify the SQL query string
-		let sql = "CREATE $what CONTENT $data RETURN AFTER";
+		let sql = match timeout {
+			Some(t) => format!("CREATE $what CONTENT $data RETURN AFTER TIMEOUT {t}"),
+			None => "CREATE $what CONTENT $data RETURN AFTER".to_owned(),
+		};
 		// Specify the query parameters
 		let var = Some(map! {
 			String::from("what") => what.could_be_table(),
@@ -365,7 +421,7 @@ impl Processor {
 			=> &self.vars
 		});
 		// Execute the query on the database
-		let mut res = kvs.execute(sql, &self.session, var).await?;
+		let mut res = kvs.execute(&sql, &self.session, var).await?;
 		// Extract the first query result
 		let res = match one {
 			true => res.remove(0).result?.first(),
@@ -532,6 +588,48 @@ impl Processor {
 		Ok(res)
 	}
 
+	// ------------------------------
+	// Methods for prepared statements
+	// ------------------------------
+
+	async fn prepare(&mut self, sql: Strand) -> Result<Value, Error> {
+		// Limit the number of stored queries
+		if self.prepared.len() >= MAX_PREPARED_STATEMENTS {
+			return Err(Error::TooManyPrepared);
+		}
+		// Parse the query only once
+		let sql = surrealdb::sql::parse(&sql)?;
+		// Store the query using a new handle
+		let id = Uuid::new_v4();
+		self.prepared.insert(id, sql);
+		// Return the handle to the client
+		Ok(Value::Uuid(id.into()))
+	}
+
+	async fn execute(&self, id: Uuid, mut vars: Object) -> Result<Vec<Response>, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Get the prepared query
+		let sql = self.prepared.get(&id).ok_or(Error::PreparedNotFound(id))?;
+		// Specify the query parameters
+		let var = Some(mrg! { vars.0, &self.vars });
+		// Execute the query on the database
+		let res = kvs.process(sql.clone(), &self.session, var).await?;
+		// Post-process hooks for web layer
+		for response in &res {
+			self.handle_live_query_results(response).await;
+		}
+		// Return the result to the client
+		Ok(res)
+	}
+
+	async fn unprepare(&mut self, id: Uuid) -> Result<Value, Error> {
+		match self.prepared.remove(&id) {
+			Some(_) => Ok(Value::None),
+			None => Err(Error::PreparedNotFound(id)),
+		}
+	}
+
 	// ------------------------------
 	// Private methods
 	// ------------------------------
@@ -557,3 +655,21 @@ impl Processor {
 		}
 	}
 }
+
+/// Get a prepared statement handle from a request parameter
+fn handle(v: Value) -> Option<Uuid> {
+	match v {
+		Value::Uuid(v) => Some(v.0),
+		Value::Strand(v) => Uuid::parse_str(&v).ok(),
+		_ => None,
+	}
+}
+
+/// Get an optional request timeout from a request parameter
+fn timeout(v: Value) -> Option<Option<Duration>> {
+	match v {
+		Value::None | Value::Null => Some(None),
+		Value::Duration(v) => Some(Some(v)),
+		_ => None,
+	}
+}
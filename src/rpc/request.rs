@@ -9,11 +9,13 @@ use super::res::{Failure, OutputFormat};
 pub static ID: Lazy<[Part; 1]> = Lazy::new(|| [Part::from("id")]);
 pub static METHOD: Lazy<[Part; 1]> = Lazy::new(|| [Part::from("method")]);
 pub static PARAMS: Lazy<[Part; 1]> = Lazy::new(|| [Part::from("params")]);
+pub static KEY: Lazy<[Part; 1]> = Lazy::new(|| [Part::from("key")]);
 
 pub struct Request {
 	pub id: Option<Value>,
 	pub method: String,
 	pub params: Array,
+	pub key: Option<String>,
 	pub size: usize,
 	pub out_fmt: Option<OutputFormat>,
 }
@@ -74,10 +76,17 @@ pub async fn parse_request(msg: Message) -> Result<Request, Failure> {
 		_ => Array::new(),
 	};
 
+	// Fetch the 'key' argument, used to deduplicate retried requests
+	let key = match req.pick(&*KEY) {
+		Value::Strand(v) => Some(v.to_raw()),
+		_ => None,
+	};
+
 	Ok(Request {
 		id,
 		method,
 		params,
+		key,
 		size,
 		out_fmt,
 	})
@@ -6,6 +6,8 @@ use std::borrow::Cow;
 use surrealdb::channel::Sender;
 use surrealdb::dbs;
 use surrealdb::dbs::Notification;
+use surrealdb::dbs::QueryType;
+use surrealdb::error::Db as SurrealDbError;
 use surrealdb::sql;
 use surrealdb::sql::Value;
 use tracing::Span;
@@ -60,6 +62,34 @@ impl From<Notification> for Data {
 	}
 }
 
+impl Data {
+	/// Copies the data, so that it can be returned again when a request is retried
+	///
+	/// Errors within query results can not be cloned, so are replaced by an error with the same message.
+	pub fn replay(&self) -> Data {
+		match self {
+			Data::Other(v) => Data::Other(v.clone()),
+			Data::Query(v) => Data::Query(
+				v.iter()
+					.map(|res| dbs::Response {
+						time: res.time,
+						result: match &res.result {
+							Ok(v) => Ok(v.clone()),
+							Err(e) => Err(SurrealDbError::Replayed(e.to_string())),
+						},
+						query_type: match res.query_type {
+							QueryType::Other => QueryType::Other,
+							QueryType::Live => QueryType::Live,
+							QueryType::Kill => QueryType::Kill,
+						},
+					})
+					.collect(),
+			),
+			Data::Live(n) => Data::Live(n.clone()),
+		}
+	}
+}
+
 #[derive(Debug, Serialize)]
 pub struct Response {
 	#[serde(skip_serializing_if = "Option::is_none")]
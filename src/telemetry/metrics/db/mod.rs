@@ -0,0 +1,37 @@
+use once_cell::sync::Lazy;
+use opentelemetry::metrics::{MetricsError, ObservableCounter};
+use opentelemetry::KeyValue;
+
+use super::METER_DURATION;
+use crate::dbs::DB;
+
+pub static DB_TABLE_READS: Lazy<ObservableCounter<u64>> = Lazy::new(|| {
+	METER_DURATION
+		.u64_observable_counter("db.table.reads")
+		.with_description("The number of records read from each table.")
+		.init()
+});
+
+pub static DB_TABLE_WRITES: Lazy<ObservableCounter<u64>> = Lazy::new(|| {
+	METER_DURATION
+		.u64_observable_counter("db.table.writes")
+		.with_description("The number of records written to each table.")
+		.init()
+});
+
+/// Registers the callback that reports the table access statistics.
+pub(super) fn observe_table_stats() -> Result<(), MetricsError> {
+	METER_DURATION.register_callback(|cx| {
+		if let Some(ds) = DB.get() {
+			for v in ds.stats().tables() {
+				let attrs = [
+					KeyValue::new("db.namespace", v.ns),
+					KeyValue::new("db.database", v.db),
+					KeyValue::new("db.table", v.tb),
+				];
+				DB_TABLE_READS.observe(cx, v.reads, &attrs);
+				DB_TABLE_WRITES.observe(cx, v.writes, &attrs);
+			}
+		}
+	})
+}
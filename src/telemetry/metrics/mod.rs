@@ -1,3 +1,5 @@
+#[cfg(feature = "has-storage")]
+pub mod db;
 pub mod http;
 pub mod ws;
 
@@ -18,6 +20,8 @@ use opentelemetry::{
 };
 use opentelemetry_otlp::MetricsExporterBuilder;
 
+#[cfg(feature = "has-storage")]
+use self::db::observe_table_stats;
 pub use self::http::tower_layer::HttpMetricsLayer;
 use self::ws::observe_active_connection;
 
@@ -80,6 +84,8 @@ pub fn init(cx: &TelemetryContext) -> Result<(), MetricsError> {
 	METER_PROVIDER_SIZE.start(cx, runtime::Tokio)?;
 
 	observe_active_connection(0)?;
+	#[cfg(feature = "has-storage")]
+	observe_table_stats()?;
 
 	Ok(())
 }
@@ -1427,6 +1427,114 @@ mod ws_integration {
 		Ok(())
 	}
 
+	#[test(tokio::test)]
+	async fn prepare_execute() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
+		let socket = &mut common::connect_ws(&addr).await?;
+
+		//
+		// Prepare the connection
+		//
+		let res = common::ws_signin(socket, USER, PASS, None, None, None).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = common::ws_use(socket, Some("N"), Some("D")).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+
+		//
+		// Prepare a CREATE query
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "prepare",
+				"params": [
+					"CREATE type::thing('foo', $id) SET value = $value",
+				]
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res["result"].is_string(), "result: {:?}", res);
+		let handle = res["result"].as_str().unwrap().to_owned();
+
+		//
+		// Execute the prepared query with different parameters
+		//
+		for id in 1..=3 {
+			let res = common::ws_send_msg_and_wait_response(
+				socket,
+				serde_json::to_string(&json!({
+					"id": "1",
+					"method": "execute",
+					"params": [
+						handle,
+						{ "id": id, "value": id * 10 },
+					]
+				}))
+				.unwrap(),
+			)
+			.await;
+			assert!(res.is_ok(), "result: {:?}", res);
+			let res = res.unwrap();
+			assert!(res["result"].is_array(), "result: {:?}", res);
+			let res = res["result"].as_array().unwrap();
+			assert_eq!(res[0]["result"][0]["value"], id * 10, "result: {:?}", res);
+		}
+
+		//
+		// Verify the data was created and can be queried
+		//
+		let res = common::ws_query(socket, "SELECT * FROM foo").await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res[0]["result"].is_array(), "result: {:?}", res);
+		let res = res[0]["result"].as_array().unwrap();
+		assert_eq!(res.len(), 3, "result: {:?}", res);
+
+		//
+		// Remove the prepared query
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "unprepare",
+				"params": [
+					handle,
+				]
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res["error"].is_null(), "result: {:?}", res);
+
+		//
+		// Verify the prepared query can no longer be executed
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "execute",
+				"params": [
+					handle,
+				]
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res["error"].is_object(), "result: {:?}", res);
+
+		Ok(())
+	}
+
 	#[test(tokio::test)]
 	async fn version() -> Result<(), Box<dyn std::error::Error>> {
 		let (addr, _server) = common::start_server_with_defaults().await.unwrap();